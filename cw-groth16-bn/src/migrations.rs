@@ -0,0 +1,159 @@
+use std::collections::BTreeMap;
+
+use crate::commitment::vkey_hash;
+use crate::contract::DEFAULT_COMMITMENT_EXPIRY;
+use crate::msg::DEFAULT_CIRCUIT;
+use crate::parser::prepare_vkey;
+use crate::state::{
+    CircuitMetadata, CircuitStatus, Config, FeeMode, ProofInfo, PublicSignalMode, UnrelatedDenoms,
+    VkeyStr, ZkeysStr,
+};
+use crate::state::{
+    Verification, CONFIG, PROVERLIST, PROVER_HISTORY, PROVER_SEQUENCE, SOLVED, ZKEYS,
+};
+use cosmwasm_std::{Addr, Empty, Order, StdResult, Storage};
+
+/// storage layouts of 0.1.0, the release before contract versions were recorded
+pub mod v0_1 {
+    use crate::state::ProofStr;
+    use cosmwasm_schema::cw_serde;
+    use cosmwasm_std::{Addr, Coin};
+    use cw_storage_plus::{Item, Map};
+
+    #[cw_serde]
+    pub struct Config {
+        pub zkeys_price: Option<Coin>,
+        pub proof_price: Option<Coin>,
+    }
+
+    #[cw_serde]
+    pub struct VkeyStr {
+        pub alpha_1: Vec<u8>,
+        pub beta_2: Vec<u8>,
+        pub gamma_2: Vec<u8>,
+        pub delta_2: Vec<u8>,
+        pub ic0: Vec<u8>,
+        pub ic1: Vec<u8>,
+    }
+
+    #[cw_serde]
+    pub struct ZkeysStr {
+        pub vkeys: VkeyStr,
+        pub public_signal: String,
+    }
+
+    #[cw_serde]
+    pub struct ProofInfo {
+        pub proof: ProofStr,
+        pub is_valid: bool,
+    }
+
+    pub const CONFIG: Item<Config> = Item::new("config");
+    pub const PROVERINFO: Map<&Addr, ProofInfo> = Map::new("prover_info");
+    pub const PROVERLIST: Map<(&Addr, &Addr), ProofInfo> = Map::new("prover_list");
+    pub const ZKEYS: Map<&Addr, ZkeysStr> = Map::new("zkeys");
+}
+
+impl From<v0_1::ZkeysStr> for ZkeysStr {
+    fn from(zkeys: v0_1::ZkeysStr) -> ZkeysStr {
+        let vkeys = VkeyStr {
+            alpha_1: zkeys.vkeys.alpha_1,
+            beta_2: zkeys.vkeys.beta_2,
+            gamma_2: zkeys.vkeys.gamma_2,
+            delta_2: zkeys.vkeys.delta_2,
+            ic: vec![zkeys.vkeys.ic0, zkeys.vkeys.ic1],
+        };
+        ZkeysStr {
+            // 0.1.0 didn't check keys, one that doesn't decode keeps refusing every proof
+            prepared: prepare_vkey(&vkeys).ok(),
+            vkeys,
+            public_signals: vec![zkeys.public_signal],
+            public_signal_mode: PublicSignalMode::Issuer,
+            bind_sender: false,
+            nullifier_index: None,
+            metadata: CircuitMetadata::default(),
+            status: CircuitStatus::Active,
+        }
+    }
+}
+
+/// rewrite everything 0.1.0 stored into the current layouts, 0.1.0 had no owner so it is given here,
+/// its single key per issuer becomes the issuer's `DEFAULT_CIRCUIT`, `height` stands in for the unrecorded block of each prover's latest proof
+pub fn migrate_from_v0_1(storage: &mut dyn Storage, owner: Addr, height: u64) -> StdResult<()> {
+    let config = v0_1::CONFIG.load(storage)?;
+    CONFIG.save(
+        storage,
+        &Config {
+            owner,
+            zkeys_price: config.zkeys_price,
+            proof_price: config.proof_price,
+            zkeys_cw20_price: None,
+            proof_cw20_price: None,
+            fee_mode: FeeMode::default(),
+            unrelated_denoms: UnrelatedDenoms::default(),
+            commitment_expiry: DEFAULT_COMMITMENT_EXPIRY,
+        },
+    )?;
+
+    // the old entries are read in full and removed first, the new layouts live in other namespaces
+    let zkeys = v0_1::ZKEYS
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    let mut public_signals = BTreeMap::new();
+    let mut vkey_hashes = BTreeMap::new();
+    for (issuer, zkeys) in zkeys {
+        v0_1::ZKEYS.remove(storage, &issuer);
+        public_signals.insert(issuer.clone(), vec![zkeys.public_signal.clone()]);
+        let zkeys: ZkeysStr = zkeys.into();
+        vkey_hashes.insert(issuer.clone(), hex::encode(vkey_hash(&zkeys.vkeys)));
+        ZKEYS.save(storage, (&issuer, DEFAULT_CIRCUIT), &zkeys)?;
+    }
+
+    // a proof was checked against the single public signal of its issuer
+    let proofs = v0_1::PROVERLIST
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for ((issuer, prover), proof_info) in proofs.iter() {
+        v0_1::PROVERLIST.remove(storage, (issuer, prover));
+        PROVERLIST.save(
+            storage,
+            (issuer, DEFAULT_CIRCUIT, prover),
+            &ProofInfo {
+                proof: proof_info.proof.clone(),
+                public_signals: public_signals.get(issuer).cloned().unwrap_or_default(),
+                is_valid: proof_info.is_valid,
+            },
+        )?;
+        SOLVED.save(storage, (prover, issuer, DEFAULT_CIRCUIT), &Empty {})?;
+    }
+
+    // the latest proof of a prover doesn't name its issuer, it is the one listing the same proof,
+    // it starts the prover's history as the only verification 0.1.0 kept
+    let latest = v0_1::PROVERINFO
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (prover, proof_info) in latest {
+        v0_1::PROVERINFO.remove(storage, &prover);
+        let issuer = proofs
+            .iter()
+            .find(|((_, listed_prover), listed)| {
+                *listed_prover == prover && listed.proof == proof_info.proof
+            })
+            .map(|((issuer, _), _)| issuer);
+        if let Some(issuer) = issuer {
+            PROVER_HISTORY.save(
+                storage,
+                (&prover, 0),
+                &Verification {
+                    issuer: issuer.clone(),
+                    circuit_id: DEFAULT_CIRCUIT.to_string(),
+                    vkey_hash: vkey_hashes.get(issuer).cloned().unwrap_or_default(),
+                    height,
+                },
+            )?;
+            PROVER_SEQUENCE.save(storage, &prover, &1)?;
+        }
+    }
+
+    Ok(())
+}
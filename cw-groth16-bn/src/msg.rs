@@ -1,29 +1,232 @@
-use crate::state::Config;
+use crate::snarkjs::{SnarkjsProof, SnarkjsVerifyingKey};
+use crate::state::{
+    CircuitMetadata, CircuitStatus, Config, FeeMode, PointEncoding, ProofInfo, PublicSignalMode,
+    UnrelatedDenoms, ZkeysStr,
+};
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::Coin;
+use cosmwasm_std::{Addr, Binary, Coin, Uint128};
+use cw20::{Cw20Coin, Cw20CoinVerified, Cw20ReceiveMsg};
 
 #[cw_serde]
 pub struct InstantiateMsg {
     pub set_zkeys_price: Option<Coin>,
     pub publish_proof_price: Option<Coin>,
+    /// prices in a cw20 token, paid by sending the message to this contract with cw20 `Send`
+    pub set_zkeys_cw20_price: Option<Cw20Coin>,
+    pub publish_proof_cw20_price: Option<Cw20Coin>,
+    /// blocks a `CommitProof` stays revealable, defaults to 100
+    pub commitment_expiry: Option<u64>,
+    pub fee_mode: Option<FeeMode>,
+    pub unrelated_denoms: Option<UnrelatedDenoms>,
 }
 
 #[cw_serde]
+pub struct MigrateMsg {
+    /// owner of a contract migrated from 0.1.0, which didn't record one
+    pub owner: Option<String>,
+}
+
+/// the `proof_system` attribute of the events below
+pub const PROOF_SYSTEM: &str = "groth16_bn254";
+
+/// the circuit of messages that don't name one, keys migrated from 0.1.0 are registered under it
+pub const DEFAULT_CIRCUIT: &str = "default";
+
+/// Every action emits a `wasm-<type>` event:
+///
+/// - `zkeys_registered`: `issuer`, `circuit_id`, `vkey_hash`, `proof_system`, `fee`
+/// - `bounty_opened`: `issuer`, `circuit_id`, `reward`, `deadline`
+/// - `circuit_status_changed`: `issuer`, `circuit_id`, `status`
+/// - `proof_committed`: `issuer`, `circuit_id`, `prover`
+/// - `proof_verified`: `issuer`, `circuit_id`, `prover`, `vkey_hash`, `proof_system`, `fee`, `outcome`, `sequence`
+/// - `proof_batch_verified`: `issuer`, `circuit_id`, `proofs`, `fee`
+/// - `bounty_won`: `issuer`, `circuit_id`, `prover`
+/// - `bounty_paid`: `issuer`, `circuit_id`, `prover`, `reward`
+/// - `bounty_reclaimed`: `issuer`, `circuit_id`, `reward`
+/// - `fees_withdrawn`: `recipient`, `amount`
+///
+/// `vkey_hash` is the hex sha256 of the verification key. `fee` is what the contract kept,
+/// comma separated `<amount><denom>` or `<amount><cw20 address>`. `outcome` is always `valid`,
+/// an invalid proof fails the transaction and emits nothing. `sequence` numbers the
+/// verification in the prover's `ProverHistory`. The `proof_verified` events of a batch leave
+/// `fee` empty, the batch pays once in `proof_batch_verified`.
+#[cw_serde]
+#[allow(clippy::large_enum_variant)]
 pub enum ExecuteMsg {
     Zkeys {
-        public_signal: String,
+        /// names the key among the issuer's circuits, defaults to `DEFAULT_CIRCUIT`,
+        /// a registered circuit can't be replaced
+        circuit_id: Option<String>,
+        /// what the circuit proves and where it comes from, shown by the `Circuit` query
+        metadata: Option<CircuitMetadata>,
+        public_signals: Vec<String>,
+        /// defaults to `issuer`, in `prover` mode `public_signals` must be empty
+        public_signal_mode: Option<PublicSignalMode>,
+        /// append the prover's sender signal as the last public input, defaults to false
+        bind_sender: Option<bool>,
+        /// index of the public signal used as a nullifier, a nullifier is only accepted once
+        nullifier_index: Option<usize>,
+        /// reward escrowed for the first valid proof, sent on top of the registration fee
+        /// combine with `bind_sender` so a copied proof can't collect it
+        bounty: Option<Coin>,
+        /// block height after which an unclaimed bounty goes back to the issuer
+        bounty_deadline: Option<u64>,
+        /// hex points in `point_encoding`, left out when the key comes as `snarkjs_vkey`
+        #[serde(default)]
         vk_alpha1: String,
+        #[serde(default)]
         vk_beta_2: String,
+        #[serde(default)]
         vk_gamma_2: String,
+        #[serde(default)]
         vk_delta_2: String,
-        vk_ic0: String,
-        vk_ic1: String
+        #[serde(default)]
+        vk_ic: Vec<String>,
+        /// defaults to `uncompressed`, the key is stored uncompressed either way
+        point_encoding: Option<PointEncoding>,
+        /// the `verification_key.json` exported by snarkjs, in place of the hex points
+        snarkjs_vkey: Option<SnarkjsVerifyingKey>,
     },
     Proof {
         difficuty_issuer: String,
+        /// defaults to `DEFAULT_CIRCUIT`
+        circuit_id: Option<String>,
+        /// hex points in `point_encoding`, left out when the proof comes as `snarkjs_proof`
+        #[serde(default)]
         proof_a: String,
+        #[serde(default)]
         proof_b: String,
-        proof_c: String
+        #[serde(default)]
+        proof_c: String,
+        /// defaults to `uncompressed`, the proof is stored as it is given
+        point_encoding: Option<PointEncoding>,
+        /// the `proof.json` written by snarkjs, in place of the hex points
+        snarkjs_proof: Option<SnarkjsProof>,
+        /// only for keys registered in `prover` mode, the `public.json` of snarkjs fits as it is
+        #[serde(alias = "publicSignals")]
+        public_signals: Option<Vec<String>>,
+    },
+    /// `Zkeys` with base64 points in place of hex
+    ZkeysBinary {
+        circuit_id: Option<String>,
+        metadata: Option<CircuitMetadata>,
+        public_signals: Vec<String>,
+        public_signal_mode: Option<PublicSignalMode>,
+        bind_sender: Option<bool>,
+        nullifier_index: Option<usize>,
+        bounty: Option<Coin>,
+        bounty_deadline: Option<u64>,
+        vk_alpha1: Binary,
+        vk_beta_2: Binary,
+        vk_gamma_2: Binary,
+        vk_delta_2: Binary,
+        vk_ic: Vec<Binary>,
+        /// defaults to `uncompressed`, the key is stored uncompressed either way
+        point_encoding: Option<PointEncoding>,
+    },
+    /// `Proof` with base64 points in place of hex
+    ProofBinary {
+        difficuty_issuer: String,
+        /// defaults to `DEFAULT_CIRCUIT`
+        circuit_id: Option<String>,
+        proof_a: Binary,
+        proof_b: Binary,
+        proof_c: Binary,
+        /// defaults to `uncompressed`, the proof is stored as it is given
+        point_encoding: Option<PointEncoding>,
+        /// only for keys registered in `prover` mode
+        public_signals: Option<Vec<String>>,
+    },
+    /// verify many proofs of one circuit with a single multi-pairing, for the price of
+    /// as many `Proof` messages. The batch fails as a whole, naming the invalid proofs
+    ProofBatch {
+        difficuty_issuer: String,
+        /// defaults to `DEFAULT_CIRCUIT`
+        circuit_id: Option<String>,
+        proofs: Vec<BatchProof>,
+    },
+    /// first phase of a front-running safe submission,
+    /// `commitment` is hex of sha256(pi_a || pi_b || pi_c || sender || salt)
+    CommitProof {
+        difficuty_issuer: String,
+        /// defaults to `DEFAULT_CIRCUIT`
+        circuit_id: Option<String>,
+        commitment: String,
+    },
+    /// second phase, at least one block after `CommitProof` and before it expires
+    RevealProof {
+        difficuty_issuer: String,
+        /// defaults to `DEFAULT_CIRCUIT`
+        circuit_id: Option<String>,
+        proof_a: String,
+        proof_b: String,
+        proof_c: String,
+        /// defaults to `uncompressed`, the encoding the commitment was computed over
+        point_encoding: Option<PointEncoding>,
+        public_signals: Option<Vec<String>>,
+        salt: String,
+    },
+    /// move one of the sender's circuits to `status`, a retired circuit stays retired
+    SetCircuitStatus {
+        circuit_id: Option<String>,
+        status: CircuitStatus,
+    },
+    /// take back a bounty no proof has won after its deadline
+    ReclaimBounty { circuit_id: Option<String> },
+    /// pay a circuit's bounty to its winner, once every commitment made before
+    /// the winning one has expired
+    ClaimBounty {
+        difficuty_issuer: String,
+        circuit_id: Option<String>,
+    },
+    /// send collected fees to `recipient`, only the owner can withdraw
+    Withdraw {
+        amount: Vec<Coin>,
+        recipient: String,
+    },
+    /// send collected cw20 fees of `token` to `recipient`, only the owner can withdraw
+    WithdrawCw20 {
+        token: String,
+        amount: Uint128,
+        recipient: String,
+    },
+    /// a cw20 `Send` to this contract, `msg` is a `Zkeys`, `Proof`, `ZkeysBinary`,
+    /// `ProofBinary`, `ProofBatch` or `RevealProof` message paid with the sent tokens
+    Receive(Cw20ReceiveMsg),
+}
+
+/// one proof of a `ProofBatch`
+#[cw_serde]
+pub struct BatchProof {
+    /// the address credited with the proof, defaults to the sender,
+    /// another address only for keys binding the sender
+    pub prover: Option<String>,
+    /// hex points in `point_encoding`
+    pub proof_a: String,
+    pub proof_b: String,
+    pub proof_c: String,
+    /// defaults to `uncompressed`, the proof is stored as it is given
+    pub point_encoding: Option<PointEncoding>,
+    /// only for keys registered in `prover` mode
+    pub public_signals: Option<Vec<String>>,
+}
+
+/// the verification key a `VerifyProof` query checks against
+#[cw_serde]
+pub enum VerifyingKeySource {
+    /// the key registered by `issuer`, `prover` is needed when it binds the sender
+    Registered {
+        issuer: String,
+        circuit_id: Option<String>,
+        prover: Option<String>,
+    },
+    /// a key given in the query, its public signals always come with the query
+    Inline {
+        vk_alpha1: String,
+        vk_beta_2: String,
+        vk_gamma_2: String,
+        vk_delta_2: String,
+        vk_ic: Vec<String>,
     },
 }
 
@@ -33,42 +236,300 @@ pub enum QueryMsg {
     #[returns(ConfigResponse)]
     Config {},
     #[returns(ZkeysResponse)]
-    IssuerZkeys { address: String},
+    IssuerZkeys {
+        address: String,
+        circuit_id: Option<String>,
+    },
     #[returns(ProofResponse)]
-    ProofResult {issuer_address: String, prover_address: String}
+    ProofResult {
+        issuer_address: String,
+        circuit_id: Option<String>,
+        prover_address: String,
+    },
+    /// `IssuerZkeys` with base64 points in place of hex
+    #[returns(ZkeysBinaryResponse)]
+    IssuerZkeysBinary {
+        address: String,
+        circuit_id: Option<String>,
+    },
+    /// `ProofResult` with base64 points in place of hex
+    #[returns(ProofBinaryResponse)]
+    ProofResultBinary {
+        issuer_address: String,
+        circuit_id: Option<String>,
+        prover_address: String,
+    },
+    #[returns(SenderSignalResponse)]
+    SenderSignal { address: String },
+    #[returns(CommitmentResponse)]
+    Commitment {
+        issuer_address: String,
+        circuit_id: Option<String>,
+        prover_address: String,
+    },
+    #[returns(NullifierResponse)]
+    Nullifier {
+        issuer_address: String,
+        circuit_id: Option<String>,
+        nullifier: String,
+    },
+    #[returns(BountyResponse)]
+    Bounty {
+        address: String,
+        circuit_id: Option<String>,
+    },
+    #[returns(FeeBalanceResponse)]
+    FeeBalance {},
+    /// issuers with a registered circuit, ordered by address length and then address
+    #[returns(IssuersResponse)]
+    Issuers {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// metadata and status of a circuit
+    #[returns(CircuitResponse)]
+    Circuit {
+        issuer_address: String,
+        circuit_id: Option<String>,
+    },
+    /// circuits registered by `issuer_address`, ordered by id
+    #[returns(CircuitsResponse)]
+    Circuits {
+        issuer_address: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// provers credited with a proof for a circuit of `issuer_address`
+    #[returns(ProversResponse)]
+    Provers {
+        issuer_address: String,
+        circuit_id: Option<String>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// circuits `prover_address` is credited with a proof for, ordered like `Issuers` and then by id,
+    /// `start_after` is an (issuer, circuit id) pair
+    #[returns(SolvedCircuitsResponse)]
+    SolvedCircuits {
+        prover_address: String,
+        start_after: Option<(String, String)>,
+        limit: Option<u32>,
+    },
+    /// every proof verified for `prover_address`, oldest first
+    #[returns(ProverHistoryResponse)]
+    ProverHistory {
+        prover_address: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// check a proof without paying for it or recording it, the query only fails when the
+    /// key can't be found or decoded, why a proof is rejected is explained in the response
+    #[returns(VerifyProofResponse)]
+    VerifyProof {
+        key: VerifyingKeySource,
+        proof_a: String,
+        proof_b: String,
+        proof_c: String,
+        /// of the proof and of an inline key, defaults to `uncompressed`
+        point_encoding: Option<PointEncoding>,
+        /// for inline keys and keys registered in `prover` mode
+        public_signals: Option<Vec<String>>,
+    },
 }
 
 #[cw_serde]
 pub struct ConfigResponse {
+    pub owner: Addr,
     pub zkeys_price: Option<Coin>,
     pub proof_price: Option<Coin>,
+    pub zkeys_cw20_price: Option<Cw20CoinVerified>,
+    pub proof_cw20_price: Option<Cw20CoinVerified>,
+    pub fee_mode: FeeMode,
+    pub unrelated_denoms: UnrelatedDenoms,
+    pub commitment_expiry: u64,
 }
 
 impl From<Config> for ConfigResponse {
     fn from(config: Config) -> ConfigResponse {
         ConfigResponse {
+            owner: config.owner,
             zkeys_price: config.zkeys_price,
             proof_price: config.proof_price,
+            zkeys_cw20_price: config.zkeys_cw20_price,
+            proof_cw20_price: config.proof_cw20_price,
+            fee_mode: config.fee_mode,
+            unrelated_denoms: config.unrelated_denoms,
+            commitment_expiry: config.commitment_expiry,
         }
     }
 }
 
-
 #[cw_serde]
 pub struct ProofResponse {
     pub proof_a: String,
     pub proof_b: String,
     pub proof_c: String,
+    /// of the hex points, the one they were submitted in
+    pub point_encoding: PointEncoding,
+    pub public_signals: Vec<String>,
+    pub is_valid: bool,
+}
+
+#[cw_serde]
+pub struct ProofBinaryResponse {
+    pub proof_a: Binary,
+    pub proof_b: Binary,
+    pub proof_c: Binary,
+    pub point_encoding: PointEncoding,
+    pub public_signals: Vec<String>,
     pub is_valid: bool,
 }
 
+impl From<ProofInfo> for ProofBinaryResponse {
+    fn from(proof_info: ProofInfo) -> ProofBinaryResponse {
+        ProofBinaryResponse {
+            proof_a: proof_info.proof.pi_a.into(),
+            proof_b: proof_info.proof.pi_b.into(),
+            proof_c: proof_info.proof.pi_c.into(),
+            point_encoding: proof_info.proof.encoding,
+            public_signals: proof_info.public_signals,
+            is_valid: proof_info.is_valid,
+        }
+    }
+}
+
 #[cw_serde]
 pub struct ZkeysResponse {
-    pub public_signal: String,
+    pub public_signals: Vec<String>,
+    pub public_signal_mode: PublicSignalMode,
+    pub bind_sender: bool,
+    pub nullifier_index: Option<usize>,
     pub vk_alpha1: String,
     pub vk_beta_2: String,
     pub vk_gamma_2: String,
     pub vk_delta_2: String,
-    pub vk_ic0: String,
-    pub vk_ic1: String
-}
\ No newline at end of file
+    pub vk_ic: Vec<String>,
+}
+
+#[cw_serde]
+pub struct ZkeysBinaryResponse {
+    pub public_signals: Vec<String>,
+    pub public_signal_mode: PublicSignalMode,
+    pub bind_sender: bool,
+    pub nullifier_index: Option<usize>,
+    pub vk_alpha1: Binary,
+    pub vk_beta_2: Binary,
+    pub vk_gamma_2: Binary,
+    pub vk_delta_2: Binary,
+    pub vk_ic: Vec<Binary>,
+}
+
+impl From<ZkeysStr> for ZkeysBinaryResponse {
+    fn from(zkeys: ZkeysStr) -> ZkeysBinaryResponse {
+        ZkeysBinaryResponse {
+            public_signals: zkeys.public_signals,
+            public_signal_mode: zkeys.public_signal_mode,
+            bind_sender: zkeys.bind_sender,
+            nullifier_index: zkeys.nullifier_index,
+            vk_alpha1: zkeys.vkeys.alpha_1.into(),
+            vk_beta_2: zkeys.vkeys.beta_2.into(),
+            vk_gamma_2: zkeys.vkeys.gamma_2.into(),
+            vk_delta_2: zkeys.vkeys.delta_2.into(),
+            vk_ic: zkeys.vkeys.ic.into_iter().map(Binary::from).collect(),
+        }
+    }
+}
+
+#[cw_serde]
+pub struct SenderSignalResponse {
+    pub public_signal: String,
+}
+
+#[cw_serde]
+pub struct CommitmentResponse {
+    pub commitment: String,
+    pub height: u64,
+    pub expires_at: u64,
+}
+
+#[cw_serde]
+pub struct NullifierResponse {
+    pub spent: bool,
+}
+
+#[cw_serde]
+pub struct FeeBalanceResponse {
+    pub balance: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct BountyResponse {
+    pub reward: Coin,
+    pub deadline: u64,
+    /// the prover the bounty goes to, unless an earlier commitment is revealed
+    pub winner: Option<Addr>,
+    /// the height from which `ClaimBounty` pays the winner
+    pub claimable_at: Option<u64>,
+}
+
+#[cw_serde]
+pub struct IssuersResponse {
+    pub issuers: Vec<Addr>,
+}
+
+#[cw_serde]
+pub struct CircuitResponse {
+    pub issuer: Addr,
+    pub circuit_id: String,
+    pub vkey_hash: String,
+    pub proof_system: String,
+    pub metadata: CircuitMetadata,
+    pub status: CircuitStatus,
+}
+
+#[cw_serde]
+pub struct CircuitsResponse {
+    pub circuit_ids: Vec<String>,
+}
+
+#[cw_serde]
+pub struct CircuitRef {
+    pub issuer: Addr,
+    pub circuit_id: String,
+}
+
+#[cw_serde]
+pub struct SolvedCircuitsResponse {
+    pub circuits: Vec<CircuitRef>,
+}
+
+#[cw_serde]
+pub struct ProversResponse {
+    pub provers: Vec<Addr>,
+}
+
+#[cw_serde]
+pub struct VerificationResponse {
+    pub sequence: u64,
+    pub issuer: Addr,
+    pub circuit_id: String,
+    pub vkey_hash: String,
+    pub height: u64,
+}
+
+#[cw_serde]
+pub struct ProverHistoryResponse {
+    pub verifications: Vec<VerificationResponse>,
+}
+
+#[cw_serde]
+pub struct VerifyProofResponse {
+    pub is_valid: bool,
+    pub vkey_hash: String,
+    /// the public signals the proof was checked against, a bound sender signal included
+    pub public_signals: Vec<String>,
+    /// whether `Proof` would refuse the nullifier, only for registered keys with one
+    pub nullifier_spent: Option<bool>,
+    /// why the proof was rejected
+    pub error: Option<String>,
+}
@@ -1,8 +1,12 @@
 use super::error::ContractError;
-use crate::state::{ProofStr, VkeyStr};
+use crate::state::{PointEncoding, PreparedVkey, ProofStr, VkeyStr};
 use bellman_ce_verifier::{Proof, VerifyingKey};
-use cosmwasm_std::ensure;
-use pairing_ce::bn256::{G1Affine, G1Uncompressed, G2Affine, G2Uncompressed};
+use cosmwasm_std::Binary;
+use ff_ce::{PrimeField, PrimeFieldRepr};
+use pairing_ce::bn256::{
+    Bn256, Fq, Fq12, Fq2, Fq6, FqRepr, G1Affine, G1Compressed, G1Uncompressed, G2Affine,
+    G2Compressed, G2Uncompressed,
+};
 use pairing_ce::{CurveAffine, EncodedPoint, Engine};
 
 /// convert the proof into the affine type, which will be used to verify
@@ -10,33 +14,11 @@ pub fn parse_proof<E>(pof: ProofStr) -> Result<Proof<E>, ContractError>
 where
     E: Engine<G1Affine = G1Affine, G2Affine = G2Affine>,
 {
-    let pi_a = pof.pi_a;
-    let pi_b = pof.pi_b;
-    let pi_c = pof.pi_c;
+    let encoding = pof.encoding;
 
-    ensure!(pi_a.len() == 64, ContractError::ErrorProof {});
-    ensure!(pi_b.len() == 128, ContractError::ErrorProof {});
-    ensure!(pi_c.len() == 64, ContractError::ErrorProof {});
-
-    let mut a_arr: [u8; 64] = [0; 64];
-    let mut b_arr: [u8; 128] = [0; 128];
-    let mut c_arr: [u8; 64] = [0; 64];
-
-    a_arr[..pi_a.len()].copy_from_slice(&pi_a[..]);
-
-    b_arr[..pi_b.len()].copy_from_slice(&pi_b[..]);
-
-    c_arr[..pi_c.len()].copy_from_slice(&pi_c[..]);
-
-    let pia_affine: G1Affine = G1Uncompressed::to_g1_uncompressed(a_arr)
-        .into_affine()
-        .map_err(|_| ContractError::ErrorProof {})?;
-    let pib_affine: G2Affine = G2Uncompressed::to_g2_uncompressed(b_arr)
-        .into_affine()
-        .map_err(|_| ContractError::ErrorProof {})?;
-    let pic_affine: G1Affine = G1Uncompressed::to_g1_uncompressed(c_arr)
-        .into_affine()
-        .map_err(|_| ContractError::ErrorProof {})?;
+    let pia_affine = g1_affine(&pof.pi_a, encoding).map_err(proof_error("proof_a"))?;
+    let pib_affine = g2_affine(&pof.pi_b, encoding).map_err(proof_error("proof_b"))?;
+    let pic_affine = g1_affine(&pof.pi_c, encoding).map_err(proof_error("proof_c"))?;
 
     Ok(Proof {
         a: pia_affine,
@@ -50,73 +32,24 @@ pub fn parse_vkey<E>(vk: VkeyStr) -> Result<VerifyingKey<E>, ContractError>
 where
     E: Engine<G1Affine = G1Affine, G2Affine = G2Affine>,
 {
-    let vk_alpha_1 = vk.alpha_1;
-    let vk_beta_2 = vk.beta_2;
-    let vk_gamma_2 = vk.gamma_2;
-    let vk_delta_2 = vk.delta_2;
-    let vk_ic0 = vk.ic0;
-    let vk_ic1 = vk.ic1;
-
-    ensure!(
-        vk_alpha_1.len() == 64,
-        ContractError::ErrorVerificationKey {}
-    );
-    ensure!(
-        vk_beta_2.len() == 128,
-        ContractError::ErrorVerificationKey {}
-    );
-    ensure!(
-        vk_gamma_2.len() == 128,
-        ContractError::ErrorVerificationKey {}
-    );
-    ensure!(
-        vk_delta_2.len() == 128,
-        ContractError::ErrorVerificationKey {}
-    );
-    ensure!(vk_ic0.len() == 64, ContractError::ErrorVerificationKey {});
-    ensure!(vk_ic1.len() == 64, ContractError::ErrorVerificationKey {});
-
-    let mut alpha1: [u8; 64] = [0; 64];
-    let mut beta2: [u8; 128] = [0; 128];
-    let mut gamma2: [u8; 128] = [0; 128];
-    let mut delta2: [u8; 128] = [0; 128];
-    let mut ic_0: [u8; 64] = [0; 64];
-    let mut ic_1: [u8; 64] = [0; 64];
-    let mut ic = Vec::new();
-
-    alpha1[..vk_alpha_1.len()].copy_from_slice(&vk_alpha_1[..]);
-
-    beta2[..vk_beta_2.len()].copy_from_slice(&vk_beta_2[..]);
-
-    gamma2[..vk_gamma_2.len()].copy_from_slice(&vk_gamma_2[..]);
-
-    delta2[..vk_delta_2.len()].copy_from_slice(&vk_delta_2[..]);
-
-    ic_0[..vk_ic0.len()].copy_from_slice(&vk_ic0[..]);
-
-    ic_1[..vk_ic1.len()].copy_from_slice(&vk_ic1[..]);
-
-    let alpha1_affine = G1Uncompressed::to_g1_uncompressed(alpha1)
-        .into_affine()
-        .map_err(|_| ContractError::ErrorVerificationKey {})?;
-    let beta2_affine = G2Uncompressed::to_g2_uncompressed(beta2)
-        .into_affine()
-        .map_err(|_| ContractError::ErrorVerificationKey {})?;
-    let gamma2_affine = G2Uncompressed::to_g2_uncompressed(gamma2)
-        .into_affine()
-        .map_err(|_| ContractError::ErrorVerificationKey {})?;
-    let delta2_affine = G2Uncompressed::to_g2_uncompressed(delta2)
-        .into_affine()
-        .map_err(|_| ContractError::ErrorVerificationKey {})?;
-    let ic0_affine = G1Uncompressed::to_g1_uncompressed(ic_0)
-        .into_affine()
-        .map_err(|_| ContractError::ErrorVerificationKey {})?;
-    let ic1_affine = G1Uncompressed::to_g1_uncompressed(ic_1)
-        .into_affine()
-        .map_err(|_| ContractError::ErrorVerificationKey {})?;
+    let encoding = PointEncoding::Uncompressed;
+
+    // ic[0] is the constant term, so even a circuit without public inputs has one point
+    if vk.ic.is_empty() {
+        return Err(vkey_error("vk_ic")("missing the constant term".to_string()));
+    }
 
-    ic.push(ic0_affine);
-    ic.push(ic1_affine);
+    let alpha1_affine = g1_affine(&vk.alpha_1, encoding).map_err(vkey_error("vk_alpha1"))?;
+    let beta2_affine = g2_affine(&vk.beta_2, encoding).map_err(vkey_error("vk_beta_2"))?;
+    let gamma2_affine = g2_affine(&vk.gamma_2, encoding).map_err(vkey_error("vk_gamma_2"))?;
+    let delta2_affine = g2_affine(&vk.delta_2, encoding).map_err(vkey_error("vk_delta_2"))?;
+
+    let ic = vk
+        .ic
+        .iter()
+        .enumerate()
+        .map(|(i, ic_i)| g1_affine(ic_i, encoding).map_err(vkey_error(format!("vk_ic[{}]", i))))
+        .collect::<Result<Vec<G1Affine>, ContractError>>()?;
 
     // return verification key
     Ok(VerifyingKey {
@@ -129,3 +62,185 @@ where
         ic,
     })
 }
+
+/// the key as stored, the points of a compressed key are checked like those `parse_vkey` reads
+pub fn uncompressed_vkey(vk: VkeyStr, encoding: PointEncoding) -> Result<VkeyStr, ContractError> {
+    if encoding == PointEncoding::Uncompressed {
+        return Ok(vk);
+    }
+    let g1 = |bytes: &[u8], field: String| {
+        g1_affine(bytes, encoding)
+            .map(|point| point.into_uncompressed().as_ref().to_vec())
+            .map_err(vkey_error(field))
+    };
+    let g2 = |bytes: &[u8], field: &str| {
+        g2_affine(bytes, encoding)
+            .map(|point| point.into_uncompressed().as_ref().to_vec())
+            .map_err(vkey_error(field))
+    };
+
+    Ok(VkeyStr {
+        alpha_1: g1(&vk.alpha_1, "vk_alpha1".to_string())?,
+        beta_2: g2(&vk.beta_2, "vk_beta_2")?,
+        gamma_2: g2(&vk.gamma_2, "vk_gamma_2")?,
+        delta_2: g2(&vk.delta_2, "vk_delta_2")?,
+        ic: vk
+            .ic
+            .iter()
+            .enumerate()
+            .map(|(i, ic_i)| g1(ic_i, format!("vk_ic[{}]", i)))
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+/// the values of a `PreparedVkey`
+pub struct PreparedKey {
+    pub alpha_beta: Fq12,
+    pub neg_gamma_g2: G2Affine,
+    pub neg_delta_g2: G2Affine,
+    pub ic: Vec<G1Affine>,
+}
+
+/// check a stored key once, the prepared form is read back without checking its points again
+pub fn prepare_vkey(vk: &VkeyStr) -> Result<PreparedVkey, ContractError> {
+    let vkey = parse_vkey::<Bn256>(vk.clone())?;
+
+    let mut neg_gamma_g2 = vkey.gamma_g2;
+    neg_gamma_g2.negate();
+    let mut neg_delta_g2 = vkey.delta_g2;
+    neg_delta_g2.negate();
+
+    Ok(PreparedVkey {
+        alpha_beta: Binary::from(fq12_bytes(&Bn256::pairing(vkey.alpha_g1, vkey.beta_g2))),
+        neg_gamma_2: Binary::from(neg_gamma_g2.into_uncompressed().as_ref().to_vec()),
+        neg_delta_2: Binary::from(neg_delta_g2.into_uncompressed().as_ref().to_vec()),
+        ic: vkey
+            .ic
+            .iter()
+            .map(|ic_i| Binary::from(ic_i.into_uncompressed().as_ref().to_vec()))
+            .collect(),
+    })
+}
+
+/// read back what `prepare_vkey` stored, skipping the subgroup checks it already did
+pub fn parse_prepared_vkey(pvk: &PreparedVkey) -> Result<PreparedKey, ContractError> {
+    if pvk.ic.is_empty() {
+        return Err(vkey_error("prepared.ic")(
+            "missing the constant term".to_string(),
+        ));
+    }
+
+    Ok(PreparedKey {
+        alpha_beta: fq12_from_bytes(&pvk.alpha_beta)
+            .ok_or_else(|| vkey_error("prepared.alpha_beta")("not an Fq12 element".to_string()))?,
+        neg_gamma_g2: decode_point_unchecked::<G2Uncompressed>(&pvk.neg_gamma_2)
+            .map_err(vkey_error("prepared.neg_gamma_2"))?,
+        neg_delta_g2: decode_point_unchecked::<G2Uncompressed>(&pvk.neg_delta_2)
+            .map_err(vkey_error("prepared.neg_delta_2"))?,
+        ic: pvk
+            .ic
+            .iter()
+            .enumerate()
+            .map(|(i, ic_i)| {
+                decode_point_unchecked::<G1Uncompressed>(ic_i)
+                    .map_err(vkey_error(format!("prepared.ic[{}]", i)))
+            })
+            .collect::<Result<_, _>>()?,
+    })
+}
+
+// Fq12 = c0 + c1 w over Fq6 = c0 + c1 v + c2 v^2 over Fq2 = c0 + c1 u, c0 first at every level
+fn fq12_bytes(value: &Fq12) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(12 * 32);
+    for fq6 in [&value.c0, &value.c1] {
+        for fq2 in [&fq6.c0, &fq6.c1, &fq6.c2] {
+            for fq in [&fq2.c0, &fq2.c1] {
+                fq.into_repr()
+                    .write_be(&mut bytes)
+                    .expect("writing to a vec never fails");
+            }
+        }
+    }
+    bytes
+}
+
+fn fq12_from_bytes(bytes: &[u8]) -> Option<Fq12> {
+    if bytes.len() != 12 * 32 {
+        return None;
+    }
+    let fq = bytes
+        .chunks(32)
+        .map(|chunk| {
+            let mut repr = FqRepr::default();
+            repr.read_be(chunk).ok()?;
+            Fq::from_repr(repr).ok()
+        })
+        .collect::<Option<Vec<Fq>>>()?;
+    let fq2 = |i: usize| Fq2 {
+        c0: fq[2 * i],
+        c1: fq[2 * i + 1],
+    };
+    let fq6 = |i: usize| Fq6 {
+        c0: fq2(3 * i),
+        c1: fq2(3 * i + 1),
+        c2: fq2(3 * i + 2),
+    };
+
+    Some(Fq12 {
+        c0: fq6(0),
+        c1: fq6(1),
+    })
+}
+
+/// the point of G1 the bytes encode, or why they don't
+fn g1_affine(bytes: &[u8], encoding: PointEncoding) -> Result<G1Affine, String> {
+    match encoding {
+        PointEncoding::Uncompressed => decode_point::<G1Uncompressed>(bytes),
+        PointEncoding::Compressed => decode_point::<G1Compressed>(bytes),
+    }
+}
+
+/// the point of the G2 subgroup the bytes encode, or why they don't
+fn g2_affine(bytes: &[u8], encoding: PointEncoding) -> Result<G2Affine, String> {
+    match encoding {
+        PointEncoding::Uncompressed => decode_point::<G2Uncompressed>(bytes),
+        PointEncoding::Compressed => decode_point::<G2Compressed>(bytes),
+    }
+}
+
+fn decode_point<P: EncodedPoint>(bytes: &[u8]) -> Result<P::Affine, String> {
+    encoded::<P>(bytes)?
+        .into_affine()
+        .map_err(|err| err.to_string())
+}
+
+fn decode_point_unchecked<P: EncodedPoint>(bytes: &[u8]) -> Result<P::Affine, String> {
+    encoded::<P>(bytes)?
+        .into_affine_unchecked()
+        .map_err(|err| err.to_string())
+}
+
+fn encoded<P: EncodedPoint>(bytes: &[u8]) -> Result<P, String> {
+    if bytes.len() != P::size() {
+        return Err(format!("expected {} bytes, got {}", P::size(), bytes.len()));
+    }
+    let mut encoded = P::empty();
+    encoded.as_mut().copy_from_slice(bytes);
+    Ok(encoded)
+}
+
+/// names the proof field a reason is about
+fn proof_error(field: impl Into<String>) -> impl FnOnce(String) -> ContractError {
+    move |reason| ContractError::ErrorProof {
+        field: field.into(),
+        reason,
+    }
+}
+
+/// names the key field a reason is about
+fn vkey_error(field: impl Into<String>) -> impl FnOnce(String) -> ContractError {
+    move |reason| ContractError::ErrorVerificationKey {
+        field: field.into(),
+        reason,
+    }
+}
@@ -1,12 +1,39 @@
 #[cfg(test)]
 mod test_module {
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{coin, coins, from_binary, Coin, Deps, DepsMut};
+    use bellman_ce_verifier::{prepare_verifying_key, verify_proof};
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_dependencies_with_balance, mock_env, mock_info,
+    };
+    use cosmwasm_std::{
+        attr, coin, coins, from_binary, from_slice, to_binary, Addr, BankMsg, Binary, Coin, Deps,
+        DepsMut, Env, Response, StdError, SubMsg, Uint128, WasmMsg,
+    };
+    use cw2::{get_contract_version, set_contract_version};
+    use cw20::{Cw20Coin, Cw20ExecuteMsg, Cw20ReceiveMsg};
+    use ff_ce::{Field, PrimeField};
+    use pairing_ce::bn256::{Bn256, Fr};
+    use pairing_ce::bn256::{G1Uncompressed, G2Uncompressed};
+    use pairing_ce::{CurveAffine, CurveProjective, EncodedPoint};
 
-    use crate::contract::{execute, instantiate, query};
+    use crate::commitment::{proof_commitment, vkey_hash};
+    use crate::contract::{check_proof, decode_proof, execute, instantiate, migrate, query};
     use crate::error::ContractError;
-    use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, ZkeysResponse, ProofResponse};
-    use crate::state::Config;
+    use crate::migrations::v0_1;
+    use crate::msg::{
+        BatchProof, BountyResponse, CircuitRef, CircuitResponse, CircuitsResponse,
+        CommitmentResponse, ExecuteMsg, FeeBalanceResponse, InstantiateMsg, IssuersResponse,
+        MigrateMsg, NullifierResponse, ProofBinaryResponse, ProofResponse, ProverHistoryResponse,
+        ProversResponse, QueryMsg, SenderSignalResponse, SolvedCircuitsResponse,
+        VerificationResponse, VerifyProofResponse, VerifyingKeySource, ZkeysBinaryResponse,
+        ZkeysResponse, DEFAULT_CIRCUIT, PROOF_SYSTEM,
+    };
+    use crate::parser::{parse_proof, parse_vkey};
+    use crate::signal_helpers::sender_public_signal;
+    use crate::snarkjs::SnarkjsVerifyingKey;
+    use crate::state::{
+        CircuitMetadata, CircuitStatus, Config, FeeMode, PointEncoding, ProofStr, PublicSignalMode,
+        UnrelatedDenoms, ZKEYS,
+    };
 
     fn assert_config_state(deps: Deps, expected: Config) {
         let res = query(deps, mock_env(), QueryMsg::Config {}).unwrap();
@@ -18,6 +45,11 @@ mod test_module {
         let msg = InstantiateMsg {
             set_zkeys_price: None,
             publish_proof_price: None,
+            set_zkeys_cw20_price: None,
+            publish_proof_cw20_price: None,
+            commitment_expiry: None,
+            fee_mode: None,
+            unrelated_denoms: None,
         };
 
         let info = mock_info("creator", &coins(2, "token"));
@@ -29,6 +61,11 @@ mod test_module {
         let msg = InstantiateMsg {
             set_zkeys_price: Some(zkeys_price),
             publish_proof_price: Some(proof_price),
+            set_zkeys_cw20_price: None,
+            publish_proof_cw20_price: None,
+            commitment_expiry: None,
+            fee_mode: None,
+            unrelated_denoms: None,
         };
 
         let info = mock_info("creator", &coins(2, "token"));
@@ -36,6 +73,43 @@ mod test_module {
             .expect("contract successfully handles InstantiateMsg");
     }
 
+    fn mock_init_with_exact_fee(deps: DepsMut, unrelated_denoms: UnrelatedDenoms) {
+        let msg = InstantiateMsg {
+            set_zkeys_price: Some(coin(1, "token")),
+            publish_proof_price: Some(coin(1, "token")),
+            set_zkeys_cw20_price: None,
+            publish_proof_cw20_price: None,
+            commitment_expiry: None,
+            fee_mode: Some(FeeMode::Exact),
+            unrelated_denoms: Some(unrelated_denoms),
+        };
+
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps, mock_env(), info, msg)
+            .expect("contract successfully handles InstantiateMsg");
+    }
+
+    fn mock_init_with_cw20_price(deps: DepsMut) {
+        let msg = InstantiateMsg {
+            set_zkeys_price: None,
+            publish_proof_price: None,
+            set_zkeys_cw20_price: Some(Cw20Coin {
+                address: "cw20_token".to_string(),
+                amount: Uint128::new(2),
+            }),
+            publish_proof_cw20_price: Some(Cw20Coin {
+                address: "cw20_token".to_string(),
+                amount: Uint128::new(2),
+            }),
+            commitment_expiry: None,
+            fee_mode: Some(FeeMode::Exact),
+            unrelated_denoms: None,
+        };
+
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps, mock_env(), info, msg)
+            .expect("contract successfully handles InstantiateMsg");
+    }
 
     #[test]
     fn proper_init_no_fees() {
@@ -45,13 +119,18 @@ mod test_module {
         assert_config_state(
             deps.as_ref(),
             Config {
+                owner: Addr::unchecked("creator"),
                 zkeys_price: None,
                 proof_price: None,
+                zkeys_cw20_price: None,
+                proof_cw20_price: None,
+                fee_mode: FeeMode::Sufficient,
+                unrelated_denoms: UnrelatedDenoms::Refund,
+                commitment_expiry: 100,
             },
         );
     }
 
-
     #[test]
     fn proper_init_with_fees() {
         let mut deps = mock_dependencies();
@@ -61,8 +140,14 @@ mod test_module {
         assert_config_state(
             deps.as_ref(),
             Config {
+                owner: Addr::unchecked("creator"),
                 zkeys_price: Some(coin(3, "token")),
                 proof_price: Some(coin(4, "token")),
+                zkeys_cw20_price: None,
+                proof_cw20_price: None,
+                fee_mode: FeeMode::Sufficient,
+                unrelated_denoms: UnrelatedDenoms::Refund,
+                commitment_expiry: 100,
             },
         );
     }
@@ -72,14 +157,25 @@ mod test_module {
         let mut deps = mock_dependencies();
         mock_init_with_price(deps.as_mut(), coin(2, "token"), coin(2, "token"));
         let info = mock_info("alice_key", &[]);
-        let msg = ExecuteMsg::Zkeys { 
-            public_signal: "33".to_string(), 
-            vk_alpha1: "134341fbe5f0719617003adb9c8fe9038d5d913d1a1e961618cd67f8f097d0cb203a9e851d18a4cfe8ab963083acda4af394c8c2461930397057da9edf030d4c".to_string(), 
-            vk_beta_2: "26e36c244fbd85b1f96bb3c4eecc5024f9e0507247e6675e56d5d222f43921872c13498425fa4b090c401561092dac563a0864ef22aa2bcfcf0e75bda8ad94aa10e1a9938cab807dc19806127b49d697de33abf79ad5ae46ca240927dd9c57d623c3cad4c8c16360c9199a701b707474fd6bd47e8841d4ebb7a88b826459535d".to_string(), 
-            vk_gamma_2: "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c21800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa".to_string(), 
-            vk_delta_2: "0c80d2c61aaac33c924c322ed740b6ed3775eb171b173c09640a29aa1322e5450dd122b23f09aab8b1a3fd739d296949f328fd2cd9deedc6d1ed3262803a19e804b9033fb8a3476eb8b5e609f529cc52fbaa9df6f59a5a67aab944cde646d13f2d17ed688afb5f3abc97f978fe9da3a25feee8bfbb0762ae80be6aabb76a742d".to_string(), 
-            vk_ic0: "22f4a08cff59356634d3cd5ad41e85e4b4b484a7633eb64ddd739032936add322101b08d07ff3315947df2a6652e667d6a3e96fca1a661aadc2092a85c3912fd".to_string(), 
-            vk_ic1: "17fa0f95ec76599763a6629b557bf18fd938305c7472fd81c368f8ca76615cee037b264dee54b6fd3298fd0396525cb1aec0dd971e3ab771f242ce10980763cd".to_string(),
+        let msg = ExecuteMsg::Zkeys {
+            circuit_id: None,
+            metadata: None,
+            public_signals: vec!["33".to_string()],
+            public_signal_mode: None,
+            bind_sender: None,
+            nullifier_index: None,
+            bounty: None,
+            bounty_deadline: None,
+            vk_alpha1: "134341fbe5f0719617003adb9c8fe9038d5d913d1a1e961618cd67f8f097d0cb203a9e851d18a4cfe8ab963083acda4af394c8c2461930397057da9edf030d4c".to_string(),
+            vk_beta_2: "26e36c244fbd85b1f96bb3c4eecc5024f9e0507247e6675e56d5d222f43921872c13498425fa4b090c401561092dac563a0864ef22aa2bcfcf0e75bda8ad94aa10e1a9938cab807dc19806127b49d697de33abf79ad5ae46ca240927dd9c57d623c3cad4c8c16360c9199a701b707474fd6bd47e8841d4ebb7a88b826459535d".to_string(),
+            vk_gamma_2: "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c21800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa".to_string(),
+            vk_delta_2: "0c80d2c61aaac33c924c322ed740b6ed3775eb171b173c09640a29aa1322e5450dd122b23f09aab8b1a3fd739d296949f328fd2cd9deedc6d1ed3262803a19e804b9033fb8a3476eb8b5e609f529cc52fbaa9df6f59a5a67aab944cde646d13f2d17ed688afb5f3abc97f978fe9da3a25feee8bfbb0762ae80be6aabb76a742d".to_string(),
+            vk_ic: vec![
+                "22f4a08cff59356634d3cd5ad41e85e4b4b484a7633eb64ddd739032936add322101b08d07ff3315947df2a6652e667d6a3e96fca1a661aadc2092a85c3912fd".to_string(),
+                "17fa0f95ec76599763a6629b557bf18fd938305c7472fd81c368f8ca76615cee037b264dee54b6fd3298fd0396525cb1aec0dd971e3ab771f242ce10980763cd".to_string(),
+            ],
+            point_encoding: None,
+            snarkjs_vkey: None,
         };
         let res = execute(deps.as_mut(), mock_env(), info, msg);
         match res {
@@ -89,128 +185,664 @@ mod test_module {
         }
     }
 
-
     fn mock_alice_set_zkeys(deps: DepsMut, sent: &[Coin]) {
         // alice can register an available name
         let info = mock_info("alice_key", sent);
-        let msg = ExecuteMsg::Zkeys { 
-            public_signal: "33".to_string(), 
-            vk_alpha1: "134341fbe5f0719617003adb9c8fe9038d5d913d1a1e961618cd67f8f097d0cb203a9e851d18a4cfe8ab963083acda4af394c8c2461930397057da9edf030d4c".to_string(), 
-            vk_beta_2: "26e36c244fbd85b1f96bb3c4eecc5024f9e0507247e6675e56d5d222f43921872c13498425fa4b090c401561092dac563a0864ef22aa2bcfcf0e75bda8ad94aa10e1a9938cab807dc19806127b49d697de33abf79ad5ae46ca240927dd9c57d623c3cad4c8c16360c9199a701b707474fd6bd47e8841d4ebb7a88b826459535d".to_string(), 
-            vk_gamma_2: "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c21800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa".to_string(), 
-            vk_delta_2: "0c80d2c61aaac33c924c322ed740b6ed3775eb171b173c09640a29aa1322e5450dd122b23f09aab8b1a3fd739d296949f328fd2cd9deedc6d1ed3262803a19e804b9033fb8a3476eb8b5e609f529cc52fbaa9df6f59a5a67aab944cde646d13f2d17ed688afb5f3abc97f978fe9da3a25feee8bfbb0762ae80be6aabb76a742d".to_string(), 
-            vk_ic0: "22f4a08cff59356634d3cd5ad41e85e4b4b484a7633eb64ddd739032936add322101b08d07ff3315947df2a6652e667d6a3e96fca1a661aadc2092a85c3912fd".to_string(), 
-            vk_ic1: "17fa0f95ec76599763a6629b557bf18fd938305c7472fd81c368f8ca76615cee037b264dee54b6fd3298fd0396525cb1aec0dd971e3ab771f242ce10980763cd".to_string(),
+        let msg = ExecuteMsg::Zkeys {
+            circuit_id: None,
+            metadata: None,
+            public_signals: vec!["33".to_string()],
+            public_signal_mode: None,
+            bind_sender: None,
+            nullifier_index: None,
+            bounty: None,
+            bounty_deadline: None,
+            vk_alpha1: "134341fbe5f0719617003adb9c8fe9038d5d913d1a1e961618cd67f8f097d0cb203a9e851d18a4cfe8ab963083acda4af394c8c2461930397057da9edf030d4c".to_string(),
+            vk_beta_2: "26e36c244fbd85b1f96bb3c4eecc5024f9e0507247e6675e56d5d222f43921872c13498425fa4b090c401561092dac563a0864ef22aa2bcfcf0e75bda8ad94aa10e1a9938cab807dc19806127b49d697de33abf79ad5ae46ca240927dd9c57d623c3cad4c8c16360c9199a701b707474fd6bd47e8841d4ebb7a88b826459535d".to_string(),
+            vk_gamma_2: "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c21800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa".to_string(),
+            vk_delta_2: "0c80d2c61aaac33c924c322ed740b6ed3775eb171b173c09640a29aa1322e5450dd122b23f09aab8b1a3fd739d296949f328fd2cd9deedc6d1ed3262803a19e804b9033fb8a3476eb8b5e609f529cc52fbaa9df6f59a5a67aab944cde646d13f2d17ed688afb5f3abc97f978fe9da3a25feee8bfbb0762ae80be6aabb76a742d".to_string(),
+            vk_ic: vec![
+                "22f4a08cff59356634d3cd5ad41e85e4b4b484a7633eb64ddd739032936add322101b08d07ff3315947df2a6652e667d6a3e96fca1a661aadc2092a85c3912fd".to_string(),
+                "17fa0f95ec76599763a6629b557bf18fd938305c7472fd81c368f8ca76615cee037b264dee54b6fd3298fd0396525cb1aec0dd971e3ab771f242ce10980763cd".to_string(),
+            ],
+            point_encoding: None,
+            snarkjs_vkey: None,
         };
 
-        let _res = execute(deps, mock_env(), info, msg)
-            .expect("contract handles set zkeys parameters");
+        let _res =
+            execute(deps, mock_env(), info, msg).expect("contract handles set zkeys parameters");
+    }
+
+    fn mock_alice_set_zkeys_with_cw20(
+        deps: DepsMut,
+        token: &str,
+        amount: u128,
+    ) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::Zkeys {
+            circuit_id: None,
+            metadata: None,
+            public_signals: vec!["33".to_string()],
+            public_signal_mode: None,
+            bind_sender: None,
+            nullifier_index: None,
+            bounty: None,
+            bounty_deadline: None,
+            vk_alpha1: "134341fbe5f0719617003adb9c8fe9038d5d913d1a1e961618cd67f8f097d0cb203a9e851d18a4cfe8ab963083acda4af394c8c2461930397057da9edf030d4c".to_string(),
+            vk_beta_2: "26e36c244fbd85b1f96bb3c4eecc5024f9e0507247e6675e56d5d222f43921872c13498425fa4b090c401561092dac563a0864ef22aa2bcfcf0e75bda8ad94aa10e1a9938cab807dc19806127b49d697de33abf79ad5ae46ca240927dd9c57d623c3cad4c8c16360c9199a701b707474fd6bd47e8841d4ebb7a88b826459535d".to_string(),
+            vk_gamma_2: "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c21800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa".to_string(),
+            vk_delta_2: "0c80d2c61aaac33c924c322ed740b6ed3775eb171b173c09640a29aa1322e5450dd122b23f09aab8b1a3fd739d296949f328fd2cd9deedc6d1ed3262803a19e804b9033fb8a3476eb8b5e609f529cc52fbaa9df6f59a5a67aab944cde646d13f2d17ed688afb5f3abc97f978fe9da3a25feee8bfbb0762ae80be6aabb76a742d".to_string(),
+            vk_ic: vec![
+                "22f4a08cff59356634d3cd5ad41e85e4b4b484a7633eb64ddd739032936add322101b08d07ff3315947df2a6652e667d6a3e96fca1a661aadc2092a85c3912fd".to_string(),
+                "17fa0f95ec76599763a6629b557bf18fd938305c7472fd81c368f8ca76615cee037b264dee54b6fd3298fd0396525cb1aec0dd971e3ab771f242ce10980763cd".to_string(),
+            ],
+            point_encoding: None,
+            snarkjs_vkey: None,
+        };
+
+        let wrapper = Cw20ReceiveMsg {
+            sender: "alice_key".to_string(),
+            amount: Uint128::new(amount),
+            msg: to_binary(&msg).unwrap(),
+        };
+
+        execute(
+            deps,
+            mock_env(),
+            mock_info(token, &[]),
+            ExecuteMsg::Receive(wrapper),
+        )
+    }
+
+    fn mock_alice_set_zkeys_with_bounty(deps: DepsMut, sent: &[Coin]) {
+        // alice pays 10 token to whoever solves it first
+        let info = mock_info("alice_key", sent);
+        let msg = ExecuteMsg::Zkeys {
+            circuit_id: None,
+            metadata: None,
+            public_signals: vec!["33".to_string()],
+            public_signal_mode: None,
+            bind_sender: None,
+            nullifier_index: None,
+            bounty: Some(coin(10, "token")),
+            bounty_deadline: Some(mock_env().block.height + 10),
+            vk_alpha1: "134341fbe5f0719617003adb9c8fe9038d5d913d1a1e961618cd67f8f097d0cb203a9e851d18a4cfe8ab963083acda4af394c8c2461930397057da9edf030d4c".to_string(),
+            vk_beta_2: "26e36c244fbd85b1f96bb3c4eecc5024f9e0507247e6675e56d5d222f43921872c13498425fa4b090c401561092dac563a0864ef22aa2bcfcf0e75bda8ad94aa10e1a9938cab807dc19806127b49d697de33abf79ad5ae46ca240927dd9c57d623c3cad4c8c16360c9199a701b707474fd6bd47e8841d4ebb7a88b826459535d".to_string(),
+            vk_gamma_2: "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c21800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa".to_string(),
+            vk_delta_2: "0c80d2c61aaac33c924c322ed740b6ed3775eb171b173c09640a29aa1322e5450dd122b23f09aab8b1a3fd739d296949f328fd2cd9deedc6d1ed3262803a19e804b9033fb8a3476eb8b5e609f529cc52fbaa9df6f59a5a67aab944cde646d13f2d17ed688afb5f3abc97f978fe9da3a25feee8bfbb0762ae80be6aabb76a742d".to_string(),
+            vk_ic: vec![
+                "22f4a08cff59356634d3cd5ad41e85e4b4b484a7633eb64ddd739032936add322101b08d07ff3315947df2a6652e667d6a3e96fca1a661aadc2092a85c3912fd".to_string(),
+                "17fa0f95ec76599763a6629b557bf18fd938305c7472fd81c368f8ca76615cee037b264dee54b6fd3298fd0396525cb1aec0dd971e3ab771f242ce10980763cd".to_string(),
+            ],
+            point_encoding: None,
+            snarkjs_vkey: None,
+        };
+
+        let _res =
+            execute(deps, mock_env(), info, msg).expect("contract handles set zkeys parameters");
+    }
+    fn mock_alice_set_zkeys_with_nullifier(deps: DepsMut, sent: &[Coin]) {
+        // the only public signal doubles as the nullifier
+        let info = mock_info("alice_key", sent);
+        let msg = ExecuteMsg::Zkeys {
+            circuit_id: None,
+            metadata: None,
+            public_signals: vec!["33".to_string()],
+            public_signal_mode: None,
+            bind_sender: None,
+            nullifier_index: Some(0),
+            bounty: None,
+            bounty_deadline: None,
+            vk_alpha1: "134341fbe5f0719617003adb9c8fe9038d5d913d1a1e961618cd67f8f097d0cb203a9e851d18a4cfe8ab963083acda4af394c8c2461930397057da9edf030d4c".to_string(),
+            vk_beta_2: "26e36c244fbd85b1f96bb3c4eecc5024f9e0507247e6675e56d5d222f43921872c13498425fa4b090c401561092dac563a0864ef22aa2bcfcf0e75bda8ad94aa10e1a9938cab807dc19806127b49d697de33abf79ad5ae46ca240927dd9c57d623c3cad4c8c16360c9199a701b707474fd6bd47e8841d4ebb7a88b826459535d".to_string(),
+            vk_gamma_2: "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c21800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa".to_string(),
+            vk_delta_2: "0c80d2c61aaac33c924c322ed740b6ed3775eb171b173c09640a29aa1322e5450dd122b23f09aab8b1a3fd739d296949f328fd2cd9deedc6d1ed3262803a19e804b9033fb8a3476eb8b5e609f529cc52fbaa9df6f59a5a67aab944cde646d13f2d17ed688afb5f3abc97f978fe9da3a25feee8bfbb0762ae80be6aabb76a742d".to_string(),
+            vk_ic: vec![
+                "22f4a08cff59356634d3cd5ad41e85e4b4b484a7633eb64ddd739032936add322101b08d07ff3315947df2a6652e667d6a3e96fca1a661aadc2092a85c3912fd".to_string(),
+                "17fa0f95ec76599763a6629b557bf18fd938305c7472fd81c368f8ca76615cee037b264dee54b6fd3298fd0396525cb1aec0dd971e3ab771f242ce10980763cd".to_string(),
+            ],
+            point_encoding: None,
+            snarkjs_vkey: None,
+        };
+
+        let _res =
+            execute(deps, mock_env(), info, msg).expect("contract handles set zkeys parameters");
+    }
+
+    fn mock_alice_set_zkeys_with_prover_nullifier(deps: DepsMut, sent: &[Coin]) {
+        // provers supply both public signals, the second one is the nullifier
+        let info = mock_info("alice_key", sent);
+        let msg = ExecuteMsg::Zkeys {
+            circuit_id: None,
+            metadata: None,
+            public_signals: vec![],
+            public_signal_mode: Some(PublicSignalMode::Prover),
+            bind_sender: None,
+            nullifier_index: Some(1),
+            bounty: None,
+            bounty_deadline: None,
+            vk_alpha1: "0b48398691de91c464394a8729921d3f5d9d329670135b0ec22594c2029c65d52f1c927f96bc7684b9fba23a8bdb1374780102b3afcd22f64f82a8044136e57b".to_string(),
+            vk_beta_2: "182a536d980e3be824dbdea0f716b38ab62600311c0f9c4ce687d25717d7a54e1b846a6902aa82478e77a6d64d3fe4b28646c0d5ce4452cfb9caeb141c3fa22224e8c568f00d5a3f81e85b595a61c06b3b2936b4338a79e32b024584af480efa2dac966571c316d67ba67d79cbe423cb4b7ac6e43e2b3589d6d02be19d3ddb67".to_string(),
+            vk_gamma_2: "20f7c066b859960c727d58a426fa23eb78e53174cc0212b37c9901ca419e1acf167eef253966d460050a6749b88359d2075e513700159c298865ec4a4217f9520561bc8ae0a767c31ae87edb8ca51f467fb8ab4efc2d7bfc13b24366e457765c22307ec8bb706cb3905ed052743e983abf056bda6cbd92e679c8171d1f389a43".to_string(),
+            vk_delta_2: "1620e2f77313688b433211555b7d18f18260889330887a527f35b88f2aef76de1b52943928ee84136a129c2ac7ea98191c1be4a79aa14a117652ae6160c2df952070dcbff26afaf12ca52d0c5001623ef6c5f5e74f58a2a1399258d5517fffe21026a788c6b9a6a7940e1e488834b3961513182de5b47972ea2ebe3e731b038b".to_string(),
+            vk_ic: vec![
+                "26899a02c275f3d1965685dcdf864550aa3cf29c2a464351e4bcb51ce8b5429911ce4dffdf9fd6e3660acb30bf5e68406cba712c4f1de512fbccc58ccdb4322e".to_string(),
+                "1777f0b376e74b18a1bec252774b67279bb8ce9be13e144565b5c5988307775d0c11818c354d0c7b960e9bde806c08ff6cecd78a70b010452adc88f3136bdd44".to_string(),
+                "0c7b1b2a3feef515d4a3496b949a6fc7c88d6430b9eabb53efebd951cffbbf67104163a5e758ee22430e92b161a2809d70c44270977879c442e0d31f2f5b3fec".to_string(),
+            ],
+            point_encoding: None,
+            snarkjs_vkey: None,
+        };
+
+        let _res =
+            execute(deps, mock_env(), info, msg).expect("contract handles set zkeys parameters");
+    }
+
+    fn mock_alice_set_zkeys_with_invalid_nullifier_index(deps: DepsMut, sent: &[Coin]) {
+        let info = mock_info("alice_key", sent);
+        let msg = ExecuteMsg::Zkeys {
+            circuit_id: None,
+            metadata: None,
+            public_signals: vec!["33".to_string()],
+            public_signal_mode: None,
+            bind_sender: None,
+            nullifier_index: Some(1),
+            bounty: None,
+            bounty_deadline: None,
+            vk_alpha1: "134341fbe5f0719617003adb9c8fe9038d5d913d1a1e961618cd67f8f097d0cb203a9e851d18a4cfe8ab963083acda4af394c8c2461930397057da9edf030d4c".to_string(),
+            vk_beta_2: "26e36c244fbd85b1f96bb3c4eecc5024f9e0507247e6675e56d5d222f43921872c13498425fa4b090c401561092dac563a0864ef22aa2bcfcf0e75bda8ad94aa10e1a9938cab807dc19806127b49d697de33abf79ad5ae46ca240927dd9c57d623c3cad4c8c16360c9199a701b707474fd6bd47e8841d4ebb7a88b826459535d".to_string(),
+            vk_gamma_2: "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c21800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa".to_string(),
+            vk_delta_2: "0c80d2c61aaac33c924c322ed740b6ed3775eb171b173c09640a29aa1322e5450dd122b23f09aab8b1a3fd739d296949f328fd2cd9deedc6d1ed3262803a19e804b9033fb8a3476eb8b5e609f529cc52fbaa9df6f59a5a67aab944cde646d13f2d17ed688afb5f3abc97f978fe9da3a25feee8bfbb0762ae80be6aabb76a742d".to_string(),
+            vk_ic: vec![
+                "22f4a08cff59356634d3cd5ad41e85e4b4b484a7633eb64ddd739032936add322101b08d07ff3315947df2a6652e667d6a3e96fca1a661aadc2092a85c3912fd".to_string(),
+                "17fa0f95ec76599763a6629b557bf18fd938305c7472fd81c368f8ca76615cee037b264dee54b6fd3298fd0396525cb1aec0dd971e3ab771f242ce10980763cd".to_string(),
+            ],
+            point_encoding: None,
+            snarkjs_vkey: None,
+        };
+
+        assert_eq!(
+            execute(deps, mock_env(), info, msg),
+            Err(ContractError::InvalidNullifierIndex {})
+        );
+    }
+
+    fn mock_alice_set_zkeys_bound_to_sender(
+        deps: DepsMut,
+        sent: &[Coin],
+        public_signals: Vec<String>,
+    ) -> Result<Response, ContractError> {
+        // alice can register an available name
+        let info = mock_info("alice_key", sent);
+        let msg = ExecuteMsg::Zkeys {
+            circuit_id: None,
+            metadata: None,
+            public_signals,
+            public_signal_mode: None,
+            //NOTE: the key's only public input is the sender signal
+            bind_sender: Some(true),
+            nullifier_index: None,
+            bounty: None,
+            bounty_deadline: None,
+            vk_alpha1: "134341fbe5f0719617003adb9c8fe9038d5d913d1a1e961618cd67f8f097d0cb203a9e851d18a4cfe8ab963083acda4af394c8c2461930397057da9edf030d4c".to_string(),
+            vk_beta_2: "26e36c244fbd85b1f96bb3c4eecc5024f9e0507247e6675e56d5d222f43921872c13498425fa4b090c401561092dac563a0864ef22aa2bcfcf0e75bda8ad94aa10e1a9938cab807dc19806127b49d697de33abf79ad5ae46ca240927dd9c57d623c3cad4c8c16360c9199a701b707474fd6bd47e8841d4ebb7a88b826459535d".to_string(),
+            vk_gamma_2: "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c21800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa".to_string(),
+            vk_delta_2: "0c80d2c61aaac33c924c322ed740b6ed3775eb171b173c09640a29aa1322e5450dd122b23f09aab8b1a3fd739d296949f328fd2cd9deedc6d1ed3262803a19e804b9033fb8a3476eb8b5e609f529cc52fbaa9df6f59a5a67aab944cde646d13f2d17ed688afb5f3abc97f978fe9da3a25feee8bfbb0762ae80be6aabb76a742d".to_string(),
+            vk_ic: vec![
+                "22f4a08cff59356634d3cd5ad41e85e4b4b484a7633eb64ddd739032936add322101b08d07ff3315947df2a6652e667d6a3e96fca1a661aadc2092a85c3912fd".to_string(),
+                "17fa0f95ec76599763a6629b557bf18fd938305c7472fd81c368f8ca76615cee037b264dee54b6fd3298fd0396525cb1aec0dd971e3ab771f242ce10980763cd".to_string(),
+            ],
+            point_encoding: None,
+            snarkjs_vkey: None,
+        };
+
+        execute(deps, mock_env(), info, msg)
+    }
+
+    fn mock_alice_set_zkeys_with_prover_public_signal(deps: DepsMut, sent: &[Coin]) {
+        // alice can register an available name
+        let info = mock_info("alice_key", sent);
+        let msg = ExecuteMsg::Zkeys {
+            circuit_id: None,
+            metadata: None,
+            //NOTE: provers supply the public signal
+            public_signals: vec![],
+            public_signal_mode: Some(PublicSignalMode::Prover),
+            bind_sender: None,
+            nullifier_index: None,
+            bounty: None,
+            bounty_deadline: None,
+            vk_alpha1: "134341fbe5f0719617003adb9c8fe9038d5d913d1a1e961618cd67f8f097d0cb203a9e851d18a4cfe8ab963083acda4af394c8c2461930397057da9edf030d4c".to_string(),
+            vk_beta_2: "26e36c244fbd85b1f96bb3c4eecc5024f9e0507247e6675e56d5d222f43921872c13498425fa4b090c401561092dac563a0864ef22aa2bcfcf0e75bda8ad94aa10e1a9938cab807dc19806127b49d697de33abf79ad5ae46ca240927dd9c57d623c3cad4c8c16360c9199a701b707474fd6bd47e8841d4ebb7a88b826459535d".to_string(),
+            vk_gamma_2: "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c21800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa".to_string(),
+            vk_delta_2: "0c80d2c61aaac33c924c322ed740b6ed3775eb171b173c09640a29aa1322e5450dd122b23f09aab8b1a3fd739d296949f328fd2cd9deedc6d1ed3262803a19e804b9033fb8a3476eb8b5e609f529cc52fbaa9df6f59a5a67aab944cde646d13f2d17ed688afb5f3abc97f978fe9da3a25feee8bfbb0762ae80be6aabb76a742d".to_string(),
+            vk_ic: vec![
+                "22f4a08cff59356634d3cd5ad41e85e4b4b484a7633eb64ddd739032936add322101b08d07ff3315947df2a6652e667d6a3e96fca1a661aadc2092a85c3912fd".to_string(),
+                "17fa0f95ec76599763a6629b557bf18fd938305c7472fd81c368f8ca76615cee037b264dee54b6fd3298fd0396525cb1aec0dd971e3ab771f242ce10980763cd".to_string(),
+            ],
+            point_encoding: None,
+            snarkjs_vkey: None,
+        };
+
+        let _res =
+            execute(deps, mock_env(), info, msg).expect("contract handles set zkeys parameters");
+    }
+
+    fn mock_alice_set_zkeys_with_extra_public_signal(
+        deps: DepsMut,
+        sent: &[Coin],
+    ) -> Result<Response, ContractError> {
+        // alice can register an available name
+        let info = mock_info("alice_key", sent);
+        let msg = ExecuteMsg::Zkeys {
+            circuit_id: None,
+            metadata: None,
+            //NOTE: two public signals for a key with a single public input
+            public_signals: vec!["33".to_string(), "1".to_string()],
+            public_signal_mode: None,
+            bind_sender: None,
+            nullifier_index: None,
+            bounty: None,
+            bounty_deadline: None,
+            vk_alpha1: "134341fbe5f0719617003adb9c8fe9038d5d913d1a1e961618cd67f8f097d0cb203a9e851d18a4cfe8ab963083acda4af394c8c2461930397057da9edf030d4c".to_string(),
+            vk_beta_2: "26e36c244fbd85b1f96bb3c4eecc5024f9e0507247e6675e56d5d222f43921872c13498425fa4b090c401561092dac563a0864ef22aa2bcfcf0e75bda8ad94aa10e1a9938cab807dc19806127b49d697de33abf79ad5ae46ca240927dd9c57d623c3cad4c8c16360c9199a701b707474fd6bd47e8841d4ebb7a88b826459535d".to_string(),
+            vk_gamma_2: "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c21800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa".to_string(),
+            vk_delta_2: "0c80d2c61aaac33c924c322ed740b6ed3775eb171b173c09640a29aa1322e5450dd122b23f09aab8b1a3fd739d296949f328fd2cd9deedc6d1ed3262803a19e804b9033fb8a3476eb8b5e609f529cc52fbaa9df6f59a5a67aab944cde646d13f2d17ed688afb5f3abc97f978fe9da3a25feee8bfbb0762ae80be6aabb76a742d".to_string(),
+            vk_ic: vec![
+                "22f4a08cff59356634d3cd5ad41e85e4b4b484a7633eb64ddd739032936add322101b08d07ff3315947df2a6652e667d6a3e96fca1a661aadc2092a85c3912fd".to_string(),
+                "17fa0f95ec76599763a6629b557bf18fd938305c7472fd81c368f8ca76615cee037b264dee54b6fd3298fd0396525cb1aec0dd971e3ab771f242ce10980763cd".to_string(),
+            ],
+            point_encoding: None,
+            snarkjs_vkey: None,
+        };
+
+        execute(deps, mock_env(), info, msg)
     }
 
     fn mock_alice_set_invalid_zkeys(deps: DepsMut, sent: &[Coin]) {
         // alice can register an available name
         let info = mock_info("alice_key", sent);
-        let msg = ExecuteMsg::Zkeys { 
-            public_signal: "33".to_string(), 
+        let msg = ExecuteMsg::Zkeys {
+            circuit_id: None,
+            metadata: None,
+            public_signals: vec!["33".to_string()],
+            public_signal_mode: None,
+            bind_sender: None,
+            nullifier_index: None,
+            bounty: None,
+            bounty_deadline: None,
             //NOTE: invalid vk_alpha1
-            vk_alpha1: "234341fbe5f0719617003adb9c8fe9038d5d913d1a1e961618cd67f8f097d0cb203a9e851d18a4cfe8ab963083acda4af394c8c2461930397057da9edf030d4c".to_string(), 
-            vk_beta_2: "26e36c244fbd85b1f96bb3c4eecc5024f9e0507247e6675e56d5d222f43921872c13498425fa4b090c401561092dac563a0864ef22aa2bcfcf0e75bda8ad94aa10e1a9938cab807dc19806127b49d697de33abf79ad5ae46ca240927dd9c57d623c3cad4c8c16360c9199a701b707474fd6bd47e8841d4ebb7a88b826459535d".to_string(), 
-            vk_gamma_2: "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c21800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa".to_string(), 
-            vk_delta_2: "0c80d2c61aaac33c924c322ed740b6ed3775eb171b173c09640a29aa1322e5450dd122b23f09aab8b1a3fd739d296949f328fd2cd9deedc6d1ed3262803a19e804b9033fb8a3476eb8b5e609f529cc52fbaa9df6f59a5a67aab944cde646d13f2d17ed688afb5f3abc97f978fe9da3a25feee8bfbb0762ae80be6aabb76a742d".to_string(), 
-            vk_ic0: "22f4a08cff59356634d3cd5ad41e85e4b4b484a7633eb64ddd739032936add322101b08d07ff3315947df2a6652e667d6a3e96fca1a661aadc2092a85c3912fd".to_string(), 
-            vk_ic1: "17fa0f95ec76599763a6629b557bf18fd938305c7472fd81c368f8ca76615cee037b264dee54b6fd3298fd0396525cb1aec0dd971e3ab771f242ce10980763cd".to_string(),
+            vk_alpha1: "234341fbe5f0719617003adb9c8fe9038d5d913d1a1e961618cd67f8f097d0cb203a9e851d18a4cfe8ab963083acda4af394c8c2461930397057da9edf030d4c".to_string(),
+            vk_beta_2: "26e36c244fbd85b1f96bb3c4eecc5024f9e0507247e6675e56d5d222f43921872c13498425fa4b090c401561092dac563a0864ef22aa2bcfcf0e75bda8ad94aa10e1a9938cab807dc19806127b49d697de33abf79ad5ae46ca240927dd9c57d623c3cad4c8c16360c9199a701b707474fd6bd47e8841d4ebb7a88b826459535d".to_string(),
+            vk_gamma_2: "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c21800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa".to_string(),
+            vk_delta_2: "0c80d2c61aaac33c924c322ed740b6ed3775eb171b173c09640a29aa1322e5450dd122b23f09aab8b1a3fd739d296949f328fd2cd9deedc6d1ed3262803a19e804b9033fb8a3476eb8b5e609f529cc52fbaa9df6f59a5a67aab944cde646d13f2d17ed688afb5f3abc97f978fe9da3a25feee8bfbb0762ae80be6aabb76a742d".to_string(),
+            vk_ic: vec![
+                "22f4a08cff59356634d3cd5ad41e85e4b4b484a7633eb64ddd739032936add322101b08d07ff3315947df2a6652e667d6a3e96fca1a661aadc2092a85c3912fd".to_string(),
+                "17fa0f95ec76599763a6629b557bf18fd938305c7472fd81c368f8ca76615cee037b264dee54b6fd3298fd0396525cb1aec0dd971e3ab771f242ce10980763cd".to_string(),
+            ],
+            point_encoding: None,
+            snarkjs_vkey: None,
         };
 
-        assert_eq!(execute(deps, mock_env(), info, msg), Err(ContractError::ErrorVerificationKey{}));
+        assert!(matches!(
+            execute(deps, mock_env(), info, msg),
+            Err(ContractError::ErrorVerificationKey { field, .. }) if field == "vk_alpha1"
+        ));
     }
 
     fn mock_alice_set_zkeys_with_different_public_signal(deps: DepsMut, sent: &[Coin]) {
         // alice can register an available name
         let info = mock_info("alice_key", sent);
-        let msg = ExecuteMsg::Zkeys { 
-            public_signal: "30".to_string(), 
-            vk_alpha1: "134341fbe5f0719617003adb9c8fe9038d5d913d1a1e961618cd67f8f097d0cb203a9e851d18a4cfe8ab963083acda4af394c8c2461930397057da9edf030d4c".to_string(), 
-            vk_beta_2: "26e36c244fbd85b1f96bb3c4eecc5024f9e0507247e6675e56d5d222f43921872c13498425fa4b090c401561092dac563a0864ef22aa2bcfcf0e75bda8ad94aa10e1a9938cab807dc19806127b49d697de33abf79ad5ae46ca240927dd9c57d623c3cad4c8c16360c9199a701b707474fd6bd47e8841d4ebb7a88b826459535d".to_string(), 
-            vk_gamma_2: "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c21800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa".to_string(), 
-            vk_delta_2: "0c80d2c61aaac33c924c322ed740b6ed3775eb171b173c09640a29aa1322e5450dd122b23f09aab8b1a3fd739d296949f328fd2cd9deedc6d1ed3262803a19e804b9033fb8a3476eb8b5e609f529cc52fbaa9df6f59a5a67aab944cde646d13f2d17ed688afb5f3abc97f978fe9da3a25feee8bfbb0762ae80be6aabb76a742d".to_string(), 
-            vk_ic0: "22f4a08cff59356634d3cd5ad41e85e4b4b484a7633eb64ddd739032936add322101b08d07ff3315947df2a6652e667d6a3e96fca1a661aadc2092a85c3912fd".to_string(), 
-            vk_ic1: "17fa0f95ec76599763a6629b557bf18fd938305c7472fd81c368f8ca76615cee037b264dee54b6fd3298fd0396525cb1aec0dd971e3ab771f242ce10980763cd".to_string(),
+        let msg = ExecuteMsg::Zkeys {
+            circuit_id: None,
+            metadata: None,
+            public_signals: vec!["30".to_string()],
+            public_signal_mode: None,
+            bind_sender: None,
+            nullifier_index: None,
+            bounty: None,
+            bounty_deadline: None,
+            vk_alpha1: "134341fbe5f0719617003adb9c8fe9038d5d913d1a1e961618cd67f8f097d0cb203a9e851d18a4cfe8ab963083acda4af394c8c2461930397057da9edf030d4c".to_string(),
+            vk_beta_2: "26e36c244fbd85b1f96bb3c4eecc5024f9e0507247e6675e56d5d222f43921872c13498425fa4b090c401561092dac563a0864ef22aa2bcfcf0e75bda8ad94aa10e1a9938cab807dc19806127b49d697de33abf79ad5ae46ca240927dd9c57d623c3cad4c8c16360c9199a701b707474fd6bd47e8841d4ebb7a88b826459535d".to_string(),
+            vk_gamma_2: "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c21800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa".to_string(),
+            vk_delta_2: "0c80d2c61aaac33c924c322ed740b6ed3775eb171b173c09640a29aa1322e5450dd122b23f09aab8b1a3fd739d296949f328fd2cd9deedc6d1ed3262803a19e804b9033fb8a3476eb8b5e609f529cc52fbaa9df6f59a5a67aab944cde646d13f2d17ed688afb5f3abc97f978fe9da3a25feee8bfbb0762ae80be6aabb76a742d".to_string(),
+            vk_ic: vec![
+                "22f4a08cff59356634d3cd5ad41e85e4b4b484a7633eb64ddd739032936add322101b08d07ff3315947df2a6652e667d6a3e96fca1a661aadc2092a85c3912fd".to_string(),
+                "17fa0f95ec76599763a6629b557bf18fd938305c7472fd81c368f8ca76615cee037b264dee54b6fd3298fd0396525cb1aec0dd971e3ab771f242ce10980763cd".to_string(),
+            ],
+            point_encoding: None,
+            snarkjs_vkey: None,
         };
 
-        let _res = execute(deps, mock_env(), info, msg)
-            .expect("contract handles set zkeys parameters");
+        let _res =
+            execute(deps, mock_env(), info, msg).expect("contract handles set zkeys parameters");
     }
 
     fn mock_bob_publish_proof_to_verify(deps: DepsMut, sent: &[Coin]) {
         let info = mock_info("bob_key", sent);
-        let msg = ExecuteMsg::Proof { 
-            difficuty_issuer: "alice_key".to_string(), 
-            proof_a: "2a7efa6d4fee4a2df464f6c926a81e709ecf27642f4f99aa5c90bc479ce1122a149e00a4b97ea4ef3caec4d5ab168eb0effa1441ee448678d6e77caa2d19f3b2".to_string(), 
-            proof_b: "023290eac0dc45935bb65780f2dd380c594b207509fceeb768c8d9a33a530c640a3cc26b8fa867e1484bbe1c98131cdaad2c48a370688e259f1aff52a0d393872c41442472714933964a28c649c2ebe4608f08e8e0dd023bb90df134f45d20281c7c24fd81f0affa2450181480411973b2d7b52683fdd9d4e3068ef6b5054296".to_string(), 
-            proof_c: "24102019b76cd1f917b5e765519f65504ecfebfb5f4a11a168fd29048e004e0f03b05f4cd80703e6f7f51c7c3394253a4900f0186386be4015d363425ea27488".to_string(), 
+        let msg = ExecuteMsg::Proof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proof_a: "2a7efa6d4fee4a2df464f6c926a81e709ecf27642f4f99aa5c90bc479ce1122a149e00a4b97ea4ef3caec4d5ab168eb0effa1441ee448678d6e77caa2d19f3b2".to_string(),
+            proof_b: "023290eac0dc45935bb65780f2dd380c594b207509fceeb768c8d9a33a530c640a3cc26b8fa867e1484bbe1c98131cdaad2c48a370688e259f1aff52a0d393872c41442472714933964a28c649c2ebe4608f08e8e0dd023bb90df134f45d20281c7c24fd81f0affa2450181480411973b2d7b52683fdd9d4e3068ef6b5054296".to_string(),
+            proof_c: "24102019b76cd1f917b5e765519f65504ecfebfb5f4a11a168fd29048e004e0f03b05f4cd80703e6f7f51c7c3394253a4900f0186386be4015d363425ea27488".to_string(),
+            point_encoding: None,
+            snarkjs_proof: None,
+            public_signals: None,
         };
 
-        let _res = execute(deps, mock_env(), info, msg)
-            .expect("contract handles verify proof failed");
+        let _res =
+            execute(deps, mock_env(), info, msg).expect("contract handles verify proof failed");
     }
 
     fn mock_bob_publish_proof_to_verify_with_different_public_signal(deps: DepsMut, sent: &[Coin]) {
         let info = mock_info("bob_key", sent);
-        let msg = ExecuteMsg::Proof { 
-            difficuty_issuer: "alice_key".to_string(), 
-            proof_a: "2a7efa6d4fee4a2df464f6c926a81e709ecf27642f4f99aa5c90bc479ce1122a149e00a4b97ea4ef3caec4d5ab168eb0effa1441ee448678d6e77caa2d19f3b2".to_string(), 
-            proof_b: "023290eac0dc45935bb65780f2dd380c594b207509fceeb768c8d9a33a530c640a3cc26b8fa867e1484bbe1c98131cdaad2c48a370688e259f1aff52a0d393872c41442472714933964a28c649c2ebe4608f08e8e0dd023bb90df134f45d20281c7c24fd81f0affa2450181480411973b2d7b52683fdd9d4e3068ef6b5054296".to_string(), 
-            proof_c: "24102019b76cd1f917b5e765519f65504ecfebfb5f4a11a168fd29048e004e0f03b05f4cd80703e6f7f51c7c3394253a4900f0186386be4015d363425ea27488".to_string(), 
+        let msg = ExecuteMsg::Proof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proof_a: "2a7efa6d4fee4a2df464f6c926a81e709ecf27642f4f99aa5c90bc479ce1122a149e00a4b97ea4ef3caec4d5ab168eb0effa1441ee448678d6e77caa2d19f3b2".to_string(),
+            proof_b: "023290eac0dc45935bb65780f2dd380c594b207509fceeb768c8d9a33a530c640a3cc26b8fa867e1484bbe1c98131cdaad2c48a370688e259f1aff52a0d393872c41442472714933964a28c649c2ebe4608f08e8e0dd023bb90df134f45d20281c7c24fd81f0affa2450181480411973b2d7b52683fdd9d4e3068ef6b5054296".to_string(),
+            proof_c: "24102019b76cd1f917b5e765519f65504ecfebfb5f4a11a168fd29048e004e0f03b05f4cd80703e6f7f51c7c3394253a4900f0186386be4015d363425ea27488".to_string(),
+            point_encoding: None,
+            snarkjs_proof: None,
+            public_signals: None,
         };
-        assert_eq!(execute(deps, mock_env(), info, msg), Err(ContractError::InvalidProof {}));
+        assert_eq!(
+            execute(deps, mock_env(), info, msg),
+            Err(ContractError::InvalidProof {})
+        );
     }
 
     fn mock_bob_publish_error_hex_format_proof_to_verify(deps: DepsMut, sent: &[Coin]) {
         let info = mock_info("bob_key", sent);
-        let msg = ExecuteMsg::Proof { 
-            difficuty_issuer: "alice_key".to_string(), 
+        let msg = ExecuteMsg::Proof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
             //NOTE:  error format proof
-            proof_a: "f6c926a81e709ecf27642f4f99aa5c90bc479ce1122a149e00a4b97ea4ef3caec4d5ab168eb0effa1441ee448678d6e77caa2d19f3b2".to_string(), 
-            proof_b: "023290594b207509fceeb768c8d9a33a530c640a3cc26b8fa867e1484bbe1c98131cdaad2c48a370688e259f1aff52a0d393872c41442472714933964a28c649c2ebe4608f08e8e0dd023bb90df134f45d20281c7c24fd81f0affa2450181480411973b2d7b52683fdd9d4e3068ef6b5054296".to_string(), 
-            proof_c: "24102019b76cd1f917b5e76555f4a11a168fd29048e004e0f03b05f4cd80703e6f7f51c7c3394253a4900f0186386be4015d363425ea27488".to_string(), 
+            proof_a: "f6c926a81e709ecf27642f4f99aa5c90bc479ce1122a149e00a4b97ea4ef3caec4d5ab168eb0effa1441ee448678d6e77caa2d19f3b2".to_string(),
+            proof_b: "023290594b207509fceeb768c8d9a33a530c640a3cc26b8fa867e1484bbe1c98131cdaad2c48a370688e259f1aff52a0d393872c41442472714933964a28c649c2ebe4608f08e8e0dd023bb90df134f45d20281c7c24fd81f0affa2450181480411973b2d7b52683fdd9d4e3068ef6b5054296".to_string(),
+            proof_c: "24102019b76cd1f917b5e76555f4a11a168fd29048e004e0f03b05f4cd80703e6f7f51c7c3394253a4900f0186386be4015d363425ea27488".to_string(),
+            point_encoding: None,
+            snarkjs_proof: None,
+            public_signals: None,
         };
 
-        assert_eq!(execute(deps, mock_env(), info, msg), Err(ContractError::HexDecodingError {}));
+        assert_eq!(
+            execute(deps, mock_env(), info, msg),
+            Err(ContractError::HexDecodingError {
+                field: "proof_c".to_string()
+            })
+        );
     }
 
-
     fn mock_bob_publish_invalid_proof_to_verify(deps: DepsMut, sent: &[Coin]) {
         let info = mock_info("bob_key", sent);
-        let msg = ExecuteMsg::Proof { 
-            difficuty_issuer: "alice_key".to_string(), 
+        let msg = ExecuteMsg::Proof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
             //NOTE: invalid proof_a
-            proof_a: "3a7efa6d4fee4a2df464f6c926a81e709ecf27642f4f99aa5c90bc479ce1122a149e00a4b97ea4ef3caec4d5ab168eb0effa1441ee448678d6e77caa2d19f3b2".to_string(), 
-            proof_b: "023290eac0dc45935bb65780f2dd380c594b207509fceeb768c8d9a33a530c640a3cc26b8fa867e1484bbe1c98131cdaad2c48a370688e259f1aff52a0d393872c41442472714933964a28c649c2ebe4608f08e8e0dd023bb90df134f45d20281c7c24fd81f0affa2450181480411973b2d7b52683fdd9d4e3068ef6b5054296".to_string(), 
-            proof_c: "24102019b76cd1f917b5e765519f65504ecfebfb5f4a11a168fd29048e004e0f03b05f4cd80703e6f7f51c7c3394253a4900f0186386be4015d363425ea27488".to_string(), 
+            proof_a: "3a7efa6d4fee4a2df464f6c926a81e709ecf27642f4f99aa5c90bc479ce1122a149e00a4b97ea4ef3caec4d5ab168eb0effa1441ee448678d6e77caa2d19f3b2".to_string(),
+            proof_b: "023290eac0dc45935bb65780f2dd380c594b207509fceeb768c8d9a33a530c640a3cc26b8fa867e1484bbe1c98131cdaad2c48a370688e259f1aff52a0d393872c41442472714933964a28c649c2ebe4608f08e8e0dd023bb90df134f45d20281c7c24fd81f0affa2450181480411973b2d7b52683fdd9d4e3068ef6b5054296".to_string(),
+            proof_c: "24102019b76cd1f917b5e765519f65504ecfebfb5f4a11a168fd29048e004e0f03b05f4cd80703e6f7f51c7c3394253a4900f0186386be4015d363425ea27488".to_string(),
+            point_encoding: None,
+            snarkjs_proof: None,
+            public_signals: None,
+        };
+
+        assert!(matches!(
+            execute(deps, mock_env(), info, msg),
+            Err(ContractError::ErrorProof { field, .. }) if field == "proof_a"
+        ));
+    }
+
+    fn bob_proof() -> (String, String, String) {
+        (
+            "2a7efa6d4fee4a2df464f6c926a81e709ecf27642f4f99aa5c90bc479ce1122a149e00a4b97ea4ef3caec4d5ab168eb0effa1441ee448678d6e77caa2d19f3b2".to_string(),
+            "023290eac0dc45935bb65780f2dd380c594b207509fceeb768c8d9a33a530c640a3cc26b8fa867e1484bbe1c98131cdaad2c48a370688e259f1aff52a0d393872c41442472714933964a28c649c2ebe4608f08e8e0dd023bb90df134f45d20281c7c24fd81f0affa2450181480411973b2d7b52683fdd9d4e3068ef6b5054296".to_string(),
+            "24102019b76cd1f917b5e765519f65504ecfebfb5f4a11a168fd29048e004e0f03b05f4cd80703e6f7f51c7c3394253a4900f0186386be4015d363425ea27488".to_string(),
+        )
+    }
+
+    /// uncompressed hex points in the compressed encoding
+    fn compressed_g1(point: &str) -> String {
+        let mut encoded = G1Uncompressed::empty();
+        encoded
+            .as_mut()
+            .copy_from_slice(&hex::decode(point).unwrap());
+        hex::encode(encoded.into_affine().unwrap().into_compressed())
+    }
+
+    fn compressed_g2(point: &str) -> String {
+        let mut encoded = G2Uncompressed::empty();
+        encoded
+            .as_mut()
+            .copy_from_slice(&hex::decode(point).unwrap());
+        hex::encode(encoded.into_affine().unwrap().into_compressed())
+    }
+
+    /// another valid proof of the same statement, (A / r, B * r, C) pairs to the same value
+    fn rerandomized_proof(
+        (proof_a, proof_b, proof_c): (String, String, String),
+    ) -> (String, String, String) {
+        let r = Fr::from_str("7").unwrap();
+        let mut a = G1Uncompressed::empty();
+        a.as_mut().copy_from_slice(&hex::decode(proof_a).unwrap());
+        let mut b = G2Uncompressed::empty();
+        b.as_mut().copy_from_slice(&hex::decode(proof_b).unwrap());
+        let a = a
+            .into_affine()
+            .unwrap()
+            .mul(r.inverse().unwrap().into_repr())
+            .into_affine();
+        let b = b.into_affine().unwrap().mul(r.into_repr()).into_affine();
+        (
+            hex::encode(a.into_uncompressed()),
+            hex::encode(b.into_uncompressed()),
+            proof_c,
+        )
+    }
+
+    fn batch_proof(
+        prover: Option<&str>,
+        (proof_a, proof_b, proof_c): (String, String, String),
+    ) -> BatchProof {
+        BatchProof {
+            prover: prover.map(str::to_string),
+            proof_a,
+            proof_b,
+            proof_c,
+            point_encoding: None,
+            public_signals: None,
+        }
+    }
+
+    const ALICE_SNARKJS_VKEY: &str = r#"
+        {
+         "protocol": "groth16",
+         "curve": "bn128",
+         "nPublic": 1,
+         "vk_alpha_1": ["8712778281096795562299121767348571987499274867854599967182686246883548451019","14577582349081564687812173299562851976012811762545125997512007214248193363276","1"],
+         "vk_beta_2": [["19935842822090305569446084787175307234811307865059128705162029958502521083050","17589708897429985371385918583725799585930957427481783927584299949872415056263"],["16176884767458867290157078643461697815487777409647032847047819951649704203101","7635716539966438995431331770312594838873539522942992479358059674336451385302"],["1","0"]],
+         "vk_gamma_2": [["10857046999023057135944570762232829481370756359578518086990519993285655852781","11559732032986387107991004021392285783925812861821192530917403151452391805634"],["8495653923123431417604973247489272438418190587263600148770280649306958101930","4082367875863433681332203403145435568316851327593401208105741076214120093531"],["1","0"]],
+         "vk_delta_2": [["6249577533006094104901832805178231287030136592618232134774957132422131096040","5655365314901463229089173095380174775227026992214692429507757007821588325701"],["20396354201092037752707369285931691198864944883338855346573989527708915889197","2136140524477377821984660122051683202986571451309333393192655233142816690495"],["1","0"]],
+         "vk_alphabeta_12": [],
+         "IC": [["15810855616336335533565431936598447799903661153588239034826550835261483965746","14929309359864253454625613840874750850853966810843111236471133520168952075005","1"],["10845014851739365464876603189900671612234860064316107802924017333999138594030","1574525102093786914573704537406669396460264662630768688590469509774800544717","1"]]
+        }
+    "#;
+
+    const BOB_SNARKJS_PROOF: &str = r#"
+        {
+         "pi_a": ["19221490754297846862427722309256925076877055371929012382128241601838286180906","9325423248866438296038776548397120067607625733334596536037883708436601500594","1"],
+         "pi_b": [["4630481148353873526751766878683873179202015117503469625959994866111106749319","993968230817484502384349286134356749181854193484184118391188177366463745124"],["12884104093783268463736338612583731849429790021995755422818608326583832101526","20017080698236182229864110668121900066383889763401898414147644459228134711336"],["1","0"]],
+         "pi_c": ["16311753651232723519544796565113041412527921217876356216236871819375664975375","1668561366760027518146159263468748884620464755270397406839663066189087798408","1"],
+         "protocol": "groth16",
+         "curve": "bn128"
+        }
+    "#;
+
+    fn mock_bob_publish_proof_with_cw20(
+        deps: DepsMut,
+        token: &str,
+        amount: u128,
+    ) -> Result<Response, ContractError> {
+        let (proof_a, proof_b, proof_c) = bob_proof();
+        let msg = ExecuteMsg::Proof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proof_a,
+            proof_b,
+            proof_c,
+            point_encoding: None,
+            snarkjs_proof: None,
+            public_signals: None,
+        };
+        let wrapper = Cw20ReceiveMsg {
+            sender: "bob_key".to_string(),
+            amount: Uint128::new(amount),
+            msg: to_binary(&msg).unwrap(),
+        };
+
+        execute(
+            deps,
+            mock_env(),
+            mock_info(token, &[]),
+            ExecuteMsg::Receive(wrapper),
+        )
+    }
+
+    fn mock_env_at(height: u64) -> Env {
+        let mut env = mock_env();
+        env.block.height = height;
+        env
+    }
+
+    fn mock_commit_bob_proof(deps: DepsMut, prover: &str, salt: &str, height: u64) {
+        let (proof_a, proof_b, proof_c) = bob_proof();
+        let proof_str = ProofStr {
+            pi_a: hex::decode(proof_a).unwrap(),
+            pi_b: hex::decode(proof_b).unwrap(),
+            pi_c: hex::decode(proof_c).unwrap(),
+            encoding: PointEncoding::Uncompressed,
+        };
+        let commitment = proof_commitment(&proof_str, &Addr::unchecked(prover), salt);
+        let msg = ExecuteMsg::CommitProof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            commitment: hex::encode(commitment),
+        };
+
+        let _res = execute(deps, mock_env_at(height), mock_info(prover, &[]), msg)
+            .expect("contract handles commit proof");
+    }
+
+    fn mock_reveal_bob_proof(
+        deps: DepsMut,
+        prover: &str,
+        salt: &str,
+        height: u64,
+    ) -> Result<Response, ContractError> {
+        let (proof_a, proof_b, proof_c) = bob_proof();
+        let msg = ExecuteMsg::RevealProof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proof_a,
+            proof_b,
+            proof_c,
+            point_encoding: None,
+            public_signals: None,
+            salt: salt.to_string(),
+        };
+
+        execute(deps, mock_env_at(height), mock_info(prover, &[]), msg)
+    }
+
+    fn mock_publish_bob_proof(
+        deps: DepsMut,
+        prover: &str,
+        height: u64,
+    ) -> Result<Response, ContractError> {
+        let (proof_a, proof_b, proof_c) = bob_proof();
+        let msg = ExecuteMsg::Proof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proof_a,
+            proof_b,
+            proof_c,
+            point_encoding: None,
+            snarkjs_proof: None,
+            public_signals: None,
+        };
+
+        execute(deps, mock_env_at(height), mock_info(prover, &[]), msg)
+    }
+
+    fn mock_reveal_bob_proof_with_public_signal(
+        deps: DepsMut,
+        prover: &str,
+        salt: &str,
+        height: u64,
+    ) -> Result<Response, ContractError> {
+        let (proof_a, proof_b, proof_c) = bob_proof();
+        let msg = ExecuteMsg::RevealProof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proof_a,
+            proof_b,
+            proof_c,
+            point_encoding: None,
+            public_signals: Some(vec!["33".to_string()]),
+            salt: salt.to_string(),
         };
 
-        assert_eq!(execute(deps, mock_env(), info, msg), Err(ContractError::ErrorProof{}));
+        execute(deps, mock_env_at(height), mock_info(prover, &[]), msg)
     }
 
+    fn mock_publish_bob_proof_with_public_signal(
+        deps: DepsMut,
+        prover: &str,
+        height: u64,
+    ) -> Result<Response, ContractError> {
+        let (proof_a, proof_b, proof_c) = bob_proof();
+        let msg = ExecuteMsg::Proof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proof_a,
+            proof_b,
+            proof_c,
+            point_encoding: None,
+            snarkjs_proof: None,
+            public_signals: Some(vec!["33".to_string()]),
+        };
+
+        execute(deps, mock_env_at(height), mock_info(prover, &[]), msg)
+    }
 
     fn query_zkeys(deps: Deps) {
         let res = query(
-            deps, 
+            deps,
             mock_env(),
-            QueryMsg::IssuerZkeys { address: "alice_key".to_string() }
-        ).unwrap();
+            QueryMsg::IssuerZkeys {
+                address: "alice_key".to_string(),
+                circuit_id: None,
+            },
+        )
+        .unwrap();
 
         // get response
         let value: ZkeysResponse = from_binary(&res).unwrap();
         println!("zkey is :{:?}", value);
     }
-    
+
     fn query_verification_result(deps: Deps) {
         let res = query(
             deps,
             mock_env(),
-            QueryMsg::ProofResult { issuer_address: "alice_key".to_string(), prover_address: "bob_key".to_string() }
-        ).unwrap();
+            QueryMsg::ProofResult {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                prover_address: "bob_key".to_string(),
+            },
+        )
+        .unwrap();
 
         let value: ProofResponse = from_binary(&res).unwrap();
         print!("proof info is: {:?}", value);
@@ -225,7 +857,6 @@ mod test_module {
         query_zkeys(deps.as_ref());
     }
 
-
     #[test]
     fn verify_proof_and_query_works_with_no_price() {
         let mut deps = mock_dependencies();
@@ -258,7 +889,10 @@ mod test_module {
         mock_alice_set_zkeys_with_different_public_signal(deps.as_mut(), &[coin(2, "token")]);
 
         // verify the proof of bob
-        mock_bob_publish_proof_to_verify_with_different_public_signal(deps.as_mut(), &[coin(2, "token")]);
+        mock_bob_publish_proof_to_verify_with_different_public_signal(
+            deps.as_mut(),
+            &[coin(2, "token")],
+        );
     }
 
     #[test]
@@ -290,4 +924,2609 @@ mod test_module {
         mock_bob_publish_invalid_proof_to_verify(deps.as_mut(), &[coin(2, "token")]);
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn set_zkeys_failed_with_mismatched_public_signals() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        // alice fixes more public signals than the key has IC points for
+        assert_eq!(
+            mock_alice_set_zkeys_with_extra_public_signal(deps.as_mut(), &[]),
+            Err(ContractError::PublicSignalCountMismatch {
+                expected: 1,
+                actual: 2
+            })
+        );
+    }
+
+    #[test]
+    fn verify_proof_and_query_works_with_prover_public_signal() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys_with_prover_public_signal(deps.as_mut(), &[]);
+
+        let info = mock_info("bob_key", &[]);
+        let msg = ExecuteMsg::Proof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proof_a: "2a7efa6d4fee4a2df464f6c926a81e709ecf27642f4f99aa5c90bc479ce1122a149e00a4b97ea4ef3caec4d5ab168eb0effa1441ee448678d6e77caa2d19f3b2".to_string(),
+            proof_b: "023290eac0dc45935bb65780f2dd380c594b207509fceeb768c8d9a33a530c640a3cc26b8fa867e1484bbe1c98131cdaad2c48a370688e259f1aff52a0d393872c41442472714933964a28c649c2ebe4608f08e8e0dd023bb90df134f45d20281c7c24fd81f0affa2450181480411973b2d7b52683fdd9d4e3068ef6b5054296".to_string(),
+            proof_c: "24102019b76cd1f917b5e765519f65504ecfebfb5f4a11a168fd29048e004e0f03b05f4cd80703e6f7f51c7c3394253a4900f0186386be4015d363425ea27488".to_string(),
+            point_encoding: None,
+            snarkjs_proof: None,
+            public_signals: Some(vec!["33".to_string()]),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract handles verify proof failed");
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProofResult {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                prover_address: "bob_key".to_string(),
+            },
+        )
+        .unwrap();
+        let value: ProofResponse = from_binary(&res).unwrap();
+        assert_eq!(value.public_signals, vec!["33".to_string()]);
+    }
+
+    #[test]
+    fn verify_proof_failed_without_prover_public_signal() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys_with_prover_public_signal(deps.as_mut(), &[]);
+
+        let info = mock_info("bob_key", &[]);
+        let msg = ExecuteMsg::Proof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proof_a: "2a7efa6d4fee4a2df464f6c926a81e709ecf27642f4f99aa5c90bc479ce1122a149e00a4b97ea4ef3caec4d5ab168eb0effa1441ee448678d6e77caa2d19f3b2".to_string(),
+            proof_b: "023290eac0dc45935bb65780f2dd380c594b207509fceeb768c8d9a33a530c640a3cc26b8fa867e1484bbe1c98131cdaad2c48a370688e259f1aff52a0d393872c41442472714933964a28c649c2ebe4608f08e8e0dd023bb90df134f45d20281c7c24fd81f0affa2450181480411973b2d7b52683fdd9d4e3068ef6b5054296".to_string(),
+            proof_c: "24102019b76cd1f917b5e765519f65504ecfebfb5f4a11a168fd29048e004e0f03b05f4cd80703e6f7f51c7c3394253a4900f0186386be4015d363425ea27488".to_string(),
+            point_encoding: None,
+            snarkjs_proof: None,
+            public_signals: None,
+        };
+        assert_eq!(
+            execute(deps.as_mut(), mock_env(), info, msg),
+            Err(ContractError::NoPublicSignal {})
+        );
+    }
+
+    #[test]
+    fn verify_proof_failed_with_wrong_prover_public_signal() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys_with_prover_public_signal(deps.as_mut(), &[]);
+
+        let info = mock_info("bob_key", &[]);
+        let msg = ExecuteMsg::Proof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proof_a: "2a7efa6d4fee4a2df464f6c926a81e709ecf27642f4f99aa5c90bc479ce1122a149e00a4b97ea4ef3caec4d5ab168eb0effa1441ee448678d6e77caa2d19f3b2".to_string(),
+            proof_b: "023290eac0dc45935bb65780f2dd380c594b207509fceeb768c8d9a33a530c640a3cc26b8fa867e1484bbe1c98131cdaad2c48a370688e259f1aff52a0d393872c41442472714933964a28c649c2ebe4608f08e8e0dd023bb90df134f45d20281c7c24fd81f0affa2450181480411973b2d7b52683fdd9d4e3068ef6b5054296".to_string(),
+            proof_c: "24102019b76cd1f917b5e765519f65504ecfebfb5f4a11a168fd29048e004e0f03b05f4cd80703e6f7f51c7c3394253a4900f0186386be4015d363425ea27488".to_string(),
+            point_encoding: None,
+            snarkjs_proof: None,
+            public_signals: Some(vec!["30".to_string()]),
+        };
+        assert_eq!(
+            execute(deps.as_mut(), mock_env(), info, msg),
+            Err(ContractError::InvalidProof {})
+        );
+    }
+
+    #[test]
+    fn set_zkeys_bound_to_sender_leaves_room_for_sender_signal() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        // the key's one public input is taken by the sender signal
+        assert_eq!(
+            mock_alice_set_zkeys_bound_to_sender(deps.as_mut(), &[], vec!["33".to_string()]),
+            Err(ContractError::PublicSignalCountMismatch {
+                expected: 0,
+                actual: 1
+            })
+        );
+    }
+
+    #[test]
+    fn verify_proof_bound_to_sender_appends_sender_signal() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys_bound_to_sender(deps.as_mut(), &[], vec![])
+            .expect("contract handles set zkeys parameters");
+
+        let info = mock_info("bob_key", &[]);
+        let msg = ExecuteMsg::Proof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proof_a: "2a7efa6d4fee4a2df464f6c926a81e709ecf27642f4f99aa5c90bc479ce1122a149e00a4b97ea4ef3caec4d5ab168eb0effa1441ee448678d6e77caa2d19f3b2".to_string(),
+            proof_b: "023290eac0dc45935bb65780f2dd380c594b207509fceeb768c8d9a33a530c640a3cc26b8fa867e1484bbe1c98131cdaad2c48a370688e259f1aff52a0d393872c41442472714933964a28c649c2ebe4608f08e8e0dd023bb90df134f45d20281c7c24fd81f0affa2450181480411973b2d7b52683fdd9d4e3068ef6b5054296".to_string(),
+            proof_c: "24102019b76cd1f917b5e765519f65504ecfebfb5f4a11a168fd29048e004e0f03b05f4cd80703e6f7f51c7c3394253a4900f0186386be4015d363425ea27488".to_string(),
+            point_encoding: None,
+            snarkjs_proof: None,
+            public_signals: None,
+        };
+        // bob's proof is for the signal 33, not for his sender signal
+        assert_eq!(
+            execute(deps.as_mut(), mock_env(), info, msg),
+            Err(ContractError::InvalidProof {})
+        );
+    }
+
+    #[test]
+    fn query_sender_signal_works() {
+        let deps = mock_dependencies();
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::SenderSignal {
+                address: "bob_key".to_string(),
+            },
+        )
+        .unwrap();
+        let value: SenderSignalResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            value.public_signal,
+            sender_public_signal(&Addr::unchecked("bob_key"))
+        );
+    }
+
+    #[test]
+    fn commit_and_reveal_proof_works() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys(deps.as_mut(), &[]);
+
+        mock_commit_bob_proof(deps.as_mut(), "bob_key", "salt", 100);
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Commitment {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                prover_address: "bob_key".to_string(),
+            },
+        )
+        .unwrap();
+        let value: CommitmentResponse = from_binary(&res).unwrap();
+        assert_eq!(value.height, 100);
+        assert_eq!(value.expires_at, 200);
+
+        mock_reveal_bob_proof(deps.as_mut(), "bob_key", "salt", 101)
+            .expect("contract handles reveal proof");
+        query_verification_result(deps.as_ref());
+    }
+
+    #[test]
+    fn reveal_proof_failed_with_invalid_commitment() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys(deps.as_mut(), &[]);
+
+        assert_eq!(
+            mock_reveal_bob_proof(deps.as_mut(), "bob_key", "salt", 101),
+            Err(ContractError::NoCommitment {})
+        );
+
+        mock_commit_bob_proof(deps.as_mut(), "bob_key", "salt", 100);
+        assert_eq!(
+            mock_reveal_bob_proof(deps.as_mut(), "bob_key", "salt", 100),
+            Err(ContractError::RevealTooEarly {})
+        );
+        assert_eq!(
+            mock_reveal_bob_proof(deps.as_mut(), "bob_key", "pepper", 101),
+            Err(ContractError::CommitmentMismatch {})
+        );
+        assert_eq!(
+            mock_reveal_bob_proof(deps.as_mut(), "bob_key", "salt", 201),
+            Err(ContractError::CommitmentExpired {})
+        );
+    }
+
+    #[test]
+    fn reveal_proof_takes_credit_from_earlier_reveal() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys_with_prover_public_signal(deps.as_mut(), &[]);
+
+        mock_commit_bob_proof(deps.as_mut(), "bob_key", "salt", 100);
+        // carol copies bob's proof and lands it first
+        mock_publish_bob_proof_with_public_signal(deps.as_mut(), "carol_key", 101)
+            .expect("contract handles verify proof");
+        mock_reveal_bob_proof_with_public_signal(deps.as_mut(), "bob_key", "salt", 102)
+            .expect("contract handles reveal proof");
+
+        query_verification_result(deps.as_ref());
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProofResult {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                prover_address: "carol_key".to_string(),
+            },
+        );
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn reveal_proof_takes_credit_from_rerandomized_copy() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys_with_prover_public_signal(deps.as_mut(), &[]);
+
+        mock_commit_bob_proof(deps.as_mut(), "bob_key", "salt", 100);
+        // carol re-randomizes bob's proof, it hashes differently but proves the same statement
+        let (proof_a, proof_b, proof_c) = rerandomized_proof(bob_proof());
+        let msg = ExecuteMsg::Proof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proof_a,
+            proof_b,
+            proof_c,
+            point_encoding: None,
+            snarkjs_proof: None,
+            public_signals: Some(vec!["33".to_string()]),
+        };
+        execute(
+            deps.as_mut(),
+            mock_env_at(101),
+            mock_info("carol_key", &[]),
+            msg,
+        )
+        .expect("contract handles verify proof");
+        mock_reveal_bob_proof_with_public_signal(deps.as_mut(), "bob_key", "salt", 102)
+            .expect("contract handles reveal proof");
+
+        query_verification_result(deps.as_ref());
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProofResult {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                prover_address: "carol_key".to_string(),
+            },
+        );
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn reveal_proof_failed_with_later_commitment() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys_with_prover_public_signal(deps.as_mut(), &[]);
+
+        mock_publish_bob_proof_with_public_signal(deps.as_mut(), "bob_key", 100)
+            .expect("contract handles verify proof");
+        mock_commit_bob_proof(deps.as_mut(), "carol_key", "salt", 100);
+        assert_eq!(
+            mock_reveal_bob_proof_with_public_signal(deps.as_mut(), "carol_key", "salt", 101),
+            Err(ContractError::ProofAlreadyClaimed {})
+        );
+    }
+
+    #[test]
+    fn issuer_circuit_credits_every_prover() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys(deps.as_mut(), &[]);
+
+        // the issuer's signals are the same statement for everyone, carol proves it after bob
+        mock_publish_bob_proof(deps.as_mut(), "bob_key", 100)
+            .expect("contract handles verify proof");
+        mock_publish_bob_proof(deps.as_mut(), "carol_key", 101)
+            .expect("contract handles verify proof");
+        mock_commit_bob_proof(deps.as_mut(), "dave_key", "salt", 102);
+        mock_reveal_bob_proof(deps.as_mut(), "dave_key", "salt", 103)
+            .expect("contract handles reveal proof");
+
+        for prover in ["bob_key", "carol_key", "dave_key"] {
+            let res = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::ProofResult {
+                    issuer_address: "alice_key".to_string(),
+                    circuit_id: None,
+                    prover_address: prover.to_string(),
+                },
+            )
+            .unwrap();
+            let value: ProofResponse = from_binary(&res).unwrap();
+            assert!(value.is_valid);
+        }
+    }
+
+    #[test]
+    fn verify_proof_with_nullifier_and_query_works() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys_with_nullifier(deps.as_mut(), &[]);
+
+        mock_bob_publish_proof_to_verify(deps.as_mut(), &[]);
+        query_verification_result(deps.as_ref());
+
+        // adding the field modulus doesn't make a different nullifier
+        for (nullifier, spent) in [
+            ("33", true),
+            (
+                "21888242871839275222246405745257275088548364400416034343698204186575808495650",
+                true,
+            ),
+            ("34", false),
+        ] {
+            let res = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Nullifier {
+                    issuer_address: "alice_key".to_string(),
+                    circuit_id: None,
+                    nullifier: nullifier.to_string(),
+                },
+            )
+            .unwrap();
+            let value: NullifierResponse = from_binary(&res).unwrap();
+            assert_eq!(value.spent, spent);
+        }
+
+        // an unreadable nullifier is reported as the public signal it stands for
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Nullifier {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                nullifier: "thirty-three".to_string(),
+            },
+        );
+        match res {
+            Err(StdError::GenericErr { msg, .. }) => assert_eq!(
+                msg,
+                ContractError::ParsePulbicSignalError { index: 0 }.to_string()
+            ),
+            res => panic!("Unexpected result: {:?}", res),
+        }
+
+        // neither bob again nor a copy from carol spends the nullifier twice
+        for prover in ["bob_key", "carol_key"] {
+            assert_eq!(
+                mock_publish_bob_proof(deps.as_mut(), prover, mock_env().block.height),
+                Err(ContractError::NullifierAlreadySpent {})
+            );
+        }
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProverHistory {
+                prover_address: "bob_key".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: ProverHistoryResponse = from_binary(&res).unwrap();
+        assert_eq!(value.verifications.len(), 1);
+    }
+
+    #[test]
+    fn reveal_proof_takes_spent_nullifier_from_copy() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys_with_nullifier(deps.as_mut(), &[]);
+
+        mock_commit_bob_proof(deps.as_mut(), "bob_key", "salt", 100);
+        // carol copies bob's proof before he reveals it
+        mock_publish_bob_proof(deps.as_mut(), "carol_key", 101)
+            .expect("contract handles verify proof");
+        mock_reveal_bob_proof(deps.as_mut(), "bob_key", "salt", 102)
+            .expect("contract handles reveal proof");
+        query_verification_result(deps.as_ref());
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProofResult {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                prover_address: "carol_key".to_string(),
+            },
+        );
+        assert!(res.is_err());
+
+        // the nullifier moves once, it isn't spent again by either of them
+        for prover in ["bob_key", "carol_key"] {
+            assert_eq!(
+                mock_publish_bob_proof(deps.as_mut(), prover, 103),
+                Err(ContractError::NullifierAlreadySpent {})
+            );
+        }
+    }
+
+    #[test]
+    fn set_zkeys_failed_with_invalid_nullifier_index() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys_with_invalid_nullifier_index(deps.as_mut(), &[]);
+    }
+
+    #[test]
+    fn withdraw_fees_works() {
+        let mut deps = mock_dependencies_with_balance(&coins(5, "token"));
+        mock_init_with_price(deps.as_mut(), coin(1, "token"), coin(1, "token"));
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::FeeBalance {}).unwrap();
+        let value: FeeBalanceResponse = from_binary(&res).unwrap();
+        assert_eq!(value.balance, coins(5, "token"));
+
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::Withdraw {
+            amount: coins(3, "token"),
+            recipient: "treasury".to_string(),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "treasury".to_string(),
+                amount: coins(3, "token")
+            })]
+        );
+    }
+
+    #[test]
+    fn withdraw_fees_failed_with_unauthorized() {
+        let mut deps = mock_dependencies_with_balance(&coins(5, "token"));
+        mock_init_with_price(deps.as_mut(), coin(1, "token"), coin(1, "token"));
+
+        let info = mock_info("alice_key", &[]);
+        let msg = ExecuteMsg::Withdraw {
+            amount: coins(3, "token"),
+            recipient: "alice_key".to_string(),
+        };
+        assert_eq!(
+            execute(deps.as_mut(), mock_env(), info, msg),
+            Err(ContractError::Unauthorized {})
+        );
+    }
+
+    #[test]
+    fn withdraw_fees_failed_with_empty_amount() {
+        let mut deps = mock_dependencies_with_balance(&coins(5, "token"));
+        mock_init_with_price(deps.as_mut(), coin(1, "token"), coin(1, "token"));
+
+        let info = mock_info("creator", &[]);
+        for amount in [
+            vec![],
+            coins(0, "token"),
+            vec![coin(1, "token"), coin(0, "stake")],
+        ] {
+            let msg = ExecuteMsg::Withdraw {
+                amount,
+                recipient: "treasury".to_string(),
+            };
+            assert_eq!(
+                execute(deps.as_mut(), mock_env(), info.clone(), msg),
+                Err(ContractError::EmptyWithdrawal {})
+            );
+        }
+        let msg = ExecuteMsg::WithdrawCw20 {
+            token: "cw20_token".to_string(),
+            amount: Uint128::zero(),
+            recipient: "treasury".to_string(),
+        };
+        assert_eq!(
+            execute(deps.as_mut(), mock_env(), info, msg),
+            Err(ContractError::EmptyWithdrawal {})
+        );
+    }
+
+    #[test]
+    fn verify_proof_refunds_overpayment_with_exact_fee() {
+        let mut deps = mock_dependencies();
+        mock_init_with_exact_fee(deps.as_mut(), UnrelatedDenoms::Refund);
+        mock_alice_set_zkeys(deps.as_mut(), &[coin(1, "token")]);
+
+        let (proof_a, proof_b, proof_c) = bob_proof();
+        let msg = ExecuteMsg::Proof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proof_a,
+            proof_b,
+            proof_c,
+            point_encoding: None,
+            snarkjs_proof: None,
+            public_signals: None,
+        };
+        let info = mock_info("bob_key", &[coin(3, "token"), coin(2, "smokin")]);
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "bob_key".to_string(),
+                amount: vec![coin(2, "token"), coin(2, "smokin")]
+            })]
+        );
+    }
+
+    #[test]
+    fn verify_proof_failed_with_unrelated_denom() {
+        let mut deps = mock_dependencies();
+        mock_init_with_exact_fee(deps.as_mut(), UnrelatedDenoms::Reject);
+        mock_alice_set_zkeys(deps.as_mut(), &[coin(1, "token")]);
+
+        let (proof_a, proof_b, proof_c) = bob_proof();
+        let msg = ExecuteMsg::Proof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proof_a,
+            proof_b,
+            proof_c,
+            point_encoding: None,
+            snarkjs_proof: None,
+            public_signals: None,
+        };
+        let info = mock_info("bob_key", &[coin(1, "token"), coin(2, "smokin")]);
+        assert_eq!(
+            execute(deps.as_mut(), mock_env(), info, msg),
+            Err(ContractError::UnrelatedDenom {
+                denom: "smokin".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn verify_proof_collects_bounty() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys_with_bounty(deps.as_mut(), &coins(10, "token"));
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Bounty {
+                address: "alice_key".to_string(),
+                circuit_id: None,
+            },
+        )
+        .unwrap();
+        let value: BountyResponse = from_binary(&res).unwrap();
+        assert_eq!(value.reward, coin(10, "token"));
+        // the escrowed reward is not a fee
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::FeeBalance {}).unwrap();
+        let value: FeeBalanceResponse = from_binary(&res).unwrap();
+        assert_eq!(value.balance, vec![]);
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::Withdraw {
+            amount: coins(10, "token"),
+            recipient: "creator".to_string(),
+        };
+        assert_eq!(
+            execute(deps.as_mut(), mock_env(), info, msg),
+            Err(ContractError::InsufficientFees {
+                denom: "token".to_string()
+            })
+        );
+
+        // the proof wins the bounty, which is paid once no earlier commitment can be revealed
+        let height = mock_env().block.height;
+        let res = mock_publish_bob_proof(deps.as_mut(), "bob_key", height).unwrap();
+        assert_eq!(res.messages, vec![]);
+        assert_eq!(res.events[1].ty, "bounty_won");
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Bounty {
+                address: "alice_key".to_string(),
+                circuit_id: None,
+            },
+        )
+        .unwrap();
+        let value: BountyResponse = from_binary(&res).unwrap();
+        assert_eq!(value.winner, Some(Addr::unchecked("bob_key")));
+        assert_eq!(value.claimable_at, Some(height + 100));
+
+        let claim = || ExecuteMsg::ClaimBounty {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+        };
+        match execute(
+            deps.as_mut(),
+            mock_env_at(height + 99),
+            mock_info("carol_key", &[]),
+            claim(),
+        ) {
+            Err(ContractError::BountyNotClaimable { claimable_at }) => {
+                assert_eq!(claimable_at, height + 100)
+            }
+            res => panic!("Unexpected result: {:?}", res),
+        }
+        // the issuer can't take back a bounty that was won
+        match execute(
+            deps.as_mut(),
+            mock_env_at(height + 11),
+            mock_info("alice_key", &[]),
+            ExecuteMsg::ReclaimBounty { circuit_id: None },
+        ) {
+            Err(ContractError::BountyAlreadyWon {}) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+        // anyone can pay it out to the winner
+        let res = execute(
+            deps.as_mut(),
+            mock_env_at(height + 100),
+            mock_info("carol_key", &[]),
+            claim(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "bob_key".to_string(),
+                amount: coins(10, "token")
+            })]
+        );
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Bounty {
+                address: "alice_key".to_string(),
+                circuit_id: None,
+            },
+        );
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn bounty_goes_to_the_earlier_commitment() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys_with_bounty(deps.as_mut(), &coins(10, "token"));
+
+        // carol front-runs bob's reveal with a re-randomized copy of his proof
+        mock_commit_bob_proof(deps.as_mut(), "bob_key", "salt", 100);
+        let (proof_a, proof_b, proof_c) = rerandomized_proof(bob_proof());
+        let msg = ExecuteMsg::Proof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proof_a,
+            proof_b,
+            proof_c,
+            point_encoding: None,
+            snarkjs_proof: None,
+            public_signals: None,
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env_at(101),
+            mock_info("carol_key", &[]),
+            msg,
+        )
+        .unwrap();
+        assert_eq!(res.messages, vec![]);
+        let claim = || ExecuteMsg::ClaimBounty {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+        };
+        match execute(
+            deps.as_mut(),
+            mock_env_at(102),
+            mock_info("carol_key", &[]),
+            claim(),
+        ) {
+            Err(ContractError::BountyNotClaimable { claimable_at }) => {
+                assert_eq!(claimable_at, 201)
+            }
+            res => panic!("Unexpected result: {:?}", res),
+        }
+
+        // bob reveals later, his commitment is earlier
+        mock_reveal_bob_proof(deps.as_mut(), "bob_key", "salt", 102)
+            .expect("contract handles reveal proof");
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Bounty {
+                address: "alice_key".to_string(),
+                circuit_id: None,
+            },
+        )
+        .unwrap();
+        let value: BountyResponse = from_binary(&res).unwrap();
+        assert_eq!(value.winner, Some(Addr::unchecked("bob_key")));
+        assert_eq!(value.claimable_at, Some(200));
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env_at(200),
+            mock_info("carol_key", &[]),
+            claim(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "bob_key".to_string(),
+                amount: coins(10, "token")
+            })]
+        );
+        match execute(
+            deps.as_mut(),
+            mock_env_at(201),
+            mock_info("carol_key", &[]),
+            claim(),
+        ) {
+            Err(ContractError::NoBounty {}) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn reclaim_bounty_works() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys_with_bounty(deps.as_mut(), &coins(10, "token"));
+
+        let info = mock_info("alice_key", &[]);
+        let height = mock_env().block.height;
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                info.clone(),
+                ExecuteMsg::ReclaimBounty { circuit_id: None }
+            ),
+            Err(ContractError::BountyNotExpired {
+                reclaimable_at: height + 111
+            })
+        );
+        // a commitment made by the deadline may still be revealed
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                mock_env_at(height + 110),
+                info.clone(),
+                ExecuteMsg::ReclaimBounty { circuit_id: None }
+            ),
+            Err(ContractError::BountyNotExpired {
+                reclaimable_at: height + 111
+            })
+        );
+
+        let env = mock_env_at(height + 111);
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::ReclaimBounty { circuit_id: None },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "alice_key".to_string(),
+                amount: coins(10, "token")
+            })]
+        );
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                env,
+                info,
+                ExecuteMsg::ReclaimBounty { circuit_id: None }
+            ),
+            Err(ContractError::NoBounty {})
+        );
+    }
+
+    #[test]
+    fn reclaim_bounty_waits_for_a_commitment_made_by_the_deadline() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys_with_bounty(deps.as_mut(), &coins(10, "token"));
+
+        // bob commits on the last block of the bounty
+        let deadline = mock_env().block.height + 10;
+        mock_commit_bob_proof(deps.as_mut(), "bob_key", "salt", deadline);
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                mock_env_at(deadline + 1),
+                mock_info("alice_key", &[]),
+                ExecuteMsg::ReclaimBounty { circuit_id: None }
+            ),
+            Err(ContractError::BountyNotExpired {
+                reclaimable_at: deadline + 101
+            })
+        );
+
+        // his reveal after the deadline still wins the reward
+        let res = mock_reveal_bob_proof(deps.as_mut(), "bob_key", "salt", deadline + 2).unwrap();
+        assert_eq!(res.events[1].ty, "bounty_won");
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                mock_env_at(deadline + 101),
+                mock_info("alice_key", &[]),
+                ExecuteMsg::ReclaimBounty { circuit_id: None }
+            ),
+            Err(ContractError::BountyAlreadyWon {})
+        );
+        let claim = ExecuteMsg::ClaimBounty {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env_at(deadline + 100),
+            mock_info("carol_key", &[]),
+            claim,
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "bob_key".to_string(),
+                amount: coins(10, "token")
+            })]
+        );
+    }
+    #[test]
+    fn verify_proof_and_query_works_with_cw20_price() {
+        let mut deps = mock_dependencies();
+        mock_init_with_cw20_price(deps.as_mut());
+
+        let res = mock_alice_set_zkeys_with_cw20(deps.as_mut(), "cw20_token", 2).unwrap();
+        assert_eq!(res.messages, vec![]);
+        // the price is only payable in the cw20 token
+        assert_eq!(
+            mock_publish_bob_proof(deps.as_mut(), "bob_key", mock_env().block.height),
+            Err(ContractError::InsufficientFundsSend {})
+        );
+
+        // the overpayment goes back with the exact fee mode
+        let res = mock_bob_publish_proof_with_cw20(deps.as_mut(), "cw20_token", 3).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(WasmMsg::Execute {
+                contract_addr: "cw20_token".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "bob_key".to_string(),
+                    amount: Uint128::new(1)
+                })
+                .unwrap(),
+                funds: vec![],
+            })]
+        );
+        query_verification_result(deps.as_ref());
+    }
+
+    #[test]
+    fn receive_failed_with_unaccepted_token_or_hook() {
+        let mut deps = mock_dependencies();
+        mock_init_with_cw20_price(deps.as_mut());
+
+        assert_eq!(
+            mock_alice_set_zkeys_with_cw20(deps.as_mut(), "other_token", 2),
+            Err(ContractError::UnacceptedToken {
+                token: "other_token".to_string()
+            })
+        );
+        assert_eq!(
+            mock_alice_set_zkeys_with_cw20(deps.as_mut(), "cw20_token", 1),
+            Err(ContractError::InsufficientFundsSend {})
+        );
+
+        let wrapper = Cw20ReceiveMsg {
+            sender: "alice_key".to_string(),
+            amount: Uint128::new(2),
+            msg: to_binary(&ExecuteMsg::ReclaimBounty { circuit_id: None }).unwrap(),
+        };
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("cw20_token", &[]),
+                ExecuteMsg::Receive(wrapper)
+            ),
+            Err(ContractError::InvalidCw20Hook {})
+        );
+    }
+
+    #[test]
+    fn withdraw_cw20_fees_works() {
+        let mut deps = mock_dependencies();
+        mock_init_with_cw20_price(deps.as_mut());
+
+        let msg = ExecuteMsg::WithdrawCw20 {
+            token: "cw20_token".to_string(),
+            amount: Uint128::new(2),
+            recipient: "treasury".to_string(),
+        };
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("alice_key", &[]),
+                msg.clone()
+            ),
+            Err(ContractError::Unauthorized {})
+        );
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(WasmMsg::Execute {
+                contract_addr: "cw20_token".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "treasury".to_string(),
+                    amount: Uint128::new(2)
+                })
+                .unwrap(),
+                funds: vec![],
+            })]
+        );
+    }
+    #[test]
+    fn migrate_refuses_downgrade_and_other_contract() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        assert_eq!(
+            get_contract_version(deps.as_ref().storage).unwrap().version,
+            env!("CARGO_PKG_VERSION")
+        );
+
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg { owner: None }).unwrap();
+        assert_eq!(res.messages, vec![]);
+
+        set_contract_version(deps.as_mut().storage, "crates.io:cw-groth16-bn", "99.0.0").unwrap();
+        assert_eq!(
+            migrate(deps.as_mut(), mock_env(), MigrateMsg { owner: None }),
+            Err(ContractError::CannotDowngrade {
+                previous_version: "99.0.0".to_string(),
+                new_version: env!("CARGO_PKG_VERSION").to_string()
+            })
+        );
+
+        set_contract_version(deps.as_mut().storage, "crates.io:cw20-base", "0.1.0").unwrap();
+        assert_eq!(
+            migrate(deps.as_mut(), mock_env(), MigrateMsg { owner: None }),
+            Err(ContractError::CannotMigrate {
+                previous_contract: "crates.io:cw20-base".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn migrate_rewrites_v0_1_layouts() {
+        let mut deps = mock_dependencies();
+        let (proof_a, proof_b, proof_c) = bob_proof();
+        let proof = ProofStr {
+            pi_a: hex::decode(proof_a).unwrap(),
+            pi_b: hex::decode(proof_b).unwrap(),
+            pi_c: hex::decode(proof_c).unwrap(),
+            encoding: PointEncoding::Uncompressed,
+        };
+        let alice = Addr::unchecked("alice_key");
+        let bob = Addr::unchecked("bob_key");
+        let storage = deps.as_mut().storage;
+        v0_1::CONFIG
+            .save(
+                storage,
+                &v0_1::Config {
+                    zkeys_price: None,
+                    proof_price: Some(coin(1, "token")),
+                },
+            )
+            .unwrap();
+        let zkeys = v0_1::ZkeysStr {
+            vkeys: v0_1::VkeyStr {
+                alpha_1: hex::decode("134341fbe5f0719617003adb9c8fe9038d5d913d1a1e961618cd67f8f097d0cb203a9e851d18a4cfe8ab963083acda4af394c8c2461930397057da9edf030d4c").unwrap(),
+                beta_2: hex::decode("26e36c244fbd85b1f96bb3c4eecc5024f9e0507247e6675e56d5d222f43921872c13498425fa4b090c401561092dac563a0864ef22aa2bcfcf0e75bda8ad94aa10e1a9938cab807dc19806127b49d697de33abf79ad5ae46ca240927dd9c57d623c3cad4c8c16360c9199a701b707474fd6bd47e8841d4ebb7a88b826459535d").unwrap(),
+                gamma_2: hex::decode("198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c21800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa").unwrap(),
+                delta_2: hex::decode("0c80d2c61aaac33c924c322ed740b6ed3775eb171b173c09640a29aa1322e5450dd122b23f09aab8b1a3fd739d296949f328fd2cd9deedc6d1ed3262803a19e804b9033fb8a3476eb8b5e609f529cc52fbaa9df6f59a5a67aab944cde646d13f2d17ed688afb5f3abc97f978fe9da3a25feee8bfbb0762ae80be6aabb76a742d").unwrap(),
+                ic0: hex::decode("22f4a08cff59356634d3cd5ad41e85e4b4b484a7633eb64ddd739032936add322101b08d07ff3315947df2a6652e667d6a3e96fca1a661aadc2092a85c3912fd").unwrap(),
+                ic1: hex::decode("17fa0f95ec76599763a6629b557bf18fd938305c7472fd81c368f8ca76615cee037b264dee54b6fd3298fd0396525cb1aec0dd971e3ab771f242ce10980763cd").unwrap(),
+            },
+            public_signal: "33".to_string(),
+        };
+        v0_1::ZKEYS.save(storage, &alice, &zkeys).unwrap();
+        let proof_info = v0_1::ProofInfo {
+            proof,
+            is_valid: true,
+        };
+        v0_1::PROVERLIST
+            .save(storage, (&alice, &bob), &proof_info)
+            .unwrap();
+        v0_1::PROVERINFO.save(storage, &bob, &proof_info).unwrap();
+
+        // 0.1.0 didn't have an owner
+        assert_eq!(
+            migrate(deps.as_mut(), mock_env(), MigrateMsg { owner: None }),
+            Err(ContractError::MissingOwner {})
+        );
+        migrate(
+            deps.as_mut(),
+            mock_env(),
+            MigrateMsg {
+                owner: Some("creator".to_string()),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            get_contract_version(deps.as_ref().storage).unwrap().version,
+            env!("CARGO_PKG_VERSION")
+        );
+
+        assert_config_state(
+            deps.as_ref(),
+            Config {
+                owner: Addr::unchecked("creator"),
+                zkeys_price: None,
+                proof_price: Some(coin(1, "token")),
+                zkeys_cw20_price: None,
+                proof_cw20_price: None,
+                fee_mode: FeeMode::Sufficient,
+                unrelated_denoms: UnrelatedDenoms::Refund,
+                commitment_expiry: 100,
+            },
+        );
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::IssuerZkeys {
+                address: "alice_key".to_string(),
+                circuit_id: None,
+            },
+        )
+        .unwrap();
+        let value: ZkeysResponse = from_binary(&res).unwrap();
+        assert_eq!(value.public_signals, vec!["33".to_string()]);
+        assert_eq!(value.vk_ic.len(), 2);
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProofResult {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                prover_address: "bob_key".to_string(),
+            },
+        )
+        .unwrap();
+        let value: ProofResponse = from_binary(&res).unwrap();
+        assert_eq!(value.public_signals, vec!["33".to_string()]);
+        assert!(value.is_valid);
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::SolvedCircuits {
+                prover_address: "bob_key".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: SolvedCircuitsResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            value.circuits,
+            vec![CircuitRef {
+                issuer: Addr::unchecked("alice_key"),
+                circuit_id: DEFAULT_CIRCUIT.to_string()
+            }]
+        );
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProverHistory {
+                prover_address: "bob_key".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: ProverHistoryResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            value.verifications,
+            vec![VerificationResponse {
+                sequence: 0,
+                issuer: Addr::unchecked("alice_key"),
+                circuit_id: DEFAULT_CIRCUIT.to_string(),
+                vkey_hash: hex::encode(vkey_hash(
+                    &ZKEYS
+                        .load(deps.as_ref().storage, (&alice, DEFAULT_CIRCUIT))
+                        .unwrap()
+                        .vkeys
+                )),
+                height: mock_env().block.height,
+            }]
+        );
+        assert!(!v0_1::PROVERINFO.has(deps.as_ref().storage, &Addr::unchecked("bob_key")));
+        assert!(!v0_1::ZKEYS.has(deps.as_ref().storage, &alice));
+        assert!(!v0_1::PROVERLIST.has(deps.as_ref().storage, (&alice, &bob)));
+
+        // the migrated key still verifies proofs
+        mock_bob_publish_proof_to_verify(deps.as_mut(), &coins(1, "token"));
+    }
+    #[test]
+    fn set_zkeys_and_verify_proof_emit_events() {
+        let mut deps = mock_dependencies();
+        mock_init_with_cw20_price(deps.as_mut());
+
+        let res = mock_alice_set_zkeys_with_cw20(deps.as_mut(), "cw20_token", 2).unwrap();
+        let zkeys = ZKEYS
+            .load(
+                deps.as_ref().storage,
+                (&Addr::unchecked("alice_key"), DEFAULT_CIRCUIT),
+            )
+            .unwrap();
+        let vkey_hash = hex::encode(vkey_hash(&zkeys.vkeys));
+        assert_eq!(res.events.len(), 1);
+        assert_eq!(res.events[0].ty, "zkeys_registered");
+        assert_eq!(
+            res.events[0].attributes,
+            vec![
+                attr("issuer", "alice_key"),
+                attr("circuit_id", DEFAULT_CIRCUIT),
+                attr("vkey_hash", &vkey_hash),
+                attr("proof_system", PROOF_SYSTEM),
+                attr("fee", "2cw20_token"),
+            ]
+        );
+
+        // only the price is kept with the exact fee mode
+        let res = mock_bob_publish_proof_with_cw20(deps.as_mut(), "cw20_token", 3).unwrap();
+        assert_eq!(res.events.len(), 1);
+        assert_eq!(res.events[0].ty, "proof_verified");
+        assert_eq!(
+            res.events[0].attributes,
+            vec![
+                attr("issuer", "alice_key"),
+                attr("circuit_id", DEFAULT_CIRCUIT),
+                attr("prover", "bob_key"),
+                attr("vkey_hash", &vkey_hash),
+                attr("proof_system", PROOF_SYSTEM),
+                attr("fee", "2cw20_token"),
+                attr("outcome", "valid"),
+                attr("sequence", "0"),
+            ]
+        );
+    }
+
+    #[test]
+    fn withdraw_fees_emits_event() {
+        let mut deps = mock_dependencies_with_balance(&coins(5, "token"));
+        mock_init_with_price(deps.as_mut(), coin(1, "token"), coin(1, "token"));
+
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::Withdraw {
+            amount: vec![coin(3, "token")],
+            recipient: "treasury".to_string(),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(res.events[0].ty, "fees_withdrawn");
+        assert_eq!(
+            res.events[0].attributes,
+            vec![attr("recipient", "treasury"), attr("amount", "3token")]
+        );
+    }
+    #[test]
+    fn list_issuers_and_provers_works() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys(deps.as_mut(), &[]);
+        mock_bob_publish_proof_to_verify(deps.as_mut(), &[]);
+        // carol and dave issue the same problem
+        let zkeys = ZKEYS
+            .load(
+                deps.as_ref().storage,
+                (&Addr::unchecked("alice_key"), DEFAULT_CIRCUIT),
+            )
+            .unwrap();
+        ZKEYS
+            .save(
+                deps.as_mut().storage,
+                (&Addr::unchecked("carol_key"), DEFAULT_CIRCUIT),
+                &zkeys,
+            )
+            .unwrap();
+        ZKEYS
+            .save(
+                deps.as_mut().storage,
+                (&Addr::unchecked("dave_key"), DEFAULT_CIRCUIT),
+                &zkeys,
+            )
+            .unwrap();
+        let (proof_a, proof_b, proof_c) = bob_proof();
+        let msg = ExecuteMsg::Proof {
+            difficuty_issuer: "dave_key".to_string(),
+            circuit_id: None,
+            proof_a,
+            proof_b,
+            proof_c,
+            point_encoding: None,
+            snarkjs_proof: None,
+            public_signals: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("bob_key", &[]), msg).unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Issuers {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: IssuersResponse = from_binary(&res).unwrap();
+        // an issuer key is stored behind its length
+        assert_eq!(
+            value.issuers,
+            vec![
+                Addr::unchecked("dave_key"),
+                Addr::unchecked("alice_key"),
+                Addr::unchecked("carol_key")
+            ]
+        );
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Issuers {
+                start_after: Some("alice_key".to_string()),
+                limit: Some(1),
+            },
+        )
+        .unwrap();
+        let value: IssuersResponse = from_binary(&res).unwrap();
+        assert_eq!(value.issuers, vec![Addr::unchecked("carol_key")]);
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Provers {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: ProversResponse = from_binary(&res).unwrap();
+        assert_eq!(value.provers, vec![Addr::unchecked("bob_key")]);
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Provers {
+                issuer_address: "carol_key".to_string(),
+                circuit_id: None,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: ProversResponse = from_binary(&res).unwrap();
+        assert!(value.provers.is_empty());
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::SolvedCircuits {
+                prover_address: "bob_key".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: SolvedCircuitsResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            value.circuits,
+            vec![
+                CircuitRef {
+                    issuer: Addr::unchecked("dave_key"),
+                    circuit_id: DEFAULT_CIRCUIT.to_string()
+                },
+                CircuitRef {
+                    issuer: Addr::unchecked("alice_key"),
+                    circuit_id: DEFAULT_CIRCUIT.to_string()
+                },
+            ]
+        );
+        let start_after = Some(("dave_key".to_string(), DEFAULT_CIRCUIT.to_string()));
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::SolvedCircuits {
+                prover_address: "bob_key".to_string(),
+                start_after,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: SolvedCircuitsResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            value.circuits,
+            vec![CircuitRef {
+                issuer: Addr::unchecked("alice_key"),
+                circuit_id: DEFAULT_CIRCUIT.to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn prover_history_keeps_every_verification() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys(deps.as_mut(), &[]);
+        mock_bob_publish_proof_to_verify(deps.as_mut(), &[]);
+        let zkeys = ZKEYS
+            .load(
+                deps.as_ref().storage,
+                (&Addr::unchecked("alice_key"), DEFAULT_CIRCUIT),
+            )
+            .unwrap();
+        let vkey_hash = hex::encode(vkey_hash(&zkeys.vkeys));
+        ZKEYS
+            .save(
+                deps.as_mut().storage,
+                (&Addr::unchecked("dave_key"), DEFAULT_CIRCUIT),
+                &zkeys,
+            )
+            .unwrap();
+
+        // a later proof for another issuer doesn't replace the first one
+        let mut env = mock_env();
+        env.block.height += 1;
+        let (proof_a, proof_b, proof_c) = bob_proof();
+        let msg = ExecuteMsg::Proof {
+            difficuty_issuer: "dave_key".to_string(),
+            circuit_id: None,
+            proof_a,
+            proof_b,
+            proof_c,
+            point_encoding: None,
+            snarkjs_proof: None,
+            public_signals: None,
+        };
+        execute(deps.as_mut(), env, mock_info("bob_key", &[]), msg).unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProverHistory {
+                prover_address: "bob_key".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: ProverHistoryResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            value.verifications,
+            vec![
+                VerificationResponse {
+                    sequence: 0,
+                    issuer: Addr::unchecked("alice_key"),
+                    circuit_id: DEFAULT_CIRCUIT.to_string(),
+                    vkey_hash: vkey_hash.clone(),
+                    height: mock_env().block.height,
+                },
+                VerificationResponse {
+                    sequence: 1,
+                    issuer: Addr::unchecked("dave_key"),
+                    circuit_id: DEFAULT_CIRCUIT.to_string(),
+                    vkey_hash,
+                    height: mock_env().block.height + 1,
+                },
+            ]
+        );
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProverHistory {
+                prover_address: "bob_key".to_string(),
+                start_after: None,
+                limit: Some(1),
+            },
+        )
+        .unwrap();
+        let value: ProverHistoryResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            value
+                .verifications
+                .iter()
+                .map(|v| v.sequence)
+                .collect::<Vec<_>>(),
+            vec![0]
+        );
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProverHistory {
+                prover_address: "bob_key".to_string(),
+                start_after: Some(0),
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: ProverHistoryResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            value
+                .verifications
+                .iter()
+                .map(|v| v.sequence)
+                .collect::<Vec<_>>(),
+            vec![1]
+        );
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProverHistory {
+                prover_address: "carol_key".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: ProverHistoryResponse = from_binary(&res).unwrap();
+        assert!(value.verifications.is_empty());
+    }
+
+    #[test]
+    fn verify_proof_query_works() {
+        let mut deps = mock_dependencies();
+        mock_init_with_price(deps.as_mut(), coin(1, "token"), coin(1, "token"));
+        mock_alice_set_zkeys(deps.as_mut(), &[coin(1, "token")]);
+        let zkeys = ZKEYS
+            .load(
+                deps.as_ref().storage,
+                (&Addr::unchecked("alice_key"), DEFAULT_CIRCUIT),
+            )
+            .unwrap();
+        let vkey_hash = hex::encode(vkey_hash(&zkeys.vkeys));
+        let (proof_a, proof_b, proof_c) = bob_proof();
+        let verify = |deps: Deps,
+                      key: VerifyingKeySource,
+                      public_signals: Option<Vec<String>>|
+         -> VerifyProofResponse {
+            let msg = QueryMsg::VerifyProof {
+                key,
+                proof_a: proof_a.clone(),
+                proof_b: proof_b.clone(),
+                proof_c: proof_c.clone(),
+                point_encoding: None,
+                public_signals,
+            };
+            from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap()
+        };
+
+        // no fee and nothing recorded
+        let registered = VerifyingKeySource::Registered {
+            issuer: "alice_key".to_string(),
+            circuit_id: None,
+            prover: None,
+        };
+        let value = verify(deps.as_ref(), registered.clone(), None);
+        assert_eq!(
+            value,
+            VerifyProofResponse {
+                is_valid: true,
+                vkey_hash: vkey_hash.clone(),
+                public_signals: zkeys.public_signals.clone(),
+                nullifier_spent: None,
+                error: None,
+            }
+        );
+        let msg = QueryMsg::ProofResult {
+            issuer_address: "alice_key".to_string(),
+            circuit_id: None,
+            prover_address: "bob_key".to_string(),
+        };
+        assert!(query(deps.as_ref(), mock_env(), msg).is_err());
+
+        // alice fixed the public signals
+        let value = verify(
+            deps.as_ref(),
+            registered,
+            Some(zkeys.public_signals.clone()),
+        );
+        assert!(!value.is_valid);
+        assert_eq!(value.error, Some("error public signal".to_string()));
+
+        // an unknown issuer fails the query
+        let msg = QueryMsg::VerifyProof {
+            key: VerifyingKeySource::Registered {
+                issuer: "carol_key".to_string(),
+                circuit_id: None,
+                prover: None,
+            },
+            proof_a: proof_a.clone(),
+            proof_b: proof_b.clone(),
+            proof_c: proof_c.clone(),
+            point_encoding: None,
+            public_signals: None,
+        };
+        assert!(query(deps.as_ref(), mock_env(), msg).is_err());
+
+        // the same key given inline takes its public signals from the query
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::IssuerZkeys {
+                address: "alice_key".to_string(),
+                circuit_id: None,
+            },
+        )
+        .unwrap();
+        let value: ZkeysResponse = from_binary(&res).unwrap();
+        let inline = VerifyingKeySource::Inline {
+            vk_alpha1: value.vk_alpha1,
+            vk_beta_2: value.vk_beta_2,
+            vk_gamma_2: value.vk_gamma_2,
+            vk_delta_2: value.vk_delta_2,
+            vk_ic: value.vk_ic,
+        };
+        let value = verify(
+            deps.as_ref(),
+            inline.clone(),
+            Some(zkeys.public_signals.clone()),
+        );
+        assert!(value.is_valid);
+        assert_eq!(value.vkey_hash, vkey_hash);
+        let value = verify(deps.as_ref(), inline.clone(), Some(vec!["34".to_string()]));
+        assert!(!value.is_valid);
+        assert!(value.error.is_some());
+        assert_eq!(value.public_signals, vec!["34".to_string()]);
+        let value = verify(deps.as_ref(), inline, None);
+        assert_eq!(value.error, Some("no public signal".to_string()));
+    }
+
+    #[test]
+    fn verify_proof_query_reports_nullifier_and_sender() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys_with_nullifier(deps.as_mut(), &[]);
+        let (proof_a, proof_b, proof_c) = bob_proof();
+        let msg = QueryMsg::VerifyProof {
+            key: VerifyingKeySource::Registered {
+                issuer: "alice_key".to_string(),
+                circuit_id: None,
+                prover: None,
+            },
+            proof_a,
+            proof_b,
+            proof_c,
+            point_encoding: None,
+            public_signals: None,
+        };
+        let value: VerifyProofResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), msg.clone()).unwrap()).unwrap();
+        assert_eq!(value.nullifier_spent, Some(false));
+        mock_bob_publish_proof_to_verify(deps.as_mut(), &[]);
+        let value: VerifyProofResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert!(value.is_valid);
+        assert_eq!(value.nullifier_spent, Some(true));
+
+        // a key bound to the sender needs to know the prover
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys_bound_to_sender(deps.as_mut(), &[], vec![])
+            .expect("contract handles set zkeys parameters");
+        let (proof_a, proof_b, proof_c) = bob_proof();
+        let msg = QueryMsg::VerifyProof {
+            key: VerifyingKeySource::Registered {
+                issuer: "alice_key".to_string(),
+                circuit_id: None,
+                prover: None,
+            },
+            proof_a,
+            proof_b,
+            proof_c,
+            point_encoding: None,
+            public_signals: None,
+        };
+        let value: VerifyProofResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert!(!value.is_valid);
+        assert_eq!(
+            value.error,
+            Some("the key binds the sender, a prover address is needed".to_string())
+        );
+    }
+
+    #[test]
+    fn named_circuits_are_kept_apart() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys(deps.as_mut(), &[]);
+        // alice publishes the same problem again under another name
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::IssuerZkeys {
+                address: "alice_key".to_string(),
+                circuit_id: None,
+            },
+        )
+        .unwrap();
+        let value: ZkeysResponse = from_binary(&res).unwrap();
+        let set_zkeys = |circuit_id: Option<String>| ExecuteMsg::Zkeys {
+            circuit_id,
+            metadata: None,
+            public_signals: value.public_signals.clone(),
+            public_signal_mode: None,
+            bind_sender: None,
+            nullifier_index: None,
+            bounty: None,
+            bounty_deadline: None,
+            vk_alpha1: value.vk_alpha1.clone(),
+            vk_beta_2: value.vk_beta_2.clone(),
+            vk_gamma_2: value.vk_gamma_2.clone(),
+            vk_delta_2: value.vk_delta_2.clone(),
+            vk_ic: value.vk_ic.clone(),
+            point_encoding: None,
+            snarkjs_vkey: None,
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice_key", &[]),
+            set_zkeys(Some("square".to_string())),
+        )
+        .unwrap();
+
+        // a key is replaced until a proof is listed against it
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice_key", &[]),
+            set_zkeys(None),
+        )
+        .unwrap();
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice_key", &[]),
+            set_zkeys(Some("".to_string())),
+        ) {
+            Err(ContractError::InvalidCircuitId {}) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Circuits {
+                issuer_address: "alice_key".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: CircuitsResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            value.circuit_ids,
+            vec![DEFAULT_CIRCUIT.to_string(), "square".to_string()]
+        );
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Circuits {
+                issuer_address: "alice_key".to_string(),
+                start_after: Some(DEFAULT_CIRCUIT.to_string()),
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: CircuitsResponse = from_binary(&res).unwrap();
+        assert_eq!(value.circuit_ids, vec!["square".to_string()]);
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Issuers {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: IssuersResponse = from_binary(&res).unwrap();
+        assert_eq!(value.issuers, vec![Addr::unchecked("alice_key")]);
+        // the next page starts past every circuit of alice
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Issuers {
+                start_after: Some("alice_key".to_string()),
+                limit: Some(1),
+            },
+        )
+        .unwrap();
+        let value: IssuersResponse = from_binary(&res).unwrap();
+        assert_eq!(value.issuers, Vec::<Addr>::new());
+
+        // a proof only counts for the circuit it names
+        let (proof_a, proof_b, proof_c) = bob_proof();
+        let proof = |circuit_id: &str| ExecuteMsg::Proof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: Some(circuit_id.to_string()),
+            proof_a: proof_a.clone(),
+            proof_b: proof_b.clone(),
+            proof_c: proof_c.clone(),
+            point_encoding: None,
+            snarkjs_proof: None,
+            public_signals: None,
+        };
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bob_key", &[]),
+            proof("cube"),
+        ) {
+            Err(ContractError::NonPublishDifficulty { difficuty_issuer }) => {
+                assert_eq!(difficuty_issuer, "alice_key")
+            }
+            res => panic!("Unexpected result: {:?}", res),
+        }
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bob_key", &[]),
+            proof("square"),
+        )
+        .unwrap();
+        assert!(res.events[0]
+            .attributes
+            .contains(&attr("circuit_id", "square")));
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice_key", &[]),
+            set_zkeys(Some("square".to_string())),
+        ) {
+            Err(ContractError::CircuitAlreadyRegistered { circuit_id }) => {
+                assert_eq!(circuit_id, "square")
+            }
+            res => panic!("Unexpected result: {:?}", res),
+        }
+
+        let msg = QueryMsg::ProofResult {
+            issuer_address: "alice_key".to_string(),
+            circuit_id: Some("square".to_string()),
+            prover_address: "bob_key".to_string(),
+        };
+        let value: ProofResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert!(value.is_valid);
+        let msg = QueryMsg::ProofResult {
+            issuer_address: "alice_key".to_string(),
+            circuit_id: None,
+            prover_address: "bob_key".to_string(),
+        };
+        assert!(query(deps.as_ref(), mock_env(), msg).is_err());
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Provers {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: ProversResponse = from_binary(&res).unwrap();
+        assert!(value.provers.is_empty());
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::SolvedCircuits {
+                prover_address: "bob_key".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: SolvedCircuitsResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            value.circuits,
+            vec![CircuitRef {
+                issuer: Addr::unchecked("alice_key"),
+                circuit_id: "square".to_string()
+            }]
+        );
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProverHistory {
+                prover_address: "bob_key".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: ProverHistoryResponse = from_binary(&res).unwrap();
+        assert_eq!(value.verifications[0].circuit_id, "square");
+
+        // the default circuit is still open to the same proof
+        mock_bob_publish_proof_to_verify(deps.as_mut(), &[]);
+        query_verification_result(deps.as_ref());
+    }
+
+    #[test]
+    fn circuit_registry_tracks_metadata_and_status() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys(deps.as_mut(), &[]);
+        let zkeys = ZKEYS
+            .load(
+                deps.as_ref().storage,
+                (&Addr::unchecked("alice_key"), DEFAULT_CIRCUIT),
+            )
+            .unwrap();
+
+        // a key registered without metadata
+        let msg = QueryMsg::Circuit {
+            issuer_address: "alice_key".to_string(),
+            circuit_id: None,
+        };
+        let value: CircuitResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(
+            value,
+            CircuitResponse {
+                issuer: Addr::unchecked("alice_key"),
+                circuit_id: DEFAULT_CIRCUIT.to_string(),
+                vkey_hash: hex::encode(vkey_hash(&zkeys.vkeys)),
+                proof_system: PROOF_SYSTEM.to_string(),
+                metadata: CircuitMetadata::default(),
+                status: CircuitStatus::Active,
+            }
+        );
+
+        // the same problem described under another name
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::IssuerZkeys {
+                address: "alice_key".to_string(),
+                circuit_id: None,
+            },
+        )
+        .unwrap();
+        let value: ZkeysResponse = from_binary(&res).unwrap();
+        let set_zkeys = |metadata: CircuitMetadata| ExecuteMsg::Zkeys {
+            circuit_id: Some("square".to_string()),
+            metadata: Some(metadata),
+            public_signals: value.public_signals.clone(),
+            public_signal_mode: None,
+            bind_sender: None,
+            nullifier_index: None,
+            bounty: None,
+            bounty_deadline: None,
+            vk_alpha1: value.vk_alpha1.clone(),
+            vk_beta_2: value.vk_beta_2.clone(),
+            vk_gamma_2: value.vk_gamma_2.clone(),
+            vk_delta_2: value.vk_delta_2.clone(),
+            vk_ic: value.vk_ic.clone(),
+            point_encoding: None,
+            snarkjs_vkey: None,
+        };
+        let metadata = CircuitMetadata {
+            name: "square".to_string(),
+            description: "knows a square root of the public signal".to_string(),
+            source_uri: "https://github.com/alice/circuits/blob/main/square.circom".to_string(),
+            artifact_hash: hex::encode([7u8; 32]),
+        };
+        let invalid = CircuitMetadata {
+            artifact_hash: "07".to_string(),
+            ..metadata.clone()
+        };
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice_key", &[]),
+            set_zkeys(invalid),
+        ) {
+            Err(ContractError::InvalidCircuitMetadata { field }) => {
+                assert_eq!(field, "artifact_hash")
+            }
+            res => panic!("Unexpected result: {:?}", res),
+        }
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice_key", &[]),
+            set_zkeys(metadata.clone()),
+        )
+        .unwrap();
+        let msg = QueryMsg::Circuit {
+            issuer_address: "alice_key".to_string(),
+            circuit_id: Some("square".to_string()),
+        };
+        let value: CircuitResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(value.metadata, metadata);
+        assert_eq!(value.status, CircuitStatus::Active);
+
+        // a deprecated circuit still accepts proofs
+        let msg = ExecuteMsg::SetCircuitStatus {
+            circuit_id: None,
+            status: CircuitStatus::Deprecated,
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alice_key", &[]), msg).unwrap();
+        assert_eq!(res.events[0].ty, "circuit_status_changed");
+        assert_eq!(
+            res.events[0].attributes,
+            vec![
+                attr("issuer", "alice_key"),
+                attr("circuit_id", DEFAULT_CIRCUIT),
+                attr("status", "deprecated")
+            ]
+        );
+        mock_commit_bob_proof(deps.as_mut(), "carol_key", "salt", 100);
+        mock_publish_bob_proof(deps.as_mut(), "bob_key", 101).unwrap();
+
+        // a retired one refuses them, what was proven stays
+        let msg = ExecuteMsg::SetCircuitStatus {
+            circuit_id: None,
+            status: CircuitStatus::Retired,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("alice_key", &[]), msg).unwrap();
+        match mock_reveal_bob_proof(deps.as_mut(), "carol_key", "salt", 102) {
+            Err(ContractError::CircuitRetired { circuit_id }) => {
+                assert_eq!(circuit_id, DEFAULT_CIRCUIT)
+            }
+            res => panic!("Unexpected result: {:?}", res),
+        }
+        let msg = ExecuteMsg::CommitProof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            commitment: hex::encode([0u8; 32]),
+        };
+        match execute(deps.as_mut(), mock_env(), mock_info("carol_key", &[]), msg) {
+            Err(ContractError::CircuitRetired { .. }) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+        let (proof_a, proof_b, proof_c) = bob_proof();
+        let msg = QueryMsg::VerifyProof {
+            key: VerifyingKeySource::Registered {
+                issuer: "alice_key".to_string(),
+                circuit_id: None,
+                prover: None,
+            },
+            proof_a,
+            proof_b,
+            proof_c,
+            point_encoding: None,
+            public_signals: None,
+        };
+        let value: VerifyProofResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert!(!value.is_valid);
+        assert_eq!(
+            value.error,
+            Some(
+                ContractError::CircuitRetired {
+                    circuit_id: DEFAULT_CIRCUIT.to_string()
+                }
+                .to_string()
+            )
+        );
+        query_verification_result(deps.as_ref());
+
+        // for good, and only by its issuer
+        let msg = ExecuteMsg::SetCircuitStatus {
+            circuit_id: None,
+            status: CircuitStatus::Active,
+        };
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice_key", &[]),
+            msg.clone(),
+        ) {
+            Err(ContractError::CircuitRetired { .. }) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+        match execute(deps.as_mut(), mock_env(), mock_info("bob_key", &[]), msg) {
+            Err(ContractError::NonPublishDifficulty { difficuty_issuer }) => {
+                assert_eq!(difficuty_issuer, "bob_key")
+            }
+            res => panic!("Unexpected result: {:?}", res),
+        }
+    }
+
+    fn mock_alice_set_snarkjs_zkeys(
+        deps: DepsMut,
+        snarkjs_vkey: SnarkjsVerifyingKey,
+    ) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::Zkeys {
+            circuit_id: None,
+            metadata: None,
+            public_signals: vec![],
+            public_signal_mode: Some(PublicSignalMode::Prover),
+            bind_sender: None,
+            nullifier_index: None,
+            bounty: None,
+            bounty_deadline: None,
+            vk_alpha1: String::new(),
+            vk_beta_2: String::new(),
+            vk_gamma_2: String::new(),
+            vk_delta_2: String::new(),
+            vk_ic: vec![],
+            point_encoding: None,
+            snarkjs_vkey: Some(snarkjs_vkey),
+        };
+        execute(deps, mock_env(), mock_info("alice_key", &[]), msg)
+    }
+
+    #[test]
+    fn verify_snarkjs_proof_works() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        let vkey: SnarkjsVerifyingKey = from_slice(ALICE_SNARKJS_VKEY.as_bytes()).unwrap();
+        mock_alice_set_snarkjs_zkeys(deps.as_mut(), vkey).unwrap();
+
+        // stored like the hex points of the same key
+        let mut hex_deps = mock_dependencies();
+        mock_init_no_price(hex_deps.as_mut());
+        mock_alice_set_zkeys(hex_deps.as_mut(), &[]);
+        let zkeys = |deps: Deps| -> ZkeysResponse {
+            let res = query(
+                deps,
+                mock_env(),
+                QueryMsg::IssuerZkeys {
+                    address: "alice_key".to_string(),
+                    circuit_id: None,
+                },
+            )
+            .unwrap();
+            from_binary(&res).unwrap()
+        };
+        let (stored, expected) = (zkeys(deps.as_ref()), zkeys(hex_deps.as_ref()));
+        assert_eq!(stored.vk_alpha1, expected.vk_alpha1);
+        assert_eq!(stored.vk_beta_2, expected.vk_beta_2);
+        assert_eq!(stored.vk_gamma_2, expected.vk_gamma_2);
+        assert_eq!(stored.vk_delta_2, expected.vk_delta_2);
+        assert_eq!(stored.vk_ic, expected.vk_ic);
+
+        // proof.json and public.json as snarkjs writes them, without the hex fields
+        let msg = format!(
+            r#"{{"proof": {{"difficuty_issuer": "alice_key", "snarkjs_proof": {}, "publicSignals": ["33"]}}}}"#,
+            BOB_SNARKJS_PROOF
+        );
+        let msg: ExecuteMsg = from_slice(msg.as_bytes()).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("bob_key", &[]), msg).unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProofResult {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                prover_address: "bob_key".to_string(),
+            },
+        )
+        .unwrap();
+        let value: ProofResponse = from_binary(&res).unwrap();
+        let (proof_a, proof_b, proof_c) = bob_proof();
+        assert_eq!(
+            (value.proof_a, value.proof_b, value.proof_c),
+            (proof_a, proof_b, proof_c)
+        );
+        assert!(value.is_valid);
+    }
+
+    #[test]
+    fn set_snarkjs_zkeys_failed_with_malformed_key() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        let vkey: SnarkjsVerifyingKey = from_slice(ALICE_SNARKJS_VKEY.as_bytes()).unwrap();
+
+        let other_curve = SnarkjsVerifyingKey {
+            curve: "other".to_string(),
+            ..vkey.clone()
+        };
+        match mock_alice_set_snarkjs_zkeys(deps.as_mut(), other_curve) {
+            Ok(_) => panic!("a key of another curve should be refused"),
+            Err(ContractError::SnarkjsMismatch { field, .. }) => assert_eq!(field, "curve"),
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+        let wrong_count = SnarkjsVerifyingKey {
+            n_public: 2,
+            ..vkey.clone()
+        };
+        match mock_alice_set_snarkjs_zkeys(deps.as_mut(), wrong_count) {
+            Ok(_) => panic!("nPublic must match the IC points"),
+            Err(ContractError::SnarkjsMismatch { field, .. }) => assert_eq!(field, "nPublic"),
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+        let mut not_decimal = vkey.clone();
+        not_decimal.ic[1][0] = "0x1f".to_string();
+        match mock_alice_set_snarkjs_zkeys(deps.as_mut(), not_decimal) {
+            Ok(_) => panic!("coordinates are decimal"),
+            Err(ContractError::InvalidSnarkjsCoordinate { field }) => assert_eq!(field, "IC"),
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+
+        // hex points and snarkjs json at once
+        let mut msg: ExecuteMsg = from_slice(format!(r#"{{"zkeys": {{"public_signals": [], "public_signal_mode": "prover", "snarkjs_vkey": {}}}}}"#, ALICE_SNARKJS_VKEY).as_bytes()).unwrap();
+        if let ExecuteMsg::Zkeys { vk_alpha1, .. } = &mut msg {
+            *vk_alpha1 = "00".to_string();
+        }
+        match execute(deps.as_mut(), mock_env(), mock_info("alice_key", &[]), msg) {
+            Ok(_) => panic!("the key must come in one encoding"),
+            Err(ContractError::AmbiguousEncoding {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn verify_compressed_proof_works() {
+        let zkeys = |deps: Deps| -> ZkeysResponse {
+            let res = query(
+                deps,
+                mock_env(),
+                QueryMsg::IssuerZkeys {
+                    address: "alice_key".to_string(),
+                    circuit_id: None,
+                },
+            )
+            .unwrap();
+            from_binary(&res).unwrap()
+        };
+        let mut hex_deps = mock_dependencies();
+        mock_init_no_price(hex_deps.as_mut());
+        mock_alice_set_zkeys(hex_deps.as_mut(), &[]);
+        let expected = zkeys(hex_deps.as_ref());
+
+        // a compressed key is stored as if it came uncompressed
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        let msg = ExecuteMsg::Zkeys {
+            circuit_id: None,
+            metadata: None,
+            public_signals: vec!["33".to_string()],
+            public_signal_mode: None,
+            bind_sender: None,
+            nullifier_index: None,
+            bounty: None,
+            bounty_deadline: None,
+            vk_alpha1: compressed_g1(&expected.vk_alpha1),
+            vk_beta_2: compressed_g2(&expected.vk_beta_2),
+            vk_gamma_2: compressed_g2(&expected.vk_gamma_2),
+            vk_delta_2: compressed_g2(&expected.vk_delta_2),
+            vk_ic: expected
+                .vk_ic
+                .iter()
+                .map(|ic_i| compressed_g1(ic_i))
+                .collect(),
+            point_encoding: Some(PointEncoding::Compressed),
+            snarkjs_vkey: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("alice_key", &[]), msg).unwrap();
+        assert_eq!(zkeys(deps.as_ref()), expected);
+
+        let (proof_a, proof_b, proof_c) = bob_proof();
+        let proof = |proof_a: &str,
+                     proof_b: &str,
+                     proof_c: &str,
+                     point_encoding: Option<PointEncoding>| ExecuteMsg::Proof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proof_a: proof_a.to_string(),
+            proof_b: proof_b.to_string(),
+            proof_c: proof_c.to_string(),
+            point_encoding,
+            snarkjs_proof: None,
+            public_signals: None,
+        };
+        let compressed = (
+            compressed_g1(&proof_a),
+            compressed_g2(&proof_b),
+            compressed_g1(&proof_c),
+        );
+
+        // the tag decides how the points are read
+        let msg = proof(
+            &proof_a,
+            &proof_b,
+            &proof_c,
+            Some(PointEncoding::Compressed),
+        );
+        match execute(deps.as_mut(), mock_env(), mock_info("bob_key", &[]), msg) {
+            Ok(_) => panic!("uncompressed points tagged as compressed"),
+            Err(e @ ContractError::ErrorProof { .. }) => {
+                assert_eq!(e.to_string(), "proof_a: expected 32 bytes, got 64")
+            }
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+        let msg = proof(&compressed.0, &compressed.1, &compressed.2, None);
+        match execute(deps.as_mut(), mock_env(), mock_info("bob_key", &[]), msg) {
+            Ok(_) => panic!("compressed points without the tag"),
+            Err(e @ ContractError::ErrorProof { .. }) => {
+                assert_eq!(e.to_string(), "proof_a: expected 64 bytes, got 32")
+            }
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+
+        // the proof is stored compressed
+        let msg = proof(
+            &compressed.0,
+            &compressed.1,
+            &compressed.2,
+            Some(PointEncoding::Compressed),
+        );
+        execute(deps.as_mut(), mock_env(), mock_info("bob_key", &[]), msg).unwrap();
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProofResult {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                prover_address: "bob_key".to_string(),
+            },
+        )
+        .unwrap();
+        let value: ProofResponse = from_binary(&res).unwrap();
+        assert_eq!(value.point_encoding, PointEncoding::Compressed);
+        assert_eq!((value.proof_a, value.proof_b, value.proof_c), compressed);
+        assert!(value.is_valid);
+
+        // every prover of the issuer's signals is credited, in the encoding they sent
+        let msg = proof(&proof_a, &proof_b, &proof_c, None);
+        execute(deps.as_mut(), mock_env(), mock_info("carol_key", &[]), msg).unwrap();
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProofResult {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                prover_address: "carol_key".to_string(),
+            },
+        )
+        .unwrap();
+        let value: ProofResponse = from_binary(&res).unwrap();
+        assert_eq!(value.point_encoding, PointEncoding::Uncompressed);
+        assert_eq!(value.proof_a, proof_a);
+    }
+
+    #[test]
+    fn verify_binary_proof_works() {
+        let zkeys = |deps: Deps| -> ZkeysResponse {
+            let res = query(
+                deps,
+                mock_env(),
+                QueryMsg::IssuerZkeys {
+                    address: "alice_key".to_string(),
+                    circuit_id: None,
+                },
+            )
+            .unwrap();
+            from_binary(&res).unwrap()
+        };
+        let binary = |point: &str| Binary::from(hex::decode(point).unwrap());
+        let mut hex_deps = mock_dependencies();
+        mock_init_no_price(hex_deps.as_mut());
+        mock_alice_set_zkeys(hex_deps.as_mut(), &[]);
+        let expected = zkeys(hex_deps.as_ref());
+
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        let msg = ExecuteMsg::ZkeysBinary {
+            circuit_id: None,
+            metadata: None,
+            public_signals: vec!["33".to_string()],
+            public_signal_mode: None,
+            bind_sender: None,
+            nullifier_index: None,
+            bounty: None,
+            bounty_deadline: None,
+            vk_alpha1: binary(&expected.vk_alpha1),
+            vk_beta_2: binary(&expected.vk_beta_2),
+            vk_gamma_2: binary(&expected.vk_gamma_2),
+            vk_delta_2: binary(&expected.vk_delta_2),
+            vk_ic: expected.vk_ic.iter().map(|ic_i| binary(ic_i)).collect(),
+            point_encoding: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("alice_key", &[]), msg).unwrap();
+        // the same key for either query
+        assert_eq!(zkeys(deps.as_ref()), expected);
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::IssuerZkeysBinary {
+                address: "alice_key".to_string(),
+                circuit_id: None,
+            },
+        )
+        .unwrap();
+        let value: ZkeysBinaryResponse = from_binary(&res).unwrap();
+        assert_eq!(value.vk_alpha1, binary(&expected.vk_alpha1));
+        assert_eq!(
+            value.vk_ic,
+            expected
+                .vk_ic
+                .iter()
+                .map(|ic_i| binary(ic_i))
+                .collect::<Vec<_>>()
+        );
+
+        let (proof_a, proof_b, proof_c) = bob_proof();
+        let msg = ExecuteMsg::ProofBinary {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proof_a: binary(&proof_a),
+            proof_b: binary(&proof_b),
+            proof_c: binary(&proof_c),
+            point_encoding: None,
+            public_signals: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("bob_key", &[]), msg).unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProofResultBinary {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                prover_address: "bob_key".to_string(),
+            },
+        )
+        .unwrap();
+        let value: ProofBinaryResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            (value.proof_a, value.proof_b, value.proof_c),
+            (binary(&proof_a), binary(&proof_b), binary(&proof_c))
+        );
+        assert!(value.is_valid);
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProofResult {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                prover_address: "bob_key".to_string(),
+            },
+        )
+        .unwrap();
+        let value: ProofResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            (value.proof_a, value.proof_b, value.proof_c),
+            (proof_a, proof_b, proof_c)
+        );
+    }
+
+    #[test]
+    fn prepared_vkey_holds_alpha_beta() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys(deps.as_mut(), &[]);
+        let zkeys = ZKEYS
+            .load(
+                deps.as_ref().storage,
+                (&Addr::unchecked("alice_key"), DEFAULT_CIRCUIT),
+            )
+            .unwrap();
+        let mut prepared = zkeys.prepared.clone().unwrap();
+
+        // a proof is judged as the uncached verifier judges it
+        let (a, b, c) = bob_proof();
+        let proof_str = decode_proof(a, b, c, PointEncoding::Uncompressed).unwrap();
+        let public_signals = vec!["33".to_string()];
+        let (is_passed, public_inputs) =
+            check_proof(&prepared, proof_str.clone(), &public_signals).unwrap();
+        assert!(is_passed);
+        let pvk = prepare_verifying_key(&parse_vkey::<Bn256>(zkeys.vkeys.clone()).unwrap());
+        let proof = parse_proof::<Bn256>(proof_str.clone()).unwrap();
+        assert!(verify_proof(&pvk, &proof, &public_inputs).unwrap());
+
+        // against another e(alpha, beta) the same proof fails
+        let mut alpha_beta = prepared.alpha_beta.to_vec();
+        alpha_beta[31] ^= 1;
+        prepared.alpha_beta = Binary::from(alpha_beta);
+        assert_eq!(
+            check_proof(&prepared, proof_str, &public_signals).map(|(is_passed, _)| is_passed),
+            Ok(false)
+        );
+    }
+
+    #[test]
+    fn verify_proof_batch_works() {
+        let mut deps = mock_dependencies();
+        mock_init_with_price(deps.as_mut(), coin(1, "token"), coin(1, "token"));
+        mock_alice_set_zkeys(deps.as_mut(), &coins(1, "token"));
+
+        // bob sends his proof along with a re-randomized copy, naming himself
+        let batch = || ExecuteMsg::ProofBatch {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proofs: vec![
+                batch_proof(None, bob_proof()),
+                batch_proof(Some("bob_key"), rerandomized_proof(bob_proof())),
+            ],
+        };
+
+        // the batch pays for every proof
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bob_key", &coins(1, "token")),
+            batch(),
+        ) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::InsufficientFundsSend {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bob_key", &coins(2, "token")),
+            batch(),
+        )
+        .unwrap();
+        assert_eq!(res.events.len(), 3);
+        assert_eq!(res.events[0].attributes[2], attr("prover", "bob_key"));
+        assert_eq!(res.events[1].attributes[2], attr("prover", "bob_key"));
+        assert_eq!(res.events[1].attributes[5], attr("fee", ""));
+        assert_eq!(res.events[2].ty, "proof_batch_verified");
+        assert_eq!(
+            res.events[2].attributes,
+            vec![
+                attr("issuer", "alice_key"),
+                attr("circuit_id", DEFAULT_CIRCUIT),
+                attr("proofs", "2"),
+                attr("fee", "2token"),
+            ]
+        );
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProofResult {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                prover_address: "bob_key".to_string(),
+            },
+        )
+        .unwrap();
+        let value: ProofResponse = from_binary(&res).unwrap();
+        assert!(value.is_valid);
+    }
+
+    #[test]
+    fn verify_proof_batch_failed_with_relayed_prover() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys(deps.as_mut(), &[]);
+
+        // the proof would credit carol without her having made it
+        let msg = ExecuteMsg::ProofBatch {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proofs: vec![
+                batch_proof(None, bob_proof()),
+                batch_proof(Some("carol_key"), rerandomized_proof(bob_proof())),
+            ],
+        };
+        assert_eq!(
+            execute(deps.as_mut(), mock_env(), mock_info("bob_key", &[]), msg),
+            Err(ContractError::ProverNotSender {})
+        );
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Provers {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: ProversResponse = from_binary(&res).unwrap();
+        assert_eq!(value.provers, Vec::<Addr>::new());
+    }
+
+    #[test]
+    fn verify_proof_batch_checks_relayed_proofs_against_their_prover() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys_bound_to_sender(deps.as_mut(), &[], vec![])
+            .expect("contract handles set zkeys parameters");
+
+        // carol may name bob on a key binding the sender, the proof is checked against his sender signal
+        let msg = ExecuteMsg::ProofBatch {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proofs: vec![batch_proof(Some("bob_key"), bob_proof())],
+        };
+        assert_eq!(
+            execute(deps.as_mut(), mock_env(), mock_info("carol_key", &[]), msg),
+            Err(ContractError::InvalidBatchProofs { indexes: vec![0] })
+        );
+    }
+
+    #[test]
+    fn verify_proof_batch_failed_with_spent_nullifier() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys_with_prover_nullifier(deps.as_mut(), &[]);
+
+        let bob = BatchProof {
+            public_signals: Some(vec!["25".to_string(), "7".to_string()]),
+            ..batch_proof(None, (
+                "0c7b198d2730961ec14e88e3f66ade96f337cd084c62203afddb78ca7004ded6261bc1dd65afe765f562383d1511ea5d408874f254f8effb494fac8c0d6841de".to_string(),
+                "2eab025730ddebf2d1d5ffeacd34a61d19cc635fb4ed5690fe80327b72f18a742eaf4fa0ee05b09362ed80411c7fe6a3618a462b0da7689c4d5a8e006fa491e7240864a7254823857c1f80e3d372c9e372e849a5ea94cc0e2ab27f0858ceef94157eb59ac42328c345354c0ff646603599cb1794d1f96a9b15379c8119fa41e1".to_string(),
+                "045c9a633eca9596bb7cf65b4f4f1aa7c04f0c23038cd64f1b317b971841b61e0d5ab59dfa1d2baead5c312f12c4fc876c4626587e7a0775be1ae7bb0cec9975".to_string(),
+            ))
+        };
+        let batch = |proofs| ExecuteMsg::ProofBatch {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proofs,
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bob_key", &[]),
+            batch(vec![bob.clone()]),
+        )
+        .expect("contract handles verify proof batch");
+
+        // a copy of bob's proof doesn't spend its nullifier again
+        let copy = BatchProof {
+            prover: Some("dave_key".to_string()),
+            ..bob
+        };
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("dave_key", &[]),
+                batch(vec![copy])
+            ),
+            Err(ContractError::NullifierAlreadySpent {})
+        );
+    }
+
+    #[test]
+    fn verify_proof_batch_reports_invalid_proofs() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys(deps.as_mut(), &[]);
+
+        let msg = ExecuteMsg::ProofBatch {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proofs: vec![],
+        };
+        match execute(deps.as_mut(), mock_env(), mock_info("bob_key", &[]), msg) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::EmptyProofBatch {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+
+        // well formed points, but not a proof of the statement
+        let (proof_a, proof_b, _) = bob_proof();
+        let forged = (proof_a.clone(), proof_b, proof_a);
+        let msg = ExecuteMsg::ProofBatch {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proofs: vec![
+                batch_proof(None, bob_proof()),
+                batch_proof(None, forged),
+                batch_proof(None, rerandomized_proof(bob_proof())),
+            ],
+        };
+        match execute(deps.as_mut(), mock_env(), mock_info("bob_key", &[]), msg) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::InvalidBatchProofs { indexes }) => assert_eq!(indexes, vec![1]),
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+    }
+}
@@ -1,11 +1,12 @@
 use cosmwasm_schema::write_api;
 
-use cw_groth16::msg::{InstantiateMsg, QueryMsg, ExecuteMsg};
+use cw_groth16::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
 
 fn main() {
     write_api! {
         instantiate: InstantiateMsg,
         execute: ExecuteMsg,
         query: QueryMsg,
+        migrate: MigrateMsg,
     }
-}
\ No newline at end of file
+}
@@ -1,5 +1,7 @@
 use crate::error::ContractError;
-use cosmwasm_std::Coin;
+use crate::state::{FeeMode, UnrelatedDenoms};
+use cosmwasm_std::{to_binary, Addr, BankMsg, Coin, WasmMsg};
+use cw20::{Cw20CoinVerified, Cw20ExecuteMsg};
 
 pub fn assert_sent_sufficient_coin(
     sent: &[Coin],
@@ -24,10 +26,118 @@ pub fn assert_sent_sufficient_coin(
     Ok(())
 }
 
+/// take `required` out of `sent`, with `FeeMode::Exact` everything else
+/// goes back to `sender` in the returned message
+pub fn charge_fee(
+    sender: &Addr,
+    sent: &[Coin],
+    required: Option<Coin>,
+    fee_mode: &FeeMode,
+    unrelated_denoms: &UnrelatedDenoms,
+) -> Result<Option<BankMsg>, ContractError> {
+    assert_sent_sufficient_coin(sent, required.clone())?;
+    if *fee_mode == FeeMode::Sufficient {
+        return Ok(None);
+    }
+
+    let mut refund = vec![];
+    for coin in sent {
+        match &required {
+            Some(required_coin) if required_coin.denom == coin.denom => {
+                let rest = coin.amount.saturating_sub(required_coin.amount);
+                if !rest.is_zero() {
+                    refund.push(Coin {
+                        denom: coin.denom.clone(),
+                        amount: rest,
+                    });
+                }
+            }
+            _ => {
+                if *unrelated_denoms == UnrelatedDenoms::Reject {
+                    return Err(ContractError::UnrelatedDenom {
+                        denom: coin.denom.clone(),
+                    });
+                }
+                refund.push(coin.clone());
+            }
+        }
+    }
+
+    if refund.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(BankMsg::Send {
+        to_address: sender.to_string(),
+        amount: refund,
+    }))
+}
+
+/// take `required` out of the cw20 tokens in `sent`, with `FeeMode::Exact`
+/// the rest is transferred back to `sender` by the returned message
+pub fn charge_cw20_fee(
+    sender: &Addr,
+    sent: &Cw20CoinVerified,
+    required: Option<Cw20CoinVerified>,
+    fee_mode: &FeeMode,
+) -> Result<Option<WasmMsg>, ContractError> {
+    let required = match required {
+        Some(required_coin) if required_coin.address == sent.address => required_coin,
+        _ => {
+            return Err(ContractError::UnacceptedToken {
+                token: sent.address.to_string(),
+            })
+        }
+    };
+    let rest = sent
+        .amount
+        .checked_sub(required.amount)
+        .map_err(|_| ContractError::InsufficientFundsSend {})?;
+    if *fee_mode == FeeMode::Sufficient || rest.is_zero() {
+        return Ok(None);
+    }
+
+    Ok(Some(WasmMsg::Execute {
+        contract_addr: sent.address.to_string(),
+        msg: to_binary(&Cw20ExecuteMsg::Transfer {
+            recipient: sender.to_string(),
+            amount: rest,
+        })?,
+        funds: vec![],
+    }))
+}
+
+/// the funds left in `sent` after taking `amount` out of them
+pub fn deduct_coin(sent: &[Coin], amount: &Coin) -> Result<Vec<Coin>, ContractError> {
+    let mut rest = vec![];
+    let mut deducted = false;
+    for coin in sent {
+        if coin.denom == amount.denom {
+            let left = coin
+                .amount
+                .checked_sub(amount.amount)
+                .map_err(|_| ContractError::InsufficientFundsSend {})?;
+            if !left.is_zero() {
+                rest.push(Coin {
+                    denom: coin.denom.clone(),
+                    amount: left,
+                });
+            }
+            deducted = true;
+        } else {
+            rest.push(coin.clone());
+        }
+    }
+
+    if !deducted && !amount.amount.is_zero() {
+        return Err(ContractError::InsufficientFundsSend {});
+    }
+    Ok(rest)
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
-    use cosmwasm_std::{coin, coins};
+    use cosmwasm_std::{coin, coins, Uint128};
 
     #[test]
     fn assert_sent_sufficient_coin_works() {
@@ -59,4 +169,154 @@ mod test {
             Err(e) => panic!("Unexpected error: {:?}", e),
         };
     }
+
+    #[test]
+    fn charge_fee_works() {
+        let sender = Addr::unchecked("bob_key");
+        let sent = vec![coin(2, "smokin"), coin(7, "token")];
+
+        // the default mode keeps everything
+        let refund = charge_fee(
+            &sender,
+            &sent,
+            Some(coin(5, "token")),
+            &FeeMode::Sufficient,
+            &UnrelatedDenoms::Refund,
+        )
+        .unwrap();
+        assert_eq!(refund, None);
+
+        let refund = charge_fee(
+            &sender,
+            &sent,
+            Some(coin(5, "token")),
+            &FeeMode::Exact,
+            &UnrelatedDenoms::Refund,
+        )
+        .unwrap();
+        assert_eq!(
+            refund,
+            Some(BankMsg::Send {
+                to_address: "bob_key".to_string(),
+                amount: vec![coin(2, "smokin"), coin(2, "token")]
+            })
+        );
+
+        let refund = charge_fee(
+            &sender,
+            &coins(5, "token"),
+            Some(coin(5, "token")),
+            &FeeMode::Exact,
+            &UnrelatedDenoms::Reject,
+        )
+        .unwrap();
+        assert_eq!(refund, None);
+
+        match charge_fee(
+            &sender,
+            &sent,
+            Some(coin(5, "token")),
+            &FeeMode::Exact,
+            &UnrelatedDenoms::Reject,
+        ) {
+            Ok(_) => panic!("Should have raised unrelated denom error"),
+            Err(ContractError::UnrelatedDenom { denom }) => assert_eq!(denom, "smokin"),
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        };
+
+        match charge_fee(
+            &sender,
+            &coins(4, "token"),
+            Some(coin(5, "token")),
+            &FeeMode::Exact,
+            &UnrelatedDenoms::Refund,
+        ) {
+            Ok(_) => panic!("Should have raised insufficient funds error"),
+            Err(ContractError::InsufficientFundsSend {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        };
+    }
+
+    #[test]
+    fn charge_cw20_fee_works() {
+        let sender = Addr::unchecked("bob_key");
+        let price = Cw20CoinVerified {
+            address: Addr::unchecked("cw20_token"),
+            amount: Uint128::new(5),
+        };
+        let sent = Cw20CoinVerified {
+            address: Addr::unchecked("cw20_token"),
+            amount: Uint128::new(7),
+        };
+
+        let refund =
+            charge_cw20_fee(&sender, &sent, Some(price.clone()), &FeeMode::Sufficient).unwrap();
+        assert_eq!(refund, None);
+
+        let refund = charge_cw20_fee(&sender, &sent, Some(price.clone()), &FeeMode::Exact).unwrap();
+        assert_eq!(
+            refund,
+            Some(WasmMsg::Execute {
+                contract_addr: "cw20_token".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "bob_key".to_string(),
+                    amount: Uint128::new(2)
+                })
+                .unwrap(),
+                funds: vec![],
+            })
+        );
+
+        let other = Cw20CoinVerified {
+            address: Addr::unchecked("other_token"),
+            amount: Uint128::new(7),
+        };
+        match charge_cw20_fee(&sender, &other, Some(price.clone()), &FeeMode::Sufficient) {
+            Ok(_) => panic!("Should have raised unaccepted token error"),
+            Err(ContractError::UnacceptedToken { token }) => assert_eq!(token, "other_token"),
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        };
+
+        // without a cw20 price no token is accepted
+        match charge_cw20_fee(&sender, &sent, None, &FeeMode::Sufficient) {
+            Ok(_) => panic!("Should have raised unaccepted token error"),
+            Err(ContractError::UnacceptedToken { token }) => assert_eq!(token, "cw20_token"),
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        };
+
+        let short = Cw20CoinVerified {
+            address: Addr::unchecked("cw20_token"),
+            amount: Uint128::new(4),
+        };
+        match charge_cw20_fee(&sender, &short, Some(price), &FeeMode::Exact) {
+            Ok(_) => panic!("Should have raised insufficient funds error"),
+            Err(ContractError::InsufficientFundsSend {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        };
+    }
+
+    #[test]
+    fn deduct_coin_works() {
+        let sent = vec![coin(2, "smokin"), coin(7, "token")];
+        assert_eq!(
+            deduct_coin(&sent, &coin(5, "token")).unwrap(),
+            vec![coin(2, "smokin"), coin(2, "token")]
+        );
+        assert_eq!(
+            deduct_coin(&sent, &coin(2, "smokin")).unwrap(),
+            vec![coin(7, "token")]
+        );
+
+        match deduct_coin(&sent, &coin(8, "token")) {
+            Ok(_) => panic!("Should have raised insufficient funds error"),
+            Err(ContractError::InsufficientFundsSend {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        };
+
+        match deduct_coin(&sent, &coin(1, "earth")) {
+            Ok(_) => panic!("Should have raised insufficient funds error"),
+            Err(ContractError::InsufficientFundsSend {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        };
+    }
 }
@@ -0,0 +1,94 @@
+use crate::state::{ProofStr, VkeyStr};
+use cosmwasm_std::Addr;
+use sha2::{Digest, Sha256};
+
+/// the curve points of a proof in message order and in the encoding they were revealed in
+fn proof_points(proof: &ProofStr) -> Vec<u8> {
+    [&proof.pi_a[..], &proof.pi_b[..], &proof.pi_c[..]].concat()
+}
+
+/// sha256(pi_a || pi_b || pi_c || sender || salt), the value a prover sends with `CommitProof`
+pub fn proof_commitment(proof: &ProofStr, sender: &Addr, salt: &str) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    hasher.update(proof_points(proof));
+    hasher.update(sender.as_bytes());
+    hasher.update(salt.as_bytes());
+    hasher.finalize().to_vec()
+}
+
+/// sha256(vkey hash || public inputs), what a proof proves. Every proof of a statement has
+/// the same hash, re-randomized or not and whoever submits it
+pub fn statement_hash(vkey_hash: &[u8], public_inputs: &[Vec<u8>]) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    hasher.update(vkey_hash);
+    for input in public_inputs {
+        hasher.update(input);
+    }
+    hasher.finalize().to_vec()
+}
+
+/// sha256(statement hash || prover), the claim of one prover on a statement every prover
+/// can prove, such as the public signals set by the issuer
+pub fn prover_claim(statement: &[u8], prover: &Addr) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    hasher.update(statement);
+    hasher.update(prover.as_bytes());
+    hasher.finalize().to_vec()
+}
+
+/// sha256 of a verification key, identifies the circuit it belongs to
+pub fn vkey_hash(vkeys: &VkeyStr) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    hasher.update(&vkeys.alpha_1);
+    hasher.update(&vkeys.beta_2);
+    hasher.update(&vkeys.gamma_2);
+    hasher.update(&vkeys.delta_2);
+    hasher.update(vkeys.ic.concat());
+    hasher.finalize().to_vec()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::state::PointEncoding;
+
+    #[test]
+    fn proof_commitment_works() {
+        let proof = ProofStr {
+            pi_a: vec![1; 96],
+            pi_b: vec![2; 192],
+            pi_c: vec![3; 96],
+            encoding: PointEncoding::Uncompressed,
+        };
+        let bob = Addr::unchecked("bob_key");
+        let commitment = proof_commitment(&proof, &bob, "salt");
+        assert_eq!(commitment.len(), 32);
+
+        // the same proof commits differently for another sender or salt
+        assert_ne!(
+            commitment,
+            proof_commitment(&proof, &Addr::unchecked("carol_key"), "salt")
+        );
+        assert_ne!(commitment, proof_commitment(&proof, &bob, "pepper"));
+    }
+
+    #[test]
+    fn statement_hash_works() {
+        let statement = statement_hash(&[1; 32], &[vec![7; 32]]);
+        // another key or other public inputs make another statement
+        assert_ne!(statement, statement_hash(&[2; 32], &[vec![7; 32]]));
+        assert_ne!(statement, statement_hash(&[1; 32], &[vec![8; 32]]));
+    }
+
+    #[test]
+    fn prover_claim_works() {
+        let statement = statement_hash(&[1; 32], &[vec![7; 32]]);
+        let claim = prover_claim(&statement, &Addr::unchecked("bob_key"));
+        // every prover has their own claim on the statement
+        assert_ne!(claim, statement);
+        assert_ne!(
+            claim,
+            prover_claim(&statement, &Addr::unchecked("carol_key"))
+        );
+    }
+}
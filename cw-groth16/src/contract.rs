@@ -1,34 +1,127 @@
-use cosmwasm_std::{
-    entry_point, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult
+use super::commitment::{proof_commitment, prover_claim, statement_hash, vkey_hash};
+use super::migrations::migrate_from_v0_1;
+use super::msg::{
+    BatchProof, ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg,
+    VerifyingKeySource, DEFAULT_CIRCUIT, PROOF_SYSTEM,
+};
+use super::msg::{
+    BountyResponse, CircuitRef, CircuitResponse, CircuitsResponse, CommitmentResponse,
+    FeeBalanceResponse, IssuersResponse, NullifierResponse, ProofBinaryResponse, ProofResponse,
+    ProverHistoryResponse, ProversResponse, SenderSignalResponse, SolvedCircuitsResponse,
+    VerificationResponse, VerifyProofResponse, ZkeysBinaryResponse, ZkeysResponse,
+};
+use super::parser::{parse_prepared_vkey, parse_proof, prepare_vkey, uncompressed_vkey};
+use super::signal_helpers::sender_public_signal;
+use super::snarkjs::{SnarkjsProof, SnarkjsVerifyingKey};
+use super::state::{
+    Bounty, Commitment, ProofClaim, BOUNTIES, BOUNTY_ESCROW, COMMITMENTS, NULLIFIERS, PROOF_CLAIMS,
+    SOLVED,
+};
+use super::state::{
+    CircuitMetadata, CircuitStatus, Config, FeeMode, PreparedVkey, ProofInfo, PublicSignalMode,
+    VkeyStr, ZkeysStr, CONFIG, PROVERLIST, ZKEYS,
 };
-use crate::coin_helpers::assert_sent_sufficient_coin;
-use crate::state::ProofStr;
+use super::state::{Verification, PROVER_HISTORY, PROVER_SEQUENCE};
+use crate::coin_helpers::{charge_cw20_fee, charge_fee, deduct_coin};
+use crate::state::{PointEncoding, ProofStr};
 use crate::ContractError;
-use super::msg::{ExecuteMsg, ConfigResponse, InstantiateMsg, QueryMsg};
-use super::state::{Config, CONFIG, PROVERINFO, PROVERLIST, ProofInfo, ZKEYS, ZkeysStr, VkeyStr};
-use super::msg::{ProofResponse, ZkeysResponse};
-use super::parser::{parse_proof, parse_vkey};
-use bls12_381::Bls12;
+use bls12_381_plus::{multi_miller_loop, Bls12, G1Affine, G1Projective, G2Prepared, Gt, Scalar};
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    from_binary, to_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, Deps, DepsMut, Empty, Env,
+    Event, MessageInfo, Order, Response, StdError, StdResult, Storage, Uint128, WasmMsg,
+};
+use cw2::{set_contract_version, CONTRACT};
+use cw20::{Cw20Coin, Cw20CoinVerified, Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw_storage_plus::{Bound, PrefixBound};
 use ff::PrimeField as Fr;
-use bellman_verifier::{prepare_verifying_key, verify_proof};
+use semver::Version;
+use sha2::{Digest, Sha256};
+
+// version info for migration
+const CONTRACT_NAME: &str = "crates.io:cw-groth16";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub const DEFAULT_COMMITMENT_EXPIRY: u64 = 100;
+
+const MAX_CIRCUIT_ID_LENGTH: usize = 64;
+const MAX_CIRCUIT_NAME_LENGTH: usize = 64;
+const MAX_CIRCUIT_DESCRIPTION_LENGTH: usize = 1024;
+const MAX_CIRCUIT_SOURCE_URI_LENGTH: usize = 256;
+
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
 
 // instantiate the contract
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
     _env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, StdError> {
     let config = Config {
+        owner: info.sender,
         zkeys_price: msg.set_zkeys_price,
         proof_price: msg.publish_proof_price,
+        zkeys_cw20_price: validate_cw20_price(deps.as_ref(), msg.set_zkeys_cw20_price)?,
+        proof_cw20_price: validate_cw20_price(deps.as_ref(), msg.publish_proof_cw20_price)?,
+        fee_mode: msg.fee_mode.unwrap_or_default(),
+        unrelated_denoms: msg.unrelated_denoms.unwrap_or_default(),
+        commitment_expiry: msg.commitment_expiry.unwrap_or(DEFAULT_COMMITMENT_EXPIRY),
     };
     CONFIG.save(deps.storage, &config)?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     Ok(Response::default())
 }
 
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    let new_version: Version = CONTRACT_VERSION.parse()?;
+    match CONTRACT.may_load(deps.storage)? {
+        Some(stored) => {
+            if stored.contract != CONTRACT_NAME {
+                return Err(ContractError::CannotMigrate {
+                    previous_contract: stored.contract,
+                });
+            }
+            let previous_version: Version = stored.version.parse()?;
+            if previous_version > new_version {
+                return Err(ContractError::CannotDowngrade {
+                    previous_version: stored.version,
+                    new_version: CONTRACT_VERSION.to_string(),
+                });
+            }
+            // releases that change a stored layout add their rewrite here, keyed on `previous_version`
+        }
+        None => {
+            // only 0.1.0 didn't record its version
+            let owner = msg.owner.ok_or(ContractError::MissingOwner {})?;
+            migrate_from_v0_1(
+                deps.storage,
+                deps.api.addr_validate(&owner)?,
+                env.block.height,
+            )?;
+        }
+    }
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::default())
+}
+
+fn validate_cw20_price(deps: Deps, price: Option<Cw20Coin>) -> StdResult<Option<Cw20CoinVerified>> {
+    price
+        .map(|coin| {
+            Ok(Cw20CoinVerified {
+                address: deps.api.addr_validate(&coin.address)?,
+                amount: coin.amount,
+            })
+        })
+        .transpose()
+}
 
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
@@ -38,147 +131,2104 @@ pub fn execute(
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     match msg {
-        ExecuteMsg::Zkeys { 
-            public_signal,
+        ExecuteMsg::Zkeys {
+            circuit_id,
+            metadata,
+            public_signals,
+            public_signal_mode,
+            bind_sender,
+            nullifier_index,
+            bounty,
+            bounty_deadline,
+            vk_alpha1,
+            vk_beta_2,
+            vk_gamma_2,
+            vk_delta_2,
+            vk_ic,
+            point_encoding,
+            snarkjs_vkey,
+        } => execute_set_zkeys(
+            deps,
+            env,
+            info,
+            None,
+            circuit_id,
+            metadata,
+            public_signals,
+            public_signal_mode,
+            bind_sender,
+            nullifier_index,
+            bounty,
+            bounty_deadline,
+            submitted_vkey(
+                vk_alpha1,
+                vk_beta_2,
+                vk_gamma_2,
+                vk_delta_2,
+                vk_ic,
+                point_encoding,
+                snarkjs_vkey,
+            )?,
+        ),
+        ExecuteMsg::Proof {
+            difficuty_issuer,
+            circuit_id,
+            proof_a,
+            proof_b,
+            proof_c,
+            point_encoding,
+            snarkjs_proof,
+            public_signals,
+        } => execute_publish_proof(
+            deps,
+            env,
+            info,
+            None,
+            difficuty_issuer,
+            circuit_id,
+            submitted_proof(proof_a, proof_b, proof_c, point_encoding, snarkjs_proof)?,
+            public_signals,
+        ),
+        ExecuteMsg::ZkeysBinary {
+            circuit_id,
+            metadata,
+            public_signals,
+            public_signal_mode,
+            bind_sender,
+            nullifier_index,
+            bounty,
+            bounty_deadline,
+            vk_alpha1,
+            vk_beta_2,
+            vk_gamma_2,
+            vk_delta_2,
+            vk_ic,
+            point_encoding,
+        } => execute_set_zkeys(
+            deps,
+            env,
+            info,
+            None,
+            circuit_id,
+            metadata,
+            public_signals,
+            public_signal_mode,
+            bind_sender,
+            nullifier_index,
+            bounty,
+            bounty_deadline,
+            binary_vkey(
+                vk_alpha1,
+                vk_beta_2,
+                vk_gamma_2,
+                vk_delta_2,
+                vk_ic,
+                point_encoding.unwrap_or_default(),
+            )?,
+        ),
+        ExecuteMsg::ProofBinary {
+            difficuty_issuer,
+            circuit_id,
+            proof_a,
+            proof_b,
+            proof_c,
+            point_encoding,
+            public_signals,
+        } => execute_publish_proof(
+            deps,
+            env,
+            info,
+            None,
+            difficuty_issuer,
+            circuit_id,
+            binary_proof(
+                proof_a,
+                proof_b,
+                proof_c,
+                point_encoding.unwrap_or_default(),
+            ),
+            public_signals,
+        ),
+        ExecuteMsg::ProofBatch {
+            difficuty_issuer,
+            circuit_id,
+            proofs,
+        } => {
+            execute_publish_proof_batch(deps, env, info, None, difficuty_issuer, circuit_id, proofs)
+        }
+        ExecuteMsg::CommitProof {
+            difficuty_issuer,
+            circuit_id,
+            commitment,
+        } => execute_commit_proof(deps, env, info, difficuty_issuer, circuit_id, commitment),
+        ExecuteMsg::RevealProof {
+            difficuty_issuer,
+            circuit_id,
+            proof_a,
+            proof_b,
+            proof_c,
+            point_encoding,
+            public_signals,
+            salt,
+        } => execute_reveal_proof(
+            deps,
+            env,
+            info,
+            None,
+            difficuty_issuer,
+            circuit_id,
+            decode_proof(
+                proof_a,
+                proof_b,
+                proof_c,
+                point_encoding.unwrap_or_default(),
+            )?,
+            public_signals,
+            salt,
+        ),
+        ExecuteMsg::SetCircuitStatus { circuit_id, status } => {
+            execute_set_circuit_status(deps, info, circuit_id, status)
+        }
+        ExecuteMsg::ReclaimBounty { circuit_id } => {
+            execute_reclaim_bounty(deps, env, info, circuit_id)
+        }
+        ExecuteMsg::ClaimBounty {
+            difficuty_issuer,
+            circuit_id,
+        } => execute_claim_bounty(deps, env, difficuty_issuer, circuit_id),
+        ExecuteMsg::Withdraw { amount, recipient } => {
+            execute_withdraw(deps, env, info, amount, recipient)
+        }
+        ExecuteMsg::WithdrawCw20 {
+            token,
+            amount,
+            recipient,
+        } => execute_withdraw_cw20(deps, info, token, amount, recipient),
+        ExecuteMsg::Receive(wrapper) => execute_receive(deps, env, info, wrapper),
+    }
+}
+
+/// the hook of a cw20 `Send`, the token contract is the caller and the
+/// embedded message is executed for the original sender
+pub fn execute_receive(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    wrapper: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let payment = Cw20CoinVerified {
+        address: info.sender,
+        amount: wrapper.amount,
+    };
+    let info = MessageInfo {
+        sender: deps.api.addr_validate(&wrapper.sender)?,
+        funds: vec![],
+    };
+
+    match from_binary(&wrapper.msg)? {
+        ExecuteMsg::Zkeys {
+            circuit_id,
+            metadata,
+            public_signals,
+            public_signal_mode,
+            bind_sender,
+            nullifier_index,
+            bounty,
+            bounty_deadline,
+            vk_alpha1,
+            vk_beta_2,
+            vk_gamma_2,
+            vk_delta_2,
+            vk_ic,
+            point_encoding,
+            snarkjs_vkey,
+        } => execute_set_zkeys(
+            deps,
+            env,
+            info,
+            Some(payment),
+            circuit_id,
+            metadata,
+            public_signals,
+            public_signal_mode,
+            bind_sender,
+            nullifier_index,
+            bounty,
+            bounty_deadline,
+            submitted_vkey(
+                vk_alpha1,
+                vk_beta_2,
+                vk_gamma_2,
+                vk_delta_2,
+                vk_ic,
+                point_encoding,
+                snarkjs_vkey,
+            )?,
+        ),
+        ExecuteMsg::Proof {
+            difficuty_issuer,
+            circuit_id,
+            proof_a,
+            proof_b,
+            proof_c,
+            point_encoding,
+            snarkjs_proof,
+            public_signals,
+        } => execute_publish_proof(
+            deps,
+            env,
+            info,
+            Some(payment),
+            difficuty_issuer,
+            circuit_id,
+            submitted_proof(proof_a, proof_b, proof_c, point_encoding, snarkjs_proof)?,
+            public_signals,
+        ),
+        ExecuteMsg::ZkeysBinary {
+            circuit_id,
+            metadata,
+            public_signals,
+            public_signal_mode,
+            bind_sender,
+            nullifier_index,
+            bounty,
+            bounty_deadline,
             vk_alpha1,
             vk_beta_2,
             vk_gamma_2,
             vk_delta_2,
-            vk_ic0,
-            vk_ic1
-        } 
-            => execute_set_zkeys(deps, env, info, public_signal, vk_alpha1, vk_beta_2, vk_gamma_2, vk_delta_2, vk_ic0, vk_ic1),
-        ExecuteMsg::Proof { 
+            vk_ic,
+            point_encoding,
+        } => execute_set_zkeys(
+            deps,
+            env,
+            info,
+            Some(payment),
+            circuit_id,
+            metadata,
+            public_signals,
+            public_signal_mode,
+            bind_sender,
+            nullifier_index,
+            bounty,
+            bounty_deadline,
+            binary_vkey(
+                vk_alpha1,
+                vk_beta_2,
+                vk_gamma_2,
+                vk_delta_2,
+                vk_ic,
+                point_encoding.unwrap_or_default(),
+            )?,
+        ),
+        ExecuteMsg::ProofBinary {
             difficuty_issuer,
+            circuit_id,
             proof_a,
             proof_b,
             proof_c,
-        } 
-            => execute_publish_proof(deps, env, info, difficuty_issuer, proof_a, proof_b, proof_c),
+            point_encoding,
+            public_signals,
+        } => execute_publish_proof(
+            deps,
+            env,
+            info,
+            Some(payment),
+            difficuty_issuer,
+            circuit_id,
+            binary_proof(
+                proof_a,
+                proof_b,
+                proof_c,
+                point_encoding.unwrap_or_default(),
+            ),
+            public_signals,
+        ),
+        ExecuteMsg::ProofBatch {
+            difficuty_issuer,
+            circuit_id,
+            proofs,
+        } => execute_publish_proof_batch(
+            deps,
+            env,
+            info,
+            Some(payment),
+            difficuty_issuer,
+            circuit_id,
+            proofs,
+        ),
+        ExecuteMsg::RevealProof {
+            difficuty_issuer,
+            circuit_id,
+            proof_a,
+            proof_b,
+            proof_c,
+            point_encoding,
+            public_signals,
+            salt,
+        } => execute_reveal_proof(
+            deps,
+            env,
+            info,
+            Some(payment),
+            difficuty_issuer,
+            circuit_id,
+            decode_proof(
+                proof_a,
+                proof_b,
+                proof_c,
+                point_encoding.unwrap_or_default(),
+            )?,
+            public_signals,
+            salt,
+        ),
+        _ => Err(ContractError::InvalidCw20Hook {}),
     }
 }
 
 pub fn execute_set_zkeys(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
-    public_signal: String,
-    vk_alpha1: String,
-    vk_beta_2: String,
-    vk_gamma_2: String,
-    vk_delta_2: String,
-    vk_ic0: String,
-    vk_ic1: String,
+    cw20_payment: Option<Cw20CoinVerified>,
+    circuit_id: Option<String>,
+    metadata: Option<CircuitMetadata>,
+    public_signals: Vec<String>,
+    public_signal_mode: Option<PublicSignalMode>,
+    bind_sender: Option<bool>,
+    nullifier_index: Option<usize>,
+    bounty: Option<Coin>,
+    bounty_deadline: Option<u64>,
+    vkeys: VkeyStr,
 ) -> Result<Response, ContractError> {
     let config = CONFIG.load(deps.storage)?;
-    assert_sent_sufficient_coin(&info.funds, config.zkeys_price)?;
+    let circuit_id = circuit_or_default(circuit_id);
+    if circuit_id.is_empty() || circuit_id.len() > MAX_CIRCUIT_ID_LENGTH {
+        return Err(ContractError::InvalidCircuitId {});
+    }
+    if let Some(registered) = ZKEYS.may_load(deps.storage, (&info.sender, &circuit_id))? {
+        // a key is only replaced while no listed proof or open bounty depends on it
+        let proven = PROVERLIST
+            .prefix((&info.sender, &circuit_id))
+            .keys(deps.storage, None, None, Order::Ascending)
+            .next()
+            .is_some();
+        if proven
+            || registered.status == CircuitStatus::Retired
+            || BOUNTIES.has(deps.storage, (&info.sender, &circuit_id))
+        {
+            return Err(ContractError::CircuitAlreadyRegistered { circuit_id });
+        }
+    }
+    let metadata = metadata.unwrap_or_default();
+    validate_metadata(&metadata)?;
+    // the reward is held apart from the registration fee
+    let funds = match &bounty {
+        Some(reward) => deduct_coin(&info.funds, reward)?,
+        None => info.funds.clone(),
+    };
+    let (refund, fee) = charge_price(
+        &info.sender,
+        &funds,
+        cw20_payment,
+        config.zkeys_price.clone(),
+        config.zkeys_cw20_price.clone(),
+        &config,
+    )?;
+    let bounty = match bounty {
+        Some(reward) => {
+            let deadline = bounty_deadline.ok_or(ContractError::InvalidBounty {})?;
+            if reward.amount.is_zero() || deadline <= env.block.height {
+                return Err(ContractError::InvalidBounty {});
+            }
+            if BOUNTIES.has(deps.storage, (&info.sender, &circuit_id)) {
+                return Err(ContractError::BountyAlreadyOpen {});
+            }
+            Some(Bounty {
+                reward,
+                deadline,
+                winner: None,
+            })
+        }
+        None => None,
+    };
+
+    let public_signal_mode = public_signal_mode.unwrap_or_default();
+    if public_signal_mode == PublicSignalMode::Prover && !public_signals.is_empty() {
+        // provers bring their own public signals, the issuer must not fix any
+        return Err(ContractError::ErrorPublicSignal {});
+    }
+    let bind_sender = bind_sender.unwrap_or(false);
+    if public_signal_mode == PublicSignalMode::Issuer {
+        check_issuer_public_signals(&vkeys, &public_signals, bind_sender)?;
+    }
+    if nullifier_index.is_some_and(|index| index + 1 >= vkeys.ic.len()) {
+        // ic[0] is the constant term, not a public input
+        return Err(ContractError::InvalidNullifierIndex {});
+    }
     // address
     // let key = info.sender.as_str().as_bytes();
-    let vkeys = VkeyStr {
-        alpha_1: hex::decode(vk_alpha1).unwrap(),
-        beta_2: hex::decode(vk_beta_2).unwrap(),
-        gamma_2: hex::decode(vk_gamma_2).unwrap(),
-        delta_2: hex::decode(vk_delta_2).unwrap(),
-        ic0: hex::decode(vk_ic0).unwrap(),
-        ic1: hex::decode(vk_ic1).unwrap(),
-    };
+    let prepared = prepare_vkey(&vkeys)?;
     let zkeys = ZkeysStr {
         vkeys,
-        public_signal
+        prepared: Some(prepared),
+        public_signals,
+        public_signal_mode,
+        bind_sender,
+        nullifier_index,
+        metadata,
+        status: CircuitStatus::Active,
     };
 
-    ZKEYS.save(deps.storage, &info.sender, &zkeys)?;
+    ZKEYS.save(deps.storage, (&info.sender, &circuit_id), &zkeys)?;
+    let mut response = Response::new().add_messages(refund).add_event(
+        Event::new("zkeys_registered")
+            .add_attribute("issuer", info.sender.as_str())
+            .add_attribute("circuit_id", &circuit_id)
+            .add_attribute("vkey_hash", hex::encode(vkey_hash(&zkeys.vkeys)))
+            .add_attribute("proof_system", PROOF_SYSTEM)
+            .add_attribute("fee", fee),
+    );
+    if let Some(bounty) = bounty {
+        escrow_bounty(deps.storage, &info.sender, &circuit_id, &bounty)?;
+        response = response.add_event(
+            Event::new("bounty_opened")
+                .add_attribute("issuer", info.sender.as_str())
+                .add_attribute("circuit_id", circuit_id)
+                .add_attribute("reward", bounty.reward.to_string())
+                .add_attribute("deadline", bounty.deadline.to_string()),
+        );
+    }
 
-    Ok(Response::default())
+    Ok(response)
 }
 
 pub fn execute_publish_proof(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
+    info: MessageInfo,
+    cw20_payment: Option<Cw20CoinVerified>,
+    difficuty_issuer: String,
+    circuit_id: Option<String>,
+    proof_str: ProofStr,
+    public_signals: Option<Vec<String>>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let (refund, fee) = charge_price(
+        &info.sender,
+        &info.funds,
+        cw20_payment,
+        config.proof_price.clone(),
+        config.proof_cw20_price.clone(),
+        &config,
+    )?;
+
+    // a direct submission counts as committed in the block it lands in
+    Ok(verify_and_record(
+        deps,
+        &env,
+        &info.sender,
+        difficuty_issuer,
+        circuit_or_default(circuit_id),
+        proof_str,
+        public_signals,
+        env.block.height,
+        fee,
+    )?
+    .add_messages(refund))
+}
+
+pub fn execute_publish_proof_batch(
+    deps: DepsMut,
+    env: Env,
     info: MessageInfo,
+    cw20_payment: Option<Cw20CoinVerified>,
     difficuty_issuer: String,
+    circuit_id: Option<String>,
+    proofs: Vec<BatchProof>,
+) -> Result<Response, ContractError> {
+    if proofs.is_empty() {
+        return Err(ContractError::EmptyProofBatch {});
+    }
+    let config = CONFIG.load(deps.storage)?;
+    let (price, cw20_price) = batch_price(&config, proofs.len())?;
+    let (refund, fee) = charge_price(
+        &info.sender,
+        &info.funds,
+        cw20_payment,
+        price,
+        cw20_price,
+        &config,
+    )?;
+
+    let circuit_id = circuit_or_default(circuit_id);
+    let (issuer, zkeys) = load_verifying_zkeys(deps.as_ref(), difficuty_issuer, &circuit_id)?;
+    let prepared = zkeys
+        .prepared
+        .as_ref()
+        .ok_or(ContractError::NoVerificationKey {})?;
+
+    let mut provers = Vec::with_capacity(proofs.len());
+    let mut batch = Vec::with_capacity(proofs.len());
+    for proof in proofs {
+        let prover = match proof.prover {
+            Some(prover) => deps.api.addr_validate(&prover)?,
+            None => info.sender.clone(),
+        };
+        if prover != info.sender && !zkeys.bind_sender {
+            // only a proof bound to its prover can't be taken over by the relayer naming them
+            return Err(ContractError::ProverNotSender {});
+        }
+        let proof_str = decode_proof(
+            proof.proof_a,
+            proof.proof_b,
+            proof.proof_c,
+            proof.point_encoding.unwrap_or_default(),
+        )?;
+        let public_signals = resolve_public_signals(&zkeys, Some(&prover), proof.public_signals)?;
+        provers.push(prover);
+        batch.push((proof_str, public_signals));
+    }
+
+    let (is_passed, public_inputs) = check_proof_batch(prepared, &batch)?;
+    if !is_passed {
+        // the combined pairing can't tell which proofs failed, check them one by one
+        let mut indexes = vec![];
+        for (index, (proof_str, public_signals)) in batch.into_iter().enumerate() {
+            if !check_proof(prepared, proof_str, &public_signals)?.0 {
+                indexes.push(index);
+            }
+        }
+        return Err(ContractError::InvalidBatchProofs { indexes });
+    }
+
+    let count = batch.len();
+    let mut response = Response::new();
+    for ((prover, (proof_str, public_signals)), public_inputs) in
+        provers.into_iter().zip(batch).zip(public_inputs)
+    {
+        // the fee is paid once for the whole batch
+        let recorded = record_proof(
+            deps.storage,
+            &env,
+            &issuer,
+            &circuit_id,
+            &zkeys,
+            VerifiedProof {
+                prover,
+                proof_str,
+                public_signals,
+                public_inputs,
+                committed_at: env.block.height,
+            },
+            "",
+        )?;
+        response = response
+            .add_submessages(recorded.messages)
+            .add_events(recorded.events);
+    }
+
+    Ok(response
+        .add_event(
+            Event::new("proof_batch_verified")
+                .add_attribute("issuer", issuer.as_str())
+                .add_attribute("circuit_id", circuit_id)
+                .add_attribute("proofs", count.to_string())
+                .add_attribute("fee", fee),
+        )
+        .add_messages(refund))
+}
+
+pub fn execute_commit_proof(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    difficuty_issuer: String,
+    circuit_id: Option<String>,
+    commitment: String,
+) -> Result<Response, ContractError> {
+    let issuer = deps.api.addr_validate(&difficuty_issuer)?;
+    let circuit_id = circuit_or_default(circuit_id);
+
+    match ZKEYS.may_load(deps.storage, (&issuer, &circuit_id))? {
+        None => return Err(ContractError::NonPublishDifficulty { difficuty_issuer }),
+        Some(zkeys) if zkeys.status == CircuitStatus::Retired => {
+            return Err(ContractError::CircuitRetired { circuit_id })
+        }
+        Some(_) => {}
+    }
+
+    let hash = hex::decode(commitment).map_err(|_| ContractError::InvalidCommitment {})?;
+    if hash.len() != 32 {
+        return Err(ContractError::InvalidCommitment {});
+    }
+
+    // committing again replaces the previous commitment of this prover
+    let commitment = Commitment {
+        hash,
+        height: env.block.height,
+    };
+    COMMITMENTS.save(
+        deps.storage,
+        (&issuer, &circuit_id, &info.sender),
+        &commitment,
+    )?;
+
+    Ok(Response::new().add_event(
+        Event::new("proof_committed")
+            .add_attribute("issuer", issuer.as_str())
+            .add_attribute("circuit_id", circuit_id)
+            .add_attribute("prover", info.sender.as_str()),
+    ))
+}
+
+pub fn execute_reveal_proof(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_payment: Option<Cw20CoinVerified>,
+    difficuty_issuer: String,
+    circuit_id: Option<String>,
+    proof_str: ProofStr,
+    public_signals: Option<Vec<String>>,
+    salt: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let (refund, fee) = charge_price(
+        &info.sender,
+        &info.funds,
+        cw20_payment,
+        config.proof_price.clone(),
+        config.proof_cw20_price.clone(),
+        &config,
+    )?;
+
+    let issuer = deps.api.addr_validate(&difficuty_issuer)?;
+    let circuit_id = circuit_or_default(circuit_id);
+    let commitment = COMMITMENTS
+        .may_load(deps.storage, (&issuer, &circuit_id, &info.sender))?
+        .ok_or(ContractError::NoCommitment {})?;
+
+    if env.block.height <= commitment.height {
+        // the proof would be public in the same block as the commitment
+        return Err(ContractError::RevealTooEarly {});
+    }
+    if env.block.height > commitment.height.saturating_add(config.commitment_expiry) {
+        return Err(ContractError::CommitmentExpired {});
+    }
+
+    if proof_commitment(&proof_str, &info.sender, &salt) != commitment.hash {
+        return Err(ContractError::CommitmentMismatch {});
+    }
+    COMMITMENTS.remove(deps.storage, (&issuer, &circuit_id, &info.sender));
+
+    Ok(verify_and_record(
+        deps,
+        &env,
+        &info.sender,
+        difficuty_issuer,
+        circuit_id,
+        proof_str,
+        public_signals,
+        commitment.height,
+        fee,
+    )?
+    .add_messages(refund))
+}
+
+pub fn execute_set_circuit_status(
+    deps: DepsMut,
+    info: MessageInfo,
+    circuit_id: Option<String>,
+    status: CircuitStatus,
+) -> Result<Response, ContractError> {
+    let circuit_id = circuit_or_default(circuit_id);
+    let mut zkeys = ZKEYS
+        .may_load(deps.storage, (&info.sender, &circuit_id))?
+        .ok_or_else(|| ContractError::NonPublishDifficulty {
+            difficuty_issuer: info.sender.to_string(),
+        })?;
+    if zkeys.status == CircuitStatus::Retired && status != CircuitStatus::Retired {
+        // provers may have dropped the circuit for good
+        return Err(ContractError::CircuitRetired { circuit_id });
+    }
+
+    zkeys.status = status;
+    ZKEYS.save(deps.storage, (&info.sender, &circuit_id), &zkeys)?;
+    Ok(Response::new().add_event(
+        Event::new("circuit_status_changed")
+            .add_attribute("issuer", info.sender.as_str())
+            .add_attribute("circuit_id", circuit_id)
+            .add_attribute("status", status_attribute(&zkeys.status)),
+    ))
+}
+
+pub fn execute_reclaim_bounty(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    circuit_id: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let circuit_id = circuit_or_default(circuit_id);
+    let bounty = BOUNTIES
+        .may_load(deps.storage, (&info.sender, &circuit_id))?
+        .ok_or(ContractError::NoBounty {})?;
+    if bounty.winner.is_some() {
+        return Err(ContractError::BountyAlreadyWon {});
+    }
+    // a commitment made by the deadline can still be revealed to win the bounty
+    let reclaimable_at = bounty
+        .deadline
+        .saturating_add(config.commitment_expiry)
+        .saturating_add(1);
+    if env.block.height < reclaimable_at {
+        return Err(ContractError::BountyNotExpired { reclaimable_at });
+    }
+
+    release_bounty(deps.storage, &info.sender, &circuit_id, &bounty)?;
+    Ok(Response::new()
+        .add_event(
+            Event::new("bounty_reclaimed")
+                .add_attribute("issuer", info.sender.as_str())
+                .add_attribute("circuit_id", circuit_id)
+                .add_attribute("reward", bounty.reward.to_string()),
+        )
+        .add_message(BankMsg::Send {
+            to_address: info.sender.into_string(),
+            amount: vec![bounty.reward],
+        }))
+}
+
+pub fn execute_claim_bounty(
+    deps: DepsMut,
+    env: Env,
+    difficuty_issuer: String,
+    circuit_id: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let issuer = deps.api.addr_validate(&difficuty_issuer)?;
+    let circuit_id = circuit_or_default(circuit_id);
+    let bounty = BOUNTIES
+        .may_load(deps.storage, (&issuer, &circuit_id))?
+        .ok_or(ContractError::NoBounty {})?;
+    let winner = bounty
+        .winner
+        .clone()
+        .ok_or(ContractError::BountyNotWon {})?;
+    let claimable_at = winner.committed_at.saturating_add(config.commitment_expiry);
+    if env.block.height < claimable_at {
+        // a commitment made before the winning one can still be revealed
+        return Err(ContractError::BountyNotClaimable { claimable_at });
+    }
+
+    release_bounty(deps.storage, &issuer, &circuit_id, &bounty)?;
+    Ok(Response::new()
+        .add_event(
+            Event::new("bounty_paid")
+                .add_attribute("issuer", issuer.as_str())
+                .add_attribute("circuit_id", circuit_id)
+                .add_attribute("prover", winner.prover.as_str())
+                .add_attribute("reward", bounty.reward.to_string()),
+        )
+        .add_message(BankMsg::Send {
+            to_address: winner.prover.into_string(),
+            amount: vec![bounty.reward],
+        }))
+}
+
+pub fn execute_withdraw(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Vec<Coin>,
+    recipient: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if amount.is_empty() || amount.iter().any(|coin| coin.amount.is_zero()) {
+        return Err(ContractError::EmptyWithdrawal {});
+    }
+
+    // open bounties are not fees
+    let fees = fee_balance(deps.as_ref(), &env)?;
+    for coin in amount.iter() {
+        let available = fees
+            .iter()
+            .find(|fee| fee.denom == coin.denom)
+            .map_or(Uint128::zero(), |fee| fee.amount);
+        if coin.amount > available {
+            return Err(ContractError::InsufficientFees {
+                denom: coin.denom.clone(),
+            });
+        }
+    }
+
+    let recipient = deps.api.addr_validate(&recipient)?;
+    Ok(Response::new()
+        .add_event(
+            Event::new("fees_withdrawn")
+                .add_attribute("recipient", recipient.as_str())
+                .add_attribute("amount", coins_attribute(&amount)),
+        )
+        .add_message(BankMsg::Send {
+            to_address: recipient.into_string(),
+            amount,
+        }))
+}
+
+pub fn execute_withdraw_cw20(
+    deps: DepsMut,
+    info: MessageInfo,
+    token: String,
+    amount: Uint128,
+    recipient: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if amount.is_zero() {
+        return Err(ContractError::EmptyWithdrawal {});
+    }
+
+    // bounties are native coins, every cw20 token held is a fee
+    let token = deps.api.addr_validate(&token)?;
+    let recipient = deps.api.addr_validate(&recipient)?;
+    Ok(Response::new()
+        .add_event(
+            Event::new("fees_withdrawn")
+                .add_attribute("recipient", recipient.as_str())
+                .add_attribute("amount", format!("{}{}", amount, token)),
+        )
+        .add_message(WasmMsg::Execute {
+            contract_addr: token.into_string(),
+            msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: recipient.into_string(),
+                amount,
+            })?,
+            funds: vec![],
+        }))
+}
+
+/// charge a price from the native funds, or from the cw20 tokens of a `Receive`,
+/// and return the refund messages with the fee that was kept
+fn charge_price(
+    sender: &Addr,
+    funds: &[Coin],
+    cw20_payment: Option<Cw20CoinVerified>,
+    price: Option<Coin>,
+    cw20_price: Option<Cw20CoinVerified>,
+    config: &Config,
+) -> Result<(Vec<CosmosMsg>, String), ContractError> {
+    let exact = config.fee_mode == FeeMode::Exact;
+    match cw20_payment {
+        Some(payment) => {
+            let refund = charge_cw20_fee(sender, &payment, cw20_price.clone(), &config.fee_mode)?;
+            let paid = match cw20_price {
+                Some(price) if exact => price,
+                _ => payment,
+            };
+            Ok((
+                refund.into_iter().map(CosmosMsg::from).collect(),
+                format!("{}{}", paid.amount, paid.address),
+            ))
+        }
+        None => {
+            if price.is_none() && cw20_price.is_some() {
+                // only payable in the cw20 token
+                return Err(ContractError::InsufficientFundsSend {});
+            }
+            let refund = charge_fee(
+                sender,
+                funds,
+                price.clone(),
+                &config.fee_mode,
+                &config.unrelated_denoms,
+            )?;
+            let paid = if exact {
+                price.into_iter().collect()
+            } else {
+                funds.to_vec()
+            };
+            Ok((
+                refund.into_iter().map(CosmosMsg::from).collect(),
+                coins_attribute(&paid),
+            ))
+        }
+    }
+}
+
+/// the proof prices of the config for `count` proofs
+fn batch_price(
+    config: &Config,
+    count: usize,
+) -> StdResult<(Option<Coin>, Option<Cw20CoinVerified>)> {
+    let count = Uint128::from(count as u128);
+    let price = match &config.proof_price {
+        Some(price) => Some(Coin {
+            denom: price.denom.clone(),
+            amount: price.amount.checked_mul(count)?,
+        }),
+        None => None,
+    };
+    let cw20_price = match &config.proof_cw20_price {
+        Some(price) => Some(Cw20CoinVerified {
+            address: price.address.clone(),
+            amount: price.amount.checked_mul(count)?,
+        }),
+        None => None,
+    };
+    Ok((price, cw20_price))
+}
+
+/// the circuit a message is about, `DEFAULT_CIRCUIT` when it doesn't name one
+fn circuit_or_default(circuit_id: Option<String>) -> String {
+    circuit_id.unwrap_or_else(|| DEFAULT_CIRCUIT.to_string())
+}
+
+/// the limits on what an issuer can tell about a circuit, the artifact hash is optional
+fn validate_metadata(metadata: &CircuitMetadata) -> Result<(), ContractError> {
+    let invalid = |field: &str| ContractError::InvalidCircuitMetadata {
+        field: field.to_string(),
+    };
+    if metadata.name.len() > MAX_CIRCUIT_NAME_LENGTH {
+        return Err(invalid("name"));
+    }
+    if metadata.description.len() > MAX_CIRCUIT_DESCRIPTION_LENGTH {
+        return Err(invalid("description"));
+    }
+    if metadata.source_uri.len() > MAX_CIRCUIT_SOURCE_URI_LENGTH {
+        return Err(invalid("source_uri"));
+    }
+    if !metadata.artifact_hash.is_empty()
+        && !matches!(hex::decode(&metadata.artifact_hash), Ok(hash) if hash.len() == 32)
+    {
+        return Err(invalid("artifact_hash"));
+    }
+    Ok(())
+}
+
+fn status_attribute(status: &CircuitStatus) -> &'static str {
+    match status {
+        CircuitStatus::Active => "active",
+        CircuitStatus::Deprecated => "deprecated",
+        CircuitStatus::Retired => "retired",
+    }
+}
+
+/// coins as an event attribute value, `<amount><denom>` separated by commas
+fn coins_attribute(coins: &[Coin]) -> String {
+    coins
+        .iter()
+        .map(Coin::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// the public signals a proof for `zkeys` is checked against,
+/// `prover` is only needed by keys binding the sender
+fn resolve_public_signals(
+    zkeys: &ZkeysStr,
+    prover: Option<&Addr>,
+    public_signals: Option<Vec<String>>,
+) -> Result<Vec<String>, ContractError> {
+    let mut resolved = match zkeys.public_signal_mode {
+        PublicSignalMode::Issuer => {
+            if public_signals.is_some() {
+                // the issuer already fixed the statement to prove
+                return Err(ContractError::ErrorPublicSignal {});
+            }
+            zkeys.public_signals.clone()
+        }
+        PublicSignalMode::Prover => public_signals.ok_or(ContractError::NoPublicSignal {})?,
+    };
+    if zkeys.bind_sender {
+        // a proof copied by another address won't verify against its sender signal
+        resolved.push(sender_public_signal(
+            prover.ok_or(ContractError::NoProver {})?,
+        ));
+    }
+    Ok(resolved)
+}
+
+/// run the verifier against a prepared key, the parsed public inputs are returned for the nullifier
+pub fn check_proof(
+    prepared: &PreparedVkey,
+    proof_str: ProofStr,
+    public_signals: &[String],
+) -> Result<(bool, Vec<Scalar>), ContractError> {
+    let public_inputs = parse_public_inputs(prepared, public_signals)?;
+
+    // verify the proof
+    let pof = parse_proof::<Bls12>(proof_str)?;
+    let pvk = parse_prepared_vkey(prepared)?;
+    let acc = input_commitment(&pvk.ic, &public_inputs);
+    // e(A, B) e(acc, -gamma) e(C, -delta) = e(alpha, beta), the right side was paired at registration
+    let lhs = multi_miller_loop(&[
+        (&pof.a, &G2Prepared::from(pof.b)),
+        (&G1Affine::from(acc), &G2Prepared::from(pvk.neg_gamma_g2)),
+        (&pof.c, &G2Prepared::from(pvk.neg_delta_g2)),
+    ])
+    .final_exponentiation();
+    let is_passed = lhs == pvk.alpha_beta;
+
+    Ok((is_passed, public_inputs))
+}
+
+/// the public signals an issuer fixes fill every public input but the sender signal a bound
+/// key appends, and are field elements, so a key can't be registered that no proof satisfies
+fn check_issuer_public_signals(
+    vkeys: &VkeyStr,
+    public_signals: &[String],
+    bind_sender: bool,
+) -> Result<(), ContractError> {
+    let expected = vkeys
+        .ic
+        .len()
+        .saturating_sub(1)
+        .saturating_sub(bind_sender as usize);
+    if public_signals.len() != expected {
+        return Err(ContractError::PublicSignalCountMismatch {
+            expected,
+            actual: public_signals.len(),
+        });
+    }
+    match public_signals
+        .iter()
+        .position(|x| Scalar::from_str_vartime(x).is_none())
+    {
+        Some(index) => Err(ContractError::ParsePulbicSignalError { index }),
+        None => Ok(()),
+    }
+}
+
+/// the public signals as field elements, one for every IC point except the constant term ic[0]
+fn parse_public_inputs(
+    prepared: &PreparedVkey,
+    public_signals: &[String],
+) -> Result<Vec<Scalar>, ContractError> {
+    let expected = prepared.ic.len().saturating_sub(1);
+    if public_signals.len() != expected {
+        return Err(ContractError::PublicSignalCountMismatch {
+            expected,
+            actual: public_signals.len(),
+        });
+    }
+    public_signals
+        .iter()
+        .enumerate()
+        .map(|(index, x)| {
+            Fr::from_str_vartime(x).ok_or(ContractError::ParsePulbicSignalError { index })
+        })
+        .collect()
+}
+
+/// ic[0] + sum(input_i * ic[i]), the point paired with -gamma
+fn input_commitment(ic: &[G1Affine], public_inputs: &[Scalar]) -> G1Projective {
+    public_inputs
+        .iter()
+        .zip(ic.iter().skip(1))
+        .fold(G1Projective::from(ic[0]), |acc, (input, ic_i)| {
+            acc + ic_i * input
+        })
+}
+
+/// check proofs of one key with a single multi-pairing. Every proof is weighted by a
+/// scalar hashed from the whole batch, so invalid proofs can't be crafted to cancel out
+/// and the batch passes only if each proof would. The public inputs are returned per proof
+pub fn check_proof_batch(
+    prepared: &PreparedVkey,
+    proofs: &[(ProofStr, Vec<String>)],
+) -> Result<(bool, Vec<Vec<Scalar>>), ContractError> {
+    let pvk = parse_prepared_vkey(prepared)?;
+    let mut hasher = Sha256::new();
+    for bytes in [
+        &prepared.alpha_beta,
+        &prepared.neg_alpha_1,
+        &prepared.beta_2,
+        &prepared.neg_gamma_2,
+        &prepared.neg_delta_2,
+    ]
+    .into_iter()
+    .chain(&prepared.ic)
+    {
+        hasher.update(bytes.as_slice());
+    }
+    let mut parsed = Vec::with_capacity(proofs.len());
+    for (proof_str, public_signals) in proofs {
+        let public_inputs = parse_public_inputs(prepared, public_signals)?;
+        let pof = parse_proof::<Bls12>(proof_str.clone())?;
+        hasher.update(pof.a.to_uncompressed());
+        hasher.update(pof.b.to_uncompressed());
+        hasher.update(pof.c.to_uncompressed());
+        for input in &public_inputs {
+            hasher.update(input.to_repr());
+        }
+        parsed.push((pof, public_inputs));
+    }
+    let batch_hash = hasher.finalize();
+
+    // prod e(r_i A_i, B_i) e(sum r_i acc_i, -gamma) e(sum r_i C_i, -delta) e(-(sum r_i) alpha, beta) = 1
+    let mut pairs = Vec::with_capacity(parsed.len() + 3);
+    let mut acc = G1Projective::identity();
+    let mut c = G1Projective::identity();
+    let mut weights = Scalar::zero();
+    for (index, (pof, public_inputs)) in parsed.iter().enumerate() {
+        let r = batch_weight(&batch_hash, index);
+        pairs.push((G1Affine::from(pof.a * r), G2Prepared::from(pof.b)));
+        acc += input_commitment(&pvk.ic, public_inputs) * r;
+        c += pof.c * r;
+        weights += r;
+    }
+    pairs.push((G1Affine::from(acc), G2Prepared::from(pvk.neg_gamma_g2)));
+    pairs.push((G1Affine::from(c), G2Prepared::from(pvk.neg_delta_g2)));
+    pairs.push((
+        G1Affine::from(pvk.neg_alpha_g1 * weights),
+        G2Prepared::from(pvk.beta_g2),
+    ));
+    let terms = pairs.iter().map(|(g1, g2)| (g1, g2)).collect::<Vec<_>>();
+    let is_passed = multi_miller_loop(&terms).final_exponentiation() == Gt::identity();
+
+    Ok((
+        is_passed,
+        parsed
+            .into_iter()
+            .map(|(_, public_inputs)| public_inputs)
+            .collect(),
+    ))
+}
+
+/// the 128 bit weight of the proof at `index`, a forger would have to guess it
+fn batch_weight(batch_hash: &[u8], index: usize) -> Scalar {
+    let digest = Sha256::new()
+        .chain_update(batch_hash)
+        .chain_update((index as u64).to_be_bytes())
+        .finalize();
+    let mut low = [0u8; 8];
+    let mut high = [0u8; 8];
+    low.copy_from_slice(&digest[..8]);
+    high.copy_from_slice(&digest[8..16]);
+    Scalar::from_raw([u64::from_le_bytes(low), u64::from_le_bytes(high), 0, 0])
+}
+
+/// the hex points of a key in `encoding`, decompressed the way it is stored
+pub fn decode_vkey(
+    vk_alpha1: String,
+    vk_beta_2: String,
+    vk_gamma_2: String,
+    vk_delta_2: String,
+    vk_ic: Vec<String>,
+    encoding: PointEncoding,
+) -> Result<VkeyStr, ContractError> {
+    let vkeys = VkeyStr {
+        alpha_1: decode_hex(vk_alpha1, "vk_alpha1")?,
+        beta_2: decode_hex(vk_beta_2, "vk_beta_2")?,
+        gamma_2: decode_hex(vk_gamma_2, "vk_gamma_2")?,
+        delta_2: decode_hex(vk_delta_2, "vk_delta_2")?,
+        ic: vk_ic
+            .into_iter()
+            .enumerate()
+            .map(|(i, x)| decode_hex(x, &format!("vk_ic[{}]", i)))
+            .collect::<Result<Vec<Vec<u8>>, ContractError>>()?,
+    };
+    uncompressed_vkey(vkeys, encoding)
+}
+
+/// the key of a `Zkeys` message, given either as hex points or as snarkjs json
+fn submitted_vkey(
+    vk_alpha1: String,
+    vk_beta_2: String,
+    vk_gamma_2: String,
+    vk_delta_2: String,
+    vk_ic: Vec<String>,
+    point_encoding: Option<PointEncoding>,
+    snarkjs_vkey: Option<SnarkjsVerifyingKey>,
+) -> Result<VkeyStr, ContractError> {
+    match snarkjs_vkey {
+        Some(vkey) => {
+            if point_encoding.is_some()
+                || !(vk_alpha1.is_empty()
+                    && vk_beta_2.is_empty()
+                    && vk_gamma_2.is_empty()
+                    && vk_delta_2.is_empty()
+                    && vk_ic.is_empty())
+            {
+                return Err(ContractError::AmbiguousEncoding {});
+            }
+            vkey.to_vkey_str()
+        }
+        None => decode_vkey(
+            vk_alpha1,
+            vk_beta_2,
+            vk_gamma_2,
+            vk_delta_2,
+            vk_ic,
+            point_encoding.unwrap_or_default(),
+        ),
+    }
+}
+
+/// the proof of a `Proof` message, given either as hex points or as snarkjs json
+fn submitted_proof(
     proof_a: String,
     proof_b: String,
     proof_c: String,
-) -> Result<Response, ContractError> {
-    let config = CONFIG.load(deps.storage)?;
-    assert_sent_sufficient_coin(&info.funds, config.proof_price)?;
+    point_encoding: Option<PointEncoding>,
+    snarkjs_proof: Option<SnarkjsProof>,
+) -> Result<ProofStr, ContractError> {
+    match snarkjs_proof {
+        Some(proof) => {
+            if point_encoding.is_some()
+                || !(proof_a.is_empty() && proof_b.is_empty() && proof_c.is_empty())
+            {
+                return Err(ContractError::AmbiguousEncoding {});
+            }
+            proof.to_proof_str()
+        }
+        None => decode_proof(
+            proof_a,
+            proof_b,
+            proof_c,
+            point_encoding.unwrap_or_default(),
+        ),
+    }
+}
+
+/// the points of a `ZkeysBinary` key in `encoding`, decompressed the way it is stored
+pub fn binary_vkey(
+    vk_alpha1: Binary,
+    vk_beta_2: Binary,
+    vk_gamma_2: Binary,
+    vk_delta_2: Binary,
+    vk_ic: Vec<Binary>,
+    encoding: PointEncoding,
+) -> Result<VkeyStr, ContractError> {
+    let vkeys = VkeyStr {
+        alpha_1: vk_alpha1.into(),
+        beta_2: vk_beta_2.into(),
+        gamma_2: vk_gamma_2.into(),
+        delta_2: vk_delta_2.into(),
+        ic: vk_ic.into_iter().map(Vec::from).collect(),
+    };
+    uncompressed_vkey(vkeys, encoding)
+}
+
+pub fn binary_proof(
+    proof_a: Binary,
+    proof_b: Binary,
+    proof_c: Binary,
+    encoding: PointEncoding,
+) -> ProofStr {
+    ProofStr {
+        pi_a: proof_a.into(),
+        pi_b: proof_b.into(),
+        pi_c: proof_c.into(),
+        encoding,
+    }
+}
 
+pub fn decode_proof(
+    proof_a: String,
+    proof_b: String,
+    proof_c: String,
+    encoding: PointEncoding,
+) -> Result<ProofStr, ContractError> {
+    Ok(ProofStr {
+        pi_a: decode_hex(proof_a, "proof_a")?,
+        pi_b: decode_hex(proof_b, "proof_b")?,
+        pi_c: decode_hex(proof_c, "proof_c")?,
+        encoding,
+    })
+}
+
+/// the bytes of a hex message field, a failure names the field
+fn decode_hex(value: String, field: &str) -> Result<Vec<u8>, ContractError> {
+    hex::decode(value).map_err(|_| ContractError::HexDecodingError {
+        field: field.to_string(),
+    })
+}
+
+/// the key of an issuer's circuit, as long as it still accepts proofs
+fn load_verifying_zkeys(
+    deps: Deps,
+    difficuty_issuer: String,
+    circuit_id: &str,
+) -> Result<(Addr, ZkeysStr), ContractError> {
     //  the issuer address is valid?
     let issuer = deps.api.addr_validate(&difficuty_issuer)?;
 
-    if !(ZKEYS.may_load(deps.storage, &issuer)?).is_some() {
+    let zkeys = match ZKEYS.may_load(deps.storage, (&issuer, circuit_id))? {
+        Some(zkeys) => zkeys,
         // this issuer didn't public diffuculty problem
-        return Err(ContractError::NonPublishDifficulty { difficuty_issuer })
+        None => return Err(ContractError::NonPublishDifficulty { difficuty_issuer }),
+    };
+    if zkeys.status == CircuitStatus::Retired {
+        return Err(ContractError::CircuitRetired {
+            circuit_id: circuit_id.to_string(),
+        });
     }
+    Ok((issuer, zkeys))
+}
 
-    let zkeys = ZKEYS.load(deps.storage, &issuer).unwrap();
-    let vkeys_str = zkeys.vkeys;
-    let public_inputs = zkeys.public_signal;
+/// verify the proof against the key of the issuer's circuit and credit the prover,
+/// unless its statement was committed to earlier by someone else
+fn verify_and_record(
+    deps: DepsMut,
+    env: &Env,
+    prover: &Addr,
+    difficuty_issuer: String,
+    circuit_id: String,
+    proof_str: ProofStr,
+    public_signals: Option<Vec<String>>,
+    committed_at: u64,
+    fee: String,
+) -> Result<Response, ContractError> {
+    let (issuer, zkeys) = load_verifying_zkeys(deps.as_ref(), difficuty_issuer, &circuit_id)?;
+    let public_signals = resolve_public_signals(&zkeys, Some(prover), public_signals)?;
+    let prepared = zkeys
+        .prepared
+        .as_ref()
+        .ok_or(ContractError::NoVerificationKey {})?;
+    let (is_passed, public_inputs) = check_proof(prepared, proof_str.clone(), &public_signals)?;
+    if !is_passed {
+        return Err(ContractError::InvalidProof {});
+    }
 
-    // verify the proof
-    let proof_str = ProofStr {
-        pi_a: hex::decode(proof_a).unwrap(),
-        pi_b: hex::decode(proof_b).unwrap(),
-        pi_c: hex::decode(proof_c).unwrap(),
-    };
+    record_proof(
+        deps.storage,
+        env,
+        &issuer,
+        &circuit_id,
+        &zkeys,
+        VerifiedProof {
+            prover: prover.clone(),
+            proof_str,
+            public_signals,
+            public_inputs,
+            committed_at,
+        },
+        &fee,
+    )
+}
 
-    let pof = parse_proof::<Bls12>(proof_str.clone())?;
-    let vkey = parse_vkey::<Bls12>(vkeys_str)?;
-    let pvk = prepare_verifying_key(&vkey);
-    let is_passed = verify_proof(&pvk, &pof, &[Fr::from_str_vartime(&public_inputs).unwrap()]).is_ok();
+/// a proof that passed the verifier, with what it is recorded under
+struct VerifiedProof {
+    prover: Addr,
+    proof_str: ProofStr,
+    public_signals: Vec<String>,
+    public_inputs: Vec<Scalar>,
+    committed_at: u64,
+}
 
-    if is_passed {
-        let proof_info = ProofInfo {
-            proof: proof_str,
-            is_valid: is_passed
-        };
-        // save the storage
-        PROVERINFO.save(deps.storage, &info.sender, &proof_info)?;
-        PROVERLIST.save(deps.storage, (&issuer, &info.sender), &proof_info)?;
+/// credit the prover of a verified proof, unless its statement was committed to earlier by someone else
+fn record_proof(
+    storage: &mut dyn Storage,
+    env: &Env,
+    issuer: &Addr,
+    circuit_id: &str,
+    zkeys: &ZkeysStr,
+    verified: VerifiedProof,
+    fee: &str,
+) -> Result<Response, ContractError> {
+    let VerifiedProof {
+        prover,
+        proof_str,
+        public_signals,
+        public_inputs,
+        committed_at,
+    } = verified;
+    let prover = &prover;
+    let key_hash = vkey_hash(&zkeys.vkeys);
+    let vkey_hash = hex::encode(&key_hash);
 
+    let mut response = Response::new();
+    // a re-randomized copy of a proof proves the same statement, so claims are kept per statement
+    // when the prover picks it or its nullifier can be spent once. The issuer's signals are the
+    // same statement for every prover, each of them is credited for proving it
+    let statement = statement_hash(
+        &key_hash,
+        &public_inputs.iter().map(field_key).collect::<Vec<_>>(),
+    );
+    let per_statement = zkeys.bind_sender
+        || zkeys.public_signal_mode == PublicSignalMode::Prover
+        || zkeys.nullifier_index.is_some();
+    let claim_key = if per_statement {
+        statement.clone()
     } else {
-        return Err(ContractError::InvalidProof {});
+        prover_claim(&statement, prover)
+    };
+    let claim = PROOF_CLAIMS.may_load(storage, (issuer, circuit_id, &claim_key))?;
+    // the earliest commitment takes the credit of a statement from a later submission
+    let takeover = claim
+        .as_ref()
+        .is_some_and(|claim| claim.prover != *prover && committed_at < claim.committed_at);
+    let nullifier = zkeys
+        .nullifier_index
+        .map(|index| field_key(&public_inputs[index]));
+    if let Some(nullifier) = &nullifier {
+        // a spent nullifier only passes on to the earlier commitment of the statement that spent it
+        if NULLIFIERS.has(storage, (issuer, circuit_id, nullifier)) && !takeover {
+            return Err(ContractError::NullifierAlreadySpent {});
+        }
+    }
+    let mut committed_at = committed_at;
+    if let Some(claim) = claim {
+        if claim.prover == *prover {
+            committed_at = committed_at.min(claim.committed_at);
+        } else if !takeover {
+            return Err(ContractError::ProofAlreadyClaimed {});
+        } else {
+            // the earliest commitment takes the credit from a copied submission
+            PROVERLIST.remove(storage, (issuer, circuit_id, &claim.prover));
+            SOLVED.remove(storage, (&claim.prover, issuer, circuit_id));
+            // the copied verification stays in their history, which is never rewritten
+        }
+    }
+    PROOF_CLAIMS.save(
+        storage,
+        (issuer, circuit_id, &claim_key),
+        &ProofClaim {
+            prover: prover.clone(),
+            committed_at,
+        },
+    )?;
+    if let Some(nullifier) = &nullifier {
+        NULLIFIERS.save(storage, (issuer, circuit_id, nullifier), &statement)?;
     }
 
-    Ok(Response::default())
+    let proof_info = ProofInfo {
+        proof: proof_str,
+        public_signals,
+        is_valid: true,
+    };
+    // save the storage
+    PROVERLIST.save(storage, (issuer, circuit_id, prover), &proof_info)?;
+    SOLVED.save(storage, (prover, issuer, circuit_id), &Empty {})?;
+    let sequence = PROVER_SEQUENCE
+        .may_load(storage, prover)?
+        .unwrap_or_default();
+    PROVER_HISTORY.save(
+        storage,
+        (prover, sequence),
+        &Verification {
+            issuer: issuer.clone(),
+            circuit_id: circuit_id.to_string(),
+            vkey_hash: vkey_hash.clone(),
+            height: env.block.height,
+        },
+    )?;
+    PROVER_SEQUENCE.save(storage, prover, &(sequence + 1))?;
+
+    response = response.add_event(
+        Event::new("proof_verified")
+            .add_attribute("issuer", issuer.as_str())
+            .add_attribute("circuit_id", circuit_id)
+            .add_attribute("prover", prover.as_str())
+            .add_attribute("vkey_hash", vkey_hash)
+            .add_attribute("proof_system", PROOF_SYSTEM)
+            .add_attribute("fee", fee)
+            .add_attribute("outcome", "valid")
+            .add_attribute("sequence", sequence.to_string()),
+    );
 
+    // the earliest proof committed before the deadline wins the bounty,
+    // `ClaimBounty` pays it once no earlier commitment can be revealed
+    if let Some(mut bounty) = BOUNTIES.may_load(storage, (issuer, circuit_id))? {
+        let earliest = bounty
+            .winner
+            .as_ref()
+            .is_none_or(|winner| committed_at < winner.committed_at);
+        if committed_at <= bounty.deadline && earliest {
+            bounty.winner = Some(ProofClaim {
+                prover: prover.clone(),
+                committed_at,
+            });
+            BOUNTIES.save(storage, (issuer, circuit_id), &bounty)?;
+            response = response.add_event(
+                Event::new("bounty_won")
+                    .add_attribute("issuer", issuer.as_str())
+                    .add_attribute("circuit_id", circuit_id)
+                    .add_attribute("prover", prover.as_str()),
+            );
+        }
+    }
+
+    Ok(response)
+}
+
+/// hold the reward of a circuit until a prover collects it or the issuer reclaims it
+fn escrow_bounty(
+    storage: &mut dyn Storage,
+    issuer: &Addr,
+    circuit_id: &str,
+    bounty: &Bounty,
+) -> StdResult<()> {
+    BOUNTIES.save(storage, (issuer, circuit_id), bounty)?;
+    BOUNTY_ESCROW.update(storage, &bounty.reward.denom, |escrowed| -> StdResult<_> {
+        Ok(escrowed
+            .unwrap_or_default()
+            .checked_add(bounty.reward.amount)?)
+    })?;
+    Ok(())
 }
 
+fn release_bounty(
+    storage: &mut dyn Storage,
+    issuer: &Addr,
+    circuit_id: &str,
+    bounty: &Bounty,
+) -> StdResult<()> {
+    BOUNTIES.remove(storage, (issuer, circuit_id));
+    BOUNTY_ESCROW.update(storage, &bounty.reward.denom, |escrowed| -> StdResult<_> {
+        Ok(escrowed
+            .unwrap_or_default()
+            .saturating_sub(bounty.reward.amount))
+    })?;
+    Ok(())
+}
+
+/// canonical bytes of a field element, signals that reduce to the same element are the same nullifier and statement
+fn field_key(nullifier: &Scalar) -> Vec<u8> {
+    nullifier.to_repr().to_vec()
+}
+
+/// the canonical bytes of a decimal public signal, `None` when it isn't a field element
+pub fn public_signal_key(public_signal: &str) -> Option<Vec<u8>> {
+    Scalar::from_str_vartime(public_signal).map(|x| field_key(&x))
+}
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::Config {} => to_binary::<ConfigResponse>(&CONFIG.load(deps.storage)?.into()),
-        QueryMsg::IssuerZkeys { address } => to_binary(&query_issuer_zkeys(deps, address)?),
-        QueryMsg::ProofResult { issuer_address, prover_address } => to_binary(&query_proof_result(deps, issuer_address, prover_address)?)
+        QueryMsg::IssuerZkeys {
+            address,
+            circuit_id,
+        } => to_binary(&query_issuer_zkeys(deps, address, circuit_id)?),
+        QueryMsg::ProofResult {
+            issuer_address,
+            circuit_id,
+            prover_address,
+        } => to_binary(&query_proof_result(
+            deps,
+            issuer_address,
+            circuit_id,
+            prover_address,
+        )?),
+        QueryMsg::IssuerZkeysBinary {
+            address,
+            circuit_id,
+        } => {
+            to_binary::<ZkeysBinaryResponse>(&load_issuer_zkeys(deps, address, circuit_id)?.into())
+        }
+        QueryMsg::ProofResultBinary {
+            issuer_address,
+            circuit_id,
+            prover_address,
+        } => to_binary::<ProofBinaryResponse>(
+            &load_proof_info(deps, issuer_address, circuit_id, prover_address)?.into(),
+        ),
+        QueryMsg::SenderSignal { address } => to_binary(&query_sender_signal(deps, address)?),
+        QueryMsg::Commitment {
+            issuer_address,
+            circuit_id,
+            prover_address,
+        } => to_binary(&query_commitment(
+            deps,
+            issuer_address,
+            circuit_id,
+            prover_address,
+        )?),
+        QueryMsg::Nullifier {
+            issuer_address,
+            circuit_id,
+            nullifier,
+        } => to_binary(&query_nullifier(
+            deps,
+            issuer_address,
+            circuit_id,
+            nullifier,
+        )?),
+        QueryMsg::Bounty {
+            address,
+            circuit_id,
+        } => to_binary(&query_bounty(deps, address, circuit_id)?),
+        QueryMsg::FeeBalance {} => to_binary(&query_fee_balance(deps, env)?),
+        QueryMsg::Issuers { start_after, limit } => {
+            to_binary(&query_issuers(deps, start_after, limit)?)
+        }
+        QueryMsg::Circuit {
+            issuer_address,
+            circuit_id,
+        } => to_binary(&query_circuit(deps, issuer_address, circuit_id)?),
+        QueryMsg::Circuits {
+            issuer_address,
+            start_after,
+            limit,
+        } => to_binary(&query_circuits(deps, issuer_address, start_after, limit)?),
+        QueryMsg::Provers {
+            issuer_address,
+            circuit_id,
+            start_after,
+            limit,
+        } => to_binary(&query_provers(
+            deps,
+            issuer_address,
+            circuit_id,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::SolvedCircuits {
+            prover_address,
+            start_after,
+            limit,
+        } => to_binary(&query_solved_circuits(
+            deps,
+            prover_address,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::ProverHistory {
+            prover_address,
+            start_after,
+            limit,
+        } => to_binary(&query_prover_history(
+            deps,
+            prover_address,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::VerifyProof {
+            key,
+            proof_a,
+            proof_b,
+            proof_c,
+            point_encoding,
+            public_signals,
+        } => to_binary(&query_verify_proof(
+            deps,
+            key,
+            proof_a,
+            proof_b,
+            proof_c,
+            point_encoding,
+            public_signals,
+        )?),
     }
 }
 
-fn query_issuer_zkeys(deps: Deps, address: String) -> StdResult<ZkeysResponse> {
+fn load_issuer_zkeys(
+    deps: Deps,
+    address: String,
+    circuit_id: Option<String>,
+) -> StdResult<ZkeysStr> {
     let issuer_addr = deps.api.addr_validate(&address)?;
 
-    let zkeys = ZKEYS.load(deps.storage, &issuer_addr)?;
+    ZKEYS.load(
+        deps.storage,
+        (&issuer_addr, &circuit_or_default(circuit_id)),
+    )
+}
+
+fn query_issuer_zkeys(
+    deps: Deps,
+    address: String,
+    circuit_id: Option<String>,
+) -> StdResult<ZkeysResponse> {
+    let zkeys = load_issuer_zkeys(deps, address, circuit_id)?;
     Ok(ZkeysResponse {
-        public_signal: zkeys.public_signal,
+        public_signals: zkeys.public_signals,
+        public_signal_mode: zkeys.public_signal_mode,
+        bind_sender: zkeys.bind_sender,
+        nullifier_index: zkeys.nullifier_index,
         vk_alpha1: hex::encode(zkeys.vkeys.alpha_1),
         vk_beta_2: hex::encode(zkeys.vkeys.beta_2),
         vk_gamma_2: hex::encode(zkeys.vkeys.gamma_2),
         vk_delta_2: hex::encode(zkeys.vkeys.delta_2),
-        vk_ic0: hex::encode(zkeys.vkeys.ic0),
-        vk_ic1: hex::encode(zkeys.vkeys.ic1)
+        vk_ic: zkeys.vkeys.ic.into_iter().map(hex::encode).collect(),
     })
 }
 
-fn query_proof_result(deps: Deps, issuer_address: String, prover_address: String) -> StdResult<ProofResponse> {
+fn load_proof_info(
+    deps: Deps,
+    issuer_address: String,
+    circuit_id: Option<String>,
+    prover_address: String,
+) -> StdResult<ProofInfo> {
     let issuer_addr = deps.api.addr_validate(&issuer_address)?;
     let prover_addr = deps.api.addr_validate(&prover_address)?;
 
-    let proof_info = PROVERLIST.load(deps.storage, (&issuer_addr, &prover_addr))?;
+    PROVERLIST.load(
+        deps.storage,
+        (&issuer_addr, &circuit_or_default(circuit_id), &prover_addr),
+    )
+}
+
+fn query_proof_result(
+    deps: Deps,
+    issuer_address: String,
+    circuit_id: Option<String>,
+    prover_address: String,
+) -> StdResult<ProofResponse> {
+    let proof_info = load_proof_info(deps, issuer_address, circuit_id, prover_address)?;
     Ok(ProofResponse {
         proof_a: hex::encode(proof_info.proof.pi_a),
         proof_b: hex::encode(proof_info.proof.pi_b),
         proof_c: hex::encode(proof_info.proof.pi_c),
+        point_encoding: proof_info.proof.encoding,
+        public_signals: proof_info.public_signals,
         is_valid: proof_info.is_valid,
     })
-}
\ No newline at end of file
+}
+
+fn query_sender_signal(deps: Deps, address: String) -> StdResult<SenderSignalResponse> {
+    let addr = deps.api.addr_validate(&address)?;
+
+    Ok(SenderSignalResponse {
+        public_signal: sender_public_signal(&addr),
+    })
+}
+
+fn query_commitment(
+    deps: Deps,
+    issuer_address: String,
+    circuit_id: Option<String>,
+    prover_address: String,
+) -> StdResult<CommitmentResponse> {
+    let issuer_addr = deps.api.addr_validate(&issuer_address)?;
+    let prover_addr = deps.api.addr_validate(&prover_address)?;
+
+    let config = CONFIG.load(deps.storage)?;
+    let commitment = COMMITMENTS.load(
+        deps.storage,
+        (&issuer_addr, &circuit_or_default(circuit_id), &prover_addr),
+    )?;
+    Ok(CommitmentResponse {
+        commitment: hex::encode(commitment.hash),
+        height: commitment.height,
+        expires_at: commitment.height.saturating_add(config.commitment_expiry),
+    })
+}
+
+fn query_nullifier(
+    deps: Deps,
+    issuer_address: String,
+    circuit_id: Option<String>,
+    nullifier: String,
+) -> StdResult<NullifierResponse> {
+    let issuer_addr = deps.api.addr_validate(&issuer_address)?;
+
+    let circuit_id = circuit_or_default(circuit_id);
+    // a nullifier that can't be read is reported as the public signal it stands for
+    let index = ZKEYS
+        .may_load(deps.storage, (&issuer_addr, &circuit_id))?
+        .and_then(|zkeys| zkeys.nullifier_index)
+        .unwrap_or_default();
+    let nullifier = public_signal_key(&nullifier).ok_or_else(|| {
+        StdError::generic_err(ContractError::ParsePulbicSignalError { index }.to_string())
+    })?;
+    Ok(NullifierResponse {
+        spent: NULLIFIERS.has(deps.storage, (&issuer_addr, &circuit_id, &nullifier)),
+    })
+}
+
+fn query_bounty(
+    deps: Deps,
+    address: String,
+    circuit_id: Option<String>,
+) -> StdResult<BountyResponse> {
+    let issuer_addr = deps.api.addr_validate(&address)?;
+
+    let config = CONFIG.load(deps.storage)?;
+    let bounty = BOUNTIES.load(
+        deps.storage,
+        (&issuer_addr, &circuit_or_default(circuit_id)),
+    )?;
+    Ok(BountyResponse {
+        reward: bounty.reward,
+        deadline: bounty.deadline,
+        claimable_at: bounty
+            .winner
+            .as_ref()
+            .map(|winner| winner.committed_at.saturating_add(config.commitment_expiry)),
+        winner: bounty.winner.map(|winner| winner.prover),
+    })
+}
+
+fn query_fee_balance(deps: Deps, env: Env) -> StdResult<FeeBalanceResponse> {
+    Ok(FeeBalanceResponse {
+        balance: fee_balance(deps, &env)?,
+    })
+}
+
+fn query_issuers(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<IssuersResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let mut start_after = start_after
+        .map(|address| deps.api.addr_validate(&address))
+        .transpose()?;
+
+    // an issuer is listed once, whatever the number of its circuits, so every
+    // issuer is a seek past the circuits of the previous one, not a scan over them
+    let mut issuers: Vec<Addr> = Vec::with_capacity(limit);
+    while issuers.len() < limit {
+        let start = start_after.as_ref().map(PrefixBound::exclusive);
+        let ((issuer, _), _) = match ZKEYS
+            .prefix_range(deps.storage, start, None, Order::Ascending)
+            .next()
+        {
+            Some(item) => item?,
+            None => break,
+        };
+        start_after = Some(issuer.clone());
+        issuers.push(issuer);
+    }
+    Ok(IssuersResponse { issuers })
+}
+
+fn query_circuit(
+    deps: Deps,
+    issuer_address: String,
+    circuit_id: Option<String>,
+) -> StdResult<CircuitResponse> {
+    let issuer_addr = deps.api.addr_validate(&issuer_address)?;
+    let circuit_id = circuit_or_default(circuit_id);
+
+    let zkeys = ZKEYS.load(deps.storage, (&issuer_addr, &circuit_id))?;
+    Ok(CircuitResponse {
+        issuer: issuer_addr,
+        circuit_id,
+        vkey_hash: hex::encode(vkey_hash(&zkeys.vkeys)),
+        proof_system: PROOF_SYSTEM.to_string(),
+        metadata: zkeys.metadata,
+        status: zkeys.status,
+    })
+}
+
+fn query_circuits(
+    deps: Deps,
+    issuer_address: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<CircuitsResponse> {
+    let issuer_addr = deps.api.addr_validate(&issuer_address)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+
+    let circuit_ids = ZKEYS
+        .prefix(&issuer_addr)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(CircuitsResponse { circuit_ids })
+}
+
+fn query_provers(
+    deps: Deps,
+    issuer_address: String,
+    circuit_id: Option<String>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ProversResponse> {
+    let issuer_addr = deps.api.addr_validate(&issuer_address)?;
+    let circuit_id = circuit_or_default(circuit_id);
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after
+        .map(|address| deps.api.addr_validate(&address))
+        .transpose()?;
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    let provers = PROVERLIST
+        .prefix((&issuer_addr, &circuit_id))
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(ProversResponse { provers })
+}
+
+fn query_solved_circuits(
+    deps: Deps,
+    prover_address: String,
+    start_after: Option<(String, String)>,
+    limit: Option<u32>,
+) -> StdResult<SolvedCircuitsResponse> {
+    let prover_addr = deps.api.addr_validate(&prover_address)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after
+        .map(|(address, circuit_id)| {
+            deps.api
+                .addr_validate(&address)
+                .map(|issuer| (issuer, circuit_id))
+        })
+        .transpose()?;
+    let start = start_after
+        .as_ref()
+        .map(|(issuer, circuit_id)| Bound::exclusive((issuer, circuit_id.as_str())));
+
+    let circuits = SOLVED
+        .sub_prefix(&prover_addr)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(issuer, circuit_id)| CircuitRef { issuer, circuit_id }))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(SolvedCircuitsResponse { circuits })
+}
+
+fn query_prover_history(
+    deps: Deps,
+    prover_address: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<ProverHistoryResponse> {
+    let prover_addr = deps.api.addr_validate(&prover_address)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let verifications = PROVER_HISTORY
+        .prefix(&prover_addr)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            item.map(|(sequence, verification)| VerificationResponse {
+                sequence,
+                issuer: verification.issuer,
+                circuit_id: verification.circuit_id,
+                vkey_hash: verification.vkey_hash,
+                height: verification.height,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(ProverHistoryResponse { verifications })
+}
+
+/// the checks of `verify_and_record` without recording anything
+fn query_verify_proof(
+    deps: Deps,
+    key: VerifyingKeySource,
+    proof_a: String,
+    proof_b: String,
+    proof_c: String,
+    point_encoding: Option<PointEncoding>,
+    public_signals: Option<Vec<String>>,
+) -> StdResult<VerifyProofResponse> {
+    let encoding = point_encoding.unwrap_or_default();
+    let (circuit, prover, zkeys) = match key {
+        VerifyingKeySource::Registered {
+            issuer,
+            circuit_id,
+            prover,
+        } => {
+            let issuer = deps.api.addr_validate(&issuer)?;
+            let circuit_id = circuit_or_default(circuit_id);
+            let prover = prover
+                .map(|address| deps.api.addr_validate(&address))
+                .transpose()?;
+            let zkeys = ZKEYS
+                .may_load(deps.storage, (&issuer, &circuit_id))?
+                .ok_or_else(|| {
+                    StdError::generic_err(ContractError::NoVerificationKey {}.to_string())
+                })?;
+            (Some((issuer, circuit_id)), prover, zkeys)
+        }
+        VerifyingKeySource::Inline {
+            vk_alpha1,
+            vk_beta_2,
+            vk_gamma_2,
+            vk_delta_2,
+            vk_ic,
+        } => {
+            let vkeys = decode_vkey(
+                vk_alpha1, vk_beta_2, vk_gamma_2, vk_delta_2, vk_ic, encoding,
+            )
+            .map_err(|err| StdError::generic_err(err.to_string()))?;
+            let prepared =
+                prepare_vkey(&vkeys).map_err(|err| StdError::generic_err(err.to_string()))?;
+            let zkeys = ZkeysStr {
+                vkeys,
+                prepared: Some(prepared),
+                public_signals: vec![],
+                public_signal_mode: PublicSignalMode::Prover,
+                bind_sender: false,
+                nullifier_index: None,
+                metadata: CircuitMetadata::default(),
+                status: CircuitStatus::Active,
+            };
+            (None, None, zkeys)
+        }
+    };
+    let vkey_hash = hex::encode(vkey_hash(&zkeys.vkeys));
+    let nullifier_index = zkeys.nullifier_index;
+    let rejected = |public_signals: Vec<String>, err: ContractError| VerifyProofResponse {
+        is_valid: false,
+        vkey_hash: vkey_hash.clone(),
+        public_signals,
+        nullifier_spent: None,
+        error: Some(err.to_string()),
+    };
+
+    if let (Some((_, circuit_id)), CircuitStatus::Retired) = (&circuit, &zkeys.status) {
+        return Ok(rejected(
+            vec![],
+            ContractError::CircuitRetired {
+                circuit_id: circuit_id.clone(),
+            },
+        ));
+    }
+
+    let public_signals = match resolve_public_signals(&zkeys, prover.as_ref(), public_signals) {
+        Ok(public_signals) => public_signals,
+        Err(err) => return Ok(rejected(vec![], err)),
+    };
+    let checked = decode_proof(proof_a, proof_b, proof_c, encoding).and_then(|proof_str| {
+        check_proof(
+            zkeys
+                .prepared
+                .as_ref()
+                .ok_or(ContractError::NoVerificationKey {})?,
+            proof_str,
+            &public_signals,
+        )
+    });
+    let public_inputs = match checked {
+        Ok((true, public_inputs)) => public_inputs,
+        Ok((false, _)) => return Ok(rejected(public_signals, ContractError::InvalidProof {})),
+        Err(err) => return Ok(rejected(public_signals, err)),
+    };
+
+    // a valid proof is still refused by `Proof` once its nullifier is spent
+    let nullifier_spent = match (circuit, nullifier_index) {
+        (Some((issuer, circuit_id)), Some(index)) => Some(NULLIFIERS.has(
+            deps.storage,
+            (&issuer, &circuit_id, &field_key(&public_inputs[index])),
+        )),
+        _ => None,
+    };
+    Ok(VerifyProofResponse {
+        is_valid: true,
+        vkey_hash,
+        public_signals,
+        nullifier_spent,
+        error: None,
+    })
+}
+
+/// what the contract holds minus the rewards of open bounties
+fn fee_balance(deps: Deps, env: &Env) -> StdResult<Vec<Coin>> {
+    let mut balance = deps
+        .querier
+        .query_all_balances(env.contract.address.as_str())?;
+    for coin in balance.iter_mut() {
+        let escrowed = BOUNTY_ESCROW
+            .may_load(deps.storage, &coin.denom)?
+            .unwrap_or_default();
+        coin.amount = coin.amount.saturating_sub(escrowed);
+    }
+    balance.retain(|coin| !coin.amount.is_zero());
+    Ok(balance)
+}
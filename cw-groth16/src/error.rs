@@ -12,11 +12,20 @@ pub enum ContractError {
     #[error("Insufficient funds sent")]
     InsufficientFundsSend {},
 
-    #[error("error verification")]
-    ErrorVerificationKey {},
+    #[error("{denom} is not accepted as a fee")]
+    UnrelatedDenom { denom: String },
 
-    #[error("error proof")]
-    ErrorProof {},
+    #[error("not enough fees collected in {denom}")]
+    InsufficientFees { denom: String },
+
+    #[error("a withdrawal needs a non-zero amount")]
+    EmptyWithdrawal {},
+
+    #[error("{field}: {reason}")]
+    ErrorVerificationKey { field: String, reason: String },
+
+    #[error("{field}: {reason}")]
+    ErrorProof { field: String, reason: String },
 
     #[error("error public signal")]
     ErrorPublicSignal {},
@@ -27,12 +36,127 @@ pub enum ContractError {
     #[error("no public signal")]
     NoPublicSignal {},
 
-    #[error("parse public signal error")]
-    ParsePulbicSignalError {},
+    #[error("the key binds the sender, a prover address is needed")]
+    NoProver {},
+
+    #[error("only a key binding the sender takes proofs relayed for another prover")]
+    ProverNotSender {},
+
+    #[error("public signal {index} is not a field element")]
+    ParsePulbicSignalError { index: usize },
+
+    #[error("expected {expected} public signals, got {actual}")]
+    PublicSignalCountMismatch { expected: usize, actual: usize },
 
     #[error("invalid proof, verify failed")]
     InvalidProof {},
 
+    #[error("a proof batch needs at least one proof")]
+    EmptyProofBatch {},
+
+    #[error("invalid proofs in the batch at {indexes:?}")]
+    InvalidBatchProofs { indexes: Vec<usize> },
+
     #[error("this account({difficuty_issuer}) didn't issue difficulty problem")]
     NonPublishDifficulty { difficuty_issuer: String },
+
+    #[error("circuit ids are 1 to 64 characters")]
+    InvalidCircuitId {},
+
+    #[error("circuit {circuit_id} is already registered and proven, retired or under a bounty")]
+    CircuitAlreadyRegistered { circuit_id: String },
+
+    #[error("circuit metadata has an invalid {field}")]
+    InvalidCircuitMetadata { field: String },
+
+    #[error("circuit {circuit_id} is retired")]
+    CircuitRetired { circuit_id: String },
+
+    #[error("commitment must be a hex encoded sha256 hash")]
+    InvalidCommitment {},
+
+    #[error("no commitment for this proof")]
+    NoCommitment {},
+
+    #[error("commitment can only be revealed in a later block")]
+    RevealTooEarly {},
+
+    #[error("commitment expired")]
+    CommitmentExpired {},
+
+    #[error("revealed proof doesn't match the commitment")]
+    CommitmentMismatch {},
+
+    #[error("this proof was already claimed with an earlier commitment")]
+    ProofAlreadyClaimed {},
+
+    #[error("nullifier index is out of the public signals")]
+    InvalidNullifierIndex {},
+
+    #[error("nullifier already spent")]
+    NullifierAlreadySpent {},
+
+    #[error("{field}: invalid hex")]
+    HexDecodingError { field: String },
+
+    #[error("give the key or proof either as hex points or as snarkjs json")]
+    AmbiguousEncoding {},
+
+    #[error("snarkjs {field} is {found}, expected {expected}")]
+    SnarkjsMismatch {
+        field: String,
+        expected: String,
+        found: String,
+    },
+
+    #[error("snarkjs {field} has an invalid coordinate")]
+    InvalidSnarkjsCoordinate { field: String },
+
+    #[error("bounty needs a non-zero reward and a deadline in the future")]
+    InvalidBounty {},
+
+    #[error("the previous bounty is still open")]
+    BountyAlreadyOpen {},
+
+    #[error("no bounty")]
+    NoBounty {},
+
+    #[error("the bounty can be reclaimed from height {reclaimable_at}, once the commitments made by its deadline have expired")]
+    BountyNotExpired { reclaimable_at: u64 },
+
+    #[error("the bounty was won by a valid proof")]
+    BountyAlreadyWon {},
+
+    #[error("no valid proof has won the bounty yet")]
+    BountyNotWon {},
+
+    #[error("the bounty can be claimed from height {claimable_at}")]
+    BountyNotClaimable { claimable_at: u64 },
+
+    #[error("{token} is not accepted as payment")]
+    UnacceptedToken { token: String },
+
+    #[error("only Zkeys, Proof, ProofBatch and RevealProof can be paid with cw20 tokens")]
+    InvalidCw20Hook {},
+
+    #[error("cannot migrate from a different contract: {previous_contract}")]
+    CannotMigrate { previous_contract: String },
+
+    #[error("cannot migrate from version {previous_version} down to {new_version}")]
+    CannotDowngrade {
+        previous_version: String,
+        new_version: String,
+    },
+
+    #[error("migrating from 0.1.0 needs an owner")]
+    MissingOwner {},
+
+    #[error("semver parsing error: {0}")]
+    SemVer(String),
+}
+
+impl From<semver::Error> for ContractError {
+    fn from(err: semver::Error) -> Self {
+        Self::SemVer(err.to_string())
+    }
 }
@@ -1,110 +1,204 @@
-use crate::state::{ProofStr, VkeyStr};
+use super::error::ContractError;
+use crate::state::{PointEncoding, PreparedVkey, ProofStr, VkeyStr};
 use bellman_verifier::{Proof, VerifyingKey};
-use bls12_381::{G1Affine, G2Affine};
+use bls12_381_plus::{pairing, Bls12, G1Affine, G2Affine, Gt};
+use cosmwasm_std::Binary;
 use pairing::Engine;
-use super::error::ContractError;
-use cosmwasm_std::ensure;
 
 /// convert the proof into the affine type, which will be used to verify
-pub fn parse_proof<E>(pof: ProofStr) -> Result<Proof<E>, ContractError> 
+pub fn parse_proof<E>(pof: ProofStr) -> Result<Proof<E>, ContractError>
 where
-	E: Engine<G1Affine = G1Affine, G2Affine = G2Affine>,
+    E: Engine<G1Affine = G1Affine, G2Affine = G2Affine>,
 {
-	let pi_a = pof.pi_a;
-	let pi_b = pof.pi_b;
-	let pi_c = pof.pi_c;
+    let encoding = pof.encoding;
+
+    let pia_affine = g1_affine(&pof.pi_a, encoding).map_err(proof_error("proof_a"))?;
+    let pib_affine = g2_affine(&pof.pi_b, encoding).map_err(proof_error("proof_b"))?;
+    let pic_affine = g1_affine(&pof.pi_c, encoding).map_err(proof_error("proof_c"))?;
+
+    Ok(Proof {
+        a: pia_affine,
+        b: pib_affine,
+        c: pic_affine,
+    })
+}
 
-	ensure!(pi_a.len() == 96, ContractError::ErrorProof{});
-	ensure!(pi_b.len() == 192, ContractError::ErrorProof{});
-	ensure!(pi_c.len() == 96, ContractError::ErrorProof{});
+/// convert the verification key into the affine type, which will be used in verification
+pub fn parse_vkey<E>(vk: VkeyStr) -> Result<VerifyingKey<E>, ContractError>
+where
+    E: Engine<G1Affine = G1Affine, G2Affine = G2Affine>,
+{
+    let encoding = PointEncoding::Uncompressed;
+
+    // ic[0] is the constant term, so even a circuit without public inputs has one point
+    if vk.ic.is_empty() {
+        return Err(vkey_error("vk_ic")("missing the constant term".to_string()));
+    }
+
+    // ensure the format of verification key is correct, otherwaise, it can not be converted into affine type
+    let alpha1_affine = g1_affine(&vk.alpha_1, encoding).map_err(vkey_error("vk_alpha1"))?;
+    let beta2_affine = g2_affine(&vk.beta_2, encoding).map_err(vkey_error("vk_beta_2"))?;
+    let gamma2_affine = g2_affine(&vk.gamma_2, encoding).map_err(vkey_error("vk_gamma_2"))?;
+    let delta2_affine = g2_affine(&vk.delta_2, encoding).map_err(vkey_error("vk_delta_2"))?;
+
+    let ic = vk
+        .ic
+        .iter()
+        .enumerate()
+        .map(|(i, ic_i)| g1_affine(ic_i, encoding).map_err(vkey_error(format!("vk_ic[{}]", i))))
+        .collect::<Result<Vec<G1Affine>, ContractError>>()?;
+
+    // return verification key
+    Ok(VerifyingKey {
+        alpha_g1: alpha1_affine,
+        beta_g1: G1Affine::identity(),
+        beta_g2: beta2_affine,
+        gamma_g2: gamma2_affine,
+        delta_g1: G1Affine::identity(),
+        delta_g2: delta2_affine,
+        ic,
+    })
+}
 
-	let mut a_arr: [u8; 96] = [0; 96];
-	let mut b_arr: [u8; 192] = [0; 192];
-	let mut c_arr: [u8; 96] = [0; 96];
+/// the key as stored, the points of a compressed key are checked like those `parse_vkey` reads
+pub fn uncompressed_vkey(vk: VkeyStr, encoding: PointEncoding) -> Result<VkeyStr, ContractError> {
+    if encoding == PointEncoding::Uncompressed {
+        return Ok(vk);
+    }
+    let g1 = |bytes: &[u8], field: String| {
+        g1_affine(bytes, encoding)
+            .map(|point| point.to_uncompressed().to_vec())
+            .map_err(vkey_error(field))
+    };
+    let g2 = |bytes: &[u8], field: &str| {
+        g2_affine(bytes, encoding)
+            .map(|point| point.to_uncompressed().to_vec())
+            .map_err(vkey_error(field))
+    };
+
+    Ok(VkeyStr {
+        alpha_1: g1(&vk.alpha_1, "vk_alpha1".to_string())?,
+        beta_2: g2(&vk.beta_2, "vk_beta_2")?,
+        gamma_2: g2(&vk.gamma_2, "vk_gamma_2")?,
+        delta_2: g2(&vk.delta_2, "vk_delta_2")?,
+        ic: vk
+            .ic
+            .iter()
+            .enumerate()
+            .map(|(i, ic_i)| g1(ic_i, format!("vk_ic[{}]", i)))
+            .collect::<Result<_, _>>()?,
+    })
+}
 
-	a_arr[..pi_a.len()].copy_from_slice(&pi_a[..]);
+/// bytes of the twelve base field coefficients of a Gt element
+const GT_BYTES: usize = 12 * 48;
+
+/// the points of a `PreparedVkey`
+pub struct PreparedKey {
+    pub alpha_beta: Gt,
+    pub neg_alpha_g1: G1Affine,
+    pub beta_g2: G2Affine,
+    pub neg_gamma_g2: G2Affine,
+    pub neg_delta_g2: G2Affine,
+    pub ic: Vec<G1Affine>,
+}
 
-	b_arr[..pi_b.len()].copy_from_slice(&pi_b[..]);
+/// check a stored key once, the prepared form is read back without checking its points again
+pub fn prepare_vkey(vk: &VkeyStr) -> Result<PreparedVkey, ContractError> {
+    let vkey = parse_vkey::<Bls12>(vk.clone())?;
+
+    Ok(PreparedVkey {
+        alpha_beta: Binary::from(pairing(&vkey.alpha_g1, &vkey.beta_g2).to_bytes().to_vec()),
+        neg_alpha_1: Binary::from((-vkey.alpha_g1).to_uncompressed().to_vec()),
+        beta_2: Binary::from(vkey.beta_g2.to_uncompressed().to_vec()),
+        neg_gamma_2: Binary::from((-vkey.gamma_g2).to_uncompressed().to_vec()),
+        neg_delta_2: Binary::from((-vkey.delta_g2).to_uncompressed().to_vec()),
+        ic: vkey
+            .ic
+            .iter()
+            .map(|ic_i| Binary::from(ic_i.to_uncompressed().to_vec()))
+            .collect(),
+    })
+}
 
-	c_arr[..pi_c.len()].copy_from_slice(&pi_c[..]);
+/// read back what `prepare_vkey` stored, skipping the subgroup checks it already did
+pub fn parse_prepared_vkey(pvk: &PreparedVkey) -> Result<PreparedKey, ContractError> {
+    if pvk.ic.is_empty() {
+        return Err(vkey_error("prepared.ic")(
+            "missing the constant term".to_string(),
+        ));
+    }
+
+    let g1 = |bytes: &Binary, field: String| -> Result<G1Affine, ContractError> {
+        let affine = G1Affine::from_uncompressed_unchecked(
+            sized(bytes.as_slice()).map_err(vkey_error(field.clone()))?,
+        );
+        Option::from(affine).ok_or_else(|| vkey_error(field)("not a point of G1".to_string()))
+    };
+    let g2 = |bytes: &Binary, field: &str| -> Result<G2Affine, ContractError> {
+        let affine = G2Affine::from_uncompressed_unchecked(
+            sized(bytes.as_slice()).map_err(vkey_error(field))?,
+        );
+        Option::from(affine).ok_or_else(|| vkey_error(field)("not a point of G2".to_string()))
+    };
+
+    let alpha_beta =
+        sized::<GT_BYTES>(pvk.alpha_beta.as_slice()).map_err(vkey_error("prepared.alpha_beta"))?;
+    let alpha_beta = Option::from(Gt::from_bytes(alpha_beta))
+        .ok_or_else(|| vkey_error("prepared.alpha_beta")("not an element of Fp12".to_string()))?;
+
+    Ok(PreparedKey {
+        alpha_beta,
+        neg_alpha_g1: g1(&pvk.neg_alpha_1, "prepared.neg_alpha_1".to_string())?,
+        beta_g2: g2(&pvk.beta_2, "prepared.beta_2")?,
+        neg_gamma_g2: g2(&pvk.neg_gamma_2, "prepared.neg_gamma_2")?,
+        neg_delta_g2: g2(&pvk.neg_delta_2, "prepared.neg_delta_2")?,
+        ic: pvk
+            .ic
+            .iter()
+            .enumerate()
+            .map(|(i, ic_i)| g1(ic_i, format!("prepared.ic[{}]", i)))
+            .collect::<Result<_, _>>()?,
+    })
+}
 
-	let pia_affine = G1Affine::from_uncompressed(&a_arr);
-	let pib_affine = G2Affine::from_uncompressed(&b_arr);
-	let pic_affine = G1Affine::from_uncompressed(&c_arr);
+/// the point of the G1 subgroup the bytes encode, or why they don't
+fn g1_affine(bytes: &[u8], encoding: PointEncoding) -> Result<G1Affine, String> {
+    let affine = match encoding {
+        PointEncoding::Uncompressed => G1Affine::from_uncompressed(sized(bytes)?),
+        PointEncoding::Compressed => G1Affine::from_compressed(sized(bytes)?),
+    };
+    Option::from(affine).ok_or_else(|| "not a point of the G1 subgroup".to_string())
+}
 
-	ensure!(pia_affine.is_some().unwrap_u8()== 1, ContractError::ErrorProof{});
-	ensure!(pib_affine.is_some().unwrap_u8()== 1, ContractError::ErrorProof{});
-	ensure!(pic_affine.is_some().unwrap_u8()== 1, ContractError::ErrorProof{});
+/// the point of the G2 subgroup the bytes encode, or why they don't
+fn g2_affine(bytes: &[u8], encoding: PointEncoding) -> Result<G2Affine, String> {
+    let affine = match encoding {
+        PointEncoding::Uncompressed => G2Affine::from_uncompressed(sized(bytes)?),
+        PointEncoding::Compressed => G2Affine::from_compressed(sized(bytes)?),
+    };
+    Option::from(affine).ok_or_else(|| "not a point of the G2 subgroup".to_string())
+}
 
-	Ok(Proof { a: pia_affine.unwrap(), b: pib_affine.unwrap(), c: pic_affine.unwrap() })
+/// the bytes as the array an encoding of `N` bytes is read from
+fn sized<const N: usize>(bytes: &[u8]) -> Result<&[u8; N], String> {
+    bytes
+        .try_into()
+        .map_err(|_| format!("expected {} bytes, got {}", N, bytes.len()))
 }
 
+/// names the proof field a reason is about
+fn proof_error(field: impl Into<String>) -> impl FnOnce(String) -> ContractError {
+    move |reason| ContractError::ErrorProof {
+        field: field.into(),
+        reason,
+    }
+}
 
-/// convert the verification key into the affine type, which will be used in verification
-pub fn parse_vkey<E>(vk: VkeyStr) ->  Result<VerifyingKey<E>, ContractError>
-where
-	E: Engine<G1Affine = G1Affine, G2Affine = G2Affine>,
-{
-	let vk_alpha_1 = vk.alpha_1;
-	let vk_beta_2 = vk.beta_2;
-	let vk_gamma_2 = vk.gamma_2;
-	let vk_delta_2 = vk.delta_2;
-	let vk_ic0 = vk.ic0;
-	let vk_ic1 = vk.ic1;
-
-	ensure!(vk_alpha_1.len() == 96, ContractError::ErrorVerificationKey{});
-	ensure!(vk_beta_2.len() == 192, ContractError::ErrorVerificationKey{});
-	ensure!(vk_gamma_2.len() == 192, ContractError::ErrorVerificationKey{});
-	ensure!(vk_delta_2.len() == 192, ContractError::ErrorVerificationKey{});
-	ensure!(vk_ic0.len() == 96, ContractError::ErrorVerificationKey{});
-	ensure!(vk_ic1.len() == 96, ContractError::ErrorVerificationKey{});
-
-	let mut alpha1: [u8; 96] = [0; 96];
-	let mut beta2: [u8; 192] = [0; 192];
-	let mut gamma2: [u8; 192] = [0; 192];
-	let mut delta2: [u8; 192] = [0; 192];
-	let mut ic_0: [u8; 96] = [0; 96];
-	let mut ic_1: [u8; 96] = [0; 96];
-	let mut ic = Vec::new();
-
-	alpha1[..vk_alpha_1.len()].copy_from_slice(&vk_alpha_1[..]);
-
-	beta2[..vk_beta_2.len()].copy_from_slice(&vk_beta_2[..]);
-
-	gamma2[..vk_gamma_2.len()].copy_from_slice(&vk_gamma_2[..]);
-
-	delta2[..vk_delta_2.len()].copy_from_slice(&vk_delta_2[..]);
-
-	ic_0[..vk_ic0.len()].copy_from_slice(&vk_ic0[..]);
-
-	ic_1[..vk_ic1.len()].copy_from_slice(&vk_ic1[..]);
-
-	let alpha1_affine = G1Affine::from_uncompressed(&alpha1);
-	let beta2_affine = G2Affine::from_uncompressed(&beta2);
-	let gamma2_affine = G2Affine::from_uncompressed(&gamma2);
-	let delta2_affine = G2Affine::from_uncompressed(&delta2);
-	let ic0_affine = G1Affine::from_uncompressed(&ic_0);
-	let ic1_affine = G1Affine::from_uncompressed(&ic_1);
-
-	// ensure the format of verification key is correct, otherwaise, it can not be converted into affine type
-	ensure!(alpha1_affine.is_some().unwrap_u8() == 1, ContractError::ErrorVerificationKey{});
-	ensure!(beta2_affine.is_some().unwrap_u8()== 1, ContractError::ErrorVerificationKey{});
-	ensure!(gamma2_affine.is_some().unwrap_u8()== 1, ContractError::ErrorVerificationKey{});
-	ensure!(delta2_affine.is_some().unwrap_u8()== 1, ContractError::ErrorVerificationKey{});
-	ensure!(ic0_affine.is_some().unwrap_u8()== 1, ContractError::ErrorVerificationKey{});
-	ensure!(ic1_affine.is_some().unwrap_u8()== 1, ContractError::ErrorVerificationKey{});
-
-	ic.push(ic0_affine.unwrap());
-	ic.push(ic1_affine.unwrap());
-
-	// return verification key
-	Ok(VerifyingKey {
-		alpha_g1: alpha1_affine.unwrap(),
-		beta_g1: G1Affine::identity(),
-		beta_g2: beta2_affine.unwrap(),
-		gamma_g2: gamma2_affine.unwrap(),
-		delta_g1: G1Affine::identity(),
-		delta_g2: delta2_affine.unwrap(),
-		ic,
-	})
-}
\ No newline at end of file
+/// names the key field a reason is about
+fn vkey_error(field: impl Into<String>) -> impl FnOnce(String) -> ContractError {
+    move |reason| ContractError::ErrorVerificationKey {
+        field: field.into(),
+        reason,
+    }
+}
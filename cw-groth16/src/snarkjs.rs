@@ -0,0 +1,145 @@
+use std::str::FromStr;
+
+use crate::state::{PointEncoding, ProofStr, VkeyStr};
+use crate::ContractError;
+use cosmwasm_schema::{schemars, serde};
+use cosmwasm_std::Uint512;
+
+/// the `curve` snarkjs writes for keys and proofs this contract verifies
+pub const SNARKJS_CURVE: &str = "bls12381";
+
+// bytes of a base field element in the uncompressed point encoding
+const FIELD_BYTES: usize = 48;
+
+/// a `verification_key.json` as written by `snarkjs zkey export verificationkey`,
+/// coordinates are decimal and points projective with z = 1
+///
+/// Unlike `cw_serde` types unknown fields are allowed, snarkjs also writes
+/// `vk_alphabeta_12`, which the verifier doesn't need.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, schemars::JsonSchema)]
+#[serde(crate = "cosmwasm_schema::serde")]
+#[schemars(crate = "cosmwasm_schema::schemars")]
+pub struct SnarkjsVerifyingKey {
+    pub protocol: String,
+    pub curve: String,
+    #[serde(rename = "nPublic")]
+    pub n_public: usize,
+    pub vk_alpha_1: Vec<String>,
+    pub vk_beta_2: Vec<Vec<String>>,
+    pub vk_gamma_2: Vec<Vec<String>>,
+    pub vk_delta_2: Vec<Vec<String>>,
+    #[serde(rename = "IC")]
+    pub ic: Vec<Vec<String>>,
+}
+
+/// a `proof.json` as written by `snarkjs groth16 prove`, its `public.json`
+/// goes into the `public_signals` of the message as it is
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, schemars::JsonSchema)]
+#[serde(crate = "cosmwasm_schema::serde")]
+#[schemars(crate = "cosmwasm_schema::schemars")]
+pub struct SnarkjsProof {
+    pub pi_a: Vec<String>,
+    pub pi_b: Vec<Vec<String>>,
+    pub pi_c: Vec<String>,
+    pub protocol: String,
+    pub curve: String,
+}
+
+impl SnarkjsVerifyingKey {
+    /// the key in the uncompressed encoding `parse_vkey` reads
+    pub fn to_vkey_str(&self) -> Result<VkeyStr, ContractError> {
+        check_header(&self.protocol, &self.curve)?;
+        if self.ic.len() != self.n_public + 1 {
+            // one IC point for every public input and the constant term
+            return Err(ContractError::SnarkjsMismatch {
+                field: "nPublic".to_string(),
+                expected: (self.ic.len().saturating_sub(1)).to_string(),
+                found: self.n_public.to_string(),
+            });
+        }
+        Ok(VkeyStr {
+            alpha_1: g1_bytes("vk_alpha_1", &self.vk_alpha_1)?,
+            beta_2: g2_bytes("vk_beta_2", &self.vk_beta_2)?,
+            gamma_2: g2_bytes("vk_gamma_2", &self.vk_gamma_2)?,
+            delta_2: g2_bytes("vk_delta_2", &self.vk_delta_2)?,
+            ic: self
+                .ic
+                .iter()
+                .map(|ic_i| g1_bytes("IC", ic_i))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+impl SnarkjsProof {
+    /// the proof in the uncompressed encoding `parse_proof` reads
+    pub fn to_proof_str(&self) -> Result<ProofStr, ContractError> {
+        check_header(&self.protocol, &self.curve)?;
+        Ok(ProofStr {
+            pi_a: g1_bytes("pi_a", &self.pi_a)?,
+            pi_b: g2_bytes("pi_b", &self.pi_b)?,
+            pi_c: g1_bytes("pi_c", &self.pi_c)?,
+            encoding: PointEncoding::Uncompressed,
+        })
+    }
+}
+
+fn check_header(protocol: &str, curve: &str) -> Result<(), ContractError> {
+    let mismatch = |field: &str, expected: &str, found: &str| ContractError::SnarkjsMismatch {
+        field: field.to_string(),
+        expected: expected.to_string(),
+        found: found.to_string(),
+    };
+    if protocol != "groth16" {
+        return Err(mismatch("protocol", "groth16", protocol));
+    }
+    if curve != SNARKJS_CURVE {
+        return Err(mismatch("curve", SNARKJS_CURVE, curve));
+    }
+    Ok(())
+}
+
+/// a decimal coordinate as a big-endian field element, the curve decoding
+/// rejects values outside the field
+fn field_element(field: &str, value: &str) -> Result<[u8; FIELD_BYTES], ContractError> {
+    let invalid = || ContractError::InvalidSnarkjsCoordinate {
+        field: field.to_string(),
+    };
+    let bytes = Uint512::from_str(value)
+        .map_err(|_| invalid())?
+        .to_be_bytes();
+    let (high, low) = bytes.split_at(bytes.len() - FIELD_BYTES);
+    if high.iter().any(|byte| *byte != 0) {
+        return Err(invalid());
+    }
+    let mut element = [0; FIELD_BYTES];
+    element.copy_from_slice(low);
+    Ok(element)
+}
+
+/// `[x, y, "1"]` as x || y
+fn g1_bytes(field: &str, point: &[String]) -> Result<Vec<u8>, ContractError> {
+    match point {
+        [x, y, z] if z == "1" => Ok([field_element(field, x)?, field_element(field, y)?].concat()),
+        // the point at infinity or a projective point snarkjs didn't normalize
+        _ => Err(ContractError::InvalidSnarkjsCoordinate {
+            field: field.to_string(),
+        }),
+    }
+}
+
+/// `[[x.c0, x.c1], [y.c0, y.c1], ["1", "0"]]` as x.c1 || x.c0 || y.c1 || y.c0
+fn g2_bytes(field: &str, point: &[Vec<String>]) -> Result<Vec<u8>, ContractError> {
+    match point {
+        [x, y, z] if x.len() == 2 && y.len() == 2 && z[..] == ["1", "0"] => Ok([
+            field_element(field, &x[1])?,
+            field_element(field, &x[0])?,
+            field_element(field, &y[1])?,
+            field_element(field, &y[0])?,
+        ]
+        .concat()),
+        _ => Err(ContractError::InvalidSnarkjsCoordinate {
+            field: field.to_string(),
+        }),
+    }
+}
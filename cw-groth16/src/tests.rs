@@ -1,12 +1,35 @@
 #[cfg(test)]
 mod test_module {
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{coin, coins, from_binary, Coin, Deps, DepsMut};
+    use bellman_verifier::{prepare_verifying_key, verify_proof};
+    use bls12_381_plus::{pairing, Bls12, G1Affine, G2Affine, Scalar};
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_dependencies_with_balance, mock_env, mock_info,
+    };
+    use cosmwasm_std::{
+        attr, coin, coins, from_binary, from_slice, to_binary, Addr, BankMsg, Binary, Coin, Deps,
+        DepsMut, Env, Response, StdError, SubMsg, Uint128, WasmMsg,
+    };
+    use cw2::{get_contract_version, set_contract_version};
+    use cw20::{Cw20Coin, Cw20ExecuteMsg, Cw20ReceiveMsg};
 
-    use crate::contract::{execute, instantiate, query};
+    use crate::commitment::{proof_commitment, vkey_hash};
+    use crate::contract::{check_proof, decode_proof, execute, instantiate, migrate, query};
     use crate::error::ContractError;
-    use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, ZkeysResponse, ProofResponse};
-    use crate::state::Config;
+    use crate::migrations::v0_1;
+    use crate::msg::{
+        BatchProof, BountyResponse, CircuitRef, CircuitResponse, CircuitsResponse,
+        CommitmentResponse, ExecuteMsg, FeeBalanceResponse, InstantiateMsg, IssuersResponse,
+        MigrateMsg, NullifierResponse, ProofBinaryResponse, ProofResponse, ProverHistoryResponse,
+        ProversResponse, QueryMsg, SenderSignalResponse, SolvedCircuitsResponse,
+        VerificationResponse, VerifyProofResponse, VerifyingKeySource, ZkeysBinaryResponse,
+        ZkeysResponse, DEFAULT_CIRCUIT, PROOF_SYSTEM,
+    };
+    use crate::parser::{parse_proof, parse_vkey};
+    use crate::snarkjs::SnarkjsVerifyingKey;
+    use crate::state::{
+        CircuitMetadata, CircuitStatus, Config, FeeMode, PointEncoding, ProofStr, PublicSignalMode,
+        UnrelatedDenoms, ZKEYS,
+    };
 
     fn assert_config_state(deps: Deps, expected: Config) {
         let res = query(deps, mock_env(), QueryMsg::Config {}).unwrap();
@@ -18,6 +41,11 @@ mod test_module {
         let msg = InstantiateMsg {
             set_zkeys_price: None,
             publish_proof_price: None,
+            set_zkeys_cw20_price: None,
+            publish_proof_cw20_price: None,
+            commitment_expiry: None,
+            fee_mode: None,
+            unrelated_denoms: None,
         };
 
         let info = mock_info("creator", &coins(2, "token"));
@@ -29,6 +57,11 @@ mod test_module {
         let msg = InstantiateMsg {
             set_zkeys_price: Some(zkeys_price),
             publish_proof_price: Some(proof_price),
+            set_zkeys_cw20_price: None,
+            publish_proof_cw20_price: None,
+            commitment_expiry: None,
+            fee_mode: None,
+            unrelated_denoms: None,
         };
 
         let info = mock_info("creator", &coins(2, "token"));
@@ -36,6 +69,43 @@ mod test_module {
             .expect("contract successfully handles InstantiateMsg");
     }
 
+    fn mock_init_with_exact_fee(deps: DepsMut, unrelated_denoms: UnrelatedDenoms) {
+        let msg = InstantiateMsg {
+            set_zkeys_price: Some(coin(1, "token")),
+            publish_proof_price: Some(coin(1, "token")),
+            set_zkeys_cw20_price: None,
+            publish_proof_cw20_price: None,
+            commitment_expiry: None,
+            fee_mode: Some(FeeMode::Exact),
+            unrelated_denoms: Some(unrelated_denoms),
+        };
+
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps, mock_env(), info, msg)
+            .expect("contract successfully handles InstantiateMsg");
+    }
+
+    fn mock_init_with_cw20_price(deps: DepsMut) {
+        let msg = InstantiateMsg {
+            set_zkeys_price: None,
+            publish_proof_price: None,
+            set_zkeys_cw20_price: Some(Cw20Coin {
+                address: "cw20_token".to_string(),
+                amount: Uint128::new(2),
+            }),
+            publish_proof_cw20_price: Some(Cw20Coin {
+                address: "cw20_token".to_string(),
+                amount: Uint128::new(2),
+            }),
+            commitment_expiry: None,
+            fee_mode: Some(FeeMode::Exact),
+            unrelated_denoms: None,
+        };
+
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps, mock_env(), info, msg)
+            .expect("contract successfully handles InstantiateMsg");
+    }
 
     #[test]
     fn proper_init_no_fees() {
@@ -45,13 +115,18 @@ mod test_module {
         assert_config_state(
             deps.as_ref(),
             Config {
+                owner: Addr::unchecked("creator"),
                 zkeys_price: None,
                 proof_price: None,
+                zkeys_cw20_price: None,
+                proof_cw20_price: None,
+                fee_mode: FeeMode::Sufficient,
+                unrelated_denoms: UnrelatedDenoms::Refund,
+                commitment_expiry: 100,
             },
         );
     }
 
-
     #[test]
     fn proper_init_with_fees() {
         let mut deps = mock_dependencies();
@@ -61,8 +136,14 @@ mod test_module {
         assert_config_state(
             deps.as_ref(),
             Config {
+                owner: Addr::unchecked("creator"),
                 zkeys_price: Some(coin(3, "token")),
                 proof_price: Some(coin(4, "token")),
+                zkeys_cw20_price: None,
+                proof_cw20_price: None,
+                fee_mode: FeeMode::Sufficient,
+                unrelated_denoms: UnrelatedDenoms::Refund,
+                commitment_expiry: 100,
             },
         );
     }
@@ -72,14 +153,25 @@ mod test_module {
         let mut deps = mock_dependencies();
         mock_init_with_price(deps.as_mut(), coin(2, "token"), coin(2, "token"));
         let info = mock_info("alice_key", &[]);
-        let msg = ExecuteMsg::Zkeys { 
-            public_signal: "33".to_string(), 
-            vk_alpha1: "121ec6cddca2aa0dedf8dbb86e96dc4100b58ea07c01d7ea68a37f8f72191ab2bbe9f16bfe675f71c899ff11e23cbb04064831acc8c18f561f446eeaac3a9a056cb9a89b0b3f13a57eab4e97ebaff6f0a39327bd0a4b5f725d633c87474d35f2".to_string(), 
-            vk_beta_2: "00f3edfbbbe5e2dab32cac1d1ba2f0fdd9eff4067c7152520f0ebbf556c21f98e72590b3cdb614b1ea116991305da942077b7419fac8cc2d38dc6639d68a4cf7c8362efd8395020836f3aa564537fa02a17f2d1b423c19b6cf4784037b1d9f1510afbae9e95703ff3a98c46720f05e642588ef21ccb09580c84d211c0fd60acda18a699f61cef4925b9b113c8a2377f0147c5ee0882a97519627776222438d3e29f581f0e4b61fe18ab42089dfe24a1b9d7376667382941e37329860ec84d105".to_string(), 
-            vk_gamma_2: "13e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb80606c4a02ea734cc32acd2b02bc28b99cb3e287e85a763af267492ab572e99ab3f370d275cec1da1aaa9075ff05f79be0ce5d527727d6e118cc9cdc6da2e351aadfd9baa8cbdd3a76d429a695160d12c923ac9cc3baca289e193548608b82801".to_string(), 
-            vk_delta_2: "07709351aa9646a1311053a0e4cfe6d7db03513beaebb9b87da192758e5ecd40d21ac535e7664e78d669399de703cb72109a5d6b3943018f1dd43462eb71be512213f05e61b2c93bc5f65d270bf78122b00e24d38b0f98efdee072cf3b4c8d0a1905dcb70f21d51fdd376d5fcd258df6c3477a2421527d1702b848954fd7a3bbf710eda0c1880b79a996516ec37d616c13082219d90a7743ad8eb5e3faceec7ad6374029d52eaeca7b66c598b3dd7066e4b6246cea47794fdcffcf7891984272".to_string(), 
-            vk_ic0: "16aca3c7fb4157ef2f70fa4098434d97721a2ffa30f1ed64d3123cccb3928433899ab147217331f74f18ce687cc591700e79ca556db5b53e92f1133b889dbc11ef79615331a9a810cbef02d3a760b437a1bd50c1b6c396288abcb37479bc18a5".to_string(), 
-            vk_ic1: "0c1aeb08622db17dab3de7590db8f46349c7e08eff70fa63af8332db75b977bd0e630b04d8e28d4b3416381b27f4bded12e8067fd6f65bd436608cf66f0eb0c19b7da57b72785966d71b91229cde327918d14b3330b891bdfcf255e3d0ecfbfd".to_string(),
+        let msg = ExecuteMsg::Zkeys {
+            circuit_id: None,
+            metadata: None,
+            public_signals: vec!["33".to_string()],
+            public_signal_mode: None,
+            bind_sender: None,
+            nullifier_index: None,
+            bounty: None,
+            bounty_deadline: None,
+            vk_alpha1: "121ec6cddca2aa0dedf8dbb86e96dc4100b58ea07c01d7ea68a37f8f72191ab2bbe9f16bfe675f71c899ff11e23cbb04064831acc8c18f561f446eeaac3a9a056cb9a89b0b3f13a57eab4e97ebaff6f0a39327bd0a4b5f725d633c87474d35f2".to_string(),
+            vk_beta_2: "00f3edfbbbe5e2dab32cac1d1ba2f0fdd9eff4067c7152520f0ebbf556c21f98e72590b3cdb614b1ea116991305da942077b7419fac8cc2d38dc6639d68a4cf7c8362efd8395020836f3aa564537fa02a17f2d1b423c19b6cf4784037b1d9f1510afbae9e95703ff3a98c46720f05e642588ef21ccb09580c84d211c0fd60acda18a699f61cef4925b9b113c8a2377f0147c5ee0882a97519627776222438d3e29f581f0e4b61fe18ab42089dfe24a1b9d7376667382941e37329860ec84d105".to_string(),
+            vk_gamma_2: "13e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb80606c4a02ea734cc32acd2b02bc28b99cb3e287e85a763af267492ab572e99ab3f370d275cec1da1aaa9075ff05f79be0ce5d527727d6e118cc9cdc6da2e351aadfd9baa8cbdd3a76d429a695160d12c923ac9cc3baca289e193548608b82801".to_string(),
+            vk_delta_2: "07709351aa9646a1311053a0e4cfe6d7db03513beaebb9b87da192758e5ecd40d21ac535e7664e78d669399de703cb72109a5d6b3943018f1dd43462eb71be512213f05e61b2c93bc5f65d270bf78122b00e24d38b0f98efdee072cf3b4c8d0a1905dcb70f21d51fdd376d5fcd258df6c3477a2421527d1702b848954fd7a3bbf710eda0c1880b79a996516ec37d616c13082219d90a7743ad8eb5e3faceec7ad6374029d52eaeca7b66c598b3dd7066e4b6246cea47794fdcffcf7891984272".to_string(),
+            vk_ic: vec![
+                "16aca3c7fb4157ef2f70fa4098434d97721a2ffa30f1ed64d3123cccb3928433899ab147217331f74f18ce687cc591700e79ca556db5b53e92f1133b889dbc11ef79615331a9a810cbef02d3a760b437a1bd50c1b6c396288abcb37479bc18a5".to_string(),
+                "0c1aeb08622db17dab3de7590db8f46349c7e08eff70fa63af8332db75b977bd0e630b04d8e28d4b3416381b27f4bded12e8067fd6f65bd436608cf66f0eb0c19b7da57b72785966d71b91229cde327918d14b3330b891bdfcf255e3d0ecfbfd".to_string(),
+            ],
+            point_encoding: None,
+            snarkjs_vkey: None,
         };
         let res = execute(deps.as_mut(), mock_env(), info, msg);
         match res {
@@ -89,56 +181,482 @@ mod test_module {
         }
     }
 
-
     fn mock_alice_set_zkeys(deps: DepsMut, sent: &[Coin]) {
         // alice can register an available name
         let info = mock_info("alice_key", sent);
-        let msg = ExecuteMsg::Zkeys { 
-            public_signal: "33".to_string(), 
-            vk_alpha1: "121ec6cddca2aa0dedf8dbb86e96dc4100b58ea07c01d7ea68a37f8f72191ab2bbe9f16bfe675f71c899ff11e23cbb04064831acc8c18f561f446eeaac3a9a056cb9a89b0b3f13a57eab4e97ebaff6f0a39327bd0a4b5f725d633c87474d35f2".to_string(), 
-            vk_beta_2: "00f3edfbbbe5e2dab32cac1d1ba2f0fdd9eff4067c7152520f0ebbf556c21f98e72590b3cdb614b1ea116991305da942077b7419fac8cc2d38dc6639d68a4cf7c8362efd8395020836f3aa564537fa02a17f2d1b423c19b6cf4784037b1d9f1510afbae9e95703ff3a98c46720f05e642588ef21ccb09580c84d211c0fd60acda18a699f61cef4925b9b113c8a2377f0147c5ee0882a97519627776222438d3e29f581f0e4b61fe18ab42089dfe24a1b9d7376667382941e37329860ec84d105".to_string(), 
-            vk_gamma_2: "13e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb80606c4a02ea734cc32acd2b02bc28b99cb3e287e85a763af267492ab572e99ab3f370d275cec1da1aaa9075ff05f79be0ce5d527727d6e118cc9cdc6da2e351aadfd9baa8cbdd3a76d429a695160d12c923ac9cc3baca289e193548608b82801".to_string(), 
-            vk_delta_2: "07709351aa9646a1311053a0e4cfe6d7db03513beaebb9b87da192758e5ecd40d21ac535e7664e78d669399de703cb72109a5d6b3943018f1dd43462eb71be512213f05e61b2c93bc5f65d270bf78122b00e24d38b0f98efdee072cf3b4c8d0a1905dcb70f21d51fdd376d5fcd258df6c3477a2421527d1702b848954fd7a3bbf710eda0c1880b79a996516ec37d616c13082219d90a7743ad8eb5e3faceec7ad6374029d52eaeca7b66c598b3dd7066e4b6246cea47794fdcffcf7891984272".to_string(), 
-            vk_ic0: "16aca3c7fb4157ef2f70fa4098434d97721a2ffa30f1ed64d3123cccb3928433899ab147217331f74f18ce687cc591700e79ca556db5b53e92f1133b889dbc11ef79615331a9a810cbef02d3a760b437a1bd50c1b6c396288abcb37479bc18a5".to_string(), 
-            vk_ic1: "0c1aeb08622db17dab3de7590db8f46349c7e08eff70fa63af8332db75b977bd0e630b04d8e28d4b3416381b27f4bded12e8067fd6f65bd436608cf66f0eb0c19b7da57b72785966d71b91229cde327918d14b3330b891bdfcf255e3d0ecfbfd".to_string(),
+        let msg = ExecuteMsg::Zkeys {
+            circuit_id: None,
+            metadata: None,
+            public_signals: vec!["33".to_string()],
+            public_signal_mode: None,
+            bind_sender: None,
+            nullifier_index: None,
+            bounty: None,
+            bounty_deadline: None,
+            vk_alpha1: "121ec6cddca2aa0dedf8dbb86e96dc4100b58ea07c01d7ea68a37f8f72191ab2bbe9f16bfe675f71c899ff11e23cbb04064831acc8c18f561f446eeaac3a9a056cb9a89b0b3f13a57eab4e97ebaff6f0a39327bd0a4b5f725d633c87474d35f2".to_string(),
+            vk_beta_2: "00f3edfbbbe5e2dab32cac1d1ba2f0fdd9eff4067c7152520f0ebbf556c21f98e72590b3cdb614b1ea116991305da942077b7419fac8cc2d38dc6639d68a4cf7c8362efd8395020836f3aa564537fa02a17f2d1b423c19b6cf4784037b1d9f1510afbae9e95703ff3a98c46720f05e642588ef21ccb09580c84d211c0fd60acda18a699f61cef4925b9b113c8a2377f0147c5ee0882a97519627776222438d3e29f581f0e4b61fe18ab42089dfe24a1b9d7376667382941e37329860ec84d105".to_string(),
+            vk_gamma_2: "13e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb80606c4a02ea734cc32acd2b02bc28b99cb3e287e85a763af267492ab572e99ab3f370d275cec1da1aaa9075ff05f79be0ce5d527727d6e118cc9cdc6da2e351aadfd9baa8cbdd3a76d429a695160d12c923ac9cc3baca289e193548608b82801".to_string(),
+            vk_delta_2: "07709351aa9646a1311053a0e4cfe6d7db03513beaebb9b87da192758e5ecd40d21ac535e7664e78d669399de703cb72109a5d6b3943018f1dd43462eb71be512213f05e61b2c93bc5f65d270bf78122b00e24d38b0f98efdee072cf3b4c8d0a1905dcb70f21d51fdd376d5fcd258df6c3477a2421527d1702b848954fd7a3bbf710eda0c1880b79a996516ec37d616c13082219d90a7743ad8eb5e3faceec7ad6374029d52eaeca7b66c598b3dd7066e4b6246cea47794fdcffcf7891984272".to_string(),
+            vk_ic: vec![
+                "16aca3c7fb4157ef2f70fa4098434d97721a2ffa30f1ed64d3123cccb3928433899ab147217331f74f18ce687cc591700e79ca556db5b53e92f1133b889dbc11ef79615331a9a810cbef02d3a760b437a1bd50c1b6c396288abcb37479bc18a5".to_string(),
+                "0c1aeb08622db17dab3de7590db8f46349c7e08eff70fa63af8332db75b977bd0e630b04d8e28d4b3416381b27f4bded12e8067fd6f65bd436608cf66f0eb0c19b7da57b72785966d71b91229cde327918d14b3330b891bdfcf255e3d0ecfbfd".to_string(),
+            ],
+            point_encoding: None,
+            snarkjs_vkey: None,
+        };
+
+        let _res =
+            execute(deps, mock_env(), info, msg).expect("contract handles set zkeys parameters");
+    }
+
+    fn mock_alice_set_zkeys_with_cw20(
+        deps: DepsMut,
+        token: &str,
+        amount: u128,
+    ) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::Zkeys {
+            circuit_id: None,
+            metadata: None,
+            public_signals: vec!["33".to_string()],
+            public_signal_mode: None,
+            bind_sender: None,
+            nullifier_index: None,
+            bounty: None,
+            bounty_deadline: None,
+            vk_alpha1: "121ec6cddca2aa0dedf8dbb86e96dc4100b58ea07c01d7ea68a37f8f72191ab2bbe9f16bfe675f71c899ff11e23cbb04064831acc8c18f561f446eeaac3a9a056cb9a89b0b3f13a57eab4e97ebaff6f0a39327bd0a4b5f725d633c87474d35f2".to_string(),
+            vk_beta_2: "00f3edfbbbe5e2dab32cac1d1ba2f0fdd9eff4067c7152520f0ebbf556c21f98e72590b3cdb614b1ea116991305da942077b7419fac8cc2d38dc6639d68a4cf7c8362efd8395020836f3aa564537fa02a17f2d1b423c19b6cf4784037b1d9f1510afbae9e95703ff3a98c46720f05e642588ef21ccb09580c84d211c0fd60acda18a699f61cef4925b9b113c8a2377f0147c5ee0882a97519627776222438d3e29f581f0e4b61fe18ab42089dfe24a1b9d7376667382941e37329860ec84d105".to_string(),
+            vk_gamma_2: "13e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb80606c4a02ea734cc32acd2b02bc28b99cb3e287e85a763af267492ab572e99ab3f370d275cec1da1aaa9075ff05f79be0ce5d527727d6e118cc9cdc6da2e351aadfd9baa8cbdd3a76d429a695160d12c923ac9cc3baca289e193548608b82801".to_string(),
+            vk_delta_2: "07709351aa9646a1311053a0e4cfe6d7db03513beaebb9b87da192758e5ecd40d21ac535e7664e78d669399de703cb72109a5d6b3943018f1dd43462eb71be512213f05e61b2c93bc5f65d270bf78122b00e24d38b0f98efdee072cf3b4c8d0a1905dcb70f21d51fdd376d5fcd258df6c3477a2421527d1702b848954fd7a3bbf710eda0c1880b79a996516ec37d616c13082219d90a7743ad8eb5e3faceec7ad6374029d52eaeca7b66c598b3dd7066e4b6246cea47794fdcffcf7891984272".to_string(),
+            vk_ic: vec![
+                "16aca3c7fb4157ef2f70fa4098434d97721a2ffa30f1ed64d3123cccb3928433899ab147217331f74f18ce687cc591700e79ca556db5b53e92f1133b889dbc11ef79615331a9a810cbef02d3a760b437a1bd50c1b6c396288abcb37479bc18a5".to_string(),
+                "0c1aeb08622db17dab3de7590db8f46349c7e08eff70fa63af8332db75b977bd0e630b04d8e28d4b3416381b27f4bded12e8067fd6f65bd436608cf66f0eb0c19b7da57b72785966d71b91229cde327918d14b3330b891bdfcf255e3d0ecfbfd".to_string(),
+            ],
+            point_encoding: None,
+            snarkjs_vkey: None,
+        };
+
+        let wrapper = Cw20ReceiveMsg {
+            sender: "alice_key".to_string(),
+            amount: Uint128::new(amount),
+            msg: to_binary(&msg).unwrap(),
+        };
+
+        execute(
+            deps,
+            mock_env(),
+            mock_info(token, &[]),
+            ExecuteMsg::Receive(wrapper),
+        )
+    }
+
+    fn mock_alice_set_zkeys_with_bounty(deps: DepsMut, sent: &[Coin]) {
+        // alice pays 10 token to whoever solves it first
+        let info = mock_info("alice_key", sent);
+        let msg = ExecuteMsg::Zkeys {
+            circuit_id: None,
+            metadata: None,
+            public_signals: vec!["33".to_string()],
+            public_signal_mode: None,
+            bind_sender: None,
+            nullifier_index: None,
+            bounty: Some(coin(10, "token")),
+            bounty_deadline: Some(mock_env().block.height + 10),
+            vk_alpha1: "121ec6cddca2aa0dedf8dbb86e96dc4100b58ea07c01d7ea68a37f8f72191ab2bbe9f16bfe675f71c899ff11e23cbb04064831acc8c18f561f446eeaac3a9a056cb9a89b0b3f13a57eab4e97ebaff6f0a39327bd0a4b5f725d633c87474d35f2".to_string(),
+            vk_beta_2: "00f3edfbbbe5e2dab32cac1d1ba2f0fdd9eff4067c7152520f0ebbf556c21f98e72590b3cdb614b1ea116991305da942077b7419fac8cc2d38dc6639d68a4cf7c8362efd8395020836f3aa564537fa02a17f2d1b423c19b6cf4784037b1d9f1510afbae9e95703ff3a98c46720f05e642588ef21ccb09580c84d211c0fd60acda18a699f61cef4925b9b113c8a2377f0147c5ee0882a97519627776222438d3e29f581f0e4b61fe18ab42089dfe24a1b9d7376667382941e37329860ec84d105".to_string(),
+            vk_gamma_2: "13e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb80606c4a02ea734cc32acd2b02bc28b99cb3e287e85a763af267492ab572e99ab3f370d275cec1da1aaa9075ff05f79be0ce5d527727d6e118cc9cdc6da2e351aadfd9baa8cbdd3a76d429a695160d12c923ac9cc3baca289e193548608b82801".to_string(),
+            vk_delta_2: "07709351aa9646a1311053a0e4cfe6d7db03513beaebb9b87da192758e5ecd40d21ac535e7664e78d669399de703cb72109a5d6b3943018f1dd43462eb71be512213f05e61b2c93bc5f65d270bf78122b00e24d38b0f98efdee072cf3b4c8d0a1905dcb70f21d51fdd376d5fcd258df6c3477a2421527d1702b848954fd7a3bbf710eda0c1880b79a996516ec37d616c13082219d90a7743ad8eb5e3faceec7ad6374029d52eaeca7b66c598b3dd7066e4b6246cea47794fdcffcf7891984272".to_string(),
+            vk_ic: vec![
+                "16aca3c7fb4157ef2f70fa4098434d97721a2ffa30f1ed64d3123cccb3928433899ab147217331f74f18ce687cc591700e79ca556db5b53e92f1133b889dbc11ef79615331a9a810cbef02d3a760b437a1bd50c1b6c396288abcb37479bc18a5".to_string(),
+                "0c1aeb08622db17dab3de7590db8f46349c7e08eff70fa63af8332db75b977bd0e630b04d8e28d4b3416381b27f4bded12e8067fd6f65bd436608cf66f0eb0c19b7da57b72785966d71b91229cde327918d14b3330b891bdfcf255e3d0ecfbfd".to_string(),
+            ],
+            point_encoding: None,
+            snarkjs_vkey: None,
+        };
+
+        let _res =
+            execute(deps, mock_env(), info, msg).expect("contract handles set zkeys parameters");
+    }
+    fn mock_alice_set_zkeys_with_prover_public_signal(deps: DepsMut, sent: &[Coin]) {
+        // alice can register an available name
+        let info = mock_info("alice_key", sent);
+        let msg = ExecuteMsg::Zkeys {
+            circuit_id: None,
+            metadata: None,
+            //NOTE: provers supply the public signal
+            public_signals: vec![],
+            public_signal_mode: Some(PublicSignalMode::Prover),
+            bind_sender: None,
+            nullifier_index: None,
+            bounty: None,
+            bounty_deadline: None,
+            vk_alpha1: "121ec6cddca2aa0dedf8dbb86e96dc4100b58ea07c01d7ea68a37f8f72191ab2bbe9f16bfe675f71c899ff11e23cbb04064831acc8c18f561f446eeaac3a9a056cb9a89b0b3f13a57eab4e97ebaff6f0a39327bd0a4b5f725d633c87474d35f2".to_string(),
+            vk_beta_2: "00f3edfbbbe5e2dab32cac1d1ba2f0fdd9eff4067c7152520f0ebbf556c21f98e72590b3cdb614b1ea116991305da942077b7419fac8cc2d38dc6639d68a4cf7c8362efd8395020836f3aa564537fa02a17f2d1b423c19b6cf4784037b1d9f1510afbae9e95703ff3a98c46720f05e642588ef21ccb09580c84d211c0fd60acda18a699f61cef4925b9b113c8a2377f0147c5ee0882a97519627776222438d3e29f581f0e4b61fe18ab42089dfe24a1b9d7376667382941e37329860ec84d105".to_string(),
+            vk_gamma_2: "13e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb80606c4a02ea734cc32acd2b02bc28b99cb3e287e85a763af267492ab572e99ab3f370d275cec1da1aaa9075ff05f79be0ce5d527727d6e118cc9cdc6da2e351aadfd9baa8cbdd3a76d429a695160d12c923ac9cc3baca289e193548608b82801".to_string(),
+            vk_delta_2: "07709351aa9646a1311053a0e4cfe6d7db03513beaebb9b87da192758e5ecd40d21ac535e7664e78d669399de703cb72109a5d6b3943018f1dd43462eb71be512213f05e61b2c93bc5f65d270bf78122b00e24d38b0f98efdee072cf3b4c8d0a1905dcb70f21d51fdd376d5fcd258df6c3477a2421527d1702b848954fd7a3bbf710eda0c1880b79a996516ec37d616c13082219d90a7743ad8eb5e3faceec7ad6374029d52eaeca7b66c598b3dd7066e4b6246cea47794fdcffcf7891984272".to_string(),
+            vk_ic: vec![
+                "16aca3c7fb4157ef2f70fa4098434d97721a2ffa30f1ed64d3123cccb3928433899ab147217331f74f18ce687cc591700e79ca556db5b53e92f1133b889dbc11ef79615331a9a810cbef02d3a760b437a1bd50c1b6c396288abcb37479bc18a5".to_string(),
+                "0c1aeb08622db17dab3de7590db8f46349c7e08eff70fa63af8332db75b977bd0e630b04d8e28d4b3416381b27f4bded12e8067fd6f65bd436608cf66f0eb0c19b7da57b72785966d71b91229cde327918d14b3330b891bdfcf255e3d0ecfbfd".to_string(),
+            ],
+            point_encoding: None,
+            snarkjs_vkey: None,
+        };
+
+        let _res =
+            execute(deps, mock_env(), info, msg).expect("contract handles set zkeys parameters");
+    }
+
+    fn mock_alice_set_zkeys_with_extra_public_signal(
+        deps: DepsMut,
+        sent: &[Coin],
+    ) -> Result<Response, ContractError> {
+        // alice can register an available name
+        let info = mock_info("alice_key", sent);
+        let msg = ExecuteMsg::Zkeys {
+            circuit_id: None,
+            metadata: None,
+            //NOTE: two public signals for a key with a single public input
+            public_signals: vec!["33".to_string(), "1".to_string()],
+            public_signal_mode: None,
+            bind_sender: None,
+            nullifier_index: None,
+            bounty: None,
+            bounty_deadline: None,
+            vk_alpha1: "121ec6cddca2aa0dedf8dbb86e96dc4100b58ea07c01d7ea68a37f8f72191ab2bbe9f16bfe675f71c899ff11e23cbb04064831acc8c18f561f446eeaac3a9a056cb9a89b0b3f13a57eab4e97ebaff6f0a39327bd0a4b5f725d633c87474d35f2".to_string(),
+            vk_beta_2: "00f3edfbbbe5e2dab32cac1d1ba2f0fdd9eff4067c7152520f0ebbf556c21f98e72590b3cdb614b1ea116991305da942077b7419fac8cc2d38dc6639d68a4cf7c8362efd8395020836f3aa564537fa02a17f2d1b423c19b6cf4784037b1d9f1510afbae9e95703ff3a98c46720f05e642588ef21ccb09580c84d211c0fd60acda18a699f61cef4925b9b113c8a2377f0147c5ee0882a97519627776222438d3e29f581f0e4b61fe18ab42089dfe24a1b9d7376667382941e37329860ec84d105".to_string(),
+            vk_gamma_2: "13e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb80606c4a02ea734cc32acd2b02bc28b99cb3e287e85a763af267492ab572e99ab3f370d275cec1da1aaa9075ff05f79be0ce5d527727d6e118cc9cdc6da2e351aadfd9baa8cbdd3a76d429a695160d12c923ac9cc3baca289e193548608b82801".to_string(),
+            vk_delta_2: "07709351aa9646a1311053a0e4cfe6d7db03513beaebb9b87da192758e5ecd40d21ac535e7664e78d669399de703cb72109a5d6b3943018f1dd43462eb71be512213f05e61b2c93bc5f65d270bf78122b00e24d38b0f98efdee072cf3b4c8d0a1905dcb70f21d51fdd376d5fcd258df6c3477a2421527d1702b848954fd7a3bbf710eda0c1880b79a996516ec37d616c13082219d90a7743ad8eb5e3faceec7ad6374029d52eaeca7b66c598b3dd7066e4b6246cea47794fdcffcf7891984272".to_string(),
+            vk_ic: vec![
+                "16aca3c7fb4157ef2f70fa4098434d97721a2ffa30f1ed64d3123cccb3928433899ab147217331f74f18ce687cc591700e79ca556db5b53e92f1133b889dbc11ef79615331a9a810cbef02d3a760b437a1bd50c1b6c396288abcb37479bc18a5".to_string(),
+                "0c1aeb08622db17dab3de7590db8f46349c7e08eff70fa63af8332db75b977bd0e630b04d8e28d4b3416381b27f4bded12e8067fd6f65bd436608cf66f0eb0c19b7da57b72785966d71b91229cde327918d14b3330b891bdfcf255e3d0ecfbfd".to_string(),
+            ],
+            point_encoding: None,
+            snarkjs_vkey: None,
+        };
+
+        execute(deps, mock_env(), info, msg)
+    }
+
+    fn mock_alice_set_zkeys_bound_to_sender(deps: DepsMut, sent: &[Coin]) {
+        // circuit proving w * w = 25, with the sender signal as second public input
+        let info = mock_info("alice_key", sent);
+        let msg = ExecuteMsg::Zkeys {
+            circuit_id: None,
+            metadata: None,
+            public_signals: vec!["25".to_string()],
+            public_signal_mode: None,
+            bind_sender: Some(true),
+            nullifier_index: None,
+            bounty: None,
+            bounty_deadline: None,
+            vk_alpha1: "01eddf6b8a05caf98fb73de9fc29c281f16adb3b3e17357bbd548c3d56c491fa4ad357827f12de02b81f42cdaaf43171078dd4ec8ddba86fe301f9099e7244b618b92eb8f19bef3c83cdc6ef7be497f1f257728c960001ba2ce54eddff8efe08".to_string(),
+            vk_beta_2: "0ebd827449d55bf11e1b8e40d81c57bb79f5287ea910f27829666737335341d3922541e4962d446e11e8fae3fee77c950cf1bf2d438da16f7f2fcce372855722b4b1a4c5b35cc0d40a3f5d1c6e0d07505c8ca9eaac271c5130b2273171bb88e80fa18e262fea34c820e727ad43c4c04e7477da9e61a1900d988d77f87790acfcb7ca52a9aa1e231d1aa40cc7767a7cc91207e9d76bb22ed8d0fd1ec1b8c1d38b542f6d9950cf68dcafa55057d06452683cf63b6b3df3f7776c7f51312fb12f47".to_string(),
+            vk_gamma_2: "03dd45b82d87ed161bbf855337586fea5765e7dc85f8bbcc3d4a3394905df921d1cb962536b28402717cc1b0c1ada4d4194827dc6932f2c28fb4b58b8d8e7045f989a940fab39a8af91d5ccbd86d91f6062b509d05faaa61b2492039aeae417d0753b08dd835163d6fd6b09527c66f5955db4500d2db982181b502bc359541ef9da9d65bb7f2a4083719a2862c19f922137d5a832a35722784f9f6487af9594b2caeb2bd3b910e8b1f36aef5ccd23797ff54f27ca07ea2e7faa4547ce5159a22".to_string(),
+            vk_delta_2: "1911a65bfbea20bbf9e78219772fbc7cb74fcb5e24e32f14a2c484cad41c4217526c4557106607c393e1c4a29c430e7106a38ff96f2c4083b1a5202ad743978b84341687d9784969575bf1ce35775e49054f367d7b07e3f5d5b7653f0fb463cd03e311b2177fbfe122dd64328bd750f6b1b1b6ad7f4a579e948e55facf4cd779589799b198c2ea86fcc5db2efaab17b91686313d64e0a0e970476fa3e12944598ce5a3caf2aef869564dcbc7ec362afd9fe263f480b9208b8598d7062409c806".to_string(),
+            vk_ic: vec![
+                "0bc41192b404acf94c0850587153434ae56960ef82382d71ddd36c8a3abb8933bf53a9f20bd5342d79a16c11ad128a9706a405128322d1fb1bc387332c633cb7dbbf8c1d8185c1a2e5b19086a9458dc8dc2962f61de9e0fc4d646795b6fbaae3".to_string(),
+                "1260d408468e96902a0529eefb2da05ee2b3c80346912debb449105ed3ed09e4ff14617588c89e12c146865ab22221240bec78086ae2dfc755a0d445a476b7246eaf5c900ea6f6f33a6168f8d19908fa07b89f86227f93c25b209a38b293e2b3".to_string(),
+                "0a2fc1494353a52376d8eba63438e929bbbf983b4ddf9005096831a050271264493282f8e50c85fa86fe9b0ee4a1ecde0a1533a5cf65ea977d7a00ffa3a5e8c0fc66ec40800ac3763b3c1989152b52671ae79a568b59316ce6d03a6618938e12".to_string(),
+            ],
+            point_encoding: None,
+            snarkjs_vkey: None,
+        };
+
+        let _res =
+            execute(deps, mock_env(), info, msg).expect("contract handles set zkeys parameters");
+    }
+
+    fn mock_alice_set_zkeys_with_nullifier(deps: DepsMut, sent: &[Coin]) {
+        // circuit proving w * w = x, the second public input is the nullifier
+        let info = mock_info("alice_key", sent);
+        let msg = ExecuteMsg::Zkeys {
+            circuit_id: None,
+            metadata: None,
+            public_signals: vec![],
+            public_signal_mode: Some(PublicSignalMode::Prover),
+            bind_sender: None,
+            nullifier_index: Some(1),
+            bounty: None,
+            bounty_deadline: None,
+            vk_alpha1: "16ab352f52dd0abdee48a8852a30620031988b3e658bb877fae4312bd7509b4313433edecbbc720ac4b74d4ce23769e012a20f7578cf65b7afd49e7adab5d125ff91f42ed15edaea1a94c6cdc73cf493f8aa8e94367a966c4cf53a9deb6287df".to_string(),
+            vk_beta_2: "06a47c31a640375aaa534a57719f0581fef45b3201ea76c07ea997ac4a9fa9b6b5cff51e6fbba1e94312702631e7f83d1322f5e1fb855feef949e1c86dcb6a50804c5bf5a52d2f9773edb7d778bbf8c0b65a68cbd84acf48142f83679e522e1d1199d0c90ba55bc38b6b0b49edb2f919405b0b721b16b2d527d7156e5e32457d27ece44483c56a03daaef151d174d1d4140c388f4c6f3f100c7b7533ea26e52efdf6bdc62e2ed64a1784777de1af113630e315980eba15421364e67204ffc298".to_string(),
+            vk_gamma_2: "0f15b6ab9d51465f3b5ddc0569df67312f9dbcc10a389dc4647554143038a40933f67ee4a2e797ca331fded7cd9dc49900920e8ca4218c48e37539db5ef35dcfbdc696347d02f2e554faba0f51b7dce55ddb02528558185673721bcbf53baa97187a010e323b87ce4ead15b378fbc51d7c70fa11329091e14c0e1af34d0adc334823629351291a17b71724718b38607406b192b7f346023cb4a67b89fcceeffd895407237aeee4db218d568a8e602b5b7686c426c667e0e73658a6eff1123690".to_string(),
+            vk_delta_2: "02aab99a03d4ef7eb2a6272c0c6c360a567db33a9c2a29ab985d3b8e65da19b6119e6ddb59926335b118ee245266601608aa276683b5be7ef5ea429aabe5c78717fd0e00fd9bebb215f58f81d366f04d62e3d7a74d7162c7fb7c514d0c3a64f214bdf0a5e4a3fe3cf0a7c957820f89cef12ce15cfef2404b3533d6aa409f3d40868be20c17262d0e2eb96c15e9409c6d0000c6673f5391466c2ae72c28c9c8099b453fb0da89ef9e56bcc507ec7af337043b62334ffac4bcf98cb4896dfba6da".to_string(),
+            vk_ic: vec![
+                "167c2dbf48b2f21e481b32f8e5237ba14be75c061464f4913dd24010aeefe1b42c0a880ea2bab40c89473dfd00756aed099e56f5b5c888107b6f822a042adbb76add4e986d04147ab6c2c510dc45785f062f41918926417f7028063fd69fa22e".to_string(),
+                "0c63048bad092c68ef1d1ce927f8242cfb863b0129ca4b77b014e65677b86a71f4f33db6a4996af5e5cf33e0a695a5980c0ebecb119a9f76ad474ff6ad5df9c2dc69eb9e75fa8fecbe0aca4a33e8cace5e484e62f0320e42232e25365b2de2a3".to_string(),
+                "0a6b06d162344bc53e8141d3fe319f94b1ca28e409c01aa3f2e5a9e8ebd3814160e90a9c48e313105a217d8499080d7a117c7bd4330196b29f2b9f0142893108bc0ecb7e11ed7ca554cad4744c0366748fa9412f6f679b45fccab61385eab319".to_string(),
+            ],
+            point_encoding: None,
+            snarkjs_vkey: None,
         };
 
-        let _res = execute(deps, mock_env(), info, msg)
-            .expect("contract handles set zkeys parameters");
+        let _res =
+            execute(deps, mock_env(), info, msg).expect("contract handles set zkeys parameters");
     }
 
     fn mock_bob_publish_proof_to_verify(deps: DepsMut, sent: &[Coin]) {
         let info = mock_info("bob_key", sent);
-        let msg = ExecuteMsg::Proof { 
-            difficuty_issuer: "alice_key".to_string(), 
-            proof_a: "020fcbc0e3ae8e322a5cb6fb707d2511878eda020096f8f421cf75c263e43c2ee6d2392b6e03410d5555fd80628581f1054ce4ab8c9c277ce545b05efc145a1aeecd84038c67972a55367b2e1181c19311a7b3a3aa2b2cd70c4823db3ae498a0".to_string(), 
-            proof_b: "1545a18455dd6e1abaf4e27f3ee198bb5abb199b0650030593ce0d03b7cc59d458864acc3db510efe2300f778aa797e017c8d8fa15654b1995f0e659910bbdf8c0d88ef6801e1615e664b559daa8fd139b88569e95e6058d077fb5ae6aafe93116d6254de64023b0e8b41b145bb43d53bbee70486de6dd67c00f4f05c5e6a563f3b808b942184fa3488ace3a57e90016106fcc94b8d3d95c52ca1a616348b9095e7df0ba97156e4e93e787474d19e0ea423eda0bf5ebc81efce1b12f4c22ee00".to_string(), 
-            proof_c: "10406a2ada964c701668b06be2e3011bf22d9b6c6c0731f5b042a6b7ccf777d58b8e8b8b19fd711953b170d591981eb80f373990aee796b4797bb6ee63f57cbc402ce8dc2360ef18e40c5a44e8d2948e94d6c7f226f384f6cf4c0190de295b87".to_string(), 
+        let msg = ExecuteMsg::Proof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proof_a: "020fcbc0e3ae8e322a5cb6fb707d2511878eda020096f8f421cf75c263e43c2ee6d2392b6e03410d5555fd80628581f1054ce4ab8c9c277ce545b05efc145a1aeecd84038c67972a55367b2e1181c19311a7b3a3aa2b2cd70c4823db3ae498a0".to_string(),
+            proof_b: "1545a18455dd6e1abaf4e27f3ee198bb5abb199b0650030593ce0d03b7cc59d458864acc3db510efe2300f778aa797e017c8d8fa15654b1995f0e659910bbdf8c0d88ef6801e1615e664b559daa8fd139b88569e95e6058d077fb5ae6aafe93116d6254de64023b0e8b41b145bb43d53bbee70486de6dd67c00f4f05c5e6a563f3b808b942184fa3488ace3a57e90016106fcc94b8d3d95c52ca1a616348b9095e7df0ba97156e4e93e787474d19e0ea423eda0bf5ebc81efce1b12f4c22ee00".to_string(),
+            proof_c: "10406a2ada964c701668b06be2e3011bf22d9b6c6c0731f5b042a6b7ccf777d58b8e8b8b19fd711953b170d591981eb80f373990aee796b4797bb6ee63f57cbc402ce8dc2360ef18e40c5a44e8d2948e94d6c7f226f384f6cf4c0190de295b87".to_string(),
+            point_encoding: None,
+            snarkjs_proof: None,
+            public_signals: None,
         };
 
-        let _res = execute(deps, mock_env(), info, msg)
-            .expect("contract handles verify proof failed");
+        let _res =
+            execute(deps, mock_env(), info, msg).expect("contract handles verify proof failed");
+    }
+
+    fn bob_proof() -> (String, String, String) {
+        (
+            "020fcbc0e3ae8e322a5cb6fb707d2511878eda020096f8f421cf75c263e43c2ee6d2392b6e03410d5555fd80628581f1054ce4ab8c9c277ce545b05efc145a1aeecd84038c67972a55367b2e1181c19311a7b3a3aa2b2cd70c4823db3ae498a0".to_string(),
+            "1545a18455dd6e1abaf4e27f3ee198bb5abb199b0650030593ce0d03b7cc59d458864acc3db510efe2300f778aa797e017c8d8fa15654b1995f0e659910bbdf8c0d88ef6801e1615e664b559daa8fd139b88569e95e6058d077fb5ae6aafe93116d6254de64023b0e8b41b145bb43d53bbee70486de6dd67c00f4f05c5e6a563f3b808b942184fa3488ace3a57e90016106fcc94b8d3d95c52ca1a616348b9095e7df0ba97156e4e93e787474d19e0ea423eda0bf5ebc81efce1b12f4c22ee00".to_string(),
+            "10406a2ada964c701668b06be2e3011bf22d9b6c6c0731f5b042a6b7ccf777d58b8e8b8b19fd711953b170d591981eb80f373990aee796b4797bb6ee63f57cbc402ce8dc2360ef18e40c5a44e8d2948e94d6c7f226f384f6cf4c0190de295b87".to_string(),
+        )
+    }
+
+    /// uncompressed hex points in the compressed encoding
+    fn compressed_g1(point: &str) -> String {
+        let bytes: [u8; 96] = hex::decode(point).unwrap().try_into().unwrap();
+        hex::encode(G1Affine::from_uncompressed(&bytes).unwrap().to_compressed())
+    }
+
+    fn compressed_g2(point: &str) -> String {
+        let bytes: [u8; 192] = hex::decode(point).unwrap().try_into().unwrap();
+        hex::encode(G2Affine::from_uncompressed(&bytes).unwrap().to_compressed())
+    }
+
+    /// bob's proof of 5 * 5 = 25 with the nullifier 7, for `mock_alice_set_zkeys_with_nullifier`
+    fn nullifier_proof() -> (String, String, String) {
+        (
+            "1570f63fcb28c5346b96e9bf9c748c2e8ec244f4f71c583fa7da3a7bcfd5ca55f81a5a754e63844ecf092692da0d717502a37b841bb8d2285a7d62dcff8db2f45dca4b480846ed6d690377006ecf26c763e8cab793a0c4df01be5d7cd196042a".to_string(),
+            "0c5a5df7cb5d03c3fc864868cb5d41c4f5f0e75757793b86b0e7419880349324833ff06aa91588ba26023c2387826cc011a8e13d8f4c393b44431d31e32ac516bd64258ab28ce6a2e066fa3d2bdd435e5ec61fa44e3f859edb0dffe697ffafeb03678528d1ce2bd904b0065bde165b48eff70ad280fc4267844afca5e6dfc3c0a04756d48d32a713026ef8b420ceef4e0984ed1c554578deb52562d4862f2176d9f9448b3f49618c0c8f9d4f644875a49b31fc243852b8a8f0e5fb7a302f5b1a".to_string(),
+            "07b2945c2aeb8170509cf0eb26b0755945563fc65a173680d116af9bdf58ddb37dd2b9d240b0ff81f27dbd3531566a82064519507398c9b1aa59c704ad54a997ab31d06d71486e7404fc1915b66a6e36a6f7e4bec7e5626634d92220151846d5".to_string(),
+        )
+    }
+
+    /// another valid proof of the same statement, (A / r, B * r, C) pairs to the same value
+    fn rerandomized_proof(
+        (proof_a, proof_b, proof_c): (String, String, String),
+    ) -> (String, String, String) {
+        let r = Scalar::from(7u64);
+        let a: [u8; 96] = hex::decode(proof_a).unwrap().try_into().unwrap();
+        let b: [u8; 192] = hex::decode(proof_b).unwrap().try_into().unwrap();
+        let a = G1Affine::from(G1Affine::from_uncompressed(&a).unwrap() * r.invert().unwrap());
+        let b = G2Affine::from(G2Affine::from_uncompressed(&b).unwrap() * r);
+        (
+            hex::encode(a.to_uncompressed()),
+            hex::encode(b.to_uncompressed()),
+            proof_c,
+        )
+    }
+
+    fn batch_proof(
+        prover: Option<&str>,
+        (proof_a, proof_b, proof_c): (String, String, String),
+    ) -> BatchProof {
+        BatchProof {
+            prover: prover.map(str::to_string),
+            proof_a,
+            proof_b,
+            proof_c,
+            point_encoding: None,
+            public_signals: None,
+        }
+    }
+
+    const ALICE_SNARKJS_VKEY: &str = r#"
+        {
+         "protocol": "groth16",
+         "curve": "bls12381",
+         "nPublic": 1,
+         "vk_alpha_1": ["2788957267726616085530946370333686468977008762217322955837756503525250759335221493076865381383569806409492745468676","966889520821565093682289897053241498883150617686420914123254313818917195703833293855015479256620741777821656757746","1"],
+         "vk_beta_2": [["1151622185055594789671375799125106257457295205005672811331962104863682134327046102769852317276957599826594043371285","146657050987419131971868536027256063958758809263114731402845620267729095345086144464467867673986115475627738769730"],["3153056692698078549782690074447845844588274699406149167000036143051725552308762508753748825851508873849913131454725","2568279069805977911582022295414550167364706272490707612862189747531652251774698189002913886134451629183095051352048"],["1","0"]],
+         "vk_gamma_2": [["352701069587466618187139116011060144890029952792775240219908644239793785735715026873347600343865175952761926303160","3059144344244213709971259814753781636986470325476647558659373206291635324768958432433509563104347017837885763365758"],["1985150602287291935568054521177171638300868978215655730859378665066344726373823718423869104263333984641494340347905","927553665492332455747201965776037880757740193453592970025027978793976877002675564980949289727957565575433344219582"],["1","0"]],
+         "vk_delta_2": [["2555433728187563150666293485043495573163815382515886729857315516933247311983239456330319505995594403413042574167306","1145082004832314148410985956073092295358281925900707511127880547496166889082771780001148972669112342657190494194546"],["2929257550173708274397454407315656261213124352415468828093630949074761800091975631133008466058500282848823469425266","3851376660874070765964177345411576264264114012330419968098725836297502738865247928219012117350953965349305063399788"],["1","0"]],
+         "vk_alphabeta_12": [],
+         "IC": [["3489905581577200376109889599762881569721495955934473009494839570534625268395968588437131308927948331393594031444336","2228020858232126140431469156668602201603711644494680578008647637323224668117823975550488279516225355885909651495077","1"],["1863152924304157501625168193429184421015175291866215447908491857313623353171631199046597471073612624501888913817069","2909953465816256174504609658537949236765410510172692752328267460811351942961672955340058764146148021328644515101693","1"]]
+        }
+    "#;
+
+    const BOB_SNARKJS_PROOF: &str = r#"
+        {
+         "pi_a": ["317325100637611439175715380817731156946917352934448213437787139069507854383158472052280805152843796936120854741489","815800719514590468765968086663173047858045796079893068314999260750323774055713136097136749639130594919336259131552","1"],
+         "pi_b": [["3660778953908565578703440087091142728834561998100892077604586472156257695140767349745797032103469841120174091004209","3274059806380246973030801749807407267742855455180020806181014143356755250255836934235192165755229551463457305892832"],["2529842040368286734184294392909138966074429256657326590360931992928005086412865048344422023341174863869845145054720","3514860075081707740135584048201639433029381688516507096912784004185587179481745886570025621893716731968976302178326"],["1","0"]],
+         "pi_c": ["2501353247605632011882741867998935016638311029034593638988929782479432231730294175950010544340276753790549152505528","2341913974385733591300003879180024712644040097239910088663629565958351924484962655830586371828705370817821036600199","1"],
+         "protocol": "groth16",
+         "curve": "bls12381"
+        }
+    "#;
+
+    fn mock_bob_publish_proof_with_cw20(
+        deps: DepsMut,
+        token: &str,
+        amount: u128,
+    ) -> Result<Response, ContractError> {
+        let (proof_a, proof_b, proof_c) = bob_proof();
+        let msg = ExecuteMsg::Proof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proof_a,
+            proof_b,
+            proof_c,
+            point_encoding: None,
+            snarkjs_proof: None,
+            public_signals: None,
+        };
+        let wrapper = Cw20ReceiveMsg {
+            sender: "bob_key".to_string(),
+            amount: Uint128::new(amount),
+            msg: to_binary(&msg).unwrap(),
+        };
+
+        execute(
+            deps,
+            mock_env(),
+            mock_info(token, &[]),
+            ExecuteMsg::Receive(wrapper),
+        )
+    }
+
+    fn mock_env_at(height: u64) -> Env {
+        let mut env = mock_env();
+        env.block.height = height;
+        env
+    }
+
+    fn mock_commit_bob_proof(deps: DepsMut, prover: &str, salt: &str, height: u64) {
+        let (proof_a, proof_b, proof_c) = bob_proof();
+        let proof_str = ProofStr {
+            pi_a: hex::decode(proof_a).unwrap(),
+            pi_b: hex::decode(proof_b).unwrap(),
+            pi_c: hex::decode(proof_c).unwrap(),
+            encoding: PointEncoding::Uncompressed,
+        };
+        let commitment = proof_commitment(&proof_str, &Addr::unchecked(prover), salt);
+        let msg = ExecuteMsg::CommitProof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            commitment: hex::encode(commitment),
+        };
+
+        let _res = execute(deps, mock_env_at(height), mock_info(prover, &[]), msg)
+            .expect("contract handles commit proof");
+    }
+
+    fn mock_reveal_bob_proof(
+        deps: DepsMut,
+        prover: &str,
+        salt: &str,
+        height: u64,
+    ) -> Result<Response, ContractError> {
+        let (proof_a, proof_b, proof_c) = bob_proof();
+        let msg = ExecuteMsg::RevealProof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proof_a,
+            proof_b,
+            proof_c,
+            point_encoding: None,
+            public_signals: None,
+            salt: salt.to_string(),
+        };
+
+        execute(deps, mock_env_at(height), mock_info(prover, &[]), msg)
+    }
+
+    fn mock_publish_bob_proof_with_public_signal(
+        deps: DepsMut,
+        prover: &str,
+        height: u64,
+    ) -> Result<Response, ContractError> {
+        let (proof_a, proof_b, proof_c) = bob_proof();
+        let msg = ExecuteMsg::Proof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proof_a,
+            proof_b,
+            proof_c,
+            point_encoding: None,
+            snarkjs_proof: None,
+            public_signals: Some(vec!["33".to_string()]),
+        };
+
+        execute(deps, mock_env_at(height), mock_info(prover, &[]), msg)
+    }
+
+    fn mock_reveal_bob_proof_with_public_signal(
+        deps: DepsMut,
+        prover: &str,
+        salt: &str,
+        height: u64,
+    ) -> Result<Response, ContractError> {
+        let (proof_a, proof_b, proof_c) = bob_proof();
+        let msg = ExecuteMsg::RevealProof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proof_a,
+            proof_b,
+            proof_c,
+            point_encoding: None,
+            public_signals: Some(vec!["33".to_string()]),
+            salt: salt.to_string(),
+        };
+
+        execute(deps, mock_env_at(height), mock_info(prover, &[]), msg)
     }
 
+    fn mock_publish_bob_proof(
+        deps: DepsMut,
+        prover: &str,
+        height: u64,
+    ) -> Result<Response, ContractError> {
+        let (proof_a, proof_b, proof_c) = bob_proof();
+        let msg = ExecuteMsg::Proof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proof_a,
+            proof_b,
+            proof_c,
+            point_encoding: None,
+            snarkjs_proof: None,
+            public_signals: None,
+        };
+
+        execute(deps, mock_env_at(height), mock_info(prover, &[]), msg)
+    }
 
     fn query_zkeys(deps: Deps) {
         let res = query(
-            deps, 
+            deps,
             mock_env(),
-            QueryMsg::IssuerZkeys { address: "alice_key".to_string() }
-        ).unwrap();
+            QueryMsg::IssuerZkeys {
+                address: "alice_key".to_string(),
+                circuit_id: None,
+            },
+        )
+        .unwrap();
 
         // get response
         let value: ZkeysResponse = from_binary(&res).unwrap();
         println!("zkey is :{:?}", value);
     }
-    
+
     fn query_verification_result(deps: Deps) {
         let res = query(
             deps,
             mock_env(),
-            QueryMsg::ProofResult { issuer_address: "alice_key".to_string(), prover_address: "bob_key".to_string() }
-        ).unwrap();
+            QueryMsg::ProofResult {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                prover_address: "bob_key".to_string(),
+            },
+        )
+        .unwrap();
 
         let value: ProofResponse = from_binary(&res).unwrap();
         print!("proof info is: {:?}", value);
@@ -153,7 +671,6 @@ mod test_module {
         query_zkeys(deps.as_ref());
     }
 
-
     #[test]
     fn verify_proof_and_query_works_with_no_price() {
         let mut deps = mock_dependencies();
@@ -177,4 +694,2909 @@ mod test_module {
         mock_bob_publish_proof_to_verify(deps.as_mut(), &[coin(2, "token")]);
         query_verification_result(deps.as_ref());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn set_zkeys_failed_with_mismatched_public_signals() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        // alice fixes more public signals than the key has IC points for
+        match mock_alice_set_zkeys_with_extra_public_signal(deps.as_mut(), &[]) {
+            Ok(_) => panic!("no proof could satisfy the key"),
+            Err(ContractError::PublicSignalCountMismatch {
+                expected: 1,
+                actual: 2,
+            }) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn verify_proof_and_query_works_with_prover_public_signal() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys_with_prover_public_signal(deps.as_mut(), &[]);
+
+        let info = mock_info("bob_key", &[]);
+        let msg = ExecuteMsg::Proof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proof_a: "020fcbc0e3ae8e322a5cb6fb707d2511878eda020096f8f421cf75c263e43c2ee6d2392b6e03410d5555fd80628581f1054ce4ab8c9c277ce545b05efc145a1aeecd84038c67972a55367b2e1181c19311a7b3a3aa2b2cd70c4823db3ae498a0".to_string(),
+            proof_b: "1545a18455dd6e1abaf4e27f3ee198bb5abb199b0650030593ce0d03b7cc59d458864acc3db510efe2300f778aa797e017c8d8fa15654b1995f0e659910bbdf8c0d88ef6801e1615e664b559daa8fd139b88569e95e6058d077fb5ae6aafe93116d6254de64023b0e8b41b145bb43d53bbee70486de6dd67c00f4f05c5e6a563f3b808b942184fa3488ace3a57e90016106fcc94b8d3d95c52ca1a616348b9095e7df0ba97156e4e93e787474d19e0ea423eda0bf5ebc81efce1b12f4c22ee00".to_string(),
+            proof_c: "10406a2ada964c701668b06be2e3011bf22d9b6c6c0731f5b042a6b7ccf777d58b8e8b8b19fd711953b170d591981eb80f373990aee796b4797bb6ee63f57cbc402ce8dc2360ef18e40c5a44e8d2948e94d6c7f226f384f6cf4c0190de295b87".to_string(),
+            point_encoding: None,
+            snarkjs_proof: None,
+            public_signals: Some(vec!["33".to_string()]),
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract handles verify proof failed");
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProofResult {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                prover_address: "bob_key".to_string(),
+            },
+        )
+        .unwrap();
+        let value: ProofResponse = from_binary(&res).unwrap();
+        assert_eq!(value.public_signals, vec!["33".to_string()]);
+    }
+
+    #[test]
+    fn verify_proof_failed_without_prover_public_signal() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys_with_prover_public_signal(deps.as_mut(), &[]);
+
+        let info = mock_info("bob_key", &[]);
+        let msg = ExecuteMsg::Proof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proof_a: "020fcbc0e3ae8e322a5cb6fb707d2511878eda020096f8f421cf75c263e43c2ee6d2392b6e03410d5555fd80628581f1054ce4ab8c9c277ce545b05efc145a1aeecd84038c67972a55367b2e1181c19311a7b3a3aa2b2cd70c4823db3ae498a0".to_string(),
+            proof_b: "1545a18455dd6e1abaf4e27f3ee198bb5abb199b0650030593ce0d03b7cc59d458864acc3db510efe2300f778aa797e017c8d8fa15654b1995f0e659910bbdf8c0d88ef6801e1615e664b559daa8fd139b88569e95e6058d077fb5ae6aafe93116d6254de64023b0e8b41b145bb43d53bbee70486de6dd67c00f4f05c5e6a563f3b808b942184fa3488ace3a57e90016106fcc94b8d3d95c52ca1a616348b9095e7df0ba97156e4e93e787474d19e0ea423eda0bf5ebc81efce1b12f4c22ee00".to_string(),
+            proof_c: "10406a2ada964c701668b06be2e3011bf22d9b6c6c0731f5b042a6b7ccf777d58b8e8b8b19fd711953b170d591981eb80f373990aee796b4797bb6ee63f57cbc402ce8dc2360ef18e40c5a44e8d2948e94d6c7f226f384f6cf4c0190de295b87".to_string(),
+            point_encoding: None,
+            snarkjs_proof: None,
+            public_signals: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+        match res {
+            Ok(_) => panic!("proof should fail without public signals"),
+            Err(ContractError::NoPublicSignal {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn verify_proof_failed_with_wrong_prover_public_signal() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys_with_prover_public_signal(deps.as_mut(), &[]);
+
+        let info = mock_info("bob_key", &[]);
+        let msg = ExecuteMsg::Proof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proof_a: "020fcbc0e3ae8e322a5cb6fb707d2511878eda020096f8f421cf75c263e43c2ee6d2392b6e03410d5555fd80628581f1054ce4ab8c9c277ce545b05efc145a1aeecd84038c67972a55367b2e1181c19311a7b3a3aa2b2cd70c4823db3ae498a0".to_string(),
+            proof_b: "1545a18455dd6e1abaf4e27f3ee198bb5abb199b0650030593ce0d03b7cc59d458864acc3db510efe2300f778aa797e017c8d8fa15654b1995f0e659910bbdf8c0d88ef6801e1615e664b559daa8fd139b88569e95e6058d077fb5ae6aafe93116d6254de64023b0e8b41b145bb43d53bbee70486de6dd67c00f4f05c5e6a563f3b808b942184fa3488ace3a57e90016106fcc94b8d3d95c52ca1a616348b9095e7df0ba97156e4e93e787474d19e0ea423eda0bf5ebc81efce1b12f4c22ee00".to_string(),
+            proof_c: "10406a2ada964c701668b06be2e3011bf22d9b6c6c0731f5b042a6b7ccf777d58b8e8b8b19fd711953b170d591981eb80f373990aee796b4797bb6ee63f57cbc402ce8dc2360ef18e40c5a44e8d2948e94d6c7f226f384f6cf4c0190de295b87".to_string(),
+            point_encoding: None,
+            snarkjs_proof: None,
+            public_signals: Some(vec!["30".to_string()]),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+        match res {
+            Ok(_) => panic!("proof should fail with a wrong public signal"),
+            Err(ContractError::InvalidProof {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn verify_proof_bound_to_sender_works() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys_bound_to_sender(deps.as_mut(), &[]);
+
+        let info = mock_info("bob_key", &[]);
+        let msg = ExecuteMsg::Proof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proof_a: "10a1d25e5547b8e72ab9be8927f27d06a979f0ca03b0a89f1ee3ff7d3b09ac7066b2b60bc369fa7b775ad68a07a5cc5716db38674cf4b3f124c08b0e29ce8edab622d470bb72a78009e68f8382bbc2970368d5f9aa501af292bafdb7093f89fb".to_string(),
+            proof_b: "1282487321d96676e063fb8432aae85c207fe14d5befd1d6ef5172bf24a83335d2398bcaa8c08374474835122e8818bc0c67d6c98e57d708e198eccb776fb4d1e9ea51b600392ee04e083ab79c82a208b7621dc838f93c76052d4c2a2fb607f318256f7b6e58568c3562d54f2be1ea91d372144156947dbe5e45336b2d8a4b77e288f8cbb945dcf95c827466ae9ce3680384827ac9fe2629e8450210743f6a8b7c0d25166d9c7f6f5977b96f0edf63bb0f6664a3fa6ac2632ded6f43563e8d20".to_string(),
+            proof_c: "004ea3d1fd3c8e1b67b6fbc46890a00bbf1d9ad4251a002fc1b233746ba8fd836d6646a3550f93cd4907f07be1f41e6207767430c02570f1e59812a962f9fd7ee38448f4a6f9d4e148cf975ac9d71a88ab593c70f84e87e19cea08719ec53378".to_string(),
+            point_encoding: None,
+            snarkjs_proof: None,
+            public_signals: None,
+        };
+        let _res = execute(deps.as_mut(), mock_env(), info, msg)
+            .expect("contract handles verify proof failed");
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProofResult {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                prover_address: "bob_key".to_string(),
+            },
+        )
+        .unwrap();
+        let value: ProofResponse = from_binary(&res).unwrap();
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::SenderSignal {
+                address: "bob_key".to_string(),
+            },
+        )
+        .unwrap();
+        let sender_signal: SenderSignalResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            value.public_signals,
+            vec!["25".to_string(), sender_signal.public_signal]
+        );
+    }
+
+    #[test]
+    fn verify_proof_bound_to_sender_failed_with_copied_proof() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys_bound_to_sender(deps.as_mut(), &[]);
+
+        // carol replays the proof bob generated for his own address
+        let info = mock_info("carol_key", &[]);
+        let msg = ExecuteMsg::Proof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proof_a: "10a1d25e5547b8e72ab9be8927f27d06a979f0ca03b0a89f1ee3ff7d3b09ac7066b2b60bc369fa7b775ad68a07a5cc5716db38674cf4b3f124c08b0e29ce8edab622d470bb72a78009e68f8382bbc2970368d5f9aa501af292bafdb7093f89fb".to_string(),
+            proof_b: "1282487321d96676e063fb8432aae85c207fe14d5befd1d6ef5172bf24a83335d2398bcaa8c08374474835122e8818bc0c67d6c98e57d708e198eccb776fb4d1e9ea51b600392ee04e083ab79c82a208b7621dc838f93c76052d4c2a2fb607f318256f7b6e58568c3562d54f2be1ea91d372144156947dbe5e45336b2d8a4b77e288f8cbb945dcf95c827466ae9ce3680384827ac9fe2629e8450210743f6a8b7c0d25166d9c7f6f5977b96f0edf63bb0f6664a3fa6ac2632ded6f43563e8d20".to_string(),
+            proof_c: "004ea3d1fd3c8e1b67b6fbc46890a00bbf1d9ad4251a002fc1b233746ba8fd836d6646a3550f93cd4907f07be1f41e6207767430c02570f1e59812a962f9fd7ee38448f4a6f9d4e148cf975ac9d71a88ab593c70f84e87e19cea08719ec53378".to_string(),
+            point_encoding: None,
+            snarkjs_proof: None,
+            public_signals: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
+        match res {
+            Ok(_) => panic!("copied proof should fail for another sender"),
+            Err(ContractError::InvalidProof {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn commit_and_reveal_proof_works() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys(deps.as_mut(), &[]);
+
+        mock_commit_bob_proof(deps.as_mut(), "bob_key", "salt", 100);
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Commitment {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                prover_address: "bob_key".to_string(),
+            },
+        )
+        .unwrap();
+        let value: CommitmentResponse = from_binary(&res).unwrap();
+        assert_eq!(value.height, 100);
+        assert_eq!(value.expires_at, 200);
+
+        mock_reveal_bob_proof(deps.as_mut(), "bob_key", "salt", 101)
+            .expect("contract handles reveal proof");
+        query_verification_result(deps.as_ref());
+    }
+
+    #[test]
+    fn reveal_proof_failed_with_invalid_commitment() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys(deps.as_mut(), &[]);
+
+        match mock_reveal_bob_proof(deps.as_mut(), "bob_key", "salt", 101) {
+            Err(ContractError::NoCommitment {}) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+
+        mock_commit_bob_proof(deps.as_mut(), "bob_key", "salt", 100);
+        match mock_reveal_bob_proof(deps.as_mut(), "bob_key", "salt", 100) {
+            Err(ContractError::RevealTooEarly {}) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+        match mock_reveal_bob_proof(deps.as_mut(), "bob_key", "pepper", 101) {
+            Err(ContractError::CommitmentMismatch {}) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+        match mock_reveal_bob_proof(deps.as_mut(), "bob_key", "salt", 201) {
+            Err(ContractError::CommitmentExpired {}) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn reveal_proof_takes_credit_from_earlier_reveal() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys_with_prover_public_signal(deps.as_mut(), &[]);
+
+        mock_commit_bob_proof(deps.as_mut(), "bob_key", "salt", 100);
+        // carol copies bob's proof and lands it first
+        mock_publish_bob_proof_with_public_signal(deps.as_mut(), "carol_key", 101)
+            .expect("contract handles verify proof");
+        mock_reveal_bob_proof_with_public_signal(deps.as_mut(), "bob_key", "salt", 102)
+            .expect("contract handles reveal proof");
+
+        query_verification_result(deps.as_ref());
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProofResult {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                prover_address: "carol_key".to_string(),
+            },
+        );
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn reveal_proof_takes_credit_from_rerandomized_copy() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys_with_prover_public_signal(deps.as_mut(), &[]);
+
+        mock_commit_bob_proof(deps.as_mut(), "bob_key", "salt", 100);
+        // carol re-randomizes bob's proof, it hashes differently but proves the same statement
+        let (proof_a, proof_b, proof_c) = rerandomized_proof(bob_proof());
+        let msg = ExecuteMsg::Proof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proof_a,
+            proof_b,
+            proof_c,
+            point_encoding: None,
+            snarkjs_proof: None,
+            public_signals: Some(vec!["33".to_string()]),
+        };
+        execute(
+            deps.as_mut(),
+            mock_env_at(101),
+            mock_info("carol_key", &[]),
+            msg,
+        )
+        .expect("contract handles verify proof");
+        mock_reveal_bob_proof_with_public_signal(deps.as_mut(), "bob_key", "salt", 102)
+            .expect("contract handles reveal proof");
+
+        query_verification_result(deps.as_ref());
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProofResult {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                prover_address: "carol_key".to_string(),
+            },
+        );
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn reveal_proof_failed_with_later_commitment() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys_with_prover_public_signal(deps.as_mut(), &[]);
+
+        mock_publish_bob_proof_with_public_signal(deps.as_mut(), "bob_key", 100)
+            .expect("contract handles verify proof");
+        mock_commit_bob_proof(deps.as_mut(), "carol_key", "salt", 100);
+        match mock_reveal_bob_proof_with_public_signal(deps.as_mut(), "carol_key", "salt", 101) {
+            Err(ContractError::ProofAlreadyClaimed {}) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn issuer_circuit_credits_every_prover() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys(deps.as_mut(), &[]);
+
+        // the issuer's signals are the same statement for everyone, carol proves it after bob
+        mock_publish_bob_proof(deps.as_mut(), "bob_key", 100)
+            .expect("contract handles verify proof");
+        mock_publish_bob_proof(deps.as_mut(), "carol_key", 101)
+            .expect("contract handles verify proof");
+        mock_commit_bob_proof(deps.as_mut(), "dave_key", "salt", 102);
+        mock_reveal_bob_proof(deps.as_mut(), "dave_key", "salt", 103)
+            .expect("contract handles reveal proof");
+
+        for prover in ["bob_key", "carol_key", "dave_key"] {
+            let res = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::ProofResult {
+                    issuer_address: "alice_key".to_string(),
+                    circuit_id: None,
+                    prover_address: prover.to_string(),
+                },
+            )
+            .unwrap();
+            let value: ProofResponse = from_binary(&res).unwrap();
+            assert!(value.is_valid);
+        }
+    }
+
+    #[test]
+    fn verify_proof_with_nullifier_and_query_works() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys_with_nullifier(deps.as_mut(), &[]);
+
+        let info = mock_info("bob_key", &[]);
+        let msg = ExecuteMsg::Proof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proof_a: "1570f63fcb28c5346b96e9bf9c748c2e8ec244f4f71c583fa7da3a7bcfd5ca55f81a5a754e63844ecf092692da0d717502a37b841bb8d2285a7d62dcff8db2f45dca4b480846ed6d690377006ecf26c763e8cab793a0c4df01be5d7cd196042a".to_string(),
+            proof_b: "0c5a5df7cb5d03c3fc864868cb5d41c4f5f0e75757793b86b0e7419880349324833ff06aa91588ba26023c2387826cc011a8e13d8f4c393b44431d31e32ac516bd64258ab28ce6a2e066fa3d2bdd435e5ec61fa44e3f859edb0dffe697ffafeb03678528d1ce2bd904b0065bde165b48eff70ad280fc4267844afca5e6dfc3c0a04756d48d32a713026ef8b420ceef4e0984ed1c554578deb52562d4862f2176d9f9448b3f49618c0c8f9d4f644875a49b31fc243852b8a8f0e5fb7a302f5b1a".to_string(),
+            proof_c: "07b2945c2aeb8170509cf0eb26b0755945563fc65a173680d116af9bdf58ddb37dd2b9d240b0ff81f27dbd3531566a82064519507398c9b1aa59c704ad54a997ab31d06d71486e7404fc1915b66a6e36a6f7e4bec7e5626634d92220151846d5".to_string(),
+            point_encoding: None,
+            snarkjs_proof: None,
+            public_signals: Some(vec!["25".to_string(), "7".to_string()]),
+        };
+        let _res =
+            execute(deps.as_mut(), mock_env(), info, msg).expect("contract handles verify proof");
+        query_verification_result(deps.as_ref());
+
+        // adding the field modulus doesn't make a different nullifier
+        for (nullifier, spent) in [
+            ("7", true),
+            (
+                "52435875175126190479447740508185965837690552500527637822603658699938581184520",
+                true,
+            ),
+            ("8", false),
+        ] {
+            let res = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Nullifier {
+                    issuer_address: "alice_key".to_string(),
+                    circuit_id: None,
+                    nullifier: nullifier.to_string(),
+                },
+            )
+            .unwrap();
+            let value: NullifierResponse = from_binary(&res).unwrap();
+            assert_eq!(value.spent, spent);
+        }
+
+        // an unreadable nullifier is reported as the public signal it stands for
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Nullifier {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                nullifier: "seven".to_string(),
+            },
+        );
+        match res {
+            Err(StdError::GenericErr { msg, .. }) => assert_eq!(
+                msg,
+                ContractError::ParsePulbicSignalError { index: 1 }.to_string()
+            ),
+            res => panic!("Unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn verify_proof_failed_with_spent_nullifier() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys_with_nullifier(deps.as_mut(), &[]);
+
+        let info = mock_info("bob_key", &[]);
+        let msg = ExecuteMsg::Proof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proof_a: "1570f63fcb28c5346b96e9bf9c748c2e8ec244f4f71c583fa7da3a7bcfd5ca55f81a5a754e63844ecf092692da0d717502a37b841bb8d2285a7d62dcff8db2f45dca4b480846ed6d690377006ecf26c763e8cab793a0c4df01be5d7cd196042a".to_string(),
+            proof_b: "0c5a5df7cb5d03c3fc864868cb5d41c4f5f0e75757793b86b0e7419880349324833ff06aa91588ba26023c2387826cc011a8e13d8f4c393b44431d31e32ac516bd64258ab28ce6a2e066fa3d2bdd435e5ec61fa44e3f859edb0dffe697ffafeb03678528d1ce2bd904b0065bde165b48eff70ad280fc4267844afca5e6dfc3c0a04756d48d32a713026ef8b420ceef4e0984ed1c554578deb52562d4862f2176d9f9448b3f49618c0c8f9d4f644875a49b31fc243852b8a8f0e5fb7a302f5b1a".to_string(),
+            proof_c: "07b2945c2aeb8170509cf0eb26b0755945563fc65a173680d116af9bdf58ddb37dd2b9d240b0ff81f27dbd3531566a82064519507398c9b1aa59c704ad54a997ab31d06d71486e7404fc1915b66a6e36a6f7e4bec7e5626634d92220151846d5".to_string(),
+            point_encoding: None,
+            snarkjs_proof: None,
+            public_signals: Some(vec!["25".to_string(), "7".to_string()]),
+        };
+        let _res =
+            execute(deps.as_mut(), mock_env(), info, msg).expect("contract handles verify proof");
+
+        // carol re-randomizes bob's proof, it verifies but reuses the nullifier
+        let info = mock_info("carol_key", &[]);
+        let msg = ExecuteMsg::Proof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proof_a: "108d01ed22f6c9ff690ed65f0120dd7f5f813cc4479a3a9c5200e9caab6158c341b356a42412e2bd8a4cd50556b4fd6f104cf795c6aec72e855859d4a33b2b89992873cbec1208ea50e9d18603177fe7cb4d6d9b4875aa1a7dc2389a22548080".to_string(),
+            proof_b: "149508dd3e38c0c7729c15de236913e8a584bd773aa8851b274a6136a2bb018097d9d432b0f6681f0aced5e2458c3e8f0594b9a20fd190a26d4a921d6a25e5b39eefa2c1fef450a29b1f19db36227e18caa4e5f81112e54a7eeb806f100241e60117b96ac23f9dac6cc950c1b5b85be9a63529f8ac420d415b9c328252e0a00847ed37a6906be0326b69272f66d0f1070b4408eec80e7ae8d106ccdc4ff94a5ca56b9e12495286828ccfbf93748c8f1133a2751d3a614e764ad44d96eab609fe".to_string(),
+            proof_c: "173b8625ee8f2395af22779cda3256e15a72ef7ef4daf808473507917f65a9214c48aefe7515d1488e324932cba6db5404062933c84e576a7b63bab8d1ae708b513174a97d0a3c4b31b7f544ee0ff0135f7124807d4c939075e9ce19d10d3acf".to_string(),
+            point_encoding: None,
+            snarkjs_proof: None,
+            public_signals: Some(vec!["25".to_string(), "7".to_string()]),
+        };
+        match execute(deps.as_mut(), mock_env(), info, msg) {
+            Err(ContractError::NullifierAlreadySpent {}) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+
+        // nor does bob spend it twice with his own proof
+        let (proof_a, proof_b, proof_c) = nullifier_proof();
+        let msg = ExecuteMsg::Proof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proof_a,
+            proof_b,
+            proof_c,
+            point_encoding: None,
+            snarkjs_proof: None,
+            public_signals: Some(vec!["25".to_string(), "7".to_string()]),
+        };
+        match execute(deps.as_mut(), mock_env(), mock_info("bob_key", &[]), msg) {
+            Err(ContractError::NullifierAlreadySpent {}) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProverHistory {
+                prover_address: "bob_key".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: ProverHistoryResponse = from_binary(&res).unwrap();
+        assert_eq!(value.verifications.len(), 1);
+    }
+
+    #[test]
+    fn reveal_proof_takes_spent_nullifier_from_copy() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys_with_nullifier(deps.as_mut(), &[]);
+        let public_signals = Some(vec!["25".to_string(), "7".to_string()]);
+
+        let (proof_a, proof_b, proof_c) = nullifier_proof();
+        let proof_str = ProofStr {
+            pi_a: hex::decode(&proof_a).unwrap(),
+            pi_b: hex::decode(&proof_b).unwrap(),
+            pi_c: hex::decode(&proof_c).unwrap(),
+            encoding: PointEncoding::Uncompressed,
+        };
+        let msg = ExecuteMsg::CommitProof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            commitment: hex::encode(proof_commitment(
+                &proof_str,
+                &Addr::unchecked("bob_key"),
+                "salt",
+            )),
+        };
+        execute(
+            deps.as_mut(),
+            mock_env_at(100),
+            mock_info("bob_key", &[]),
+            msg,
+        )
+        .expect("contract handles commit proof");
+
+        // carol re-randomizes bob's proof before he reveals it
+        let publish = |(proof_a, proof_b, proof_c): (String, String, String)| ExecuteMsg::Proof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proof_a,
+            proof_b,
+            proof_c,
+            point_encoding: None,
+            snarkjs_proof: None,
+            public_signals: public_signals.clone(),
+        };
+        execute(
+            deps.as_mut(),
+            mock_env_at(101),
+            mock_info("carol_key", &[]),
+            publish(rerandomized_proof(nullifier_proof())),
+        )
+        .expect("contract handles verify proof");
+        let msg = ExecuteMsg::RevealProof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proof_a,
+            proof_b,
+            proof_c,
+            point_encoding: None,
+            public_signals: public_signals.clone(),
+            salt: "salt".to_string(),
+        };
+        execute(
+            deps.as_mut(),
+            mock_env_at(102),
+            mock_info("bob_key", &[]),
+            msg,
+        )
+        .expect("contract handles reveal proof");
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProofResult {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                prover_address: "carol_key".to_string(),
+            },
+        );
+        assert!(res.is_err());
+
+        // the nullifier moves once, it isn't spent again by either of them
+        for prover in ["bob_key", "carol_key"] {
+            match execute(
+                deps.as_mut(),
+                mock_env_at(103),
+                mock_info(prover, &[]),
+                publish(nullifier_proof()),
+            ) {
+                Err(ContractError::NullifierAlreadySpent {}) => {}
+                res => panic!("Unexpected result: {:?}", res),
+            }
+        }
+    }
+
+    #[test]
+    fn set_zkeys_failed_with_invalid_nullifier_index() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+
+        let info = mock_info("alice_key", &[]);
+        let msg = ExecuteMsg::Zkeys {
+            circuit_id: None,
+            metadata: None,
+            public_signals: vec![],
+            public_signal_mode: Some(PublicSignalMode::Prover),
+            bind_sender: None,
+            nullifier_index: Some(2),
+            bounty: None,
+            bounty_deadline: None,
+            vk_alpha1: "16ab352f52dd0abdee48a8852a30620031988b3e658bb877fae4312bd7509b4313433edecbbc720ac4b74d4ce23769e012a20f7578cf65b7afd49e7adab5d125ff91f42ed15edaea1a94c6cdc73cf493f8aa8e94367a966c4cf53a9deb6287df".to_string(),
+            vk_beta_2: "06a47c31a640375aaa534a57719f0581fef45b3201ea76c07ea997ac4a9fa9b6b5cff51e6fbba1e94312702631e7f83d1322f5e1fb855feef949e1c86dcb6a50804c5bf5a52d2f9773edb7d778bbf8c0b65a68cbd84acf48142f83679e522e1d1199d0c90ba55bc38b6b0b49edb2f919405b0b721b16b2d527d7156e5e32457d27ece44483c56a03daaef151d174d1d4140c388f4c6f3f100c7b7533ea26e52efdf6bdc62e2ed64a1784777de1af113630e315980eba15421364e67204ffc298".to_string(),
+            vk_gamma_2: "0f15b6ab9d51465f3b5ddc0569df67312f9dbcc10a389dc4647554143038a40933f67ee4a2e797ca331fded7cd9dc49900920e8ca4218c48e37539db5ef35dcfbdc696347d02f2e554faba0f51b7dce55ddb02528558185673721bcbf53baa97187a010e323b87ce4ead15b378fbc51d7c70fa11329091e14c0e1af34d0adc334823629351291a17b71724718b38607406b192b7f346023cb4a67b89fcceeffd895407237aeee4db218d568a8e602b5b7686c426c667e0e73658a6eff1123690".to_string(),
+            vk_delta_2: "02aab99a03d4ef7eb2a6272c0c6c360a567db33a9c2a29ab985d3b8e65da19b6119e6ddb59926335b118ee245266601608aa276683b5be7ef5ea429aabe5c78717fd0e00fd9bebb215f58f81d366f04d62e3d7a74d7162c7fb7c514d0c3a64f214bdf0a5e4a3fe3cf0a7c957820f89cef12ce15cfef2404b3533d6aa409f3d40868be20c17262d0e2eb96c15e9409c6d0000c6673f5391466c2ae72c28c9c8099b453fb0da89ef9e56bcc507ec7af337043b62334ffac4bcf98cb4896dfba6da".to_string(),
+            vk_ic: vec![
+                "167c2dbf48b2f21e481b32f8e5237ba14be75c061464f4913dd24010aeefe1b42c0a880ea2bab40c89473dfd00756aed099e56f5b5c888107b6f822a042adbb76add4e986d04147ab6c2c510dc45785f062f41918926417f7028063fd69fa22e".to_string(),
+                "0c63048bad092c68ef1d1ce927f8242cfb863b0129ca4b77b014e65677b86a71f4f33db6a4996af5e5cf33e0a695a5980c0ebecb119a9f76ad474ff6ad5df9c2dc69eb9e75fa8fecbe0aca4a33e8cace5e484e62f0320e42232e25365b2de2a3".to_string(),
+                "0a6b06d162344bc53e8141d3fe319f94b1ca28e409c01aa3f2e5a9e8ebd3814160e90a9c48e313105a217d8499080d7a117c7bd4330196b29f2b9f0142893108bc0ecb7e11ed7ca554cad4744c0366748fa9412f6f679b45fccab61385eab319".to_string(),
+            ],
+            point_encoding: None,
+            snarkjs_vkey: None,
+        };
+        match execute(deps.as_mut(), mock_env(), info, msg) {
+            Err(ContractError::InvalidNullifierIndex {}) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn withdraw_fees_works() {
+        let mut deps = mock_dependencies_with_balance(&coins(5, "token"));
+        mock_init_with_price(deps.as_mut(), coin(1, "token"), coin(1, "token"));
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::FeeBalance {}).unwrap();
+        let value: FeeBalanceResponse = from_binary(&res).unwrap();
+        assert_eq!(value.balance, coins(5, "token"));
+
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::Withdraw {
+            amount: coins(3, "token"),
+            recipient: "treasury".to_string(),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "treasury".to_string(),
+                amount: coins(3, "token")
+            })]
+        );
+    }
+
+    #[test]
+    fn withdraw_fees_failed_with_unauthorized() {
+        let mut deps = mock_dependencies_with_balance(&coins(5, "token"));
+        mock_init_with_price(deps.as_mut(), coin(1, "token"), coin(1, "token"));
+
+        let info = mock_info("alice_key", &[]);
+        let msg = ExecuteMsg::Withdraw {
+            amount: coins(3, "token"),
+            recipient: "alice_key".to_string(),
+        };
+        match execute(deps.as_mut(), mock_env(), info, msg) {
+            Err(ContractError::Unauthorized {}) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn withdraw_fees_failed_with_empty_amount() {
+        let mut deps = mock_dependencies_with_balance(&coins(5, "token"));
+        mock_init_with_price(deps.as_mut(), coin(1, "token"), coin(1, "token"));
+
+        let info = mock_info("creator", &[]);
+        for amount in [
+            vec![],
+            coins(0, "token"),
+            vec![coin(1, "token"), coin(0, "stake")],
+        ] {
+            let msg = ExecuteMsg::Withdraw {
+                amount,
+                recipient: "treasury".to_string(),
+            };
+            match execute(deps.as_mut(), mock_env(), info.clone(), msg) {
+                Err(ContractError::EmptyWithdrawal {}) => {}
+                res => panic!("Unexpected result: {:?}", res),
+            }
+        }
+        let msg = ExecuteMsg::WithdrawCw20 {
+            token: "cw20_token".to_string(),
+            amount: Uint128::zero(),
+            recipient: "treasury".to_string(),
+        };
+        match execute(deps.as_mut(), mock_env(), info, msg) {
+            Err(ContractError::EmptyWithdrawal {}) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn verify_proof_refunds_overpayment_with_exact_fee() {
+        let mut deps = mock_dependencies();
+        mock_init_with_exact_fee(deps.as_mut(), UnrelatedDenoms::Refund);
+        mock_alice_set_zkeys(deps.as_mut(), &[coin(1, "token")]);
+
+        let (proof_a, proof_b, proof_c) = bob_proof();
+        let msg = ExecuteMsg::Proof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proof_a,
+            proof_b,
+            proof_c,
+            point_encoding: None,
+            snarkjs_proof: None,
+            public_signals: None,
+        };
+        let info = mock_info("bob_key", &[coin(3, "token"), coin(2, "smokin")]);
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "bob_key".to_string(),
+                amount: vec![coin(2, "token"), coin(2, "smokin")]
+            })]
+        );
+    }
+
+    #[test]
+    fn verify_proof_failed_with_unrelated_denom() {
+        let mut deps = mock_dependencies();
+        mock_init_with_exact_fee(deps.as_mut(), UnrelatedDenoms::Reject);
+        mock_alice_set_zkeys(deps.as_mut(), &[coin(1, "token")]);
+
+        let (proof_a, proof_b, proof_c) = bob_proof();
+        let msg = ExecuteMsg::Proof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proof_a,
+            proof_b,
+            proof_c,
+            point_encoding: None,
+            snarkjs_proof: None,
+            public_signals: None,
+        };
+        let info = mock_info("bob_key", &[coin(1, "token"), coin(2, "smokin")]);
+        match execute(deps.as_mut(), mock_env(), info, msg) {
+            Err(ContractError::UnrelatedDenom { denom }) => assert_eq!(denom, "smokin"),
+            res => panic!("Unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn verify_proof_collects_bounty() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys_with_bounty(deps.as_mut(), &coins(10, "token"));
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Bounty {
+                address: "alice_key".to_string(),
+                circuit_id: None,
+            },
+        )
+        .unwrap();
+        let value: BountyResponse = from_binary(&res).unwrap();
+        assert_eq!(value.reward, coin(10, "token"));
+        // the escrowed reward is not a fee
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::FeeBalance {}).unwrap();
+        let value: FeeBalanceResponse = from_binary(&res).unwrap();
+        assert_eq!(value.balance, vec![]);
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::Withdraw {
+            amount: coins(10, "token"),
+            recipient: "creator".to_string(),
+        };
+        match execute(deps.as_mut(), mock_env(), info, msg) {
+            Err(ContractError::InsufficientFees { denom }) => assert_eq!(denom, "token"),
+            res => panic!("Unexpected result: {:?}", res),
+        }
+
+        // the proof wins the bounty, which is paid once no earlier commitment can be revealed
+        let height = mock_env().block.height;
+        let res = mock_publish_bob_proof(deps.as_mut(), "bob_key", height).unwrap();
+        assert_eq!(res.messages, vec![]);
+        assert_eq!(res.events[1].ty, "bounty_won");
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Bounty {
+                address: "alice_key".to_string(),
+                circuit_id: None,
+            },
+        )
+        .unwrap();
+        let value: BountyResponse = from_binary(&res).unwrap();
+        assert_eq!(value.winner, Some(Addr::unchecked("bob_key")));
+        assert_eq!(value.claimable_at, Some(height + 100));
+
+        let claim = || ExecuteMsg::ClaimBounty {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+        };
+        match execute(
+            deps.as_mut(),
+            mock_env_at(height + 99),
+            mock_info("carol_key", &[]),
+            claim(),
+        ) {
+            Err(ContractError::BountyNotClaimable { claimable_at }) => {
+                assert_eq!(claimable_at, height + 100)
+            }
+            res => panic!("Unexpected result: {:?}", res),
+        }
+        // the issuer can't take back a bounty that was won
+        match execute(
+            deps.as_mut(),
+            mock_env_at(height + 11),
+            mock_info("alice_key", &[]),
+            ExecuteMsg::ReclaimBounty { circuit_id: None },
+        ) {
+            Err(ContractError::BountyAlreadyWon {}) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+        // anyone can pay it out to the winner
+        let res = execute(
+            deps.as_mut(),
+            mock_env_at(height + 100),
+            mock_info("carol_key", &[]),
+            claim(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "bob_key".to_string(),
+                amount: coins(10, "token")
+            })]
+        );
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Bounty {
+                address: "alice_key".to_string(),
+                circuit_id: None,
+            },
+        );
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn bounty_goes_to_the_earlier_commitment() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys_with_bounty(deps.as_mut(), &coins(10, "token"));
+
+        // carol front-runs bob's reveal with a re-randomized copy of his proof
+        mock_commit_bob_proof(deps.as_mut(), "bob_key", "salt", 100);
+        let (proof_a, proof_b, proof_c) = rerandomized_proof(bob_proof());
+        let msg = ExecuteMsg::Proof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proof_a,
+            proof_b,
+            proof_c,
+            point_encoding: None,
+            snarkjs_proof: None,
+            public_signals: None,
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env_at(101),
+            mock_info("carol_key", &[]),
+            msg,
+        )
+        .unwrap();
+        assert_eq!(res.messages, vec![]);
+        let claim = || ExecuteMsg::ClaimBounty {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+        };
+        match execute(
+            deps.as_mut(),
+            mock_env_at(102),
+            mock_info("carol_key", &[]),
+            claim(),
+        ) {
+            Err(ContractError::BountyNotClaimable { claimable_at }) => {
+                assert_eq!(claimable_at, 201)
+            }
+            res => panic!("Unexpected result: {:?}", res),
+        }
+
+        // bob reveals later, his commitment is earlier
+        mock_reveal_bob_proof(deps.as_mut(), "bob_key", "salt", 102)
+            .expect("contract handles reveal proof");
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Bounty {
+                address: "alice_key".to_string(),
+                circuit_id: None,
+            },
+        )
+        .unwrap();
+        let value: BountyResponse = from_binary(&res).unwrap();
+        assert_eq!(value.winner, Some(Addr::unchecked("bob_key")));
+        assert_eq!(value.claimable_at, Some(200));
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env_at(200),
+            mock_info("carol_key", &[]),
+            claim(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "bob_key".to_string(),
+                amount: coins(10, "token")
+            })]
+        );
+        match execute(
+            deps.as_mut(),
+            mock_env_at(201),
+            mock_info("carol_key", &[]),
+            claim(),
+        ) {
+            Err(ContractError::NoBounty {}) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn reclaim_bounty_works() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys_with_bounty(deps.as_mut(), &coins(10, "token"));
+
+        let info = mock_info("alice_key", &[]);
+        let height = mock_env().block.height;
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            ExecuteMsg::ReclaimBounty { circuit_id: None },
+        ) {
+            Err(ContractError::BountyNotExpired { reclaimable_at }) => {
+                assert_eq!(reclaimable_at, height + 111)
+            }
+            res => panic!("Unexpected result: {:?}", res),
+        }
+        // a commitment made by the deadline may still be revealed
+        match execute(
+            deps.as_mut(),
+            mock_env_at(height + 110),
+            info.clone(),
+            ExecuteMsg::ReclaimBounty { circuit_id: None },
+        ) {
+            Err(ContractError::BountyNotExpired { reclaimable_at }) => {
+                assert_eq!(reclaimable_at, height + 111)
+            }
+            res => panic!("Unexpected result: {:?}", res),
+        }
+
+        let env = mock_env_at(height + 111);
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::ReclaimBounty { circuit_id: None },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "alice_key".to_string(),
+                amount: coins(10, "token")
+            })]
+        );
+        match execute(
+            deps.as_mut(),
+            env,
+            info,
+            ExecuteMsg::ReclaimBounty { circuit_id: None },
+        ) {
+            Err(ContractError::NoBounty {}) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn reclaim_bounty_waits_for_a_commitment_made_by_the_deadline() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys_with_bounty(deps.as_mut(), &coins(10, "token"));
+
+        // bob commits on the last block of the bounty
+        let deadline = mock_env().block.height + 10;
+        mock_commit_bob_proof(deps.as_mut(), "bob_key", "salt", deadline);
+        match execute(
+            deps.as_mut(),
+            mock_env_at(deadline + 1),
+            mock_info("alice_key", &[]),
+            ExecuteMsg::ReclaimBounty { circuit_id: None },
+        ) {
+            Err(ContractError::BountyNotExpired { reclaimable_at }) => {
+                assert_eq!(reclaimable_at, deadline + 101)
+            }
+            res => panic!("Unexpected result: {:?}", res),
+        }
+
+        // his reveal after the deadline still wins the reward
+        let res = mock_reveal_bob_proof(deps.as_mut(), "bob_key", "salt", deadline + 2).unwrap();
+        assert_eq!(res.events[1].ty, "bounty_won");
+        match execute(
+            deps.as_mut(),
+            mock_env_at(deadline + 101),
+            mock_info("alice_key", &[]),
+            ExecuteMsg::ReclaimBounty { circuit_id: None },
+        ) {
+            Err(ContractError::BountyAlreadyWon {}) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+        let claim = ExecuteMsg::ClaimBounty {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env_at(deadline + 100),
+            mock_info("carol_key", &[]),
+            claim,
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "bob_key".to_string(),
+                amount: coins(10, "token")
+            })]
+        );
+    }
+    #[test]
+    fn verify_proof_and_query_works_with_cw20_price() {
+        let mut deps = mock_dependencies();
+        mock_init_with_cw20_price(deps.as_mut());
+
+        let res = mock_alice_set_zkeys_with_cw20(deps.as_mut(), "cw20_token", 2).unwrap();
+        assert_eq!(res.messages, vec![]);
+        // the price is only payable in the cw20 token
+        match mock_publish_bob_proof(deps.as_mut(), "bob_key", mock_env().block.height) {
+            Err(ContractError::InsufficientFundsSend {}) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+
+        // the overpayment goes back with the exact fee mode
+        let res = mock_bob_publish_proof_with_cw20(deps.as_mut(), "cw20_token", 3).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(WasmMsg::Execute {
+                contract_addr: "cw20_token".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "bob_key".to_string(),
+                    amount: Uint128::new(1)
+                })
+                .unwrap(),
+                funds: vec![],
+            })]
+        );
+        query_verification_result(deps.as_ref());
+    }
+
+    #[test]
+    fn receive_failed_with_unaccepted_token_or_hook() {
+        let mut deps = mock_dependencies();
+        mock_init_with_cw20_price(deps.as_mut());
+
+        match mock_alice_set_zkeys_with_cw20(deps.as_mut(), "other_token", 2) {
+            Err(ContractError::UnacceptedToken { token }) => assert_eq!(token, "other_token"),
+            res => panic!("Unexpected result: {:?}", res),
+        }
+        match mock_alice_set_zkeys_with_cw20(deps.as_mut(), "cw20_token", 1) {
+            Err(ContractError::InsufficientFundsSend {}) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+
+        let wrapper = Cw20ReceiveMsg {
+            sender: "alice_key".to_string(),
+            amount: Uint128::new(2),
+            msg: to_binary(&ExecuteMsg::ReclaimBounty { circuit_id: None }).unwrap(),
+        };
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("cw20_token", &[]),
+            ExecuteMsg::Receive(wrapper),
+        ) {
+            Err(ContractError::InvalidCw20Hook {}) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn withdraw_cw20_fees_works() {
+        let mut deps = mock_dependencies();
+        mock_init_with_cw20_price(deps.as_mut());
+
+        let msg = ExecuteMsg::WithdrawCw20 {
+            token: "cw20_token".to_string(),
+            amount: Uint128::new(2),
+            recipient: "treasury".to_string(),
+        };
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice_key", &[]),
+            msg.clone(),
+        ) {
+            Err(ContractError::Unauthorized {}) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(WasmMsg::Execute {
+                contract_addr: "cw20_token".to_string(),
+                msg: to_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "treasury".to_string(),
+                    amount: Uint128::new(2)
+                })
+                .unwrap(),
+                funds: vec![],
+            })]
+        );
+    }
+    #[test]
+    fn migrate_refuses_downgrade_and_other_contract() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        assert_eq!(
+            get_contract_version(deps.as_ref().storage).unwrap().version,
+            env!("CARGO_PKG_VERSION")
+        );
+
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg { owner: None }).unwrap();
+        assert_eq!(res.messages, vec![]);
+
+        set_contract_version(deps.as_mut().storage, "crates.io:cw-groth16", "99.0.0").unwrap();
+        match migrate(deps.as_mut(), mock_env(), MigrateMsg { owner: None }) {
+            Err(ContractError::CannotDowngrade {
+                previous_version, ..
+            }) => assert_eq!(previous_version, "99.0.0"),
+            res => panic!("Unexpected result: {:?}", res),
+        }
+
+        set_contract_version(deps.as_mut().storage, "crates.io:cw20-base", "0.1.0").unwrap();
+        match migrate(deps.as_mut(), mock_env(), MigrateMsg { owner: None }) {
+            Err(ContractError::CannotMigrate { previous_contract }) => {
+                assert_eq!(previous_contract, "crates.io:cw20-base")
+            }
+            res => panic!("Unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn migrate_rewrites_v0_1_layouts() {
+        let mut deps = mock_dependencies();
+        let (proof_a, proof_b, proof_c) = bob_proof();
+        let proof = ProofStr {
+            pi_a: hex::decode(proof_a).unwrap(),
+            pi_b: hex::decode(proof_b).unwrap(),
+            pi_c: hex::decode(proof_c).unwrap(),
+            encoding: PointEncoding::Uncompressed,
+        };
+        let alice = Addr::unchecked("alice_key");
+        let bob = Addr::unchecked("bob_key");
+        let storage = deps.as_mut().storage;
+        v0_1::CONFIG
+            .save(
+                storage,
+                &v0_1::Config {
+                    zkeys_price: None,
+                    proof_price: Some(coin(1, "token")),
+                },
+            )
+            .unwrap();
+        let zkeys = v0_1::ZkeysStr {
+            vkeys: v0_1::VkeyStr {
+                alpha_1: hex::decode("121ec6cddca2aa0dedf8dbb86e96dc4100b58ea07c01d7ea68a37f8f72191ab2bbe9f16bfe675f71c899ff11e23cbb04064831acc8c18f561f446eeaac3a9a056cb9a89b0b3f13a57eab4e97ebaff6f0a39327bd0a4b5f725d633c87474d35f2").unwrap(),
+                beta_2: hex::decode("00f3edfbbbe5e2dab32cac1d1ba2f0fdd9eff4067c7152520f0ebbf556c21f98e72590b3cdb614b1ea116991305da942077b7419fac8cc2d38dc6639d68a4cf7c8362efd8395020836f3aa564537fa02a17f2d1b423c19b6cf4784037b1d9f1510afbae9e95703ff3a98c46720f05e642588ef21ccb09580c84d211c0fd60acda18a699f61cef4925b9b113c8a2377f0147c5ee0882a97519627776222438d3e29f581f0e4b61fe18ab42089dfe24a1b9d7376667382941e37329860ec84d105").unwrap(),
+                gamma_2: hex::decode("13e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb80606c4a02ea734cc32acd2b02bc28b99cb3e287e85a763af267492ab572e99ab3f370d275cec1da1aaa9075ff05f79be0ce5d527727d6e118cc9cdc6da2e351aadfd9baa8cbdd3a76d429a695160d12c923ac9cc3baca289e193548608b82801").unwrap(),
+                delta_2: hex::decode("07709351aa9646a1311053a0e4cfe6d7db03513beaebb9b87da192758e5ecd40d21ac535e7664e78d669399de703cb72109a5d6b3943018f1dd43462eb71be512213f05e61b2c93bc5f65d270bf78122b00e24d38b0f98efdee072cf3b4c8d0a1905dcb70f21d51fdd376d5fcd258df6c3477a2421527d1702b848954fd7a3bbf710eda0c1880b79a996516ec37d616c13082219d90a7743ad8eb5e3faceec7ad6374029d52eaeca7b66c598b3dd7066e4b6246cea47794fdcffcf7891984272").unwrap(),
+                ic0: hex::decode("16aca3c7fb4157ef2f70fa4098434d97721a2ffa30f1ed64d3123cccb3928433899ab147217331f74f18ce687cc591700e79ca556db5b53e92f1133b889dbc11ef79615331a9a810cbef02d3a760b437a1bd50c1b6c396288abcb37479bc18a5").unwrap(),
+                ic1: hex::decode("0c1aeb08622db17dab3de7590db8f46349c7e08eff70fa63af8332db75b977bd0e630b04d8e28d4b3416381b27f4bded12e8067fd6f65bd436608cf66f0eb0c19b7da57b72785966d71b91229cde327918d14b3330b891bdfcf255e3d0ecfbfd").unwrap(),
+            },
+            public_signal: "33".to_string(),
+        };
+        v0_1::ZKEYS.save(storage, &alice, &zkeys).unwrap();
+        let proof_info = v0_1::ProofInfo {
+            proof,
+            is_valid: true,
+        };
+        v0_1::PROVERLIST
+            .save(storage, (&alice, &bob), &proof_info)
+            .unwrap();
+        v0_1::PROVERINFO.save(storage, &bob, &proof_info).unwrap();
+
+        // 0.1.0 didn't have an owner
+        match migrate(deps.as_mut(), mock_env(), MigrateMsg { owner: None }) {
+            Err(ContractError::MissingOwner {}) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+        migrate(
+            deps.as_mut(),
+            mock_env(),
+            MigrateMsg {
+                owner: Some("creator".to_string()),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            get_contract_version(deps.as_ref().storage).unwrap().version,
+            env!("CARGO_PKG_VERSION")
+        );
+
+        assert_config_state(
+            deps.as_ref(),
+            Config {
+                owner: Addr::unchecked("creator"),
+                zkeys_price: None,
+                proof_price: Some(coin(1, "token")),
+                zkeys_cw20_price: None,
+                proof_cw20_price: None,
+                fee_mode: FeeMode::Sufficient,
+                unrelated_denoms: UnrelatedDenoms::Refund,
+                commitment_expiry: 100,
+            },
+        );
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::IssuerZkeys {
+                address: "alice_key".to_string(),
+                circuit_id: None,
+            },
+        )
+        .unwrap();
+        let value: ZkeysResponse = from_binary(&res).unwrap();
+        assert_eq!(value.public_signals, vec!["33".to_string()]);
+        assert_eq!(value.vk_ic.len(), 2);
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProofResult {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                prover_address: "bob_key".to_string(),
+            },
+        )
+        .unwrap();
+        let value: ProofResponse = from_binary(&res).unwrap();
+        assert_eq!(value.public_signals, vec!["33".to_string()]);
+        assert!(value.is_valid);
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::SolvedCircuits {
+                prover_address: "bob_key".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: SolvedCircuitsResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            value.circuits,
+            vec![CircuitRef {
+                issuer: Addr::unchecked("alice_key"),
+                circuit_id: DEFAULT_CIRCUIT.to_string()
+            }]
+        );
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProverHistory {
+                prover_address: "bob_key".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: ProverHistoryResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            value.verifications,
+            vec![VerificationResponse {
+                sequence: 0,
+                issuer: Addr::unchecked("alice_key"),
+                circuit_id: DEFAULT_CIRCUIT.to_string(),
+                vkey_hash: hex::encode(vkey_hash(
+                    &ZKEYS
+                        .load(deps.as_ref().storage, (&alice, DEFAULT_CIRCUIT))
+                        .unwrap()
+                        .vkeys
+                )),
+                height: mock_env().block.height,
+            }]
+        );
+        assert!(!v0_1::PROVERINFO.has(deps.as_ref().storage, &Addr::unchecked("bob_key")));
+        assert!(!v0_1::ZKEYS.has(deps.as_ref().storage, &alice));
+        assert!(!v0_1::PROVERLIST.has(deps.as_ref().storage, (&alice, &bob)));
+
+        // the migrated key still verifies proofs
+        mock_bob_publish_proof_to_verify(deps.as_mut(), &coins(1, "token"));
+    }
+    #[test]
+    fn set_zkeys_and_verify_proof_emit_events() {
+        let mut deps = mock_dependencies();
+        mock_init_with_cw20_price(deps.as_mut());
+
+        let res = mock_alice_set_zkeys_with_cw20(deps.as_mut(), "cw20_token", 2).unwrap();
+        let zkeys = ZKEYS
+            .load(
+                deps.as_ref().storage,
+                (&Addr::unchecked("alice_key"), DEFAULT_CIRCUIT),
+            )
+            .unwrap();
+        let vkey_hash = hex::encode(vkey_hash(&zkeys.vkeys));
+        assert_eq!(res.events.len(), 1);
+        assert_eq!(res.events[0].ty, "zkeys_registered");
+        assert_eq!(
+            res.events[0].attributes,
+            vec![
+                attr("issuer", "alice_key"),
+                attr("circuit_id", DEFAULT_CIRCUIT),
+                attr("vkey_hash", &vkey_hash),
+                attr("proof_system", PROOF_SYSTEM),
+                attr("fee", "2cw20_token"),
+            ]
+        );
+
+        // only the price is kept with the exact fee mode
+        let res = mock_bob_publish_proof_with_cw20(deps.as_mut(), "cw20_token", 3).unwrap();
+        assert_eq!(res.events.len(), 1);
+        assert_eq!(res.events[0].ty, "proof_verified");
+        assert_eq!(
+            res.events[0].attributes,
+            vec![
+                attr("issuer", "alice_key"),
+                attr("circuit_id", DEFAULT_CIRCUIT),
+                attr("prover", "bob_key"),
+                attr("vkey_hash", &vkey_hash),
+                attr("proof_system", PROOF_SYSTEM),
+                attr("fee", "2cw20_token"),
+                attr("outcome", "valid"),
+                attr("sequence", "0"),
+            ]
+        );
+    }
+
+    #[test]
+    fn withdraw_fees_emits_event() {
+        let mut deps = mock_dependencies_with_balance(&coins(5, "token"));
+        mock_init_with_price(deps.as_mut(), coin(1, "token"), coin(1, "token"));
+
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::Withdraw {
+            amount: vec![coin(3, "token")],
+            recipient: "treasury".to_string(),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(res.events[0].ty, "fees_withdrawn");
+        assert_eq!(
+            res.events[0].attributes,
+            vec![attr("recipient", "treasury"), attr("amount", "3token")]
+        );
+    }
+    #[test]
+    fn list_issuers_and_provers_works() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys(deps.as_mut(), &[]);
+        mock_bob_publish_proof_to_verify(deps.as_mut(), &[]);
+        // carol and dave issue the same problem
+        let zkeys = ZKEYS
+            .load(
+                deps.as_ref().storage,
+                (&Addr::unchecked("alice_key"), DEFAULT_CIRCUIT),
+            )
+            .unwrap();
+        ZKEYS
+            .save(
+                deps.as_mut().storage,
+                (&Addr::unchecked("carol_key"), DEFAULT_CIRCUIT),
+                &zkeys,
+            )
+            .unwrap();
+        ZKEYS
+            .save(
+                deps.as_mut().storage,
+                (&Addr::unchecked("dave_key"), DEFAULT_CIRCUIT),
+                &zkeys,
+            )
+            .unwrap();
+        let (proof_a, proof_b, proof_c) = bob_proof();
+        let msg = ExecuteMsg::Proof {
+            difficuty_issuer: "dave_key".to_string(),
+            circuit_id: None,
+            proof_a,
+            proof_b,
+            proof_c,
+            point_encoding: None,
+            snarkjs_proof: None,
+            public_signals: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("bob_key", &[]), msg).unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Issuers {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: IssuersResponse = from_binary(&res).unwrap();
+        // an issuer key is stored behind its length
+        assert_eq!(
+            value.issuers,
+            vec![
+                Addr::unchecked("dave_key"),
+                Addr::unchecked("alice_key"),
+                Addr::unchecked("carol_key")
+            ]
+        );
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Issuers {
+                start_after: Some("alice_key".to_string()),
+                limit: Some(1),
+            },
+        )
+        .unwrap();
+        let value: IssuersResponse = from_binary(&res).unwrap();
+        assert_eq!(value.issuers, vec![Addr::unchecked("carol_key")]);
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Provers {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: ProversResponse = from_binary(&res).unwrap();
+        assert_eq!(value.provers, vec![Addr::unchecked("bob_key")]);
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Provers {
+                issuer_address: "carol_key".to_string(),
+                circuit_id: None,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: ProversResponse = from_binary(&res).unwrap();
+        assert!(value.provers.is_empty());
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::SolvedCircuits {
+                prover_address: "bob_key".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: SolvedCircuitsResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            value.circuits,
+            vec![
+                CircuitRef {
+                    issuer: Addr::unchecked("dave_key"),
+                    circuit_id: DEFAULT_CIRCUIT.to_string()
+                },
+                CircuitRef {
+                    issuer: Addr::unchecked("alice_key"),
+                    circuit_id: DEFAULT_CIRCUIT.to_string()
+                },
+            ]
+        );
+        let start_after = Some(("dave_key".to_string(), DEFAULT_CIRCUIT.to_string()));
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::SolvedCircuits {
+                prover_address: "bob_key".to_string(),
+                start_after,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: SolvedCircuitsResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            value.circuits,
+            vec![CircuitRef {
+                issuer: Addr::unchecked("alice_key"),
+                circuit_id: DEFAULT_CIRCUIT.to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn prover_history_keeps_every_verification() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys(deps.as_mut(), &[]);
+        mock_bob_publish_proof_to_verify(deps.as_mut(), &[]);
+        let zkeys = ZKEYS
+            .load(
+                deps.as_ref().storage,
+                (&Addr::unchecked("alice_key"), DEFAULT_CIRCUIT),
+            )
+            .unwrap();
+        let vkey_hash = hex::encode(vkey_hash(&zkeys.vkeys));
+        ZKEYS
+            .save(
+                deps.as_mut().storage,
+                (&Addr::unchecked("dave_key"), DEFAULT_CIRCUIT),
+                &zkeys,
+            )
+            .unwrap();
+
+        // a later proof for another issuer doesn't replace the first one
+        let mut env = mock_env();
+        env.block.height += 1;
+        let (proof_a, proof_b, proof_c) = bob_proof();
+        let msg = ExecuteMsg::Proof {
+            difficuty_issuer: "dave_key".to_string(),
+            circuit_id: None,
+            proof_a,
+            proof_b,
+            proof_c,
+            point_encoding: None,
+            snarkjs_proof: None,
+            public_signals: None,
+        };
+        execute(deps.as_mut(), env, mock_info("bob_key", &[]), msg).unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProverHistory {
+                prover_address: "bob_key".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: ProverHistoryResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            value.verifications,
+            vec![
+                VerificationResponse {
+                    sequence: 0,
+                    issuer: Addr::unchecked("alice_key"),
+                    circuit_id: DEFAULT_CIRCUIT.to_string(),
+                    vkey_hash: vkey_hash.clone(),
+                    height: mock_env().block.height,
+                },
+                VerificationResponse {
+                    sequence: 1,
+                    issuer: Addr::unchecked("dave_key"),
+                    circuit_id: DEFAULT_CIRCUIT.to_string(),
+                    vkey_hash,
+                    height: mock_env().block.height + 1,
+                },
+            ]
+        );
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProverHistory {
+                prover_address: "bob_key".to_string(),
+                start_after: None,
+                limit: Some(1),
+            },
+        )
+        .unwrap();
+        let value: ProverHistoryResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            value
+                .verifications
+                .iter()
+                .map(|v| v.sequence)
+                .collect::<Vec<_>>(),
+            vec![0]
+        );
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProverHistory {
+                prover_address: "bob_key".to_string(),
+                start_after: Some(0),
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: ProverHistoryResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            value
+                .verifications
+                .iter()
+                .map(|v| v.sequence)
+                .collect::<Vec<_>>(),
+            vec![1]
+        );
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProverHistory {
+                prover_address: "carol_key".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: ProverHistoryResponse = from_binary(&res).unwrap();
+        assert!(value.verifications.is_empty());
+    }
+
+    #[test]
+    fn verify_proof_query_works() {
+        let mut deps = mock_dependencies();
+        mock_init_with_price(deps.as_mut(), coin(1, "token"), coin(1, "token"));
+        mock_alice_set_zkeys(deps.as_mut(), &[coin(1, "token")]);
+        let zkeys = ZKEYS
+            .load(
+                deps.as_ref().storage,
+                (&Addr::unchecked("alice_key"), DEFAULT_CIRCUIT),
+            )
+            .unwrap();
+        let vkey_hash = hex::encode(vkey_hash(&zkeys.vkeys));
+        let (proof_a, proof_b, proof_c) = bob_proof();
+        let verify = |deps: Deps,
+                      key: VerifyingKeySource,
+                      public_signals: Option<Vec<String>>|
+         -> VerifyProofResponse {
+            let msg = QueryMsg::VerifyProof {
+                key,
+                proof_a: proof_a.clone(),
+                proof_b: proof_b.clone(),
+                proof_c: proof_c.clone(),
+                point_encoding: None,
+                public_signals,
+            };
+            from_binary(&query(deps, mock_env(), msg).unwrap()).unwrap()
+        };
+
+        // no fee and nothing recorded
+        let registered = VerifyingKeySource::Registered {
+            issuer: "alice_key".to_string(),
+            circuit_id: None,
+            prover: None,
+        };
+        let value = verify(deps.as_ref(), registered.clone(), None);
+        assert_eq!(
+            value,
+            VerifyProofResponse {
+                is_valid: true,
+                vkey_hash: vkey_hash.clone(),
+                public_signals: zkeys.public_signals.clone(),
+                nullifier_spent: None,
+                error: None,
+            }
+        );
+        let msg = QueryMsg::ProofResult {
+            issuer_address: "alice_key".to_string(),
+            circuit_id: None,
+            prover_address: "bob_key".to_string(),
+        };
+        assert!(query(deps.as_ref(), mock_env(), msg).is_err());
+
+        // alice fixed the public signals
+        let value = verify(
+            deps.as_ref(),
+            registered,
+            Some(zkeys.public_signals.clone()),
+        );
+        assert!(!value.is_valid);
+        assert_eq!(value.error, Some("error public signal".to_string()));
+
+        // an unknown issuer fails the query
+        let msg = QueryMsg::VerifyProof {
+            key: VerifyingKeySource::Registered {
+                issuer: "carol_key".to_string(),
+                circuit_id: None,
+                prover: None,
+            },
+            proof_a: proof_a.clone(),
+            proof_b: proof_b.clone(),
+            proof_c: proof_c.clone(),
+            point_encoding: None,
+            public_signals: None,
+        };
+        assert!(query(deps.as_ref(), mock_env(), msg).is_err());
+
+        // the same key given inline takes its public signals from the query
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::IssuerZkeys {
+                address: "alice_key".to_string(),
+                circuit_id: None,
+            },
+        )
+        .unwrap();
+        let value: ZkeysResponse = from_binary(&res).unwrap();
+        let inline = VerifyingKeySource::Inline {
+            vk_alpha1: value.vk_alpha1,
+            vk_beta_2: value.vk_beta_2,
+            vk_gamma_2: value.vk_gamma_2,
+            vk_delta_2: value.vk_delta_2,
+            vk_ic: value.vk_ic,
+        };
+        let value = verify(
+            deps.as_ref(),
+            inline.clone(),
+            Some(zkeys.public_signals.clone()),
+        );
+        assert!(value.is_valid);
+        assert_eq!(value.vkey_hash, vkey_hash);
+        let value = verify(deps.as_ref(), inline.clone(), Some(vec!["34".to_string()]));
+        assert!(!value.is_valid);
+        assert_eq!(
+            value.error,
+            Some("invalid proof, verify failed".to_string())
+        );
+        assert_eq!(value.public_signals, vec!["34".to_string()]);
+        let value = verify(deps.as_ref(), inline, None);
+        assert_eq!(value.error, Some("no public signal".to_string()));
+    }
+
+    #[test]
+    fn verify_proof_query_reports_nullifier_and_sender() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys_with_nullifier(deps.as_mut(), &[]);
+        let proof_a = "1570f63fcb28c5346b96e9bf9c748c2e8ec244f4f71c583fa7da3a7bcfd5ca55f81a5a754e63844ecf092692da0d717502a37b841bb8d2285a7d62dcff8db2f45dca4b480846ed6d690377006ecf26c763e8cab793a0c4df01be5d7cd196042a".to_string();
+        let proof_b = "0c5a5df7cb5d03c3fc864868cb5d41c4f5f0e75757793b86b0e7419880349324833ff06aa91588ba26023c2387826cc011a8e13d8f4c393b44431d31e32ac516bd64258ab28ce6a2e066fa3d2bdd435e5ec61fa44e3f859edb0dffe697ffafeb03678528d1ce2bd904b0065bde165b48eff70ad280fc4267844afca5e6dfc3c0a04756d48d32a713026ef8b420ceef4e0984ed1c554578deb52562d4862f2176d9f9448b3f49618c0c8f9d4f644875a49b31fc243852b8a8f0e5fb7a302f5b1a".to_string();
+        let proof_c = "07b2945c2aeb8170509cf0eb26b0755945563fc65a173680d116af9bdf58ddb37dd2b9d240b0ff81f27dbd3531566a82064519507398c9b1aa59c704ad54a997ab31d06d71486e7404fc1915b66a6e36a6f7e4bec7e5626634d92220151846d5".to_string();
+        let public_signals = Some(vec!["25".to_string(), "7".to_string()]);
+        let msg = QueryMsg::VerifyProof {
+            key: VerifyingKeySource::Registered {
+                issuer: "alice_key".to_string(),
+                circuit_id: None,
+                prover: None,
+            },
+            proof_a: proof_a.clone(),
+            proof_b: proof_b.clone(),
+            proof_c: proof_c.clone(),
+            point_encoding: None,
+            public_signals: public_signals.clone(),
+        };
+        let value: VerifyProofResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), msg.clone()).unwrap()).unwrap();
+        assert_eq!(value.nullifier_spent, Some(false));
+        let proof = ExecuteMsg::Proof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proof_a,
+            proof_b,
+            proof_c,
+            point_encoding: None,
+            snarkjs_proof: None,
+            public_signals,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("bob_key", &[]), proof).unwrap();
+        let value: VerifyProofResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert!(value.is_valid);
+        assert_eq!(value.nullifier_spent, Some(true));
+
+        // a key bound to the sender needs to know the prover
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys_bound_to_sender(deps.as_mut(), &[]);
+        let (proof_a, proof_b, proof_c) = bob_proof();
+        let msg = QueryMsg::VerifyProof {
+            key: VerifyingKeySource::Registered {
+                issuer: "alice_key".to_string(),
+                circuit_id: None,
+                prover: None,
+            },
+            proof_a,
+            proof_b,
+            proof_c,
+            point_encoding: None,
+            public_signals: None,
+        };
+        let value: VerifyProofResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert!(!value.is_valid);
+        assert_eq!(
+            value.error,
+            Some("the key binds the sender, a prover address is needed".to_string())
+        );
+    }
+
+    #[test]
+    fn named_circuits_are_kept_apart() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys(deps.as_mut(), &[]);
+        // alice publishes the same problem again under another name
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::IssuerZkeys {
+                address: "alice_key".to_string(),
+                circuit_id: None,
+            },
+        )
+        .unwrap();
+        let value: ZkeysResponse = from_binary(&res).unwrap();
+        let set_zkeys = |circuit_id: Option<String>| ExecuteMsg::Zkeys {
+            circuit_id,
+            metadata: None,
+            public_signals: value.public_signals.clone(),
+            public_signal_mode: None,
+            bind_sender: None,
+            nullifier_index: None,
+            bounty: None,
+            bounty_deadline: None,
+            vk_alpha1: value.vk_alpha1.clone(),
+            vk_beta_2: value.vk_beta_2.clone(),
+            vk_gamma_2: value.vk_gamma_2.clone(),
+            vk_delta_2: value.vk_delta_2.clone(),
+            vk_ic: value.vk_ic.clone(),
+            point_encoding: None,
+            snarkjs_vkey: None,
+        };
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice_key", &[]),
+            set_zkeys(Some("square".to_string())),
+        )
+        .unwrap();
+
+        // a key is replaced until a proof is listed against it
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice_key", &[]),
+            set_zkeys(None),
+        )
+        .unwrap();
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice_key", &[]),
+            set_zkeys(Some("".to_string())),
+        ) {
+            Err(ContractError::InvalidCircuitId {}) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Circuits {
+                issuer_address: "alice_key".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: CircuitsResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            value.circuit_ids,
+            vec![DEFAULT_CIRCUIT.to_string(), "square".to_string()]
+        );
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Circuits {
+                issuer_address: "alice_key".to_string(),
+                start_after: Some(DEFAULT_CIRCUIT.to_string()),
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: CircuitsResponse = from_binary(&res).unwrap();
+        assert_eq!(value.circuit_ids, vec!["square".to_string()]);
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Issuers {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: IssuersResponse = from_binary(&res).unwrap();
+        assert_eq!(value.issuers, vec![Addr::unchecked("alice_key")]);
+        // the next page starts past every circuit of alice
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Issuers {
+                start_after: Some("alice_key".to_string()),
+                limit: Some(1),
+            },
+        )
+        .unwrap();
+        let value: IssuersResponse = from_binary(&res).unwrap();
+        assert_eq!(value.issuers, Vec::<Addr>::new());
+
+        // a proof only counts for the circuit it names
+        let (proof_a, proof_b, proof_c) = bob_proof();
+        let proof = |circuit_id: &str| ExecuteMsg::Proof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: Some(circuit_id.to_string()),
+            proof_a: proof_a.clone(),
+            proof_b: proof_b.clone(),
+            proof_c: proof_c.clone(),
+            point_encoding: None,
+            snarkjs_proof: None,
+            public_signals: None,
+        };
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bob_key", &[]),
+            proof("cube"),
+        ) {
+            Err(ContractError::NonPublishDifficulty { difficuty_issuer }) => {
+                assert_eq!(difficuty_issuer, "alice_key")
+            }
+            res => panic!("Unexpected result: {:?}", res),
+        }
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bob_key", &[]),
+            proof("square"),
+        )
+        .unwrap();
+        assert!(res.events[0]
+            .attributes
+            .contains(&attr("circuit_id", "square")));
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice_key", &[]),
+            set_zkeys(Some("square".to_string())),
+        ) {
+            Err(ContractError::CircuitAlreadyRegistered { circuit_id }) => {
+                assert_eq!(circuit_id, "square")
+            }
+            res => panic!("Unexpected result: {:?}", res),
+        }
+
+        let msg = QueryMsg::ProofResult {
+            issuer_address: "alice_key".to_string(),
+            circuit_id: Some("square".to_string()),
+            prover_address: "bob_key".to_string(),
+        };
+        let value: ProofResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert!(value.is_valid);
+        let msg = QueryMsg::ProofResult {
+            issuer_address: "alice_key".to_string(),
+            circuit_id: None,
+            prover_address: "bob_key".to_string(),
+        };
+        assert!(query(deps.as_ref(), mock_env(), msg).is_err());
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Provers {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: ProversResponse = from_binary(&res).unwrap();
+        assert!(value.provers.is_empty());
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::SolvedCircuits {
+                prover_address: "bob_key".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: SolvedCircuitsResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            value.circuits,
+            vec![CircuitRef {
+                issuer: Addr::unchecked("alice_key"),
+                circuit_id: "square".to_string()
+            }]
+        );
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProverHistory {
+                prover_address: "bob_key".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: ProverHistoryResponse = from_binary(&res).unwrap();
+        assert_eq!(value.verifications[0].circuit_id, "square");
+
+        // the default circuit is still open to the same proof
+        mock_bob_publish_proof_to_verify(deps.as_mut(), &[]);
+        query_verification_result(deps.as_ref());
+    }
+
+    #[test]
+    fn circuit_registry_tracks_metadata_and_status() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys(deps.as_mut(), &[]);
+        let zkeys = ZKEYS
+            .load(
+                deps.as_ref().storage,
+                (&Addr::unchecked("alice_key"), DEFAULT_CIRCUIT),
+            )
+            .unwrap();
+
+        // a key registered without metadata
+        let msg = QueryMsg::Circuit {
+            issuer_address: "alice_key".to_string(),
+            circuit_id: None,
+        };
+        let value: CircuitResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(
+            value,
+            CircuitResponse {
+                issuer: Addr::unchecked("alice_key"),
+                circuit_id: DEFAULT_CIRCUIT.to_string(),
+                vkey_hash: hex::encode(vkey_hash(&zkeys.vkeys)),
+                proof_system: PROOF_SYSTEM.to_string(),
+                metadata: CircuitMetadata::default(),
+                status: CircuitStatus::Active,
+            }
+        );
+
+        // the same problem described under another name
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::IssuerZkeys {
+                address: "alice_key".to_string(),
+                circuit_id: None,
+            },
+        )
+        .unwrap();
+        let value: ZkeysResponse = from_binary(&res).unwrap();
+        let set_zkeys = |metadata: CircuitMetadata| ExecuteMsg::Zkeys {
+            circuit_id: Some("square".to_string()),
+            metadata: Some(metadata),
+            public_signals: value.public_signals.clone(),
+            public_signal_mode: None,
+            bind_sender: None,
+            nullifier_index: None,
+            bounty: None,
+            bounty_deadline: None,
+            vk_alpha1: value.vk_alpha1.clone(),
+            vk_beta_2: value.vk_beta_2.clone(),
+            vk_gamma_2: value.vk_gamma_2.clone(),
+            vk_delta_2: value.vk_delta_2.clone(),
+            vk_ic: value.vk_ic.clone(),
+            point_encoding: None,
+            snarkjs_vkey: None,
+        };
+        let metadata = CircuitMetadata {
+            name: "square".to_string(),
+            description: "knows a square root of the public signal".to_string(),
+            source_uri: "https://github.com/alice/circuits/blob/main/square.circom".to_string(),
+            artifact_hash: hex::encode([7u8; 32]),
+        };
+        let invalid = CircuitMetadata {
+            artifact_hash: "07".to_string(),
+            ..metadata.clone()
+        };
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice_key", &[]),
+            set_zkeys(invalid),
+        ) {
+            Err(ContractError::InvalidCircuitMetadata { field }) => {
+                assert_eq!(field, "artifact_hash")
+            }
+            res => panic!("Unexpected result: {:?}", res),
+        }
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice_key", &[]),
+            set_zkeys(metadata.clone()),
+        )
+        .unwrap();
+        let msg = QueryMsg::Circuit {
+            issuer_address: "alice_key".to_string(),
+            circuit_id: Some("square".to_string()),
+        };
+        let value: CircuitResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(value.metadata, metadata);
+        assert_eq!(value.status, CircuitStatus::Active);
+
+        // a deprecated circuit still accepts proofs
+        let msg = ExecuteMsg::SetCircuitStatus {
+            circuit_id: None,
+            status: CircuitStatus::Deprecated,
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alice_key", &[]), msg).unwrap();
+        assert_eq!(res.events[0].ty, "circuit_status_changed");
+        assert_eq!(
+            res.events[0].attributes,
+            vec![
+                attr("issuer", "alice_key"),
+                attr("circuit_id", DEFAULT_CIRCUIT),
+                attr("status", "deprecated")
+            ]
+        );
+        mock_commit_bob_proof(deps.as_mut(), "carol_key", "salt", 100);
+        mock_publish_bob_proof(deps.as_mut(), "bob_key", 101).unwrap();
+
+        // a retired one refuses them, what was proven stays
+        let msg = ExecuteMsg::SetCircuitStatus {
+            circuit_id: None,
+            status: CircuitStatus::Retired,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("alice_key", &[]), msg).unwrap();
+        match mock_reveal_bob_proof(deps.as_mut(), "carol_key", "salt", 102) {
+            Err(ContractError::CircuitRetired { circuit_id }) => {
+                assert_eq!(circuit_id, DEFAULT_CIRCUIT)
+            }
+            res => panic!("Unexpected result: {:?}", res),
+        }
+        let msg = ExecuteMsg::CommitProof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            commitment: hex::encode([0u8; 32]),
+        };
+        match execute(deps.as_mut(), mock_env(), mock_info("carol_key", &[]), msg) {
+            Err(ContractError::CircuitRetired { .. }) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+        let (proof_a, proof_b, proof_c) = bob_proof();
+        let msg = QueryMsg::VerifyProof {
+            key: VerifyingKeySource::Registered {
+                issuer: "alice_key".to_string(),
+                circuit_id: None,
+                prover: None,
+            },
+            proof_a,
+            proof_b,
+            proof_c,
+            point_encoding: None,
+            public_signals: None,
+        };
+        let value: VerifyProofResponse =
+            from_binary(&query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert!(!value.is_valid);
+        assert_eq!(
+            value.error,
+            Some(
+                ContractError::CircuitRetired {
+                    circuit_id: DEFAULT_CIRCUIT.to_string()
+                }
+                .to_string()
+            )
+        );
+        query_verification_result(deps.as_ref());
+
+        // for good, and only by its issuer
+        let msg = ExecuteMsg::SetCircuitStatus {
+            circuit_id: None,
+            status: CircuitStatus::Active,
+        };
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice_key", &[]),
+            msg.clone(),
+        ) {
+            Err(ContractError::CircuitRetired { .. }) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+        match execute(deps.as_mut(), mock_env(), mock_info("bob_key", &[]), msg) {
+            Err(ContractError::NonPublishDifficulty { difficuty_issuer }) => {
+                assert_eq!(difficuty_issuer, "bob_key")
+            }
+            res => panic!("Unexpected result: {:?}", res),
+        }
+    }
+
+    fn mock_alice_set_snarkjs_zkeys(
+        deps: DepsMut,
+        snarkjs_vkey: SnarkjsVerifyingKey,
+    ) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::Zkeys {
+            circuit_id: None,
+            metadata: None,
+            public_signals: vec![],
+            public_signal_mode: Some(PublicSignalMode::Prover),
+            bind_sender: None,
+            nullifier_index: None,
+            bounty: None,
+            bounty_deadline: None,
+            vk_alpha1: String::new(),
+            vk_beta_2: String::new(),
+            vk_gamma_2: String::new(),
+            vk_delta_2: String::new(),
+            vk_ic: vec![],
+            point_encoding: None,
+            snarkjs_vkey: Some(snarkjs_vkey),
+        };
+        execute(deps, mock_env(), mock_info("alice_key", &[]), msg)
+    }
+
+    #[test]
+    fn verify_snarkjs_proof_works() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        let vkey: SnarkjsVerifyingKey = from_slice(ALICE_SNARKJS_VKEY.as_bytes()).unwrap();
+        mock_alice_set_snarkjs_zkeys(deps.as_mut(), vkey).unwrap();
+
+        // stored like the hex points of the same key
+        let mut hex_deps = mock_dependencies();
+        mock_init_no_price(hex_deps.as_mut());
+        mock_alice_set_zkeys(hex_deps.as_mut(), &[]);
+        let zkeys = |deps: Deps| -> ZkeysResponse {
+            let res = query(
+                deps,
+                mock_env(),
+                QueryMsg::IssuerZkeys {
+                    address: "alice_key".to_string(),
+                    circuit_id: None,
+                },
+            )
+            .unwrap();
+            from_binary(&res).unwrap()
+        };
+        let (stored, expected) = (zkeys(deps.as_ref()), zkeys(hex_deps.as_ref()));
+        assert_eq!(stored.vk_alpha1, expected.vk_alpha1);
+        assert_eq!(stored.vk_beta_2, expected.vk_beta_2);
+        assert_eq!(stored.vk_gamma_2, expected.vk_gamma_2);
+        assert_eq!(stored.vk_delta_2, expected.vk_delta_2);
+        assert_eq!(stored.vk_ic, expected.vk_ic);
+
+        // proof.json and public.json as snarkjs writes them, without the hex fields
+        let msg = format!(
+            r#"{{"proof": {{"difficuty_issuer": "alice_key", "snarkjs_proof": {}, "publicSignals": ["33"]}}}}"#,
+            BOB_SNARKJS_PROOF
+        );
+        let msg: ExecuteMsg = from_slice(msg.as_bytes()).unwrap();
+        execute(deps.as_mut(), mock_env(), mock_info("bob_key", &[]), msg).unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProofResult {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                prover_address: "bob_key".to_string(),
+            },
+        )
+        .unwrap();
+        let value: ProofResponse = from_binary(&res).unwrap();
+        let (proof_a, proof_b, proof_c) = bob_proof();
+        assert_eq!(
+            (value.proof_a, value.proof_b, value.proof_c),
+            (proof_a, proof_b, proof_c)
+        );
+        assert!(value.is_valid);
+    }
+
+    #[test]
+    fn set_snarkjs_zkeys_failed_with_malformed_key() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        let vkey: SnarkjsVerifyingKey = from_slice(ALICE_SNARKJS_VKEY.as_bytes()).unwrap();
+
+        let other_curve = SnarkjsVerifyingKey {
+            curve: "other".to_string(),
+            ..vkey.clone()
+        };
+        match mock_alice_set_snarkjs_zkeys(deps.as_mut(), other_curve) {
+            Ok(_) => panic!("a key of another curve should be refused"),
+            Err(ContractError::SnarkjsMismatch { field, .. }) => assert_eq!(field, "curve"),
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+        let wrong_count = SnarkjsVerifyingKey {
+            n_public: 2,
+            ..vkey.clone()
+        };
+        match mock_alice_set_snarkjs_zkeys(deps.as_mut(), wrong_count) {
+            Ok(_) => panic!("nPublic must match the IC points"),
+            Err(ContractError::SnarkjsMismatch { field, .. }) => assert_eq!(field, "nPublic"),
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+        let mut not_decimal = vkey.clone();
+        not_decimal.ic[1][0] = "0x1f".to_string();
+        match mock_alice_set_snarkjs_zkeys(deps.as_mut(), not_decimal) {
+            Ok(_) => panic!("coordinates are decimal"),
+            Err(ContractError::InvalidSnarkjsCoordinate { field }) => assert_eq!(field, "IC"),
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+
+        // hex points and snarkjs json at once
+        let mut msg: ExecuteMsg = from_slice(format!(r#"{{"zkeys": {{"public_signals": [], "public_signal_mode": "prover", "snarkjs_vkey": {}}}}}"#, ALICE_SNARKJS_VKEY).as_bytes()).unwrap();
+        if let ExecuteMsg::Zkeys { vk_alpha1, .. } = &mut msg {
+            *vk_alpha1 = "00".to_string();
+        }
+        match execute(deps.as_mut(), mock_env(), mock_info("alice_key", &[]), msg) {
+            Ok(_) => panic!("the key must come in one encoding"),
+            Err(ContractError::AmbiguousEncoding {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn verify_compressed_proof_works() {
+        let zkeys = |deps: Deps| -> ZkeysResponse {
+            let res = query(
+                deps,
+                mock_env(),
+                QueryMsg::IssuerZkeys {
+                    address: "alice_key".to_string(),
+                    circuit_id: None,
+                },
+            )
+            .unwrap();
+            from_binary(&res).unwrap()
+        };
+        let mut hex_deps = mock_dependencies();
+        mock_init_no_price(hex_deps.as_mut());
+        mock_alice_set_zkeys(hex_deps.as_mut(), &[]);
+        let expected = zkeys(hex_deps.as_ref());
+
+        // a compressed key is stored as if it came uncompressed
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        let msg = ExecuteMsg::Zkeys {
+            circuit_id: None,
+            metadata: None,
+            public_signals: vec!["33".to_string()],
+            public_signal_mode: None,
+            bind_sender: None,
+            nullifier_index: None,
+            bounty: None,
+            bounty_deadline: None,
+            vk_alpha1: compressed_g1(&expected.vk_alpha1),
+            vk_beta_2: compressed_g2(&expected.vk_beta_2),
+            vk_gamma_2: compressed_g2(&expected.vk_gamma_2),
+            vk_delta_2: compressed_g2(&expected.vk_delta_2),
+            vk_ic: expected
+                .vk_ic
+                .iter()
+                .map(|ic_i| compressed_g1(ic_i))
+                .collect(),
+            point_encoding: Some(PointEncoding::Compressed),
+            snarkjs_vkey: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("alice_key", &[]), msg).unwrap();
+        assert_eq!(zkeys(deps.as_ref()), expected);
+
+        let (proof_a, proof_b, proof_c) = bob_proof();
+        let proof = |proof_a: &str,
+                     proof_b: &str,
+                     proof_c: &str,
+                     point_encoding: Option<PointEncoding>| ExecuteMsg::Proof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proof_a: proof_a.to_string(),
+            proof_b: proof_b.to_string(),
+            proof_c: proof_c.to_string(),
+            point_encoding,
+            snarkjs_proof: None,
+            public_signals: None,
+        };
+        let compressed = (
+            compressed_g1(&proof_a),
+            compressed_g2(&proof_b),
+            compressed_g1(&proof_c),
+        );
+
+        // the tag decides how the points are read
+        let msg = proof(
+            &proof_a,
+            &proof_b,
+            &proof_c,
+            Some(PointEncoding::Compressed),
+        );
+        match execute(deps.as_mut(), mock_env(), mock_info("bob_key", &[]), msg) {
+            Ok(_) => panic!("uncompressed points tagged as compressed"),
+            Err(e @ ContractError::ErrorProof { .. }) => {
+                assert_eq!(e.to_string(), "proof_a: expected 48 bytes, got 96")
+            }
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+        let msg = proof(&compressed.0, &compressed.1, &compressed.2, None);
+        match execute(deps.as_mut(), mock_env(), mock_info("bob_key", &[]), msg) {
+            Ok(_) => panic!("compressed points without the tag"),
+            Err(e @ ContractError::ErrorProof { .. }) => {
+                assert_eq!(e.to_string(), "proof_a: expected 96 bytes, got 48")
+            }
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+
+        // the proof is stored compressed
+        let msg = proof(
+            &compressed.0,
+            &compressed.1,
+            &compressed.2,
+            Some(PointEncoding::Compressed),
+        );
+        execute(deps.as_mut(), mock_env(), mock_info("bob_key", &[]), msg).unwrap();
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProofResult {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                prover_address: "bob_key".to_string(),
+            },
+        )
+        .unwrap();
+        let value: ProofResponse = from_binary(&res).unwrap();
+        assert_eq!(value.point_encoding, PointEncoding::Compressed);
+        assert_eq!((value.proof_a, value.proof_b, value.proof_c), compressed);
+        assert!(value.is_valid);
+
+        // every prover of the issuer's signals is credited, in the encoding they sent
+        let msg = proof(&proof_a, &proof_b, &proof_c, None);
+        execute(deps.as_mut(), mock_env(), mock_info("carol_key", &[]), msg).unwrap();
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProofResult {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                prover_address: "carol_key".to_string(),
+            },
+        )
+        .unwrap();
+        let value: ProofResponse = from_binary(&res).unwrap();
+        assert_eq!(value.point_encoding, PointEncoding::Uncompressed);
+        assert_eq!(value.proof_a, proof_a);
+    }
+
+    #[test]
+    fn verify_binary_proof_works() {
+        let zkeys = |deps: Deps| -> ZkeysResponse {
+            let res = query(
+                deps,
+                mock_env(),
+                QueryMsg::IssuerZkeys {
+                    address: "alice_key".to_string(),
+                    circuit_id: None,
+                },
+            )
+            .unwrap();
+            from_binary(&res).unwrap()
+        };
+        let binary = |point: &str| Binary::from(hex::decode(point).unwrap());
+        let mut hex_deps = mock_dependencies();
+        mock_init_no_price(hex_deps.as_mut());
+        mock_alice_set_zkeys(hex_deps.as_mut(), &[]);
+        let expected = zkeys(hex_deps.as_ref());
+
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        let msg = ExecuteMsg::ZkeysBinary {
+            circuit_id: None,
+            metadata: None,
+            public_signals: vec!["33".to_string()],
+            public_signal_mode: None,
+            bind_sender: None,
+            nullifier_index: None,
+            bounty: None,
+            bounty_deadline: None,
+            vk_alpha1: binary(&expected.vk_alpha1),
+            vk_beta_2: binary(&expected.vk_beta_2),
+            vk_gamma_2: binary(&expected.vk_gamma_2),
+            vk_delta_2: binary(&expected.vk_delta_2),
+            vk_ic: expected.vk_ic.iter().map(|ic_i| binary(ic_i)).collect(),
+            point_encoding: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("alice_key", &[]), msg).unwrap();
+        // the same key for either query
+        assert_eq!(zkeys(deps.as_ref()), expected);
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::IssuerZkeysBinary {
+                address: "alice_key".to_string(),
+                circuit_id: None,
+            },
+        )
+        .unwrap();
+        let value: ZkeysBinaryResponse = from_binary(&res).unwrap();
+        assert_eq!(value.vk_alpha1, binary(&expected.vk_alpha1));
+        assert_eq!(
+            value.vk_ic,
+            expected
+                .vk_ic
+                .iter()
+                .map(|ic_i| binary(ic_i))
+                .collect::<Vec<_>>()
+        );
+
+        let (proof_a, proof_b, proof_c) = bob_proof();
+        let msg = ExecuteMsg::ProofBinary {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proof_a: binary(&proof_a),
+            proof_b: binary(&proof_b),
+            proof_c: binary(&proof_c),
+            point_encoding: None,
+            public_signals: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("bob_key", &[]), msg).unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProofResultBinary {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                prover_address: "bob_key".to_string(),
+            },
+        )
+        .unwrap();
+        let value: ProofBinaryResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            (value.proof_a, value.proof_b, value.proof_c),
+            (binary(&proof_a), binary(&proof_b), binary(&proof_c))
+        );
+        assert!(value.is_valid);
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProofResult {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                prover_address: "bob_key".to_string(),
+            },
+        )
+        .unwrap();
+        let value: ProofResponse = from_binary(&res).unwrap();
+        assert_eq!(
+            (value.proof_a, value.proof_b, value.proof_c),
+            (proof_a, proof_b, proof_c)
+        );
+    }
+
+    #[test]
+    fn prepared_vkey_holds_alpha_beta() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys(deps.as_mut(), &[]);
+        let zkeys = ZKEYS
+            .load(
+                deps.as_ref().storage,
+                (&Addr::unchecked("alice_key"), DEFAULT_CIRCUIT),
+            )
+            .unwrap();
+        let mut prepared = zkeys.prepared.clone().unwrap();
+        let vkey = parse_vkey::<Bls12>(zkeys.vkeys.clone()).unwrap();
+        // e(alpha, beta) is paired once, at registration
+        assert_eq!(
+            prepared.alpha_beta.to_vec(),
+            pairing(&vkey.alpha_g1, &vkey.beta_g2).to_bytes().to_vec()
+        );
+
+        // and a proof is judged as the uncached verifier judges it
+        let (a, b, c) = bob_proof();
+        let proof_str = decode_proof(a, b, c, PointEncoding::Uncompressed).unwrap();
+        let public_signals = vec!["33".to_string()];
+        let (is_passed, public_inputs) =
+            check_proof(&prepared, proof_str.clone(), &public_signals).unwrap();
+        assert!(is_passed);
+        let proof = parse_proof::<Bls12>(proof_str.clone()).unwrap();
+        assert!(verify_proof(&prepare_verifying_key(&vkey), &proof, &public_inputs).is_ok());
+
+        // against another e(alpha, beta) the same proof fails
+        let mut alpha_beta = prepared.alpha_beta.to_vec();
+        alpha_beta[47] ^= 1;
+        prepared.alpha_beta = Binary::from(alpha_beta);
+        assert!(
+            !check_proof(&prepared, proof_str.clone(), &public_signals)
+                .unwrap()
+                .0
+        );
+
+        // and bytes that don't encode one are refused rather than compared
+        for (alpha_beta, reason) in [
+            (vec![0xff; 576], "not an element of Fp12"),
+            (vec![0; 48], "expected 576 bytes, got 48"),
+        ] {
+            prepared.alpha_beta = Binary::from(alpha_beta);
+            match check_proof(&prepared, proof_str.clone(), &public_signals) {
+                Err(ContractError::ErrorVerificationKey {
+                    field,
+                    reason: actual,
+                }) => assert_eq!(
+                    (field.as_str(), actual.as_str()),
+                    ("prepared.alpha_beta", reason)
+                ),
+                res => panic!("Unexpected result: {:?}", res),
+            }
+        }
+    }
+
+    #[test]
+    fn verify_proof_batch_works() {
+        let mut deps = mock_dependencies();
+        mock_init_with_price(deps.as_mut(), coin(1, "token"), coin(1, "token"));
+        mock_alice_set_zkeys(deps.as_mut(), &coins(1, "token"));
+
+        // bob sends his proof along with a re-randomized copy, naming himself
+        let batch = || ExecuteMsg::ProofBatch {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proofs: vec![
+                batch_proof(None, bob_proof()),
+                batch_proof(Some("bob_key"), rerandomized_proof(bob_proof())),
+            ],
+        };
+
+        // the batch pays for every proof
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bob_key", &coins(1, "token")),
+            batch(),
+        ) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::InsufficientFundsSend {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bob_key", &coins(2, "token")),
+            batch(),
+        )
+        .unwrap();
+        assert_eq!(res.events.len(), 3);
+        assert_eq!(res.events[0].attributes[2], attr("prover", "bob_key"));
+        assert_eq!(res.events[1].attributes[2], attr("prover", "bob_key"));
+        assert_eq!(res.events[1].attributes[5], attr("fee", ""));
+        assert_eq!(res.events[2].ty, "proof_batch_verified");
+        assert_eq!(
+            res.events[2].attributes,
+            vec![
+                attr("issuer", "alice_key"),
+                attr("circuit_id", DEFAULT_CIRCUIT),
+                attr("proofs", "2"),
+                attr("fee", "2token"),
+            ]
+        );
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProofResult {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                prover_address: "bob_key".to_string(),
+            },
+        )
+        .unwrap();
+        let value: ProofResponse = from_binary(&res).unwrap();
+        assert!(value.is_valid);
+    }
+
+    #[test]
+    fn verify_proof_batch_failed_with_relayed_prover() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys(deps.as_mut(), &[]);
+
+        // the proof would credit carol without her having made it
+        let msg = ExecuteMsg::ProofBatch {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proofs: vec![
+                batch_proof(None, bob_proof()),
+                batch_proof(Some("carol_key"), rerandomized_proof(bob_proof())),
+            ],
+        };
+        match execute(deps.as_mut(), mock_env(), mock_info("bob_key", &[]), msg) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::ProverNotSender {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Provers {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: ProversResponse = from_binary(&res).unwrap();
+        assert_eq!(value.provers, Vec::<Addr>::new());
+    }
+
+    #[test]
+    fn verify_proof_batch_relays_proofs_bound_to_their_prover() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys_bound_to_sender(deps.as_mut(), &[]);
+
+        // carol relays the proof bob made for his own address, it only credits him
+        let bob = (
+            "10a1d25e5547b8e72ab9be8927f27d06a979f0ca03b0a89f1ee3ff7d3b09ac7066b2b60bc369fa7b775ad68a07a5cc5716db38674cf4b3f124c08b0e29ce8edab622d470bb72a78009e68f8382bbc2970368d5f9aa501af292bafdb7093f89fb".to_string(),
+            "1282487321d96676e063fb8432aae85c207fe14d5befd1d6ef5172bf24a83335d2398bcaa8c08374474835122e8818bc0c67d6c98e57d708e198eccb776fb4d1e9ea51b600392ee04e083ab79c82a208b7621dc838f93c76052d4c2a2fb607f318256f7b6e58568c3562d54f2be1ea91d372144156947dbe5e45336b2d8a4b77e288f8cbb945dcf95c827466ae9ce3680384827ac9fe2629e8450210743f6a8b7c0d25166d9c7f6f5977b96f0edf63bb0f6664a3fa6ac2632ded6f43563e8d20".to_string(),
+            "004ea3d1fd3c8e1b67b6fbc46890a00bbf1d9ad4251a002fc1b233746ba8fd836d6646a3550f93cd4907f07be1f41e6207767430c02570f1e59812a962f9fd7ee38448f4a6f9d4e148cf975ac9d71a88ab593c70f84e87e19cea08719ec53378".to_string(),
+        );
+        let msg = ExecuteMsg::ProofBatch {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proofs: vec![batch_proof(Some("bob_key"), bob)],
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("carol_key", &[]), msg).unwrap();
+        assert_eq!(res.events[0].attributes[2], attr("prover", "bob_key"));
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Provers {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: ProversResponse = from_binary(&res).unwrap();
+        assert_eq!(value.provers, vec![Addr::unchecked("bob_key")]);
+    }
+
+    #[test]
+    fn verify_proof_batch_failed_with_spent_nullifier() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys_with_nullifier(deps.as_mut(), &[]);
+
+        let bob = BatchProof {
+            public_signals: Some(vec!["25".to_string(), "7".to_string()]),
+            ..batch_proof(None, nullifier_proof())
+        };
+        let batch = |proofs| ExecuteMsg::ProofBatch {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proofs,
+        };
+        let _res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bob_key", &[]),
+            batch(vec![bob.clone()]),
+        )
+        .expect("contract handles verify proof batch");
+
+        // a copy of bob's proof doesn't spend its nullifier again
+        let copy = BatchProof {
+            prover: Some("dave_key".to_string()),
+            ..bob
+        };
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("dave_key", &[]),
+            batch(vec![copy]),
+        ) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::NullifierAlreadySpent {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn verify_proof_batch_reports_invalid_proofs() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys(deps.as_mut(), &[]);
+
+        let msg = ExecuteMsg::ProofBatch {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proofs: vec![],
+        };
+        match execute(deps.as_mut(), mock_env(), mock_info("bob_key", &[]), msg) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::EmptyProofBatch {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+
+        // well formed points, but not a proof of the statement
+        let (proof_a, proof_b, _) = bob_proof();
+        let forged = (proof_a.clone(), proof_b, proof_a);
+        let msg = ExecuteMsg::ProofBatch {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proofs: vec![
+                batch_proof(None, bob_proof()),
+                batch_proof(None, forged),
+                batch_proof(None, rerandomized_proof(bob_proof())),
+            ],
+        };
+        match execute(deps.as_mut(), mock_env(), mock_info("bob_key", &[]), msg) {
+            Ok(_) => panic!("Must return error"),
+            Err(ContractError::InvalidBatchProofs { indexes }) => assert_eq!(indexes, vec![1]),
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn malformed_input_is_reported_by_field() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys(deps.as_mut(), &[]);
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::IssuerZkeys {
+                address: "alice_key".to_string(),
+                circuit_id: None,
+            },
+        )
+        .unwrap();
+        let alice: ZkeysResponse = from_binary(&res).unwrap();
+
+        let zkeys =
+            |circuit_id: &str, public_signals: Vec<String>, vk_ic: Vec<String>| ExecuteMsg::Zkeys {
+                circuit_id: Some(circuit_id.to_string()),
+                metadata: None,
+                public_signals,
+                public_signal_mode: None,
+                bind_sender: None,
+                nullifier_index: None,
+                bounty: None,
+                bounty_deadline: None,
+                vk_alpha1: alice.vk_alpha1.clone(),
+                vk_beta_2: alice.vk_beta_2.clone(),
+                vk_gamma_2: alice.vk_gamma_2.clone(),
+                vk_delta_2: alice.vk_delta_2.clone(),
+                vk_ic,
+                point_encoding: None,
+                snarkjs_vkey: None,
+            };
+        let short_ic = vec![alice.vk_ic[0].clone(), alice.vk_ic[1][..96].to_string()];
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice_key", &[]),
+            zkeys("short", vec!["33".to_string()], short_ic),
+        ) {
+            Ok(_) => panic!("a truncated IC point should be refused"),
+            Err(e @ ContractError::ErrorVerificationKey { .. }) => {
+                assert_eq!(e.to_string(), "vk_ic[1]: expected 96 bytes, got 48")
+            }
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+        let odd_ic = vec![alice.vk_ic[0].clone(), alice.vk_ic[1][1..].to_string()];
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice_key", &[]),
+            zkeys("odd", vec!["33".to_string()], odd_ic),
+        ) {
+            Ok(_) => panic!("an IC point of odd hex length should be refused"),
+            Err(ContractError::HexDecodingError { field }) => assert_eq!(field, "vk_ic[1]"),
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+
+        // the public signals an issuer fixes are read with the key
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice_key", &[]),
+            zkeys(
+                "words",
+                vec!["thirty-three".to_string()],
+                alice.vk_ic.clone(),
+            ),
+        ) {
+            Ok(_) => panic!("a public signal that is not a number should be refused"),
+            Err(e @ ContractError::ParsePulbicSignalError { index: 0 }) => {
+                assert_eq!(e.to_string(), "public signal 0 is not a field element")
+            }
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice_key", &[]),
+            zkeys("none", vec![], alice.vk_ic.clone()),
+        ) {
+            Ok(_) => panic!("the key's public input is left unfilled"),
+            Err(e @ ContractError::PublicSignalCountMismatch { .. }) => {
+                assert_eq!(e.to_string(), "expected 1 public signals, got 0")
+            }
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+    }
+}
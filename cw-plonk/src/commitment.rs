@@ -0,0 +1,104 @@
+use crate::state::{ProofStr, VkeyStr};
+use cosmwasm_std::Addr;
+use sha2::{Digest, Sha256};
+
+/// the curve points of a proof in message order, the evaluations are bound to them by the transcript
+fn proof_points(proof: &ProofStr) -> Vec<u8> {
+    let mut points = proof.wire_commitments.concat();
+    points.extend_from_slice(&proof.grand_product_commitment);
+    points.extend(proof.quotient_poly_commitments.concat());
+    points.extend_from_slice(&proof.opening_at_z_proof);
+    points.extend_from_slice(&proof.opening_at_z_omega_proof);
+    points
+}
+
+/// sha256(proof points || sender || salt), the value a prover sends with `CommitProof`
+pub fn proof_commitment(proof: &ProofStr, sender: &Addr, salt: &str) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    hasher.update(proof_points(proof));
+    hasher.update(sender.as_bytes());
+    hasher.update(salt.as_bytes());
+    hasher.finalize().to_vec()
+}
+
+/// sha256(vkey hash || public inputs), what a proof proves. Every proof of a statement has
+/// the same hash, re-randomized or not and whoever submits it
+pub fn statement_hash(vkey_hash: &[u8], public_inputs: &[Vec<u8>]) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    hasher.update(vkey_hash);
+    for input in public_inputs {
+        hasher.update(input);
+    }
+    hasher.finalize().to_vec()
+}
+
+/// sha256 of a verification key, identifies the circuit it belongs to. Every field is
+/// length prefixed, so bytes moved from one field or element to the next hash differently
+pub fn vkey_hash(vkeys: &VkeyStr) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    hasher.update((vkeys.n as u64).to_be_bytes());
+    hasher.update((vkeys.num_inputs as u64).to_be_bytes());
+    hash_elements(&mut hasher, &vkeys.selector_commitments);
+    hash_elements(&mut hasher, &vkeys.next_step_selector_commitments);
+    hash_elements(&mut hasher, &vkeys.permutation_commitments);
+    hash_elements(&mut hasher, &vkeys.non_residues);
+    hash_elements(&mut hasher, &vkeys.g2_elements);
+    // the same bytes are another key in another parameter set
+    hasher.update([vkeys.params as u8]);
+    hasher.finalize().to_vec()
+}
+
+/// the number of elements, then every element behind its length
+fn hash_elements<T: AsRef<[u8]>>(hasher: &mut Sha256, elements: &[T]) {
+    hasher.update((elements.len() as u64).to_be_bytes());
+    for element in elements {
+        hasher.update((element.as_ref().len() as u64).to_be_bytes());
+        hasher.update(element);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::state::PlonkParams;
+
+    fn vkeys() -> VkeyStr {
+        VkeyStr {
+            n: 3,
+            num_inputs: 1,
+            selector_commitments: vec![vec![1; 64]; 6],
+            next_step_selector_commitments: vec![vec![2; 64]],
+            permutation_commitments: vec![vec![3; 64]; 4],
+            non_residues: vec!["5".to_string(), "7".to_string(), "13".to_string()],
+            g2_elements: vec![vec![4; 128]; 2],
+            params: PlonkParams::Width4WithNextStep,
+        }
+    }
+
+    #[test]
+    fn vkey_hash_works() {
+        let hash = vkey_hash(&vkeys());
+        assert_eq!(hash.len(), 32);
+
+        // moving bytes between the non residues makes another key
+        let moved = VkeyStr {
+            non_residues: vec!["57".to_string(), "".to_string(), "13".to_string()],
+            ..vkeys()
+        };
+        assert_ne!(hash, vkey_hash(&moved));
+        // so does moving an element from one field to the next
+        let mut moved = vkeys();
+        moved
+            .next_step_selector_commitments
+            .push(moved.selector_commitments.pop().unwrap());
+        assert_ne!(hash, vkey_hash(&moved));
+        // and reading the same key in another parameter set
+        assert_ne!(
+            hash,
+            vkey_hash(&VkeyStr {
+                params: PlonkParams::Width3WithNextStep,
+                ..vkeys()
+            })
+        );
+    }
+}
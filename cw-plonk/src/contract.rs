@@ -1,57 +1,146 @@
-use super::msg::{ConfigResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
-use super::msg::{ProofResponse, ZkeysResponse};
+use super::commitment::{proof_commitment, statement_hash, vkey_hash};
+use super::migrations::migrate_from_v0_1;
+use super::msg::{
+    BountyResponse, CircuitRef, CircuitResponse, CircuitsResponse, CommitmentResponse,
+    FeeBalanceResponse, IssuersResponse, NullifierResponse, ProofBinaryResponse, ProofResponse,
+    ProverHistoryResponse, ProversResponse, SenderSignalResponse, SolvedCircuitsResponse,
+    VerificationResponse, VerifyProofResponse, ZkeysBinaryResponse, ZkeysResponse,
+};
+use super::msg::{
+    ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, VerifyingKeySource,
+    DEFAULT_CIRCUIT, PROOF_SYSTEM,
+};
 use super::parser::{parse_proof, parse_vkey};
-use super::state::{Config, ProofInfo, VkeyStr, CONFIG, PROVERINFO, PROVERLIST, ZKEYS};
-use crate::coin_helpers::assert_sent_sufficient_coin;
+use super::signal_helpers::sender_public_signal;
+use super::snarkjs::{self, SnarkjsProof, SnarkjsVerifyingKey};
+use super::state::{
+    Bounty, Commitment, ProofClaim, BOUNTIES, BOUNTY_ESCROW, COMMITMENTS, NULLIFIERS, PROOF_CLAIMS,
+    SOLVED,
+};
+use super::state::{
+    CircuitMetadata, CircuitStatus, Config, FeeMode, PlonkParams, ProofInfo, VkeyStr, ZkeysStr,
+    CONFIG, PROVERLIST, ZKEYS,
+};
+use super::state::{Verification, PROVER_HISTORY, PROVER_SEQUENCE};
+use crate::coin_helpers::{charge_cw20_fee, charge_fee, deduct_coin};
 use crate::state::ProofStr;
 use crate::ContractError;
 // use bellman_ce::bn256::G1Uncompressed;
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
 use cosmwasm_std::{
-    entry_point, to_binary, Binary, Deps, DepsMut, Env, MessageInfo, Response, StdError, StdResult,
+    from_json, to_json_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, Deps, DepsMut, Empty, Env,
+    Event, MessageInfo, Order, Response, StdError, StdResult, Storage, Uint128, WasmMsg,
 };
-use ff_ce::PrimeField as Fr;
+use cw2::{set_contract_version, CONTRACT};
+use cw20::{Cw20Coin, Cw20CoinVerified, Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw_storage_plus::{Bound, PrefixBound};
+use ff_ce::{PrimeField as Fr, PrimeFieldRepr};
+use semver::Version;
 
-use pairing_ce::from_hex;
-use pairing_ce::Engine;
-use pairing_ce::bn256::{G1Affine, G1Uncompressed, G2Affine, G2Uncompressed};
 use pairing_ce::bn256::Bn256;
 
-use bellman_ce::ScalarEngine;
-use bellman_ce::plonk::better_cs::verifier::verify;
-use bellman_ce::plonk::better_cs::cs::{PlonkConstraintSystemParams, PlonkCsWidth4WithNextStepParams};
+use bellman_ce::plonk::better_cs::cs::{
+    PlonkConstraintSystemParams, PlonkCsWidth3WithNextStepParams, PlonkCsWidth4WithNextStepParams,
+};
 use bellman_ce::plonk::better_cs::verifier::verify as plonk_verify;
 use bellman_ce::plonk::commitments::transcript::keccak_transcript::RollingKeccakTranscript;
 
+// version info for migration
+const CONTRACT_NAME: &str = "crates.io:cw-plonk";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub const DEFAULT_COMMITMENT_EXPIRY: u64 = 100;
+
+const MAX_CIRCUIT_ID_LENGTH: usize = 64;
+const MAX_CIRCUIT_NAME_LENGTH: usize = 64;
+const MAX_CIRCUIT_DESCRIPTION_LENGTH: usize = 1024;
+const MAX_CIRCUIT_SOURCE_URI_LENGTH: usize = 256;
+
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
 // instantiate the contract
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn instantiate(
     deps: DepsMut,
     _env: Env,
-    _info: MessageInfo,
+    info: MessageInfo,
     msg: InstantiateMsg,
 ) -> Result<Response, StdError> {
     let config = Config {
+        owner: info.sender,
         zkeys_price: msg.set_zkeys_price,
         proof_price: msg.publish_proof_price,
+        zkeys_cw20_price: validate_cw20_price(deps.as_ref(), msg.set_zkeys_cw20_price)?,
+        proof_cw20_price: validate_cw20_price(deps.as_ref(), msg.publish_proof_cw20_price)?,
+        fee_mode: msg.fee_mode.unwrap_or_default(),
+        unrelated_denoms: msg.unrelated_denoms.unwrap_or_default(),
+        commitment_expiry: msg.commitment_expiry.unwrap_or(DEFAULT_COMMITMENT_EXPIRY),
     };
     CONFIG.save(deps.storage, &config)?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, env: Env, msg: MigrateMsg) -> Result<Response, ContractError> {
+    let new_version: Version = CONTRACT_VERSION.parse()?;
+    match CONTRACT.may_load(deps.storage)? {
+        Some(stored) => {
+            if stored.contract != CONTRACT_NAME {
+                return Err(ContractError::CannotMigrate {
+                    previous_contract: stored.contract,
+                });
+            }
+            let previous_version: Version = stored.version.parse()?;
+            if previous_version > new_version {
+                return Err(ContractError::CannotDowngrade {
+                    previous_version: stored.version,
+                    new_version: CONTRACT_VERSION.to_string(),
+                });
+            }
+            // releases that change a stored layout add their rewrite here, keyed on `previous_version`
+        }
+        None => {
+            // only 0.1.0 didn't record its version
+            let owner = msg.owner.ok_or(ContractError::MissingOwner {})?;
+            migrate_from_v0_1(
+                deps.storage,
+                deps.api.addr_validate(&owner)?,
+                env.block.height,
+            )?;
+        }
+    }
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
 
     Ok(Response::default())
 }
 
+fn validate_cw20_price(deps: Deps, price: Option<Cw20Coin>) -> StdResult<Option<Cw20CoinVerified>> {
+    price
+        .map(|coin| {
+            Ok(Cw20CoinVerified {
+                address: deps.api.addr_validate(&coin.address)?,
+                amount: coin.amount,
+            })
+        })
+        .transpose()
+}
+
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn execute<E, P>(
+pub fn execute(
     deps: DepsMut,
     env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
-) -> Result<Response, ContractError> 
-where
-    E: Engine<G1Affine = G1Affine, G2Affine = G2Affine>,
-    P: PlonkConstraintSystemParams<E>,
-{
+) -> Result<Response, ContractError> {
     match msg {
         ExecuteMsg::Zkeys {
+            circuit_id,
+            metadata,
             n,
             num_inputs,
             selector_commitments,
@@ -59,10 +148,18 @@ where
             permutation_commitments,
             non_residues,
             g2_elements,
-        } => execute_set_zkeys::<E, P>(
+            params,
+            bind_sender,
+            nullifier_index,
+            bounty,
+            bounty_deadline,
+        } => execute_set_zkeys(
             deps,
             env,
             info,
+            None,
+            circuit_id,
+            metadata,
             n,
             num_inputs,
             selector_commitments,
@@ -70,9 +167,143 @@ where
             permutation_commitments,
             non_residues,
             g2_elements,
+            params,
+            bind_sender,
+            nullifier_index,
+            bounty,
+            bounty_deadline,
         ),
         ExecuteMsg::Proof {
             difficuty_issuer,
+            circuit_id,
+            num_inputs,
+            n,
+            input_values,
+            wire_commitments,
+            grand_product_commitment,
+            quotient_poly_commitments,
+            wire_values_at_z,
+            wire_values_at_z_omega,
+            grand_product_at_z_omega,
+            quotient_polynomial_at_z,
+            linearization_polynomial_at_z,
+            permutation_polynomials_at_z,
+            opening_at_z_proof,
+            opening_at_z_omega_proof,
+        } => execute_publish_proof(
+            deps,
+            env,
+            info,
+            None,
+            difficuty_issuer,
+            circuit_id,
+            num_inputs,
+            n,
+            input_values,
+            wire_commitments,
+            grand_product_commitment,
+            quotient_poly_commitments,
+            wire_values_at_z,
+            wire_values_at_z_omega,
+            grand_product_at_z_omega,
+            quotient_polynomial_at_z,
+            linearization_polynomial_at_z,
+            permutation_polynomials_at_z,
+            opening_at_z_proof,
+            opening_at_z_omega_proof,
+        ),
+        ExecuteMsg::ZkeysBinary {
+            circuit_id,
+            metadata,
+            n,
+            num_inputs,
+            selector_commitments,
+            next_step_selector_commitments,
+            permutation_commitments,
+            non_residues,
+            g2_elements,
+            params,
+            bind_sender,
+            nullifier_index,
+            bounty,
+            bounty_deadline,
+        } => {
+            let vkeys = binary_vkey(
+                n,
+                num_inputs,
+                selector_commitments,
+                next_step_selector_commitments,
+                permutation_commitments,
+                non_residues,
+                g2_elements,
+                params.unwrap_or_default(),
+            );
+            register_zkeys(
+                deps,
+                env,
+                info,
+                None,
+                circuit_id,
+                metadata,
+                Ok(vkeys),
+                bind_sender,
+                nullifier_index,
+                bounty,
+                bounty_deadline,
+            )
+        }
+        ExecuteMsg::ProofBinary {
+            difficuty_issuer,
+            circuit_id,
+            num_inputs,
+            n,
+            input_values,
+            wire_commitments,
+            grand_product_commitment,
+            quotient_poly_commitments,
+            wire_values_at_z,
+            wire_values_at_z_omega,
+            grand_product_at_z_omega,
+            quotient_polynomial_at_z,
+            linearization_polynomial_at_z,
+            permutation_polynomials_at_z,
+            opening_at_z_proof,
+            opening_at_z_omega_proof,
+        } => {
+            let proof_str = binary_proof(
+                num_inputs,
+                n,
+                input_values,
+                wire_commitments,
+                grand_product_commitment,
+                quotient_poly_commitments,
+                wire_values_at_z,
+                wire_values_at_z_omega,
+                grand_product_at_z_omega,
+                quotient_polynomial_at_z,
+                linearization_polynomial_at_z,
+                permutation_polynomials_at_z,
+                opening_at_z_proof,
+                opening_at_z_omega_proof,
+            );
+            publish_proof(
+                deps,
+                env,
+                info,
+                None,
+                difficuty_issuer,
+                circuit_id,
+                Ok(proof_str),
+            )
+        }
+        ExecuteMsg::CommitProof {
+            difficuty_issuer,
+            circuit_id,
+            commitment,
+        } => execute_commit_proof(deps, env, info, difficuty_issuer, circuit_id, commitment),
+        ExecuteMsg::RevealProof {
+            difficuty_issuer,
+            circuit_id,
             num_inputs,
             n,
             input_values,
@@ -87,11 +318,14 @@ where
             permutation_polynomials_at_z,
             opening_at_z_proof,
             opening_at_z_omega_proof,
-        } => execute_publish_proof::<E, P>(
-            deps, 
-            env, 
-            info, 
+            salt,
+        } => execute_reveal_proof(
+            deps,
+            env,
+            info,
+            None,
             difficuty_issuer,
+            circuit_id,
             num_inputs,
             n,
             input_values,
@@ -105,15 +339,331 @@ where
             linearization_polynomial_at_z,
             permutation_polynomials_at_z,
             opening_at_z_proof,
-            opening_at_z_omega_proof
+            opening_at_z_omega_proof,
+            salt,
+        ),
+        ExecuteMsg::SnarkjsZkeys {
+            circuit_id,
+            metadata,
+            vkey,
+            bind_sender,
+            nullifier_index,
+            bounty,
+            bounty_deadline,
+        } => execute_set_snarkjs_zkeys(
+            deps,
+            env,
+            info,
+            None,
+            circuit_id,
+            metadata,
+            vkey,
+            bind_sender,
+            nullifier_index,
+            bounty,
+            bounty_deadline,
+        ),
+        ExecuteMsg::SnarkjsProof {
+            difficuty_issuer,
+            circuit_id,
+            proof,
+            public_signals,
+        } => execute_publish_snarkjs_proof(
+            deps,
+            env,
+            info,
+            None,
+            difficuty_issuer,
+            circuit_id,
+            proof,
+            public_signals,
         ),
+        ExecuteMsg::SetCircuitStatus { circuit_id, status } => {
+            execute_set_circuit_status(deps, info, circuit_id, status)
+        }
+        ExecuteMsg::ReclaimBounty { circuit_id } => {
+            execute_reclaim_bounty(deps, env, info, circuit_id)
+        }
+        ExecuteMsg::ClaimBounty {
+            difficuty_issuer,
+            circuit_id,
+        } => execute_claim_bounty(deps, env, difficuty_issuer, circuit_id),
+        ExecuteMsg::Withdraw { amount, recipient } => {
+            execute_withdraw(deps, env, info, amount, recipient)
+        }
+        ExecuteMsg::WithdrawCw20 {
+            token,
+            amount,
+            recipient,
+        } => execute_withdraw_cw20(deps, info, token, amount, recipient),
+        ExecuteMsg::Receive(wrapper) => execute_receive(deps, env, info, wrapper),
     }
 }
 
-pub fn execute_set_zkeys<E, P>(
+/// the hook of a cw20 `Send`, the token contract is the caller and the
+/// embedded message is executed for the original sender
+pub fn execute_receive(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
+    info: MessageInfo,
+    wrapper: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let payment = Cw20CoinVerified {
+        address: info.sender,
+        amount: wrapper.amount,
+    };
+    let info = MessageInfo {
+        sender: deps.api.addr_validate(&wrapper.sender)?,
+        funds: vec![],
+    };
+
+    match from_json(&wrapper.msg)? {
+        ExecuteMsg::Zkeys {
+            circuit_id,
+            metadata,
+            n,
+            num_inputs,
+            selector_commitments,
+            next_step_selector_commitments,
+            permutation_commitments,
+            non_residues,
+            g2_elements,
+            params,
+            bind_sender,
+            nullifier_index,
+            bounty,
+            bounty_deadline,
+        } => execute_set_zkeys(
+            deps,
+            env,
+            info,
+            Some(payment),
+            circuit_id,
+            metadata,
+            n,
+            num_inputs,
+            selector_commitments,
+            next_step_selector_commitments,
+            permutation_commitments,
+            non_residues,
+            g2_elements,
+            params,
+            bind_sender,
+            nullifier_index,
+            bounty,
+            bounty_deadline,
+        ),
+        ExecuteMsg::Proof {
+            difficuty_issuer,
+            circuit_id,
+            num_inputs,
+            n,
+            input_values,
+            wire_commitments,
+            grand_product_commitment,
+            quotient_poly_commitments,
+            wire_values_at_z,
+            wire_values_at_z_omega,
+            grand_product_at_z_omega,
+            quotient_polynomial_at_z,
+            linearization_polynomial_at_z,
+            permutation_polynomials_at_z,
+            opening_at_z_proof,
+            opening_at_z_omega_proof,
+        } => execute_publish_proof(
+            deps,
+            env,
+            info,
+            Some(payment),
+            difficuty_issuer,
+            circuit_id,
+            num_inputs,
+            n,
+            input_values,
+            wire_commitments,
+            grand_product_commitment,
+            quotient_poly_commitments,
+            wire_values_at_z,
+            wire_values_at_z_omega,
+            grand_product_at_z_omega,
+            quotient_polynomial_at_z,
+            linearization_polynomial_at_z,
+            permutation_polynomials_at_z,
+            opening_at_z_proof,
+            opening_at_z_omega_proof,
+        ),
+        ExecuteMsg::ZkeysBinary {
+            circuit_id,
+            metadata,
+            n,
+            num_inputs,
+            selector_commitments,
+            next_step_selector_commitments,
+            permutation_commitments,
+            non_residues,
+            g2_elements,
+            params,
+            bind_sender,
+            nullifier_index,
+            bounty,
+            bounty_deadline,
+        } => {
+            let vkeys = binary_vkey(
+                n,
+                num_inputs,
+                selector_commitments,
+                next_step_selector_commitments,
+                permutation_commitments,
+                non_residues,
+                g2_elements,
+                params.unwrap_or_default(),
+            );
+            register_zkeys(
+                deps,
+                env,
+                info,
+                Some(payment),
+                circuit_id,
+                metadata,
+                Ok(vkeys),
+                bind_sender,
+                nullifier_index,
+                bounty,
+                bounty_deadline,
+            )
+        }
+        ExecuteMsg::ProofBinary {
+            difficuty_issuer,
+            circuit_id,
+            num_inputs,
+            n,
+            input_values,
+            wire_commitments,
+            grand_product_commitment,
+            quotient_poly_commitments,
+            wire_values_at_z,
+            wire_values_at_z_omega,
+            grand_product_at_z_omega,
+            quotient_polynomial_at_z,
+            linearization_polynomial_at_z,
+            permutation_polynomials_at_z,
+            opening_at_z_proof,
+            opening_at_z_omega_proof,
+        } => {
+            let proof_str = binary_proof(
+                num_inputs,
+                n,
+                input_values,
+                wire_commitments,
+                grand_product_commitment,
+                quotient_poly_commitments,
+                wire_values_at_z,
+                wire_values_at_z_omega,
+                grand_product_at_z_omega,
+                quotient_polynomial_at_z,
+                linearization_polynomial_at_z,
+                permutation_polynomials_at_z,
+                opening_at_z_proof,
+                opening_at_z_omega_proof,
+            );
+            publish_proof(
+                deps,
+                env,
+                info,
+                Some(payment),
+                difficuty_issuer,
+                circuit_id,
+                Ok(proof_str),
+            )
+        }
+        ExecuteMsg::RevealProof {
+            difficuty_issuer,
+            circuit_id,
+            num_inputs,
+            n,
+            input_values,
+            wire_commitments,
+            grand_product_commitment,
+            quotient_poly_commitments,
+            wire_values_at_z,
+            wire_values_at_z_omega,
+            grand_product_at_z_omega,
+            quotient_polynomial_at_z,
+            linearization_polynomial_at_z,
+            permutation_polynomials_at_z,
+            opening_at_z_proof,
+            opening_at_z_omega_proof,
+            salt,
+        } => execute_reveal_proof(
+            deps,
+            env,
+            info,
+            Some(payment),
+            difficuty_issuer,
+            circuit_id,
+            num_inputs,
+            n,
+            input_values,
+            wire_commitments,
+            grand_product_commitment,
+            quotient_poly_commitments,
+            wire_values_at_z,
+            wire_values_at_z_omega,
+            grand_product_at_z_omega,
+            quotient_polynomial_at_z,
+            linearization_polynomial_at_z,
+            permutation_polynomials_at_z,
+            opening_at_z_proof,
+            opening_at_z_omega_proof,
+            salt,
+        ),
+        ExecuteMsg::SnarkjsZkeys {
+            circuit_id,
+            metadata,
+            vkey,
+            bind_sender,
+            nullifier_index,
+            bounty,
+            bounty_deadline,
+        } => execute_set_snarkjs_zkeys(
+            deps,
+            env,
+            info,
+            Some(payment),
+            circuit_id,
+            metadata,
+            vkey,
+            bind_sender,
+            nullifier_index,
+            bounty,
+            bounty_deadline,
+        ),
+        ExecuteMsg::SnarkjsProof {
+            difficuty_issuer,
+            circuit_id,
+            proof,
+            public_signals,
+        } => execute_publish_snarkjs_proof(
+            deps,
+            env,
+            info,
+            Some(payment),
+            difficuty_issuer,
+            circuit_id,
+            proof,
+            public_signals,
+        ),
+        _ => Err(ContractError::InvalidCw20Hook {}),
+    }
+}
+
+pub fn execute_set_zkeys(
+    deps: DepsMut,
+    env: Env,
     info: MessageInfo,
+    cw20_payment: Option<Cw20CoinVerified>,
+    circuit_id: Option<String>,
+    metadata: Option<CircuitMetadata>,
     n: usize,
     num_inputs: usize,
     selector_commitments: Vec<String>,
@@ -121,38 +671,185 @@ pub fn execute_set_zkeys<E, P>(
     permutation_commitments: Vec<String>,
     non_residues: Vec<String>,
     g2_elements: Vec<String>,
-) -> Result<Response, ContractError> 
-where
-    E: Engine<G1Affine = G1Affine, G2Affine = G2Affine>,
-    P: PlonkConstraintSystemParams<E>,
-{
-    let config = CONFIG.load(deps.storage)?;
-    assert_sent_sufficient_coin(&info.funds, config.zkeys_price)?;
-    // address
-    // let key = info.sender.as_str().as_bytes();
-    let vkeys = VkeyStr {
+    params: Option<PlonkParams>,
+    bind_sender: Option<bool>,
+    nullifier_index: Option<usize>,
+    bounty: Option<Coin>,
+    bounty_deadline: Option<u64>,
+) -> Result<Response, ContractError> {
+    let vkeys = decode_vkey(
         n,
         num_inputs,
-        selector_commitments: selector_commitments.into_iter().map(|x| hex::decode(x).unwrap()).collect(),
-        next_step_selector_commitments: next_step_selector_commitments.into_iter().map(|x| hex::decode(x).unwrap()).collect(),
-        permutation_commitments: permutation_commitments.into_iter().map(|x| hex::decode(x).unwrap()).collect(),
+        selector_commitments,
+        next_step_selector_commitments,
+        permutation_commitments,
         non_residues,
-        g2_elements: g2_elements.into_iter().map(|x| hex::decode(x).unwrap()).collect()
+        g2_elements,
+        params.unwrap_or_default(),
+    );
+    register_zkeys(
+        deps,
+        env,
+        info,
+        cw20_payment,
+        circuit_id,
+        metadata,
+        vkeys,
+        bind_sender,
+        nullifier_index,
+        bounty,
+        bounty_deadline,
+    )
+}
+
+pub fn execute_set_snarkjs_zkeys(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_payment: Option<Cw20CoinVerified>,
+    circuit_id: Option<String>,
+    metadata: Option<CircuitMetadata>,
+    vkey: SnarkjsVerifyingKey,
+    bind_sender: Option<bool>,
+    nullifier_index: Option<usize>,
+    bounty: Option<Coin>,
+    bounty_deadline: Option<u64>,
+) -> Result<Response, ContractError> {
+    register_zkeys(
+        deps,
+        env,
+        info,
+        cw20_payment,
+        circuit_id,
+        metadata,
+        vkey.to_vkey_str(),
+        bind_sender,
+        nullifier_index,
+        bounty,
+        bounty_deadline,
+    )
+}
+
+/// register a decoded key, its decoding error is only reported after the
+/// circuit and the payment are checked
+fn register_zkeys(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_payment: Option<Cw20CoinVerified>,
+    circuit_id: Option<String>,
+    metadata: Option<CircuitMetadata>,
+    vkeys: Result<VkeyStr, ContractError>,
+    bind_sender: Option<bool>,
+    nullifier_index: Option<usize>,
+    bounty: Option<Coin>,
+    bounty_deadline: Option<u64>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let circuit_id = circuit_or_default(circuit_id);
+    if circuit_id.is_empty() || circuit_id.len() > MAX_CIRCUIT_ID_LENGTH {
+        return Err(ContractError::InvalidCircuitId {});
+    }
+    if let Some(registered) = ZKEYS.may_load(deps.storage, (&info.sender, &circuit_id))? {
+        // a key is only replaced while no listed proof or open bounty depends on it
+        let proven = PROVERLIST
+            .prefix((&info.sender, &circuit_id))
+            .keys(deps.storage, None, None, Order::Ascending)
+            .next()
+            .is_some();
+        if proven
+            || registered.status == CircuitStatus::Retired
+            || BOUNTIES.has(deps.storage, (&info.sender, &circuit_id))
+        {
+            return Err(ContractError::CircuitAlreadyRegistered { circuit_id });
+        }
+    }
+    let metadata = metadata.unwrap_or_default();
+    validate_metadata(&metadata)?;
+    // the reward is held apart from the registration fee
+    let funds = match &bounty {
+        Some(reward) => deduct_coin(&info.funds, reward)?,
+        None => info.funds.clone(),
+    };
+    let (refund, fee) = charge_price(
+        &info.sender,
+        &funds,
+        cw20_payment,
+        config.zkeys_price.clone(),
+        config.zkeys_cw20_price.clone(),
+        &config,
+    )?;
+    let bounty = match bounty {
+        Some(reward) => {
+            let deadline = bounty_deadline.ok_or(ContractError::InvalidBounty {})?;
+            if reward.amount.is_zero() || deadline <= env.block.height {
+                return Err(ContractError::InvalidBounty {});
+            }
+            if BOUNTIES.has(deps.storage, (&info.sender, &circuit_id)) {
+                return Err(ContractError::BountyAlreadyOpen {});
+            }
+            Some(Bounty {
+                reward,
+                deadline,
+                winner: None,
+            })
+        }
+        None => None,
     };
 
+    let vkeys = vkeys?;
+    if nullifier_index.is_some_and(|index| index >= vkeys.num_inputs) {
+        return Err(ContractError::InvalidNullifierIndex {});
+    }
+    let bind_sender = bind_sender.unwrap_or(false);
+    if bind_sender && vkeys.num_inputs == 0 {
+        return Err(ContractError::ErrorVerificationKey {
+            field: "num_inputs".to_string(),
+            reason: "a key binding the sender needs a public input for the sender signal"
+                .to_string(),
+        });
+    }
+
     // jsut check the vkey is valid
-    let _ = parse_vkey::<Bn256, PlonkCsWidth4WithNextStepParams>(vkeys.clone())?;
+    check_vkey(&vkeys)?;
 
-    ZKEYS.save(deps.storage, &info.sender, &vkeys)?;
+    let zkeys = ZkeysStr {
+        vkeys,
+        bind_sender,
+        nullifier_index,
+        metadata,
+        status: CircuitStatus::Active,
+    };
+    ZKEYS.save(deps.storage, (&info.sender, &circuit_id), &zkeys)?;
+    let mut response = Response::new().add_messages(refund).add_event(
+        Event::new("zkeys_registered")
+            .add_attribute("issuer", info.sender.as_str())
+            .add_attribute("circuit_id", &circuit_id)
+            .add_attribute("vkey_hash", hex::encode(vkey_hash(&zkeys.vkeys)))
+            .add_attribute("proof_system", PROOF_SYSTEM)
+            .add_attribute("fee", fee),
+    );
+    if let Some(bounty) = bounty {
+        escrow_bounty(deps.storage, &info.sender, &circuit_id, &bounty)?;
+        response = response.add_event(
+            Event::new("bounty_opened")
+                .add_attribute("issuer", info.sender.as_str())
+                .add_attribute("circuit_id", circuit_id)
+                .add_attribute("reward", bounty.reward.to_string())
+                .add_attribute("deadline", bounty.deadline.to_string()),
+        );
+    }
 
-    Ok(Response::default())
+    Ok(response)
 }
 
-pub fn execute_publish_proof<E, P>(
+pub fn execute_publish_proof(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
+    cw20_payment: Option<Cw20CoinVerified>,
     difficuty_issuer: String,
+    circuit_id: Option<String>,
 
     num_inputs: usize,
     n: usize,
@@ -168,114 +865,1522 @@ pub fn execute_publish_proof<E, P>(
     permutation_polynomials_at_z: Vec<String>,
     opening_at_z_proof: String,
     opening_at_z_omega_proof: String,
-) -> Result<Response, ContractError> 
-where
-    E: Engine<G1Affine = G1Affine, G2Affine = G2Affine>,
-    P: PlonkConstraintSystemParams<E>,
-{
-    let config = CONFIG.load(deps.storage)?;
-    assert_sent_sufficient_coin(&info.funds, config.proof_price)?;
-
-    //  the issuer address is valid?
-    let issuer = deps.api.addr_validate(&difficuty_issuer)?;
-
-    if !(ZKEYS.may_load(deps.storage, &issuer)?).is_some() {
-        // this issuer didn't public diffuculty problem
-        return Err(ContractError::NonPublishDifficulty { difficuty_issuer });
-    }
-
-    let vkeys_str = ZKEYS.load(deps.storage, &issuer).unwrap();
-
-    // verify the proof
-    let proof_str = ProofStr {
+) -> Result<Response, ContractError> {
+    let proof_str = decode_proof(
         num_inputs,
         n,
         input_values,
-        wire_commitments: wire_commitments.into_iter().map(|x| hex::decode(x).unwrap()).collect(),
-        grand_product_commitment: hex::decode(grand_product_commitment).map_err(|_| ContractError::HexDecodingError{})?,
-        quotient_poly_commitments: quotient_poly_commitments.into_iter().map(|x| hex::decode(x).unwrap()).collect(),
+        wire_commitments,
+        grand_product_commitment,
+        quotient_poly_commitments,
         wire_values_at_z,
         wire_values_at_z_omega,
         grand_product_at_z_omega,
         quotient_polynomial_at_z,
         linearization_polynomial_at_z,
         permutation_polynomials_at_z,
-        opening_at_z_proof: hex::decode(opening_at_z_proof).map_err(|_| ContractError::HexDecodingError{})?,
-        opening_at_z_omega_proof: hex::decode(opening_at_z_omega_proof).map_err(|_| ContractError::HexDecodingError{})?
-    };
-
-    let pof = parse_proof::<Bn256, PlonkCsWidth4WithNextStepParams>(proof_str.clone())?;
-    let vkey = parse_vkey::<Bn256, PlonkCsWidth4WithNextStepParams>(vkeys_str)?;
-    
-    let ok = plonk_verify::<_, _, RollingKeccakTranscript<pairing_ce::bn256::Fr>>(&pof, &vkey, None).map_err(|_| ContractError::SynthesisError {})?;
+        opening_at_z_proof,
+        opening_at_z_omega_proof,
+    );
+    publish_proof(
+        deps,
+        env,
+        info,
+        cw20_payment,
+        difficuty_issuer,
+        circuit_id,
+        proof_str,
+    )
+}
 
+/// verify and record a decoded proof, its decoding error is only reported after the payment is checked
+fn publish_proof(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_payment: Option<Cw20CoinVerified>,
+    difficuty_issuer: String,
+    circuit_id: Option<String>,
+    proof_str: Result<ProofStr, ContractError>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let (refund, fee) = charge_price(
+        &info.sender,
+        &info.funds,
+        cw20_payment,
+        config.proof_price.clone(),
+        config.proof_cw20_price.clone(),
+        &config,
+    )?;
+
+    // a direct submission counts as committed in the block it lands in
+    let proof_str = proof_str?;
+    Ok(verify_and_record(
+        deps,
+        &env,
+        &info.sender,
+        difficuty_issuer,
+        circuit_or_default(circuit_id),
+        move |vkeys| bellman_proof(vkeys, proof_str),
+        env.block.height,
+        fee,
+    )?
+    .add_messages(refund))
+}
+
+pub fn execute_publish_snarkjs_proof(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_payment: Option<Cw20CoinVerified>,
+    difficuty_issuer: String,
+    circuit_id: Option<String>,
+    proof: SnarkjsProof,
+    public_signals: Vec<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let (refund, fee) = charge_price(
+        &info.sender,
+        &info.funds,
+        cw20_payment,
+        config.proof_price.clone(),
+        config.proof_cw20_price.clone(),
+        &config,
+    )?;
+
+    // the domain of the proof is only known from the key
+    let proof_str = move |vkeys: &VkeyStr| proof.to_proof_str(vkeys, public_signals);
+    Ok(verify_and_record(
+        deps,
+        &env,
+        &info.sender,
+        difficuty_issuer,
+        circuit_or_default(circuit_id),
+        proof_str,
+        env.block.height,
+        fee,
+    )?
+    .add_messages(refund))
+}
+
+pub fn execute_commit_proof(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    difficuty_issuer: String,
+    circuit_id: Option<String>,
+    commitment: String,
+) -> Result<Response, ContractError> {
+    let issuer = deps.api.addr_validate(&difficuty_issuer)?;
+    let circuit_id = circuit_or_default(circuit_id);
+
+    match ZKEYS.may_load(deps.storage, (&issuer, &circuit_id))? {
+        None => return Err(ContractError::NonPublishDifficulty { difficuty_issuer }),
+        Some(zkeys) if zkeys.status == CircuitStatus::Retired => {
+            return Err(ContractError::CircuitRetired { circuit_id })
+        }
+        // a snarkjs proof can't be revealed, so there is nothing to commit to
+        Some(zkeys) if zkeys.vkeys.params == PlonkParams::Snarkjs => {
+            return Err(ContractError::SnarkjsCircuit {})
+        }
+        Some(_) => {}
+    }
+
+    let hash = hex::decode(commitment).map_err(|_| ContractError::InvalidCommitment {})?;
+    if hash.len() != 32 {
+        return Err(ContractError::InvalidCommitment {});
+    }
+
+    // committing again replaces the previous commitment of this prover
+    let commitment = Commitment {
+        hash,
+        height: env.block.height,
+    };
+    COMMITMENTS.save(
+        deps.storage,
+        (&issuer, &circuit_id, &info.sender),
+        &commitment,
+    )?;
+
+    Ok(Response::new().add_event(
+        Event::new("proof_committed")
+            .add_attribute("issuer", issuer.as_str())
+            .add_attribute("circuit_id", circuit_id)
+            .add_attribute("prover", info.sender.as_str()),
+    ))
+}
+
+pub fn execute_reveal_proof(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_payment: Option<Cw20CoinVerified>,
+    difficuty_issuer: String,
+    circuit_id: Option<String>,
+
+    num_inputs: usize,
+    n: usize,
+    input_values: Vec<String>,
+    wire_commitments: Vec<String>,
+    grand_product_commitment: String,
+    quotient_poly_commitments: Vec<String>,
+    wire_values_at_z: Vec<String>,
+    wire_values_at_z_omega: Vec<String>,
+    grand_product_at_z_omega: String,
+    quotient_polynomial_at_z: String,
+    linearization_polynomial_at_z: String,
+    permutation_polynomials_at_z: Vec<String>,
+    opening_at_z_proof: String,
+    opening_at_z_omega_proof: String,
+    salt: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let (refund, fee) = charge_price(
+        &info.sender,
+        &info.funds,
+        cw20_payment,
+        config.proof_price.clone(),
+        config.proof_cw20_price.clone(),
+        &config,
+    )?;
+
+    let issuer = deps.api.addr_validate(&difficuty_issuer)?;
+    let circuit_id = circuit_or_default(circuit_id);
+    let commitment = COMMITMENTS
+        .may_load(deps.storage, (&issuer, &circuit_id, &info.sender))?
+        .ok_or(ContractError::NoCommitment {})?;
+
+    if env.block.height <= commitment.height {
+        // the proof would be public in the same block as the commitment
+        return Err(ContractError::RevealTooEarly {});
+    }
+    if env.block.height > commitment.height.saturating_add(config.commitment_expiry) {
+        return Err(ContractError::CommitmentExpired {});
+    }
+
+    let proof_str = decode_proof(
+        num_inputs,
+        n,
+        input_values,
+        wire_commitments,
+        grand_product_commitment,
+        quotient_poly_commitments,
+        wire_values_at_z,
+        wire_values_at_z_omega,
+        grand_product_at_z_omega,
+        quotient_polynomial_at_z,
+        linearization_polynomial_at_z,
+        permutation_polynomials_at_z,
+        opening_at_z_proof,
+        opening_at_z_omega_proof,
+    )?;
+    if proof_commitment(&proof_str, &info.sender, &salt) != commitment.hash {
+        return Err(ContractError::CommitmentMismatch {});
+    }
+    COMMITMENTS.remove(deps.storage, (&issuer, &circuit_id, &info.sender));
+
+    Ok(verify_and_record(
+        deps,
+        &env,
+        &info.sender,
+        difficuty_issuer,
+        circuit_id,
+        move |vkeys| bellman_proof(vkeys, proof_str),
+        commitment.height,
+        fee,
+    )?
+    .add_messages(refund))
+}
+
+pub fn execute_set_circuit_status(
+    deps: DepsMut,
+    info: MessageInfo,
+    circuit_id: Option<String>,
+    status: CircuitStatus,
+) -> Result<Response, ContractError> {
+    let circuit_id = circuit_or_default(circuit_id);
+    let mut zkeys = ZKEYS
+        .may_load(deps.storage, (&info.sender, &circuit_id))?
+        .ok_or_else(|| ContractError::NonPublishDifficulty {
+            difficuty_issuer: info.sender.to_string(),
+        })?;
+    if zkeys.status == CircuitStatus::Retired && status != CircuitStatus::Retired {
+        // provers may have dropped the circuit for good
+        return Err(ContractError::CircuitRetired { circuit_id });
+    }
+
+    zkeys.status = status;
+    ZKEYS.save(deps.storage, (&info.sender, &circuit_id), &zkeys)?;
+    Ok(Response::new().add_event(
+        Event::new("circuit_status_changed")
+            .add_attribute("issuer", info.sender.as_str())
+            .add_attribute("circuit_id", circuit_id)
+            .add_attribute("status", status_attribute(&zkeys.status)),
+    ))
+}
+
+pub fn execute_reclaim_bounty(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    circuit_id: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let circuit_id = circuit_or_default(circuit_id);
+    let bounty = BOUNTIES
+        .may_load(deps.storage, (&info.sender, &circuit_id))?
+        .ok_or(ContractError::NoBounty {})?;
+    if bounty.winner.is_some() {
+        return Err(ContractError::BountyAlreadyWon {});
+    }
+    // a commitment made by the deadline can still be revealed to win the bounty
+    let reclaimable_at = bounty
+        .deadline
+        .saturating_add(config.commitment_expiry)
+        .saturating_add(1);
+    if env.block.height < reclaimable_at {
+        return Err(ContractError::BountyNotExpired { reclaimable_at });
+    }
+
+    release_bounty(deps.storage, &info.sender, &circuit_id, &bounty)?;
+    Ok(Response::new()
+        .add_event(
+            Event::new("bounty_reclaimed")
+                .add_attribute("issuer", info.sender.as_str())
+                .add_attribute("circuit_id", circuit_id)
+                .add_attribute("reward", bounty.reward.to_string()),
+        )
+        .add_message(BankMsg::Send {
+            to_address: info.sender.into_string(),
+            amount: vec![bounty.reward],
+        }))
+}
+
+pub fn execute_claim_bounty(
+    deps: DepsMut,
+    env: Env,
+    difficuty_issuer: String,
+    circuit_id: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let issuer = deps.api.addr_validate(&difficuty_issuer)?;
+    let circuit_id = circuit_or_default(circuit_id);
+    let bounty = BOUNTIES
+        .may_load(deps.storage, (&issuer, &circuit_id))?
+        .ok_or(ContractError::NoBounty {})?;
+    let winner = bounty
+        .winner
+        .clone()
+        .ok_or(ContractError::BountyNotWon {})?;
+    let claimable_at = winner.committed_at.saturating_add(config.commitment_expiry);
+    if env.block.height < claimable_at {
+        // a commitment made before the winning one can still be revealed
+        return Err(ContractError::BountyNotClaimable { claimable_at });
+    }
+
+    release_bounty(deps.storage, &issuer, &circuit_id, &bounty)?;
+    Ok(Response::new()
+        .add_event(
+            Event::new("bounty_paid")
+                .add_attribute("issuer", issuer.as_str())
+                .add_attribute("circuit_id", circuit_id)
+                .add_attribute("prover", winner.prover.as_str())
+                .add_attribute("reward", bounty.reward.to_string()),
+        )
+        .add_message(BankMsg::Send {
+            to_address: winner.prover.into_string(),
+            amount: vec![bounty.reward],
+        }))
+}
+
+pub fn execute_withdraw(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Vec<Coin>,
+    recipient: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if amount.is_empty() || amount.iter().any(|coin| coin.amount.is_zero()) {
+        return Err(ContractError::EmptyWithdrawal {});
+    }
+
+    // open bounties are not fees
+    let fees = fee_balance(deps.as_ref(), &env)?;
+    for coin in amount.iter() {
+        let available = fees
+            .iter()
+            .find(|fee| fee.denom == coin.denom)
+            .map_or(Uint128::zero(), |fee| fee.amount);
+        if coin.amount > available {
+            return Err(ContractError::InsufficientFees {
+                denom: coin.denom.clone(),
+            });
+        }
+    }
+
+    let recipient = deps.api.addr_validate(&recipient)?;
+    Ok(Response::new()
+        .add_event(
+            Event::new("fees_withdrawn")
+                .add_attribute("recipient", recipient.as_str())
+                .add_attribute("amount", coins_attribute(&amount)),
+        )
+        .add_message(BankMsg::Send {
+            to_address: recipient.into_string(),
+            amount,
+        }))
+}
+
+pub fn execute_withdraw_cw20(
+    deps: DepsMut,
+    info: MessageInfo,
+    token: String,
+    amount: Uint128,
+    recipient: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if amount.is_zero() {
+        return Err(ContractError::EmptyWithdrawal {});
+    }
+
+    // bounties are native coins, every cw20 token held is a fee
+    let token = deps.api.addr_validate(&token)?;
+    let recipient = deps.api.addr_validate(&recipient)?;
+    Ok(Response::new()
+        .add_event(
+            Event::new("fees_withdrawn")
+                .add_attribute("recipient", recipient.as_str())
+                .add_attribute("amount", format!("{}{}", amount, token)),
+        )
+        .add_message(WasmMsg::Execute {
+            contract_addr: token.into_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: recipient.into_string(),
+                amount,
+            })?,
+            funds: vec![],
+        }))
+}
+
+/// charge a price from the native funds, or from the cw20 tokens of a `Receive`,
+/// and return the refund messages with the fee that was kept
+fn charge_price(
+    sender: &Addr,
+    funds: &[Coin],
+    cw20_payment: Option<Cw20CoinVerified>,
+    price: Option<Coin>,
+    cw20_price: Option<Cw20CoinVerified>,
+    config: &Config,
+) -> Result<(Vec<CosmosMsg>, String), ContractError> {
+    let exact = config.fee_mode == FeeMode::Exact;
+    match cw20_payment {
+        Some(payment) => {
+            let refund = charge_cw20_fee(sender, &payment, cw20_price.clone(), &config.fee_mode)?;
+            let paid = match cw20_price {
+                Some(price) if exact => price,
+                _ => payment,
+            };
+            Ok((
+                refund.into_iter().map(CosmosMsg::from).collect(),
+                format!("{}{}", paid.amount, paid.address),
+            ))
+        }
+        None => {
+            if price.is_none() && cw20_price.is_some() {
+                // only payable in the cw20 token
+                return Err(ContractError::InsufficientFundsSend {});
+            }
+            let refund = charge_fee(
+                sender,
+                funds,
+                price.clone(),
+                &config.fee_mode,
+                &config.unrelated_denoms,
+            )?;
+            let paid = if exact {
+                price.into_iter().collect()
+            } else {
+                funds.to_vec()
+            };
+            Ok((
+                refund.into_iter().map(CosmosMsg::from).collect(),
+                coins_attribute(&paid),
+            ))
+        }
+    }
+}
+
+/// the circuit a message is about, `DEFAULT_CIRCUIT` when it doesn't name one
+fn circuit_or_default(circuit_id: Option<String>) -> String {
+    circuit_id.unwrap_or_else(|| DEFAULT_CIRCUIT.to_string())
+}
+
+/// the limits on what an issuer can tell about a circuit, the artifact hash is optional
+fn validate_metadata(metadata: &CircuitMetadata) -> Result<(), ContractError> {
+    let invalid = |field: &str| ContractError::InvalidCircuitMetadata {
+        field: field.to_string(),
+    };
+    if metadata.name.len() > MAX_CIRCUIT_NAME_LENGTH {
+        return Err(invalid("name"));
+    }
+    if metadata.description.len() > MAX_CIRCUIT_DESCRIPTION_LENGTH {
+        return Err(invalid("description"));
+    }
+    if metadata.source_uri.len() > MAX_CIRCUIT_SOURCE_URI_LENGTH {
+        return Err(invalid("source_uri"));
+    }
+    if !metadata.artifact_hash.is_empty()
+        && !matches!(hex::decode(&metadata.artifact_hash), Ok(hash) if hash.len() == 32)
+    {
+        return Err(invalid("artifact_hash"));
+    }
+    Ok(())
+}
+
+fn status_attribute(status: &CircuitStatus) -> &'static str {
+    match status {
+        CircuitStatus::Active => "active",
+        CircuitStatus::Deprecated => "deprecated",
+        CircuitStatus::Retired => "retired",
+    }
+}
+
+/// coins as an event attribute value, `<amount><denom>` separated by commas
+fn coins_attribute(coins: &[Coin]) -> String {
+    coins
+        .iter()
+        .map(Coin::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+pub fn decode_proof(
+    num_inputs: usize,
+    n: usize,
+    input_values: Vec<String>,
+    wire_commitments: Vec<String>,
+    grand_product_commitment: String,
+    quotient_poly_commitments: Vec<String>,
+    wire_values_at_z: Vec<String>,
+    wire_values_at_z_omega: Vec<String>,
+    grand_product_at_z_omega: String,
+    quotient_polynomial_at_z: String,
+    linearization_polynomial_at_z: String,
+    permutation_polynomials_at_z: Vec<String>,
+    opening_at_z_proof: String,
+    opening_at_z_omega_proof: String,
+) -> Result<ProofStr, ContractError> {
+    Ok(ProofStr {
+        num_inputs,
+        n,
+        input_values,
+        wire_commitments: decode_hex_list(wire_commitments, "wire_commitments")?,
+        grand_product_commitment: decode_hex(grand_product_commitment, "grand_product_commitment")?,
+        quotient_poly_commitments: decode_hex_list(
+            quotient_poly_commitments,
+            "quotient_poly_commitments",
+        )?,
+        wire_values_at_z,
+        wire_values_at_z_omega,
+        grand_product_at_z_omega,
+        quotient_polynomial_at_z,
+        linearization_polynomial_at_z,
+        permutation_polynomials_at_z,
+        opening_at_z_proof: decode_hex(opening_at_z_proof, "opening_at_z_proof")?,
+        opening_at_z_omega_proof: decode_hex(opening_at_z_omega_proof, "opening_at_z_omega_proof")?,
+    })
+}
+
+pub fn decode_vkey(
+    n: usize,
+    num_inputs: usize,
+    selector_commitments: Vec<String>,
+    next_step_selector_commitments: Vec<String>,
+    permutation_commitments: Vec<String>,
+    non_residues: Vec<String>,
+    g2_elements: Vec<String>,
+    params: PlonkParams,
+) -> Result<VkeyStr, ContractError> {
+    Ok(VkeyStr {
+        n,
+        num_inputs,
+        selector_commitments: decode_hex_list(selector_commitments, "selector_commitments")?,
+        next_step_selector_commitments: decode_hex_list(
+            next_step_selector_commitments,
+            "next_step_selector_commitments",
+        )?,
+        permutation_commitments: decode_hex_list(
+            permutation_commitments,
+            "permutation_commitments",
+        )?,
+        non_residues,
+        g2_elements: decode_hex_list(g2_elements, "g2_elements")?,
+        params,
+    })
+}
+
+/// the points of a `ProofBinary` proof, nothing is left to decode
+pub fn binary_proof(
+    num_inputs: usize,
+    n: usize,
+    input_values: Vec<String>,
+    wire_commitments: Vec<Binary>,
+    grand_product_commitment: Binary,
+    quotient_poly_commitments: Vec<Binary>,
+    wire_values_at_z: Vec<String>,
+    wire_values_at_z_omega: Vec<String>,
+    grand_product_at_z_omega: String,
+    quotient_polynomial_at_z: String,
+    linearization_polynomial_at_z: String,
+    permutation_polynomials_at_z: Vec<String>,
+    opening_at_z_proof: Binary,
+    opening_at_z_omega_proof: Binary,
+) -> ProofStr {
+    ProofStr {
+        num_inputs,
+        n,
+        input_values,
+        wire_commitments: wire_commitments.into_iter().map(Vec::from).collect(),
+        grand_product_commitment: grand_product_commitment.into(),
+        quotient_poly_commitments: quotient_poly_commitments
+            .into_iter()
+            .map(Vec::from)
+            .collect(),
+        wire_values_at_z,
+        wire_values_at_z_omega,
+        grand_product_at_z_omega,
+        quotient_polynomial_at_z,
+        linearization_polynomial_at_z,
+        permutation_polynomials_at_z,
+        opening_at_z_proof: opening_at_z_proof.into(),
+        opening_at_z_omega_proof: opening_at_z_omega_proof.into(),
+    }
+}
+
+/// the points of a `ZkeysBinary` key
+pub fn binary_vkey(
+    n: usize,
+    num_inputs: usize,
+    selector_commitments: Vec<Binary>,
+    next_step_selector_commitments: Vec<Binary>,
+    permutation_commitments: Vec<Binary>,
+    non_residues: Vec<String>,
+    g2_elements: Vec<Binary>,
+    params: PlonkParams,
+) -> VkeyStr {
+    VkeyStr {
+        n,
+        num_inputs,
+        selector_commitments: selector_commitments.into_iter().map(Vec::from).collect(),
+        next_step_selector_commitments: next_step_selector_commitments
+            .into_iter()
+            .map(Vec::from)
+            .collect(),
+        permutation_commitments: permutation_commitments.into_iter().map(Vec::from).collect(),
+        non_residues,
+        g2_elements: g2_elements.into_iter().map(Vec::from).collect(),
+        params,
+    }
+}
+
+/// the bytes of a hex message field, a failure names the field
+fn decode_hex(value: String, field: &str) -> Result<Vec<u8>, ContractError> {
+    hex::decode(value).map_err(|_| ContractError::HexDecodingError {
+        field: field.to_string(),
+    })
+}
+
+/// the bytes of every hex string of a list field, a failure names the index
+fn decode_hex_list(values: Vec<String>, field: &str) -> Result<Vec<Vec<u8>>, ContractError> {
+    values
+        .into_iter()
+        .enumerate()
+        .map(|(i, x)| decode_hex(x, &format!("{}[{}]", field, i)))
+        .collect()
+}
+
+/// a proof in the layout of the bellman messages, a circuit registered with `SnarkjsZkeys`
+/// only takes the `proof.json` of `SnarkjsProof`
+pub fn bellman_proof(vkeys: &VkeyStr, proof_str: ProofStr) -> Result<ProofStr, ContractError> {
+    if vkeys.params == PlonkParams::Snarkjs {
+        return Err(ContractError::SnarkjsCircuit {});
+    }
+    Ok(proof_str)
+}
+
+/// check the key parses in the shape of its parameter set
+pub fn check_vkey(vkeys_str: &VkeyStr) -> Result<(), ContractError> {
+    match vkeys_str.params {
+        PlonkParams::Width4WithNextStep => {
+            parse_vkey::<Bn256, PlonkCsWidth4WithNextStepParams>(vkeys_str.clone()).map(|_| ())
+        }
+        PlonkParams::Width3WithNextStep => {
+            parse_vkey::<Bn256, PlonkCsWidth3WithNextStepParams>(vkeys_str.clone()).map(|_| ())
+        }
+        PlonkParams::Snarkjs => snarkjs::check_vkey(vkeys_str),
+    }
+}
+
+/// run the verifier for the parameter set of the key, the parsed public inputs are returned with the result
+pub fn check_proof(
+    vkeys_str: VkeyStr,
+    proof_str: ProofStr,
+) -> Result<(bool, Vec<pairing_ce::bn256::Fr>), ContractError> {
+    match vkeys_str.params {
+        PlonkParams::Width4WithNextStep => {
+            verify_with::<PlonkCsWidth4WithNextStepParams>(vkeys_str, proof_str)
+        }
+        PlonkParams::Width3WithNextStep => {
+            verify_with::<PlonkCsWidth3WithNextStepParams>(vkeys_str, proof_str)
+        }
+        PlonkParams::Snarkjs => snarkjs::verify(&vkeys_str, &proof_str),
+    }
+}
+
+fn verify_with<P: PlonkConstraintSystemParams<Bn256>>(
+    vkeys_str: VkeyStr,
+    proof_str: ProofStr,
+) -> Result<(bool, Vec<pairing_ce::bn256::Fr>), ContractError> {
+    let pof = parse_proof::<Bn256, P>(proof_str)?;
+    let vkey = parse_vkey::<Bn256, P>(vkeys_str)?;
+    if pof.n != vkey.n {
+        return Err(ContractError::ErrorProof {
+            field: "n".to_string(),
+            reason: format!("expected {}, got {}", vkey.n, pof.n),
+        });
+    }
+    if pof.num_inputs != vkey.num_inputs {
+        return Err(ContractError::ErrorProof {
+            field: "num_inputs".to_string(),
+            reason: format!("expected {}, got {}", vkey.num_inputs, pof.num_inputs),
+        });
+    }
+
+    let ok =
+        plonk_verify::<_, _, RollingKeccakTranscript<pairing_ce::bn256::Fr>>(&pof, &vkey, None)
+            .map_err(|_| ContractError::SynthesisError {})?;
+    Ok((ok, pof.input_values))
+}
+
+/// verify the proof against the key of the issuer's circuit and credit the prover,
+/// unless its statement was committed to earlier by someone else
+fn verify_and_record(
+    deps: DepsMut,
+    env: &Env,
+    prover: &Addr,
+    difficuty_issuer: String,
+    circuit_id: String,
+    proof_str: impl FnOnce(&VkeyStr) -> Result<ProofStr, ContractError>,
+    committed_at: u64,
+    fee: String,
+) -> Result<Response, ContractError> {
+    //  the issuer address is valid?
+    let issuer = deps.api.addr_validate(&difficuty_issuer)?;
+
+    let zkeys = match ZKEYS.may_load(deps.storage, (&issuer, &circuit_id))? {
+        Some(zkeys) => zkeys,
+        // this issuer didn't public diffuculty problem
+        None => return Err(ContractError::NonPublishDifficulty { difficuty_issuer }),
+    };
+    if zkeys.status == CircuitStatus::Retired {
+        return Err(ContractError::CircuitRetired { circuit_id });
+    }
+    let key_hash = vkey_hash(&zkeys.vkeys);
+    let vkey_hash = hex::encode(&key_hash);
+    let proof_str = proof_str(&zkeys.vkeys)?;
+    let bind_sender = zkeys.bind_sender;
+    let (ok, input_values) = check_proof(zkeys.vkeys, proof_str.clone())?;
+    if ok && bind_sender {
+        check_sender_signal(Some(prover), &input_values)?;
+    }
+
+    let mut response = Response::new();
     if ok {
+        // another proof of the same statement is as good as a copy, so claims are kept per statement
+        let statement = statement_hash(
+            &key_hash,
+            &input_values.iter().map(field_key).collect::<Vec<_>>(),
+        );
+        let claim = PROOF_CLAIMS.may_load(deps.storage, (&issuer, &circuit_id, &statement))?;
+        // the earliest commitment takes the credit of a statement from a later submission
+        let takeover = claim
+            .as_ref()
+            .is_some_and(|claim| claim.prover != *prover && committed_at < claim.committed_at);
+        let nullifier = match zkeys.nullifier_index {
+            Some(index) => Some(field_key(
+                input_values
+                    .get(index)
+                    .ok_or(ContractError::ErrorPublicSignal {})?,
+            )),
+            None => None,
+        };
+        if let Some(nullifier) = &nullifier {
+            // a spent nullifier only passes on to the earlier commitment of the statement that spent it
+            if NULLIFIERS.has(deps.storage, (&issuer, &circuit_id, nullifier)) && !takeover {
+                return Err(ContractError::NullifierAlreadySpent {});
+            }
+        }
+        let mut committed_at = committed_at;
+        if let Some(claim) = claim {
+            if claim.prover == *prover {
+                committed_at = committed_at.min(claim.committed_at);
+            } else if !takeover {
+                return Err(ContractError::ProofAlreadyClaimed {});
+            } else {
+                // the earliest commitment takes the credit from a copied submission
+                PROVERLIST.remove(deps.storage, (&issuer, &circuit_id, &claim.prover));
+                SOLVED.remove(deps.storage, (&claim.prover, &issuer, &circuit_id));
+                // the copied verification stays in their history, which is never rewritten
+            }
+        }
+        PROOF_CLAIMS.save(
+            deps.storage,
+            (&issuer, &circuit_id, &statement),
+            &ProofClaim {
+                prover: prover.clone(),
+                committed_at,
+            },
+        )?;
+        if let Some(nullifier) = &nullifier {
+            NULLIFIERS.save(deps.storage, (&issuer, &circuit_id, nullifier), &statement)?;
+        }
+
         let proof_info = ProofInfo {
-            proof: proof_str, 
+            proof: proof_str,
             is_valid: ok,
         };
         // save the storage
-        PROVERINFO.save(deps.storage, &info.sender, &proof_info)?;
-        PROVERLIST.save(deps.storage, (&issuer, &info.sender), &proof_info)?;
+        PROVERLIST.save(deps.storage, (&issuer, &circuit_id, prover), &proof_info)?;
+        SOLVED.save(deps.storage, (prover, &issuer, &circuit_id), &Empty {})?;
+        let sequence = PROVER_SEQUENCE
+            .may_load(deps.storage, prover)?
+            .unwrap_or_default();
+        PROVER_HISTORY.save(
+            deps.storage,
+            (prover, sequence),
+            &Verification {
+                issuer: issuer.clone(),
+                circuit_id: circuit_id.clone(),
+                vkey_hash: vkey_hash.clone(),
+                height: env.block.height,
+            },
+        )?;
+        PROVER_SEQUENCE.save(deps.storage, prover, &(sequence + 1))?;
+
+        response = response.add_event(
+            Event::new("proof_verified")
+                .add_attribute("issuer", issuer.as_str())
+                .add_attribute("circuit_id", &circuit_id)
+                .add_attribute("prover", prover.as_str())
+                .add_attribute("vkey_hash", vkey_hash)
+                .add_attribute("proof_system", PROOF_SYSTEM)
+                .add_attribute("fee", fee)
+                .add_attribute("outcome", "valid")
+                .add_attribute("sequence", sequence.to_string()),
+        );
 
+        // the earliest proof committed before the deadline wins the bounty,
+        // `ClaimBounty` pays it once no earlier commitment can be revealed
+        if let Some(mut bounty) = BOUNTIES.may_load(deps.storage, (&issuer, &circuit_id))? {
+            let earliest = bounty
+                .winner
+                .as_ref()
+                .is_none_or(|winner| committed_at < winner.committed_at);
+            if committed_at <= bounty.deadline && earliest {
+                bounty.winner = Some(ProofClaim {
+                    prover: prover.clone(),
+                    committed_at,
+                });
+                BOUNTIES.save(deps.storage, (&issuer, &circuit_id), &bounty)?;
+                response = response.add_event(
+                    Event::new("bounty_won")
+                        .add_attribute("issuer", issuer.as_str())
+                        .add_attribute("circuit_id", circuit_id)
+                        .add_attribute("prover", prover.as_str()),
+                );
+            }
+        }
     } else {
         return Err(ContractError::InvalidProof {});
     }
 
-    Ok(Response::default())
+    Ok(response)
+}
+
+/// a key binding the sender only accepts proofs whose last public input is the sender signal of
+/// the prover, the proof carries its public inputs so a copied proof would verify for anyone
+fn check_sender_signal(
+    prover: Option<&Addr>,
+    public_inputs: &[pairing_ce::bn256::Fr],
+) -> Result<(), ContractError> {
+    let signal = sender_public_signal(prover.ok_or(ContractError::NoProver {})?);
+    let signal =
+        pairing_ce::bn256::Fr::from_str(&signal).ok_or(ContractError::SenderSignalMismatch {})?;
+    if public_inputs.last() != Some(&signal) {
+        return Err(ContractError::SenderSignalMismatch {});
+    }
+    Ok(())
+}
+
+/// hold the reward of a circuit until a prover collects it or the issuer reclaims it
+fn escrow_bounty(
+    storage: &mut dyn Storage,
+    issuer: &Addr,
+    circuit_id: &str,
+    bounty: &Bounty,
+) -> StdResult<()> {
+    BOUNTIES.save(storage, (issuer, circuit_id), bounty)?;
+    BOUNTY_ESCROW.update(storage, &bounty.reward.denom, |escrowed| -> StdResult<_> {
+        Ok(escrowed
+            .unwrap_or_default()
+            .checked_add(bounty.reward.amount)?)
+    })?;
+    Ok(())
+}
+
+fn release_bounty(
+    storage: &mut dyn Storage,
+    issuer: &Addr,
+    circuit_id: &str,
+    bounty: &Bounty,
+) -> StdResult<()> {
+    BOUNTIES.remove(storage, (issuer, circuit_id));
+    BOUNTY_ESCROW.update(storage, &bounty.reward.denom, |escrowed| -> StdResult<_> {
+        Ok(escrowed
+            .unwrap_or_default()
+            .saturating_sub(bounty.reward.amount))
+    })?;
+    Ok(())
+}
+
+/// canonical bytes of a field element, signals that reduce to the same element are the same nullifier and statement
+fn field_key(nullifier: &pairing_ce::bn256::Fr) -> Vec<u8> {
+    let mut bytes = vec![];
+    nullifier
+        .into_repr()
+        .write_be(&mut bytes)
+        .expect("writing to a vec can't fail");
+    bytes
+}
+
+/// the canonical bytes of a hex input value, `None` when it isn't a field element
+pub fn public_signal_key(input_value: &str) -> Option<Vec<u8>> {
+    ff_ce::from_hex::<pairing_ce::bn256::Fr>(input_value)
+        .ok()
+        .map(|x| field_key(&x))
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
-        QueryMsg::Config {} => to_binary::<ConfigResponse>(&CONFIG.load(deps.storage)?.into()),
-        QueryMsg::IssuerZkeys { address } => to_binary(&query_issuer_zkeys(deps, address)?),
+        QueryMsg::Config {} => to_json_binary::<ConfigResponse>(&CONFIG.load(deps.storage)?.into()),
+        QueryMsg::IssuerZkeys {
+            address,
+            circuit_id,
+        } => to_json_binary(&query_issuer_zkeys(deps, address, circuit_id)?),
         QueryMsg::ProofResult {
             issuer_address,
+            circuit_id,
+            prover_address,
+        } => to_json_binary(&query_proof_result(
+            deps,
+            issuer_address,
+            circuit_id,
+            prover_address,
+        )?),
+        QueryMsg::IssuerZkeysBinary {
+            address,
+            circuit_id,
+        } => to_json_binary::<ZkeysBinaryResponse>(
+            &load_issuer_zkeys(deps, address, circuit_id)?.into(),
+        ),
+        QueryMsg::ProofResultBinary {
+            issuer_address,
+            circuit_id,
+            prover_address,
+        } => to_json_binary::<ProofBinaryResponse>(
+            &load_proof_info(deps, issuer_address, circuit_id, prover_address)?.into(),
+        ),
+        QueryMsg::SenderSignal { address } => to_json_binary(&query_sender_signal(deps, address)?),
+        QueryMsg::Commitment {
+            issuer_address,
+            circuit_id,
+            prover_address,
+        } => to_json_binary(&query_commitment(
+            deps,
+            issuer_address,
+            circuit_id,
+            prover_address,
+        )?),
+        QueryMsg::Nullifier {
+            issuer_address,
+            circuit_id,
+            nullifier,
+        } => to_json_binary(&query_nullifier(
+            deps,
+            issuer_address,
+            circuit_id,
+            nullifier,
+        )?),
+        QueryMsg::Bounty {
+            address,
+            circuit_id,
+        } => to_json_binary(&query_bounty(deps, address, circuit_id)?),
+        QueryMsg::FeeBalance {} => to_json_binary(&query_fee_balance(deps, env)?),
+        QueryMsg::Issuers { start_after, limit } => {
+            to_json_binary(&query_issuers(deps, start_after, limit)?)
+        }
+        QueryMsg::Circuit {
+            issuer_address,
+            circuit_id,
+        } => to_json_binary(&query_circuit(deps, issuer_address, circuit_id)?),
+        QueryMsg::Circuits {
+            issuer_address,
+            start_after,
+            limit,
+        } => to_json_binary(&query_circuits(deps, issuer_address, start_after, limit)?),
+        QueryMsg::Provers {
+            issuer_address,
+            circuit_id,
+            start_after,
+            limit,
+        } => to_json_binary(&query_provers(
+            deps,
+            issuer_address,
+            circuit_id,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::SolvedCircuits {
+            prover_address,
+            start_after,
+            limit,
+        } => to_json_binary(&query_solved_circuits(
+            deps,
+            prover_address,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::ProverHistory {
+            prover_address,
+            start_after,
+            limit,
+        } => to_json_binary(&query_prover_history(
+            deps,
             prover_address,
-        } => to_binary(&query_proof_result(deps, issuer_address, prover_address)?),
+            start_after,
+            limit,
+        )?),
+        QueryMsg::VerifyProof {
+            key,
+            num_inputs,
+            n,
+            input_values,
+            wire_commitments,
+            grand_product_commitment,
+            quotient_poly_commitments,
+            wire_values_at_z,
+            wire_values_at_z_omega,
+            grand_product_at_z_omega,
+            quotient_polynomial_at_z,
+            linearization_polynomial_at_z,
+            permutation_polynomials_at_z,
+            opening_at_z_proof,
+            opening_at_z_omega_proof,
+        } => {
+            let proof_str = decode_proof(
+                num_inputs,
+                n,
+                input_values.clone(),
+                wire_commitments,
+                grand_product_commitment,
+                quotient_poly_commitments,
+                wire_values_at_z,
+                wire_values_at_z_omega,
+                grand_product_at_z_omega,
+                quotient_polynomial_at_z,
+                linearization_polynomial_at_z,
+                permutation_polynomials_at_z,
+                opening_at_z_proof,
+                opening_at_z_omega_proof,
+            );
+            to_json_binary(&query_verify_proof(
+                deps,
+                key,
+                input_values,
+                move |vkeys| bellman_proof(vkeys, proof_str?),
+            )?)
+        }
+        QueryMsg::VerifySnarkjsProof {
+            key,
+            proof,
+            public_signals,
+        } => {
+            let input_values = public_signals.clone();
+            to_json_binary(&query_verify_proof(
+                deps,
+                key,
+                input_values,
+                move |vkeys| proof.to_proof_str(vkeys, public_signals),
+            )?)
+        }
     }
 }
 
-fn query_issuer_zkeys(deps: Deps, address: String) -> StdResult<ZkeysResponse> {
+fn load_issuer_zkeys(
+    deps: Deps,
+    address: String,
+    circuit_id: Option<String>,
+) -> StdResult<ZkeysStr> {
     let issuer_addr = deps.api.addr_validate(&address)?;
+    ZKEYS.load(
+        deps.storage,
+        (&issuer_addr, &circuit_or_default(circuit_id)),
+    )
+}
 
-    let vkeys = ZKEYS.load(deps.storage, &issuer_addr)?;
+fn query_issuer_zkeys(
+    deps: Deps,
+    address: String,
+    circuit_id: Option<String>,
+) -> StdResult<ZkeysResponse> {
+    let zkeys = load_issuer_zkeys(deps, address, circuit_id)?;
+    let vkeys = zkeys.vkeys;
     Ok(ZkeysResponse {
         n: vkeys.n,
         num_inputs: vkeys.num_inputs,
-        selector_commitments: vkeys.selector_commitments.into_iter().map(|x| hex::encode(x)).collect(),
-        next_step_selector_commitments: vkeys.next_step_selector_commitments.into_iter().map(|x| hex::encode(x)).collect(),
-        permutation_commitments: vkeys.permutation_commitments.into_iter().map(|x| hex::encode(x)).collect(),
+        selector_commitments: vkeys
+            .selector_commitments
+            .into_iter()
+            .map(hex::encode)
+            .collect(),
+        next_step_selector_commitments: vkeys
+            .next_step_selector_commitments
+            .into_iter()
+            .map(hex::encode)
+            .collect(),
+        permutation_commitments: vkeys
+            .permutation_commitments
+            .into_iter()
+            .map(hex::encode)
+            .collect(),
         non_residues: vkeys.non_residues,
-        g2_elements: vkeys.g2_elements.into_iter().map(|x| hex::encode(x)).collect()
+        g2_elements: vkeys.g2_elements.into_iter().map(hex::encode).collect(),
+        params: vkeys.params,
+        bind_sender: zkeys.bind_sender,
+        nullifier_index: zkeys.nullifier_index,
     })
 }
 
-fn query_proof_result(
+fn load_proof_info(
     deps: Deps,
     issuer_address: String,
+    circuit_id: Option<String>,
     prover_address: String,
-) -> StdResult<ProofResponse> {
+) -> StdResult<ProofInfo> {
     let issuer_addr = deps.api.addr_validate(&issuer_address)?;
     let prover_addr = deps.api.addr_validate(&prover_address)?;
+    PROVERLIST.load(
+        deps.storage,
+        (&issuer_addr, &circuit_or_default(circuit_id), &prover_addr),
+    )
+}
 
-    let proof_info = PROVERLIST.load(deps.storage, (&issuer_addr, &prover_addr))?;
+fn query_proof_result(
+    deps: Deps,
+    issuer_address: String,
+    circuit_id: Option<String>,
+    prover_address: String,
+) -> StdResult<ProofResponse> {
+    let proof_info = load_proof_info(deps, issuer_address, circuit_id, prover_address)?;
     Ok(ProofResponse {
-         num_inputs: proof_info.proof.num_inputs,
-         n: proof_info.proof.n,
-         input_values: proof_info.proof.input_values,
-         wire_commitments: proof_info.proof.wire_commitments.into_iter().map(|x| hex::encode(x)).collect(),
-         grand_product_commitment: hex::encode(proof_info.proof.grand_product_commitment),
-         quotient_poly_commitments: proof_info.proof.quotient_poly_commitments.into_iter().map(|x| hex::encode(x)).collect(),
-         wire_values_at_z: proof_info.proof.wire_values_at_z,
-         wire_values_at_z_omega: proof_info.proof.wire_values_at_z_omega,
-         grand_product_at_z_omega: proof_info.proof.grand_product_at_z_omega,
-         quotient_polynomial_at_z: proof_info.proof.quotient_polynomial_at_z,
-         linearization_polynomial_at_z: proof_info.proof.linearization_polynomial_at_z,
-         permutation_polynomials_at_z: proof_info.proof.permutation_polynomials_at_z,
-         opening_at_z_proof: hex::encode(proof_info.proof.opening_at_z_proof),
+        num_inputs: proof_info.proof.num_inputs,
+        n: proof_info.proof.n,
+        input_values: proof_info.proof.input_values,
+        wire_commitments: proof_info
+            .proof
+            .wire_commitments
+            .into_iter()
+            .map(hex::encode)
+            .collect(),
+        grand_product_commitment: hex::encode(proof_info.proof.grand_product_commitment),
+        quotient_poly_commitments: proof_info
+            .proof
+            .quotient_poly_commitments
+            .into_iter()
+            .map(hex::encode)
+            .collect(),
+        wire_values_at_z: proof_info.proof.wire_values_at_z,
+        wire_values_at_z_omega: proof_info.proof.wire_values_at_z_omega,
+        grand_product_at_z_omega: proof_info.proof.grand_product_at_z_omega,
+        quotient_polynomial_at_z: proof_info.proof.quotient_polynomial_at_z,
+        linearization_polynomial_at_z: proof_info.proof.linearization_polynomial_at_z,
+        permutation_polynomials_at_z: proof_info.proof.permutation_polynomials_at_z,
+        opening_at_z_proof: hex::encode(proof_info.proof.opening_at_z_proof),
         opening_at_z_omega_proof: hex::encode(proof_info.proof.opening_at_z_omega_proof),
         is_valid: proof_info.is_valid,
     })
 }
+
+fn query_sender_signal(deps: Deps, address: String) -> StdResult<SenderSignalResponse> {
+    let addr = deps.api.addr_validate(&address)?;
+
+    Ok(SenderSignalResponse {
+        public_signal: sender_public_signal(&addr),
+    })
+}
+
+fn query_commitment(
+    deps: Deps,
+    issuer_address: String,
+    circuit_id: Option<String>,
+    prover_address: String,
+) -> StdResult<CommitmentResponse> {
+    let issuer_addr = deps.api.addr_validate(&issuer_address)?;
+    let prover_addr = deps.api.addr_validate(&prover_address)?;
+
+    let config = CONFIG.load(deps.storage)?;
+    let commitment = COMMITMENTS.load(
+        deps.storage,
+        (&issuer_addr, &circuit_or_default(circuit_id), &prover_addr),
+    )?;
+    Ok(CommitmentResponse {
+        commitment: hex::encode(commitment.hash),
+        height: commitment.height,
+        expires_at: commitment.height.saturating_add(config.commitment_expiry),
+    })
+}
+
+fn query_nullifier(
+    deps: Deps,
+    issuer_address: String,
+    circuit_id: Option<String>,
+    nullifier: String,
+) -> StdResult<NullifierResponse> {
+    let issuer_addr = deps.api.addr_validate(&issuer_address)?;
+
+    let circuit_id = circuit_or_default(circuit_id);
+    // a nullifier that can't be read is reported as the public signal it stands for
+    let index = ZKEYS
+        .may_load(deps.storage, (&issuer_addr, &circuit_id))?
+        .and_then(|zkeys| zkeys.nullifier_index)
+        .unwrap_or_default();
+    // same hex encoding as the proof's input values
+    let nullifier = public_signal_key(&nullifier).ok_or_else(|| {
+        StdError::generic_err(ContractError::ParsePulbicSignalError { index }.to_string())
+    })?;
+    Ok(NullifierResponse {
+        spent: NULLIFIERS.has(deps.storage, (&issuer_addr, &circuit_id, &nullifier)),
+    })
+}
+
+fn query_bounty(
+    deps: Deps,
+    address: String,
+    circuit_id: Option<String>,
+) -> StdResult<BountyResponse> {
+    let issuer_addr = deps.api.addr_validate(&address)?;
+
+    let config = CONFIG.load(deps.storage)?;
+    let bounty = BOUNTIES.load(
+        deps.storage,
+        (&issuer_addr, &circuit_or_default(circuit_id)),
+    )?;
+    Ok(BountyResponse {
+        reward: bounty.reward,
+        deadline: bounty.deadline,
+        claimable_at: bounty
+            .winner
+            .as_ref()
+            .map(|winner| winner.committed_at.saturating_add(config.commitment_expiry)),
+        winner: bounty.winner.map(|winner| winner.prover),
+    })
+}
+
+fn query_fee_balance(deps: Deps, env: Env) -> StdResult<FeeBalanceResponse> {
+    Ok(FeeBalanceResponse {
+        balance: fee_balance(deps, &env)?,
+    })
+}
+
+fn query_issuers(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<IssuersResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let mut start_after = start_after
+        .map(|address| deps.api.addr_validate(&address))
+        .transpose()?;
+
+    // an issuer is listed once, whatever the number of its circuits, so every
+    // issuer is a seek past the circuits of the previous one, not a scan over them
+    let mut issuers: Vec<Addr> = Vec::with_capacity(limit);
+    while issuers.len() < limit {
+        let start = start_after.as_ref().map(PrefixBound::exclusive);
+        let ((issuer, _), _) = match ZKEYS
+            .prefix_range(deps.storage, start, None, Order::Ascending)
+            .next()
+        {
+            Some(item) => item?,
+            None => break,
+        };
+        start_after = Some(issuer.clone());
+        issuers.push(issuer);
+    }
+    Ok(IssuersResponse { issuers })
+}
+
+fn query_circuit(
+    deps: Deps,
+    issuer_address: String,
+    circuit_id: Option<String>,
+) -> StdResult<CircuitResponse> {
+    let issuer_addr = deps.api.addr_validate(&issuer_address)?;
+    let circuit_id = circuit_or_default(circuit_id);
+
+    let zkeys = ZKEYS.load(deps.storage, (&issuer_addr, &circuit_id))?;
+    Ok(CircuitResponse {
+        issuer: issuer_addr,
+        circuit_id,
+        vkey_hash: hex::encode(vkey_hash(&zkeys.vkeys)),
+        proof_system: PROOF_SYSTEM.to_string(),
+        metadata: zkeys.metadata,
+        status: zkeys.status,
+    })
+}
+
+fn query_circuits(
+    deps: Deps,
+    issuer_address: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<CircuitsResponse> {
+    let issuer_addr = deps.api.addr_validate(&issuer_address)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+
+    let circuit_ids = ZKEYS
+        .prefix(&issuer_addr)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(CircuitsResponse { circuit_ids })
+}
+
+fn query_provers(
+    deps: Deps,
+    issuer_address: String,
+    circuit_id: Option<String>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ProversResponse> {
+    let issuer_addr = deps.api.addr_validate(&issuer_address)?;
+    let circuit_id = circuit_or_default(circuit_id);
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after
+        .map(|address| deps.api.addr_validate(&address))
+        .transpose()?;
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    let provers = PROVERLIST
+        .prefix((&issuer_addr, &circuit_id))
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(ProversResponse { provers })
+}
+
+fn query_solved_circuits(
+    deps: Deps,
+    prover_address: String,
+    start_after: Option<(String, String)>,
+    limit: Option<u32>,
+) -> StdResult<SolvedCircuitsResponse> {
+    let prover_addr = deps.api.addr_validate(&prover_address)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after
+        .map(|(address, circuit_id)| {
+            deps.api
+                .addr_validate(&address)
+                .map(|issuer| (issuer, circuit_id))
+        })
+        .transpose()?;
+    let start = start_after
+        .as_ref()
+        .map(|(issuer, circuit_id)| Bound::exclusive((issuer, circuit_id.as_str())));
+
+    let circuits = SOLVED
+        .sub_prefix(&prover_addr)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(issuer, circuit_id)| CircuitRef { issuer, circuit_id }))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(SolvedCircuitsResponse { circuits })
+}
+
+fn query_prover_history(
+    deps: Deps,
+    prover_address: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<ProverHistoryResponse> {
+    let prover_addr = deps.api.addr_validate(&prover_address)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let verifications = PROVER_HISTORY
+        .prefix(&prover_addr)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            item.map(|(sequence, verification)| VerificationResponse {
+                sequence,
+                issuer: verification.issuer,
+                circuit_id: verification.circuit_id,
+                vkey_hash: verification.vkey_hash,
+                height: verification.height,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(ProverHistoryResponse { verifications })
+}
+
+/// the checks of `verify_and_record` without recording anything
+fn query_verify_proof(
+    deps: Deps,
+    key: VerifyingKeySource,
+    input_values: Vec<String>,
+    proof_str: impl FnOnce(&VkeyStr) -> Result<ProofStr, ContractError>,
+) -> StdResult<VerifyProofResponse> {
+    let (circuit, prover, zkeys) = match key {
+        VerifyingKeySource::Registered {
+            issuer,
+            circuit_id,
+            prover,
+        } => {
+            let issuer = deps.api.addr_validate(&issuer)?;
+            let circuit_id = circuit_or_default(circuit_id);
+            let prover = prover
+                .map(|address| deps.api.addr_validate(&address))
+                .transpose()?;
+            let zkeys = ZKEYS
+                .may_load(deps.storage, (&issuer, &circuit_id))?
+                .ok_or_else(|| {
+                    StdError::generic_err(ContractError::NoVerificationKey {}.to_string())
+                })?;
+            (Some((issuer, circuit_id)), prover, zkeys)
+        }
+        VerifyingKeySource::Inline {
+            n,
+            num_inputs,
+            selector_commitments,
+            next_step_selector_commitments,
+            permutation_commitments,
+            non_residues,
+            g2_elements,
+            params,
+        } => {
+            let vkeys = decode_vkey(
+                n,
+                num_inputs,
+                selector_commitments,
+                next_step_selector_commitments,
+                permutation_commitments,
+                non_residues,
+                g2_elements,
+                params.unwrap_or_default(),
+            )
+            .map_err(|err| StdError::generic_err(err.to_string()))?;
+            let zkeys = ZkeysStr {
+                vkeys,
+                bind_sender: false,
+                nullifier_index: None,
+                metadata: CircuitMetadata::default(),
+                status: CircuitStatus::Active,
+            };
+            (None, None, zkeys)
+        }
+        VerifyingKeySource::Snarkjs { vkey } => {
+            let vkeys = vkey
+                .to_vkey_str()
+                .map_err(|err| StdError::generic_err(err.to_string()))?;
+            let zkeys = ZkeysStr {
+                vkeys,
+                bind_sender: false,
+                nullifier_index: None,
+                metadata: CircuitMetadata::default(),
+                status: CircuitStatus::Active,
+            };
+            (None, None, zkeys)
+        }
+    };
+    let vkey_hash = hex::encode(vkey_hash(&zkeys.vkeys));
+    let rejected = |err: ContractError| VerifyProofResponse {
+        is_valid: false,
+        vkey_hash: vkey_hash.clone(),
+        input_values: input_values.clone(),
+        nullifier_spent: None,
+        error: Some(err.to_string()),
+    };
+
+    if let (Some((_, circuit_id)), CircuitStatus::Retired) = (&circuit, &zkeys.status) {
+        return Ok(rejected(ContractError::CircuitRetired {
+            circuit_id: circuit_id.clone(),
+        }));
+    }
+
+    let bind_sender = zkeys.bind_sender;
+    let checked = proof_str(&zkeys.vkeys).and_then(|proof_str| check_proof(zkeys.vkeys, proof_str));
+    let public_inputs = match checked {
+        Ok((true, public_inputs)) if bind_sender => {
+            match check_sender_signal(prover.as_ref(), &public_inputs) {
+                Ok(()) => public_inputs,
+                Err(err) => return Ok(rejected(err)),
+            }
+        }
+        Ok((true, public_inputs)) => public_inputs,
+        Ok((false, _)) => return Ok(rejected(ContractError::InvalidProof {})),
+        Err(err) => return Ok(rejected(err)),
+    };
+
+    // a valid proof is still refused by `Proof` once its nullifier is spent
+    let nullifier_spent = match (circuit, zkeys.nullifier_index) {
+        (Some((issuer, circuit_id)), Some(index)) => match public_inputs.get(index) {
+            Some(nullifier) => {
+                Some(NULLIFIERS.has(deps.storage, (&issuer, &circuit_id, &field_key(nullifier))))
+            }
+            None => return Ok(rejected(ContractError::ErrorPublicSignal {})),
+        },
+        _ => None,
+    };
+    Ok(VerifyProofResponse {
+        is_valid: true,
+        vkey_hash,
+        input_values,
+        nullifier_spent,
+        error: None,
+    })
+}
+
+/// what the contract holds minus the rewards of open bounties
+fn fee_balance(deps: Deps, env: &Env) -> StdResult<Vec<Coin>> {
+    let mut balance = deps
+        .querier
+        .query_all_balances(env.contract.address.as_str())?;
+    for coin in balance.iter_mut() {
+        let escrowed = BOUNTY_ESCROW
+            .may_load(deps.storage, &coin.denom)?
+            .unwrap_or_default();
+        coin.amount = coin.amount.saturating_sub(escrowed);
+    }
+    balance.retain(|coin| !coin.amount.is_zero());
+    Ok(balance)
+}
@@ -1,9 +1,16 @@
+// message handlers take the fields of their message as arguments
+#![allow(clippy::too_many_arguments)]
+
 pub mod coin_helpers;
+pub mod commitment;
 pub mod contract;
 mod error;
+pub mod migrations;
 pub mod msg;
-pub mod state;
 pub mod parser;
+pub mod signal_helpers;
+pub mod snarkjs;
+pub mod state;
 
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,108 @@
+use std::collections::BTreeMap;
+
+use crate::commitment::vkey_hash;
+use crate::contract::DEFAULT_COMMITMENT_EXPIRY;
+use crate::msg::DEFAULT_CIRCUIT;
+use crate::state::{
+    CircuitMetadata, CircuitStatus, Config, FeeMode, UnrelatedDenoms, Verification, ZkeysStr,
+};
+use crate::state::{CONFIG, PROVERLIST, PROVER_HISTORY, PROVER_SEQUENCE, SOLVED, ZKEYS};
+use cosmwasm_std::{Addr, Empty, Order, StdResult, Storage};
+
+/// storage layouts of 0.1.0, the release before contract versions were recorded
+pub mod v0_1 {
+    use crate::state::{ProofInfo, VkeyStr};
+    use cosmwasm_schema::cw_serde;
+    use cosmwasm_std::{Addr, Coin};
+    use cw_storage_plus::{Item, Map};
+
+    #[cw_serde]
+    pub struct Config {
+        pub zkeys_price: Option<Coin>,
+        pub proof_price: Option<Coin>,
+    }
+
+    pub const CONFIG: Item<Config> = Item::new("config");
+    // the bare verification key, without the settings kept next to it now
+    pub const ZKEYS: Map<&Addr, VkeyStr> = Map::new("vkeys");
+    // the latest proof of each prover, replaced by their history
+    pub const PROVERINFO: Map<&Addr, ProofInfo> = Map::new("prover_info");
+    pub const PROVERLIST: Map<(&Addr, &Addr), ProofInfo> = Map::new("prover_list");
+}
+
+/// rewrite everything 0.1.0 stored into the current layouts, 0.1.0 had no owner so it is given here,
+/// its single key per issuer becomes the issuer's `DEFAULT_CIRCUIT`, `height` stands in for the unrecorded block of each prover's latest proof
+pub fn migrate_from_v0_1(storage: &mut dyn Storage, owner: Addr, height: u64) -> StdResult<()> {
+    let config = v0_1::CONFIG.load(storage)?;
+    CONFIG.save(
+        storage,
+        &Config {
+            owner,
+            zkeys_price: config.zkeys_price,
+            proof_price: config.proof_price,
+            zkeys_cw20_price: None,
+            proof_cw20_price: None,
+            fee_mode: FeeMode::default(),
+            unrelated_denoms: UnrelatedDenoms::default(),
+            commitment_expiry: DEFAULT_COMMITMENT_EXPIRY,
+        },
+    )?;
+
+    // the old entries are read in full and removed first, the new layouts live in other namespaces
+    let zkeys = v0_1::ZKEYS
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    let mut vkey_hashes = BTreeMap::new();
+    for (issuer, vkeys) in zkeys {
+        v0_1::ZKEYS.remove(storage, &issuer);
+        vkey_hashes.insert(issuer.clone(), hex::encode(vkey_hash(&vkeys)));
+        let zkeys = ZkeysStr {
+            vkeys,
+            bind_sender: false,
+            nullifier_index: None,
+            metadata: CircuitMetadata::default(),
+            status: CircuitStatus::Active,
+        };
+        ZKEYS.save(storage, (&issuer, DEFAULT_CIRCUIT), &zkeys)?;
+    }
+
+    // `ProofInfo` kept its layout, it moves under the default circuit
+    let proofs = v0_1::PROVERLIST
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for ((issuer, prover), proof_info) in proofs.iter() {
+        v0_1::PROVERLIST.remove(storage, (issuer, prover));
+        PROVERLIST.save(storage, (issuer, DEFAULT_CIRCUIT, prover), proof_info)?;
+        SOLVED.save(storage, (prover, issuer, DEFAULT_CIRCUIT), &Empty {})?;
+    }
+
+    // the latest proof of a prover doesn't name its issuer, it is the one listing the same proof,
+    // it starts the prover's history as the only verification 0.1.0 kept
+    let latest = v0_1::PROVERINFO
+        .range(storage, None, None, Order::Ascending)
+        .collect::<StdResult<Vec<_>>>()?;
+    for (prover, proof_info) in latest {
+        v0_1::PROVERINFO.remove(storage, &prover);
+        let issuer = proofs
+            .iter()
+            .find(|((_, listed_prover), listed)| {
+                *listed_prover == prover && listed.proof == proof_info.proof
+            })
+            .map(|((issuer, _), _)| issuer);
+        if let Some(issuer) = issuer {
+            PROVER_HISTORY.save(
+                storage,
+                (&prover, 0),
+                &Verification {
+                    issuer: issuer.clone(),
+                    circuit_id: DEFAULT_CIRCUIT.to_string(),
+                    vkey_hash: vkey_hashes.get(issuer).cloned().unwrap_or_default(),
+                    height,
+                },
+            )?;
+            PROVER_SEQUENCE.save(storage, &prover, &1)?;
+        }
+    }
+
+    Ok(())
+}
@@ -1,16 +1,61 @@
-use crate::state::Config;
+use crate::snarkjs::{SnarkjsProof, SnarkjsVerifyingKey};
+use crate::state::{
+    CircuitMetadata, CircuitStatus, Config, FeeMode, PlonkParams, ProofInfo, UnrelatedDenoms,
+    ZkeysStr,
+};
 use cosmwasm_schema::{cw_serde, QueryResponses};
-use cosmwasm_std::Coin;
+use cosmwasm_std::{Addr, Binary, Coin, Uint128};
+use cw20::{Cw20Coin, Cw20CoinVerified, Cw20ReceiveMsg};
 
 #[cw_serde]
 pub struct InstantiateMsg {
     pub set_zkeys_price: Option<Coin>,
     pub publish_proof_price: Option<Coin>,
+    /// prices in a cw20 token, paid by sending the message to this contract with cw20 `Send`
+    pub set_zkeys_cw20_price: Option<Cw20Coin>,
+    pub publish_proof_cw20_price: Option<Cw20Coin>,
+    /// blocks a `CommitProof` stays revealable, defaults to 100
+    pub commitment_expiry: Option<u64>,
+    pub fee_mode: Option<FeeMode>,
+    pub unrelated_denoms: Option<UnrelatedDenoms>,
 }
 
+#[cw_serde]
+pub struct MigrateMsg {
+    /// owner of a contract migrated from 0.1.0, which didn't record one
+    pub owner: Option<String>,
+}
+
+/// the `proof_system` attribute of the events below
+pub const PROOF_SYSTEM: &str = "plonk_bn254";
+
+/// the circuit of messages that don't name one, keys migrated from 0.1.0 are registered under it
+pub const DEFAULT_CIRCUIT: &str = "default";
+
+/// Every action emits a `wasm-<type>` event:
+///
+/// - `zkeys_registered`: `issuer`, `circuit_id`, `vkey_hash`, `proof_system`, `fee`
+/// - `bounty_opened`: `issuer`, `circuit_id`, `reward`, `deadline`
+/// - `circuit_status_changed`: `issuer`, `circuit_id`, `status`
+/// - `proof_committed`: `issuer`, `circuit_id`, `prover`
+/// - `proof_verified`: `issuer`, `circuit_id`, `prover`, `vkey_hash`, `proof_system`, `fee`, `outcome`, `sequence`
+/// - `bounty_won`: `issuer`, `circuit_id`, `prover`
+/// - `bounty_paid`: `issuer`, `circuit_id`, `prover`, `reward`
+/// - `bounty_reclaimed`: `issuer`, `circuit_id`, `reward`
+/// - `fees_withdrawn`: `recipient`, `amount`
+///
+/// `vkey_hash` is the hex sha256 of the verification key. `fee` is what the contract kept,
+/// comma separated `<amount><denom>` or `<amount><cw20 address>`. `outcome` is always `valid`,
+/// an invalid proof fails the transaction and emits nothing. `sequence` numbers the
+/// verification in the prover's `ProverHistory`.
 #[cw_serde]
 pub enum ExecuteMsg {
     Zkeys {
+        /// names the key among the issuer's circuits, defaults to `DEFAULT_CIRCUIT`,
+        /// a registered circuit can't be replaced
+        circuit_id: Option<String>,
+        /// what the circuit proves and where it comes from, shown by the `Circuit` query
+        metadata: Option<CircuitMetadata>,
         n: usize,
         num_inputs: usize,
         selector_commitments: Vec<String>,
@@ -18,9 +63,87 @@ pub enum ExecuteMsg {
         permutation_commitments: Vec<String>,
         non_residues: Vec<String>,
         g2_elements: Vec<String>,
+        /// defaults to `width4_with_next_step`, the shape the key and its proofs are read in
+        params: Option<PlonkParams>,
+        /// require the prover's sender signal as the last public input, defaults to false
+        bind_sender: Option<bool>,
+        /// index of the public input used as a nullifier, a nullifier is only accepted once
+        nullifier_index: Option<usize>,
+        /// reward escrowed for the first valid proof, sent on top of the registration fee
+        bounty: Option<Coin>,
+        /// block height after which an unclaimed bounty goes back to the issuer
+        bounty_deadline: Option<u64>,
     },
     Proof {
         difficuty_issuer: String,
+        /// defaults to `DEFAULT_CIRCUIT`
+        circuit_id: Option<String>,
+        num_inputs: usize,
+        n: usize,
+        input_values: Vec<String>,
+        wire_commitments: Vec<String>,
+        grand_product_commitment: String,
+        quotient_poly_commitments: Vec<String>,
+        wire_values_at_z: Vec<String>,
+        wire_values_at_z_omega: Vec<String>,
+        grand_product_at_z_omega: String,
+        quotient_polynomial_at_z: String,
+        linearization_polynomial_at_z: String,
+        permutation_polynomials_at_z: Vec<String>,
+        opening_at_z_proof: String,
+        opening_at_z_omega_proof: String,
+    },
+    /// `Zkeys` with base64 points in place of hex, `non_residues` stay as in `Zkeys`
+    ZkeysBinary {
+        circuit_id: Option<String>,
+        metadata: Option<CircuitMetadata>,
+        n: usize,
+        num_inputs: usize,
+        selector_commitments: Vec<Binary>,
+        next_step_selector_commitments: Vec<Binary>,
+        permutation_commitments: Vec<Binary>,
+        non_residues: Vec<String>,
+        g2_elements: Vec<Binary>,
+        params: Option<PlonkParams>,
+        bind_sender: Option<bool>,
+        nullifier_index: Option<usize>,
+        bounty: Option<Coin>,
+        bounty_deadline: Option<u64>,
+    },
+    /// `Proof` with base64 points in place of hex, the evaluations stay as in `Proof`
+    ProofBinary {
+        difficuty_issuer: String,
+        /// defaults to `DEFAULT_CIRCUIT`
+        circuit_id: Option<String>,
+        num_inputs: usize,
+        n: usize,
+        input_values: Vec<String>,
+        wire_commitments: Vec<Binary>,
+        grand_product_commitment: Binary,
+        quotient_poly_commitments: Vec<Binary>,
+        wire_values_at_z: Vec<String>,
+        wire_values_at_z_omega: Vec<String>,
+        grand_product_at_z_omega: String,
+        quotient_polynomial_at_z: String,
+        linearization_polynomial_at_z: String,
+        permutation_polynomials_at_z: Vec<String>,
+        opening_at_z_proof: Binary,
+        opening_at_z_omega_proof: Binary,
+    },
+    /// first phase of a front-running safe submission, `commitment` is hex of
+    /// sha256(wire_commitments || grand_product_commitment || quotient_poly_commitments
+    /// || opening_at_z_proof || opening_at_z_omega_proof || sender || salt)
+    CommitProof {
+        difficuty_issuer: String,
+        /// defaults to `DEFAULT_CIRCUIT`
+        circuit_id: Option<String>,
+        commitment: String,
+    },
+    /// second phase, at least one block after `CommitProof` and before it expires
+    RevealProof {
+        difficuty_issuer: String,
+        /// defaults to `DEFAULT_CIRCUIT`
+        circuit_id: Option<String>,
         num_inputs: usize,
         n: usize,
         input_values: Vec<String>,
@@ -35,7 +158,81 @@ pub enum ExecuteMsg {
         permutation_polynomials_at_z: Vec<String>,
         opening_at_z_proof: String,
         opening_at_z_omega_proof: String,
+        salt: String,
+    },
+    /// `Zkeys` for a circuit proven with `snarkjs plonk prove`, the key is stored
+    /// with `PlonkParams::Snarkjs`
+    SnarkjsZkeys {
+        /// defaults to `DEFAULT_CIRCUIT`, a registered circuit can't be replaced
+        circuit_id: Option<String>,
+        metadata: Option<CircuitMetadata>,
+        vkey: SnarkjsVerifyingKey,
+        bind_sender: Option<bool>,
+        nullifier_index: Option<usize>,
+        bounty: Option<Coin>,
+        bounty_deadline: Option<u64>,
+    },
+    /// `Proof` for a circuit registered with `SnarkjsZkeys`, accepted exactly when
+    /// `snarkjs plonk verify` accepts it
+    SnarkjsProof {
+        difficuty_issuer: String,
+        /// defaults to `DEFAULT_CIRCUIT`
+        circuit_id: Option<String>,
+        proof: SnarkjsProof,
+        /// the decimal signals of `public.json`
+        public_signals: Vec<String>,
+    },
+    /// move one of the sender's circuits to `status`, a retired circuit stays retired
+    SetCircuitStatus {
+        circuit_id: Option<String>,
+        status: CircuitStatus,
+    },
+    /// take back a bounty no proof has won after its deadline
+    ReclaimBounty { circuit_id: Option<String> },
+    /// pay a circuit's bounty to its winner, once every commitment made before
+    /// the winning one has expired
+    ClaimBounty {
+        difficuty_issuer: String,
+        circuit_id: Option<String>,
+    },
+    /// send collected fees to `recipient`, only the owner can withdraw
+    Withdraw {
+        amount: Vec<Coin>,
+        recipient: String,
+    },
+    /// send collected cw20 fees of `token` to `recipient`, only the owner can withdraw
+    WithdrawCw20 {
+        token: String,
+        amount: Uint128,
+        recipient: String,
+    },
+    /// a cw20 `Send` to this contract, `msg` is a `Zkeys`, `Proof`, `ZkeysBinary`, `ProofBinary`,
+    /// `RevealProof`, `SnarkjsZkeys` or `SnarkjsProof` message paid with the sent tokens
+    Receive(Cw20ReceiveMsg),
+}
+
+/// the verification key a `VerifyProof` query checks against
+#[cw_serde]
+pub enum VerifyingKeySource {
+    /// the key registered by `issuer` for `circuit_id`, `prover` is needed when it binds the sender
+    Registered {
+        issuer: String,
+        circuit_id: Option<String>,
+        prover: Option<String>,
     },
+    /// a key given in the query, in the encoding of `Zkeys`
+    Inline {
+        n: usize,
+        num_inputs: usize,
+        selector_commitments: Vec<String>,
+        next_step_selector_commitments: Vec<String>,
+        permutation_commitments: Vec<String>,
+        non_residues: Vec<String>,
+        g2_elements: Vec<String>,
+        params: Option<PlonkParams>,
+    },
+    /// a snarkjs key given in the query
+    Snarkjs { vkey: SnarkjsVerifyingKey },
 }
 
 #[cw_serde]
@@ -44,27 +241,148 @@ pub enum QueryMsg {
     #[returns(ConfigResponse)]
     Config {},
     #[returns(ZkeysResponse)]
-    IssuerZkeys { address: String},
+    IssuerZkeys {
+        address: String,
+        circuit_id: Option<String>,
+    },
     #[returns(ProofResponse)]
-    ProofResult {issuer_address: String, prover_address: String}
+    ProofResult {
+        issuer_address: String,
+        circuit_id: Option<String>,
+        prover_address: String,
+    },
+    /// `IssuerZkeys` with base64 points
+    #[returns(ZkeysBinaryResponse)]
+    IssuerZkeysBinary {
+        address: String,
+        circuit_id: Option<String>,
+    },
+    /// `ProofResult` with base64 points
+    #[returns(ProofBinaryResponse)]
+    ProofResultBinary {
+        issuer_address: String,
+        circuit_id: Option<String>,
+        prover_address: String,
+    },
+    #[returns(SenderSignalResponse)]
+    SenderSignal { address: String },
+    #[returns(CommitmentResponse)]
+    Commitment {
+        issuer_address: String,
+        circuit_id: Option<String>,
+        prover_address: String,
+    },
+    #[returns(NullifierResponse)]
+    Nullifier {
+        issuer_address: String,
+        circuit_id: Option<String>,
+        nullifier: String,
+    },
+    #[returns(BountyResponse)]
+    Bounty {
+        address: String,
+        circuit_id: Option<String>,
+    },
+    #[returns(FeeBalanceResponse)]
+    FeeBalance {},
+    /// issuers with a registered circuit, ordered by address length and then address
+    #[returns(IssuersResponse)]
+    Issuers {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// metadata and status of a circuit
+    #[returns(CircuitResponse)]
+    Circuit {
+        issuer_address: String,
+        circuit_id: Option<String>,
+    },
+    /// circuits registered by `issuer_address`, ordered by id
+    #[returns(CircuitsResponse)]
+    Circuits {
+        issuer_address: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// provers credited with a proof for a circuit of `issuer_address`
+    #[returns(ProversResponse)]
+    Provers {
+        issuer_address: String,
+        circuit_id: Option<String>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// circuits `prover_address` is credited with a proof for, ordered like `Issuers` and then by id,
+    /// `start_after` is an (issuer, circuit id) pair
+    #[returns(SolvedCircuitsResponse)]
+    SolvedCircuits {
+        prover_address: String,
+        start_after: Option<(String, String)>,
+        limit: Option<u32>,
+    },
+    /// every proof verified for `prover_address`, oldest first
+    #[returns(ProverHistoryResponse)]
+    ProverHistory {
+        prover_address: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// check a proof without paying for it or recording it, the query only fails when the
+    /// key can't be found or decoded, why a proof is rejected is explained in the response
+    #[returns(VerifyProofResponse)]
+    VerifyProof {
+        key: VerifyingKeySource,
+        num_inputs: usize,
+        n: usize,
+        input_values: Vec<String>,
+        wire_commitments: Vec<String>,
+        grand_product_commitment: String,
+        quotient_poly_commitments: Vec<String>,
+        wire_values_at_z: Vec<String>,
+        wire_values_at_z_omega: Vec<String>,
+        grand_product_at_z_omega: String,
+        quotient_polynomial_at_z: String,
+        linearization_polynomial_at_z: String,
+        permutation_polynomials_at_z: Vec<String>,
+        opening_at_z_proof: String,
+        opening_at_z_omega_proof: String,
+    },
+    /// `VerifyProof` for a snarkjs proof, `key` has to be a snarkjs key
+    #[returns(VerifyProofResponse)]
+    VerifySnarkjsProof {
+        key: VerifyingKeySource,
+        proof: SnarkjsProof,
+        public_signals: Vec<String>,
+    },
 }
 
 #[cw_serde]
 pub struct ConfigResponse {
+    pub owner: Addr,
     pub zkeys_price: Option<Coin>,
     pub proof_price: Option<Coin>,
+    pub zkeys_cw20_price: Option<Cw20CoinVerified>,
+    pub proof_cw20_price: Option<Cw20CoinVerified>,
+    pub fee_mode: FeeMode,
+    pub unrelated_denoms: UnrelatedDenoms,
+    pub commitment_expiry: u64,
 }
 
 impl From<Config> for ConfigResponse {
     fn from(config: Config) -> ConfigResponse {
         ConfigResponse {
+            owner: config.owner,
             zkeys_price: config.zkeys_price,
             proof_price: config.proof_price,
+            zkeys_cw20_price: config.zkeys_cw20_price,
+            proof_cw20_price: config.proof_cw20_price,
+            fee_mode: config.fee_mode,
+            unrelated_denoms: config.unrelated_denoms,
+            commitment_expiry: config.commitment_expiry,
         }
     }
 }
 
-
 #[cw_serde]
 pub struct ProofResponse {
     pub num_inputs: usize,
@@ -93,4 +411,195 @@ pub struct ZkeysResponse {
     pub permutation_commitments: Vec<String>,
     pub non_residues: Vec<String>,
     pub g2_elements: Vec<String>,
-}
\ No newline at end of file
+    pub params: PlonkParams,
+    pub bind_sender: bool,
+    pub nullifier_index: Option<usize>,
+}
+
+#[cw_serde]
+pub struct ProofBinaryResponse {
+    pub num_inputs: usize,
+    pub n: usize,
+    pub input_values: Vec<String>,
+    pub wire_commitments: Vec<Binary>,
+    pub grand_product_commitment: Binary,
+    pub quotient_poly_commitments: Vec<Binary>,
+    pub wire_values_at_z: Vec<String>,
+    pub wire_values_at_z_omega: Vec<String>,
+    pub grand_product_at_z_omega: String,
+    pub quotient_polynomial_at_z: String,
+    pub linearization_polynomial_at_z: String,
+    pub permutation_polynomials_at_z: Vec<String>,
+    pub opening_at_z_proof: Binary,
+    pub opening_at_z_omega_proof: Binary,
+    pub is_valid: bool,
+}
+
+impl From<ProofInfo> for ProofBinaryResponse {
+    fn from(proof_info: ProofInfo) -> ProofBinaryResponse {
+        let proof = proof_info.proof;
+        ProofBinaryResponse {
+            num_inputs: proof.num_inputs,
+            n: proof.n,
+            input_values: proof.input_values,
+            wire_commitments: proof
+                .wire_commitments
+                .into_iter()
+                .map(Binary::from)
+                .collect(),
+            grand_product_commitment: proof.grand_product_commitment.into(),
+            quotient_poly_commitments: proof
+                .quotient_poly_commitments
+                .into_iter()
+                .map(Binary::from)
+                .collect(),
+            wire_values_at_z: proof.wire_values_at_z,
+            wire_values_at_z_omega: proof.wire_values_at_z_omega,
+            grand_product_at_z_omega: proof.grand_product_at_z_omega,
+            quotient_polynomial_at_z: proof.quotient_polynomial_at_z,
+            linearization_polynomial_at_z: proof.linearization_polynomial_at_z,
+            permutation_polynomials_at_z: proof.permutation_polynomials_at_z,
+            opening_at_z_proof: proof.opening_at_z_proof.into(),
+            opening_at_z_omega_proof: proof.opening_at_z_omega_proof.into(),
+            is_valid: proof_info.is_valid,
+        }
+    }
+}
+
+#[cw_serde]
+pub struct ZkeysBinaryResponse {
+    pub n: usize,
+    pub num_inputs: usize,
+    pub selector_commitments: Vec<Binary>,
+    pub next_step_selector_commitments: Vec<Binary>,
+    pub permutation_commitments: Vec<Binary>,
+    pub non_residues: Vec<String>,
+    pub g2_elements: Vec<Binary>,
+    pub params: PlonkParams,
+    pub bind_sender: bool,
+    pub nullifier_index: Option<usize>,
+}
+
+impl From<ZkeysStr> for ZkeysBinaryResponse {
+    fn from(zkeys: ZkeysStr) -> ZkeysBinaryResponse {
+        let vkeys = zkeys.vkeys;
+        ZkeysBinaryResponse {
+            n: vkeys.n,
+            num_inputs: vkeys.num_inputs,
+            selector_commitments: vkeys
+                .selector_commitments
+                .into_iter()
+                .map(Binary::from)
+                .collect(),
+            next_step_selector_commitments: vkeys
+                .next_step_selector_commitments
+                .into_iter()
+                .map(Binary::from)
+                .collect(),
+            permutation_commitments: vkeys
+                .permutation_commitments
+                .into_iter()
+                .map(Binary::from)
+                .collect(),
+            non_residues: vkeys.non_residues,
+            g2_elements: vkeys.g2_elements.into_iter().map(Binary::from).collect(),
+            params: vkeys.params,
+            bind_sender: zkeys.bind_sender,
+            nullifier_index: zkeys.nullifier_index,
+        }
+    }
+}
+
+#[cw_serde]
+pub struct SenderSignalResponse {
+    pub public_signal: String,
+}
+
+#[cw_serde]
+pub struct CommitmentResponse {
+    pub commitment: String,
+    pub height: u64,
+    pub expires_at: u64,
+}
+
+#[cw_serde]
+pub struct NullifierResponse {
+    pub spent: bool,
+}
+
+#[cw_serde]
+pub struct FeeBalanceResponse {
+    pub balance: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct BountyResponse {
+    pub reward: Coin,
+    pub deadline: u64,
+    /// the prover the bounty goes to, unless an earlier commitment is revealed
+    pub winner: Option<Addr>,
+    /// the height from which `ClaimBounty` pays the winner
+    pub claimable_at: Option<u64>,
+}
+
+#[cw_serde]
+pub struct IssuersResponse {
+    pub issuers: Vec<Addr>,
+}
+
+#[cw_serde]
+pub struct CircuitResponse {
+    pub issuer: Addr,
+    pub circuit_id: String,
+    pub vkey_hash: String,
+    pub proof_system: String,
+    pub metadata: CircuitMetadata,
+    pub status: CircuitStatus,
+}
+
+#[cw_serde]
+pub struct CircuitsResponse {
+    pub circuit_ids: Vec<String>,
+}
+
+#[cw_serde]
+pub struct CircuitRef {
+    pub issuer: Addr,
+    pub circuit_id: String,
+}
+
+#[cw_serde]
+pub struct SolvedCircuitsResponse {
+    pub circuits: Vec<CircuitRef>,
+}
+
+#[cw_serde]
+pub struct ProversResponse {
+    pub provers: Vec<Addr>,
+}
+
+#[cw_serde]
+pub struct VerificationResponse {
+    pub sequence: u64,
+    pub issuer: Addr,
+    pub circuit_id: String,
+    pub vkey_hash: String,
+    pub height: u64,
+}
+
+#[cw_serde]
+pub struct ProverHistoryResponse {
+    pub verifications: Vec<VerificationResponse>,
+}
+
+#[cw_serde]
+pub struct VerifyProofResponse {
+    pub is_valid: bool,
+    pub vkey_hash: String,
+    /// the public inputs carried by the proof
+    pub input_values: Vec<String>,
+    /// whether `Proof` would refuse the nullifier, only for registered keys with one
+    pub nullifier_spent: Option<bool>,
+    /// why the proof was rejected
+    pub error: Option<String>,
+}
@@ -1,13 +1,10 @@
 use super::error::ContractError;
 use crate::state::{ProofStr, VkeyStr};
-use cosmwasm_std::ensure;
+use bellman_ce::plonk::better_cs::cs::PlonkConstraintSystemParams;
+use bellman_ce::plonk::better_cs::keys::{Proof, VerificationKey};
 use ff_ce::from_hex;
 use pairing_ce::bn256::{G1Affine, G1Uncompressed, G2Affine, G2Uncompressed};
-use pairing_ce::{CurveAffine, EncodedPoint, Engine};
-use bellman_ce::plonk::better_cs::keys::{ Proof, VerificationKey };
-use bellman_ce::plonk::better_cs::cs::PlonkConstraintSystemParams;
-use core::num;
-use std::marker::PhantomData;
+use pairing_ce::{EncodedPoint, Engine};
 
 /// convert the proof into the Affine/Fr type, which will be used to verify
 pub fn parse_proof<E, P>(pof: ProofStr) -> Result<Proof<E, P>, ContractError>
@@ -15,103 +12,62 @@ where
     E: Engine<G1Affine = G1Affine, G2Affine = G2Affine>,
     P: PlonkConstraintSystemParams<E>,
 {
-    let num_inputs = pof.num_inputs;
-    let n = pof.n;
-    // String -> Fr
-    let input_values = pof.input_values;
-    // Vec<u8> -> Uncompressed -> G1Affine
-    let wire_commitments =  pof.wire_commitments;
-    // Vec<u8> -> Uncompressed -> G1Affine
-    let grand_product_commitment = pof.grand_product_commitment;
-    // Vec<u8> -> Uncompressed -> G1Affine
-    let quotient_poly_commitments = pof.quotient_poly_commitments;
-
-    // String -> Fr
-    let wire_values_at_z = pof.wire_values_at_z;
-    // String -> Fr
-    let wire_values_at_z_omega = pof.wire_values_at_z_omega;
-    // String -> Fr
-    let grand_product_at_z_omega = pof.grand_product_at_z_omega;
-    // String -> Fr
-    let quotient_polynomial_at_z = pof.quotient_polynomial_at_z;
-    // String -> Fr
-    let linearization_polynomial_at_z = pof.linearization_polynomial_at_z;
-    // String -> Fr
-    let permutation_polynomials_at_z = pof.permutation_polynomials_at_z;
-    // Vec<u8> -> Uncompressed -> G1Affine
-    let opening_at_z_proof = pof.opening_at_z_proof;
-    // Vec<u8> -> Uncompressed -> G1Affine
-    let opening_at_z_omega_proof = pof.opening_at_z_omega_proof;
-
-    // ensure the format of proof is correct!
-    ensure!(wire_commitments.iter().all(|inner_vec| inner_vec.len() == 64), ContractError::ErrorProof {});
-    ensure!(grand_product_commitment.len() == 64, ContractError::ErrorProof {});
-    ensure!(quotient_poly_commitments.iter().all(|inner_vec| inner_vec.len() == 64), ContractError::ErrorProof {});
-    ensure!(opening_at_z_proof.len() == 64, ContractError::ErrorProof {});
-    ensure!(opening_at_z_omega_proof.len() == 64, ContractError::ErrorProof {});
-
-
-    // start transform the Affine type
-    let mut wire_commitments_affine: Vec<E::G1Affine> = Vec::new();
-    let mut grand_product_commitment_arr: [u8; 64] = [0; 64];
-    let mut quotient_poly_commitments_affine: Vec<E::G1Affine> = Vec::new();
-    let mut opening_at_z_proof_arr: [u8; 64] = [0; 64];
-    let mut opening_at_z_omega_proof_arr: [u8; 64] = [0; 64];
-
-    let wire_commitments_affine_res: Result<Vec<E::G1Affine>, ContractError> = wire_commitments.into_iter().map(|inner_vec| {
-        let mut array = [0; 64];
-        array[..inner_vec.len()].copy_from_slice(&inner_vec[..]);
-        G1Uncompressed::from_fixed_bytes(array).into_affine().map_err(|_| ContractError::ErrorProof {})
-    }).collect();
-    wire_commitments_affine = wire_commitments_affine_res?;
-
-    grand_product_commitment_arr[..grand_product_commitment.len()].copy_from_slice(&grand_product_commitment[..]);
-
-    let quotient_poly_commitments_res: Result<Vec<E::G1Affine>, ContractError> = quotient_poly_commitments.into_iter().map(|inner_vec| {
-        let mut array = [0; 64];
-        array[..inner_vec.len()].copy_from_slice(&inner_vec[..]);
-        G1Uncompressed::from_fixed_bytes(array).into_affine().map_err(|_| ContractError::ErrorProof {})
-    }).collect();
-    quotient_poly_commitments_affine = quotient_poly_commitments_res?;
-
-    opening_at_z_proof_arr[..opening_at_z_proof.len()].copy_from_slice(&opening_at_z_proof[..]);
-    opening_at_z_omega_proof_arr[..opening_at_z_omega_proof.len()].copy_from_slice(&opening_at_z_omega_proof[..]);
-
-    let grand_product_commitment_affine = G1Uncompressed::from_fixed_bytes(grand_product_commitment_arr)
-    .into_affine()
-    .map_err(|_| ContractError::ErrorProof {})?;
-
-    let opening_at_z_proof_affine = G1Uncompressed::from_fixed_bytes(opening_at_z_proof_arr)
-    .into_affine()
-    .map_err(|_| ContractError::ErrorProof {})?;
-
-    let opening_at_z_omega_proof_affine = G1Uncompressed::from_fixed_bytes(opening_at_z_omega_proof_arr)
-    .into_affine()
-    .map_err(|_| ContractError::ErrorProof {})?;
-
-    // start transform the Prime Field type
-    let wire_values_at_z_res: Result<Vec<E::Fr>, ContractError> = wire_values_at_z.into_iter().map(|x| from_hex(&x).map_err(|_| ContractError::InvalidPrimeField {})).collect();
-
-    // transform end
+    // the verifier indexes these by the shape of `P`, so a short list would abort it
+    check_domain(pof.n).map_err(proof_error("n"))?;
+    check_len(&pof.input_values, pof.num_inputs).map_err(proof_error("input_values"))?;
+    check_len(&pof.wire_commitments, P::STATE_WIDTH).map_err(proof_error("wire_commitments"))?;
+    check_len(&pof.quotient_poly_commitments, P::STATE_WIDTH)
+        .map_err(proof_error("quotient_poly_commitments"))?;
+    check_len(&pof.wire_values_at_z, P::STATE_WIDTH).map_err(proof_error("wire_values_at_z"))?;
+    check_len(&pof.wire_values_at_z_omega, next_step_len::<E, P>())
+        .map_err(proof_error("wire_values_at_z_omega"))?;
+    check_len(&pof.permutation_polynomials_at_z, P::STATE_WIDTH - 1)
+        .map_err(proof_error("permutation_polynomials_at_z"))?;
 
     // return the proof
     let mut final_proof = Proof::empty();
-    final_proof.num_inputs = num_inputs;
-    final_proof.n = n;
-    final_proof.input_values = input_values.into_iter().map(|x| from_hex(&x).unwrap()).collect();
-    final_proof.wire_commitments = wire_commitments_affine;
-    final_proof.grand_product_commitment = grand_product_commitment_affine;
-    final_proof.quotient_poly_commitments = quotient_poly_commitments_affine;
-
-    final_proof.wire_values_at_z = wire_values_at_z_res?;
-    final_proof.wire_values_at_z_omega = wire_values_at_z_omega.into_iter().map(|x| from_hex(&x).unwrap()).collect();
-    final_proof.grand_product_at_z_omega = from_hex(&grand_product_at_z_omega).unwrap();
-    final_proof.quotient_polynomial_at_z = from_hex(&quotient_polynomial_at_z).unwrap();
-    final_proof.linearization_polynomial_at_z = from_hex(&linearization_polynomial_at_z).unwrap();
-    final_proof.permutation_polynomials_at_z = permutation_polynomials_at_z.into_iter().map(|x| from_hex(&x).unwrap()).collect();
-
-    final_proof.opening_at_z_proof = opening_at_z_proof_affine;
-    final_proof.opening_at_z_omega_proof = opening_at_z_omega_proof_affine;
+    final_proof.num_inputs = pof.num_inputs;
+    final_proof.n = pof.n;
+    final_proof.input_values = pof
+        .input_values
+        .iter()
+        .enumerate()
+        .map(|(index, x)| from_hex(x).map_err(|_| ContractError::ParsePulbicSignalError { index }))
+        .collect::<Result<_, _>>()?;
+    final_proof.wire_commitments =
+        g1_points(&pof.wire_commitments, "wire_commitments", proof_error)?;
+    final_proof.grand_product_commitment = g1_affine(&pof.grand_product_commitment)
+        .map_err(proof_error("grand_product_commitment"))?;
+    final_proof.quotient_poly_commitments = g1_points(
+        &pof.quotient_poly_commitments,
+        "quotient_poly_commitments",
+        proof_error,
+    )?;
+
+    final_proof.wire_values_at_z = scalars::<E>(&pof.wire_values_at_z, "wire_values_at_z")?;
+    final_proof.wire_values_at_z_omega =
+        scalars::<E>(&pof.wire_values_at_z_omega, "wire_values_at_z_omega")?;
+    final_proof.grand_product_at_z_omega = scalar::<E>(
+        &pof.grand_product_at_z_omega,
+        "grand_product_at_z_omega".to_string(),
+    )?;
+    final_proof.quotient_polynomial_at_z = scalar::<E>(
+        &pof.quotient_polynomial_at_z,
+        "quotient_polynomial_at_z".to_string(),
+    )?;
+    final_proof.linearization_polynomial_at_z = scalar::<E>(
+        &pof.linearization_polynomial_at_z,
+        "linearization_polynomial_at_z".to_string(),
+    )?;
+    final_proof.permutation_polynomials_at_z = scalars::<E>(
+        &pof.permutation_polynomials_at_z,
+        "permutation_polynomials_at_z",
+    )?;
+
+    final_proof.opening_at_z_proof =
+        g1_affine(&pof.opening_at_z_proof).map_err(proof_error("opening_at_z_proof"))?;
+    final_proof.opening_at_z_omega_proof = g1_affine(&pof.opening_at_z_omega_proof)
+        .map_err(proof_error("opening_at_z_omega_proof"))?;
 
     Ok(final_proof)
 }
@@ -122,66 +78,132 @@ where
     E: Engine<G1Affine = G1Affine, G2Affine = G2Affine>,
     P: PlonkConstraintSystemParams<E>,
 {
-    let n = vk.n;
-    let num_inputs = vk.num_inputs;
-    let selector_commitments = vk.selector_commitments;
-    let next_step_selector_commitments = vk.next_step_selector_commitments;
-    let permutation_commitments = vk.permutation_commitments;
-
-    let non_residues = vk.non_residues;
-    let g2_elements = vk.g2_elements;
-
-
-    ensure!(selector_commitments.iter().all(|inner_vec| inner_vec.len() == 64), ContractError::ErrorVerificationKey {});
-    ensure!(next_step_selector_commitments.iter().all(|inner_vec| inner_vec.len() == 64), ContractError::ErrorVerificationKey {});
-    ensure!(permutation_commitments.iter().all(|inner_vec| inner_vec.len() == 64), ContractError::ErrorVerificationKey {});
-    ensure!(g2_elements.iter().all(|inner_vec| inner_vec.len() == 128), ContractError::ErrorVerificationKey {});
-
-    let mut selector_commitments_affine: Vec<E::G1Affine> = Vec::new();
-    let mut next_step_selector_commitments_affine: Vec<E::G1Affine> = Vec::new();
-    let mut permutation_commitments_affine: Vec<E::G1Affine> = Vec::new();
-    let mut g2_elements_affine: Vec<E::G2Affine> = Vec::new();
-
-    let selector_commitments_res: Result<Vec<E::G1Affine>, ContractError> = selector_commitments.into_iter().map(|inner_vec| {
-        let mut array = [0; 64];
-        array[..inner_vec.len()].copy_from_slice(&inner_vec[..]);
-        G1Uncompressed::from_fixed_bytes(array).into_affine().map_err(|_| ContractError::ErrorVerificationKey {})
-    }).collect();
-    selector_commitments_affine = selector_commitments_res?;
-
-    let next_step_selector_commitments_res: Result<Vec<E::G1Affine>, ContractError> = next_step_selector_commitments.into_iter().map(|inner_vec| {
-        let mut array = [0; 64];
-        array[..inner_vec.len()].copy_from_slice(&inner_vec[..]);
-        G1Uncompressed::from_fixed_bytes(array).into_affine().map_err(|_| ContractError::ErrorVerificationKey {})
-    }).collect();
-    next_step_selector_commitments_affine = next_step_selector_commitments_res?;
-
-    let permutation_commitments_res: Result<Vec<E::G1Affine>, ContractError> = permutation_commitments.into_iter().map(|inner_vec| {
-        let mut array = [0; 64];
-        array[..inner_vec.len()].copy_from_slice(&inner_vec[..]);
-        G1Uncompressed::from_fixed_bytes(array).into_affine().map_err(|_| ContractError::ErrorVerificationKey {})
-    }).collect();
-    permutation_commitments_affine = permutation_commitments_res?;
-
-    let g2_elements_res: Result<Vec<E::G2Affine>, ContractError> = g2_elements.into_iter().map(|inner_vec| {
-        let mut array = [0; 128];
-        array[..inner_vec.len()].copy_from_slice(&inner_vec[..]);
-        G2Uncompressed::from_fixed_bytes(array).into_affine().map_err(|_| ContractError::ErrorVerificationKey {})
-    }).collect();
-    g2_elements_affine = g2_elements_res?;
-
-    let mut g2_elements_affine_arr: [E::G2Affine; 2] = [E::G2Affine::zero(); 2];
-    g2_elements_affine_arr[..g2_elements_affine.len()].copy_from_slice(&g2_elements_affine[..]);
+    check_domain(vk.n).map_err(vkey_error("n"))?;
+    check_len(&vk.selector_commitments, P::STATE_WIDTH + 2)
+        .map_err(vkey_error("selector_commitments"))?;
+    check_len(&vk.next_step_selector_commitments, next_step_len::<E, P>())
+        .map_err(vkey_error("next_step_selector_commitments"))?;
+    check_len(&vk.permutation_commitments, P::STATE_WIDTH)
+        .map_err(vkey_error("permutation_commitments"))?;
+    check_len(&vk.non_residues, P::STATE_WIDTH - 1).map_err(vkey_error("non_residues"))?;
+    check_len(&vk.g2_elements, 2).map_err(vkey_error("g2_elements"))?;
+
+    let g2_elements = vk
+        .g2_elements
+        .iter()
+        .enumerate()
+        .map(|(i, bytes)| {
+            decode_point::<G2Uncompressed>(bytes).map_err(vkey_error(format!("g2_elements[{}]", i)))
+        })
+        .collect::<Result<Vec<G2Affine>, ContractError>>()?;
 
     // return verification key
     Ok(VerificationKey {
-        n,
-        num_inputs,
-        selector_commitments: selector_commitments_affine,
-        next_step_selector_commitments: next_step_selector_commitments_affine,
-        permutation_commitments: permutation_commitments_affine,
-        non_residues: non_residues.into_iter().map(|x| from_hex(&x).unwrap()).collect(),
-        g2_elements: g2_elements_affine_arr,
+        n: vk.n,
+        num_inputs: vk.num_inputs,
+        selector_commitments: g1_points(
+            &vk.selector_commitments,
+            "selector_commitments",
+            vkey_error,
+        )?,
+        next_step_selector_commitments: g1_points(
+            &vk.next_step_selector_commitments,
+            "next_step_selector_commitments",
+            vkey_error,
+        )?,
+        permutation_commitments: g1_points(
+            &vk.permutation_commitments,
+            "permutation_commitments",
+            vkey_error,
+        )?,
+        non_residues: scalars::<E>(&vk.non_residues, "non_residues")?,
+        g2_elements: [g2_elements[0], g2_elements[1]],
         _marker: std::marker::PhantomData,
     })
 }
+
+/// the evaluations at z * omega, only taken when the gates reach the next row
+fn next_step_len<E: Engine, P: PlonkConstraintSystemParams<E>>() -> usize {
+    if P::CAN_ACCESS_NEXT_TRACE_STEP {
+        1
+    } else {
+        0
+    }
+}
+
+/// the verifier works over a domain of n + 1 rows
+pub(crate) fn check_domain(n: usize) -> Result<(), String> {
+    match n.checked_add(1) {
+        Some(size) if size.is_power_of_two() => Ok(()),
+        _ => Err(format!("{} + 1 is not a power of two", n)),
+    }
+}
+
+pub(crate) fn check_len<T>(values: &[T], expected: usize) -> Result<(), String> {
+    if values.len() != expected {
+        return Err(format!(
+            "expected {} elements, got {}",
+            expected,
+            values.len()
+        ));
+    }
+    Ok(())
+}
+
+/// the points of a list field, a failure names the index of the point
+pub(crate) fn g1_points<F>(
+    points: &[Vec<u8>],
+    field: &str,
+    error: impl Fn(String) -> F,
+) -> Result<Vec<G1Affine>, ContractError>
+where
+    F: FnOnce(String) -> ContractError,
+{
+    points
+        .iter()
+        .enumerate()
+        .map(|(i, bytes)| g1_affine(bytes).map_err(error(format!("{}[{}]", field, i))))
+        .collect()
+}
+
+fn g1_affine(bytes: &[u8]) -> Result<G1Affine, String> {
+    decode_point::<G1Uncompressed>(bytes)
+}
+
+pub(crate) fn decode_point<P: EncodedPoint>(bytes: &[u8]) -> Result<P::Affine, String> {
+    if bytes.len() != P::size() {
+        return Err(format!("expected {} bytes, got {}", P::size(), bytes.len()));
+    }
+    let mut encoded = P::empty();
+    encoded.as_mut().copy_from_slice(bytes);
+    encoded.into_affine().map_err(|err| err.to_string())
+}
+
+/// the field elements of a list field, a failure names the index of the element
+fn scalars<E: Engine>(values: &[String], field: &str) -> Result<Vec<E::Fr>, ContractError> {
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, x)| scalar::<E>(x, format!("{}[{}]", field, i)))
+        .collect()
+}
+
+fn scalar<E: Engine>(value: &str, field: String) -> Result<E::Fr, ContractError> {
+    from_hex(value).map_err(|_| ContractError::InvalidPrimeField { field })
+}
+
+/// names the proof field a reason is about
+pub(crate) fn proof_error(field: impl Into<String>) -> impl FnOnce(String) -> ContractError {
+    move |reason| ContractError::ErrorProof {
+        field: field.into(),
+        reason,
+    }
+}
+
+/// names the key field a reason is about
+pub(crate) fn vkey_error(field: impl Into<String>) -> impl FnOnce(String) -> ContractError {
+    move |reason| ContractError::ErrorVerificationKey {
+        field: field.into(),
+        reason,
+    }
+}
@@ -0,0 +1,50 @@
+use cosmwasm_std::{Addr, Uint256};
+use sha2::{Digest, Sha256};
+
+/// The public signal that binds a proof to the address submitting it.
+///
+/// sha256 of the address string with the first byte cleared, read as a
+/// big-endian integer, so the value always fits in the scalar field. Circuits
+/// registered with `bind_sender` must take it as their last public input.
+pub fn sender_public_signal(sender: &Addr) -> String {
+    Uint256::from_be_bytes(sender_hash(sender)).to_string()
+}
+
+/// `sender_public_signal` as a hex input value, the encoding proofs carry their inputs in
+pub fn sender_input_value(sender: &Addr) -> String {
+    hex::encode(sender_hash(sender))
+}
+
+fn sender_hash(sender: &Addr) -> [u8; 32] {
+    let mut hash: [u8; 32] = Sha256::digest(sender.as_bytes()).into();
+    hash[0] = 0;
+    hash
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ff_ce::PrimeField;
+
+    #[test]
+    fn sender_public_signal_works() {
+        let bob = sender_public_signal(&Addr::unchecked("bob_key"));
+        assert_eq!(
+            bob,
+            "436755631849614684803579447320562306979667821650121700687015980709801145381"
+        );
+        // different senders never share a signal
+        assert_ne!(bob, sender_public_signal(&Addr::unchecked("carol_key")));
+    }
+
+    #[test]
+    fn sender_input_value_works() {
+        let bob = Addr::unchecked("bob_key");
+        let input_value =
+            ff_ce::from_hex::<pairing_ce::bn256::Fr>(&sender_input_value(&bob)).unwrap();
+        assert_eq!(
+            Some(input_value),
+            pairing_ce::bn256::Fr::from_str(&sender_public_signal(&bob))
+        );
+    }
+}
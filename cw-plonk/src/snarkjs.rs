@@ -0,0 +1,662 @@
+use cosmwasm_schema::{schemars, serde};
+use ff_ce::{Field, PrimeField, PrimeFieldRepr};
+use pairing_ce::bn256::{
+    Bn256, Fq, Fq2, Fr, FrRepr, G1Affine, G1Uncompressed, G2Affine, G2Uncompressed, G1,
+};
+use pairing_ce::{CurveAffine, CurveProjective, EncodedPoint, Engine};
+
+use crate::parser::{check_domain, check_len, decode_point, g1_points, proof_error, vkey_error};
+use crate::state::{PlonkParams, ProofStr, VkeyStr};
+use crate::ContractError;
+
+/// the `curve` snarkjs writes for keys and proofs this contract verifies
+pub const SNARKJS_CURVE: &str = "bn128";
+
+/// the largest domain snarkjs has a root of unity for is 2^28 rows
+pub const MAX_POWER: u32 = 28;
+
+// 5^((r - 1) / 2^28), the root of unity snarkjs squares down to the one of a domain
+const ROOT_OF_UNITY: &str =
+    "19103219067921713944291392827692070036145651957329286315305642004821462161904";
+
+/// a `verification_key.json` as written by `snarkjs zkey export verificationkey`
+/// for a PLONK circuit, numbers are decimal and points jacobian
+///
+/// Unlike `cw_serde` types unknown fields are allowed, snarkjs also writes `w`,
+/// which is derived from `power` here.
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, schemars::JsonSchema)]
+#[serde(crate = "cosmwasm_schema::serde")]
+#[schemars(crate = "cosmwasm_schema::schemars")]
+pub struct SnarkjsVerifyingKey {
+    pub protocol: String,
+    pub curve: String,
+    #[serde(rename = "nPublic")]
+    pub n_public: usize,
+    pub power: u32,
+    pub k1: String,
+    pub k2: String,
+    #[serde(rename = "Qm")]
+    pub qm: Vec<String>,
+    #[serde(rename = "Ql")]
+    pub ql: Vec<String>,
+    #[serde(rename = "Qr")]
+    pub qr: Vec<String>,
+    #[serde(rename = "Qo")]
+    pub qo: Vec<String>,
+    #[serde(rename = "Qc")]
+    pub qc: Vec<String>,
+    #[serde(rename = "S1")]
+    pub s1: Vec<String>,
+    #[serde(rename = "S2")]
+    pub s2: Vec<String>,
+    #[serde(rename = "S3")]
+    pub s3: Vec<String>,
+    #[serde(rename = "X_2")]
+    pub x_2: Vec<Vec<String>>,
+}
+
+/// a `proof.json` as written by `snarkjs plonk prove`, its `public.json`
+/// goes into the `public_signals` of the message as it is
+#[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq, schemars::JsonSchema)]
+#[serde(crate = "cosmwasm_schema::serde")]
+#[schemars(crate = "cosmwasm_schema::schemars")]
+pub struct SnarkjsProof {
+    #[serde(rename = "A")]
+    pub a: Vec<String>,
+    #[serde(rename = "B")]
+    pub b: Vec<String>,
+    #[serde(rename = "C")]
+    pub c: Vec<String>,
+    #[serde(rename = "Z")]
+    pub z: Vec<String>,
+    #[serde(rename = "T1")]
+    pub t1: Vec<String>,
+    #[serde(rename = "T2")]
+    pub t2: Vec<String>,
+    #[serde(rename = "T3")]
+    pub t3: Vec<String>,
+    #[serde(rename = "Wxi")]
+    pub wxi: Vec<String>,
+    #[serde(rename = "Wxiw")]
+    pub wxiw: Vec<String>,
+    pub eval_a: String,
+    pub eval_b: String,
+    pub eval_c: String,
+    pub eval_s1: String,
+    pub eval_s2: String,
+    pub eval_zw: String,
+    pub protocol: String,
+    pub curve: String,
+}
+
+impl SnarkjsVerifyingKey {
+    /// the key in the `PlonkParams::Snarkjs` layout of `VkeyStr`
+    pub fn to_vkey_str(&self) -> Result<VkeyStr, ContractError> {
+        check_header(&self.protocol, &self.curve)?;
+        if self.power > MAX_POWER {
+            return Err(vkey_error("power")(format!(
+                "snarkjs has no domain above 2^{}",
+                MAX_POWER
+            )));
+        }
+        let g1 = |field: &str, point: &[String]| g1_bytes(field, point, vkey_error);
+
+        Ok(VkeyStr {
+            n: (1 << self.power) - 1,
+            num_inputs: self.n_public,
+            selector_commitments: vec![
+                g1("Qm", &self.qm)?,
+                g1("Ql", &self.ql)?,
+                g1("Qr", &self.qr)?,
+                g1("Qo", &self.qo)?,
+                g1("Qc", &self.qc)?,
+            ],
+            next_step_selector_commitments: vec![],
+            permutation_commitments: vec![
+                g1("S1", &self.s1)?,
+                g1("S2", &self.s2)?,
+                g1("S3", &self.s3)?,
+            ],
+            non_residues: vec![
+                checked_scalar("k1", &self.k1)?,
+                checked_scalar("k2", &self.k2)?,
+            ],
+            g2_elements: vec![
+                G2Uncompressed::from_affine(G2Affine::one())
+                    .as_ref()
+                    .to_vec(),
+                G2Uncompressed::from_affine(g2_point("X_2", &self.x_2, vkey_error)?)
+                    .as_ref()
+                    .to_vec(),
+            ],
+            params: PlonkParams::Snarkjs,
+        })
+    }
+}
+
+impl SnarkjsProof {
+    /// the proof in the `PlonkParams::Snarkjs` layout of `ProofStr`, a snarkjs
+    /// proof doesn't carry its domain so `n` is taken from the key
+    pub fn to_proof_str(
+        &self,
+        vkey: &VkeyStr,
+        public_signals: Vec<String>,
+    ) -> Result<ProofStr, ContractError> {
+        check_header(&self.protocol, &self.curve)?;
+        if vkey.params != PlonkParams::Snarkjs {
+            return Err(ContractError::NotSnarkjsCircuit {});
+        }
+        let g1 = |field: &str, point: &[String]| g1_bytes(field, point, proof_error);
+
+        Ok(ProofStr {
+            num_inputs: public_signals.len(),
+            n: vkey.n,
+            input_values: public_signals,
+            wire_commitments: vec![g1("A", &self.a)?, g1("B", &self.b)?, g1("C", &self.c)?],
+            grand_product_commitment: g1("Z", &self.z)?,
+            quotient_poly_commitments: vec![
+                g1("T1", &self.t1)?,
+                g1("T2", &self.t2)?,
+                g1("T3", &self.t3)?,
+            ],
+            wire_values_at_z: vec![
+                checked_scalar("eval_a", &self.eval_a)?,
+                checked_scalar("eval_b", &self.eval_b)?,
+                checked_scalar("eval_c", &self.eval_c)?,
+            ],
+            wire_values_at_z_omega: vec![],
+            grand_product_at_z_omega: checked_scalar("eval_zw", &self.eval_zw)?,
+            // snarkjs opens neither of them, the verifier rebuilds both from the other evaluations
+            quotient_polynomial_at_z: String::new(),
+            linearization_polynomial_at_z: String::new(),
+            permutation_polynomials_at_z: vec![
+                checked_scalar("eval_s1", &self.eval_s1)?,
+                checked_scalar("eval_s2", &self.eval_s2)?,
+            ],
+            opening_at_z_proof: g1("Wxi", &self.wxi)?,
+            opening_at_z_omega_proof: g1("Wxiw", &self.wxiw)?,
+        })
+    }
+}
+
+/// the points and scalars of a key in the `PlonkParams::Snarkjs` layout
+struct Key {
+    power: u32,
+    num_inputs: usize,
+    k1: Fr,
+    k2: Fr,
+    selectors: Vec<G1Affine>,
+    permutations: Vec<G1Affine>,
+    x_2: G2Affine,
+}
+
+/// the points and scalars of a proof in the `PlonkParams::Snarkjs` layout
+struct Proof {
+    public_inputs: Vec<Fr>,
+    wires: Vec<G1Affine>,
+    z: G1Affine,
+    quotients: Vec<G1Affine>,
+    wire_evals: Vec<Fr>,
+    permutation_evals: Vec<Fr>,
+    eval_zw: Fr,
+    wxi: G1Affine,
+    wxiw: G1Affine,
+}
+
+/// the Fiat-Shamir challenges, in the order snarkjs draws them
+struct Challenges {
+    beta: Fr,
+    gamma: Fr,
+    alpha: Fr,
+    xi: Fr,
+    // v^1 to v^5
+    v: Vec<Fr>,
+    u: Fr,
+}
+
+/// `snarkjs plonk verify`, the parsed public inputs are returned with the result
+pub fn verify(vk: &VkeyStr, pof: &ProofStr) -> Result<(bool, Vec<Fr>), ContractError> {
+    let key = parse_vkey(vk)?;
+    if pof.n != vk.n {
+        return Err(ContractError::ErrorProof {
+            field: "n".to_string(),
+            reason: format!("expected {}, got {}", vk.n, pof.n),
+        });
+    }
+    if pof.num_inputs != key.num_inputs {
+        return Err(ContractError::ErrorProof {
+            field: "num_inputs".to_string(),
+            reason: format!("expected {}, got {}", key.num_inputs, pof.num_inputs),
+        });
+    }
+    let proof = parse_proof(pof)?;
+    let ch = challenges(&key, &proof);
+
+    let [eval_a, eval_b, eval_c] = [
+        proof.wire_evals[0],
+        proof.wire_evals[1],
+        proof.wire_evals[2],
+    ];
+    let [eval_s1, eval_s2] = [proof.permutation_evals[0], proof.permutation_evals[1]];
+    let omega = root_of_unity(key.power);
+
+    // the vanishing polynomial and the lagrange polynomials of the public inputs at xi
+    let mut xin = ch.xi;
+    for _ in 0..key.power {
+        xin.square();
+    }
+    let zh = sub(xin, &Fr::one());
+    let domain_size = from_u64(1 << key.power);
+    let mut lagrange = Vec::with_capacity(key.num_inputs.max(1));
+    let mut w = Fr::one();
+    for _ in 0..key.num_inputs.max(1) {
+        let inverse = match mul(domain_size, &sub(ch.xi, &w)).inverse() {
+            Some(inverse) => inverse,
+            // xi on the domain, snarkjs fails on the division as well
+            None => return Ok((false, proof.public_inputs)),
+        };
+        lagrange.push(mul(mul(w, &zh), &inverse));
+        w.mul_assign(&omega);
+    }
+    let mut pi = Fr::zero();
+    for (input, l) in proof.public_inputs.iter().zip(&lagrange) {
+        pi.sub_assign(&mul(*input, l));
+    }
+
+    // the constant part of the linearization
+    let mut alpha2 = ch.alpha;
+    alpha2.square();
+    let l1_alpha2 = mul(lagrange[0], &alpha2);
+    let e3a = add(add(eval_a, &mul(ch.beta, &eval_s1)), &ch.gamma);
+    let e3b = add(add(eval_b, &mul(ch.beta, &eval_s2)), &ch.gamma);
+    let e3c = add(eval_c, &ch.gamma);
+    let e3 = mul(mul(mul(mul(e3a, &e3b), &e3c), &proof.eval_zw), &ch.alpha);
+    let r0 = sub(sub(pi, &l1_alpha2), &e3);
+
+    // D, the commitment of the linearization without its constant part
+    let [qm, ql, qr, qo, qc] = [
+        &key.selectors[0],
+        &key.selectors[1],
+        &key.selectors[2],
+        &key.selectors[3],
+        &key.selectors[4],
+    ];
+    let mut d1 = times(qm, &mul(eval_a, &eval_b));
+    d1.add_assign(&times(ql, &eval_a));
+    d1.add_assign(&times(qr, &eval_b));
+    d1.add_assign(&times(qo, &eval_c));
+    d1.add_assign_mixed(qc);
+
+    let betaxi = mul(ch.beta, &ch.xi);
+    let d2a1 = add(add(eval_a, &betaxi), &ch.gamma);
+    let d2a2 = add(add(eval_b, &mul(betaxi, &key.k1)), &ch.gamma);
+    let d2a3 = add(add(eval_c, &mul(betaxi, &key.k2)), &ch.gamma);
+    let d2a = mul(mul(mul(d2a1, &d2a2), &d2a3), &ch.alpha);
+    let d2 = times(&proof.z, &add(add(d2a, &l1_alpha2), &ch.u));
+
+    let d3c = mul(mul(ch.alpha, &ch.beta), &proof.eval_zw);
+    let mut d3 = times(&key.permutations[2], &mul(mul(e3a, &e3b), &d3c));
+
+    let mut xin2 = xin;
+    xin2.square();
+    let mut d4 = times(&proof.quotients[1], &xin);
+    d4.add_assign(&times(&proof.quotients[2], &xin2));
+    d4.add_assign_mixed(&proof.quotients[0]);
+    d4.mul_assign(zh.into_repr());
+
+    // F = D + v1 A + v2 B + v3 C + v4 S1 + v5 S2
+    let mut f = d1;
+    f.add_assign(&d2);
+    d3.negate();
+    f.add_assign(&d3);
+    d4.negate();
+    f.add_assign(&d4);
+    let opened = [
+        &proof.wires[0],
+        &proof.wires[1],
+        &proof.wires[2],
+        &key.permutations[0],
+        &key.permutations[1],
+    ];
+    for (point, v) in opened.iter().zip(&ch.v) {
+        f.add_assign(&times(point, v));
+    }
+
+    // E, the commitment of the evaluations F opens to
+    let mut e = r0;
+    e.negate();
+    for (eval, v) in [eval_a, eval_b, eval_c, eval_s1, eval_s2].iter().zip(&ch.v) {
+        e.add_assign(&mul(*eval, v));
+    }
+    e.add_assign(&mul(ch.u, &proof.eval_zw));
+    let mut e = times(&G1Affine::one(), &e);
+
+    // e(-(Wxi + u Wxiw), X_2) * e(xi Wxi + u xi omega Wxiw + F - E, [1]_2) == 1
+    let mut a1 = times(&proof.wxiw, &ch.u);
+    a1.add_assign_mixed(&proof.wxi);
+    a1.negate();
+    let mut b1 = times(&proof.wxi, &ch.xi);
+    b1.add_assign(&times(&proof.wxiw, &mul(mul(ch.u, &ch.xi), &omega)));
+    b1.add_assign(&f);
+    e.negate();
+    b1.add_assign(&e);
+
+    let a1 = a1.into_affine().prepare();
+    let b1 = b1.into_affine().prepare();
+    let x_2 = key.x_2.prepare();
+    let g2 = G2Affine::one().prepare();
+    let pairing = Bn256::final_exponentiation(&Bn256::miller_loop(&[(&a1, &x_2), (&b1, &g2)]));
+    Ok((
+        pairing == Some(<Bn256 as Engine>::Fqk::one()),
+        proof.public_inputs,
+    ))
+}
+
+/// check the key parses in the `PlonkParams::Snarkjs` layout
+pub fn check_vkey(vk: &VkeyStr) -> Result<(), ContractError> {
+    parse_vkey(vk).map(|_| ())
+}
+
+fn parse_vkey(vk: &VkeyStr) -> Result<Key, ContractError> {
+    check_domain(vk.n).map_err(vkey_error("n"))?;
+    let power = (vk.n + 1).trailing_zeros();
+    if power > MAX_POWER {
+        return Err(vkey_error("n")(format!(
+            "snarkjs has no domain above 2^{}",
+            MAX_POWER
+        )));
+    }
+    check_len(&vk.selector_commitments, 5).map_err(vkey_error("selector_commitments"))?;
+    check_len(&vk.next_step_selector_commitments, 0)
+        .map_err(vkey_error("next_step_selector_commitments"))?;
+    check_len(&vk.permutation_commitments, 3).map_err(vkey_error("permutation_commitments"))?;
+    check_len(&vk.non_residues, 2).map_err(vkey_error("non_residues"))?;
+    check_len(&vk.g2_elements, 2).map_err(vkey_error("g2_elements"))?;
+
+    let g2 = |i: usize| {
+        decode_point::<G2Uncompressed>(&vk.g2_elements[i])
+            .map_err(vkey_error(format!("g2_elements[{}]", i)))
+    };
+    if g2(0)? != G2Affine::one() {
+        // snarkjs pairs with the generator, the key only carries X_2
+        return Err(vkey_error("g2_elements[0]")(
+            "not the generator of G2".to_string(),
+        ));
+    }
+    let non_residues = scalars(&vk.non_residues, "non_residues")?;
+
+    Ok(Key {
+        power,
+        num_inputs: vk.num_inputs,
+        k1: non_residues[0],
+        k2: non_residues[1],
+        selectors: g1_points(&vk.selector_commitments, "selector_commitments", vkey_error)?,
+        permutations: g1_points(
+            &vk.permutation_commitments,
+            "permutation_commitments",
+            vkey_error,
+        )?,
+        x_2: g2(1)?,
+    })
+}
+
+fn parse_proof(pof: &ProofStr) -> Result<Proof, ContractError> {
+    check_len(&pof.input_values, pof.num_inputs).map_err(proof_error("input_values"))?;
+    check_len(&pof.wire_commitments, 3).map_err(proof_error("wire_commitments"))?;
+    check_len(&pof.quotient_poly_commitments, 3)
+        .map_err(proof_error("quotient_poly_commitments"))?;
+    check_len(&pof.wire_values_at_z, 3).map_err(proof_error("wire_values_at_z"))?;
+    check_len(&pof.wire_values_at_z_omega, 0).map_err(proof_error("wire_values_at_z_omega"))?;
+    check_len(&pof.permutation_polynomials_at_z, 2)
+        .map_err(proof_error("permutation_polynomials_at_z"))?;
+
+    let g1 = |bytes: &[u8], field: &str| {
+        decode_point::<G1Uncompressed>(bytes).map_err(proof_error(field))
+    };
+
+    Ok(Proof {
+        public_inputs: pof
+            .input_values
+            .iter()
+            .enumerate()
+            .map(|(index, x)| {
+                Fr::from_str(x).ok_or(ContractError::ParsePulbicSignalError { index })
+            })
+            .collect::<Result<_, _>>()?,
+        wires: g1_points(&pof.wire_commitments, "wire_commitments", proof_error)?,
+        z: g1(&pof.grand_product_commitment, "grand_product_commitment")?,
+        quotients: g1_points(
+            &pof.quotient_poly_commitments,
+            "quotient_poly_commitments",
+            proof_error,
+        )?,
+        wire_evals: scalars(&pof.wire_values_at_z, "wire_values_at_z")?,
+        permutation_evals: scalars(
+            &pof.permutation_polynomials_at_z,
+            "permutation_polynomials_at_z",
+        )?,
+        eval_zw: scalar(
+            &pof.grand_product_at_z_omega,
+            "grand_product_at_z_omega".to_string(),
+        )?,
+        wxi: g1(&pof.opening_at_z_proof, "opening_at_z_proof")?,
+        wxiw: g1(&pof.opening_at_z_omega_proof, "opening_at_z_omega_proof")?,
+    })
+}
+
+fn challenges(key: &Key, proof: &Proof) -> Challenges {
+    let mut transcript = Transcript::default();
+    key.selectors
+        .iter()
+        .chain(&key.permutations)
+        .for_each(|point| transcript.add_point(point));
+    proof
+        .public_inputs
+        .iter()
+        .for_each(|input| transcript.add_scalar(input));
+    proof
+        .wires
+        .iter()
+        .for_each(|point| transcript.add_point(point));
+    let beta = transcript.challenge();
+
+    transcript.add_scalar(&beta);
+    let gamma = transcript.challenge();
+
+    transcript.add_scalar(&beta);
+    transcript.add_scalar(&gamma);
+    transcript.add_point(&proof.z);
+    let alpha = transcript.challenge();
+
+    transcript.add_scalar(&alpha);
+    proof
+        .quotients
+        .iter()
+        .for_each(|point| transcript.add_point(point));
+    let xi = transcript.challenge();
+
+    transcript.add_scalar(&xi);
+    proof
+        .wire_evals
+        .iter()
+        .chain(&proof.permutation_evals)
+        .for_each(|eval| transcript.add_scalar(eval));
+    transcript.add_scalar(&proof.eval_zw);
+    let v1 = transcript.challenge();
+    let v = std::iter::successors(Some(v1), |vi| Some(mul(*vi, &v1)))
+        .take(5)
+        .collect();
+
+    transcript.add_point(&proof.wxi);
+    transcript.add_point(&proof.wxiw);
+    let u = transcript.challenge();
+
+    Challenges {
+        beta,
+        gamma,
+        alpha,
+        xi,
+        v,
+        u,
+    }
+}
+
+/// the keccak256 transcript of snarkjs, points go in uncompressed and scalars as 32 big-endian bytes
+#[derive(Default)]
+struct Transcript(Vec<u8>);
+
+impl Transcript {
+    fn add_point(&mut self, point: &G1Affine) {
+        // the point at infinity is flagged like snarkjs does, 0x40 and zeros
+        self.0
+            .extend_from_slice(G1Uncompressed::from_affine(*point).as_ref());
+    }
+
+    fn add_scalar(&mut self, scalar: &Fr) {
+        scalar
+            .into_repr()
+            .write_be(&mut self.0)
+            .expect("writing to a vec can't fail");
+    }
+
+    /// the hash as a big-endian number mod r, the transcript starts over after it
+    fn challenge(&mut self) -> Fr {
+        let hash = tiny_keccak::keccak256(&self.0);
+        self.0.clear();
+        let byte = from_u64(256);
+        hash.iter().fold(Fr::zero(), |acc, b| {
+            add(mul(acc, &byte), &from_u64(u64::from(*b)))
+        })
+    }
+}
+
+/// the root of unity of a domain of 2^power rows, `power` is at most `MAX_POWER`
+fn root_of_unity(power: u32) -> Fr {
+    let mut omega = Fr::from_str(ROOT_OF_UNITY).expect("the root of unity is below r");
+    for _ in power..MAX_POWER {
+        omega.square();
+    }
+    omega
+}
+
+fn check_header(protocol: &str, curve: &str) -> Result<(), ContractError> {
+    let mismatch = |field: &str, expected: &str, found: &str| ContractError::SnarkjsMismatch {
+        field: field.to_string(),
+        expected: expected.to_string(),
+        found: found.to_string(),
+    };
+    if protocol != "plonk" {
+        return Err(mismatch("protocol", "plonk", protocol));
+    }
+    if curve != SNARKJS_CURVE {
+        return Err(mismatch("curve", SNARKJS_CURVE, curve));
+    }
+    Ok(())
+}
+
+/// a decimal scalar of a snarkjs file, kept as written once it's known to parse
+fn checked_scalar(field: &str, value: &str) -> Result<String, ContractError> {
+    scalar(value, field.to_string()).map(|_| value.to_string())
+}
+
+/// the decimal field elements of a list field, a failure names the index of the element
+fn scalars(values: &[String], field: &str) -> Result<Vec<Fr>, ContractError> {
+    values
+        .iter()
+        .enumerate()
+        .map(|(i, x)| scalar(x, format!("{}[{}]", field, i)))
+        .collect()
+}
+
+/// a decimal number as a field element, reduced mod r like snarkjs reads it
+fn scalar(value: &str, field: String) -> Result<Fr, ContractError> {
+    Fr::from_str(value).ok_or(ContractError::InvalidPrimeField { field })
+}
+
+/// `[x, y, z]` as the uncompressed encoding of the point
+fn g1_bytes<F>(
+    field: &str,
+    point: &[String],
+    error: impl Fn(String) -> F,
+) -> Result<Vec<u8>, ContractError>
+where
+    F: FnOnce(String) -> ContractError,
+{
+    let coordinate = |value: &String| Fq::from_str(value).ok_or_else(|| invalid_coordinate(field));
+    match point {
+        [x, y, z] => {
+            let affine = from_jacobian::<G1Affine>(coordinate(x)?, coordinate(y)?, coordinate(z)?)
+                .map_err(error(field.to_string()))?;
+            Ok(G1Uncompressed::from_affine(affine).as_ref().to_vec())
+        }
+        _ => Err(invalid_coordinate(field)),
+    }
+}
+
+/// `[[x.c0, x.c1], [y.c0, y.c1], [z.c0, z.c1]]` as the affine point
+fn g2_point<F>(
+    field: &str,
+    point: &[Vec<String>],
+    error: impl Fn(String) -> F,
+) -> Result<G2Affine, ContractError>
+where
+    F: FnOnce(String) -> ContractError,
+{
+    let coordinate = |value: &Vec<String>| -> Result<Fq2, ContractError> {
+        match &value[..] {
+            [c0, c1] => Ok(Fq2 {
+                c0: Fq::from_str(c0).ok_or_else(|| invalid_coordinate(field))?,
+                c1: Fq::from_str(c1).ok_or_else(|| invalid_coordinate(field))?,
+            }),
+            _ => Err(invalid_coordinate(field)),
+        }
+    };
+    match point {
+        [x, y, z] => from_jacobian::<G2Affine>(coordinate(x)?, coordinate(y)?, coordinate(z)?)
+            .map_err(error(field.to_string())),
+        _ => Err(invalid_coordinate(field)),
+    }
+}
+
+/// (x / z^2, y / z^3), snarkjs writes the point at infinity with z = 0
+fn from_jacobian<G: CurveAffine>(mut x: G::Base, mut y: G::Base, z: G::Base) -> Result<G, String> {
+    let z_inv = match z.inverse() {
+        Some(z_inv) => z_inv,
+        None => return Ok(G::zero()),
+    };
+    let mut z_inv2 = z_inv;
+    z_inv2.square();
+    x.mul_assign(&z_inv2);
+    y.mul_assign(&z_inv2);
+    y.mul_assign(&z_inv);
+    G::from_xy_checked(x, y).map_err(|err| err.to_string())
+}
+
+fn invalid_coordinate(field: &str) -> ContractError {
+    ContractError::InvalidSnarkjsCoordinate {
+        field: field.to_string(),
+    }
+}
+
+fn times(point: &G1Affine, scalar: &Fr) -> G1 {
+    point.mul(scalar.into_repr())
+}
+
+fn from_u64(value: u64) -> Fr {
+    Fr::from_repr(FrRepr::from(value)).expect("a u64 is below r")
+}
+
+fn add(mut a: Fr, b: &Fr) -> Fr {
+    a.add_assign(b);
+    a
+}
+
+fn sub(mut a: Fr, b: &Fr) -> Fr {
+    a.sub_assign(b);
+    a
+}
+
+fn mul(mut a: Fr, b: &Fr) -> Fr {
+    a.mul_assign(b);
+    a
+}
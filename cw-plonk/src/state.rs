@@ -1,11 +1,40 @@
 use cosmwasm_schema::cw_serde;
-use cosmwasm_std::{Addr, Coin};
+use cosmwasm_std::{Addr, Coin, Empty, Uint128};
+use cw20::Cw20CoinVerified;
 use cw_storage_plus::{Item, Map};
 
 #[cw_serde]
 pub struct Config {
+    pub owner: Addr,
     pub zkeys_price: Option<Coin>,
     pub proof_price: Option<Coin>,
+    // the same prices in a cw20 token, paid through `Receive`
+    pub zkeys_cw20_price: Option<Cw20CoinVerified>,
+    pub proof_cw20_price: Option<Cw20CoinVerified>,
+    pub fee_mode: FeeMode,
+    pub unrelated_denoms: UnrelatedDenoms,
+    // blocks a proof commitment stays revealable
+    pub commitment_expiry: u64,
+}
+
+/// how a price is taken from the funds sent with a message
+#[cw_serde]
+#[derive(Default)]
+pub enum FeeMode {
+    /// keep everything sent as long as it covers the price
+    #[default]
+    Sufficient,
+    /// take exactly the price and refund the rest to the sender
+    Exact,
+}
+
+/// what `FeeMode::Exact` does with coins in a denom other than the price
+#[cw_serde]
+#[derive(Default)]
+pub enum UnrelatedDenoms {
+    #[default]
+    Refund,
+    Reject,
 }
 
 #[cw_serde]
@@ -35,8 +64,61 @@ pub struct VkeyStr {
     pub permutation_commitments: Vec<Vec<u8>>,
     pub non_residues: Vec<String>,
     pub g2_elements: Vec<Vec<u8>>,
+    // keys stored before 0.2.0 are all width 4 with next step
+    #[serde(default)]
+    pub params: PlonkParams,
 }
 
+/// the constraint system a key was generated for, it decides the shape of its keys and proofs
+#[cw_serde]
+#[derive(Copy, Default)]
+pub enum PlonkParams {
+    /// 4 wires and a selector on the next row, `PlonkCsWidth4WithNextStepParams`
+    #[default]
+    Width4WithNextStep,
+    /// 3 wires and a selector on the next row, `PlonkCsWidth3WithNextStepParams`
+    Width3WithNextStep,
+    /// a snarkjs PLONK key, selector_commitments are Qm, Ql, Qr, Qo, Qc,
+    /// permutation_commitments S1, S2, S3, non_residues the decimal k1, k2 and
+    /// g2_elements [1]_2, X_2; its proofs carry decimal evaluations and leave
+    /// quotient_polynomial_at_z and linearization_polynomial_at_z empty
+    Snarkjs,
+}
+
+/// where a circuit is in its life, only its issuer moves it along
+#[cw_serde]
+#[derive(Default)]
+pub enum CircuitStatus {
+    #[default]
+    Active,
+    /// still accepts proofs, provers should move to the circuit replacing it
+    Deprecated,
+    /// refuses new proofs and commitments, what was already proven stays queryable
+    Retired,
+}
+
+/// what a circuit proves and where its key comes from, as told by the issuer
+#[cw_serde]
+#[derive(Default)]
+pub struct CircuitMetadata {
+    pub name: String,
+    pub description: String,
+    // where the circuit source can be found, e.g. a repository or an ipfs uri
+    pub source_uri: String,
+    // hex sha256 of the artifact the key was exported from, e.g. the circom file or the zkey
+    pub artifact_hash: String,
+}
+
+#[cw_serde]
+pub struct ZkeysStr {
+    pub vkeys: VkeyStr,
+    // the last public input must be the prover's sender signal
+    pub bind_sender: bool,
+    // public input index whose value can only be used once
+    pub nullifier_index: Option<usize>,
+    pub metadata: CircuitMetadata,
+    pub status: CircuitStatus,
+}
 
 #[cw_serde]
 pub struct ProofInfo {
@@ -44,8 +126,57 @@ pub struct ProofInfo {
     pub is_valid: bool,
 }
 
+/// a proof verified for a prover, one entry of their history
+#[cw_serde]
+pub struct Verification {
+    pub issuer: Addr,
+    pub circuit_id: String,
+    // hex sha256 of the verification key the proof was checked against
+    pub vkey_hash: String,
+    pub height: u64,
+}
+
+/// a reward for the earliest committed valid proof, held by the contract until it is claimed
+#[cw_serde]
+pub struct Bounty {
+    pub reward: Coin,
+    // block height after which the issuer can reclaim the reward
+    pub deadline: u64,
+    // the earliest committed valid proof so far, paid once no earlier commitment can be revealed
+    pub winner: Option<ProofClaim>,
+}
+
+#[cw_serde]
+pub struct Commitment {
+    pub hash: Vec<u8>,
+    pub height: u64,
+}
+
+/// the prover credited for a statement, and when they committed to their proof of it
+#[cw_serde]
+pub struct ProofClaim {
+    pub prover: Addr,
+    pub committed_at: u64,
+}
+
 pub const CONFIG: Item<Config> = Item::new("config");
-// issuer -> [ porver -> proofInfo ]
-pub const PROVERINFO: Map<&Addr, ProofInfo> = Map::new("prover_info");
-pub const PROVERLIST: Map<(&Addr, &Addr), ProofInfo> = Map::new("prover_list");
-pub const ZKEYS: Map<&Addr, VkeyStr> = Map::new("vkeys");
\ No newline at end of file
+// issuer -> [ circuit id -> [ porver -> proofInfo ] ]
+pub const PROVERLIST: Map<(&Addr, &str, &Addr), ProofInfo> = Map::new("circuit_provers");
+// prover -> [ issuer -> [ circuit id ] ], the circuits listing a prover in PROVERLIST
+pub const SOLVED: Map<(&Addr, &Addr, &str), Empty> = Map::new("solved");
+// prover -> [ sequence -> verification ], only ever appended to
+pub const PROVER_HISTORY: Map<(&Addr, u64), Verification> = Map::new("prover_history");
+// prover -> sequence of their next verification
+pub const PROVER_SEQUENCE: Map<&Addr, u64> = Map::new("prover_sequence");
+// issuer -> [ circuit id -> zkeys ]
+pub const ZKEYS: Map<(&Addr, &str), ZkeysStr> = Map::new("circuits");
+// issuer -> [ circuit id -> [ prover -> commitment ] ]
+pub const COMMITMENTS: Map<(&Addr, &str, &Addr), Commitment> = Map::new("commitments");
+// issuer -> [ circuit id -> [ statement hash -> claim ] ]
+pub const PROOF_CLAIMS: Map<(&Addr, &str, &[u8]), ProofClaim> = Map::new("proof_claims");
+// issuer -> [ circuit id -> [ nullifier -> hash of the statement that spent it ] ]
+pub const NULLIFIERS: Map<(&Addr, &str, &[u8]), Vec<u8>> = Map::new("nullifiers");
+// issuer -> [ circuit id -> bounty ]
+pub const BOUNTIES: Map<(&Addr, &str), Bounty> = Map::new("bounties");
+// denom -> amount held for open bounties
+pub const BOUNTY_ESCROW: Map<&str, Uint128> = Map::new("bounty_escrow");
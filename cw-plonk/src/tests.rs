@@ -1,18 +1,37 @@
 #[cfg(test)]
 mod test_module {
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{coin, coins, from_binary, Coin, Deps, DepsMut};
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_dependencies_with_balance, mock_env, mock_info,
+    };
+    use cosmwasm_std::{
+        attr, coin, coins, from_json, to_json_binary, Addr, BankMsg, Binary, Coin, Deps, DepsMut,
+        Response, StdError, SubMsg, Uint128, WasmMsg,
+    };
+    use cw2::{get_contract_version, set_contract_version, CONTRACT};
+    use cw20::{Cw20Coin, Cw20ExecuteMsg, Cw20ReceiveMsg};
 
-    use crate::contract::{execute, instantiate, query};
+    use crate::commitment::{proof_commitment, vkey_hash};
+    use crate::contract::{execute, instantiate, migrate, query};
     use crate::error::ContractError;
-    use crate::msg::{ExecuteMsg, InstantiateMsg, QueryMsg, ZkeysResponse, ProofResponse};
-    use crate::state::Config;
-    use pairing_ce::bn256::Bn256;
-    use bellman_ce::plonk::better_cs::cs::{PlonkConstraintSystemParams, PlonkCsWidth4WithNextStepParams};
+    use crate::migrations::v0_1;
+    use crate::msg::{
+        BountyResponse, CircuitRef, CircuitResponse, CircuitsResponse, CommitmentResponse,
+        ExecuteMsg, FeeBalanceResponse, InstantiateMsg, IssuersResponse, MigrateMsg,
+        NullifierResponse, ProofBinaryResponse, ProofResponse, ProverHistoryResponse,
+        ProversResponse, QueryMsg, SenderSignalResponse, SolvedCircuitsResponse,
+        VerificationResponse, VerifyProofResponse, VerifyingKeySource, ZkeysBinaryResponse,
+        ZkeysResponse, DEFAULT_CIRCUIT, PROOF_SYSTEM,
+    };
+    use crate::signal_helpers::sender_public_signal;
+    use crate::snarkjs::{SnarkjsProof, SnarkjsVerifyingKey};
+    use crate::state::{
+        CircuitMetadata, CircuitStatus, Config, FeeMode, PlonkParams, ProofStr, UnrelatedDenoms,
+        PROVERLIST, PROVER_HISTORY, PROVER_SEQUENCE, SOLVED, ZKEYS,
+    };
 
     fn assert_config_state(deps: Deps, expected: Config) {
         let res = query(deps, mock_env(), QueryMsg::Config {}).unwrap();
-        let value: Config = from_binary(&res).unwrap();
+        let value: Config = from_json(&res).unwrap();
         assert_eq!(value, expected);
     }
 
@@ -20,6 +39,11 @@ mod test_module {
         let msg = InstantiateMsg {
             set_zkeys_price: None,
             publish_proof_price: None,
+            set_zkeys_cw20_price: None,
+            publish_proof_cw20_price: None,
+            commitment_expiry: None,
+            fee_mode: None,
+            unrelated_denoms: None,
         };
 
         let info = mock_info("creator", &coins(2, "token"));
@@ -31,6 +55,11 @@ mod test_module {
         let msg = InstantiateMsg {
             set_zkeys_price: Some(zkeys_price),
             publish_proof_price: Some(proof_price),
+            set_zkeys_cw20_price: None,
+            publish_proof_cw20_price: None,
+            commitment_expiry: None,
+            fee_mode: None,
+            unrelated_denoms: None,
         };
 
         let info = mock_info("creator", &coins(2, "token"));
@@ -38,6 +67,43 @@ mod test_module {
             .expect("contract successfully handles InstantiateMsg");
     }
 
+    fn mock_init_with_exact_fee(deps: DepsMut, unrelated_denoms: UnrelatedDenoms) {
+        let msg = InstantiateMsg {
+            set_zkeys_price: Some(coin(1, "token")),
+            publish_proof_price: Some(coin(1, "token")),
+            set_zkeys_cw20_price: None,
+            publish_proof_cw20_price: None,
+            commitment_expiry: None,
+            fee_mode: Some(FeeMode::Exact),
+            unrelated_denoms: Some(unrelated_denoms),
+        };
+
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps, mock_env(), info, msg)
+            .expect("contract successfully handles InstantiateMsg");
+    }
+
+    fn mock_init_with_cw20_price(deps: DepsMut) {
+        let msg = InstantiateMsg {
+            set_zkeys_price: None,
+            publish_proof_price: None,
+            set_zkeys_cw20_price: Some(Cw20Coin {
+                address: "cw20_token".to_string(),
+                amount: Uint128::new(2),
+            }),
+            publish_proof_cw20_price: Some(Cw20Coin {
+                address: "cw20_token".to_string(),
+                amount: Uint128::new(2),
+            }),
+            commitment_expiry: None,
+            fee_mode: Some(FeeMode::Exact),
+            unrelated_denoms: None,
+        };
+
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps, mock_env(), info, msg)
+            .expect("contract successfully handles InstantiateMsg");
+    }
 
     #[test]
     fn proper_init_no_fees() {
@@ -47,13 +113,18 @@ mod test_module {
         assert_config_state(
             deps.as_ref(),
             Config {
+                owner: Addr::unchecked("creator"),
                 zkeys_price: None,
                 proof_price: None,
+                zkeys_cw20_price: None,
+                proof_cw20_price: None,
+                fee_mode: FeeMode::Sufficient,
+                unrelated_denoms: UnrelatedDenoms::Refund,
+                commitment_expiry: 100,
             },
         );
     }
 
-
     #[test]
     fn proper_init_with_fees() {
         let mut deps = mock_dependencies();
@@ -63,8 +134,14 @@ mod test_module {
         assert_config_state(
             deps.as_ref(),
             Config {
+                owner: Addr::unchecked("creator"),
                 zkeys_price: Some(coin(3, "token")),
                 proof_price: Some(coin(4, "token")),
+                zkeys_cw20_price: None,
+                proof_cw20_price: None,
+                fee_mode: FeeMode::Sufficient,
+                unrelated_denoms: UnrelatedDenoms::Refund,
+                commitment_expiry: 100,
             },
         );
     }
@@ -74,7 +151,9 @@ mod test_module {
         let mut deps = mock_dependencies();
         mock_init_with_price(deps.as_mut(), coin(2, "token"), coin(2, "token"));
         let info = mock_info("alice_key", &[]);
-        let msg = ExecuteMsg::Zkeys { 
+        let msg = ExecuteMsg::Zkeys {
+            circuit_id: None,
+            metadata: None,
             n: 3,
             num_inputs: 1,
             selector_commitments: [
@@ -102,9 +181,14 @@ mod test_module {
             g2_elements: [
                 "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c21800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa".to_string(),
                 "12740934ba9615b77b6a49b06fcce83ce90d67b1d0e2a530069e3a7306569a91116da8c89a0d090f3d8644ada33a5f1c8013ba7204aeca62d66d931b99afe6e725222d9816e5f86b4a7dedd00d04acc5c979c18bd22b834ea8c6d07c0ba441db076441042e77b6309644b56251f059cf14befc72ac8a6157d30924e58dc4c172".to_string()
-              ].to_vec()
+              ].to_vec(),
+            params: None,
+            bind_sender: None,
+            nullifier_index: None,
+            bounty: None,
+            bounty_deadline: None,
         };
-        let res = execute::<Bn256, PlonkCsWidth4WithNextStepParams>(deps.as_mut(), mock_env(), info, msg);
+        let res = execute(deps.as_mut(), mock_env(), info, msg);
         match res {
             Ok(_) => panic!("set zkeys should fail with insufficient fees"),
             Err(ContractError::InsufficientFundsSend {}) => {}
@@ -112,11 +196,202 @@ mod test_module {
         }
     }
 
-
     fn mock_alice_set_zkeys(deps: DepsMut, sent: &[Coin]) {
         // alice can register an available name
         let info = mock_info("alice_key", sent);
-        let msg = ExecuteMsg::Zkeys { 
+        let msg = ExecuteMsg::Zkeys {
+            circuit_id: None,
+            metadata: None,
+            n: 3,
+            num_inputs: 1,
+            selector_commitments: [
+                "09b3a8742e323fbb6b7e858287af59c6ff997667de6f10136356774a5e93fe872fd1ef45f38c9c0814183b2ca7eba4e2d5d5d7f871bb1a89e96217df74c9833d".to_string(),
+                "40000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000".to_string(),
+                "2b63946d2ccf8529ae7ba5324902e7ee834b19e8dd666b3533850c2ed36d7dfe2738c050f06ba19dd919bbb1e55c408ceadc583bbd190f10f54a9b79bb2b03da".to_string(),
+                "40000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000".to_string(),
+                "2b63946d2ccf8529ae7ba5324902e7ee834b19e8dd666b3533850c2ed36d7dfe092b8e21f0c5fe8bdf368a049c2517d0aca51255ab58bb7c46d5f09d1d51f96d".to_string(),
+                "40000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000".to_string()
+              ].to_vec(),
+            next_step_selector_commitments: [
+                "40000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000".to_string()
+              ].to_vec(),
+            permutation_commitments: [
+                "1516cf1540873838abfa4e7501f523c2c56ce33a33f2cefc2da70cb30795bd610182f97175b9b3bc30e175307758894dd2938fcf556669f9820f1c1d2089f379".to_string(),
+                "19c5d1b7df1125f24646c8e7ac90689c6cd593703e7cd4d7918e3d7c9c2f220a0daf49f7dfa3f8d741df0efcef1f171c961b47f8b28d46a2af00be85c8b6e713".to_string(),
+                "14ab37e299bdf4502f0034f4ee42281108e1ae6eab93e1c435e1aa68beb5153622def6b03001792d9fd384af642febcc6cc821726783d359ebfe6b30c9c0915a".to_string(),
+                "1f8dbc422d4aabab7112ee68c336bbe2bbe095d6d7fa6e5a7f1292ea7487412f0beb14b6bfb14c16a44f4e37d98e401cb38c6221b9f4ccebc52ec8088c44a2a8".to_string()
+              ].to_vec(),
+            non_residues: [
+                "0000000000000000000000000000000000000000000000000000000000000005".to_string(),
+                "0000000000000000000000000000000000000000000000000000000000000007".to_string(),
+                "000000000000000000000000000000000000000000000000000000000000000a".to_string()
+              ].to_vec(),
+            g2_elements: [
+                "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c21800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa".to_string(),
+                "12740934ba9615b77b6a49b06fcce83ce90d67b1d0e2a530069e3a7306569a91116da8c89a0d090f3d8644ada33a5f1c8013ba7204aeca62d66d931b99afe6e725222d9816e5f86b4a7dedd00d04acc5c979c18bd22b834ea8c6d07c0ba441db076441042e77b6309644b56251f059cf14befc72ac8a6157d30924e58dc4c172".to_string()
+              ].to_vec(),
+            params: None,
+            bind_sender: None,
+            nullifier_index: None,
+            bounty: None,
+            bounty_deadline: None,
+        };
+
+        let _res =
+            execute(deps, mock_env(), info, msg).expect("contract handles set zkeys parameters");
+    }
+
+    fn mock_alice_set_zkeys_with_cw20(
+        deps: DepsMut,
+        token: &str,
+        amount: u128,
+    ) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::Zkeys {
+            circuit_id: None,
+            metadata: None,
+            n: 3,
+            num_inputs: 1,
+            selector_commitments: [
+                "09b3a8742e323fbb6b7e858287af59c6ff997667de6f10136356774a5e93fe872fd1ef45f38c9c0814183b2ca7eba4e2d5d5d7f871bb1a89e96217df74c9833d".to_string(),
+                "40000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000".to_string(),
+                "2b63946d2ccf8529ae7ba5324902e7ee834b19e8dd666b3533850c2ed36d7dfe2738c050f06ba19dd919bbb1e55c408ceadc583bbd190f10f54a9b79bb2b03da".to_string(),
+                "40000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000".to_string(),
+                "2b63946d2ccf8529ae7ba5324902e7ee834b19e8dd666b3533850c2ed36d7dfe092b8e21f0c5fe8bdf368a049c2517d0aca51255ab58bb7c46d5f09d1d51f96d".to_string(),
+                "40000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000".to_string()
+              ].to_vec(),
+            next_step_selector_commitments: [
+                "40000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000".to_string()
+              ].to_vec(),
+            permutation_commitments: [
+                "1516cf1540873838abfa4e7501f523c2c56ce33a33f2cefc2da70cb30795bd610182f97175b9b3bc30e175307758894dd2938fcf556669f9820f1c1d2089f379".to_string(),
+                "19c5d1b7df1125f24646c8e7ac90689c6cd593703e7cd4d7918e3d7c9c2f220a0daf49f7dfa3f8d741df0efcef1f171c961b47f8b28d46a2af00be85c8b6e713".to_string(),
+                "14ab37e299bdf4502f0034f4ee42281108e1ae6eab93e1c435e1aa68beb5153622def6b03001792d9fd384af642febcc6cc821726783d359ebfe6b30c9c0915a".to_string(),
+                "1f8dbc422d4aabab7112ee68c336bbe2bbe095d6d7fa6e5a7f1292ea7487412f0beb14b6bfb14c16a44f4e37d98e401cb38c6221b9f4ccebc52ec8088c44a2a8".to_string()
+              ].to_vec(),
+            non_residues: [
+                "0000000000000000000000000000000000000000000000000000000000000005".to_string(),
+                "0000000000000000000000000000000000000000000000000000000000000007".to_string(),
+                "000000000000000000000000000000000000000000000000000000000000000a".to_string()
+              ].to_vec(),
+            g2_elements: [
+                "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c21800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa".to_string(),
+                "12740934ba9615b77b6a49b06fcce83ce90d67b1d0e2a530069e3a7306569a91116da8c89a0d090f3d8644ada33a5f1c8013ba7204aeca62d66d931b99afe6e725222d9816e5f86b4a7dedd00d04acc5c979c18bd22b834ea8c6d07c0ba441db076441042e77b6309644b56251f059cf14befc72ac8a6157d30924e58dc4c172".to_string()
+              ].to_vec(),
+            params: None,
+            bind_sender: None,
+            nullifier_index: None,
+            bounty: None,
+            bounty_deadline: None,
+        };
+
+        let wrapper = Cw20ReceiveMsg {
+            sender: "alice_key".to_string(),
+            amount: Uint128::new(amount),
+            msg: to_json_binary(&msg).unwrap(),
+        };
+
+        execute(
+            deps,
+            mock_env(),
+            mock_info(token, &[]),
+            ExecuteMsg::Receive(wrapper),
+        )
+    }
+
+    fn mock_alice_set_zkeys_with_bounty(deps: DepsMut, sent: &[Coin]) {
+        // alice pays 10 token to whoever solves it first
+        let info = mock_info("alice_key", sent);
+        let msg = ExecuteMsg::Zkeys {
+            circuit_id: None,
+            metadata: None,
+            n: 3,
+            num_inputs: 1,
+            selector_commitments: [
+                "09b3a8742e323fbb6b7e858287af59c6ff997667de6f10136356774a5e93fe872fd1ef45f38c9c0814183b2ca7eba4e2d5d5d7f871bb1a89e96217df74c9833d".to_string(),
+                "40000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000".to_string(),
+                "2b63946d2ccf8529ae7ba5324902e7ee834b19e8dd666b3533850c2ed36d7dfe2738c050f06ba19dd919bbb1e55c408ceadc583bbd190f10f54a9b79bb2b03da".to_string(),
+                "40000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000".to_string(),
+                "2b63946d2ccf8529ae7ba5324902e7ee834b19e8dd666b3533850c2ed36d7dfe092b8e21f0c5fe8bdf368a049c2517d0aca51255ab58bb7c46d5f09d1d51f96d".to_string(),
+                "40000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000".to_string()
+              ].to_vec(),
+            next_step_selector_commitments: [
+                "40000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000".to_string()
+              ].to_vec(),
+            permutation_commitments: [
+                "1516cf1540873838abfa4e7501f523c2c56ce33a33f2cefc2da70cb30795bd610182f97175b9b3bc30e175307758894dd2938fcf556669f9820f1c1d2089f379".to_string(),
+                "19c5d1b7df1125f24646c8e7ac90689c6cd593703e7cd4d7918e3d7c9c2f220a0daf49f7dfa3f8d741df0efcef1f171c961b47f8b28d46a2af00be85c8b6e713".to_string(),
+                "14ab37e299bdf4502f0034f4ee42281108e1ae6eab93e1c435e1aa68beb5153622def6b03001792d9fd384af642febcc6cc821726783d359ebfe6b30c9c0915a".to_string(),
+                "1f8dbc422d4aabab7112ee68c336bbe2bbe095d6d7fa6e5a7f1292ea7487412f0beb14b6bfb14c16a44f4e37d98e401cb38c6221b9f4ccebc52ec8088c44a2a8".to_string()
+              ].to_vec(),
+            non_residues: [
+                "0000000000000000000000000000000000000000000000000000000000000005".to_string(),
+                "0000000000000000000000000000000000000000000000000000000000000007".to_string(),
+                "000000000000000000000000000000000000000000000000000000000000000a".to_string()
+              ].to_vec(),
+            g2_elements: [
+                "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c21800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa".to_string(),
+                "12740934ba9615b77b6a49b06fcce83ce90d67b1d0e2a530069e3a7306569a91116da8c89a0d090f3d8644ada33a5f1c8013ba7204aeca62d66d931b99afe6e725222d9816e5f86b4a7dedd00d04acc5c979c18bd22b834ea8c6d07c0ba441db076441042e77b6309644b56251f059cf14befc72ac8a6157d30924e58dc4c172".to_string()
+              ].to_vec(),
+            params: None,
+            bind_sender: None,
+            nullifier_index: None,
+            bounty: Some(coin(10, "token")),
+            bounty_deadline: Some(mock_env().block.height + 10),
+        };
+
+        let _res =
+            execute(deps, mock_env(), info, msg).expect("contract handles set zkeys parameters");
+    }
+    fn mock_alice_set_zkeys_with_nullifier(deps: DepsMut, sent: &[Coin]) {
+        // the only public input doubles as the nullifier
+        let info = mock_info("alice_key", sent);
+        let msg = ExecuteMsg::Zkeys {
+            circuit_id: None,
+            metadata: None,
+            n: 3,
+            num_inputs: 1,
+            selector_commitments: [
+                "09b3a8742e323fbb6b7e858287af59c6ff997667de6f10136356774a5e93fe872fd1ef45f38c9c0814183b2ca7eba4e2d5d5d7f871bb1a89e96217df74c9833d".to_string(),
+                "40000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000".to_string(),
+                "2b63946d2ccf8529ae7ba5324902e7ee834b19e8dd666b3533850c2ed36d7dfe2738c050f06ba19dd919bbb1e55c408ceadc583bbd190f10f54a9b79bb2b03da".to_string(),
+                "40000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000".to_string(),
+                "2b63946d2ccf8529ae7ba5324902e7ee834b19e8dd666b3533850c2ed36d7dfe092b8e21f0c5fe8bdf368a049c2517d0aca51255ab58bb7c46d5f09d1d51f96d".to_string(),
+                "40000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000".to_string()
+              ].to_vec(),
+            next_step_selector_commitments: [
+                "40000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000".to_string()
+              ].to_vec(),
+            permutation_commitments: [
+                "1516cf1540873838abfa4e7501f523c2c56ce33a33f2cefc2da70cb30795bd610182f97175b9b3bc30e175307758894dd2938fcf556669f9820f1c1d2089f379".to_string(),
+                "19c5d1b7df1125f24646c8e7ac90689c6cd593703e7cd4d7918e3d7c9c2f220a0daf49f7dfa3f8d741df0efcef1f171c961b47f8b28d46a2af00be85c8b6e713".to_string(),
+                "14ab37e299bdf4502f0034f4ee42281108e1ae6eab93e1c435e1aa68beb5153622def6b03001792d9fd384af642febcc6cc821726783d359ebfe6b30c9c0915a".to_string(),
+                "1f8dbc422d4aabab7112ee68c336bbe2bbe095d6d7fa6e5a7f1292ea7487412f0beb14b6bfb14c16a44f4e37d98e401cb38c6221b9f4ccebc52ec8088c44a2a8".to_string()
+              ].to_vec(),
+            non_residues: [
+                "0000000000000000000000000000000000000000000000000000000000000005".to_string(),
+                "0000000000000000000000000000000000000000000000000000000000000007".to_string(),
+                "000000000000000000000000000000000000000000000000000000000000000a".to_string()
+              ].to_vec(),
+            g2_elements: [
+                "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c21800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa".to_string(),
+                "12740934ba9615b77b6a49b06fcce83ce90d67b1d0e2a530069e3a7306569a91116da8c89a0d090f3d8644ada33a5f1c8013ba7204aeca62d66d931b99afe6e725222d9816e5f86b4a7dedd00d04acc5c979c18bd22b834ea8c6d07c0ba441db076441042e77b6309644b56251f059cf14befc72ac8a6157d30924e58dc4c172".to_string()
+              ].to_vec(),
+            params: None,
+            bind_sender: None,
+            nullifier_index: Some(0),
+            bounty: None,
+            bounty_deadline: None,
+        };
+
+        let _res =
+            execute(deps, mock_env(), info, msg).expect("contract handles set zkeys parameters");
+    }
+
+    fn mock_alice_set_zkeys_with_invalid_nullifier_index(deps: DepsMut, sent: &[Coin]) {
+        let info = mock_info("alice_key", sent);
+        let msg = ExecuteMsg::Zkeys {
+            circuit_id: None,
+            metadata: None,
             n: 3,
             num_inputs: 1,
             selector_commitments: [
@@ -144,17 +419,26 @@ mod test_module {
             g2_elements: [
                 "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c21800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa".to_string(),
                 "12740934ba9615b77b6a49b06fcce83ce90d67b1d0e2a530069e3a7306569a91116da8c89a0d090f3d8644ada33a5f1c8013ba7204aeca62d66d931b99afe6e725222d9816e5f86b4a7dedd00d04acc5c979c18bd22b834ea8c6d07c0ba441db076441042e77b6309644b56251f059cf14befc72ac8a6157d30924e58dc4c172".to_string()
-              ].to_vec()
+              ].to_vec(),
+            params: None,
+            bind_sender: None,
+            nullifier_index: Some(1),
+            bounty: None,
+            bounty_deadline: None,
         };
 
-        let _res = execute::<Bn256, PlonkCsWidth4WithNextStepParams>(deps, mock_env(), info, msg)
-            .expect("contract handles set zkeys parameters");
+        assert_eq!(
+            execute(deps, mock_env(), info, msg),
+            Err(ContractError::InvalidNullifierIndex {})
+        );
     }
 
     fn mock_alice_set_invalid_zkeys(deps: DepsMut, sent: &[Coin]) {
         // alice can register an available name
         let info = mock_info("alice_key", sent);
-        let msg = ExecuteMsg::Zkeys { 
+        let msg = ExecuteMsg::Zkeys {
+            circuit_id: None,
+            metadata: None,
             //NOTE: invalid vk_alpha1
             n: 3,
             num_inputs: 1,
@@ -184,16 +468,29 @@ mod test_module {
             g2_elements: [
                 "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c21800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa".to_string(),
                 "12740934ba9615b77b6a49b06fcce83ce90d67b1d0e2a530069e3a7306569a91116da8c89a0d090f3d8644ada33a5f1c8013ba7204aeca62d66d931b99afe6e725222d9816e5f86b4a7dedd00d04acc5c979c18bd22b834ea8c6d07c0ba441db076441042e77b6309644b56251f059cf14befc72ac8a6157d30924e58dc4c172".to_string()
-              ].to_vec()
+              ].to_vec(),
+            params: None,
+            bind_sender: None,
+            nullifier_index: None,
+            bounty: None,
+            bounty_deadline: None,
         };
 
-        assert_eq!(execute::<Bn256, PlonkCsWidth4WithNextStepParams>(deps, mock_env(), info, msg), Err(ContractError::ErrorVerificationKey{}));
+        assert_eq!(
+            execute(deps, mock_env(), info, msg),
+            Err(ContractError::ErrorVerificationKey {
+                field: "selector_commitments[0]".to_string(),
+                reason: "expected 64 bytes, got 2".to_string(),
+            })
+        );
     }
 
     fn mock_alice_set_zkeys_with_different_public_signal(deps: DepsMut, sent: &[Coin]) {
         // alice can register an available name
         let info = mock_info("alice_key", sent);
-        let msg = ExecuteMsg::Zkeys { 
+        let msg = ExecuteMsg::Zkeys {
+            circuit_id: None,
+            metadata: None,
             n: 3,
             num_inputs: 1,
             selector_commitments: [
@@ -221,17 +518,62 @@ mod test_module {
             g2_elements: [
                 "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c21800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa".to_string(),
                 "12740934ba9615b77b6a49b06fcce83ce90d67b1d0e2a530069e3a7306569a91116da8c89a0d090f3d8644ada33a5f1c8013ba7204aeca62d66d931b99afe6e725222d9816e5f86b4a7dedd00d04acc5c979c18bd22b834ea8c6d07c0ba441db076441042e77b6309644b56251f059cf14befc72ac8a6157d30924e58dc4c172".to_string()
-              ].to_vec()
+              ].to_vec(),
+            params: None,
+            bind_sender: None,
+            nullifier_index: None,
+            bounty: None,
+            bounty_deadline: None,
         };
 
-        let _res = execute::<Bn256, PlonkCsWidth4WithNextStepParams>(deps, mock_env(), info, msg)
-            .expect("contract handles set zkeys parameters");
+        let _res =
+            execute(deps, mock_env(), info, msg).expect("contract handles set zkeys parameters");
     }
 
-    fn mock_bob_publish_proof_to_verify(deps: DepsMut, sent: &[Coin]) {
-        let info = mock_info("bob_key", sent);
-        let msg = ExecuteMsg::Proof { 
-            difficuty_issuer: "alice_key".to_string(), 
+    /// bob's proof as a `VerifyProof` query against `key`
+    fn mock_verify_bob_proof_query(key: VerifyingKeySource) -> QueryMsg {
+        match mock_bob_proof_msg() {
+            ExecuteMsg::Proof {
+                num_inputs,
+                n,
+                input_values,
+                wire_commitments,
+                grand_product_commitment,
+                quotient_poly_commitments,
+                wire_values_at_z,
+                wire_values_at_z_omega,
+                grand_product_at_z_omega,
+                quotient_polynomial_at_z,
+                linearization_polynomial_at_z,
+                permutation_polynomials_at_z,
+                opening_at_z_proof,
+                opening_at_z_omega_proof,
+                ..
+            } => QueryMsg::VerifyProof {
+                key,
+                num_inputs,
+                n,
+                input_values,
+                wire_commitments,
+                grand_product_commitment,
+                quotient_poly_commitments,
+                wire_values_at_z,
+                wire_values_at_z_omega,
+                grand_product_at_z_omega,
+                quotient_polynomial_at_z,
+                linearization_polynomial_at_z,
+                permutation_polynomials_at_z,
+                opening_at_z_proof,
+                opening_at_z_omega_proof,
+            },
+            _ => unreachable!(),
+        }
+    }
+
+    fn mock_bob_proof_msg() -> ExecuteMsg {
+        ExecuteMsg::Proof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
             num_inputs: 1,
             n: 3,
             input_values: [
@@ -269,16 +611,41 @@ mod test_module {
               ].to_vec(),
             opening_at_z_proof: "1d99eae30fa0e2d2a330647c3245eceb92dcc0fae92308f6003cd628e6694e682ff1a84ee025d6609fa9b16a29ff7e5bf5ea08932bd6813b989a084d28cb72c4".to_string(),
             opening_at_z_omega_proof: "2bcf1e082d97cbc88e318001fc8588be7efb1d60624d8917c9babdde02469a402bb78b2bb7e8e76635d6e34674f6255b05558b8a2de52ff00535cec6bccca8a5".to_string()
+        }
+    }
+
+    fn mock_bob_publish_proof_to_verify(deps: DepsMut, sent: &[Coin]) {
+        let info = mock_info("bob_key", sent);
+        let msg = mock_bob_proof_msg();
+
+        let _res =
+            execute(deps, mock_env(), info, msg).expect("contract handles verify proof failed");
+    }
+
+    fn mock_bob_publish_proof_with_cw20(
+        deps: DepsMut,
+        token: &str,
+        amount: u128,
+    ) -> Result<Response, ContractError> {
+        let wrapper = Cw20ReceiveMsg {
+            sender: "bob_key".to_string(),
+            amount: Uint128::new(amount),
+            msg: to_json_binary(&mock_bob_proof_msg()).unwrap(),
         };
 
-        let _res = execute::<Bn256, PlonkCsWidth4WithNextStepParams>(deps, mock_env(), info, msg)
-            .expect("contract handles verify proof failed");
+        execute(
+            deps,
+            mock_env(),
+            mock_info(token, &[]),
+            ExecuteMsg::Receive(wrapper),
+        )
     }
 
     fn mock_bob_publish_proof_to_verify_with_different_public_signal(deps: DepsMut, sent: &[Coin]) {
         let info = mock_info("bob_key", sent);
-        let msg = ExecuteMsg::Proof { 
-            difficuty_issuer: "alice_key".to_string(), 
+        let msg = ExecuteMsg::Proof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
             num_inputs: 1,
             n: 3,
             input_values: [
@@ -317,13 +684,17 @@ mod test_module {
             opening_at_z_proof: "1d99eae30fa0e2d2a330647c3245eceb92dcc0fae92308f6003cd628e6694e682ff1a84ee025d6609fa9b16a29ff7e5bf5ea08932bd6813b989a084d28cb72c4".to_string(),
             opening_at_z_omega_proof: "2bcf1e082d97cbc88e318001fc8588be7efb1d60624d8917c9babdde02469a402bb78b2bb7e8e76635d6e34674f6255b05558b8a2de52ff00535cec6bccca8a5".to_string()
         };
-        assert_eq!(execute::<Bn256, PlonkCsWidth4WithNextStepParams>(deps, mock_env(), info, msg), Err(ContractError::InvalidProof {}));
+        assert_eq!(
+            execute(deps, mock_env(), info, msg),
+            Err(ContractError::InvalidProof {})
+        );
     }
 
     fn mock_bob_publish_error_hex_format_proof_to_verify(deps: DepsMut, sent: &[Coin]) {
         let info = mock_info("bob_key", sent);
-        let msg = ExecuteMsg::Proof { 
-            difficuty_issuer: "alice_key".to_string(), 
+        let msg = ExecuteMsg::Proof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
             num_inputs: 1,
             n: 3,
             input_values: [
@@ -333,7 +704,7 @@ mod test_module {
               "1c7ade6b7b63a79bbdf4380ead5793e175e904e8a659019474f25263121599f70a5f7d13d1d549c1b5fa4695d519dce3567aa336d0ced40ce00e6dd9ad77d8c5".to_string(),
               "238e7e9105d66b54ebcf23b1ac4a8cc9179850a8bb3d9ca0495e5420ff6318901a82fb4a34ed559e3f25de9b0ca7903a7e0692bee2705809e59ed50355e89920".to_string(),
               "238e7e9105d66b54ebcf23b1ac4a8cc9179850a8bb3d9ca0495e5420ff6318901a82fb4a34ed559e3f25de9b0ca7903a7e0692bee2705809e59ed50355e89920".to_string(),
-              "40000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000".to_string()
+              "0x40000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000".to_string()
             ].to_vec(),
             grand_product_commitment: "2ebc09f9ada0ba725ea8d6c06d4c49e156578fc34388aff597a2ef4c94113eab10c2861217b6f698e334359465f85d23bcd3467a72ccefa92fcdef5cf63bb774".to_string(),
             quotient_poly_commitments: [
@@ -363,14 +734,17 @@ mod test_module {
             opening_at_z_omega_proof: "2bcf1e082d97cbc88e318001fc8588be7efb1d60624d8917c9babdde02469a402bb78b2bb7e8e76635d6e34674f6255b05558b8a2de52ff00535cec6bccca8a5".to_string()
         };
 
-        assert_eq!(execute::<Bn256, PlonkCsWidth4WithNextStepParams>(deps, mock_env(), info, msg), Err(ContractError::HexDecodingError {}));
+        assert!(matches!(
+            execute(deps, mock_env(), info, msg),
+            Err(ContractError::HexDecodingError { .. })
+        ));
     }
 
-
     fn mock_bob_publish_invalid_proof_to_verify(deps: DepsMut, sent: &[Coin]) {
         let info = mock_info("bob_key", sent);
-        let msg = ExecuteMsg::Proof { 
-            difficuty_issuer: "alice_key".to_string(), 
+        let msg = ExecuteMsg::Proof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
             num_inputs: 1,
             n: 3,
             input_values: [
@@ -411,31 +785,125 @@ mod test_module {
             opening_at_z_omega_proof: "2bcf1e082d97cbc88e318001fc8588be7efb1d60624d8917c9babdde02469a402bb78b2bb7e8e76635d6e34674f6255b05558b8a2de52ff00535cec6bccca8a5".to_string()
         };
 
-        assert_eq!(execute::<Bn256, PlonkCsWidth4WithNextStepParams>(deps, mock_env(), info, msg), Err(ContractError::InvalidProof {}));
+        assert_eq!(
+            execute(deps, mock_env(), info, msg),
+            Err(ContractError::InvalidProof {})
+        );
+    }
+
+    // bob's proof as a reveal, with the matching commitment
+    fn mock_bob_reveal_proof_msg(salt: &str) -> (String, ExecuteMsg) {
+        match mock_bob_proof_msg() {
+            ExecuteMsg::Proof {
+                difficuty_issuer,
+                circuit_id,
+                num_inputs,
+                n,
+                input_values,
+                wire_commitments,
+                grand_product_commitment,
+                quotient_poly_commitments,
+                wire_values_at_z,
+                wire_values_at_z_omega,
+                grand_product_at_z_omega,
+                quotient_polynomial_at_z,
+                linearization_polynomial_at_z,
+                permutation_polynomials_at_z,
+                opening_at_z_proof,
+                opening_at_z_omega_proof,
+            } => {
+                let proof_str = ProofStr {
+                    num_inputs,
+                    n,
+                    input_values: input_values.clone(),
+                    wire_commitments: wire_commitments
+                        .iter()
+                        .map(|x| hex::decode(x).unwrap())
+                        .collect(),
+                    grand_product_commitment: hex::decode(&grand_product_commitment).unwrap(),
+                    quotient_poly_commitments: quotient_poly_commitments
+                        .iter()
+                        .map(|x| hex::decode(x).unwrap())
+                        .collect(),
+                    wire_values_at_z: wire_values_at_z.clone(),
+                    wire_values_at_z_omega: wire_values_at_z_omega.clone(),
+                    grand_product_at_z_omega: grand_product_at_z_omega.clone(),
+                    quotient_polynomial_at_z: quotient_polynomial_at_z.clone(),
+                    linearization_polynomial_at_z: linearization_polynomial_at_z.clone(),
+                    permutation_polynomials_at_z: permutation_polynomials_at_z.clone(),
+                    opening_at_z_proof: hex::decode(&opening_at_z_proof).unwrap(),
+                    opening_at_z_omega_proof: hex::decode(&opening_at_z_omega_proof).unwrap(),
+                };
+                let commitment = proof_commitment(&proof_str, &Addr::unchecked("bob_key"), salt);
+
+                let msg = ExecuteMsg::RevealProof {
+                    difficuty_issuer,
+                    circuit_id,
+                    num_inputs,
+                    n,
+                    input_values,
+                    wire_commitments,
+                    grand_product_commitment,
+                    quotient_poly_commitments,
+                    wire_values_at_z,
+                    wire_values_at_z_omega,
+                    grand_product_at_z_omega,
+                    quotient_polynomial_at_z,
+                    linearization_polynomial_at_z,
+                    permutation_polynomials_at_z,
+                    opening_at_z_proof,
+                    opening_at_z_omega_proof,
+                    salt: salt.to_string(),
+                };
+                (hex::encode(commitment), msg)
+            }
+            _ => unreachable!(),
+        }
     }
 
+    fn mock_bob_commit_proof(deps: DepsMut, commitment: String) {
+        let mut env = mock_env();
+        env.block.height = 100;
+        let info = mock_info("bob_key", &[]);
+        let msg = ExecuteMsg::CommitProof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            commitment,
+        };
+
+        let _res = execute(deps, env, info, msg).expect("contract handles commit proof");
+    }
 
     fn query_zkeys(deps: Deps) {
         let res = query(
-            deps, 
+            deps,
             mock_env(),
-            QueryMsg::IssuerZkeys { address: "alice_key".to_string() }
-        ).unwrap();
+            QueryMsg::IssuerZkeys {
+                address: "alice_key".to_string(),
+                circuit_id: None,
+            },
+        )
+        .unwrap();
 
         // get response
-        let value: ZkeysResponse = from_binary(&res).unwrap();
-        // println!("zkey is :{:?}", value);
+        let value: ZkeysResponse = from_json(&res).unwrap();
+        assert_eq!((value.n, value.num_inputs), (3, 1));
     }
-    
+
     fn query_verification_result(deps: Deps) {
         let res = query(
             deps,
             mock_env(),
-            QueryMsg::ProofResult { issuer_address: "alice_key".to_string(), prover_address: "bob_key".to_string() }
-        ).unwrap();
+            QueryMsg::ProofResult {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                prover_address: "bob_key".to_string(),
+            },
+        )
+        .unwrap();
 
-        let value: ProofResponse = from_binary(&res).unwrap();
-        // print!("proof info is: {:?}", value);
+        let value: ProofResponse = from_json(&res).unwrap();
+        assert!(value.is_valid);
     }
 
     #[test]
@@ -447,7 +915,6 @@ mod test_module {
         query_zkeys(deps.as_ref());
     }
 
-
     #[test]
     fn verify_proof_and_query_works_with_no_price() {
         let mut deps = mock_dependencies();
@@ -472,6 +939,15 @@ mod test_module {
         query_verification_result(deps.as_ref());
     }
 
+    #[test]
+    fn verify_proof_failed_with_error_hex_format() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys(deps.as_mut(), &[]);
+
+        mock_bob_publish_error_hex_format_proof_to_verify(deps.as_mut(), &[]);
+    }
+
     #[test]
     fn verify_proof_and_query_works_with_price_with_different_public() {
         let mut deps = mock_dependencies();
@@ -480,7 +956,10 @@ mod test_module {
         mock_alice_set_zkeys_with_different_public_signal(deps.as_mut(), &[coin(2, "token")]);
 
         // verify the proof of bob
-        mock_bob_publish_proof_to_verify_with_different_public_signal(deps.as_mut(), &[coin(2, "token")]);
+        mock_bob_publish_proof_to_verify_with_different_public_signal(
+            deps.as_mut(),
+            &[coin(2, "token")],
+        );
     }
 
     #[test]
@@ -491,7 +970,6 @@ mod test_module {
         mock_alice_set_invalid_zkeys(deps.as_mut(), &[coin(2, "token")]);
     }
 
-
     #[test]
     fn verify_proof_and_query_failed_with_invalid_proof() {
         let mut deps = mock_dependencies();
@@ -503,4 +981,2332 @@ mod test_module {
         mock_bob_publish_invalid_proof_to_verify(deps.as_mut(), &[coin(2, "token")]);
     }
 
-}
\ No newline at end of file
+    #[test]
+    fn commit_and_reveal_proof_works() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys(deps.as_mut(), &[]);
+
+        let (commitment, msg) = mock_bob_reveal_proof_msg("salt");
+        mock_bob_commit_proof(deps.as_mut(), commitment.clone());
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Commitment {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                prover_address: "bob_key".to_string(),
+            },
+        )
+        .unwrap();
+        let value: CommitmentResponse = from_json(&res).unwrap();
+        assert_eq!(value.commitment, commitment);
+        assert_eq!(value.expires_at, 200);
+
+        let mut env = mock_env();
+        env.block.height = 101;
+        let _res = execute(deps.as_mut(), env, mock_info("bob_key", &[]), msg)
+            .expect("contract handles reveal proof");
+        query_verification_result(deps.as_ref());
+    }
+
+    #[test]
+    fn reveal_proof_failed_with_wrong_salt() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys(deps.as_mut(), &[]);
+
+        let (commitment, _) = mock_bob_reveal_proof_msg("salt");
+        mock_bob_commit_proof(deps.as_mut(), commitment);
+
+        let (_, msg) = mock_bob_reveal_proof_msg("pepper");
+        let mut env = mock_env();
+        env.block.height = 101;
+        assert_eq!(
+            execute(deps.as_mut(), env, mock_info("bob_key", &[]), msg),
+            Err(ContractError::CommitmentMismatch {})
+        );
+    }
+
+    #[test]
+    fn verify_proof_with_nullifier_and_query_works() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys_with_nullifier(deps.as_mut(), &[]);
+
+        mock_bob_publish_proof_to_verify(deps.as_mut(), &[]);
+        query_verification_result(deps.as_ref());
+
+        for (nullifier, spent) in [
+            (
+                "0000000000000000000000000000000000000000000000000000000000000021",
+                true,
+            ),
+            (
+                "0000000000000000000000000000000000000000000000000000000000000022",
+                false,
+            ),
+        ] {
+            let res = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Nullifier {
+                    issuer_address: "alice_key".to_string(),
+                    circuit_id: None,
+                    nullifier: nullifier.to_string(),
+                },
+            )
+            .unwrap();
+            let value: NullifierResponse = from_json(&res).unwrap();
+            assert_eq!(value.spent, spent);
+        }
+
+        // an unreadable nullifier is reported as the public signal it stands for
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Nullifier {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                nullifier: "zz".to_string(),
+            },
+        );
+        match res {
+            Err(StdError::GenericErr { msg, .. }) => assert_eq!(
+                msg,
+                ContractError::ParsePulbicSignalError { index: 0 }.to_string()
+            ),
+            res => panic!("Unexpected result: {:?}", res),
+        }
+    }
+
+    #[test]
+    fn verify_proof_failed_with_spent_nullifier() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys_with_nullifier(deps.as_mut(), &[]);
+        mock_bob_publish_proof_to_verify(deps.as_mut(), &[]);
+
+        // neither bob again nor a copy from carol spends the nullifier twice
+        for prover in ["bob_key", "carol_key"] {
+            assert_eq!(
+                execute(
+                    deps.as_mut(),
+                    mock_env(),
+                    mock_info(prover, &[]),
+                    mock_bob_proof_msg()
+                ),
+                Err(ContractError::NullifierAlreadySpent {})
+            );
+        }
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProverHistory {
+                prover_address: "bob_key".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: ProverHistoryResponse = from_json(&res).unwrap();
+        assert_eq!(value.verifications.len(), 1);
+    }
+
+    #[test]
+    fn reveal_proof_takes_spent_nullifier_from_copy() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys_with_nullifier(deps.as_mut(), &[]);
+
+        let (commitment, reveal) = mock_bob_reveal_proof_msg("salt");
+        mock_bob_commit_proof(deps.as_mut(), commitment);
+        // carol copies bob's proof before he reveals it
+        let mut env = mock_env();
+        env.block.height = 101;
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("carol_key", &[]),
+            mock_bob_proof_msg(),
+        )
+        .expect("contract handles verify proof");
+
+        env.block.height = 102;
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("bob_key", &[]),
+            reveal,
+        )
+        .expect("contract handles reveal proof");
+        query_verification_result(deps.as_ref());
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProofResult {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                prover_address: "carol_key".to_string(),
+            },
+        );
+        assert!(res.is_err());
+
+        // the nullifier moves once, it isn't spent again by either of them
+        env.block.height = 103;
+        for prover in ["bob_key", "carol_key"] {
+            assert_eq!(
+                execute(
+                    deps.as_mut(),
+                    env.clone(),
+                    mock_info(prover, &[]),
+                    mock_bob_proof_msg()
+                ),
+                Err(ContractError::NullifierAlreadySpent {})
+            );
+        }
+    }
+
+    #[test]
+    fn set_zkeys_failed_with_invalid_nullifier_index() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys_with_invalid_nullifier_index(deps.as_mut(), &[]);
+    }
+
+    #[test]
+    fn withdraw_fees_works() {
+        let mut deps = mock_dependencies_with_balance(&coins(5, "token"));
+        mock_init_with_price(deps.as_mut(), coin(1, "token"), coin(1, "token"));
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::FeeBalance {}).unwrap();
+        let value: FeeBalanceResponse = from_json(&res).unwrap();
+        assert_eq!(value.balance, coins(5, "token"));
+
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::Withdraw {
+            amount: coins(3, "token"),
+            recipient: "treasury".to_string(),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "treasury".to_string(),
+                amount: coins(3, "token")
+            })]
+        );
+    }
+
+    #[test]
+    fn withdraw_fees_failed_with_unauthorized() {
+        let mut deps = mock_dependencies_with_balance(&coins(5, "token"));
+        mock_init_with_price(deps.as_mut(), coin(1, "token"), coin(1, "token"));
+
+        let info = mock_info("alice_key", &[]);
+        let msg = ExecuteMsg::Withdraw {
+            amount: coins(3, "token"),
+            recipient: "alice_key".to_string(),
+        };
+        assert_eq!(
+            execute(deps.as_mut(), mock_env(), info, msg),
+            Err(ContractError::Unauthorized {})
+        );
+    }
+
+    #[test]
+    fn withdraw_fees_failed_with_empty_amount() {
+        let mut deps = mock_dependencies_with_balance(&coins(5, "token"));
+        mock_init_with_price(deps.as_mut(), coin(1, "token"), coin(1, "token"));
+
+        let info = mock_info("creator", &[]);
+        for amount in [
+            vec![],
+            coins(0, "token"),
+            vec![coin(1, "token"), coin(0, "stake")],
+        ] {
+            let msg = ExecuteMsg::Withdraw {
+                amount,
+                recipient: "treasury".to_string(),
+            };
+            assert_eq!(
+                execute(deps.as_mut(), mock_env(), info.clone(), msg),
+                Err(ContractError::EmptyWithdrawal {})
+            );
+        }
+        let msg = ExecuteMsg::WithdrawCw20 {
+            token: "cw20_token".to_string(),
+            amount: Uint128::zero(),
+            recipient: "treasury".to_string(),
+        };
+        assert_eq!(
+            execute(deps.as_mut(), mock_env(), info, msg),
+            Err(ContractError::EmptyWithdrawal {})
+        );
+    }
+
+    #[test]
+    fn verify_proof_refunds_overpayment_with_exact_fee() {
+        let mut deps = mock_dependencies();
+        mock_init_with_exact_fee(deps.as_mut(), UnrelatedDenoms::Refund);
+        mock_alice_set_zkeys(deps.as_mut(), &[coin(1, "token")]);
+
+        let msg = mock_bob_proof_msg();
+        let info = mock_info("bob_key", &[coin(3, "token"), coin(2, "smokin")]);
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "bob_key".to_string(),
+                amount: vec![coin(2, "token"), coin(2, "smokin")]
+            })]
+        );
+    }
+
+    #[test]
+    fn verify_proof_failed_with_unrelated_denom() {
+        let mut deps = mock_dependencies();
+        mock_init_with_exact_fee(deps.as_mut(), UnrelatedDenoms::Reject);
+        mock_alice_set_zkeys(deps.as_mut(), &[coin(1, "token")]);
+
+        let msg = mock_bob_proof_msg();
+        let info = mock_info("bob_key", &[coin(1, "token"), coin(2, "smokin")]);
+        assert_eq!(
+            execute(deps.as_mut(), mock_env(), info, msg),
+            Err(ContractError::UnrelatedDenom {
+                denom: "smokin".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn verify_proof_collects_bounty() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys_with_bounty(deps.as_mut(), &coins(10, "token"));
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Bounty {
+                address: "alice_key".to_string(),
+                circuit_id: None,
+            },
+        )
+        .unwrap();
+        let value: BountyResponse = from_json(&res).unwrap();
+        assert_eq!(value.reward, coin(10, "token"));
+        // the escrowed reward is not a fee
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::FeeBalance {}).unwrap();
+        let value: FeeBalanceResponse = from_json(&res).unwrap();
+        assert_eq!(value.balance, vec![]);
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::Withdraw {
+            amount: coins(10, "token"),
+            recipient: "creator".to_string(),
+        };
+        assert_eq!(
+            execute(deps.as_mut(), mock_env(), info, msg),
+            Err(ContractError::InsufficientFees {
+                denom: "token".to_string()
+            })
+        );
+
+        // the proof wins the bounty, which is paid once no earlier commitment can be revealed
+        let height = mock_env().block.height;
+        let info = mock_info("bob_key", &[]);
+        let res = execute(deps.as_mut(), mock_env(), info, mock_bob_proof_msg()).unwrap();
+        assert_eq!(res.messages, vec![]);
+        assert_eq!(res.events[1].ty, "bounty_won");
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Bounty {
+                address: "alice_key".to_string(),
+                circuit_id: None,
+            },
+        )
+        .unwrap();
+        let value: BountyResponse = from_json(&res).unwrap();
+        assert_eq!(value.winner, Some(Addr::unchecked("bob_key")));
+        assert_eq!(value.claimable_at, Some(height + 100));
+
+        let claim = || ExecuteMsg::ClaimBounty {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+        };
+        let mut env = mock_env();
+        env.block.height = height + 99;
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("carol_key", &[]),
+                claim()
+            ),
+            Err(ContractError::BountyNotClaimable {
+                claimable_at: height + 100
+            })
+        );
+        // the issuer can't take back a bounty that was won
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("alice_key", &[]),
+                ExecuteMsg::ReclaimBounty { circuit_id: None }
+            ),
+            Err(ContractError::BountyAlreadyWon {})
+        );
+        // anyone can pay it out to the winner
+        env.block.height = height + 100;
+        let res = execute(deps.as_mut(), env, mock_info("carol_key", &[]), claim()).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "bob_key".to_string(),
+                amount: coins(10, "token")
+            })]
+        );
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Bounty {
+                address: "alice_key".to_string(),
+                circuit_id: None,
+            },
+        );
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn bounty_goes_to_the_earlier_commitment() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys_with_bounty(deps.as_mut(), &coins(10, "token"));
+
+        // carol front-runs bob's reveal with a copy of his proof
+        let (commitment, reveal) = mock_bob_reveal_proof_msg("salt");
+        mock_bob_commit_proof(deps.as_mut(), commitment);
+        let mut env = mock_env();
+        env.block.height = 101;
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("carol_key", &[]),
+            mock_bob_proof_msg(),
+        )
+        .unwrap();
+        assert_eq!(res.messages, vec![]);
+        let claim = || ExecuteMsg::ClaimBounty {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+        };
+        env.block.height = 102;
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("carol_key", &[]),
+                claim()
+            ),
+            Err(ContractError::BountyNotClaimable { claimable_at: 201 })
+        );
+
+        // bob reveals later, his commitment is earlier
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("bob_key", &[]),
+            reveal,
+        )
+        .expect("contract handles reveal proof");
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Bounty {
+                address: "alice_key".to_string(),
+                circuit_id: None,
+            },
+        )
+        .unwrap();
+        let value: BountyResponse = from_json(&res).unwrap();
+        assert_eq!(value.winner, Some(Addr::unchecked("bob_key")));
+        assert_eq!(value.claimable_at, Some(200));
+
+        env.block.height = 200;
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("carol_key", &[]),
+            claim(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "bob_key".to_string(),
+                amount: coins(10, "token")
+            })]
+        );
+        assert_eq!(
+            execute(deps.as_mut(), env, mock_info("carol_key", &[]), claim()),
+            Err(ContractError::NoBounty {})
+        );
+    }
+
+    #[test]
+    fn reclaim_bounty_works() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys_with_bounty(deps.as_mut(), &coins(10, "token"));
+
+        let info = mock_info("alice_key", &[]);
+        let height = mock_env().block.height;
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                info.clone(),
+                ExecuteMsg::ReclaimBounty { circuit_id: None }
+            ),
+            Err(ContractError::BountyNotExpired {
+                reclaimable_at: height + 111
+            })
+        );
+        // a commitment made by the deadline may still be revealed
+        let mut env = mock_env();
+        env.block.height = height + 110;
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                info.clone(),
+                ExecuteMsg::ReclaimBounty { circuit_id: None }
+            ),
+            Err(ContractError::BountyNotExpired {
+                reclaimable_at: height + 111
+            })
+        );
+
+        env.block.height = height + 111;
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            info.clone(),
+            ExecuteMsg::ReclaimBounty { circuit_id: None },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "alice_key".to_string(),
+                amount: coins(10, "token")
+            })]
+        );
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                env,
+                info,
+                ExecuteMsg::ReclaimBounty { circuit_id: None }
+            ),
+            Err(ContractError::NoBounty {})
+        );
+    }
+
+    #[test]
+    fn reclaim_bounty_waits_for_a_commitment_made_by_the_deadline() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys_with_bounty(deps.as_mut(), &coins(10, "token"));
+
+        // bob commits on the last block of the bounty
+        let deadline = mock_env().block.height + 10;
+        let (commitment, reveal) = mock_bob_reveal_proof_msg("salt");
+        let mut env = mock_env();
+        env.block.height = deadline;
+        let commit = ExecuteMsg::CommitProof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            commitment,
+        };
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("bob_key", &[]),
+            commit,
+        )
+        .unwrap();
+        env.block.height = deadline + 1;
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("alice_key", &[]),
+                ExecuteMsg::ReclaimBounty { circuit_id: None }
+            ),
+            Err(ContractError::BountyNotExpired {
+                reclaimable_at: deadline + 101
+            })
+        );
+
+        // his reveal after the deadline still wins the reward
+        env.block.height = deadline + 2;
+        let res = execute(
+            deps.as_mut(),
+            env.clone(),
+            mock_info("bob_key", &[]),
+            reveal,
+        )
+        .unwrap();
+        assert_eq!(res.events[1].ty, "bounty_won");
+        env.block.height = deadline + 101;
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                env.clone(),
+                mock_info("alice_key", &[]),
+                ExecuteMsg::ReclaimBounty { circuit_id: None }
+            ),
+            Err(ContractError::BountyAlreadyWon {})
+        );
+        let claim = ExecuteMsg::ClaimBounty {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+        };
+        let res = execute(deps.as_mut(), env, mock_info("carol_key", &[]), claim).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "bob_key".to_string(),
+                amount: coins(10, "token")
+            })]
+        );
+    }
+    #[test]
+    fn verify_proof_and_query_works_with_cw20_price() {
+        let mut deps = mock_dependencies();
+        mock_init_with_cw20_price(deps.as_mut());
+
+        let res = mock_alice_set_zkeys_with_cw20(deps.as_mut(), "cw20_token", 2).unwrap();
+        assert_eq!(res.messages, vec![]);
+        // the price is only payable in the cw20 token
+        let info = mock_info("bob_key", &[]);
+        assert_eq!(
+            execute(deps.as_mut(), mock_env(), info, mock_bob_proof_msg()),
+            Err(ContractError::InsufficientFundsSend {})
+        );
+
+        // the overpayment goes back with the exact fee mode
+        let res = mock_bob_publish_proof_with_cw20(deps.as_mut(), "cw20_token", 3).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(WasmMsg::Execute {
+                contract_addr: "cw20_token".to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "bob_key".to_string(),
+                    amount: Uint128::new(1)
+                })
+                .unwrap(),
+                funds: vec![],
+            })]
+        );
+        query_verification_result(deps.as_ref());
+    }
+
+    #[test]
+    fn receive_failed_with_unaccepted_token_or_hook() {
+        let mut deps = mock_dependencies();
+        mock_init_with_cw20_price(deps.as_mut());
+
+        assert_eq!(
+            mock_alice_set_zkeys_with_cw20(deps.as_mut(), "other_token", 2),
+            Err(ContractError::UnacceptedToken {
+                token: "other_token".to_string()
+            })
+        );
+        assert_eq!(
+            mock_alice_set_zkeys_with_cw20(deps.as_mut(), "cw20_token", 1),
+            Err(ContractError::InsufficientFundsSend {})
+        );
+
+        let wrapper = Cw20ReceiveMsg {
+            sender: "alice_key".to_string(),
+            amount: Uint128::new(2),
+            msg: to_json_binary(&ExecuteMsg::ReclaimBounty { circuit_id: None }).unwrap(),
+        };
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("cw20_token", &[]),
+                ExecuteMsg::Receive(wrapper)
+            ),
+            Err(ContractError::InvalidCw20Hook {})
+        );
+    }
+
+    #[test]
+    fn withdraw_cw20_fees_works() {
+        let mut deps = mock_dependencies();
+        mock_init_with_cw20_price(deps.as_mut());
+
+        let msg = ExecuteMsg::WithdrawCw20 {
+            token: "cw20_token".to_string(),
+            amount: Uint128::new(2),
+            recipient: "treasury".to_string(),
+        };
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("alice_key", &[]),
+                msg.clone()
+            ),
+            Err(ContractError::Unauthorized {})
+        );
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(WasmMsg::Execute {
+                contract_addr: "cw20_token".to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "treasury".to_string(),
+                    amount: Uint128::new(2)
+                })
+                .unwrap(),
+                funds: vec![],
+            })]
+        );
+    }
+    #[test]
+    fn migrate_refuses_downgrade_and_other_contract() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        assert_eq!(
+            get_contract_version(deps.as_ref().storage).unwrap().version,
+            env!("CARGO_PKG_VERSION")
+        );
+
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg { owner: None }).unwrap();
+        assert_eq!(res.messages, vec![]);
+
+        set_contract_version(deps.as_mut().storage, "crates.io:cw-plonk", "99.0.0").unwrap();
+        assert_eq!(
+            migrate(deps.as_mut(), mock_env(), MigrateMsg { owner: None }),
+            Err(ContractError::CannotDowngrade {
+                previous_version: "99.0.0".to_string(),
+                new_version: env!("CARGO_PKG_VERSION").to_string()
+            })
+        );
+
+        set_contract_version(deps.as_mut().storage, "crates.io:cw20-base", "0.1.0").unwrap();
+        assert_eq!(
+            migrate(deps.as_mut(), mock_env(), MigrateMsg { owner: None }),
+            Err(ContractError::CannotMigrate {
+                previous_contract: "crates.io:cw20-base".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn migrate_rewrites_v0_1_layouts() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys(deps.as_mut(), &[]);
+        mock_bob_publish_proof_to_verify(deps.as_mut(), &[]);
+
+        // turn the state back into what 0.1.0 wrote
+        let alice = Addr::unchecked("alice_key");
+        let bob = Addr::unchecked("bob_key");
+        let storage = deps.as_mut().storage;
+        let proof_info = PROVERLIST
+            .load(storage, (&alice, DEFAULT_CIRCUIT, &bob))
+            .unwrap();
+        PROVERLIST.remove(storage, (&alice, DEFAULT_CIRCUIT, &bob));
+        SOLVED.remove(storage, (&bob, &alice, DEFAULT_CIRCUIT));
+        v0_1::PROVERLIST
+            .save(storage, (&alice, &bob), &proof_info)
+            .unwrap();
+        PROVER_HISTORY.remove(storage, (&bob, 0));
+        PROVER_SEQUENCE.remove(storage, &bob);
+        v0_1::PROVERINFO.save(storage, &bob, &proof_info).unwrap();
+        let vkeys = ZKEYS
+            .load(storage, (&alice, DEFAULT_CIRCUIT))
+            .unwrap()
+            .vkeys;
+        ZKEYS.remove(storage, (&alice, DEFAULT_CIRCUIT));
+        v0_1::ZKEYS.save(storage, &alice, &vkeys).unwrap();
+        v0_1::CONFIG
+            .save(
+                storage,
+                &v0_1::Config {
+                    zkeys_price: None,
+                    proof_price: Some(coin(1, "token")),
+                },
+            )
+            .unwrap();
+        CONTRACT.remove(storage);
+
+        // 0.1.0 didn't have an owner
+        assert_eq!(
+            migrate(deps.as_mut(), mock_env(), MigrateMsg { owner: None }),
+            Err(ContractError::MissingOwner {})
+        );
+        migrate(
+            deps.as_mut(),
+            mock_env(),
+            MigrateMsg {
+                owner: Some("creator".to_string()),
+            },
+        )
+        .unwrap();
+        assert_eq!(
+            get_contract_version(deps.as_ref().storage).unwrap().version,
+            env!("CARGO_PKG_VERSION")
+        );
+
+        assert_config_state(
+            deps.as_ref(),
+            Config {
+                owner: Addr::unchecked("creator"),
+                zkeys_price: None,
+                proof_price: Some(coin(1, "token")),
+                zkeys_cw20_price: None,
+                proof_cw20_price: None,
+                fee_mode: FeeMode::Sufficient,
+                unrelated_denoms: UnrelatedDenoms::Refund,
+                commitment_expiry: 100,
+            },
+        );
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::IssuerZkeys {
+                address: "alice_key".to_string(),
+                circuit_id: None,
+            },
+        )
+        .unwrap();
+        let value: ZkeysResponse = from_json(&res).unwrap();
+        assert_eq!(value.nullifier_index, None);
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::SolvedCircuits {
+                prover_address: "bob_key".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: SolvedCircuitsResponse = from_json(&res).unwrap();
+        assert_eq!(
+            value.circuits,
+            vec![CircuitRef {
+                issuer: Addr::unchecked("alice_key"),
+                circuit_id: DEFAULT_CIRCUIT.to_string()
+            }]
+        );
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProverHistory {
+                prover_address: "bob_key".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: ProverHistoryResponse = from_json(&res).unwrap();
+        assert_eq!(
+            value.verifications,
+            vec![VerificationResponse {
+                sequence: 0,
+                issuer: Addr::unchecked("alice_key"),
+                circuit_id: DEFAULT_CIRCUIT.to_string(),
+                vkey_hash: hex::encode(vkey_hash(
+                    &ZKEYS
+                        .load(deps.as_ref().storage, (&alice, DEFAULT_CIRCUIT))
+                        .unwrap()
+                        .vkeys
+                )),
+                height: mock_env().block.height,
+            }]
+        );
+        assert!(!v0_1::PROVERINFO.has(deps.as_ref().storage, &Addr::unchecked("bob_key")));
+        assert!(!v0_1::ZKEYS.has(deps.as_ref().storage, &alice));
+        assert!(!v0_1::PROVERLIST.has(deps.as_ref().storage, (&alice, &bob)));
+
+        // the migrated key still verifies proofs
+        mock_bob_publish_proof_to_verify(deps.as_mut(), &coins(1, "token"));
+    }
+    #[test]
+    fn set_zkeys_and_verify_proof_emit_events() {
+        let mut deps = mock_dependencies();
+        mock_init_with_cw20_price(deps.as_mut());
+
+        let res = mock_alice_set_zkeys_with_cw20(deps.as_mut(), "cw20_token", 2).unwrap();
+        let zkeys = ZKEYS
+            .load(
+                deps.as_ref().storage,
+                (&Addr::unchecked("alice_key"), DEFAULT_CIRCUIT),
+            )
+            .unwrap();
+        let vkey_hash = hex::encode(vkey_hash(&zkeys.vkeys));
+        assert_eq!(res.events.len(), 1);
+        assert_eq!(res.events[0].ty, "zkeys_registered");
+        assert_eq!(
+            res.events[0].attributes,
+            vec![
+                attr("issuer", "alice_key"),
+                attr("circuit_id", DEFAULT_CIRCUIT),
+                attr("vkey_hash", &vkey_hash),
+                attr("proof_system", PROOF_SYSTEM),
+                attr("fee", "2cw20_token"),
+            ]
+        );
+
+        // only the price is kept with the exact fee mode
+        let res = mock_bob_publish_proof_with_cw20(deps.as_mut(), "cw20_token", 3).unwrap();
+        assert_eq!(res.events.len(), 1);
+        assert_eq!(res.events[0].ty, "proof_verified");
+        assert_eq!(
+            res.events[0].attributes,
+            vec![
+                attr("issuer", "alice_key"),
+                attr("circuit_id", DEFAULT_CIRCUIT),
+                attr("prover", "bob_key"),
+                attr("vkey_hash", &vkey_hash),
+                attr("proof_system", PROOF_SYSTEM),
+                attr("fee", "2cw20_token"),
+                attr("outcome", "valid"),
+                attr("sequence", "0"),
+            ]
+        );
+    }
+
+    #[test]
+    fn withdraw_fees_emits_event() {
+        let mut deps = mock_dependencies_with_balance(&coins(5, "token"));
+        mock_init_with_price(deps.as_mut(), coin(1, "token"), coin(1, "token"));
+
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::Withdraw {
+            amount: vec![coin(3, "token")],
+            recipient: "treasury".to_string(),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(res.events[0].ty, "fees_withdrawn");
+        assert_eq!(
+            res.events[0].attributes,
+            vec![attr("recipient", "treasury"), attr("amount", "3token")]
+        );
+    }
+    #[test]
+    fn list_issuers_and_provers_works() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys(deps.as_mut(), &[]);
+        mock_bob_publish_proof_to_verify(deps.as_mut(), &[]);
+        // carol and dave issue the same problem
+        let zkeys = ZKEYS
+            .load(
+                deps.as_ref().storage,
+                (&Addr::unchecked("alice_key"), DEFAULT_CIRCUIT),
+            )
+            .unwrap();
+        ZKEYS
+            .save(
+                deps.as_mut().storage,
+                (&Addr::unchecked("carol_key"), DEFAULT_CIRCUIT),
+                &zkeys,
+            )
+            .unwrap();
+        ZKEYS
+            .save(
+                deps.as_mut().storage,
+                (&Addr::unchecked("dave_key"), DEFAULT_CIRCUIT),
+                &zkeys,
+            )
+            .unwrap();
+        let mut msg = mock_bob_proof_msg();
+        if let ExecuteMsg::Proof {
+            difficuty_issuer, ..
+        } = &mut msg
+        {
+            *difficuty_issuer = "dave_key".to_string();
+        }
+        execute(deps.as_mut(), mock_env(), mock_info("bob_key", &[]), msg).unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Issuers {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: IssuersResponse = from_json(&res).unwrap();
+        // an issuer key is stored behind its length
+        assert_eq!(
+            value.issuers,
+            vec![
+                Addr::unchecked("dave_key"),
+                Addr::unchecked("alice_key"),
+                Addr::unchecked("carol_key")
+            ]
+        );
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Issuers {
+                start_after: Some("alice_key".to_string()),
+                limit: Some(1),
+            },
+        )
+        .unwrap();
+        let value: IssuersResponse = from_json(&res).unwrap();
+        assert_eq!(value.issuers, vec![Addr::unchecked("carol_key")]);
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Provers {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: ProversResponse = from_json(&res).unwrap();
+        assert_eq!(value.provers, vec![Addr::unchecked("bob_key")]);
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Provers {
+                issuer_address: "carol_key".to_string(),
+                circuit_id: None,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: ProversResponse = from_json(&res).unwrap();
+        assert!(value.provers.is_empty());
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::SolvedCircuits {
+                prover_address: "bob_key".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: SolvedCircuitsResponse = from_json(&res).unwrap();
+        assert_eq!(
+            value.circuits,
+            vec![
+                CircuitRef {
+                    issuer: Addr::unchecked("dave_key"),
+                    circuit_id: DEFAULT_CIRCUIT.to_string()
+                },
+                CircuitRef {
+                    issuer: Addr::unchecked("alice_key"),
+                    circuit_id: DEFAULT_CIRCUIT.to_string()
+                },
+            ]
+        );
+        let start_after = Some(("dave_key".to_string(), DEFAULT_CIRCUIT.to_string()));
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::SolvedCircuits {
+                prover_address: "bob_key".to_string(),
+                start_after,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: SolvedCircuitsResponse = from_json(&res).unwrap();
+        assert_eq!(
+            value.circuits,
+            vec![CircuitRef {
+                issuer: Addr::unchecked("alice_key"),
+                circuit_id: DEFAULT_CIRCUIT.to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn prover_history_keeps_every_verification() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys(deps.as_mut(), &[]);
+        mock_bob_publish_proof_to_verify(deps.as_mut(), &[]);
+        let zkeys = ZKEYS
+            .load(
+                deps.as_ref().storage,
+                (&Addr::unchecked("alice_key"), DEFAULT_CIRCUIT),
+            )
+            .unwrap();
+        let vkey_hash = hex::encode(vkey_hash(&zkeys.vkeys));
+        ZKEYS
+            .save(
+                deps.as_mut().storage,
+                (&Addr::unchecked("dave_key"), DEFAULT_CIRCUIT),
+                &zkeys,
+            )
+            .unwrap();
+
+        // a later proof for another issuer doesn't replace the first one
+        let mut env = mock_env();
+        env.block.height += 1;
+        let mut msg = mock_bob_proof_msg();
+        if let ExecuteMsg::Proof {
+            difficuty_issuer, ..
+        } = &mut msg
+        {
+            *difficuty_issuer = "dave_key".to_string();
+        }
+        execute(deps.as_mut(), env, mock_info("bob_key", &[]), msg).unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProverHistory {
+                prover_address: "bob_key".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: ProverHistoryResponse = from_json(&res).unwrap();
+        assert_eq!(
+            value.verifications,
+            vec![
+                VerificationResponse {
+                    sequence: 0,
+                    issuer: Addr::unchecked("alice_key"),
+                    circuit_id: DEFAULT_CIRCUIT.to_string(),
+                    vkey_hash: vkey_hash.clone(),
+                    height: mock_env().block.height,
+                },
+                VerificationResponse {
+                    sequence: 1,
+                    issuer: Addr::unchecked("dave_key"),
+                    circuit_id: DEFAULT_CIRCUIT.to_string(),
+                    vkey_hash,
+                    height: mock_env().block.height + 1,
+                },
+            ]
+        );
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProverHistory {
+                prover_address: "bob_key".to_string(),
+                start_after: None,
+                limit: Some(1),
+            },
+        )
+        .unwrap();
+        let value: ProverHistoryResponse = from_json(&res).unwrap();
+        assert_eq!(
+            value
+                .verifications
+                .iter()
+                .map(|v| v.sequence)
+                .collect::<Vec<_>>(),
+            vec![0]
+        );
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProverHistory {
+                prover_address: "bob_key".to_string(),
+                start_after: Some(0),
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: ProverHistoryResponse = from_json(&res).unwrap();
+        assert_eq!(
+            value
+                .verifications
+                .iter()
+                .map(|v| v.sequence)
+                .collect::<Vec<_>>(),
+            vec![1]
+        );
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProverHistory {
+                prover_address: "carol_key".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: ProverHistoryResponse = from_json(&res).unwrap();
+        assert!(value.verifications.is_empty());
+    }
+
+    #[test]
+    fn verify_proof_query_works() {
+        let mut deps = mock_dependencies();
+        mock_init_with_price(deps.as_mut(), coin(1, "token"), coin(1, "token"));
+        mock_alice_set_zkeys(deps.as_mut(), &[coin(1, "token")]);
+        let zkeys = ZKEYS
+            .load(
+                deps.as_ref().storage,
+                (&Addr::unchecked("alice_key"), DEFAULT_CIRCUIT),
+            )
+            .unwrap();
+        let vkey_hash = hex::encode(vkey_hash(&zkeys.vkeys));
+        let input_values = match mock_bob_proof_msg() {
+            ExecuteMsg::Proof { input_values, .. } => input_values,
+            _ => unreachable!(),
+        };
+
+        // no fee and nothing recorded
+        let registered = VerifyingKeySource::Registered {
+            issuer: "alice_key".to_string(),
+            circuit_id: None,
+            prover: None,
+        };
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            mock_verify_bob_proof_query(registered.clone()),
+        )
+        .unwrap();
+        let value: VerifyProofResponse = from_json(&res).unwrap();
+        assert_eq!(
+            value,
+            VerifyProofResponse {
+                is_valid: true,
+                vkey_hash: vkey_hash.clone(),
+                input_values,
+                nullifier_spent: None,
+                error: None,
+            }
+        );
+        let msg = QueryMsg::ProofResult {
+            issuer_address: "alice_key".to_string(),
+            circuit_id: None,
+            prover_address: "bob_key".to_string(),
+        };
+        assert!(query(deps.as_ref(), mock_env(), msg).is_err());
+
+        // a changed public input doesn't verify
+        let mut msg = mock_verify_bob_proof_query(registered);
+        if let QueryMsg::VerifyProof { input_values, .. } = &mut msg {
+            input_values[0] = "34".to_string();
+        }
+        let value: VerifyProofResponse =
+            from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert!(!value.is_valid);
+        assert!(value.error.is_some());
+
+        // an unknown issuer fails the query
+        let msg = mock_verify_bob_proof_query(VerifyingKeySource::Registered {
+            issuer: "carol_key".to_string(),
+            circuit_id: None,
+            prover: None,
+        });
+        assert!(query(deps.as_ref(), mock_env(), msg).is_err());
+
+        // the same key given inline
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::IssuerZkeys {
+                address: "alice_key".to_string(),
+                circuit_id: None,
+            },
+        )
+        .unwrap();
+        let value: ZkeysResponse = from_json(&res).unwrap();
+        let inline = VerifyingKeySource::Inline {
+            n: value.n,
+            num_inputs: value.num_inputs,
+            selector_commitments: value.selector_commitments,
+            next_step_selector_commitments: value.next_step_selector_commitments,
+            permutation_commitments: value.permutation_commitments,
+            non_residues: value.non_residues,
+            g2_elements: value.g2_elements,
+            params: Some(value.params),
+        };
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            mock_verify_bob_proof_query(inline),
+        )
+        .unwrap();
+        let value: VerifyProofResponse = from_json(&res).unwrap();
+        assert!(value.is_valid);
+        assert_eq!(value.vkey_hash, vkey_hash);
+        assert_eq!(value.nullifier_spent, None);
+    }
+
+    #[test]
+    fn verify_proof_query_reports_nullifier() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys_with_nullifier(deps.as_mut(), &[]);
+        let registered = VerifyingKeySource::Registered {
+            issuer: "alice_key".to_string(),
+            circuit_id: None,
+            prover: None,
+        };
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            mock_verify_bob_proof_query(registered.clone()),
+        )
+        .unwrap();
+        let value: VerifyProofResponse = from_json(&res).unwrap();
+        assert_eq!(value.nullifier_spent, Some(false));
+        mock_bob_publish_proof_to_verify(deps.as_mut(), &[]);
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            mock_verify_bob_proof_query(registered),
+        )
+        .unwrap();
+        let value: VerifyProofResponse = from_json(&res).unwrap();
+        assert!(value.is_valid);
+        assert_eq!(value.nullifier_spent, Some(true));
+    }
+
+    #[test]
+    fn named_circuits_are_kept_apart() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys(deps.as_mut(), &[]);
+        // alice publishes the same problem again under another name
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::IssuerZkeys {
+                address: "alice_key".to_string(),
+                circuit_id: None,
+            },
+        )
+        .unwrap();
+        let value: ZkeysResponse = from_json(&res).unwrap();
+        let set_zkeys = |circuit_id: Option<String>| ExecuteMsg::Zkeys {
+            circuit_id,
+            metadata: None,
+            n: value.n,
+            num_inputs: value.num_inputs,
+            selector_commitments: value.selector_commitments.clone(),
+            next_step_selector_commitments: value.next_step_selector_commitments.clone(),
+            permutation_commitments: value.permutation_commitments.clone(),
+            non_residues: value.non_residues.clone(),
+            g2_elements: value.g2_elements.clone(),
+            params: None,
+            bind_sender: None,
+            nullifier_index: None,
+            bounty: None,
+            bounty_deadline: None,
+        };
+        let info = mock_info("alice_key", &[]);
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            info.clone(),
+            set_zkeys(Some("square".to_string())),
+        )
+        .unwrap();
+
+        // a key is replaced until a proof is listed against it
+        execute(deps.as_mut(), mock_env(), info.clone(), set_zkeys(None)).unwrap();
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                info,
+                set_zkeys(Some("".to_string()))
+            ),
+            Err(ContractError::InvalidCircuitId {})
+        );
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Circuits {
+                issuer_address: "alice_key".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: CircuitsResponse = from_json(&res).unwrap();
+        assert_eq!(
+            value.circuit_ids,
+            vec![DEFAULT_CIRCUIT.to_string(), "square".to_string()]
+        );
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Circuits {
+                issuer_address: "alice_key".to_string(),
+                start_after: Some(DEFAULT_CIRCUIT.to_string()),
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: CircuitsResponse = from_json(&res).unwrap();
+        assert_eq!(value.circuit_ids, vec!["square".to_string()]);
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Issuers {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: IssuersResponse = from_json(&res).unwrap();
+        assert_eq!(value.issuers, vec![Addr::unchecked("alice_key")]);
+        // the next page starts past every circuit of alice
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Issuers {
+                start_after: Some("alice_key".to_string()),
+                limit: Some(1),
+            },
+        )
+        .unwrap();
+        let value: IssuersResponse = from_json(&res).unwrap();
+        assert_eq!(value.issuers, Vec::<Addr>::new());
+
+        // a proof only counts for the circuit it names
+        let proof = |name: &str| {
+            let mut msg = mock_bob_proof_msg();
+            if let ExecuteMsg::Proof { circuit_id, .. } = &mut msg {
+                *circuit_id = Some(name.to_string());
+            }
+            msg
+        };
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("bob_key", &[]),
+                proof("cube")
+            ),
+            Err(ContractError::NonPublishDifficulty {
+                difficuty_issuer: "alice_key".to_string()
+            })
+        );
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bob_key", &[]),
+            proof("square"),
+        )
+        .unwrap();
+        assert!(res.events[0]
+            .attributes
+            .contains(&attr("circuit_id", "square")));
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("alice_key", &[]),
+                set_zkeys(Some("square".to_string()))
+            ),
+            Err(ContractError::CircuitAlreadyRegistered {
+                circuit_id: "square".to_string()
+            })
+        );
+
+        let msg = QueryMsg::ProofResult {
+            issuer_address: "alice_key".to_string(),
+            circuit_id: Some("square".to_string()),
+            prover_address: "bob_key".to_string(),
+        };
+        let value: ProofResponse =
+            from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert!(value.is_valid);
+        let msg = QueryMsg::ProofResult {
+            issuer_address: "alice_key".to_string(),
+            circuit_id: None,
+            prover_address: "bob_key".to_string(),
+        };
+        assert!(query(deps.as_ref(), mock_env(), msg).is_err());
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Provers {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: ProversResponse = from_json(&res).unwrap();
+        assert!(value.provers.is_empty());
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::SolvedCircuits {
+                prover_address: "bob_key".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: SolvedCircuitsResponse = from_json(&res).unwrap();
+        assert_eq!(
+            value.circuits,
+            vec![CircuitRef {
+                issuer: Addr::unchecked("alice_key"),
+                circuit_id: "square".to_string()
+            }]
+        );
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProverHistory {
+                prover_address: "bob_key".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: ProverHistoryResponse = from_json(&res).unwrap();
+        assert_eq!(value.verifications[0].circuit_id, "square");
+
+        // the default circuit is still open to the same proof
+        mock_bob_publish_proof_to_verify(deps.as_mut(), &[]);
+        query_verification_result(deps.as_ref());
+    }
+
+    #[test]
+    fn circuit_registry_tracks_metadata_and_status() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys(deps.as_mut(), &[]);
+        let zkeys = ZKEYS
+            .load(
+                deps.as_ref().storage,
+                (&Addr::unchecked("alice_key"), DEFAULT_CIRCUIT),
+            )
+            .unwrap();
+
+        // a key registered without metadata
+        let msg = QueryMsg::Circuit {
+            issuer_address: "alice_key".to_string(),
+            circuit_id: None,
+        };
+        let value: CircuitResponse =
+            from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(
+            value,
+            CircuitResponse {
+                issuer: Addr::unchecked("alice_key"),
+                circuit_id: DEFAULT_CIRCUIT.to_string(),
+                vkey_hash: hex::encode(vkey_hash(&zkeys.vkeys)),
+                proof_system: PROOF_SYSTEM.to_string(),
+                metadata: CircuitMetadata::default(),
+                status: CircuitStatus::Active,
+            }
+        );
+
+        // the same problem described under another name
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::IssuerZkeys {
+                address: "alice_key".to_string(),
+                circuit_id: None,
+            },
+        )
+        .unwrap();
+        let value: ZkeysResponse = from_json(&res).unwrap();
+        let set_zkeys = |metadata: CircuitMetadata| ExecuteMsg::Zkeys {
+            circuit_id: Some("square".to_string()),
+            metadata: Some(metadata),
+            n: value.n,
+            num_inputs: value.num_inputs,
+            selector_commitments: value.selector_commitments.clone(),
+            next_step_selector_commitments: value.next_step_selector_commitments.clone(),
+            permutation_commitments: value.permutation_commitments.clone(),
+            non_residues: value.non_residues.clone(),
+            g2_elements: value.g2_elements.clone(),
+            params: None,
+            bind_sender: None,
+            nullifier_index: None,
+            bounty: None,
+            bounty_deadline: None,
+        };
+        let metadata = CircuitMetadata {
+            name: "square".to_string(),
+            description: "knows a square root of the public input".to_string(),
+            source_uri: "https://github.com/alice/circuits/blob/main/square.circom".to_string(),
+            artifact_hash: hex::encode([7u8; 32]),
+        };
+        let invalid = CircuitMetadata {
+            artifact_hash: "07".to_string(),
+            ..metadata.clone()
+        };
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("alice_key", &[]),
+                set_zkeys(invalid)
+            ),
+            Err(ContractError::InvalidCircuitMetadata {
+                field: "artifact_hash".to_string()
+            })
+        );
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice_key", &[]),
+            set_zkeys(metadata.clone()),
+        )
+        .unwrap();
+        let msg = QueryMsg::Circuit {
+            issuer_address: "alice_key".to_string(),
+            circuit_id: Some("square".to_string()),
+        };
+        let value: CircuitResponse =
+            from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert_eq!(value.metadata, metadata);
+        assert_eq!(value.status, CircuitStatus::Active);
+
+        // a deprecated circuit still accepts proofs
+        let msg = ExecuteMsg::SetCircuitStatus {
+            circuit_id: None,
+            status: CircuitStatus::Deprecated,
+        };
+        let res = execute(deps.as_mut(), mock_env(), mock_info("alice_key", &[]), msg).unwrap();
+        assert_eq!(res.events[0].ty, "circuit_status_changed");
+        assert_eq!(
+            res.events[0].attributes,
+            vec![
+                attr("issuer", "alice_key"),
+                attr("circuit_id", DEFAULT_CIRCUIT),
+                attr("status", "deprecated")
+            ]
+        );
+        mock_bob_publish_proof_to_verify(deps.as_mut(), &[]);
+        let (commitment, reveal) = mock_bob_reveal_proof_msg("salt");
+        mock_bob_commit_proof(deps.as_mut(), commitment);
+
+        // a retired one refuses them, what was proven stays
+        let msg = ExecuteMsg::SetCircuitStatus {
+            circuit_id: None,
+            status: CircuitStatus::Retired,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("alice_key", &[]), msg).unwrap();
+        let mut env = mock_env();
+        env.block.height = 101;
+        let retired = || ContractError::CircuitRetired {
+            circuit_id: DEFAULT_CIRCUIT.to_string(),
+        };
+        assert_eq!(
+            execute(deps.as_mut(), env, mock_info("bob_key", &[]), reveal),
+            Err(retired())
+        );
+        let msg = ExecuteMsg::CommitProof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            commitment: hex::encode([0u8; 32]),
+        };
+        assert_eq!(
+            execute(deps.as_mut(), mock_env(), mock_info("carol_key", &[]), msg),
+            Err(retired())
+        );
+        let msg = mock_verify_bob_proof_query(VerifyingKeySource::Registered {
+            issuer: "alice_key".to_string(),
+            circuit_id: None,
+            prover: None,
+        });
+        let value: VerifyProofResponse =
+            from_json(query(deps.as_ref(), mock_env(), msg).unwrap()).unwrap();
+        assert!(!value.is_valid);
+        assert_eq!(value.error, Some(retired().to_string()));
+        query_verification_result(deps.as_ref());
+
+        // for good, and only by its issuer
+        let msg = ExecuteMsg::SetCircuitStatus {
+            circuit_id: None,
+            status: CircuitStatus::Active,
+        };
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("alice_key", &[]),
+                msg.clone()
+            ),
+            Err(retired())
+        );
+        assert_eq!(
+            execute(deps.as_mut(), mock_env(), mock_info("bob_key", &[]), msg),
+            Err(ContractError::NonPublishDifficulty {
+                difficuty_issuer: "bob_key".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn set_zkeys_reads_the_key_in_its_params() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys(deps.as_mut(), &[]);
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::IssuerZkeys {
+                address: "alice_key".to_string(),
+                circuit_id: None,
+            },
+        )
+        .unwrap();
+        let value: ZkeysResponse = from_json(&res).unwrap();
+        assert_eq!(value.params, PlonkParams::Width4WithNextStep);
+
+        // the same commitments don't make a width 3 key
+        let msg = ExecuteMsg::Zkeys {
+            circuit_id: Some("width3".to_string()),
+            metadata: None,
+            n: value.n,
+            num_inputs: value.num_inputs,
+            selector_commitments: value.selector_commitments,
+            next_step_selector_commitments: value.next_step_selector_commitments,
+            permutation_commitments: value.permutation_commitments,
+            non_residues: value.non_residues,
+            g2_elements: value.g2_elements,
+            params: Some(PlonkParams::Width3WithNextStep),
+            bind_sender: None,
+            nullifier_index: None,
+            bounty: None,
+            bounty_deadline: None,
+        };
+        assert_eq!(
+            execute(deps.as_mut(), mock_env(), mock_info("alice_key", &[]), msg),
+            Err(ContractError::ErrorVerificationKey {
+                field: "selector_commitments".to_string(),
+                reason: "expected 5 elements, got 6".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn verify_binary_proof_works() {
+        let zkeys = |deps: Deps| -> ZkeysResponse {
+            let res = query(
+                deps,
+                mock_env(),
+                QueryMsg::IssuerZkeys {
+                    address: "alice_key".to_string(),
+                    circuit_id: None,
+                },
+            )
+            .unwrap();
+            from_json(&res).unwrap()
+        };
+        let binary = |point: &str| Binary::from(hex::decode(point).unwrap());
+        let binaries =
+            |points: &[String]| points.iter().map(|point| binary(point)).collect::<Vec<_>>();
+        let mut hex_deps = mock_dependencies();
+        mock_init_no_price(hex_deps.as_mut());
+        mock_alice_set_zkeys(hex_deps.as_mut(), &[]);
+        let expected = zkeys(hex_deps.as_ref());
+
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        let msg = ExecuteMsg::ZkeysBinary {
+            circuit_id: None,
+            metadata: None,
+            n: expected.n,
+            num_inputs: expected.num_inputs,
+            selector_commitments: binaries(&expected.selector_commitments),
+            next_step_selector_commitments: binaries(&expected.next_step_selector_commitments),
+            permutation_commitments: binaries(&expected.permutation_commitments),
+            non_residues: expected.non_residues.clone(),
+            g2_elements: binaries(&expected.g2_elements),
+            params: None,
+            bind_sender: None,
+            nullifier_index: None,
+            bounty: None,
+            bounty_deadline: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("alice_key", &[]), msg).unwrap();
+        // the same key for either query
+        assert_eq!(zkeys(deps.as_ref()), expected);
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::IssuerZkeysBinary {
+                address: "alice_key".to_string(),
+                circuit_id: None,
+            },
+        )
+        .unwrap();
+        let value: ZkeysBinaryResponse = from_json(&res).unwrap();
+        assert_eq!(
+            value.selector_commitments,
+            binaries(&expected.selector_commitments)
+        );
+        assert_eq!(value.g2_elements, binaries(&expected.g2_elements));
+
+        let hex_proof = match mock_bob_proof_msg() {
+            ExecuteMsg::Proof {
+                num_inputs,
+                n,
+                input_values,
+                wire_commitments,
+                grand_product_commitment,
+                quotient_poly_commitments,
+                wire_values_at_z,
+                wire_values_at_z_omega,
+                grand_product_at_z_omega,
+                quotient_polynomial_at_z,
+                linearization_polynomial_at_z,
+                permutation_polynomials_at_z,
+                opening_at_z_proof,
+                opening_at_z_omega_proof,
+                ..
+            } => ProofResponse {
+                num_inputs,
+                n,
+                input_values,
+                wire_commitments,
+                grand_product_commitment,
+                quotient_poly_commitments,
+                wire_values_at_z,
+                wire_values_at_z_omega,
+                grand_product_at_z_omega,
+                quotient_polynomial_at_z,
+                linearization_polynomial_at_z,
+                permutation_polynomials_at_z,
+                opening_at_z_proof,
+                opening_at_z_omega_proof,
+                is_valid: true,
+            },
+            _ => unreachable!(),
+        };
+        let msg = ExecuteMsg::ProofBinary {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            num_inputs: hex_proof.num_inputs,
+            n: hex_proof.n,
+            input_values: hex_proof.input_values.clone(),
+            wire_commitments: binaries(&hex_proof.wire_commitments),
+            grand_product_commitment: binary(&hex_proof.grand_product_commitment),
+            quotient_poly_commitments: binaries(&hex_proof.quotient_poly_commitments),
+            wire_values_at_z: hex_proof.wire_values_at_z.clone(),
+            wire_values_at_z_omega: hex_proof.wire_values_at_z_omega.clone(),
+            grand_product_at_z_omega: hex_proof.grand_product_at_z_omega.clone(),
+            quotient_polynomial_at_z: hex_proof.quotient_polynomial_at_z.clone(),
+            linearization_polynomial_at_z: hex_proof.linearization_polynomial_at_z.clone(),
+            permutation_polynomials_at_z: hex_proof.permutation_polynomials_at_z.clone(),
+            opening_at_z_proof: binary(&hex_proof.opening_at_z_proof),
+            opening_at_z_omega_proof: binary(&hex_proof.opening_at_z_omega_proof),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("bob_key", &[]), msg).unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProofResultBinary {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                prover_address: "bob_key".to_string(),
+            },
+        )
+        .unwrap();
+        let value: ProofBinaryResponse = from_json(&res).unwrap();
+        assert_eq!(
+            value.wire_commitments,
+            binaries(&hex_proof.wire_commitments)
+        );
+        assert_eq!(
+            value.opening_at_z_omega_proof,
+            binary(&hex_proof.opening_at_z_omega_proof)
+        );
+        assert!(value.is_valid);
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProofResult {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                prover_address: "bob_key".to_string(),
+            },
+        )
+        .unwrap();
+        let value: ProofResponse = from_json(&res).unwrap();
+        assert_eq!(value, hex_proof);
+    }
+
+    // every commitment at infinity and X_2 the generator of G2
+    fn mock_snarkjs_vkey() -> SnarkjsVerifyingKey {
+        let infinity = || vec!["0".to_string(), "1".to_string(), "0".to_string()];
+        let fq2 = |c0: &str, c1: &str| vec![c0.to_string(), c1.to_string()];
+        SnarkjsVerifyingKey {
+            protocol: "plonk".to_string(),
+            curve: "bn128".to_string(),
+            n_public: 1,
+            power: 3,
+            k1: "2".to_string(),
+            k2: "3".to_string(),
+            qm: infinity(),
+            ql: infinity(),
+            qr: infinity(),
+            qo: infinity(),
+            qc: infinity(),
+            s1: infinity(),
+            s2: infinity(),
+            s3: infinity(),
+            x_2: vec![
+                fq2(
+                    "10857046999023057135944570762232829481370756359578518086990519993285655852781",
+                    "11559732032986387107991004021392285783925812861821192530917403151452391805634",
+                ),
+                fq2(
+                    "8495653923123431417604973247489272438418190587263600148770280649306958101930",
+                    "4082367875863433681332203403145435568316851327593401208105741076214120093531",
+                ),
+                fq2("1", "0"),
+            ],
+        }
+    }
+
+    fn mock_snarkjs_proof() -> SnarkjsProof {
+        let infinity = || vec!["0".to_string(), "1".to_string(), "0".to_string()];
+        SnarkjsProof {
+            a: infinity(),
+            b: infinity(),
+            c: infinity(),
+            z: infinity(),
+            t1: infinity(),
+            t2: infinity(),
+            t3: infinity(),
+            wxi: infinity(),
+            wxiw: infinity(),
+            eval_a: "0".to_string(),
+            eval_b: "0".to_string(),
+            eval_c: "0".to_string(),
+            eval_s1: "0".to_string(),
+            eval_s2: "0".to_string(),
+            eval_zw: "0".to_string(),
+            protocol: "plonk".to_string(),
+            curve: "bn128".to_string(),
+        }
+    }
+
+    #[test]
+    fn snarkjs_keys_and_proofs_are_checked() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        let zkeys = |vkey| ExecuteMsg::SnarkjsZkeys {
+            circuit_id: Some("snarkjs".to_string()),
+            metadata: None,
+            vkey,
+            bind_sender: None,
+            nullifier_index: None,
+            bounty: None,
+            bounty_deadline: None,
+        };
+
+        let mut vkey = mock_snarkjs_vkey();
+        vkey.protocol = "groth16".to_string();
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("alice_key", &[]),
+                zkeys(vkey)
+            ),
+            Err(ContractError::SnarkjsMismatch {
+                field: "protocol".to_string(),
+                expected: "plonk".to_string(),
+                found: "groth16".to_string()
+            })
+        );
+        let mut vkey = mock_snarkjs_vkey();
+        vkey.qm[1] = "0x".to_string();
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("alice_key", &[]),
+                zkeys(vkey)
+            ),
+            Err(ContractError::InvalidSnarkjsCoordinate {
+                field: "Qm".to_string()
+            })
+        );
+        let mut vkey = mock_snarkjs_vkey();
+        vkey.power = 29;
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("alice_key", &[]),
+                zkeys(vkey)
+            ),
+            Err(ContractError::ErrorVerificationKey {
+                field: "power".to_string(),
+                reason: "snarkjs has no domain above 2^28".to_string()
+            })
+        );
+
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice_key", &[]),
+            zkeys(mock_snarkjs_vkey()),
+        )
+        .unwrap();
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::IssuerZkeys {
+                address: "alice_key".to_string(),
+                circuit_id: Some("snarkjs".to_string()),
+            },
+        )
+        .unwrap();
+        let value: ZkeysResponse = from_json(&res).unwrap();
+        assert_eq!(
+            (value.params, value.n, value.num_inputs),
+            (PlonkParams::Snarkjs, 7, 1)
+        );
+
+        let proof = |circuit_id: &str, public_signals: &[&str]| ExecuteMsg::SnarkjsProof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: Some(circuit_id.to_string()),
+            proof: mock_snarkjs_proof(),
+            public_signals: public_signals
+                .iter()
+                .map(|signal| signal.to_string())
+                .collect(),
+        };
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("bob_key", &[]),
+                proof("snarkjs", &[])
+            ),
+            Err(ContractError::ErrorProof {
+                field: "num_inputs".to_string(),
+                reason: "expected 1, got 0".to_string()
+            })
+        );
+        // the points at infinity don't open the key
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("bob_key", &[]),
+                proof("snarkjs", &["33"])
+            ),
+            Err(ContractError::InvalidProof {})
+        );
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::VerifySnarkjsProof {
+                key: VerifyingKeySource::Snarkjs {
+                    vkey: mock_snarkjs_vkey(),
+                },
+                proof: mock_snarkjs_proof(),
+                public_signals: vec!["33".to_string()],
+            },
+        )
+        .unwrap();
+        let value: VerifyProofResponse = from_json(&res).unwrap();
+        assert_eq!(
+            (value.is_valid, value.error),
+            (false, Some(ContractError::InvalidProof {}.to_string()))
+        );
+
+        // a bellman key doesn't take snarkjs proofs
+        mock_alice_set_zkeys(deps.as_mut(), &[]);
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("bob_key", &[]),
+                proof(DEFAULT_CIRCUIT, &["33"])
+            ),
+            Err(ContractError::NotSnarkjsCircuit {})
+        );
+    }
+
+    // `out = a * b` with `out` public, proven for 3 * 11, in the files `snarkjs plonk setup` and `snarkjs plonk prove` write
+    fn multiplier_files() -> (SnarkjsVerifyingKey, SnarkjsProof, Vec<String>) {
+        (
+            from_json(include_bytes!(
+                "../testdata/multiplier/verification_key.json"
+            ))
+            .unwrap(),
+            from_json(include_bytes!("../testdata/multiplier/proof.json")).unwrap(),
+            from_json(include_bytes!("../testdata/multiplier/public.json")).unwrap(),
+        )
+    }
+
+    #[test]
+    fn snarkjs_proof_of_multiplier_works() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        let (vkey, proof, public_signals) = multiplier_files();
+        let msg = ExecuteMsg::SnarkjsZkeys {
+            circuit_id: None,
+            metadata: None,
+            vkey: vkey.clone(),
+            bind_sender: None,
+            nullifier_index: None,
+            bounty: None,
+            bounty_deadline: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("alice_key", &[]), msg).unwrap();
+        let publish = |proof: SnarkjsProof, public_signals: Vec<String>| ExecuteMsg::SnarkjsProof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proof,
+            public_signals,
+        };
+
+        // a changed public signal, evaluation or commitment no longer verifies
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("bob_key", &[]),
+                publish(proof.clone(), vec!["34".to_string()])
+            ),
+            Err(ContractError::InvalidProof {})
+        );
+        let mut tampered = proof.clone();
+        tampered.eval_a = proof.eval_b.clone();
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("bob_key", &[]),
+                publish(tampered, public_signals.clone())
+            ),
+            Err(ContractError::InvalidProof {})
+        );
+        let mut tampered = proof.clone();
+        tampered.c = vec!["1".to_string(), "2".to_string(), "1".to_string()];
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("bob_key", &[]),
+                publish(tampered, public_signals.clone())
+            ),
+            Err(ContractError::InvalidProof {})
+        );
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::VerifySnarkjsProof {
+                key: VerifyingKeySource::Snarkjs { vkey },
+                proof: proof.clone(),
+                public_signals: public_signals.clone(),
+            },
+        )
+        .unwrap();
+        let value: VerifyProofResponse = from_json(&res).unwrap();
+        assert_eq!((value.is_valid, value.error), (true, None));
+        execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bob_key", &[]),
+            publish(proof, public_signals.clone()),
+        )
+        .unwrap();
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProofResult {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                prover_address: "bob_key".to_string(),
+            },
+        )
+        .unwrap();
+        let value: ProofResponse = from_json(&res).unwrap();
+        assert_eq!((value.input_values, value.is_valid), (public_signals, true));
+    }
+
+    #[test]
+    fn snarkjs_circuits_refuse_bellman_proofs() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys(deps.as_mut(), &[]);
+        // bob commits to a bellman proof before alice replaces her key with a snarkjs one
+        let (commitment, reveal) = mock_bob_reveal_proof_msg("salt");
+        mock_bob_commit_proof(deps.as_mut(), commitment.clone());
+        let msg = ExecuteMsg::SnarkjsZkeys {
+            circuit_id: None,
+            metadata: None,
+            vkey: mock_snarkjs_vkey(),
+            bind_sender: None,
+            nullifier_index: None,
+            bounty: None,
+            bounty_deadline: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("alice_key", &[]), msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.height = 101;
+        assert_eq!(
+            execute(deps.as_mut(), env, mock_info("bob_key", &[]), reveal),
+            Err(ContractError::SnarkjsCircuit {})
+        );
+        assert_eq!(
+            execute(
+                deps.as_mut(),
+                mock_env(),
+                mock_info("bob_key", &[]),
+                mock_bob_proof_msg()
+            ),
+            Err(ContractError::SnarkjsCircuit {})
+        );
+        let msg = ExecuteMsg::CommitProof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            commitment,
+        };
+        assert_eq!(
+            execute(deps.as_mut(), mock_env(), mock_info("bob_key", &[]), msg),
+            Err(ContractError::SnarkjsCircuit {})
+        );
+        let key = VerifyingKeySource::Registered {
+            issuer: "alice_key".to_string(),
+            circuit_id: None,
+            prover: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), mock_verify_bob_proof_query(key)).unwrap();
+        let value: VerifyProofResponse = from_json(&res).unwrap();
+        assert_eq!(
+            (value.is_valid, value.error),
+            (false, Some(ContractError::SnarkjsCircuit {}.to_string()))
+        );
+    }
+
+    #[test]
+    fn bound_sender_needs_the_sender_signal() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        mock_alice_set_zkeys(deps.as_mut(), &[]);
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::IssuerZkeys {
+                address: "alice_key".to_string(),
+                circuit_id: None,
+            },
+        )
+        .unwrap();
+        let value: ZkeysResponse = from_json(&res).unwrap();
+        assert!(!value.bind_sender);
+        let msg = ExecuteMsg::Zkeys {
+            circuit_id: Some("bound".to_string()),
+            metadata: None,
+            n: value.n,
+            num_inputs: value.num_inputs,
+            selector_commitments: value.selector_commitments,
+            next_step_selector_commitments: value.next_step_selector_commitments,
+            permutation_commitments: value.permutation_commitments,
+            non_residues: value.non_residues,
+            g2_elements: value.g2_elements,
+            params: None,
+            bind_sender: Some(true),
+            nullifier_index: None,
+            bounty: None,
+            bounty_deadline: None,
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("alice_key", &[]), msg).unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::SenderSignal {
+                address: "bob_key".to_string(),
+            },
+        )
+        .unwrap();
+        let value: SenderSignalResponse = from_json(&res).unwrap();
+        assert_eq!(
+            value.public_signal,
+            sender_public_signal(&Addr::unchecked("bob_key"))
+        );
+
+        // bob's proof carries 33 as its public input, not his sender signal
+        let mut msg = mock_bob_proof_msg();
+        if let ExecuteMsg::Proof { circuit_id, .. } = &mut msg {
+            *circuit_id = Some("bound".to_string());
+        }
+        assert_eq!(
+            execute(deps.as_mut(), mock_env(), mock_info("bob_key", &[]), msg),
+            Err(ContractError::SenderSignalMismatch {})
+        );
+        let registered = |prover: Option<&str>| VerifyingKeySource::Registered {
+            issuer: "alice_key".to_string(),
+            circuit_id: Some("bound".to_string()),
+            prover: prover.map(str::to_string),
+        };
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            mock_verify_bob_proof_query(registered(None)),
+        )
+        .unwrap();
+        let value: VerifyProofResponse = from_json(&res).unwrap();
+        assert_eq!(
+            (value.is_valid, value.error),
+            (false, Some(ContractError::NoProver {}.to_string()))
+        );
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            mock_verify_bob_proof_query(registered(Some("bob_key"))),
+        )
+        .unwrap();
+        let value: VerifyProofResponse = from_json(&res).unwrap();
+        assert_eq!(
+            (value.is_valid, value.error),
+            (
+                false,
+                Some(ContractError::SenderSignalMismatch {}.to_string())
+            )
+        );
+        // the same proof still verifies against the unbound circuit
+        assert!(execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("bob_key", &[]),
+            mock_bob_proof_msg()
+        )
+        .is_ok());
+    }
+}
@@ -0,0 +1,143 @@
+use crate::verifier::Proof;
+use crate::ContractError;
+use cosmwasm_std::Addr;
+use sha2::{Digest, Sha256};
+
+/// the curve points of a proof in the order its single system contract commits to them,
+/// as given in the message, the plonk evaluations are bound to them by the transcript
+fn proof_points(proof: &Proof) -> Result<Vec<u8>, ContractError> {
+    let points: Vec<(&str, &String)> = match proof {
+        Proof::Groth16 {
+            proof_a,
+            proof_b,
+            proof_c,
+            ..
+        } => vec![
+            ("proof_a", proof_a),
+            ("proof_b", proof_b),
+            ("proof_c", proof_c),
+        ],
+        Proof::Plonk {
+            wire_commitments,
+            grand_product_commitment,
+            quotient_poly_commitments,
+            opening_at_z_proof,
+            opening_at_z_omega_proof,
+            ..
+        } => wire_commitments
+            .iter()
+            .map(|x| ("wire_commitments", x))
+            .chain([("grand_product_commitment", grand_product_commitment)])
+            .chain(
+                quotient_poly_commitments
+                    .iter()
+                    .map(|x| ("quotient_poly_commitments", x)),
+            )
+            .chain([
+                ("opening_at_z_proof", opening_at_z_proof),
+                ("opening_at_z_omega_proof", opening_at_z_omega_proof),
+            ])
+            .collect(),
+    };
+    let mut bytes = vec![];
+    for (field, point) in points {
+        bytes.extend(
+            hex::decode(point).map_err(|_| ContractError::HexDecodingError {
+                field: field.to_string(),
+            })?,
+        );
+    }
+    Ok(bytes)
+}
+
+/// sha256(proof points || sender || salt), the value a prover sends with `CommitProof`,
+/// the same the single system contract of the proof takes
+pub fn proof_commitment(
+    proof: &Proof,
+    sender: &Addr,
+    salt: &str,
+) -> Result<Vec<u8>, ContractError> {
+    let mut hasher = Sha256::new();
+    hasher.update(proof_points(proof)?);
+    hasher.update(sender.as_bytes());
+    hasher.update(salt.as_bytes());
+    Ok(hasher.finalize().to_vec())
+}
+
+/// sha256(vkey hash || public inputs), what a proof proves. Every proof of a statement has
+/// the same hash, re-randomized or not and whoever submits it
+pub fn statement_hash(vkey_hash: &[u8], public_inputs: &[Vec<u8>]) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    hasher.update(vkey_hash);
+    for input in public_inputs {
+        hasher.update(input);
+    }
+    hasher.finalize().to_vec()
+}
+
+/// sha256(statement hash || prover), the claim of one prover on a statement every prover
+/// can prove, such as the public signals set by the issuer
+pub fn prover_claim(statement: &[u8], prover: &Addr) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    hasher.update(statement);
+    hasher.update(prover.as_bytes());
+    hasher.finalize().to_vec()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn groth16_proof(proof_a: &str) -> Proof {
+        Proof::Groth16 {
+            proof_a: proof_a.to_string(),
+            proof_b: "02".repeat(192),
+            proof_c: "03".repeat(96),
+            point_encoding: None,
+        }
+    }
+
+    #[test]
+    fn proof_commitment_works() {
+        let proof = groth16_proof(&"01".repeat(96));
+        let bob = Addr::unchecked("bob_key");
+        let commitment = proof_commitment(&proof, &bob, "salt").unwrap();
+        assert_eq!(commitment.len(), 32);
+
+        // the same proof commits differently for another sender or salt
+        assert_ne!(
+            commitment,
+            proof_commitment(&proof, &Addr::unchecked("carol_key"), "salt").unwrap()
+        );
+        assert_ne!(
+            commitment,
+            proof_commitment(&proof, &bob, "pepper").unwrap()
+        );
+
+        match proof_commitment(&groth16_proof("0x01"), &bob, "salt") {
+            Ok(_) => panic!("Should have raised hex decoding error"),
+            Err(ContractError::HexDecodingError { field }) => assert_eq!(field, "proof_a"),
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[test]
+    fn statement_hash_works() {
+        let statement = statement_hash(&[1; 32], &[vec![7; 32]]);
+        // another key or other public inputs make another statement
+        assert_ne!(statement, statement_hash(&[2; 32], &[vec![7; 32]]));
+        assert_ne!(statement, statement_hash(&[1; 32], &[vec![8; 32]]));
+    }
+
+    #[test]
+    fn prover_claim_works() {
+        let statement = statement_hash(&[1; 32], &[vec![7; 32]]);
+        let claim = prover_claim(&statement, &Addr::unchecked("bob_key"));
+        // every prover has their own claim on the statement
+        assert_ne!(claim, statement);
+        assert_ne!(
+            claim,
+            prover_claim(&statement, &Addr::unchecked("carol_key"))
+        );
+    }
+}
@@ -0,0 +1,1475 @@
+use super::msg::{
+    BountyResponse, CircuitRef, CircuitResponse, CircuitsResponse, CommitmentResponse,
+    FeeBalanceResponse, IssuersResponse, NullifierResponse, ProofResponse, ProofSystemsResponse,
+    ProverHistoryResponse, ProversResponse, SenderSignalResponse, SolvedCircuitsResponse,
+    VerificationResponse, VerifyProofResponse,
+};
+use super::msg::{
+    ConfigResponse, ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg, DEFAULT_CIRCUIT,
+};
+use super::state::{
+    Bounty, Commitment, ProofClaim, BOUNTIES, BOUNTY_ESCROW, COMMITMENTS, NULLIFIERS, PROOF_CLAIMS,
+    SOLVED,
+};
+use super::state::{
+    Circuit, CircuitMetadata, CircuitStatus, Config, FeeMode, ProofInfo, CIRCUITS, CONFIG,
+    PROVERLIST,
+};
+use super::state::{Verification, PROVER_HISTORY, PROVER_SEQUENCE};
+use super::verifier::{Proof, ProofSystem, Verifier, VerifyingKey};
+use crate::coin_helpers::{charge_cw20_fee, charge_fee, deduct_coin};
+use crate::commitment::{proof_commitment, prover_claim, statement_hash};
+use crate::ContractError;
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    from_json, to_json_binary, Addr, BankMsg, Binary, Coin, CosmosMsg, Deps, DepsMut, Empty, Env,
+    Event, MessageInfo, Order, Response, StdError, StdResult, Storage, Uint128, WasmMsg,
+};
+use cw2::{set_contract_version, CONTRACT};
+use cw20::{Cw20Coin, Cw20CoinVerified, Cw20ExecuteMsg, Cw20ReceiveMsg};
+use cw_storage_plus::{Bound, PrefixBound};
+use semver::Version;
+
+// version info for migration
+const CONTRACT_NAME: &str = "crates.io:cw-verifier";
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+pub const DEFAULT_COMMITMENT_EXPIRY: u64 = 100;
+
+const MAX_CIRCUIT_ID_LENGTH: usize = 64;
+const MAX_CIRCUIT_NAME_LENGTH: usize = 64;
+const MAX_CIRCUIT_DESCRIPTION_LENGTH: usize = 1024;
+const MAX_CIRCUIT_SOURCE_URI_LENGTH: usize = 256;
+
+// settings for pagination
+const MAX_LIMIT: u32 = 30;
+const DEFAULT_LIMIT: u32 = 10;
+
+// instantiate the contract
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    info: MessageInfo,
+    msg: InstantiateMsg,
+) -> Result<Response, StdError> {
+    let config = Config {
+        owner: info.sender,
+        zkeys_price: msg.set_zkeys_price,
+        proof_price: msg.publish_proof_price,
+        zkeys_cw20_price: validate_cw20_price(deps.as_ref(), msg.set_zkeys_cw20_price)?,
+        proof_cw20_price: validate_cw20_price(deps.as_ref(), msg.publish_proof_cw20_price)?,
+        fee_mode: msg.fee_mode.unwrap_or_default(),
+        unrelated_denoms: msg.unrelated_denoms.unwrap_or_default(),
+        commitment_expiry: msg.commitment_expiry.unwrap_or(DEFAULT_COMMITMENT_EXPIRY),
+    };
+    CONFIG.save(deps.storage, &config)?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::default())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, _env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let new_version: Version = CONTRACT_VERSION.parse()?;
+    let stored = CONTRACT.load(deps.storage)?;
+    if stored.contract != CONTRACT_NAME {
+        // the single system contracts keep their keys in another layout
+        return Err(ContractError::CannotMigrate {
+            previous_contract: stored.contract,
+        });
+    }
+    let previous_version: Version = stored.version.parse()?;
+    if previous_version > new_version {
+        return Err(ContractError::CannotDowngrade {
+            previous_version: stored.version,
+            new_version: CONTRACT_VERSION.to_string(),
+        });
+    }
+    // releases that change a stored layout add their rewrite here, keyed on `previous_version`
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::default())
+}
+
+fn validate_cw20_price(deps: Deps, price: Option<Cw20Coin>) -> StdResult<Option<Cw20CoinVerified>> {
+    price
+        .map(|coin| {
+            Ok(Cw20CoinVerified {
+                address: deps.api.addr_validate(&coin.address)?,
+                amount: coin.amount,
+            })
+        })
+        .transpose()
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Zkeys {
+            circuit_id,
+            metadata,
+            proof_system,
+            key,
+            public_signals,
+            bind_sender,
+            nullifier_index,
+            bounty,
+            bounty_deadline,
+        } => execute_set_zkeys(
+            deps,
+            env,
+            info,
+            None,
+            circuit_id,
+            metadata,
+            proof_system,
+            key,
+            public_signals,
+            bind_sender,
+            nullifier_index,
+            bounty,
+            bounty_deadline,
+        ),
+        ExecuteMsg::Proof {
+            difficuty_issuer,
+            circuit_id,
+            proof,
+            public_signals,
+        } => execute_publish_proof(
+            deps,
+            env,
+            info,
+            None,
+            difficuty_issuer,
+            circuit_id,
+            proof,
+            public_signals,
+        ),
+        ExecuteMsg::CommitProof {
+            difficuty_issuer,
+            circuit_id,
+            commitment,
+        } => execute_commit_proof(deps, env, info, difficuty_issuer, circuit_id, commitment),
+        ExecuteMsg::RevealProof {
+            difficuty_issuer,
+            circuit_id,
+            proof,
+            public_signals,
+            salt,
+        } => execute_reveal_proof(
+            deps,
+            env,
+            info,
+            None,
+            difficuty_issuer,
+            circuit_id,
+            proof,
+            public_signals,
+            salt,
+        ),
+        ExecuteMsg::SetCircuitStatus { circuit_id, status } => {
+            execute_set_circuit_status(deps, info, circuit_id, status)
+        }
+        ExecuteMsg::ReclaimBounty { circuit_id } => {
+            execute_reclaim_bounty(deps, env, info, circuit_id)
+        }
+        ExecuteMsg::ClaimBounty {
+            difficuty_issuer,
+            circuit_id,
+        } => execute_claim_bounty(deps, env, difficuty_issuer, circuit_id),
+        ExecuteMsg::Withdraw { amount, recipient } => {
+            execute_withdraw(deps, env, info, amount, recipient)
+        }
+        ExecuteMsg::WithdrawCw20 {
+            token,
+            amount,
+            recipient,
+        } => execute_withdraw_cw20(deps, info, token, amount, recipient),
+        ExecuteMsg::Receive(wrapper) => execute_receive(deps, env, info, wrapper),
+    }
+}
+
+/// the hook of a cw20 `Send`, the token contract is the caller and the
+/// embedded message is executed for the original sender
+pub fn execute_receive(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    wrapper: Cw20ReceiveMsg,
+) -> Result<Response, ContractError> {
+    let payment = Cw20CoinVerified {
+        address: info.sender,
+        amount: wrapper.amount,
+    };
+    let info = MessageInfo {
+        sender: deps.api.addr_validate(&wrapper.sender)?,
+        funds: vec![],
+    };
+
+    match from_json(&wrapper.msg)? {
+        ExecuteMsg::Zkeys {
+            circuit_id,
+            metadata,
+            proof_system,
+            key,
+            public_signals,
+            bind_sender,
+            nullifier_index,
+            bounty,
+            bounty_deadline,
+        } => execute_set_zkeys(
+            deps,
+            env,
+            info,
+            Some(payment),
+            circuit_id,
+            metadata,
+            proof_system,
+            key,
+            public_signals,
+            bind_sender,
+            nullifier_index,
+            bounty,
+            bounty_deadline,
+        ),
+        ExecuteMsg::Proof {
+            difficuty_issuer,
+            circuit_id,
+            proof,
+            public_signals,
+        } => execute_publish_proof(
+            deps,
+            env,
+            info,
+            Some(payment),
+            difficuty_issuer,
+            circuit_id,
+            proof,
+            public_signals,
+        ),
+        ExecuteMsg::RevealProof {
+            difficuty_issuer,
+            circuit_id,
+            proof,
+            public_signals,
+            salt,
+        } => execute_reveal_proof(
+            deps,
+            env,
+            info,
+            Some(payment),
+            difficuty_issuer,
+            circuit_id,
+            proof,
+            public_signals,
+            salt,
+        ),
+        _ => Err(ContractError::InvalidCw20Hook {}),
+    }
+}
+
+pub fn execute_set_zkeys(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_payment: Option<Cw20CoinVerified>,
+    circuit_id: Option<String>,
+    metadata: Option<CircuitMetadata>,
+    proof_system: ProofSystem,
+    key: VerifyingKey,
+    public_signals: Option<Vec<String>>,
+    bind_sender: Option<bool>,
+    nullifier_index: Option<usize>,
+    bounty: Option<Coin>,
+    bounty_deadline: Option<u64>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let circuit_id = circuit_or_default(circuit_id);
+    if circuit_id.is_empty() || circuit_id.len() > MAX_CIRCUIT_ID_LENGTH {
+        return Err(ContractError::InvalidCircuitId {});
+    }
+    if let Some(registered) = CIRCUITS.may_load(deps.storage, (&info.sender, &circuit_id))? {
+        // a key is only replaced while no listed proof or open bounty depends on it
+        let proven = PROVERLIST
+            .prefix((&info.sender, &circuit_id))
+            .keys(deps.storage, None, None, Order::Ascending)
+            .next()
+            .is_some();
+        if proven
+            || registered.status == CircuitStatus::Retired
+            || BOUNTIES.has(deps.storage, (&info.sender, &circuit_id))
+        {
+            return Err(ContractError::CircuitAlreadyRegistered { circuit_id });
+        }
+    }
+    let metadata = metadata.unwrap_or_default();
+    validate_metadata(&metadata)?;
+    // the reward is held apart from the registration fee
+    let funds = match &bounty {
+        Some(reward) => deduct_coin(&info.funds, reward)?,
+        None => info.funds.clone(),
+    };
+    let (refund, fee) = charge_price(
+        &info.sender,
+        &funds,
+        cw20_payment,
+        config.zkeys_price.clone(),
+        config.zkeys_cw20_price.clone(),
+        &config,
+    )?;
+    let bounty = match bounty {
+        Some(reward) => {
+            let deadline = bounty_deadline.ok_or(ContractError::InvalidBounty {})?;
+            if reward.amount.is_zero() || deadline <= env.block.height {
+                return Err(ContractError::InvalidBounty {});
+            }
+            if BOUNTIES.has(deps.storage, (&info.sender, &circuit_id)) {
+                return Err(ContractError::BountyAlreadyOpen {});
+            }
+            Some(Bounty {
+                reward,
+                deadline,
+                winner: None,
+            })
+        }
+        None => None,
+    };
+
+    let verifier = proof_system.verifier()?;
+    let decoded = verifier.decode_key(key)?;
+    let bind_sender = bind_sender.unwrap_or(false);
+    if let Some(public_signals) = &public_signals {
+        check_issuer_public_signals(verifier, decoded.num_inputs, public_signals, bind_sender)?;
+    }
+    if nullifier_index.is_some_and(|index| index >= decoded.num_inputs) {
+        return Err(ContractError::InvalidNullifierIndex {});
+    }
+    let circuit = Circuit {
+        proof_system,
+        vkey: decoded.key,
+        vkey_hash: hex::encode(decoded.vkey_hash),
+        public_signals,
+        bind_sender,
+        nullifier_index,
+        metadata,
+        status: CircuitStatus::Active,
+    };
+
+    CIRCUITS.save(deps.storage, (&info.sender, &circuit_id), &circuit)?;
+    let mut response = Response::new().add_messages(refund).add_event(
+        Event::new("zkeys_registered")
+            .add_attribute("issuer", info.sender.as_str())
+            .add_attribute("circuit_id", &circuit_id)
+            .add_attribute("vkey_hash", circuit.vkey_hash)
+            .add_attribute("proof_system", proof_system.as_str())
+            .add_attribute("fee", fee),
+    );
+    if let Some(bounty) = bounty {
+        escrow_bounty(deps.storage, &info.sender, &circuit_id, &bounty)?;
+        response = response.add_event(
+            Event::new("bounty_opened")
+                .add_attribute("issuer", info.sender.as_str())
+                .add_attribute("circuit_id", circuit_id)
+                .add_attribute("reward", bounty.reward.to_string())
+                .add_attribute("deadline", bounty.deadline.to_string()),
+        );
+    }
+
+    Ok(response)
+}
+
+pub fn execute_publish_proof(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_payment: Option<Cw20CoinVerified>,
+    difficuty_issuer: String,
+    circuit_id: Option<String>,
+    proof: Proof,
+    public_signals: Option<Vec<String>>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let (refund, fee) = charge_price(
+        &info.sender,
+        &info.funds,
+        cw20_payment,
+        config.proof_price.clone(),
+        config.proof_cw20_price.clone(),
+        &config,
+    )?;
+
+    // a direct submission counts as committed in the block it lands in
+    Ok(verify_and_record(
+        deps,
+        &env,
+        &info.sender,
+        difficuty_issuer,
+        circuit_or_default(circuit_id),
+        proof,
+        public_signals,
+        env.block.height,
+        fee,
+    )?
+    .add_messages(refund))
+}
+
+pub fn execute_commit_proof(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    difficuty_issuer: String,
+    circuit_id: Option<String>,
+    commitment: String,
+) -> Result<Response, ContractError> {
+    let issuer = deps.api.addr_validate(&difficuty_issuer)?;
+    let circuit_id = circuit_or_default(circuit_id);
+
+    match CIRCUITS.may_load(deps.storage, (&issuer, &circuit_id))? {
+        None => return Err(ContractError::NonPublishDifficulty { difficuty_issuer }),
+        Some(circuit) if circuit.status == CircuitStatus::Retired => {
+            return Err(ContractError::CircuitRetired { circuit_id })
+        }
+        Some(_) => {}
+    }
+
+    let hash = hex::decode(commitment).map_err(|_| ContractError::InvalidCommitment {})?;
+    if hash.len() != 32 {
+        return Err(ContractError::InvalidCommitment {});
+    }
+
+    // committing again replaces the previous commitment of this prover
+    let commitment = Commitment {
+        hash,
+        height: env.block.height,
+    };
+    COMMITMENTS.save(
+        deps.storage,
+        (&issuer, &circuit_id, &info.sender),
+        &commitment,
+    )?;
+
+    Ok(Response::new().add_event(
+        Event::new("proof_committed")
+            .add_attribute("issuer", issuer.as_str())
+            .add_attribute("circuit_id", circuit_id)
+            .add_attribute("prover", info.sender.as_str()),
+    ))
+}
+
+pub fn execute_reveal_proof(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    cw20_payment: Option<Cw20CoinVerified>,
+    difficuty_issuer: String,
+    circuit_id: Option<String>,
+    proof: Proof,
+    public_signals: Option<Vec<String>>,
+    salt: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let (refund, fee) = charge_price(
+        &info.sender,
+        &info.funds,
+        cw20_payment,
+        config.proof_price.clone(),
+        config.proof_cw20_price.clone(),
+        &config,
+    )?;
+
+    let issuer = deps.api.addr_validate(&difficuty_issuer)?;
+    let circuit_id = circuit_or_default(circuit_id);
+    let commitment = COMMITMENTS
+        .may_load(deps.storage, (&issuer, &circuit_id, &info.sender))?
+        .ok_or(ContractError::NoCommitment {})?;
+
+    if env.block.height <= commitment.height {
+        // the proof would be public in the same block as the commitment
+        return Err(ContractError::RevealTooEarly {});
+    }
+    if env.block.height > commitment.height.saturating_add(config.commitment_expiry) {
+        return Err(ContractError::CommitmentExpired {});
+    }
+
+    if proof_commitment(&proof, &info.sender, &salt)? != commitment.hash {
+        return Err(ContractError::CommitmentMismatch {});
+    }
+    COMMITMENTS.remove(deps.storage, (&issuer, &circuit_id, &info.sender));
+
+    Ok(verify_and_record(
+        deps,
+        &env,
+        &info.sender,
+        difficuty_issuer,
+        circuit_id,
+        proof,
+        public_signals,
+        commitment.height,
+        fee,
+    )?
+    .add_messages(refund))
+}
+
+pub fn execute_set_circuit_status(
+    deps: DepsMut,
+    info: MessageInfo,
+    circuit_id: Option<String>,
+    status: CircuitStatus,
+) -> Result<Response, ContractError> {
+    let circuit_id = circuit_or_default(circuit_id);
+    let mut circuit = CIRCUITS
+        .may_load(deps.storage, (&info.sender, &circuit_id))?
+        .ok_or_else(|| ContractError::NonPublishDifficulty {
+            difficuty_issuer: info.sender.to_string(),
+        })?;
+    if circuit.status == CircuitStatus::Retired && status != CircuitStatus::Retired {
+        // provers may have dropped the circuit for good
+        return Err(ContractError::CircuitRetired { circuit_id });
+    }
+
+    circuit.status = status;
+    CIRCUITS.save(deps.storage, (&info.sender, &circuit_id), &circuit)?;
+    Ok(Response::new().add_event(
+        Event::new("circuit_status_changed")
+            .add_attribute("issuer", info.sender.as_str())
+            .add_attribute("circuit_id", circuit_id)
+            .add_attribute("status", status_attribute(&circuit.status)),
+    ))
+}
+
+pub fn execute_reclaim_bounty(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    circuit_id: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let circuit_id = circuit_or_default(circuit_id);
+    let bounty = BOUNTIES
+        .may_load(deps.storage, (&info.sender, &circuit_id))?
+        .ok_or(ContractError::NoBounty {})?;
+    if bounty.winner.is_some() {
+        return Err(ContractError::BountyAlreadyWon {});
+    }
+    // a commitment made by the deadline can still be revealed to win the bounty
+    let reclaimable_at = bounty
+        .deadline
+        .saturating_add(config.commitment_expiry)
+        .saturating_add(1);
+    if env.block.height < reclaimable_at {
+        return Err(ContractError::BountyNotExpired { reclaimable_at });
+    }
+
+    release_bounty(deps.storage, &info.sender, &circuit_id, &bounty)?;
+    Ok(Response::new()
+        .add_event(
+            Event::new("bounty_reclaimed")
+                .add_attribute("issuer", info.sender.as_str())
+                .add_attribute("circuit_id", circuit_id)
+                .add_attribute("reward", bounty.reward.to_string()),
+        )
+        .add_message(BankMsg::Send {
+            to_address: info.sender.into_string(),
+            amount: vec![bounty.reward],
+        }))
+}
+
+pub fn execute_claim_bounty(
+    deps: DepsMut,
+    env: Env,
+    difficuty_issuer: String,
+    circuit_id: Option<String>,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    let issuer = deps.api.addr_validate(&difficuty_issuer)?;
+    let circuit_id = circuit_or_default(circuit_id);
+    let bounty = BOUNTIES
+        .may_load(deps.storage, (&issuer, &circuit_id))?
+        .ok_or(ContractError::NoBounty {})?;
+    let winner = bounty
+        .winner
+        .clone()
+        .ok_or(ContractError::BountyNotWon {})?;
+    let claimable_at = winner.committed_at.saturating_add(config.commitment_expiry);
+    if env.block.height < claimable_at {
+        // a commitment made before the winning one can still be revealed
+        return Err(ContractError::BountyNotClaimable { claimable_at });
+    }
+
+    release_bounty(deps.storage, &issuer, &circuit_id, &bounty)?;
+    Ok(Response::new()
+        .add_event(
+            Event::new("bounty_paid")
+                .add_attribute("issuer", issuer.as_str())
+                .add_attribute("circuit_id", circuit_id)
+                .add_attribute("prover", winner.prover.as_str())
+                .add_attribute("reward", bounty.reward.to_string()),
+        )
+        .add_message(BankMsg::Send {
+            to_address: winner.prover.into_string(),
+            amount: vec![bounty.reward],
+        }))
+}
+
+pub fn execute_withdraw(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Vec<Coin>,
+    recipient: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if amount.is_empty() || amount.iter().any(|coin| coin.amount.is_zero()) {
+        return Err(ContractError::EmptyWithdrawal {});
+    }
+
+    // open bounties are not fees
+    let fees = fee_balance(deps.as_ref(), &env)?;
+    for coin in amount.iter() {
+        let available = fees
+            .iter()
+            .find(|fee| fee.denom == coin.denom)
+            .map_or(Uint128::zero(), |fee| fee.amount);
+        if coin.amount > available {
+            return Err(ContractError::InsufficientFees {
+                denom: coin.denom.clone(),
+            });
+        }
+    }
+
+    let recipient = deps.api.addr_validate(&recipient)?;
+    Ok(Response::new()
+        .add_event(
+            Event::new("fees_withdrawn")
+                .add_attribute("recipient", recipient.as_str())
+                .add_attribute("amount", coins_attribute(&amount)),
+        )
+        .add_message(BankMsg::Send {
+            to_address: recipient.into_string(),
+            amount,
+        }))
+}
+
+pub fn execute_withdraw_cw20(
+    deps: DepsMut,
+    info: MessageInfo,
+    token: String,
+    amount: Uint128,
+    recipient: String,
+) -> Result<Response, ContractError> {
+    let config = CONFIG.load(deps.storage)?;
+    if info.sender != config.owner {
+        return Err(ContractError::Unauthorized {});
+    }
+    if amount.is_zero() {
+        return Err(ContractError::EmptyWithdrawal {});
+    }
+
+    // bounties are native coins, every cw20 token held is a fee
+    let token = deps.api.addr_validate(&token)?;
+    let recipient = deps.api.addr_validate(&recipient)?;
+    Ok(Response::new()
+        .add_event(
+            Event::new("fees_withdrawn")
+                .add_attribute("recipient", recipient.as_str())
+                .add_attribute("amount", format!("{}{}", amount, token)),
+        )
+        .add_message(WasmMsg::Execute {
+            contract_addr: token.into_string(),
+            msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                recipient: recipient.into_string(),
+                amount,
+            })?,
+            funds: vec![],
+        }))
+}
+
+/// charge a price from the native funds, or from the cw20 tokens of a `Receive`,
+/// and return the refund messages with the fee that was kept
+fn charge_price(
+    sender: &Addr,
+    funds: &[Coin],
+    cw20_payment: Option<Cw20CoinVerified>,
+    price: Option<Coin>,
+    cw20_price: Option<Cw20CoinVerified>,
+    config: &Config,
+) -> Result<(Vec<CosmosMsg>, String), ContractError> {
+    let exact = config.fee_mode == FeeMode::Exact;
+    match cw20_payment {
+        Some(payment) => {
+            let refund = charge_cw20_fee(sender, &payment, cw20_price.clone(), &config.fee_mode)?;
+            let paid = match cw20_price {
+                Some(price) if exact => price,
+                _ => payment,
+            };
+            Ok((
+                refund.into_iter().map(CosmosMsg::from).collect(),
+                format!("{}{}", paid.amount, paid.address),
+            ))
+        }
+        None => {
+            if price.is_none() && cw20_price.is_some() {
+                // only payable in the cw20 token
+                return Err(ContractError::InsufficientFundsSend {});
+            }
+            let refund = charge_fee(
+                sender,
+                funds,
+                price.clone(),
+                &config.fee_mode,
+                &config.unrelated_denoms,
+            )?;
+            let paid = if exact {
+                price.into_iter().collect()
+            } else {
+                funds.to_vec()
+            };
+            Ok((
+                refund.into_iter().map(CosmosMsg::from).collect(),
+                coins_attribute(&paid),
+            ))
+        }
+    }
+}
+
+/// the circuit a message is about, `DEFAULT_CIRCUIT` when it doesn't name one
+fn circuit_or_default(circuit_id: Option<String>) -> String {
+    circuit_id.unwrap_or_else(|| DEFAULT_CIRCUIT.to_string())
+}
+
+/// the public signals a proof is checked against, fixed by the issuer or brought by the prover,
+/// `prover` is only needed by circuits binding the sender
+fn resolve_public_signals(
+    circuit: &Circuit,
+    prover: Option<&Addr>,
+    public_signals: Option<Vec<String>>,
+) -> Result<Vec<String>, ContractError> {
+    let mut resolved = match (&circuit.public_signals, public_signals) {
+        (Some(fixed), None) => fixed.clone(),
+        // the issuer already fixed the statement to prove
+        (Some(_), Some(_)) => return Err(ContractError::ErrorPublicSignal {}),
+        (None, Some(public_signals)) => public_signals,
+        (None, None) => return Err(ContractError::NoPublicSignal {}),
+    };
+    if circuit.bind_sender {
+        // a proof copied by another address won't verify against its sender signal
+        let prover = prover.ok_or(ContractError::NoProver {})?;
+        resolved.push(circuit.proof_system.verifier()?.sender_signal(prover));
+    }
+    Ok(resolved)
+}
+
+/// the public signals an issuer fixes fill every public input but the sender signal a bound
+/// key appends, and are field elements, so a key can't be registered that no proof satisfies
+fn check_issuer_public_signals(
+    verifier: &dyn Verifier,
+    num_inputs: usize,
+    public_signals: &[String],
+    bind_sender: bool,
+) -> Result<(), ContractError> {
+    let expected = num_inputs.saturating_sub(bind_sender as usize);
+    if public_signals.len() != expected {
+        return Err(ContractError::PublicSignalCountMismatch {
+            expected,
+            actual: public_signals.len(),
+        });
+    }
+    public_signal_keys(verifier, public_signals).map(|_| ())
+}
+
+/// the canonical bytes of every public signal, what statements and nullifiers are keyed on
+fn public_signal_keys(
+    verifier: &dyn Verifier,
+    public_signals: &[String],
+) -> Result<Vec<Vec<u8>>, ContractError> {
+    public_signals
+        .iter()
+        .enumerate()
+        .map(|(index, x)| {
+            verifier
+                .public_signal_key(x)
+                .ok_or(ContractError::ParsePulbicSignalError { index })
+        })
+        .collect()
+}
+
+/// the limits on what an issuer can tell about a circuit, the artifact hash is optional
+fn validate_metadata(metadata: &CircuitMetadata) -> Result<(), ContractError> {
+    let invalid = |field: &str| ContractError::InvalidCircuitMetadata {
+        field: field.to_string(),
+    };
+    if metadata.name.len() > MAX_CIRCUIT_NAME_LENGTH {
+        return Err(invalid("name"));
+    }
+    if metadata.description.len() > MAX_CIRCUIT_DESCRIPTION_LENGTH {
+        return Err(invalid("description"));
+    }
+    if metadata.source_uri.len() > MAX_CIRCUIT_SOURCE_URI_LENGTH {
+        return Err(invalid("source_uri"));
+    }
+    if !metadata.artifact_hash.is_empty()
+        && !matches!(hex::decode(&metadata.artifact_hash), Ok(hash) if hash.len() == 32)
+    {
+        return Err(invalid("artifact_hash"));
+    }
+    Ok(())
+}
+
+fn status_attribute(status: &CircuitStatus) -> &'static str {
+    match status {
+        CircuitStatus::Active => "active",
+        CircuitStatus::Deprecated => "deprecated",
+        CircuitStatus::Retired => "retired",
+    }
+}
+
+/// coins as an event attribute value, `<amount><denom>` separated by commas
+fn coins_attribute(coins: &[Coin]) -> String {
+    coins
+        .iter()
+        .map(Coin::to_string)
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// the circuit of an issuer, as long as it still accepts proofs
+fn load_verifying_circuit(
+    deps: Deps,
+    difficuty_issuer: String,
+    circuit_id: &str,
+) -> Result<(Addr, Circuit), ContractError> {
+    let issuer = deps.api.addr_validate(&difficuty_issuer)?;
+
+    let circuit = CIRCUITS
+        .may_load(deps.storage, (&issuer, circuit_id))?
+        .ok_or(ContractError::NonPublishDifficulty { difficuty_issuer })?;
+    if circuit.status == CircuitStatus::Retired {
+        return Err(ContractError::CircuitRetired {
+            circuit_id: circuit_id.to_string(),
+        });
+    }
+    Ok((issuer, circuit))
+}
+
+/// verify the proof against the key of the issuer's circuit and credit the prover,
+/// unless its statement was committed to earlier by someone else
+fn verify_and_record(
+    deps: DepsMut,
+    env: &Env,
+    prover: &Addr,
+    difficuty_issuer: String,
+    circuit_id: String,
+    proof: Proof,
+    public_signals: Option<Vec<String>>,
+    committed_at: u64,
+    fee: String,
+) -> Result<Response, ContractError> {
+    let (issuer, circuit) = load_verifying_circuit(deps.as_ref(), difficuty_issuer, &circuit_id)?;
+    let verifier = circuit.proof_system.verifier()?;
+    let public_signals = resolve_public_signals(&circuit, Some(prover), public_signals)?;
+    if !verifier.verify(&circuit.vkey, proof.clone(), &public_signals)? {
+        return Err(ContractError::InvalidProof {});
+    }
+    let public_inputs = public_signal_keys(verifier, &public_signals)?;
+
+    let mut response = Response::new();
+    // a re-randomized copy of a proof proves the same statement, so claims are kept per statement
+    // when the prover picks it or its nullifier can be spent once. The issuer's signals are the
+    // same statement for every prover, each of them is credited for proving it
+    let statement = statement_hash(circuit.vkey_hash.as_bytes(), &public_inputs);
+    let per_statement = circuit.bind_sender
+        || circuit.public_signals.is_none()
+        || circuit.nullifier_index.is_some();
+    let claim_key = if per_statement {
+        statement.clone()
+    } else {
+        prover_claim(&statement, prover)
+    };
+    let claim = PROOF_CLAIMS.may_load(deps.storage, (&issuer, &circuit_id, &claim_key))?;
+    // the earliest commitment takes the credit of a statement from a later submission
+    let takeover = claim
+        .as_ref()
+        .is_some_and(|claim| claim.prover != *prover && committed_at < claim.committed_at);
+    let nullifier = match circuit.nullifier_index {
+        Some(index) => Some(
+            public_inputs
+                .get(index)
+                .ok_or(ContractError::ErrorPublicSignal {})?,
+        ),
+        None => None,
+    };
+    if let Some(nullifier) = nullifier {
+        // a spent nullifier only passes on to the earlier commitment of the statement that spent it
+        if NULLIFIERS.has(deps.storage, (&issuer, &circuit_id, nullifier)) && !takeover {
+            return Err(ContractError::NullifierAlreadySpent {});
+        }
+    }
+    let mut committed_at = committed_at;
+    if let Some(claim) = claim {
+        if claim.prover == *prover {
+            committed_at = committed_at.min(claim.committed_at);
+        } else if !takeover {
+            return Err(ContractError::ProofAlreadyClaimed {});
+        } else {
+            // the earliest commitment takes the credit from a copied submission
+            PROVERLIST.remove(deps.storage, (&issuer, &circuit_id, &claim.prover));
+            SOLVED.remove(deps.storage, (&claim.prover, &issuer, &circuit_id));
+            // the copied verification stays in their history, which is never rewritten
+        }
+    }
+    PROOF_CLAIMS.save(
+        deps.storage,
+        (&issuer, &circuit_id, &claim_key),
+        &ProofClaim {
+            prover: prover.clone(),
+            committed_at,
+        },
+    )?;
+    if let Some(nullifier) = nullifier {
+        NULLIFIERS.save(deps.storage, (&issuer, &circuit_id, nullifier), &statement)?;
+    }
+
+    let proof_info = ProofInfo {
+        proof,
+        public_signals,
+        is_valid: true,
+    };
+    PROVERLIST.save(deps.storage, (&issuer, &circuit_id, prover), &proof_info)?;
+    SOLVED.save(deps.storage, (prover, &issuer, &circuit_id), &Empty {})?;
+    let sequence = PROVER_SEQUENCE
+        .may_load(deps.storage, prover)?
+        .unwrap_or_default();
+    PROVER_HISTORY.save(
+        deps.storage,
+        (prover, sequence),
+        &Verification {
+            issuer: issuer.clone(),
+            circuit_id: circuit_id.clone(),
+            vkey_hash: circuit.vkey_hash.clone(),
+            height: env.block.height,
+        },
+    )?;
+    PROVER_SEQUENCE.save(deps.storage, prover, &(sequence + 1))?;
+
+    response = response.add_event(
+        Event::new("proof_verified")
+            .add_attribute("issuer", issuer.as_str())
+            .add_attribute("circuit_id", &circuit_id)
+            .add_attribute("prover", prover.as_str())
+            .add_attribute("vkey_hash", circuit.vkey_hash)
+            .add_attribute("proof_system", circuit.proof_system.as_str())
+            .add_attribute("fee", fee)
+            .add_attribute("outcome", "valid")
+            .add_attribute("sequence", sequence.to_string()),
+    );
+
+    // the earliest proof committed before the deadline wins the bounty,
+    // `ClaimBounty` pays it once no earlier commitment can be revealed
+    if let Some(mut bounty) = BOUNTIES.may_load(deps.storage, (&issuer, &circuit_id))? {
+        let earliest = bounty
+            .winner
+            .as_ref()
+            .is_none_or(|winner| committed_at < winner.committed_at);
+        if committed_at <= bounty.deadline && earliest {
+            bounty.winner = Some(ProofClaim {
+                prover: prover.clone(),
+                committed_at,
+            });
+            BOUNTIES.save(deps.storage, (&issuer, &circuit_id), &bounty)?;
+            response = response.add_event(
+                Event::new("bounty_won")
+                    .add_attribute("issuer", issuer.as_str())
+                    .add_attribute("circuit_id", circuit_id)
+                    .add_attribute("prover", prover.as_str()),
+            );
+        }
+    }
+
+    Ok(response)
+}
+
+/// hold the reward of a circuit until a prover collects it or the issuer reclaims it
+fn escrow_bounty(
+    storage: &mut dyn Storage,
+    issuer: &Addr,
+    circuit_id: &str,
+    bounty: &Bounty,
+) -> StdResult<()> {
+    BOUNTIES.save(storage, (issuer, circuit_id), bounty)?;
+    BOUNTY_ESCROW.update(storage, &bounty.reward.denom, |escrowed| -> StdResult<_> {
+        Ok(escrowed
+            .unwrap_or_default()
+            .checked_add(bounty.reward.amount)?)
+    })?;
+    Ok(())
+}
+
+fn release_bounty(
+    storage: &mut dyn Storage,
+    issuer: &Addr,
+    circuit_id: &str,
+    bounty: &Bounty,
+) -> StdResult<()> {
+    BOUNTIES.remove(storage, (issuer, circuit_id));
+    BOUNTY_ESCROW.update(storage, &bounty.reward.denom, |escrowed| -> StdResult<_> {
+        Ok(escrowed
+            .unwrap_or_default()
+            .saturating_sub(bounty.reward.amount))
+    })?;
+    Ok(())
+}
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Config {} => to_json_binary::<ConfigResponse>(&CONFIG.load(deps.storage)?.into()),
+        QueryMsg::ProofSystems {} => to_json_binary(&query_proof_systems()),
+        QueryMsg::Circuit {
+            issuer_address,
+            circuit_id,
+        } => to_json_binary(&query_circuit(deps, issuer_address, circuit_id)?),
+        QueryMsg::Circuits {
+            issuer_address,
+            start_after,
+            limit,
+        } => to_json_binary(&query_circuits(deps, issuer_address, start_after, limit)?),
+        QueryMsg::ProofResult {
+            issuer_address,
+            circuit_id,
+            prover_address,
+        } => to_json_binary(&query_proof_result(
+            deps,
+            issuer_address,
+            circuit_id,
+            prover_address,
+        )?),
+        QueryMsg::SenderSignal {
+            address,
+            proof_system,
+        } => to_json_binary(&query_sender_signal(deps, address, proof_system)?),
+        QueryMsg::Commitment {
+            issuer_address,
+            circuit_id,
+            prover_address,
+        } => to_json_binary(&query_commitment(
+            deps,
+            issuer_address,
+            circuit_id,
+            prover_address,
+        )?),
+        QueryMsg::Nullifier {
+            issuer_address,
+            circuit_id,
+            nullifier,
+        } => to_json_binary(&query_nullifier(
+            deps,
+            issuer_address,
+            circuit_id,
+            nullifier,
+        )?),
+        QueryMsg::Bounty {
+            address,
+            circuit_id,
+        } => to_json_binary(&query_bounty(deps, address, circuit_id)?),
+        QueryMsg::FeeBalance {} => to_json_binary(&query_fee_balance(deps, env)?),
+        QueryMsg::Issuers { start_after, limit } => {
+            to_json_binary(&query_issuers(deps, start_after, limit)?)
+        }
+        QueryMsg::Provers {
+            issuer_address,
+            circuit_id,
+            start_after,
+            limit,
+        } => to_json_binary(&query_provers(
+            deps,
+            issuer_address,
+            circuit_id,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::SolvedCircuits {
+            prover_address,
+            start_after,
+            limit,
+        } => to_json_binary(&query_solved_circuits(
+            deps,
+            prover_address,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::ProverHistory {
+            prover_address,
+            start_after,
+            limit,
+        } => to_json_binary(&query_prover_history(
+            deps,
+            prover_address,
+            start_after,
+            limit,
+        )?),
+        QueryMsg::VerifyProof {
+            issuer_address,
+            circuit_id,
+            prover,
+            proof,
+            public_signals,
+        } => to_json_binary(&query_verify_proof(
+            deps,
+            issuer_address,
+            circuit_id,
+            prover,
+            proof,
+            public_signals,
+        )?),
+    }
+}
+
+fn query_proof_systems() -> ProofSystemsResponse {
+    ProofSystemsResponse {
+        proof_systems: ProofSystem::ALL
+            .into_iter()
+            .filter(ProofSystem::is_supported)
+            .collect(),
+    }
+}
+
+fn query_circuit(
+    deps: Deps,
+    issuer_address: String,
+    circuit_id: Option<String>,
+) -> StdResult<CircuitResponse> {
+    let issuer_addr = deps.api.addr_validate(&issuer_address)?;
+    let circuit_id = circuit_or_default(circuit_id);
+
+    let circuit = CIRCUITS.load(deps.storage, (&issuer_addr, &circuit_id))?;
+    Ok(CircuitResponse {
+        issuer: issuer_addr,
+        circuit_id,
+        proof_system: circuit.proof_system,
+        vkey_hash: circuit.vkey_hash,
+        public_signals: circuit.public_signals,
+        bind_sender: circuit.bind_sender,
+        nullifier_index: circuit.nullifier_index,
+        metadata: circuit.metadata,
+        status: circuit.status,
+    })
+}
+
+fn query_circuits(
+    deps: Deps,
+    issuer_address: String,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<CircuitsResponse> {
+    let issuer_addr = deps.api.addr_validate(&issuer_address)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.as_deref().map(Bound::exclusive);
+
+    let circuit_ids = CIRCUITS
+        .prefix(&issuer_addr)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(CircuitsResponse { circuit_ids })
+}
+
+fn query_proof_result(
+    deps: Deps,
+    issuer_address: String,
+    circuit_id: Option<String>,
+    prover_address: String,
+) -> StdResult<ProofResponse> {
+    let issuer_addr = deps.api.addr_validate(&issuer_address)?;
+    let prover_addr = deps.api.addr_validate(&prover_address)?;
+    let circuit_id = circuit_or_default(circuit_id);
+
+    let proof_info = PROVERLIST.load(deps.storage, (&issuer_addr, &circuit_id, &prover_addr))?;
+    Ok(ProofResponse {
+        proof: proof_info.proof,
+        public_signals: proof_info.public_signals,
+        is_valid: proof_info.is_valid,
+    })
+}
+
+fn query_sender_signal(
+    deps: Deps,
+    address: String,
+    proof_system: ProofSystem,
+) -> StdResult<SenderSignalResponse> {
+    let addr = deps.api.addr_validate(&address)?;
+
+    let verifier = proof_system
+        .verifier()
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+    Ok(SenderSignalResponse {
+        public_signal: verifier.sender_signal(&addr),
+    })
+}
+
+fn query_commitment(
+    deps: Deps,
+    issuer_address: String,
+    circuit_id: Option<String>,
+    prover_address: String,
+) -> StdResult<CommitmentResponse> {
+    let issuer_addr = deps.api.addr_validate(&issuer_address)?;
+    let prover_addr = deps.api.addr_validate(&prover_address)?;
+
+    let config = CONFIG.load(deps.storage)?;
+    let commitment = COMMITMENTS.load(
+        deps.storage,
+        (&issuer_addr, &circuit_or_default(circuit_id), &prover_addr),
+    )?;
+    Ok(CommitmentResponse {
+        commitment: hex::encode(commitment.hash),
+        height: commitment.height,
+        expires_at: commitment.height.saturating_add(config.commitment_expiry),
+    })
+}
+
+fn query_nullifier(
+    deps: Deps,
+    issuer_address: String,
+    circuit_id: Option<String>,
+    nullifier: String,
+) -> StdResult<NullifierResponse> {
+    let issuer_addr = deps.api.addr_validate(&issuer_address)?;
+    let circuit_id = circuit_or_default(circuit_id);
+
+    // the nullifier is read in the encoding of the circuit's proof system
+    let circuit = CIRCUITS.load(deps.storage, (&issuer_addr, &circuit_id))?;
+    let verifier = circuit
+        .proof_system
+        .verifier()
+        .map_err(|err| StdError::generic_err(err.to_string()))?;
+    // a nullifier that can't be read is reported as the public signal it stands for
+    let index = circuit.nullifier_index.unwrap_or_default();
+    let nullifier = verifier.public_signal_key(&nullifier).ok_or_else(|| {
+        StdError::generic_err(ContractError::ParsePulbicSignalError { index }.to_string())
+    })?;
+    Ok(NullifierResponse {
+        spent: NULLIFIERS.has(deps.storage, (&issuer_addr, &circuit_id, &nullifier)),
+    })
+}
+
+fn query_bounty(
+    deps: Deps,
+    address: String,
+    circuit_id: Option<String>,
+) -> StdResult<BountyResponse> {
+    let issuer_addr = deps.api.addr_validate(&address)?;
+
+    let config = CONFIG.load(deps.storage)?;
+    let bounty = BOUNTIES.load(
+        deps.storage,
+        (&issuer_addr, &circuit_or_default(circuit_id)),
+    )?;
+    Ok(BountyResponse {
+        reward: bounty.reward,
+        deadline: bounty.deadline,
+        claimable_at: bounty
+            .winner
+            .as_ref()
+            .map(|winner| winner.committed_at.saturating_add(config.commitment_expiry)),
+        winner: bounty.winner.map(|winner| winner.prover),
+    })
+}
+
+fn query_fee_balance(deps: Deps, env: Env) -> StdResult<FeeBalanceResponse> {
+    Ok(FeeBalanceResponse {
+        balance: fee_balance(deps, &env)?,
+    })
+}
+
+fn query_issuers(
+    deps: Deps,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<IssuersResponse> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let mut start_after = start_after
+        .map(|address| deps.api.addr_validate(&address))
+        .transpose()?;
+
+    // an issuer is listed once, whatever the number of its circuits, so every
+    // issuer is a seek past the circuits of the previous one, not a scan over them
+    let mut issuers: Vec<Addr> = Vec::with_capacity(limit);
+    while issuers.len() < limit {
+        let start = start_after.as_ref().map(PrefixBound::exclusive);
+        let ((issuer, _), _) = match CIRCUITS
+            .prefix_range(deps.storage, start, None, Order::Ascending)
+            .next()
+        {
+            Some(item) => item?,
+            None => break,
+        };
+        start_after = Some(issuer.clone());
+        issuers.push(issuer);
+    }
+    Ok(IssuersResponse { issuers })
+}
+
+fn query_provers(
+    deps: Deps,
+    issuer_address: String,
+    circuit_id: Option<String>,
+    start_after: Option<String>,
+    limit: Option<u32>,
+) -> StdResult<ProversResponse> {
+    let issuer_addr = deps.api.addr_validate(&issuer_address)?;
+    let circuit_id = circuit_or_default(circuit_id);
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after
+        .map(|address| deps.api.addr_validate(&address))
+        .transpose()?;
+    let start = start_after.as_ref().map(Bound::exclusive);
+
+    let provers = PROVERLIST
+        .prefix((&issuer_addr, &circuit_id))
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(ProversResponse { provers })
+}
+
+fn query_solved_circuits(
+    deps: Deps,
+    prover_address: String,
+    start_after: Option<(String, String)>,
+    limit: Option<u32>,
+) -> StdResult<SolvedCircuitsResponse> {
+    let prover_addr = deps.api.addr_validate(&prover_address)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start_after = start_after
+        .map(|(address, circuit_id)| {
+            deps.api
+                .addr_validate(&address)
+                .map(|issuer| (issuer, circuit_id))
+        })
+        .transpose()?;
+    let start = start_after
+        .as_ref()
+        .map(|(issuer, circuit_id)| Bound::exclusive((issuer, circuit_id.as_str())));
+
+    let circuits = SOLVED
+        .sub_prefix(&prover_addr)
+        .keys(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| item.map(|(issuer, circuit_id)| CircuitRef { issuer, circuit_id }))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(SolvedCircuitsResponse { circuits })
+}
+
+fn query_prover_history(
+    deps: Deps,
+    prover_address: String,
+    start_after: Option<u64>,
+    limit: Option<u32>,
+) -> StdResult<ProverHistoryResponse> {
+    let prover_addr = deps.api.addr_validate(&prover_address)?;
+    let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+    let start = start_after.map(Bound::exclusive);
+
+    let verifications = PROVER_HISTORY
+        .prefix(&prover_addr)
+        .range(deps.storage, start, None, Order::Ascending)
+        .take(limit)
+        .map(|item| {
+            item.map(|(sequence, verification)| VerificationResponse {
+                sequence,
+                issuer: verification.issuer,
+                circuit_id: verification.circuit_id,
+                vkey_hash: verification.vkey_hash,
+                height: verification.height,
+            })
+        })
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(ProverHistoryResponse { verifications })
+}
+
+/// the checks of `verify_and_record` without recording anything
+fn query_verify_proof(
+    deps: Deps,
+    issuer_address: String,
+    circuit_id: Option<String>,
+    prover: Option<String>,
+    proof: Proof,
+    public_signals: Option<Vec<String>>,
+) -> StdResult<VerifyProofResponse> {
+    let issuer_addr = deps.api.addr_validate(&issuer_address)?;
+    let circuit_id = circuit_or_default(circuit_id);
+    let prover = prover
+        .map(|address| deps.api.addr_validate(&address))
+        .transpose()?;
+
+    let circuit = CIRCUITS.load(deps.storage, (&issuer_addr, &circuit_id))?;
+    let rejected = |public_signals: Vec<String>, err: ContractError| VerifyProofResponse {
+        is_valid: false,
+        proof_system: circuit.proof_system,
+        vkey_hash: circuit.vkey_hash.clone(),
+        public_signals,
+        nullifier_spent: None,
+        error: Some(err.to_string()),
+    };
+
+    if circuit.status == CircuitStatus::Retired {
+        return Ok(rejected(
+            vec![],
+            ContractError::CircuitRetired { circuit_id },
+        ));
+    }
+    let public_signals = match resolve_public_signals(&circuit, prover.as_ref(), public_signals) {
+        Ok(public_signals) => public_signals,
+        Err(err) => return Ok(rejected(vec![], err)),
+    };
+    let checked = circuit.proof_system.verifier().and_then(|verifier| {
+        if verifier.verify(&circuit.vkey, proof, &public_signals)? {
+            public_signal_keys(verifier, &public_signals)
+        } else {
+            Err(ContractError::InvalidProof {})
+        }
+    });
+    let public_inputs = match checked {
+        Ok(public_inputs) => public_inputs,
+        Err(err) => return Ok(rejected(public_signals, err)),
+    };
+
+    // a valid proof is still refused by `Proof` once its nullifier is spent
+    let nullifier_spent = circuit
+        .nullifier_index
+        .and_then(|index| public_inputs.get(index))
+        .map(|nullifier| NULLIFIERS.has(deps.storage, (&issuer_addr, &circuit_id, nullifier)));
+    Ok(VerifyProofResponse {
+        is_valid: true,
+        proof_system: circuit.proof_system,
+        vkey_hash: circuit.vkey_hash,
+        public_signals,
+        nullifier_spent,
+        error: None,
+    })
+}
+
+/// what the contract holds minus the rewards of open bounties
+fn fee_balance(deps: Deps, env: &Env) -> StdResult<Vec<Coin>> {
+    let mut balance = deps
+        .querier
+        .query_all_balances(env.contract.address.as_str())?;
+    for coin in balance.iter_mut() {
+        let escrowed = BOUNTY_ESCROW
+            .may_load(deps.storage, &coin.denom)?
+            .unwrap_or_default();
+        coin.amount = coin.amount.saturating_sub(escrowed);
+    }
+    balance.retain(|coin| !coin.amount.is_zero());
+    Ok(balance)
+}
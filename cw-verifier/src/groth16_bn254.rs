@@ -0,0 +1,90 @@
+use crate::verifier::{DecodedKey, PointEncoding, Proof, ProofSystem, Verifier, VerifyingKey};
+use crate::ContractError;
+use cosmwasm_std::{from_json, to_json_binary, Addr, Binary};
+use cw_groth16_bn::commitment::vkey_hash;
+use cw_groth16_bn::contract::{check_proof, decode_proof, decode_vkey, public_signal_key};
+use cw_groth16_bn::parser::prepare_vkey;
+use cw_groth16_bn::signal_helpers::sender_public_signal;
+use cw_groth16_bn::state::{PointEncoding as BackendEncoding, PreparedVkey};
+
+const SYSTEM: ProofSystem = ProofSystem::Groth16Bn254;
+
+/// groth16 over BN254, checked by cw-groth16-bn
+pub struct Groth16Bn254;
+
+impl Verifier for Groth16Bn254 {
+    fn decode_key(&self, key: VerifyingKey) -> Result<DecodedKey, ContractError> {
+        let vkeys = match key {
+            VerifyingKey::Groth16 {
+                vk_alpha1,
+                vk_beta_2,
+                vk_gamma_2,
+                vk_delta_2,
+                vk_ic,
+                point_encoding,
+            } => decode_vkey(
+                vk_alpha1,
+                vk_beta_2,
+                vk_gamma_2,
+                vk_delta_2,
+                vk_ic,
+                backend_encoding(point_encoding),
+            )
+            .map_err(|err| SYSTEM.backend_error(err))?,
+            _ => {
+                return Err(ContractError::KeyMismatch {
+                    proof_system: SYSTEM.to_string(),
+                })
+            }
+        };
+        let prepared = prepare_vkey(&vkeys).map_err(|err| SYSTEM.backend_error(err))?;
+        // ic[0] is the constant term, not a public input
+        let num_inputs = vkeys.ic.len().saturating_sub(1);
+        Ok(DecodedKey {
+            key: to_json_binary(&prepared)?,
+            vkey_hash: vkey_hash(&vkeys),
+            num_inputs,
+        })
+    }
+
+    fn verify(
+        &self,
+        key: &Binary,
+        proof: Proof,
+        public_signals: &[String],
+    ) -> Result<bool, ContractError> {
+        let proof_str = match proof {
+            Proof::Groth16 {
+                proof_a,
+                proof_b,
+                proof_c,
+                point_encoding,
+            } => decode_proof(proof_a, proof_b, proof_c, backend_encoding(point_encoding))
+                .map_err(|err| SYSTEM.backend_error(err))?,
+            _ => {
+                return Err(ContractError::ProofMismatch {
+                    proof_system: SYSTEM.to_string(),
+                })
+            }
+        };
+        let prepared: PreparedVkey = from_json(key)?;
+        let (is_passed, _) = check_proof(&prepared, proof_str, public_signals)
+            .map_err(|err| SYSTEM.backend_error(err))?;
+        Ok(is_passed)
+    }
+
+    fn public_signal_key(&self, public_signal: &str) -> Option<Vec<u8>> {
+        public_signal_key(public_signal)
+    }
+
+    fn sender_signal(&self, sender: &Addr) -> String {
+        sender_public_signal(sender)
+    }
+}
+
+fn backend_encoding(encoding: Option<PointEncoding>) -> BackendEncoding {
+    match encoding.unwrap_or_default() {
+        PointEncoding::Uncompressed => BackendEncoding::Uncompressed,
+        PointEncoding::Compressed => BackendEncoding::Compressed,
+    }
+}
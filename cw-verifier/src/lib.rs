@@ -0,0 +1,22 @@
+// message handlers take the fields of their message as arguments
+#![allow(clippy::too_many_arguments)]
+
+pub mod coin_helpers;
+pub mod commitment;
+pub mod contract;
+mod error;
+pub mod msg;
+pub mod state;
+pub mod verifier;
+
+#[cfg(feature = "groth16-bls12-381")]
+pub mod groth16_bls12_381;
+#[cfg(feature = "groth16-bn254")]
+pub mod groth16_bn254;
+#[cfg(feature = "plonk-bn254")]
+pub mod plonk_bn254;
+
+#[cfg(test)]
+mod tests;
+
+pub use crate::error::ContractError;
@@ -0,0 +1,357 @@
+use crate::state::{CircuitMetadata, CircuitStatus, Config, FeeMode, UnrelatedDenoms};
+use crate::verifier::{Proof, ProofSystem, VerifyingKey};
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, Coin, Uint128};
+use cw20::{Cw20Coin, Cw20CoinVerified, Cw20ReceiveMsg};
+
+#[cw_serde]
+pub struct InstantiateMsg {
+    pub set_zkeys_price: Option<Coin>,
+    pub publish_proof_price: Option<Coin>,
+    /// prices in a cw20 token, paid by sending the message to this contract with cw20 `Send`
+    pub set_zkeys_cw20_price: Option<Cw20Coin>,
+    pub publish_proof_cw20_price: Option<Cw20Coin>,
+    /// blocks a `CommitProof` stays revealable, defaults to 100
+    pub commitment_expiry: Option<u64>,
+    pub fee_mode: Option<FeeMode>,
+    pub unrelated_denoms: Option<UnrelatedDenoms>,
+}
+
+#[cw_serde]
+pub struct MigrateMsg {}
+
+/// the circuit of messages that don't name one
+pub const DEFAULT_CIRCUIT: &str = "default";
+
+/// Every action emits a `wasm-<type>` event:
+///
+/// - `zkeys_registered`: `issuer`, `circuit_id`, `vkey_hash`, `proof_system`, `fee`
+/// - `bounty_opened`: `issuer`, `circuit_id`, `reward`, `deadline`
+/// - `circuit_status_changed`: `issuer`, `circuit_id`, `status`
+/// - `proof_committed`: `issuer`, `circuit_id`, `prover`
+/// - `proof_verified`: `issuer`, `circuit_id`, `prover`, `vkey_hash`, `proof_system`, `fee`, `outcome`, `sequence`
+/// - `bounty_won`: `issuer`, `circuit_id`, `prover`
+/// - `bounty_paid`: `issuer`, `circuit_id`, `prover`, `reward`
+/// - `bounty_reclaimed`: `issuer`, `circuit_id`, `reward`
+/// - `fees_withdrawn`: `recipient`, `amount`
+///
+/// `proof_system` and `vkey_hash` are the values the single system contract would emit
+/// for the same key. `fee` is what the contract kept, comma separated `<amount><denom>` or
+/// `<amount><cw20 address>`. `outcome` is always `valid`, an invalid proof fails the
+/// transaction and emits nothing. `sequence` numbers the verification in the prover's
+/// `ProverHistory`.
+#[cw_serde]
+#[allow(clippy::large_enum_variant)]
+pub enum ExecuteMsg {
+    Zkeys {
+        /// names the key among the issuer's circuits, defaults to `DEFAULT_CIRCUIT`,
+        /// a registered circuit can't be replaced
+        circuit_id: Option<String>,
+        /// what the circuit proves and where it comes from, shown by the `Circuit` query
+        metadata: Option<CircuitMetadata>,
+        /// must be compiled in, see the `ProofSystems` query
+        proof_system: ProofSystem,
+        /// in the shape of `proof_system`
+        key: VerifyingKey,
+        /// the statement every proof is checked against, when left out provers bring their own
+        public_signals: Option<Vec<String>>,
+        /// append the prover's sender signal as the last public input, defaults to false
+        bind_sender: Option<bool>,
+        /// index of the public signal used as a nullifier, a nullifier is only accepted once
+        nullifier_index: Option<usize>,
+        /// reward escrowed for the first valid proof, sent on top of the registration fee
+        /// combine with `bind_sender` so a copied proof can't collect it
+        bounty: Option<Coin>,
+        /// block height after which an unclaimed bounty goes back to the issuer
+        bounty_deadline: Option<u64>,
+    },
+    Proof {
+        difficuty_issuer: String,
+        /// defaults to `DEFAULT_CIRCUIT`
+        circuit_id: Option<String>,
+        /// in the shape of the circuit's proof system
+        proof: Proof,
+        /// only for circuits registered without public signals
+        public_signals: Option<Vec<String>>,
+    },
+    /// first phase of a front-running safe submission, `commitment` is hex of
+    /// sha256(proof points || sender || salt) with the points of the proof's single system contract
+    CommitProof {
+        difficuty_issuer: String,
+        /// defaults to `DEFAULT_CIRCUIT`
+        circuit_id: Option<String>,
+        commitment: String,
+    },
+    /// second phase, at least one block after `CommitProof` and before it expires
+    RevealProof {
+        difficuty_issuer: String,
+        /// defaults to `DEFAULT_CIRCUIT`
+        circuit_id: Option<String>,
+        proof: Proof,
+        public_signals: Option<Vec<String>>,
+        salt: String,
+    },
+    /// move one of the sender's circuits to `status`, a retired circuit stays retired
+    SetCircuitStatus {
+        circuit_id: Option<String>,
+        status: CircuitStatus,
+    },
+    /// take back a bounty no proof has won after its deadline
+    ReclaimBounty { circuit_id: Option<String> },
+    /// pay a circuit's bounty to its winner, once every commitment made before
+    /// the winning one has expired
+    ClaimBounty {
+        difficuty_issuer: String,
+        circuit_id: Option<String>,
+    },
+    /// send collected fees to `recipient`, only the owner can withdraw
+    Withdraw {
+        amount: Vec<Coin>,
+        recipient: String,
+    },
+    /// send collected cw20 fees of `token` to `recipient`, only the owner can withdraw
+    WithdrawCw20 {
+        token: String,
+        amount: Uint128,
+        recipient: String,
+    },
+    /// a cw20 `Send` to this contract, `msg` is a `Zkeys`, `Proof` or `RevealProof`
+    /// message paid with the sent tokens
+    Receive(Cw20ReceiveMsg),
+}
+
+#[cw_serde]
+#[derive(QueryResponses)]
+#[allow(clippy::large_enum_variant)]
+pub enum QueryMsg {
+    #[returns(ConfigResponse)]
+    Config {},
+    /// the proof systems this build verifies
+    #[returns(ProofSystemsResponse)]
+    ProofSystems {},
+    /// proof system, metadata and status of a circuit
+    #[returns(CircuitResponse)]
+    Circuit {
+        issuer_address: String,
+        circuit_id: Option<String>,
+    },
+    /// circuits registered by `issuer_address`, ordered by id
+    #[returns(CircuitsResponse)]
+    Circuits {
+        issuer_address: String,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    #[returns(ProofResponse)]
+    ProofResult {
+        issuer_address: String,
+        circuit_id: Option<String>,
+        prover_address: String,
+    },
+    /// the public signal binding proofs to `address`, in the encoding of `proof_system`
+    #[returns(SenderSignalResponse)]
+    SenderSignal {
+        address: String,
+        proof_system: ProofSystem,
+    },
+    #[returns(CommitmentResponse)]
+    Commitment {
+        issuer_address: String,
+        circuit_id: Option<String>,
+        prover_address: String,
+    },
+    /// `nullifier` is read as a public signal of the circuit's proof system
+    #[returns(NullifierResponse)]
+    Nullifier {
+        issuer_address: String,
+        circuit_id: Option<String>,
+        nullifier: String,
+    },
+    #[returns(BountyResponse)]
+    Bounty {
+        address: String,
+        circuit_id: Option<String>,
+    },
+    #[returns(FeeBalanceResponse)]
+    FeeBalance {},
+    /// issuers with a registered circuit, ordered by address length and then address
+    #[returns(IssuersResponse)]
+    Issuers {
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// provers credited with a proof for a circuit of `issuer_address`
+    #[returns(ProversResponse)]
+    Provers {
+        issuer_address: String,
+        circuit_id: Option<String>,
+        start_after: Option<String>,
+        limit: Option<u32>,
+    },
+    /// circuits `prover_address` is credited with a proof for, ordered like `Issuers` and then by id,
+    /// `start_after` is an (issuer, circuit id) pair
+    #[returns(SolvedCircuitsResponse)]
+    SolvedCircuits {
+        prover_address: String,
+        start_after: Option<(String, String)>,
+        limit: Option<u32>,
+    },
+    /// every proof verified for `prover_address`, oldest first
+    #[returns(ProverHistoryResponse)]
+    ProverHistory {
+        prover_address: String,
+        start_after: Option<u64>,
+        limit: Option<u32>,
+    },
+    /// check a proof against a registered circuit without paying for it or recording it,
+    /// why a proof is rejected is explained in the response
+    #[returns(VerifyProofResponse)]
+    VerifyProof {
+        issuer_address: String,
+        circuit_id: Option<String>,
+        /// needed when the circuit binds the sender
+        prover: Option<String>,
+        proof: Proof,
+        /// only for circuits registered without public signals
+        public_signals: Option<Vec<String>>,
+    },
+}
+
+#[cw_serde]
+pub struct ConfigResponse {
+    pub owner: Addr,
+    pub zkeys_price: Option<Coin>,
+    pub proof_price: Option<Coin>,
+    pub zkeys_cw20_price: Option<Cw20CoinVerified>,
+    pub proof_cw20_price: Option<Cw20CoinVerified>,
+    pub fee_mode: FeeMode,
+    pub unrelated_denoms: UnrelatedDenoms,
+    pub commitment_expiry: u64,
+}
+
+impl From<Config> for ConfigResponse {
+    fn from(config: Config) -> ConfigResponse {
+        ConfigResponse {
+            owner: config.owner,
+            zkeys_price: config.zkeys_price,
+            proof_price: config.proof_price,
+            zkeys_cw20_price: config.zkeys_cw20_price,
+            proof_cw20_price: config.proof_cw20_price,
+            fee_mode: config.fee_mode,
+            unrelated_denoms: config.unrelated_denoms,
+            commitment_expiry: config.commitment_expiry,
+        }
+    }
+}
+
+#[cw_serde]
+pub struct ProofSystemsResponse {
+    pub proof_systems: Vec<ProofSystem>,
+}
+
+#[cw_serde]
+pub struct CircuitResponse {
+    pub issuer: Addr,
+    pub circuit_id: String,
+    pub proof_system: ProofSystem,
+    pub vkey_hash: String,
+    /// `None` when provers bring their own
+    pub public_signals: Option<Vec<String>>,
+    pub bind_sender: bool,
+    pub nullifier_index: Option<usize>,
+    pub metadata: CircuitMetadata,
+    pub status: CircuitStatus,
+}
+
+#[cw_serde]
+pub struct CircuitsResponse {
+    pub circuit_ids: Vec<String>,
+}
+
+#[cw_serde]
+pub struct ProofResponse {
+    pub proof: Proof,
+    /// the public signals the proof was checked against, a bound sender signal included
+    pub public_signals: Vec<String>,
+    pub is_valid: bool,
+}
+
+#[cw_serde]
+pub struct SenderSignalResponse {
+    pub public_signal: String,
+}
+
+#[cw_serde]
+pub struct CommitmentResponse {
+    pub commitment: String,
+    pub height: u64,
+    pub expires_at: u64,
+}
+
+#[cw_serde]
+pub struct NullifierResponse {
+    pub spent: bool,
+}
+
+#[cw_serde]
+pub struct FeeBalanceResponse {
+    pub balance: Vec<Coin>,
+}
+
+#[cw_serde]
+pub struct BountyResponse {
+    pub reward: Coin,
+    pub deadline: u64,
+    /// the prover the bounty goes to, unless an earlier commitment is revealed
+    pub winner: Option<Addr>,
+    /// the height from which `ClaimBounty` pays the winner
+    pub claimable_at: Option<u64>,
+}
+
+#[cw_serde]
+pub struct IssuersResponse {
+    pub issuers: Vec<Addr>,
+}
+
+#[cw_serde]
+pub struct CircuitRef {
+    pub issuer: Addr,
+    pub circuit_id: String,
+}
+
+#[cw_serde]
+pub struct SolvedCircuitsResponse {
+    pub circuits: Vec<CircuitRef>,
+}
+
+#[cw_serde]
+pub struct ProversResponse {
+    pub provers: Vec<Addr>,
+}
+
+#[cw_serde]
+pub struct VerificationResponse {
+    pub sequence: u64,
+    pub issuer: Addr,
+    pub circuit_id: String,
+    pub vkey_hash: String,
+    pub height: u64,
+}
+
+#[cw_serde]
+pub struct ProverHistoryResponse {
+    pub verifications: Vec<VerificationResponse>,
+}
+
+#[cw_serde]
+pub struct VerifyProofResponse {
+    pub is_valid: bool,
+    pub proof_system: ProofSystem,
+    pub vkey_hash: String,
+    /// the public signals the proof was checked against, a bound sender signal included
+    pub public_signals: Vec<String>,
+    /// whether `Proof` would refuse the nullifier, only for circuits with one
+    pub nullifier_spent: Option<bool>,
+    /// why the proof was rejected
+    pub error: Option<String>,
+}
@@ -0,0 +1,131 @@
+use crate::verifier::{DecodedKey, PlonkParams, Proof, ProofSystem, Verifier, VerifyingKey};
+use crate::ContractError;
+use cosmwasm_std::{from_json, to_json_binary, Addr, Binary};
+use cw_plonk::commitment::vkey_hash;
+use cw_plonk::contract::{
+    bellman_proof, check_proof, check_vkey, decode_proof, decode_vkey, public_signal_key,
+};
+use cw_plonk::signal_helpers::sender_input_value;
+use cw_plonk::state::{PlonkParams as BackendParams, VkeyStr};
+use cw_plonk::ContractError as BackendError;
+
+const SYSTEM: ProofSystem = ProofSystem::PlonkBn254;
+
+/// plonk over BN254 in the bellman parameter sets of cw-plonk, checked by cw-plonk. Proofs
+/// are taken in the bellman layout, so snarkjs keys are refused as cw-plonk's bellman messages refuse them.
+/// Public signals are the hex input values of cw-plonk
+pub struct PlonkBn254;
+
+impl Verifier for PlonkBn254 {
+    fn decode_key(&self, key: VerifyingKey) -> Result<DecodedKey, ContractError> {
+        let vkeys = match key {
+            VerifyingKey::Plonk {
+                n,
+                num_inputs,
+                selector_commitments,
+                next_step_selector_commitments,
+                permutation_commitments,
+                non_residues,
+                g2_elements,
+                params,
+            } => decode_vkey(
+                n,
+                num_inputs,
+                selector_commitments,
+                next_step_selector_commitments,
+                permutation_commitments,
+                non_residues,
+                g2_elements,
+                backend_params(params),
+            )
+            .map_err(|err| SYSTEM.backend_error(err))?,
+            _ => {
+                return Err(ContractError::KeyMismatch {
+                    proof_system: SYSTEM.to_string(),
+                })
+            }
+        };
+        if vkeys.params == BackendParams::Snarkjs {
+            return Err(SYSTEM.backend_error(BackendError::SnarkjsCircuit {}));
+        }
+        check_vkey(&vkeys).map_err(|err| SYSTEM.backend_error(err))?;
+        Ok(DecodedKey {
+            key: to_json_binary(&vkeys)?,
+            vkey_hash: vkey_hash(&vkeys),
+            num_inputs: vkeys.num_inputs,
+        })
+    }
+
+    fn verify(
+        &self,
+        key: &Binary,
+        proof: Proof,
+        public_signals: &[String],
+    ) -> Result<bool, ContractError> {
+        let vkeys: VkeyStr = from_json(key)?;
+        if public_signals.len() != vkeys.num_inputs {
+            return Err(ContractError::PublicSignalCountMismatch {
+                expected: vkeys.num_inputs,
+                actual: public_signals.len(),
+            });
+        }
+        // the domain size and input count of a plonk proof are those of its key
+        let proof_str = match proof {
+            Proof::Plonk {
+                wire_commitments,
+                grand_product_commitment,
+                quotient_poly_commitments,
+                wire_values_at_z,
+                wire_values_at_z_omega,
+                grand_product_at_z_omega,
+                quotient_polynomial_at_z,
+                linearization_polynomial_at_z,
+                permutation_polynomials_at_z,
+                opening_at_z_proof,
+                opening_at_z_omega_proof,
+            } => decode_proof(
+                vkeys.num_inputs,
+                vkeys.n,
+                public_signals.to_vec(),
+                wire_commitments,
+                grand_product_commitment,
+                quotient_poly_commitments,
+                wire_values_at_z,
+                wire_values_at_z_omega,
+                grand_product_at_z_omega,
+                quotient_polynomial_at_z,
+                linearization_polynomial_at_z,
+                permutation_polynomials_at_z,
+                opening_at_z_proof,
+                opening_at_z_omega_proof,
+            )
+            .map_err(|err| SYSTEM.backend_error(err))?,
+            _ => {
+                return Err(ContractError::ProofMismatch {
+                    proof_system: SYSTEM.to_string(),
+                })
+            }
+        };
+        let proof_str =
+            bellman_proof(&vkeys, proof_str).map_err(|err| SYSTEM.backend_error(err))?;
+        let (is_passed, _) =
+            check_proof(vkeys, proof_str).map_err(|err| SYSTEM.backend_error(err))?;
+        Ok(is_passed)
+    }
+
+    fn public_signal_key(&self, public_signal: &str) -> Option<Vec<u8>> {
+        public_signal_key(public_signal)
+    }
+
+    fn sender_signal(&self, sender: &Addr) -> String {
+        sender_input_value(sender)
+    }
+}
+
+fn backend_params(params: Option<PlonkParams>) -> BackendParams {
+    match params.unwrap_or_default() {
+        PlonkParams::Width4WithNextStep => BackendParams::Width4WithNextStep,
+        PlonkParams::Width3WithNextStep => BackendParams::Width3WithNextStep,
+        PlonkParams::Snarkjs => BackendParams::Snarkjs,
+    }
+}
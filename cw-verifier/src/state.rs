@@ -0,0 +1,143 @@
+use crate::verifier::{Proof, ProofSystem};
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Binary, Coin, Empty, Uint128};
+use cw20::Cw20CoinVerified;
+use cw_storage_plus::{Item, Map};
+
+#[cw_serde]
+pub struct Config {
+    pub owner: Addr,
+    pub zkeys_price: Option<Coin>,
+    pub proof_price: Option<Coin>,
+    // the same prices in a cw20 token, paid through `Receive`
+    pub zkeys_cw20_price: Option<Cw20CoinVerified>,
+    pub proof_cw20_price: Option<Cw20CoinVerified>,
+    pub fee_mode: FeeMode,
+    pub unrelated_denoms: UnrelatedDenoms,
+    // blocks a proof commitment stays revealable
+    pub commitment_expiry: u64,
+}
+
+/// how a price is taken from the funds sent with a message
+#[cw_serde]
+#[derive(Default)]
+pub enum FeeMode {
+    /// keep everything sent as long as it covers the price
+    #[default]
+    Sufficient,
+    /// take exactly the price and refund the rest to the sender
+    Exact,
+}
+
+/// what `FeeMode::Exact` does with coins in a denom other than the price
+#[cw_serde]
+#[derive(Default)]
+pub enum UnrelatedDenoms {
+    #[default]
+    Refund,
+    Reject,
+}
+
+/// where a circuit is in its life, only its issuer moves it along
+#[cw_serde]
+#[derive(Default)]
+pub enum CircuitStatus {
+    #[default]
+    Active,
+    /// still accepts proofs, provers should move to the circuit replacing it
+    Deprecated,
+    /// refuses new proofs and commitments, what was already proven stays queryable
+    Retired,
+}
+
+/// what a circuit proves and where its key comes from, as told by the issuer
+#[cw_serde]
+#[derive(Default)]
+pub struct CircuitMetadata {
+    pub name: String,
+    pub description: String,
+    // where the circuit source can be found, e.g. a repository or an ipfs uri
+    pub source_uri: String,
+    // hex sha256 of the artifact the key was exported from, e.g. the circom file or the zkey
+    pub artifact_hash: String,
+}
+
+#[cw_serde]
+pub struct Circuit {
+    pub proof_system: ProofSystem,
+    // the key as decoded by the backend of `proof_system`
+    pub vkey: Binary,
+    pub vkey_hash: String,
+    // fixed by the issuer, otherwise every proof brings its own
+    pub public_signals: Option<Vec<String>>,
+    // append the prover's sender signal to the public inputs
+    pub bind_sender: bool,
+    // public input index whose value can only be used once
+    pub nullifier_index: Option<usize>,
+    pub metadata: CircuitMetadata,
+    pub status: CircuitStatus,
+}
+
+#[cw_serde]
+pub struct ProofInfo {
+    pub proof: Proof,
+    pub public_signals: Vec<String>,
+    pub is_valid: bool,
+}
+
+/// a proof verified for a prover, one entry of their history
+#[cw_serde]
+pub struct Verification {
+    pub issuer: Addr,
+    pub circuit_id: String,
+    // hex sha256 of the verification key the proof was checked against
+    pub vkey_hash: String,
+    pub height: u64,
+}
+
+/// a reward for the earliest committed valid proof, held by the contract until it is claimed
+#[cw_serde]
+pub struct Bounty {
+    pub reward: Coin,
+    // block height after which the issuer can reclaim the reward
+    pub deadline: u64,
+    // the earliest committed valid proof so far, paid once no earlier commitment can be revealed
+    pub winner: Option<ProofClaim>,
+}
+
+#[cw_serde]
+pub struct Commitment {
+    pub hash: Vec<u8>,
+    pub height: u64,
+}
+
+/// the prover credited for a statement, and when they committed to their proof of it
+#[cw_serde]
+pub struct ProofClaim {
+    pub prover: Addr,
+    pub committed_at: u64,
+}
+
+pub const CONFIG: Item<Config> = Item::new("config");
+
+// issuer -> [ circuit id -> circuit ]
+pub const CIRCUITS: Map<(&Addr, &str), Circuit> = Map::new("circuits");
+
+// issuer -> [ circuit id -> [ prover -> proofInfo ] ]
+pub const PROVERLIST: Map<(&Addr, &str, &Addr), ProofInfo> = Map::new("circuit_provers");
+// prover -> [ issuer -> [ circuit id ] ], the circuits listing a prover in PROVERLIST
+pub const SOLVED: Map<(&Addr, &Addr, &str), Empty> = Map::new("solved");
+// prover -> [ sequence -> verification ], only ever appended to
+pub const PROVER_HISTORY: Map<(&Addr, u64), Verification> = Map::new("prover_history");
+// prover -> sequence of their next verification
+pub const PROVER_SEQUENCE: Map<&Addr, u64> = Map::new("prover_sequence");
+// issuer -> [ circuit id -> [ prover -> commitment ] ]
+pub const COMMITMENTS: Map<(&Addr, &str, &Addr), Commitment> = Map::new("commitments");
+// issuer -> [ circuit id -> [ statement hash, per prover for issuer signals -> claim ] ]
+pub const PROOF_CLAIMS: Map<(&Addr, &str, &[u8]), ProofClaim> = Map::new("proof_claims");
+// issuer -> [ circuit id -> [ nullifier -> hash of the statement that spent it ] ]
+pub const NULLIFIERS: Map<(&Addr, &str, &[u8]), Vec<u8>> = Map::new("nullifiers");
+// issuer -> [ circuit id -> bounty ]
+pub const BOUNTIES: Map<(&Addr, &str), Bounty> = Map::new("bounties");
+// denom -> amount held for open bounties
+pub const BOUNTY_ESCROW: Map<&str, Uint128> = Map::new("bounty_escrow");
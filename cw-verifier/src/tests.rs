@@ -0,0 +1,1846 @@
+#[cfg(test)]
+// the fixtures of the proof systems a build leaves out go unused
+#[cfg_attr(
+    not(all(
+        feature = "groth16-bls12-381",
+        feature = "groth16-bn254",
+        feature = "plonk-bn254"
+    )),
+    allow(unused_imports, dead_code)
+)]
+mod test_module {
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_dependencies_with_balance, mock_env, mock_info,
+    };
+    use cosmwasm_std::{
+        coin, coins, from_json, to_json_binary, Addr, BankMsg, Coin, Deps, DepsMut, Env, Response,
+        StdError, SubMsg, Uint128, WasmMsg,
+    };
+    use cw20::{Cw20Coin, Cw20ExecuteMsg, Cw20ReceiveMsg};
+
+    use crate::commitment::proof_commitment;
+    use crate::contract::{execute, instantiate, query};
+    use crate::error::ContractError;
+    use crate::msg::{
+        BountyResponse, CircuitRef, CircuitResponse, CircuitsResponse, CommitmentResponse,
+        ConfigResponse, ExecuteMsg, FeeBalanceResponse, InstantiateMsg, IssuersResponse,
+        NullifierResponse, ProofResponse, ProofSystemsResponse, ProverHistoryResponse,
+        ProversResponse, QueryMsg, SenderSignalResponse, SolvedCircuitsResponse,
+        VerificationResponse, VerifyProofResponse, DEFAULT_CIRCUIT,
+    };
+    use crate::state::{CircuitMetadata, CircuitStatus, FeeMode};
+    use crate::verifier::{PlonkParams, PointEncoding, Proof, ProofSystem, VerifyingKey};
+    #[cfg(feature = "plonk-bn254")]
+    use crate::{plonk_bn254::PlonkBn254, verifier::Verifier};
+
+    fn mock_init_no_price(deps: DepsMut) {
+        let msg = InstantiateMsg {
+            set_zkeys_price: None,
+            publish_proof_price: None,
+            set_zkeys_cw20_price: None,
+            publish_proof_cw20_price: None,
+            commitment_expiry: None,
+            fee_mode: None,
+            unrelated_denoms: None,
+        };
+
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = instantiate(deps, mock_env(), info, msg)
+            .expect("contract successfully handles InstantiateMsg");
+    }
+
+    fn mock_init_with_price(deps: DepsMut, zkeys_price: Coin, proof_price: Coin) {
+        let msg = InstantiateMsg {
+            set_zkeys_price: Some(zkeys_price),
+            publish_proof_price: Some(proof_price),
+            set_zkeys_cw20_price: None,
+            publish_proof_cw20_price: None,
+            commitment_expiry: None,
+            fee_mode: None,
+            unrelated_denoms: None,
+        };
+
+        let info = mock_info("creator", &coins(2, "token"));
+        let _res = instantiate(deps, mock_env(), info, msg)
+            .expect("contract successfully handles InstantiateMsg");
+    }
+
+    #[cfg(feature = "groth16-bls12-381")]
+    fn groth16_bls12_381_key() -> VerifyingKey {
+        VerifyingKey::Groth16 {
+            vk_alpha1: "121ec6cddca2aa0dedf8dbb86e96dc4100b58ea07c01d7ea68a37f8f72191ab2bbe9f16bfe675f71c899ff11e23cbb04064831acc8c18f561f446eeaac3a9a056cb9a89b0b3f13a57eab4e97ebaff6f0a39327bd0a4b5f725d633c87474d35f2".to_string(),
+            vk_beta_2: "00f3edfbbbe5e2dab32cac1d1ba2f0fdd9eff4067c7152520f0ebbf556c21f98e72590b3cdb614b1ea116991305da942077b7419fac8cc2d38dc6639d68a4cf7c8362efd8395020836f3aa564537fa02a17f2d1b423c19b6cf4784037b1d9f1510afbae9e95703ff3a98c46720f05e642588ef21ccb09580c84d211c0fd60acda18a699f61cef4925b9b113c8a2377f0147c5ee0882a97519627776222438d3e29f581f0e4b61fe18ab42089dfe24a1b9d7376667382941e37329860ec84d105".to_string(),
+            vk_gamma_2: "13e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb80606c4a02ea734cc32acd2b02bc28b99cb3e287e85a763af267492ab572e99ab3f370d275cec1da1aaa9075ff05f79be0ce5d527727d6e118cc9cdc6da2e351aadfd9baa8cbdd3a76d429a695160d12c923ac9cc3baca289e193548608b82801".to_string(),
+            vk_delta_2: "07709351aa9646a1311053a0e4cfe6d7db03513beaebb9b87da192758e5ecd40d21ac535e7664e78d669399de703cb72109a5d6b3943018f1dd43462eb71be512213f05e61b2c93bc5f65d270bf78122b00e24d38b0f98efdee072cf3b4c8d0a1905dcb70f21d51fdd376d5fcd258df6c3477a2421527d1702b848954fd7a3bbf710eda0c1880b79a996516ec37d616c13082219d90a7743ad8eb5e3faceec7ad6374029d52eaeca7b66c598b3dd7066e4b6246cea47794fdcffcf7891984272".to_string(),
+            vk_ic: vec![
+                "16aca3c7fb4157ef2f70fa4098434d97721a2ffa30f1ed64d3123cccb3928433899ab147217331f74f18ce687cc591700e79ca556db5b53e92f1133b889dbc11ef79615331a9a810cbef02d3a760b437a1bd50c1b6c396288abcb37479bc18a5".to_string(),
+                "0c1aeb08622db17dab3de7590db8f46349c7e08eff70fa63af8332db75b977bd0e630b04d8e28d4b3416381b27f4bded12e8067fd6f65bd436608cf66f0eb0c19b7da57b72785966d71b91229cde327918d14b3330b891bdfcf255e3d0ecfbfd".to_string(),
+            ],
+            point_encoding: None,
+        }
+    }
+
+    #[cfg(feature = "groth16-bls12-381")]
+    fn groth16_bls12_381_proof() -> Proof {
+        Proof::Groth16 {
+            proof_a: "020fcbc0e3ae8e322a5cb6fb707d2511878eda020096f8f421cf75c263e43c2ee6d2392b6e03410d5555fd80628581f1054ce4ab8c9c277ce545b05efc145a1aeecd84038c67972a55367b2e1181c19311a7b3a3aa2b2cd70c4823db3ae498a0".to_string(),
+            proof_b: "1545a18455dd6e1abaf4e27f3ee198bb5abb199b0650030593ce0d03b7cc59d458864acc3db510efe2300f778aa797e017c8d8fa15654b1995f0e659910bbdf8c0d88ef6801e1615e664b559daa8fd139b88569e95e6058d077fb5ae6aafe93116d6254de64023b0e8b41b145bb43d53bbee70486de6dd67c00f4f05c5e6a563f3b808b942184fa3488ace3a57e90016106fcc94b8d3d95c52ca1a616348b9095e7df0ba97156e4e93e787474d19e0ea423eda0bf5ebc81efce1b12f4c22ee00".to_string(),
+            proof_c: "10406a2ada964c701668b06be2e3011bf22d9b6c6c0731f5b042a6b7ccf777d58b8e8b8b19fd711953b170d591981eb80f373990aee796b4797bb6ee63f57cbc402ce8dc2360ef18e40c5a44e8d2948e94d6c7f226f384f6cf4c0190de295b87".to_string(),
+            point_encoding: None,
+        }
+    }
+
+    #[cfg(feature = "groth16-bn254")]
+    fn groth16_bn254_key() -> VerifyingKey {
+        VerifyingKey::Groth16 {
+            vk_alpha1: "134341fbe5f0719617003adb9c8fe9038d5d913d1a1e961618cd67f8f097d0cb203a9e851d18a4cfe8ab963083acda4af394c8c2461930397057da9edf030d4c".to_string(),
+            vk_beta_2: "26e36c244fbd85b1f96bb3c4eecc5024f9e0507247e6675e56d5d222f43921872c13498425fa4b090c401561092dac563a0864ef22aa2bcfcf0e75bda8ad94aa10e1a9938cab807dc19806127b49d697de33abf79ad5ae46ca240927dd9c57d623c3cad4c8c16360c9199a701b707474fd6bd47e8841d4ebb7a88b826459535d".to_string(),
+            vk_gamma_2: "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c21800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa".to_string(),
+            vk_delta_2: "0c80d2c61aaac33c924c322ed740b6ed3775eb171b173c09640a29aa1322e5450dd122b23f09aab8b1a3fd739d296949f328fd2cd9deedc6d1ed3262803a19e804b9033fb8a3476eb8b5e609f529cc52fbaa9df6f59a5a67aab944cde646d13f2d17ed688afb5f3abc97f978fe9da3a25feee8bfbb0762ae80be6aabb76a742d".to_string(),
+            vk_ic: vec![
+                "22f4a08cff59356634d3cd5ad41e85e4b4b484a7633eb64ddd739032936add322101b08d07ff3315947df2a6652e667d6a3e96fca1a661aadc2092a85c3912fd".to_string(),
+                "17fa0f95ec76599763a6629b557bf18fd938305c7472fd81c368f8ca76615cee037b264dee54b6fd3298fd0396525cb1aec0dd971e3ab771f242ce10980763cd".to_string(),
+            ],
+            point_encoding: None,
+        }
+    }
+
+    #[cfg(feature = "groth16-bn254")]
+    fn groth16_bn254_proof() -> Proof {
+        Proof::Groth16 {
+            proof_a: "2a7efa6d4fee4a2df464f6c926a81e709ecf27642f4f99aa5c90bc479ce1122a149e00a4b97ea4ef3caec4d5ab168eb0effa1441ee448678d6e77caa2d19f3b2".to_string(),
+            proof_b: "023290eac0dc45935bb65780f2dd380c594b207509fceeb768c8d9a33a530c640a3cc26b8fa867e1484bbe1c98131cdaad2c48a370688e259f1aff52a0d393872c41442472714933964a28c649c2ebe4608f08e8e0dd023bb90df134f45d20281c7c24fd81f0affa2450181480411973b2d7b52683fdd9d4e3068ef6b5054296".to_string(),
+            proof_c: "24102019b76cd1f917b5e765519f65504ecfebfb5f4a11a168fd29048e004e0f03b05f4cd80703e6f7f51c7c3394253a4900f0186386be4015d363425ea27488".to_string(),
+            point_encoding: None,
+        }
+    }
+
+    #[cfg(feature = "plonk-bn254")]
+    fn plonk_bn254_key() -> VerifyingKey {
+        VerifyingKey::Plonk {
+            n: 3,
+            num_inputs: 1,
+            selector_commitments: vec![
+                "09b3a8742e323fbb6b7e858287af59c6ff997667de6f10136356774a5e93fe872fd1ef45f38c9c0814183b2ca7eba4e2d5d5d7f871bb1a89e96217df74c9833d".to_string(),
+                "40000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000".to_string(),
+                "2b63946d2ccf8529ae7ba5324902e7ee834b19e8dd666b3533850c2ed36d7dfe2738c050f06ba19dd919bbb1e55c408ceadc583bbd190f10f54a9b79bb2b03da".to_string(),
+                "40000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000".to_string(),
+                "2b63946d2ccf8529ae7ba5324902e7ee834b19e8dd666b3533850c2ed36d7dfe092b8e21f0c5fe8bdf368a049c2517d0aca51255ab58bb7c46d5f09d1d51f96d".to_string(),
+                "40000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000".to_string(),
+            ],
+            next_step_selector_commitments: vec![
+                "40000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000".to_string(),
+            ],
+            permutation_commitments: vec![
+                "1516cf1540873838abfa4e7501f523c2c56ce33a33f2cefc2da70cb30795bd610182f97175b9b3bc30e175307758894dd2938fcf556669f9820f1c1d2089f379".to_string(),
+                "19c5d1b7df1125f24646c8e7ac90689c6cd593703e7cd4d7918e3d7c9c2f220a0daf49f7dfa3f8d741df0efcef1f171c961b47f8b28d46a2af00be85c8b6e713".to_string(),
+                "14ab37e299bdf4502f0034f4ee42281108e1ae6eab93e1c435e1aa68beb5153622def6b03001792d9fd384af642febcc6cc821726783d359ebfe6b30c9c0915a".to_string(),
+                "1f8dbc422d4aabab7112ee68c336bbe2bbe095d6d7fa6e5a7f1292ea7487412f0beb14b6bfb14c16a44f4e37d98e401cb38c6221b9f4ccebc52ec8088c44a2a8".to_string(),
+            ],
+            non_residues: vec![
+                "0000000000000000000000000000000000000000000000000000000000000005".to_string(),
+                "0000000000000000000000000000000000000000000000000000000000000007".to_string(),
+                "000000000000000000000000000000000000000000000000000000000000000a".to_string(),
+            ],
+            g2_elements: vec![
+                "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c21800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa".to_string(),
+                "12740934ba9615b77b6a49b06fcce83ce90d67b1d0e2a530069e3a7306569a91116da8c89a0d090f3d8644ada33a5f1c8013ba7204aeca62d66d931b99afe6e725222d9816e5f86b4a7dedd00d04acc5c979c18bd22b834ea8c6d07c0ba441db076441042e77b6309644b56251f059cf14befc72ac8a6157d30924e58dc4c172".to_string(),
+            ],
+            params: None,
+        }
+    }
+
+    #[cfg(feature = "plonk-bn254")]
+    fn plonk_bn254_proof() -> Proof {
+        Proof::Plonk {
+            wire_commitments: vec![
+                "1c7ade6b7b63a79bbdf4380ead5793e175e904e8a659019474f25263121599f70a5f7d13d1d549c1b5fa4695d519dce3567aa336d0ced40ce00e6dd9ad77d8c5".to_string(),
+                "238e7e9105d66b54ebcf23b1ac4a8cc9179850a8bb3d9ca0495e5420ff6318901a82fb4a34ed559e3f25de9b0ca7903a7e0692bee2705809e59ed50355e89920".to_string(),
+                "238e7e9105d66b54ebcf23b1ac4a8cc9179850a8bb3d9ca0495e5420ff6318901a82fb4a34ed559e3f25de9b0ca7903a7e0692bee2705809e59ed50355e89920".to_string(),
+                "40000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000".to_string(),
+            ],
+            grand_product_commitment: "2ebc09f9ada0ba725ea8d6c06d4c49e156578fc34388aff597a2ef4c94113eab10c2861217b6f698e334359465f85d23bcd3467a72ccefa92fcdef5cf63bb774".to_string(),
+            quotient_poly_commitments: vec![
+                "1f3e303dc35d69a2886a3831bb88d927273367eda400c1a1195649f7424ce49103668c478ed318e56e3b4f7104877fd0f8255d4a2d39b019cbaba99a0f3285c5".to_string(),
+                "19019a506cb3f41e5748e268b32ba946af045a8b4015561fd36e13f001a201ec1fed0f9d8b21e555372e80803621df8c7179f800e37a3cfbcc48ea6c18fe68ba".to_string(),
+                "077cd81c81628f91c271a9c78c5e3c208b16db7af4135e9d4484aeb3fe74949f125e6bf4e044a1ebb40dca3b9f7f6298d5e6cdacc2abbbea467293987d864184".to_string(),
+                "40000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000".to_string(),
+            ],
+            wire_values_at_z: vec![
+                "24bfaac457bacdfb08eb4cac55f60f11f5f691b15e048a4fe0c3016991d92aeb".to_string(),
+                "28226e41e356f1e4de481016a45f3f9c07e17e966dda962f4a088d1b325684e9".to_string(),
+                "28226e41e356f1e4de481016a45f3f9c07e17e966dda962f4a088d1b325684e9".to_string(),
+                "0000000000000000000000000000000000000000000000000000000000000000".to_string(),
+            ],
+            wire_values_at_z_omega: vec![
+                "0000000000000000000000000000000000000000000000000000000000000000".to_string(),
+            ],
+            grand_product_at_z_omega: "1ea357b0967d029cb8714f44c66f447ccff76231ff86ea9416c0e0f60a4e40ed".to_string(),
+            quotient_polynomial_at_z: "0d3743a423440f9130eb1c36815f549442f836c734f38e1a75278d515f5c40f8".to_string(),
+            linearization_polynomial_at_z: "00c87a6192b8985101007b6280c4d90435b4f31cc20e833367637e299c957857".to_string(),
+            permutation_polynomials_at_z: vec![
+                "0853bcc8f41416377af6ad7364aa07a1b981e3f1aa98ccab9b7b26dc197f30cd".to_string(),
+                "2bc9fec71fdd891baff67f9af178e16c2c51b3c609b3fd372bbc01100fe1eb0b".to_string(),
+                "1ab9c6f84d62c1d272cb3b3efa25d288720a396c091d3da6a1c0b37e266053d3".to_string(),
+            ],
+            opening_at_z_proof: "1d99eae30fa0e2d2a330647c3245eceb92dcc0fae92308f6003cd628e6694e682ff1a84ee025d6609fa9b16a29ff7e5bf5ea08932bd6813b989a084d28cb72c4".to_string(),
+            opening_at_z_omega_proof: "2bcf1e082d97cbc88e318001fc8588be7efb1d60624d8917c9babdde02469a402bb78b2bb7e8e76635d6e34674f6255b05558b8a2de52ff00535cec6bccca8a5".to_string(),
+        }
+    }
+
+    #[cfg(feature = "plonk-bn254")]
+    fn plonk_bn254_public_signals() -> Vec<String> {
+        vec!["0000000000000000000000000000000000000000000000000000000000000021".to_string()]
+    }
+
+    fn groth16_public_signals() -> Vec<String> {
+        vec!["33".to_string()]
+    }
+
+    /// proves w * w = 25 with the sender signal as second public input
+    #[cfg(feature = "groth16-bls12-381")]
+    fn groth16_bls12_381_bound_key() -> VerifyingKey {
+        VerifyingKey::Groth16 {
+            vk_alpha1: "01eddf6b8a05caf98fb73de9fc29c281f16adb3b3e17357bbd548c3d56c491fa4ad357827f12de02b81f42cdaaf43171078dd4ec8ddba86fe301f9099e7244b618b92eb8f19bef3c83cdc6ef7be497f1f257728c960001ba2ce54eddff8efe08".to_string(),
+            vk_beta_2: "0ebd827449d55bf11e1b8e40d81c57bb79f5287ea910f27829666737335341d3922541e4962d446e11e8fae3fee77c950cf1bf2d438da16f7f2fcce372855722b4b1a4c5b35cc0d40a3f5d1c6e0d07505c8ca9eaac271c5130b2273171bb88e80fa18e262fea34c820e727ad43c4c04e7477da9e61a1900d988d77f87790acfcb7ca52a9aa1e231d1aa40cc7767a7cc91207e9d76bb22ed8d0fd1ec1b8c1d38b542f6d9950cf68dcafa55057d06452683cf63b6b3df3f7776c7f51312fb12f47".to_string(),
+            vk_gamma_2: "03dd45b82d87ed161bbf855337586fea5765e7dc85f8bbcc3d4a3394905df921d1cb962536b28402717cc1b0c1ada4d4194827dc6932f2c28fb4b58b8d8e7045f989a940fab39a8af91d5ccbd86d91f6062b509d05faaa61b2492039aeae417d0753b08dd835163d6fd6b09527c66f5955db4500d2db982181b502bc359541ef9da9d65bb7f2a4083719a2862c19f922137d5a832a35722784f9f6487af9594b2caeb2bd3b910e8b1f36aef5ccd23797ff54f27ca07ea2e7faa4547ce5159a22".to_string(),
+            vk_delta_2: "1911a65bfbea20bbf9e78219772fbc7cb74fcb5e24e32f14a2c484cad41c4217526c4557106607c393e1c4a29c430e7106a38ff96f2c4083b1a5202ad743978b84341687d9784969575bf1ce35775e49054f367d7b07e3f5d5b7653f0fb463cd03e311b2177fbfe122dd64328bd750f6b1b1b6ad7f4a579e948e55facf4cd779589799b198c2ea86fcc5db2efaab17b91686313d64e0a0e970476fa3e12944598ce5a3caf2aef869564dcbc7ec362afd9fe263f480b9208b8598d7062409c806".to_string(),
+            vk_ic: vec![
+                "0bc41192b404acf94c0850587153434ae56960ef82382d71ddd36c8a3abb8933bf53a9f20bd5342d79a16c11ad128a9706a405128322d1fb1bc387332c633cb7dbbf8c1d8185c1a2e5b19086a9458dc8dc2962f61de9e0fc4d646795b6fbaae3".to_string(),
+                "1260d408468e96902a0529eefb2da05ee2b3c80346912debb449105ed3ed09e4ff14617588c89e12c146865ab22221240bec78086ae2dfc755a0d445a476b7246eaf5c900ea6f6f33a6168f8d19908fa07b89f86227f93c25b209a38b293e2b3".to_string(),
+                "0a2fc1494353a52376d8eba63438e929bbbf983b4ddf9005096831a050271264493282f8e50c85fa86fe9b0ee4a1ecde0a1533a5cf65ea977d7a00ffa3a5e8c0fc66ec40800ac3763b3c1989152b52671ae79a568b59316ce6d03a6618938e12".to_string(),
+            ],
+            point_encoding: None,
+        }
+    }
+
+    /// bob's proof for `groth16_bls12_381_bound_key`
+    #[cfg(feature = "groth16-bls12-381")]
+    fn groth16_bls12_381_bound_proof() -> Proof {
+        Proof::Groth16 {
+            proof_a: "10a1d25e5547b8e72ab9be8927f27d06a979f0ca03b0a89f1ee3ff7d3b09ac7066b2b60bc369fa7b775ad68a07a5cc5716db38674cf4b3f124c08b0e29ce8edab622d470bb72a78009e68f8382bbc2970368d5f9aa501af292bafdb7093f89fb".to_string(),
+            proof_b: "1282487321d96676e063fb8432aae85c207fe14d5befd1d6ef5172bf24a83335d2398bcaa8c08374474835122e8818bc0c67d6c98e57d708e198eccb776fb4d1e9ea51b600392ee04e083ab79c82a208b7621dc838f93c76052d4c2a2fb607f318256f7b6e58568c3562d54f2be1ea91d372144156947dbe5e45336b2d8a4b77e288f8cbb945dcf95c827466ae9ce3680384827ac9fe2629e8450210743f6a8b7c0d25166d9c7f6f5977b96f0edf63bb0f6664a3fa6ac2632ded6f43563e8d20".to_string(),
+            proof_c: "004ea3d1fd3c8e1b67b6fbc46890a00bbf1d9ad4251a002fc1b233746ba8fd836d6646a3550f93cd4907f07be1f41e6207767430c02570f1e59812a962f9fd7ee38448f4a6f9d4e148cf975ac9d71a88ab593c70f84e87e19cea08719ec53378".to_string(),
+            point_encoding: None,
+        }
+    }
+
+    /// proves w * w = x, the second public input is a nullifier
+    #[cfg(feature = "groth16-bls12-381")]
+    fn groth16_bls12_381_nullifier_key() -> VerifyingKey {
+        VerifyingKey::Groth16 {
+            vk_alpha1: "16ab352f52dd0abdee48a8852a30620031988b3e658bb877fae4312bd7509b4313433edecbbc720ac4b74d4ce23769e012a20f7578cf65b7afd49e7adab5d125ff91f42ed15edaea1a94c6cdc73cf493f8aa8e94367a966c4cf53a9deb6287df".to_string(),
+            vk_beta_2: "06a47c31a640375aaa534a57719f0581fef45b3201ea76c07ea997ac4a9fa9b6b5cff51e6fbba1e94312702631e7f83d1322f5e1fb855feef949e1c86dcb6a50804c5bf5a52d2f9773edb7d778bbf8c0b65a68cbd84acf48142f83679e522e1d1199d0c90ba55bc38b6b0b49edb2f919405b0b721b16b2d527d7156e5e32457d27ece44483c56a03daaef151d174d1d4140c388f4c6f3f100c7b7533ea26e52efdf6bdc62e2ed64a1784777de1af113630e315980eba15421364e67204ffc298".to_string(),
+            vk_gamma_2: "0f15b6ab9d51465f3b5ddc0569df67312f9dbcc10a389dc4647554143038a40933f67ee4a2e797ca331fded7cd9dc49900920e8ca4218c48e37539db5ef35dcfbdc696347d02f2e554faba0f51b7dce55ddb02528558185673721bcbf53baa97187a010e323b87ce4ead15b378fbc51d7c70fa11329091e14c0e1af34d0adc334823629351291a17b71724718b38607406b192b7f346023cb4a67b89fcceeffd895407237aeee4db218d568a8e602b5b7686c426c667e0e73658a6eff1123690".to_string(),
+            vk_delta_2: "02aab99a03d4ef7eb2a6272c0c6c360a567db33a9c2a29ab985d3b8e65da19b6119e6ddb59926335b118ee245266601608aa276683b5be7ef5ea429aabe5c78717fd0e00fd9bebb215f58f81d366f04d62e3d7a74d7162c7fb7c514d0c3a64f214bdf0a5e4a3fe3cf0a7c957820f89cef12ce15cfef2404b3533d6aa409f3d40868be20c17262d0e2eb96c15e9409c6d0000c6673f5391466c2ae72c28c9c8099b453fb0da89ef9e56bcc507ec7af337043b62334ffac4bcf98cb4896dfba6da".to_string(),
+            vk_ic: vec![
+                "167c2dbf48b2f21e481b32f8e5237ba14be75c061464f4913dd24010aeefe1b42c0a880ea2bab40c89473dfd00756aed099e56f5b5c888107b6f822a042adbb76add4e986d04147ab6c2c510dc45785f062f41918926417f7028063fd69fa22e".to_string(),
+                "0c63048bad092c68ef1d1ce927f8242cfb863b0129ca4b77b014e65677b86a71f4f33db6a4996af5e5cf33e0a695a5980c0ebecb119a9f76ad474ff6ad5df9c2dc69eb9e75fa8fecbe0aca4a33e8cace5e484e62f0320e42232e25365b2de2a3".to_string(),
+                "0a6b06d162344bc53e8141d3fe319f94b1ca28e409c01aa3f2e5a9e8ebd3814160e90a9c48e313105a217d8499080d7a117c7bd4330196b29f2b9f0142893108bc0ecb7e11ed7ca554cad4744c0366748fa9412f6f679b45fccab61385eab319".to_string(),
+            ],
+            point_encoding: None,
+        }
+    }
+
+    /// bob's proof of 5 * 5 = 25 with the nullifier 7
+    #[cfg(feature = "groth16-bls12-381")]
+    fn groth16_bls12_381_nullifier_proof() -> Proof {
+        Proof::Groth16 {
+            proof_a: "1570f63fcb28c5346b96e9bf9c748c2e8ec244f4f71c583fa7da3a7bcfd5ca55f81a5a754e63844ecf092692da0d717502a37b841bb8d2285a7d62dcff8db2f45dca4b480846ed6d690377006ecf26c763e8cab793a0c4df01be5d7cd196042a".to_string(),
+            proof_b: "0c5a5df7cb5d03c3fc864868cb5d41c4f5f0e75757793b86b0e7419880349324833ff06aa91588ba26023c2387826cc011a8e13d8f4c393b44431d31e32ac516bd64258ab28ce6a2e066fa3d2bdd435e5ec61fa44e3f859edb0dffe697ffafeb03678528d1ce2bd904b0065bde165b48eff70ad280fc4267844afca5e6dfc3c0a04756d48d32a713026ef8b420ceef4e0984ed1c554578deb52562d4862f2176d9f9448b3f49618c0c8f9d4f644875a49b31fc243852b8a8f0e5fb7a302f5b1a".to_string(),
+            proof_c: "07b2945c2aeb8170509cf0eb26b0755945563fc65a173680d116af9bdf58ddb37dd2b9d240b0ff81f27dbd3531566a82064519507398c9b1aa59c704ad54a997ab31d06d71486e7404fc1915b66a6e36a6f7e4bec7e5626634d92220151846d5".to_string(),
+            point_encoding: None,
+        }
+    }
+
+    fn set_zkeys(
+        deps: DepsMut,
+        sent: &[Coin],
+        circuit_id: Option<&str>,
+        proof_system: ProofSystem,
+        key: VerifyingKey,
+        public_signals: Option<Vec<String>>,
+    ) -> Result<Response, ContractError> {
+        let info = mock_info("alice_key", sent);
+        let msg = ExecuteMsg::Zkeys {
+            circuit_id: circuit_id.map(str::to_string),
+            metadata: None,
+            proof_system,
+            key,
+            public_signals,
+            bind_sender: None,
+            nullifier_index: None,
+            bounty: None,
+            bounty_deadline: None,
+        };
+        execute(deps, mock_env(), info, msg)
+    }
+
+    fn publish_proof(
+        deps: DepsMut,
+        sent: &[Coin],
+        circuit_id: Option<&str>,
+        proof: Proof,
+        public_signals: Option<Vec<String>>,
+    ) -> Result<Response, ContractError> {
+        let info = mock_info("bob_key", sent);
+        let msg = ExecuteMsg::Proof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: circuit_id.map(str::to_string),
+            proof,
+            public_signals,
+        };
+        execute(deps, mock_env(), info, msg)
+    }
+
+    fn query_circuit(deps: Deps, circuit_id: Option<&str>) -> CircuitResponse {
+        let res = query(
+            deps,
+            mock_env(),
+            QueryMsg::Circuit {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: circuit_id.map(str::to_string),
+            },
+        )
+        .unwrap();
+        from_json(&res).unwrap()
+    }
+
+    fn query_proof_result(deps: Deps, circuit_id: Option<&str>) -> ProofResponse {
+        let res = query(
+            deps,
+            mock_env(),
+            QueryMsg::ProofResult {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: circuit_id.map(str::to_string),
+                prover_address: "bob_key".to_string(),
+            },
+        )
+        .unwrap();
+        from_json(&res).unwrap()
+    }
+
+    fn mock_init_with_cw20_price(deps: DepsMut) {
+        let msg = InstantiateMsg {
+            set_zkeys_price: None,
+            publish_proof_price: None,
+            set_zkeys_cw20_price: Some(Cw20Coin {
+                address: "cw20_token".to_string(),
+                amount: Uint128::new(2),
+            }),
+            publish_proof_cw20_price: Some(Cw20Coin {
+                address: "cw20_token".to_string(),
+                amount: Uint128::new(2),
+            }),
+            commitment_expiry: None,
+            fee_mode: Some(FeeMode::Exact),
+            unrelated_denoms: None,
+        };
+
+        let info = mock_info("creator", &[]);
+        let _res = instantiate(deps, mock_env(), info, msg)
+            .expect("contract successfully handles InstantiateMsg");
+    }
+
+    fn mock_env_at(height: u64) -> Env {
+        let mut env = mock_env();
+        env.block.height = height;
+        env
+    }
+
+    #[cfg(feature = "groth16-bls12-381")]
+    fn set_zkeys_with(
+        deps: DepsMut,
+        sent: &[Coin],
+        key: VerifyingKey,
+        public_signals: Option<Vec<String>>,
+        bind_sender: Option<bool>,
+        nullifier_index: Option<usize>,
+        bounty: Option<Coin>,
+    ) -> Result<Response, ContractError> {
+        let info = mock_info("alice_key", sent);
+        let msg = ExecuteMsg::Zkeys {
+            circuit_id: None,
+            metadata: None,
+            proof_system: ProofSystem::Groth16Bls12381,
+            key,
+            public_signals,
+            bind_sender,
+            nullifier_index,
+            bounty_deadline: bounty.as_ref().map(|_| mock_env().block.height + 10),
+            bounty,
+        };
+        execute(deps, mock_env(), info, msg)
+    }
+
+    #[cfg(feature = "groth16-bls12-381")]
+    fn commit_proof(deps: DepsMut, prover: &str, salt: &str, height: u64) {
+        let commitment =
+            proof_commitment(&groth16_bls12_381_proof(), &Addr::unchecked(prover), salt).unwrap();
+        let msg = ExecuteMsg::CommitProof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            commitment: hex::encode(commitment),
+        };
+        execute(deps, mock_env_at(height), mock_info(prover, &[]), msg)
+            .expect("contract handles commit proof");
+    }
+
+    #[cfg(feature = "groth16-bls12-381")]
+    fn reveal_proof(
+        deps: DepsMut,
+        prover: &str,
+        salt: &str,
+        height: u64,
+    ) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::RevealProof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proof: groth16_bls12_381_proof(),
+            public_signals: None,
+            salt: salt.to_string(),
+        };
+        execute(deps, mock_env_at(height), mock_info(prover, &[]), msg)
+    }
+
+    #[cfg(feature = "groth16-bls12-381")]
+    fn query_bounty(deps: Deps) -> BountyResponse {
+        let res = query(
+            deps,
+            mock_env(),
+            QueryMsg::Bounty {
+                address: "alice_key".to_string(),
+                circuit_id: None,
+            },
+        )
+        .unwrap();
+        from_json(&res).unwrap()
+    }
+
+    #[test]
+    fn proper_init_no_fees() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Config {}).unwrap();
+        let value: ConfigResponse = from_json(&res).unwrap();
+        assert_eq!(value.owner.as_str(), "creator");
+        assert_eq!(value.zkeys_price, None);
+        assert_eq!(value.proof_price, None);
+    }
+
+    #[test]
+    fn proof_systems_follow_the_features() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+
+        let expected: Vec<ProofSystem> = [
+            (
+                ProofSystem::Groth16Bls12381,
+                cfg!(feature = "groth16-bls12-381"),
+            ),
+            (ProofSystem::Groth16Bn254, cfg!(feature = "groth16-bn254")),
+            (ProofSystem::PlonkBn254, cfg!(feature = "plonk-bn254")),
+        ]
+        .into_iter()
+        .filter_map(|(proof_system, enabled)| enabled.then_some(proof_system))
+        .collect();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::ProofSystems {}).unwrap();
+        let value: ProofSystemsResponse = from_json(&res).unwrap();
+        assert_eq!(value.proof_systems, expected);
+        // named like the `proof_system` attribute of the single system contracts
+        assert_eq!(
+            to_json_binary(&ProofSystem::Groth16Bls12381)
+                .unwrap()
+                .to_vec(),
+            b"\"groth16_bls12_381\""
+        );
+    }
+
+    #[cfg(feature = "groth16-bls12-381")]
+    #[test]
+    fn verify_groth16_bls12_381_proof_works() {
+        let mut deps = mock_dependencies();
+        mock_init_with_price(deps.as_mut(), coin(1, "token"), coin(1, "token"));
+        set_zkeys(
+            deps.as_mut(),
+            &[coin(1, "token")],
+            None,
+            ProofSystem::Groth16Bls12381,
+            groth16_bls12_381_key(),
+            Some(groth16_public_signals()),
+        )
+        .unwrap();
+
+        let res = publish_proof(
+            deps.as_mut(),
+            &[coin(1, "token")],
+            None,
+            groth16_bls12_381_proof(),
+            None,
+        )
+        .unwrap();
+        let event = &res.events[0];
+        assert_eq!(event.ty, "proof_verified");
+        assert!(event
+            .attributes
+            .iter()
+            .any(|attr| attr.key == "proof_system" && attr.value == "groth16_bls12_381"));
+
+        let circuit = query_circuit(deps.as_ref(), None);
+        assert_eq!(circuit.proof_system, ProofSystem::Groth16Bls12381);
+        assert_eq!(circuit.circuit_id, DEFAULT_CIRCUIT);
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Circuits {
+                issuer_address: "alice_key".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: CircuitsResponse = from_json(&res).unwrap();
+        assert_eq!(value.circuit_ids, vec![DEFAULT_CIRCUIT.to_string()]);
+        let value = query_proof_result(deps.as_ref(), None);
+        assert_eq!(value.proof, groth16_bls12_381_proof());
+        assert_eq!(value.public_signals, groth16_public_signals());
+        assert!(value.is_valid);
+    }
+
+    #[cfg(feature = "groth16-bls12-381")]
+    #[test]
+    fn verify_compressed_groth16_proof_works() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        set_zkeys(
+            deps.as_mut(),
+            &[],
+            None,
+            ProofSystem::Groth16Bls12381,
+            groth16_bls12_381_key(),
+            Some(groth16_public_signals()),
+        )
+        .unwrap();
+        let expected = query_circuit(deps.as_ref(), None).vkey_hash;
+
+        // the same key and proof with every point compressed
+        let key = VerifyingKey::Groth16 {
+            vk_alpha1: "921ec6cddca2aa0dedf8dbb86e96dc4100b58ea07c01d7ea68a37f8f72191ab2bbe9f16bfe675f71c899ff11e23cbb04".to_string(),
+            vk_beta_2: "a0f3edfbbbe5e2dab32cac1d1ba2f0fdd9eff4067c7152520f0ebbf556c21f98e72590b3cdb614b1ea116991305da942077b7419fac8cc2d38dc6639d68a4cf7c8362efd8395020836f3aa564537fa02a17f2d1b423c19b6cf4784037b1d9f15".to_string(),
+            vk_gamma_2: "93e02b6052719f607dacd3a088274f65596bd0d09920b61ab5da61bbdc7f5049334cf11213945d57e5ac7d055d042b7e024aa2b2f08f0a91260805272dc51051c6e47ad4fa403b02b4510b647ae3d1770bac0326a805bbefd48056c8c121bdb8".to_string(),
+            vk_delta_2: "a7709351aa9646a1311053a0e4cfe6d7db03513beaebb9b87da192758e5ecd40d21ac535e7664e78d669399de703cb72109a5d6b3943018f1dd43462eb71be512213f05e61b2c93bc5f65d270bf78122b00e24d38b0f98efdee072cf3b4c8d0a".to_string(),
+            vk_ic: vec![
+                "b6aca3c7fb4157ef2f70fa4098434d97721a2ffa30f1ed64d3123cccb3928433899ab147217331f74f18ce687cc59170".to_string(),
+                "ac1aeb08622db17dab3de7590db8f46349c7e08eff70fa63af8332db75b977bd0e630b04d8e28d4b3416381b27f4bded".to_string(),
+            ],
+            point_encoding: Some(PointEncoding::Compressed),
+        };
+        let proof = |point_encoding| {
+            Proof::Groth16 {
+            proof_a: "820fcbc0e3ae8e322a5cb6fb707d2511878eda020096f8f421cf75c263e43c2ee6d2392b6e03410d5555fd80628581f1".to_string(),
+            proof_b: "b545a18455dd6e1abaf4e27f3ee198bb5abb199b0650030593ce0d03b7cc59d458864acc3db510efe2300f778aa797e017c8d8fa15654b1995f0e659910bbdf8c0d88ef6801e1615e664b559daa8fd139b88569e95e6058d077fb5ae6aafe931".to_string(),
+            proof_c: "b0406a2ada964c701668b06be2e3011bf22d9b6c6c0731f5b042a6b7ccf777d58b8e8b8b19fd711953b170d591981eb8".to_string(),
+            point_encoding,
+        }
+        };
+        set_zkeys(
+            deps.as_mut(),
+            &[],
+            Some("compressed"),
+            ProofSystem::Groth16Bls12381,
+            key,
+            Some(groth16_public_signals()),
+        )
+        .unwrap();
+        assert_eq!(
+            query_circuit(deps.as_ref(), Some("compressed")).vkey_hash,
+            expected
+        );
+
+        // the tag decides how the points are read
+        match publish_proof(deps.as_mut(), &[], Some("compressed"), proof(None), None) {
+            Ok(_) => panic!("compressed points without the tag"),
+            Err(e @ ContractError::Backend { .. }) => assert_eq!(
+                e.to_string(),
+                "groth16_bls12_381: proof_a: expected 96 bytes, got 48"
+            ),
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+        publish_proof(
+            deps.as_mut(),
+            &[],
+            Some("compressed"),
+            proof(Some(PointEncoding::Compressed)),
+            None,
+        )
+        .unwrap();
+        assert!(query_proof_result(deps.as_ref(), Some("compressed")).is_valid);
+    }
+
+    #[cfg(feature = "groth16-bn254")]
+    #[test]
+    fn verify_groth16_bn254_proof_works() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        set_zkeys(
+            deps.as_mut(),
+            &[],
+            None,
+            ProofSystem::Groth16Bn254,
+            groth16_bn254_key(),
+            Some(groth16_public_signals()),
+        )
+        .unwrap();
+
+        publish_proof(deps.as_mut(), &[], None, groth16_bn254_proof(), None).unwrap();
+        assert_eq!(
+            query_circuit(deps.as_ref(), None).proof_system,
+            ProofSystem::Groth16Bn254
+        );
+        assert!(query_proof_result(deps.as_ref(), None).is_valid);
+    }
+
+    #[cfg(feature = "plonk-bn254")]
+    #[test]
+    fn verify_plonk_bn254_proof_works() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        // provers bring their own public signals
+        set_zkeys(
+            deps.as_mut(),
+            &[],
+            None,
+            ProofSystem::PlonkBn254,
+            plonk_bn254_key(),
+            None,
+        )
+        .unwrap();
+
+        publish_proof(
+            deps.as_mut(),
+            &[],
+            None,
+            plonk_bn254_proof(),
+            Some(plonk_bn254_public_signals()),
+        )
+        .unwrap();
+        assert_eq!(
+            query_circuit(deps.as_ref(), None).proof_system,
+            ProofSystem::PlonkBn254
+        );
+        let value = query_proof_result(deps.as_ref(), None);
+        assert_eq!(value.public_signals, plonk_bn254_public_signals());
+        assert!(value.is_valid);
+    }
+
+    #[cfg(feature = "plonk-bn254")]
+    #[test]
+    fn plonk_key_is_read_with_its_params() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+
+        // a width 4 key has one selector too many for width 3
+        let mut key = plonk_bn254_key();
+        if let VerifyingKey::Plonk { params, .. } = &mut key {
+            *params = Some(PlonkParams::Width3WithNextStep);
+        }
+        match set_zkeys(
+            deps.as_mut(),
+            &[],
+            None,
+            ProofSystem::PlonkBn254,
+            key.clone(),
+            None,
+        ) {
+            Ok(_) => panic!("a width 4 key can't be read as width 3"),
+            Err(e @ ContractError::Backend { .. }) => assert_eq!(
+                e.to_string(),
+                "plonk_bn254: selector_commitments: expected 5 elements, got 6"
+            ),
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+
+        if let VerifyingKey::Plonk { params, .. } = &mut key {
+            *params = Some(PlonkParams::Width4WithNextStep);
+        }
+        set_zkeys(deps.as_mut(), &[], None, ProofSystem::PlonkBn254, key, None).unwrap();
+        publish_proof(
+            deps.as_mut(),
+            &[],
+            None,
+            plonk_bn254_proof(),
+            Some(plonk_bn254_public_signals()),
+        )
+        .unwrap();
+        assert!(query_proof_result(deps.as_ref(), None).is_valid);
+    }
+
+    #[cfg(feature = "plonk-bn254")]
+    #[test]
+    fn plonk_snarkjs_keys_are_refused() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+
+        // proofs come in the bellman layout, a snarkjs key only takes cw-plonk's `SnarkjsProof`
+        let mut key = plonk_bn254_key();
+        if let VerifyingKey::Plonk { params, .. } = &mut key {
+            *params = Some(PlonkParams::Snarkjs);
+        }
+        match set_zkeys(deps.as_mut(), &[], None, ProofSystem::PlonkBn254, key, None) {
+            Ok(_) => panic!("a snarkjs key can't be registered"),
+            Err(e @ ContractError::Backend { .. }) => assert_eq!(
+                e.to_string(),
+                format!(
+                    "plonk_bn254: {}",
+                    cw_plonk::ContractError::SnarkjsCircuit {}
+                )
+            ),
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+
+        // nor is a proof checked against one
+        let decoded = PlonkBn254.decode_key(plonk_bn254_key()).unwrap();
+        let mut vkeys: cw_plonk::state::VkeyStr = from_json(&decoded.key).unwrap();
+        vkeys.params = cw_plonk::state::PlonkParams::Snarkjs;
+        match PlonkBn254.verify(
+            &to_json_binary(&vkeys).unwrap(),
+            plonk_bn254_proof(),
+            &plonk_bn254_public_signals(),
+        ) {
+            Ok(_) => panic!("a bellman proof can't be checked against a snarkjs key"),
+            Err(e @ ContractError::Backend { .. }) => assert_eq!(
+                e.to_string(),
+                format!(
+                    "plonk_bn254: {}",
+                    cw_plonk::ContractError::SnarkjsCircuit {}
+                )
+            ),
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[cfg(all(feature = "groth16-bls12-381", feature = "plonk-bn254"))]
+    #[test]
+    fn circuits_of_different_systems_are_kept_apart() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        set_zkeys(
+            deps.as_mut(),
+            &[],
+            None,
+            ProofSystem::Groth16Bls12381,
+            groth16_bls12_381_key(),
+            Some(groth16_public_signals()),
+        )
+        .unwrap();
+        set_zkeys(
+            deps.as_mut(),
+            &[],
+            Some("plonk"),
+            ProofSystem::PlonkBn254,
+            plonk_bn254_key(),
+            None,
+        )
+        .unwrap();
+
+        // a proof is checked by the system of the circuit it names
+        match publish_proof(
+            deps.as_mut(),
+            &[],
+            Some("plonk"),
+            groth16_bls12_381_proof(),
+            Some(plonk_bn254_public_signals()),
+        ) {
+            Ok(_) => panic!("a groth16 proof can't verify against a plonk key"),
+            Err(ContractError::ProofMismatch { proof_system }) => {
+                assert_eq!(proof_system, "plonk_bn254")
+            }
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+        publish_proof(deps.as_mut(), &[], None, groth16_bls12_381_proof(), None).unwrap();
+        publish_proof(
+            deps.as_mut(),
+            &[],
+            Some("plonk"),
+            plonk_bn254_proof(),
+            Some(plonk_bn254_public_signals()),
+        )
+        .unwrap();
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Circuits {
+                issuer_address: "alice_key".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: CircuitsResponse = from_json(&res).unwrap();
+        assert_eq!(
+            value.circuit_ids,
+            vec![DEFAULT_CIRCUIT.to_string(), "plonk".to_string()]
+        );
+        assert_eq!(
+            query_circuit(deps.as_ref(), Some("plonk")).proof_system,
+            ProofSystem::PlonkBn254
+        );
+    }
+
+    #[cfg(all(feature = "groth16-bls12-381", feature = "plonk-bn254"))]
+    #[test]
+    fn set_zkeys_failed_with_key_of_another_system() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+
+        match set_zkeys(
+            deps.as_mut(),
+            &[],
+            None,
+            ProofSystem::PlonkBn254,
+            groth16_bls12_381_key(),
+            None,
+        ) {
+            Ok(_) => panic!("a groth16 key can't be registered as a plonk key"),
+            Err(ContractError::KeyMismatch { proof_system }) => {
+                assert_eq!(proof_system, "plonk_bn254")
+            }
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[cfg(not(feature = "plonk-bn254"))]
+    #[test]
+    fn set_zkeys_failed_with_compiled_out_system() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+
+        let key = VerifyingKey::Plonk {
+            n: 3,
+            num_inputs: 1,
+            selector_commitments: vec![],
+            next_step_selector_commitments: vec![],
+            permutation_commitments: vec![],
+            non_residues: vec![],
+            g2_elements: vec![],
+            params: None,
+        };
+        match set_zkeys(deps.as_mut(), &[], None, ProofSystem::PlonkBn254, key, None) {
+            Ok(_) => panic!("plonk isn't compiled in"),
+            Err(ContractError::UnsupportedProofSystem { proof_system }) => {
+                assert_eq!(proof_system, "plonk_bn254")
+            }
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[cfg(feature = "groth16-bls12-381")]
+    #[test]
+    fn fail_set_zkeys_insufficient_fees() {
+        let mut deps = mock_dependencies();
+        mock_init_with_price(deps.as_mut(), coin(2, "token"), coin(2, "token"));
+
+        match set_zkeys(
+            deps.as_mut(),
+            &[coin(1, "token")],
+            None,
+            ProofSystem::Groth16Bls12381,
+            groth16_bls12_381_key(),
+            Some(groth16_public_signals()),
+        ) {
+            Ok(_) => panic!("register zkeys should fail with insufficient fees"),
+            Err(ContractError::InsufficientFundsSend {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[cfg(feature = "groth16-bls12-381")]
+    #[test]
+    fn verify_proof_failed_with_wrong_public_signal() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        set_zkeys(
+            deps.as_mut(),
+            &[],
+            None,
+            ProofSystem::Groth16Bls12381,
+            groth16_bls12_381_key(),
+            None,
+        )
+        .unwrap();
+
+        match publish_proof(
+            deps.as_mut(),
+            &[],
+            None,
+            groth16_bls12_381_proof(),
+            Some(vec!["34".to_string()]),
+        ) {
+            Ok(_) => panic!("proof should fail with a wrong public signal"),
+            Err(ContractError::InvalidProof {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+        match publish_proof(deps.as_mut(), &[], None, groth16_bls12_381_proof(), None) {
+            Ok(_) => panic!("provers bring their own public signals"),
+            Err(ContractError::NoPublicSignal {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[cfg(feature = "groth16-bls12-381")]
+    #[test]
+    fn verify_proof_query_explains_rejection() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        set_zkeys(
+            deps.as_mut(),
+            &[],
+            None,
+            ProofSystem::Groth16Bls12381,
+            groth16_bls12_381_key(),
+            None,
+        )
+        .unwrap();
+
+        let verify = |deps: Deps, public_signals: Vec<String>| -> VerifyProofResponse {
+            let res = query(
+                deps,
+                mock_env(),
+                QueryMsg::VerifyProof {
+                    issuer_address: "alice_key".to_string(),
+                    circuit_id: None,
+                    prover: None,
+                    proof: groth16_bls12_381_proof(),
+                    public_signals: Some(public_signals),
+                },
+            )
+            .unwrap();
+            from_json(&res).unwrap()
+        };
+        let accepted = verify(deps.as_ref(), groth16_public_signals());
+        assert!(accepted.is_valid);
+        assert_eq!(accepted.proof_system, ProofSystem::Groth16Bls12381);
+        assert_eq!(
+            accepted.vkey_hash,
+            query_circuit(deps.as_ref(), None).vkey_hash
+        );
+        assert_eq!(accepted.error, None);
+
+        let rejected = verify(deps.as_ref(), vec!["34".to_string()]);
+        assert!(!rejected.is_valid);
+        assert_eq!(
+            rejected.error,
+            Some("invalid proof, verify failed".to_string())
+        );
+        // nothing was recorded
+        assert!(query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProofResult {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                prover_address: "bob_key".to_string(),
+            }
+        )
+        .is_err());
+    }
+
+    #[cfg(feature = "groth16-bls12-381")]
+    #[test]
+    fn retired_circuit_refuses_proofs() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        let info = mock_info("alice_key", &[]);
+        let msg = ExecuteMsg::Zkeys {
+            circuit_id: None,
+            metadata: Some(CircuitMetadata {
+                name: "multiplier".to_string(),
+                ..CircuitMetadata::default()
+            }),
+            proof_system: ProofSystem::Groth16Bls12381,
+            key: groth16_bls12_381_key(),
+            public_signals: Some(groth16_public_signals()),
+            bind_sender: None,
+            nullifier_index: None,
+            bounty: None,
+            bounty_deadline: None,
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap();
+        assert_eq!(
+            query_circuit(deps.as_ref(), None).metadata.name,
+            "multiplier"
+        );
+
+        let retire = ExecuteMsg::SetCircuitStatus {
+            circuit_id: None,
+            status: CircuitStatus::Retired,
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), retire).unwrap();
+        assert_eq!(
+            query_circuit(deps.as_ref(), None).status,
+            CircuitStatus::Retired
+        );
+        match publish_proof(deps.as_mut(), &[], None, groth16_bls12_381_proof(), None) {
+            Ok(_) => panic!("a retired circuit refuses proofs"),
+            Err(ContractError::CircuitRetired { circuit_id }) => {
+                assert_eq!(circuit_id, DEFAULT_CIRCUIT)
+            }
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+
+        let reactivate = ExecuteMsg::SetCircuitStatus {
+            circuit_id: None,
+            status: CircuitStatus::Active,
+        };
+        match execute(deps.as_mut(), mock_env(), info, reactivate) {
+            Ok(_) => panic!("a retired circuit stays retired"),
+            Err(ContractError::CircuitRetired { .. }) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[cfg(feature = "groth16-bls12-381")]
+    #[test]
+    fn set_zkeys_replaces_key_until_proven() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        set_zkeys(
+            deps.as_mut(),
+            &[],
+            None,
+            ProofSystem::Groth16Bls12381,
+            groth16_bls12_381_key(),
+            Some(vec!["34".to_string()]),
+        )
+        .unwrap();
+
+        // the issuer fixes a wrong public signal before anyone proves the circuit
+        set_zkeys(
+            deps.as_mut(),
+            &[],
+            None,
+            ProofSystem::Groth16Bls12381,
+            groth16_bls12_381_key(),
+            Some(groth16_public_signals()),
+        )
+        .unwrap();
+        publish_proof(deps.as_mut(), &[], None, groth16_bls12_381_proof(), None).unwrap();
+        match set_zkeys(
+            deps.as_mut(),
+            &[],
+            None,
+            ProofSystem::Groth16Bls12381,
+            groth16_bls12_381_key(),
+            Some(vec!["34".to_string()]),
+        ) {
+            Ok(_) => panic!("a proven key isn't replaced"),
+            Err(ContractError::CircuitAlreadyRegistered { circuit_id }) => {
+                assert_eq!(circuit_id, DEFAULT_CIRCUIT)
+            }
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+    }
+
+    #[cfg(feature = "groth16-bls12-381")]
+    #[test]
+    fn set_zkeys_failed_with_mismatched_public_signals() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+
+        match set_zkeys(
+            deps.as_mut(),
+            &[],
+            None,
+            ProofSystem::Groth16Bls12381,
+            groth16_bls12_381_key(),
+            Some(vec!["33".to_string(), "1".to_string()]),
+        ) {
+            Ok(_) => panic!("two public signals for a key with a single public input"),
+            Err(ContractError::PublicSignalCountMismatch { expected, actual }) => {
+                assert_eq!((expected, actual), (1, 2))
+            }
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+        match set_zkeys(
+            deps.as_mut(),
+            &[],
+            None,
+            ProofSystem::Groth16Bls12381,
+            groth16_bls12_381_key(),
+            Some(vec!["thirty three".to_string()]),
+        ) {
+            Ok(_) => panic!("a public signal must be a field element"),
+            Err(ContractError::ParsePulbicSignalError { index }) => assert_eq!(index, 0),
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+        // the sender signal is the last public input of a bound key
+        match set_zkeys_with(
+            deps.as_mut(),
+            &[],
+            groth16_bls12_381_bound_key(),
+            Some(vec!["25".to_string(), "1".to_string()]),
+            Some(true),
+            None,
+            None,
+        ) {
+            Err(ContractError::PublicSignalCountMismatch { expected, actual }) => {
+                assert_eq!((expected, actual), (1, 2))
+            }
+            res => panic!("Unexpected result: {:?}", res),
+        }
+        match set_zkeys_with(
+            deps.as_mut(),
+            &[],
+            groth16_bls12_381_key(),
+            None,
+            None,
+            Some(1),
+            None,
+        ) {
+            Err(ContractError::InvalidNullifierIndex {}) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+    }
+
+    #[cfg(feature = "groth16-bls12-381")]
+    #[test]
+    fn verify_proof_bound_to_sender_works() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        set_zkeys_with(
+            deps.as_mut(),
+            &[],
+            groth16_bls12_381_bound_key(),
+            Some(vec!["25".to_string()]),
+            Some(true),
+            None,
+            None,
+        )
+        .unwrap();
+
+        // the query needs the prover to check against
+        let verify = |deps: Deps, prover: Option<&str>| -> VerifyProofResponse {
+            let res = query(
+                deps,
+                mock_env(),
+                QueryMsg::VerifyProof {
+                    issuer_address: "alice_key".to_string(),
+                    circuit_id: None,
+                    prover: prover.map(str::to_string),
+                    proof: groth16_bls12_381_bound_proof(),
+                    public_signals: None,
+                },
+            )
+            .unwrap();
+            from_json(&res).unwrap()
+        };
+        assert_eq!(
+            verify(deps.as_ref(), None).error,
+            Some(ContractError::NoProver {}.to_string())
+        );
+        assert!(verify(deps.as_ref(), Some("bob_key")).is_valid);
+        assert!(!verify(deps.as_ref(), Some("carol_key")).is_valid);
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::SenderSignal {
+                address: "bob_key".to_string(),
+                proof_system: ProofSystem::Groth16Bls12381,
+            },
+        )
+        .unwrap();
+        let sender_signal: SenderSignalResponse = from_json(&res).unwrap();
+        assert_eq!(
+            sender_signal.public_signal,
+            cw_groth16::signal_helpers::sender_public_signal(&Addr::unchecked("bob_key"))
+        );
+        publish_proof(
+            deps.as_mut(),
+            &[],
+            None,
+            groth16_bls12_381_bound_proof(),
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            query_proof_result(deps.as_ref(), None).public_signals,
+            vec!["25".to_string(), sender_signal.public_signal]
+        );
+
+        // carol replays the proof bob generated for his own address
+        let msg = ExecuteMsg::Proof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proof: groth16_bls12_381_bound_proof(),
+            public_signals: None,
+        };
+        match execute(deps.as_mut(), mock_env(), mock_info("carol_key", &[]), msg) {
+            Err(ContractError::InvalidProof {}) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+    }
+
+    #[cfg(all(feature = "groth16-bls12-381", feature = "plonk-bn254"))]
+    #[test]
+    fn sender_signal_follows_the_proof_system() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+
+        let sender_signal = |proof_system| -> String {
+            let res = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::SenderSignal {
+                    address: "bob_key".to_string(),
+                    proof_system,
+                },
+            )
+            .unwrap();
+            from_json::<SenderSignalResponse>(&res)
+                .unwrap()
+                .public_signal
+        };
+        // groth16 signals are decimal, plonk input values are hex
+        let bob = Addr::unchecked("bob_key");
+        assert_eq!(
+            sender_signal(ProofSystem::Groth16Bls12381),
+            cw_groth16::signal_helpers::sender_public_signal(&bob)
+        );
+        assert_eq!(
+            sender_signal(ProofSystem::PlonkBn254),
+            cw_plonk::signal_helpers::sender_input_value(&bob)
+        );
+    }
+
+    #[cfg(feature = "groth16-bls12-381")]
+    #[test]
+    fn verify_proof_with_nullifier_works() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        set_zkeys_with(
+            deps.as_mut(),
+            &[],
+            groth16_bls12_381_nullifier_key(),
+            None,
+            None,
+            Some(1),
+            None,
+        )
+        .unwrap();
+
+        let public_signals = || Some(vec!["25".to_string(), "7".to_string()]);
+        publish_proof(
+            deps.as_mut(),
+            &[],
+            None,
+            groth16_bls12_381_nullifier_proof(),
+            public_signals(),
+        )
+        .unwrap();
+        for (nullifier, spent) in [("7", true), ("8", false)] {
+            let res = query(
+                deps.as_ref(),
+                mock_env(),
+                QueryMsg::Nullifier {
+                    issuer_address: "alice_key".to_string(),
+                    circuit_id: None,
+                    nullifier: nullifier.to_string(),
+                },
+            )
+            .unwrap();
+            let value: NullifierResponse = from_json(&res).unwrap();
+            assert_eq!(value.spent, spent);
+        }
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Nullifier {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                nullifier: "seven".to_string(),
+            },
+        );
+        match res {
+            Err(StdError::GenericErr { msg, .. }) => assert_eq!(
+                msg,
+                ContractError::ParsePulbicSignalError { index: 1 }.to_string()
+            ),
+            res => panic!("Unexpected result: {:?}", res),
+        }
+
+        // the query tells a valid proof won't be accepted again
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::VerifyProof {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                prover: None,
+                proof: groth16_bls12_381_nullifier_proof(),
+                public_signals: public_signals(),
+            },
+        )
+        .unwrap();
+        let value: VerifyProofResponse = from_json(&res).unwrap();
+        assert!(value.is_valid);
+        assert_eq!(value.nullifier_spent, Some(true));
+        let msg = ExecuteMsg::Proof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proof: groth16_bls12_381_nullifier_proof(),
+            public_signals: public_signals(),
+        };
+        match execute(deps.as_mut(), mock_env(), mock_info("carol_key", &[]), msg) {
+            Err(ContractError::NullifierAlreadySpent {}) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+    }
+
+    #[cfg(feature = "groth16-bls12-381")]
+    #[test]
+    fn commit_and_reveal_proof_works() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        set_zkeys(
+            deps.as_mut(),
+            &[],
+            None,
+            ProofSystem::Groth16Bls12381,
+            groth16_bls12_381_key(),
+            Some(groth16_public_signals()),
+        )
+        .unwrap();
+
+        commit_proof(deps.as_mut(), "bob_key", "salt", 100);
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Commitment {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                prover_address: "bob_key".to_string(),
+            },
+        )
+        .unwrap();
+        let value: CommitmentResponse = from_json(&res).unwrap();
+        assert_eq!(value.height, 100);
+        assert_eq!(value.expires_at, 200);
+
+        match reveal_proof(deps.as_mut(), "bob_key", "salt", 100) {
+            Err(ContractError::RevealTooEarly {}) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+        match reveal_proof(deps.as_mut(), "bob_key", "pepper", 101) {
+            Err(ContractError::CommitmentMismatch {}) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+        reveal_proof(deps.as_mut(), "bob_key", "salt", 101).unwrap();
+        assert!(query_proof_result(deps.as_ref(), None).is_valid);
+        match reveal_proof(deps.as_mut(), "bob_key", "salt", 102) {
+            Err(ContractError::NoCommitment {}) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+    }
+
+    #[cfg(feature = "groth16-bls12-381")]
+    #[test]
+    fn verify_proof_collects_bounty() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+        mock_init_no_price(deps.as_mut());
+        set_zkeys_with(
+            deps.as_mut(),
+            &coins(10, "token"),
+            groth16_bls12_381_key(),
+            Some(groth16_public_signals()),
+            None,
+            None,
+            Some(coin(10, "token")),
+        )
+        .unwrap();
+
+        // the escrowed reward is not a fee
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::FeeBalance {}).unwrap();
+        let value: FeeBalanceResponse = from_json(&res).unwrap();
+        assert_eq!(value.balance, vec![]);
+        let msg = ExecuteMsg::Withdraw {
+            amount: coins(10, "token"),
+            recipient: "creator".to_string(),
+        };
+        match execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg) {
+            Err(ContractError::InsufficientFees { denom }) => assert_eq!(denom, "token"),
+            res => panic!("Unexpected result: {:?}", res),
+        }
+
+        // the proof wins the bounty, which is paid once no earlier commitment can be revealed
+        let height = mock_env().block.height;
+        let res = publish_proof(deps.as_mut(), &[], None, groth16_bls12_381_proof(), None).unwrap();
+        assert_eq!(res.events[1].ty, "bounty_won");
+        let value = query_bounty(deps.as_ref());
+        assert_eq!(value.winner, Some(Addr::unchecked("bob_key")));
+        assert_eq!(value.claimable_at, Some(height + 100));
+
+        let claim = || ExecuteMsg::ClaimBounty {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+        };
+        match execute(
+            deps.as_mut(),
+            mock_env_at(height + 99),
+            mock_info("carol_key", &[]),
+            claim(),
+        ) {
+            Err(ContractError::BountyNotClaimable { claimable_at }) => {
+                assert_eq!(claimable_at, height + 100)
+            }
+            res => panic!("Unexpected result: {:?}", res),
+        }
+        match execute(
+            deps.as_mut(),
+            mock_env_at(height + 111),
+            mock_info("alice_key", &[]),
+            ExecuteMsg::ReclaimBounty { circuit_id: None },
+        ) {
+            Err(ContractError::BountyAlreadyWon {}) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+        let res = execute(
+            deps.as_mut(),
+            mock_env_at(height + 100),
+            mock_info("carol_key", &[]),
+            claim(),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "bob_key".to_string(),
+                amount: coins(10, "token")
+            })]
+        );
+    }
+
+    #[cfg(feature = "groth16-bls12-381")]
+    #[test]
+    fn reclaim_bounty_waits_for_a_commitment_made_by_the_deadline() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+        mock_init_no_price(deps.as_mut());
+        set_zkeys_with(
+            deps.as_mut(),
+            &coins(10, "token"),
+            groth16_bls12_381_key(),
+            Some(groth16_public_signals()),
+            None,
+            None,
+            Some(coin(10, "token")),
+        )
+        .unwrap();
+
+        // bob commits on the last block of the bounty
+        let deadline = mock_env().block.height + 10;
+        commit_proof(deps.as_mut(), "bob_key", "salt", deadline);
+        match execute(
+            deps.as_mut(),
+            mock_env_at(deadline + 1),
+            mock_info("alice_key", &[]),
+            ExecuteMsg::ReclaimBounty { circuit_id: None },
+        ) {
+            Err(ContractError::BountyNotExpired { reclaimable_at }) => {
+                assert_eq!(reclaimable_at, deadline + 101)
+            }
+            res => panic!("Unexpected result: {:?}", res),
+        }
+
+        // his reveal after the deadline still wins the reward
+        let res = reveal_proof(deps.as_mut(), "bob_key", "salt", deadline + 2).unwrap();
+        assert_eq!(res.events[1].ty, "bounty_won");
+        assert_eq!(
+            query_bounty(deps.as_ref()).claimable_at,
+            Some(deadline + 100)
+        );
+    }
+
+    #[cfg(feature = "groth16-bls12-381")]
+    #[test]
+    fn reclaim_bounty_works() {
+        let mut deps = mock_dependencies_with_balance(&coins(10, "token"));
+        mock_init_no_price(deps.as_mut());
+        set_zkeys_with(
+            deps.as_mut(),
+            &coins(10, "token"),
+            groth16_bls12_381_key(),
+            Some(groth16_public_signals()),
+            None,
+            None,
+            Some(coin(10, "token")),
+        )
+        .unwrap();
+        // the circuit can't be replaced under an open bounty
+        match set_zkeys(
+            deps.as_mut(),
+            &[],
+            None,
+            ProofSystem::Groth16Bls12381,
+            groth16_bls12_381_key(),
+            Some(groth16_public_signals()),
+        ) {
+            Err(ContractError::CircuitAlreadyRegistered { circuit_id }) => {
+                assert_eq!(circuit_id, DEFAULT_CIRCUIT)
+            }
+            res => panic!("Unexpected result: {:?}", res),
+        }
+
+        let height = mock_env().block.height;
+        match execute(
+            deps.as_mut(),
+            mock_env_at(height + 110),
+            mock_info("alice_key", &[]),
+            ExecuteMsg::ReclaimBounty { circuit_id: None },
+        ) {
+            Err(ContractError::BountyNotExpired { reclaimable_at }) => {
+                assert_eq!(reclaimable_at, height + 111)
+            }
+            res => panic!("Unexpected result: {:?}", res),
+        }
+        let res = execute(
+            deps.as_mut(),
+            mock_env_at(height + 111),
+            mock_info("alice_key", &[]),
+            ExecuteMsg::ReclaimBounty { circuit_id: None },
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "alice_key".to_string(),
+                amount: coins(10, "token")
+            })]
+        );
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::FeeBalance {}).unwrap();
+        let value: FeeBalanceResponse = from_json(&res).unwrap();
+        assert_eq!(value.balance, coins(10, "token"));
+    }
+
+    #[cfg(feature = "groth16-bls12-381")]
+    #[test]
+    fn verify_proof_works_with_cw20_price() {
+        let mut deps = mock_dependencies();
+        mock_init_with_cw20_price(deps.as_mut());
+
+        let zkeys = ExecuteMsg::Zkeys {
+            circuit_id: None,
+            metadata: None,
+            proof_system: ProofSystem::Groth16Bls12381,
+            key: groth16_bls12_381_key(),
+            public_signals: Some(groth16_public_signals()),
+            bind_sender: None,
+            nullifier_index: None,
+            bounty: None,
+            bounty_deadline: None,
+        };
+        let receive = |sender: &str, amount: u128, msg: &ExecuteMsg| {
+            ExecuteMsg::Receive(Cw20ReceiveMsg {
+                sender: sender.to_string(),
+                amount: Uint128::new(amount),
+                msg: to_json_binary(msg).unwrap(),
+            })
+        };
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("other_token", &[]),
+            receive("alice_key", 2, &zkeys),
+        ) {
+            Err(ContractError::UnacceptedToken { token }) => assert_eq!(token, "other_token"),
+            res => panic!("Unexpected result: {:?}", res),
+        }
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("cw20_token", &[]),
+            receive("alice_key", 2, &zkeys),
+        )
+        .unwrap();
+        assert_eq!(res.messages, vec![]);
+        // the price is only payable in the cw20 token
+        match publish_proof(deps.as_mut(), &[], None, groth16_bls12_381_proof(), None) {
+            Err(ContractError::InsufficientFundsSend {}) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+
+        // the overpayment goes back with the exact fee mode
+        let proof = ExecuteMsg::Proof {
+            difficuty_issuer: "alice_key".to_string(),
+            circuit_id: None,
+            proof: groth16_bls12_381_proof(),
+            public_signals: None,
+        };
+        let res = execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("cw20_token", &[]),
+            receive("bob_key", 3, &proof),
+        )
+        .unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(WasmMsg::Execute {
+                contract_addr: "cw20_token".to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "bob_key".to_string(),
+                    amount: Uint128::new(1)
+                })
+                .unwrap(),
+                funds: vec![],
+            })]
+        );
+        assert!(query_proof_result(deps.as_ref(), None).is_valid);
+
+        let reclaim = ExecuteMsg::ReclaimBounty { circuit_id: None };
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("cw20_token", &[]),
+            receive("alice_key", 2, &reclaim),
+        ) {
+            Err(ContractError::InvalidCw20Hook {}) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+    }
+
+    #[cfg(feature = "groth16-bls12-381")]
+    #[test]
+    fn list_issuers_provers_and_history_works() {
+        let mut deps = mock_dependencies();
+        mock_init_no_price(deps.as_mut());
+        // alice and dave issue the same problem, bob solves both
+        for issuer in ["alice_key", "dave_key"] {
+            let msg = ExecuteMsg::Zkeys {
+                circuit_id: None,
+                metadata: None,
+                proof_system: ProofSystem::Groth16Bls12381,
+                key: groth16_bls12_381_key(),
+                public_signals: Some(groth16_public_signals()),
+                bind_sender: None,
+                nullifier_index: None,
+                bounty: None,
+                bounty_deadline: None,
+            };
+            execute(deps.as_mut(), mock_env(), mock_info(issuer, &[]), msg).unwrap();
+        }
+        let vkey_hash = query_circuit(deps.as_ref(), None).vkey_hash;
+        for (issuer, height) in [("alice_key", 100), ("dave_key", 101)] {
+            let msg = ExecuteMsg::Proof {
+                difficuty_issuer: issuer.to_string(),
+                circuit_id: None,
+                proof: groth16_bls12_381_proof(),
+                public_signals: None,
+            };
+            execute(
+                deps.as_mut(),
+                mock_env_at(height),
+                mock_info("bob_key", &[]),
+                msg,
+            )
+            .unwrap();
+        }
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Issuers {
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: IssuersResponse = from_json(&res).unwrap();
+        // an issuer key is stored behind its length
+        assert_eq!(
+            value.issuers,
+            vec![Addr::unchecked("dave_key"), Addr::unchecked("alice_key")]
+        );
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Issuers {
+                start_after: Some("dave_key".to_string()),
+                limit: Some(1),
+            },
+        )
+        .unwrap();
+        let value: IssuersResponse = from_json(&res).unwrap();
+        assert_eq!(value.issuers, vec![Addr::unchecked("alice_key")]);
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Provers {
+                issuer_address: "alice_key".to_string(),
+                circuit_id: None,
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: ProversResponse = from_json(&res).unwrap();
+        assert_eq!(value.provers, vec![Addr::unchecked("bob_key")]);
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::SolvedCircuits {
+                prover_address: "bob_key".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: SolvedCircuitsResponse = from_json(&res).unwrap();
+        assert_eq!(
+            value.circuits,
+            vec![
+                CircuitRef {
+                    issuer: Addr::unchecked("dave_key"),
+                    circuit_id: DEFAULT_CIRCUIT.to_string()
+                },
+                CircuitRef {
+                    issuer: Addr::unchecked("alice_key"),
+                    circuit_id: DEFAULT_CIRCUIT.to_string()
+                },
+            ]
+        );
+
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::ProverHistory {
+                prover_address: "bob_key".to_string(),
+                start_after: None,
+                limit: None,
+            },
+        )
+        .unwrap();
+        let value: ProverHistoryResponse = from_json(&res).unwrap();
+        assert_eq!(
+            value.verifications,
+            vec![
+                VerificationResponse {
+                    sequence: 0,
+                    issuer: Addr::unchecked("alice_key"),
+                    circuit_id: DEFAULT_CIRCUIT.to_string(),
+                    vkey_hash: vkey_hash.clone(),
+                    height: 100
+                },
+                VerificationResponse {
+                    sequence: 1,
+                    issuer: Addr::unchecked("dave_key"),
+                    circuit_id: DEFAULT_CIRCUIT.to_string(),
+                    vkey_hash,
+                    height: 101
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn withdraw_fees_works() {
+        let mut deps = mock_dependencies_with_balance(&coins(2, "token"));
+        mock_init_no_price(deps.as_mut());
+
+        let msg = ExecuteMsg::Withdraw {
+            amount: coins(2, "token"),
+            recipient: "treasury".to_string(),
+        };
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice_key", &[]),
+            msg.clone(),
+        ) {
+            Ok(_) => panic!("only the owner can withdraw"),
+            Err(ContractError::Unauthorized {}) => {}
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+        for amount in [vec![], coins(0, "token")] {
+            let empty = ExecuteMsg::Withdraw {
+                amount,
+                recipient: "treasury".to_string(),
+            };
+            match execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), empty) {
+                Ok(_) => panic!("nothing to withdraw"),
+                Err(ContractError::EmptyWithdrawal {}) => {}
+                Err(e) => panic!("Unexpected error: {:?}", e),
+            }
+        }
+        let too_much = ExecuteMsg::Withdraw {
+            amount: vec![coin(2, "token"), coin(1, "stake")],
+            recipient: "treasury".to_string(),
+        };
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("creator", &[]),
+            too_much,
+        ) {
+            Ok(_) => panic!("no stake was collected"),
+            Err(ContractError::InsufficientFees { denom }) => assert_eq!(denom, "stake"),
+            Err(e) => panic!("Unexpected error: {:?}", e),
+        }
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(BankMsg::Send {
+                to_address: "treasury".to_string(),
+                amount: coins(2, "token"),
+            })]
+        );
+    }
+
+    #[test]
+    fn withdraw_cw20_fees_works() {
+        let mut deps = mock_dependencies();
+        mock_init_with_cw20_price(deps.as_mut());
+
+        let msg = ExecuteMsg::WithdrawCw20 {
+            token: "cw20_token".to_string(),
+            amount: Uint128::new(2),
+            recipient: "treasury".to_string(),
+        };
+        match execute(
+            deps.as_mut(),
+            mock_env(),
+            mock_info("alice_key", &[]),
+            msg.clone(),
+        ) {
+            Err(ContractError::Unauthorized {}) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+        let empty = ExecuteMsg::WithdrawCw20 {
+            token: "cw20_token".to_string(),
+            amount: Uint128::zero(),
+            recipient: "treasury".to_string(),
+        };
+        match execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), empty) {
+            Err(ContractError::EmptyWithdrawal {}) => {}
+            res => panic!("Unexpected result: {:?}", res),
+        }
+
+        let res = execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), msg).unwrap();
+        assert_eq!(
+            res.messages,
+            vec![SubMsg::new(WasmMsg::Execute {
+                contract_addr: "cw20_token".to_string(),
+                msg: to_json_binary(&Cw20ExecuteMsg::Transfer {
+                    recipient: "treasury".to_string(),
+                    amount: Uint128::new(2)
+                })
+                .unwrap(),
+                funds: vec![],
+            })]
+        );
+    }
+}
@@ -0,0 +1,168 @@
+use std::fmt;
+
+use crate::ContractError;
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Binary};
+
+/// the proving system of a registered key, named like the `proof_system` attribute of the
+/// single system contracts
+#[cw_serde]
+#[derive(Copy, Eq)]
+pub enum ProofSystem {
+    #[serde(rename = "groth16_bls12_381")]
+    Groth16Bls12381,
+    #[serde(rename = "groth16_bn254")]
+    Groth16Bn254,
+    #[serde(rename = "plonk_bn254")]
+    PlonkBn254,
+}
+
+impl ProofSystem {
+    pub const ALL: [ProofSystem; 3] = [
+        ProofSystem::Groth16Bls12381,
+        ProofSystem::Groth16Bn254,
+        ProofSystem::PlonkBn254,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProofSystem::Groth16Bls12381 => "groth16_bls12_381",
+            ProofSystem::Groth16Bn254 => "groth16_bn254",
+            ProofSystem::PlonkBn254 => "plonk_bn254",
+        }
+    }
+
+    /// the backend checking keys and proofs of the system, unless its feature is off
+    #[allow(unreachable_patterns)]
+    pub fn verifier(&self) -> Result<&'static dyn Verifier, ContractError> {
+        match self {
+            #[cfg(feature = "groth16-bls12-381")]
+            ProofSystem::Groth16Bls12381 => Ok(&crate::groth16_bls12_381::Groth16Bls12381),
+            #[cfg(feature = "groth16-bn254")]
+            ProofSystem::Groth16Bn254 => Ok(&crate::groth16_bn254::Groth16Bn254),
+            #[cfg(feature = "plonk-bn254")]
+            ProofSystem::PlonkBn254 => Ok(&crate::plonk_bn254::PlonkBn254),
+            _ => Err(ContractError::UnsupportedProofSystem {
+                proof_system: self.to_string(),
+            }),
+        }
+    }
+
+    pub fn is_supported(&self) -> bool {
+        self.verifier().is_ok()
+    }
+
+    /// an error of the system's backend contract
+    pub(crate) fn backend_error(&self, err: impl ToString) -> ContractError {
+        ContractError::Backend {
+            proof_system: self.to_string(),
+            error: err.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ProofSystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// how the curve points of a groth16 key or proof are encoded, as in the single system contracts
+#[cw_serde]
+#[derive(Copy, Default)]
+pub enum PointEncoding {
+    #[default]
+    Uncompressed,
+    Compressed,
+}
+
+/// the constraint system a plonk key was generated for, as in the single system contract
+#[cw_serde]
+#[derive(Copy, Default)]
+pub enum PlonkParams {
+    #[default]
+    Width4WithNextStep,
+    Width3WithNextStep,
+    /// refused, a snarkjs key only verifies the `proof.json` of cw-plonk's `SnarkjsProof`
+    Snarkjs,
+}
+
+/// a verification key as exported for its proving system, points are hex encoded
+/// in the format of the single system contract
+#[cw_serde]
+pub enum VerifyingKey {
+    Groth16 {
+        vk_alpha1: String,
+        vk_beta_2: String,
+        vk_gamma_2: String,
+        vk_delta_2: String,
+        vk_ic: Vec<String>,
+        point_encoding: Option<PointEncoding>,
+    },
+    Plonk {
+        n: usize,
+        num_inputs: usize,
+        selector_commitments: Vec<String>,
+        next_step_selector_commitments: Vec<String>,
+        permutation_commitments: Vec<String>,
+        non_residues: Vec<String>,
+        g2_elements: Vec<String>,
+        params: Option<PlonkParams>,
+    },
+}
+
+/// a proof as exported for its proving system, its public inputs are given apart
+#[cw_serde]
+pub enum Proof {
+    Groth16 {
+        proof_a: String,
+        proof_b: String,
+        proof_c: String,
+        point_encoding: Option<PointEncoding>,
+    },
+    Plonk {
+        wire_commitments: Vec<String>,
+        grand_product_commitment: String,
+        quotient_poly_commitments: Vec<String>,
+        wire_values_at_z: Vec<String>,
+        wire_values_at_z_omega: Vec<String>,
+        grand_product_at_z_omega: String,
+        quotient_polynomial_at_z: String,
+        linearization_polynomial_at_z: String,
+        permutation_polynomials_at_z: Vec<String>,
+        opening_at_z_proof: String,
+        opening_at_z_omega_proof: String,
+    },
+}
+
+/// a decoded key ready to be stored
+pub struct DecodedKey {
+    // the backend's own key encoding, only the backend reads it back
+    pub key: Binary,
+    // sha256 of the key as the single system contract computes it
+    pub vkey_hash: Vec<u8>,
+    // the public inputs a proof is checked against, a bound sender signal included
+    pub num_inputs: usize,
+}
+
+/// what the contract needs from a proving system, every implementation
+/// hands the work to the contract crate of its system
+pub trait Verifier {
+    /// decode a key at registration, a key of another system is refused
+    fn decode_key(&self, key: VerifyingKey) -> Result<DecodedKey, ContractError>;
+
+    /// check `proof` against a key returned by `decode_key`
+    fn verify(
+        &self,
+        key: &Binary,
+        proof: Proof,
+        public_signals: &[String],
+    ) -> Result<bool, ContractError>;
+
+    /// the canonical bytes of the field element a public signal stands for, `None` when it
+    /// isn't one, signals reducing to the same element are the same statement and nullifier
+    fn public_signal_key(&self, public_signal: &str) -> Option<Vec<u8>>;
+
+    /// the sender signal of `sender` in the encoding the system reads public signals in
+    fn sender_signal(&self, sender: &Addr) -> String;
+}